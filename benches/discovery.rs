@@ -0,0 +1,85 @@
+//! Discovery hot paths on a fake board of 8 buses with 24 devices each, about what a
+//! dense carrier board has. Run with `cargo bench --bench discovery`.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use tux_validation::discovery::{self, DiscoveryContext};
+use tux_validation::i2c::{I2cScanner, LinuxI2cScanner};
+use tux_validation::manifest::{self, Manifest};
+use tux_validation::report;
+use tux_validation::testing::FakeSysfs;
+
+const BUSES: u32 = 8;
+const DEVICES_PER_BUS: u16 = 24;
+
+fn board() -> FakeSysfs {
+    let mut fake = FakeSysfs::new();
+    for bus in 0..BUSES {
+        fake = fake.i2c_adapter(bus, &format!("i2c-bench-{}", bus));
+        for addr in (0x10..).step_by(4).take(DEVICES_PER_BUS as usize) {
+            let device = format!("{}-{:04x}", bus, addr);
+            fake = fake
+                .i2c_device(bus, addr, "bench", Some("at24"))
+                .file(
+                    &format!("sys/bus/i2c/devices/{}/uevent", device),
+                    "DRIVER=at24\nOF_NAME=eeprom\nOF_COMPATIBLE_0=atmel,24c02\nOF_COMPATIBLE_N=1\nMODALIAS=of:NeepromT(null)Catmel,24c02\n",
+                )
+                .udev_entry(
+                    &format!("+i2c:{}", device),
+                    &["ID_PATH=platform-fe5a0000.i2c"],
+                );
+        }
+    }
+    fake
+}
+
+fn manifest_text() -> String {
+    let mut text = String::new();
+    for bus in 0..BUSES {
+        for addr in (0x10..).step_by(4).take(DEVICES_PER_BUS as usize) {
+            text.push_str(&format!(
+                "[[i2c]]\nbus = {}\naddress = {}\nname = \"eeprom\"\ndriver = \"at24\"\n\n",
+                bus, addr
+            ));
+        }
+    }
+    text
+}
+
+fn discovery_benches(c: &mut Criterion) {
+    let fake = board();
+    let root = fake.root();
+    let context = root.discovery_context();
+
+    let scanner = LinuxI2cScanner::new(3).with_sys_root(&root.sys);
+    c.bench_function("scan_sysfs", |b| {
+        b.iter(|| black_box(scanner.scan_sysfs().unwrap()))
+    });
+    c.bench_function("discover_board", |b| {
+        b.iter(|| black_box(discovery::discover_board_in(&context).unwrap()))
+    });
+
+    let buses = discovery::discover_board_in(&context).unwrap().buses;
+    c.bench_function("report_to_json", |b| {
+        b.iter(|| black_box(serde_json::to_string(&report::to_json(&buses)).unwrap()))
+    });
+
+    let text = manifest_text();
+    c.bench_function("manifest_parse", |b| {
+        b.iter(|| black_box(Manifest::from_toml_str(&text).unwrap()))
+    });
+    let manifest = Manifest::from_toml_str(&text).unwrap();
+    c.bench_function("manifest_validate", |b| {
+        b.iter(|| black_box(manifest::validate(&manifest, &buses)))
+    });
+    let with_manifest = DiscoveryContext {
+        manifest: Some(manifest.clone()),
+        ..context.clone()
+    };
+    c.bench_function("discover_board_with_manifest", |b| {
+        b.iter(|| black_box(discovery::discover_board_in(&with_manifest).unwrap()))
+    });
+}
+
+criterion_group!(benches, discovery_benches);
+criterion_main!(benches);
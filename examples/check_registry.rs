@@ -0,0 +1,37 @@
+use clap::Parser;
+use tux_validation::registry;
+
+#[derive(Parser)]
+#[command(author, version, about = "Prints the built-in check registry")]
+struct Args {
+    /// Print Markdown documentation instead of JSON
+    #[arg(long)]
+    markdown: bool,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    if !args.markdown {
+        println!("{}", serde_json::to_string_pretty(&registry::to_json())?);
+        return Ok(());
+    }
+    for check in registry::CHECKS {
+        println!(
+            "## `{}`\n\n{} (access: {:?})\n",
+            check.id, check.description, check.access
+        );
+        println!("| Parameter | Type | Required | Description |");
+        println!("|---|---|---|---|");
+        for param in check.params {
+            println!(
+                "| `{}` | {} | {} | {} |",
+                param.name,
+                param.kind,
+                if param.required { "yes" } else { "no" },
+                param.description
+            );
+        }
+        println!();
+    }
+    Ok(())
+}
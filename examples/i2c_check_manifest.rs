@@ -0,0 +1,58 @@
+use clap::Parser;
+use std::path::PathBuf;
+use tux_validation::manifest::{self, Manifest};
+
+#[derive(Parser)]
+#[command(
+    author,
+    version,
+    about = "Checks the I2C buses against a board manifest"
+)]
+struct Args {
+    /// Board manifest (.toml, or .yaml/.yml)
+    manifest: PathBuf,
+
+    /// Perform hardware probe (smbus_quick_write)
+    #[arg(long)]
+    hw_probe: bool,
+
+    /// Print the result as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let manifest = Manifest::load(&args.manifest)?;
+    let result = manifest::run(&manifest, args.hw_probe)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&result.to_json())?);
+    } else {
+        for device in &result.present {
+            println!(
+                "OK: {} at {}-{:04x}",
+                device.name, device.bus, device.address
+            );
+        }
+        for device in &result.absent_optional {
+            println!(
+                "Optional {} at {}-{:04x} not fitted",
+                device.name, device.bus, device.address
+            );
+        }
+        for finding in &result.findings {
+            println!(
+                "{}: {} ({})",
+                finding.severity.to_uppercase(),
+                finding.message,
+                finding.address
+            );
+        }
+    }
+
+    if !result.is_ok() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -11,6 +11,9 @@ struct Args {
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
     println!(
         "{:<12} | {:<20} | {:<20}",
         "Bus", "Kernel Detected", "Responding Addresses"
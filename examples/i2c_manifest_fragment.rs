@@ -0,0 +1,22 @@
+use clap::Parser;
+use tux_validation::i2c::audit_all_i2c_buses;
+use tux_validation::manifest_gen::i2c_manifest_fragment;
+
+#[derive(Parser)]
+#[command(
+    author,
+    version,
+    about = "Prints an expected-I2C manifest fragment from a live scan."
+)]
+struct Args {
+    /// Perform hardware probe (smbus_quick_write) to include undeclared devices
+    #[arg(long)]
+    hw_probe: bool,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let buses = audit_all_i2c_buses(args.hw_probe)?;
+    print!("{}", i2c_manifest_fragment(&buses));
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use clap::Parser;
-use tux_validation::i2c::{LinuxI2cScanner, validate_bus};
+use tux_validation::i2c::{LinuxI2cScanner, validate_bus_with_forbidden};
 
 #[derive(Parser)]
 #[command(author, version, about = "Verifies I2C device addresses")]
@@ -10,11 +10,15 @@ struct Args {
 
     /// I2C BUS ID (e.g., 0)
     #[arg(short, long)]
-    bus_id: u8,
+    bus_id: u32,
 
     /// One or more device addresses (e.g., 0x1b 0x50)
     #[arg(value_parser = parse_hex, num_args = 1..)]
     addresses: Vec<u16>,
+
+    /// Addresses that must NOT be present, within the scanned range (e.g., --forbid 0x51)
+    #[arg(long, value_parser = parse_hex, num_args = 1..)]
+    forbid: Vec<u16>,
 }
 
 /// Helper to parse hex strings into u16
@@ -26,12 +30,11 @@ fn parse_hex(s: &str) -> Result<u16, String> {
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
-    let scanner = LinuxI2cScanner {
-        bus_id: args.bus_id,
-    };
+    let scanner = LinuxI2cScanner::new(args.bus_id);
 
-    println!("Checking I2C Bus {}...", args.bus_id.to_string());
-    let report = validate_bus(&scanner, &args.addresses, args.hw_probe)?;
+    println!("Checking I2C Bus {}...", args.bus_id);
+    let report =
+        validate_bus_with_forbidden(&scanner, &args.addresses, &args.forbid, args.hw_probe)?;
 
     for addr in &report.present {
         println!("Found expected device at 0x{:02x}", addr);
@@ -41,6 +44,10 @@ fn main() -> anyhow::Result<()> {
         println!("FAILED: Expected device at 0x{:02x} not found!", addr);
     }
 
+    for addr in &report.forbidden {
+        println!("FAILED: Forbidden device at 0x{:02x} is present!", addr);
+    }
+
     if !report.unexpected.is_empty() {
         println!("Found extra/unknown devices: {:02x?}", report.unexpected);
     }
@@ -50,7 +57,7 @@ fn main() -> anyhow::Result<()> {
     }
 
     //if report.missing.is_empty() {
-    //    println!("Bus {}: HEALTHY", args.bus_id.to_string());
+    //    println!("Bus {}: HEALTHY", args.bus_id);
     //} else {
     //    std::process::exit(1);
     //}
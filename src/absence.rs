@@ -0,0 +1,127 @@
+use crate::sockets::{self, Protocol};
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const USB_GADGET_DIR: &str = "/sys/kernel/config/usb_gadget";
+
+/// Something that must NOT be present on a production image.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MustBeAbsent {
+    /// I2C device known to the kernel, e.g. a JTAG debugger at 0x7f.
+    I2cDevice { bus_id: u32, addr: u16 },
+    /// Any sysfs/procfs/filesystem path, e.g. /dev/ttyGS0.
+    Path(PathBuf),
+    /// A running process, matched against /proc/<pid>/comm (e.g. "telnetd").
+    Process(String),
+    /// A USB gadget function type bound to a UDC, e.g. "acm" or "gser".
+    UsbGadgetFunction(String),
+    /// A listening socket on any address, e.g. telnet on TCP port 23.
+    ListeningPort { protocol: Protocol, port: u16 },
+}
+
+/// A must-be-absent item that was found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbsenceViolation {
+    pub item: MustBeAbsent,
+    pub evidence: String, // Where it was found
+}
+
+/// Checks each item and returns the ones that are present.
+pub fn check_absent(items: &[MustBeAbsent]) -> Result<Vec<AbsenceViolation>> {
+    let mut violations = Vec::new();
+    let mut listening = None;
+
+    for item in items {
+        let evidence = match item {
+            MustBeAbsent::I2cDevice { bus_id, addr } => {
+                let path = format!("/sys/bus/i2c/devices/{}-{:04x}", bus_id, addr);
+                Path::new(&path).exists().then_some(path)
+            }
+            MustBeAbsent::Path(path) => path.exists().then(|| path.display().to_string()),
+            MustBeAbsent::Process(name) => {
+                find_process(Path::new("/proc"), name)?.map(|pid| format!("/proc/{}/comm", pid))
+            }
+            MustBeAbsent::UsbGadgetFunction(function) => {
+                find_bound_gadget_function(Path::new(USB_GADGET_DIR), function)?
+                    .map(|p| p.display().to_string())
+            }
+            MustBeAbsent::ListeningPort { protocol, port } => {
+                if listening.is_none() {
+                    listening = Some(sockets::list_listening_sockets()?);
+                }
+                listening
+                    .iter()
+                    .flatten()
+                    .find(|s| s.protocol == *protocol && s.port == *port)
+                    .map(|s| {
+                        format!(
+                            "{} {}:{} ({})",
+                            s.protocol,
+                            s.address,
+                            s.port,
+                            s.process.as_deref().unwrap_or("unknown process")
+                        )
+                    })
+            }
+        };
+
+        if let Some(evidence) = evidence {
+            violations.push(AbsenceViolation {
+                item: item.clone(),
+                evidence,
+            });
+        }
+    }
+    Ok(violations)
+}
+
+/// Returns the PID of the first process whose `comm` equals `name`.
+pub fn find_process(proc_dir: &Path, name: &str) -> Result<Option<u32>> {
+    for entry in fs::read_dir(proc_dir)? {
+        let entry = entry?;
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        // Processes can exit between readdir and read
+        if let Ok(comm) = fs::read_to_string(entry.path().join("comm"))
+            && comm.trim() == name
+        {
+            return Ok(Some(pid));
+        }
+    }
+    Ok(None)
+}
+
+/// Finds a function of type `function` linked into a config of a gadget bound to a UDC.
+///
+/// Functions are named `<type>.<instance>`, e.g. `acm.usb0`. Unbound gadgets are ignored.
+pub fn find_bound_gadget_function(gadget_dir: &Path, function: &str) -> Result<Option<PathBuf>> {
+    let Ok(gadgets) = fs::read_dir(gadget_dir) else {
+        // configfs not mounted or no gadgets
+        return Ok(None);
+    };
+
+    for gadget in gadgets.filter_map(|e| e.ok()) {
+        let udc = fs::read_to_string(gadget.path().join("UDC")).unwrap_or_default();
+        if udc.trim().is_empty() {
+            continue;
+        }
+        let Ok(configs) = fs::read_dir(gadget.path().join("configs")) else {
+            continue;
+        };
+        for config in configs.filter_map(|e| e.ok()) {
+            for link in fs::read_dir(config.path())?.filter_map(|e| e.ok()) {
+                let name = link.file_name().to_string_lossy().to_string();
+                if link.file_type()?.is_symlink() && name.split('.').next() == Some(function) {
+                    return Ok(Some(link.path()));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,112 @@
+use crate::evidence::EvidenceBundle;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// An operator's note on one check result, added after the run, e.g. by a rework technician
+/// describing what they saw on a failing unit.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub check: String, // The result it is about, e.g. "i2c-1/1-0050" or "rpi_firmware"
+    pub author: String,
+    pub comment: String,
+    pub time: u64, // Seconds since the epoch
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<PathBuf>, // Photos and other files, relative to the evidence bundle
+}
+
+impl Annotation {
+    /// A note made now.
+    pub fn new(check: &str, author: &str, comment: &str) -> Annotation {
+        Annotation {
+            check: check.to_string(),
+            author: author.to_string(),
+            comment: comment.to_string(),
+            time: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            attachments: Vec::new(),
+        }
+    }
+
+    /// Copies files, e.g. photos of the board, into the bundle next to the check's other
+    /// evidence and references them from the annotation.
+    pub fn attach(&mut self, bundle: &mut EvidenceBundle, files: &[PathBuf]) -> Result<()> {
+        let description = format!("operator {}: {}", self.author, self.comment);
+        for file in files {
+            let path = bundle
+                .add_file(&self.check, file, &description)
+                .map_err(|e| anyhow::anyhow!("{}: {}", file.display(), e))?;
+            let relative = path.strip_prefix(&bundle.dir).unwrap_or(&path);
+            self.attachments.push(relative.to_path_buf());
+        }
+        Ok(())
+    }
+}
+
+/// Adds an annotation to a report's `annotations` list.
+pub fn add_to_report(report: &mut Value, annotation: &Annotation) -> Result<()> {
+    let Some(report) = report.as_object_mut() else {
+        anyhow::bail!("report is not a JSON object");
+    };
+    let list = report
+        .entry("annotations")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    let Some(list) = list.as_array_mut() else {
+        anyhow::bail!("report `annotations` is not a list");
+    };
+    list.push(serde_json::to_value(annotation)?);
+    Ok(())
+}
+
+/// The annotations of a report, oldest first.
+pub fn from_report(report: &Value) -> Result<Vec<Annotation>> {
+    match report.get("annotations") {
+        None => Ok(Vec::new()),
+        Some(list) => Ok(serde_json::from_value(list.clone())?),
+    }
+}
+
+/// Annotations of one check.
+pub fn for_check<'a>(annotations: &'a [Annotation], check: &str) -> Vec<&'a Annotation> {
+    annotations.iter().filter(|a| a.check == check).collect()
+}
+
+/// Merges an annotation into a stored run: its attachments into the evidence bundle at
+/// `bundle_dir`, whose index is rewritten, and the annotation into the report file.
+///
+/// A signed bundle no longer verifies afterwards; sign it again with
+/// [`crate::signing::StationKey::sign_bundle`].
+pub fn annotate_stored(
+    report_path: &Path,
+    bundle_dir: Option<&Path>,
+    mut annotation: Annotation,
+    files: &[PathBuf],
+) -> Result<Annotation> {
+    let text = fs::read_to_string(report_path)
+        .map_err(|e| anyhow::anyhow!("{}: {}", report_path.display(), e))?;
+    let mut report: Value = serde_json::from_str(&text)
+        .map_err(|e| anyhow::anyhow!("{}: {}", report_path.display(), e))?;
+    match bundle_dir {
+        Some(dir) => {
+            let mut bundle = EvidenceBundle::open(dir)?;
+            annotation.attach(&mut bundle, files)?;
+            bundle.write_index()?;
+        }
+        None if !files.is_empty() => {
+            anyhow::bail!("attachments need an evidence bundle to go into");
+        }
+        None => {}
+    }
+    add_to_report(&mut report, &annotation)
+        .map_err(|e| anyhow::anyhow!("{}: {}", report_path.display(), e))?;
+    let tmp = report_path.with_extension("tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(&report)? + "\n")
+        .and_then(|_| fs::rename(&tmp, report_path))
+        .map_err(|e| anyhow::anyhow!("{}: {}", report_path.display(), e))?;
+    Ok(annotation)
+}
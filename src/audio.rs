@@ -0,0 +1,341 @@
+use crate::derating::Threshold;
+use crate::runtime::{self, Dependency};
+use anyhow::Result;
+use serde_json::Value;
+use std::f64::consts::PI;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Frequency error allowed when the manifest sets none.
+pub const DEFAULT_FREQUENCY_TOLERANCE_HZ: f64 = 20.0;
+
+/// Bins either side of the peak counted as the tone; a Hann window spreads it over three.
+const TONE_BINS: usize = 3;
+
+/// Generates a sine tone as S16_LE samples; 0 dBFS is a full-scale sine.
+pub fn tone(frequency: f64, amplitude_dbfs: f64, rate: u32, samples: usize) -> Vec<i16> {
+    let amplitude = 32767.0 * 10f64.powf(amplitude_dbfs / 20.0);
+    (0..samples)
+        .map(|n| {
+            let phase = 2.0 * PI * frequency * n as f64 / f64::from(rate);
+            (amplitude * phase.sin()).round().clamp(-32768.0, 32767.0) as i16
+        })
+        .collect()
+}
+
+/// Decodes raw S16_LE audio, as `arecord -t raw -f S16_LE` writes it.
+pub fn parse_s16le(bytes: &[u8]) -> Vec<i16> {
+    bytes
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect()
+}
+
+/// RMS level relative to a full-scale sine, in dBFS.
+pub fn level_dbfs(samples: &[i16]) -> f64 {
+    if samples.is_empty() {
+        return f64::NEG_INFINITY;
+    }
+    let power = samples.iter().map(|s| f64::from(*s).powi(2)).sum::<f64>() / samples.len() as f64;
+    20.0 * (power.sqrt() * 2f64.sqrt() / 32768.0).log10()
+}
+
+/// The part of a recording where the tone plays: the blocks within 6 dB of the loudest,
+/// less a tenth at either end for the ramp and the codec's settling.
+pub fn tone_segment(samples: &[i16], rate: u32) -> &[i16] {
+    let block = (rate as usize / 100).max(1); // 10 ms
+    let levels: Vec<f64> = samples.chunks(block).map(level_dbfs).collect();
+    let loudest = levels.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let loud = |l: &f64| *l >= loudest - 6.0;
+    let (Some(first), Some(last)) = (levels.iter().position(loud), levels.iter().rposition(loud))
+    else {
+        return samples;
+    };
+    let (start, end) = (first * block, ((last + 1) * block).min(samples.len()));
+    let trim = (end - start) / 10;
+    &samples[start + trim..end - trim]
+}
+
+/// In-place radix-2 FFT; `re` and `im` have the same power-of-two length.
+fn fft(re: &mut [f64], im: &mut [f64]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f64).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let (tr, ti) = (re[b] * cos - im[b] * sin, re[b] * sin + im[b] * cos);
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// What a recording contains.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToneAnalysis {
+    pub frequency_hz: f64, // Strongest component, interpolated between bins
+    pub level_dbfs: f64,
+    pub snr_db: f64, // Tone against everything else but DC, noise and distortion alike
+}
+
+/// Finds the dominant tone of a recording with a Hann-windowed FFT over the largest
+/// power-of-two prefix; None for recordings too short to analyse.
+pub fn analyse(samples: &[i16], rate: u32) -> Option<ToneAnalysis> {
+    let n = 1usize << samples.len().checked_ilog2()?;
+    if n < 256 {
+        return None;
+    }
+    let mut re: Vec<f64> = samples[..n]
+        .iter()
+        .enumerate()
+        .map(|(i, s)| f64::from(*s) * (0.5 - 0.5 * (2.0 * PI * i as f64 / n as f64).cos()))
+        .collect();
+    let mut im = vec![0.0; n];
+    fft(&mut re, &mut im);
+    let power: Vec<f64> = re[..n / 2]
+        .iter()
+        .zip(&im)
+        .map(|(r, i)| r * r + i * i)
+        .collect();
+
+    // Skip DC and the window's leakage of it
+    let peak = (TONE_BINS..power.len()).max_by(|a, b| power[*a].total_cmp(&power[*b]))?;
+    let offset = match (power.get(peak - 1), power.get(peak + 1)) {
+        (Some(l), Some(r)) => {
+            let (l, c, r) = (l.sqrt().ln(), power[peak].sqrt().ln(), r.sqrt().ln());
+            let denominator = l - 2.0 * c + r;
+            if denominator == 0.0 {
+                0.0
+            } else {
+                0.5 * (l - r) / denominator
+            }
+        }
+        _ => 0.0,
+    };
+    let tone_bins = peak.saturating_sub(TONE_BINS)..(peak + TONE_BINS + 1).min(power.len());
+    let tone: f64 = power[tone_bins.clone()].iter().sum();
+    let rest: f64 = power[TONE_BINS..]
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !tone_bins.contains(&(i + TONE_BINS)))
+        .map(|(_, p)| p)
+        .sum();
+    Some(ToneAnalysis {
+        frequency_hz: (peak as f64 + offset) * f64::from(rate) / n as f64,
+        level_dbfs: level_dbfs(&samples[..n]),
+        snr_db: 10.0 * (tone / rest.max(f64::MIN_POSITIVE)).log10(),
+    })
+}
+
+/// Pass thresholds of a loopback measurement. Unset limits are not checked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoopbackLimits {
+    pub frequency_tolerance_hz: f64,
+    pub min_level_dbfs: Option<f64>,
+    pub max_level_dbfs: Option<f64>, // Catches a shorted or mis-routed gain stage
+    pub min_snr_db: Option<f64>,
+}
+
+impl Default for LoopbackLimits {
+    fn default() -> Self {
+        LoopbackLimits {
+            frequency_tolerance_hz: DEFAULT_FREQUENCY_TOLERANCE_HZ,
+            min_level_dbfs: None,
+            max_level_dbfs: None,
+            min_snr_db: None,
+        }
+    }
+}
+
+impl LoopbackLimits {
+    /// Reads the `limits` table of a manifest; like sampling limits, each may be derated by
+    /// temperature and is resolved against the temperature now.
+    pub fn resolve_in(limits: &Value, sys_root: &Path) -> Result<LoopbackLimits> {
+        let get = |key: &str| -> Result<Option<f64>> {
+            match limits.get(key) {
+                Some(value) => Threshold::from_value(value)
+                    .and_then(|t| t.resolve_in(sys_root))
+                    .map(Some)
+                    .map_err(|e| anyhow::anyhow!("limits.{}: {}", key, e)),
+                None => Ok(None),
+            }
+        };
+        Ok(LoopbackLimits {
+            frequency_tolerance_hz: get("frequency_tolerance_hz")?
+                .unwrap_or(DEFAULT_FREQUENCY_TOLERANCE_HZ),
+            min_level_dbfs: get("min_level_dbfs")?,
+            max_level_dbfs: get("max_level_dbfs")?,
+            min_snr_db: get("min_snr_db")?,
+        })
+    }
+}
+
+/// Returns human-readable descriptions of every violated limit.
+pub fn check_tone(analysis: &ToneAnalysis, frequency: f64, limits: &LoopbackLimits) -> Vec<String> {
+    let mut problems = Vec::new();
+    if (analysis.frequency_hz - frequency).abs() > limits.frequency_tolerance_hz {
+        problems.push(format!(
+            "recorded tone is {:.1} Hz, expected {:.1} ± {:.1} Hz",
+            analysis.frequency_hz, frequency, limits.frequency_tolerance_hz
+        ));
+    }
+    if let Some(min) = limits.min_level_dbfs
+        && analysis.level_dbfs < min
+    {
+        problems.push(format!(
+            "level {:.1} dBFS is below {:.1} dBFS",
+            analysis.level_dbfs, min
+        ));
+    }
+    if let Some(max) = limits.max_level_dbfs
+        && analysis.level_dbfs > max
+    {
+        problems.push(format!(
+            "level {:.1} dBFS is above {:.1} dBFS",
+            analysis.level_dbfs, max
+        ));
+    }
+    if let Some(min) = limits.min_snr_db
+        && analysis.snr_db < min
+    {
+        problems.push(format!(
+            "SNR {:.1} dB is below {:.1} dB",
+            analysis.snr_db, min
+        ));
+    }
+    problems
+}
+
+/// A tone played on an output and recorded on an input looped back on the jig.
+#[derive(Debug, Clone)]
+pub struct LoopbackConfig {
+    pub output: String, // ALSA playback device, e.g. "hw:0,0"
+    pub input: String,  // ALSA capture device
+    pub frequency: f64,
+    pub amplitude_dbfs: f64,
+    pub rate: u32,
+    pub duration: Duration, // Of the tone; the recording is a second longer
+    pub limits: LoopbackLimits,
+}
+
+impl Default for LoopbackConfig {
+    fn default() -> Self {
+        LoopbackConfig {
+            output: "default".to_string(),
+            input: "default".to_string(),
+            frequency: 1000.0,
+            amplitude_dbfs: -6.0,
+            rate: 48000,
+            duration: Duration::from_secs(1),
+            limits: LoopbackLimits::default(),
+        }
+    }
+}
+
+/// Outcome of [`run_loopback`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoopbackResult {
+    pub analysis: Option<ToneAnalysis>,
+    pub problems: Vec<String>,
+}
+
+impl LoopbackResult {
+    pub fn is_ok(&self) -> bool {
+        self.analysis.is_some() && self.problems.is_empty()
+    }
+}
+
+/// Analyses a loopback recording against the tone that was played.
+pub fn check_recording(samples: &[i16], config: &LoopbackConfig) -> LoopbackResult {
+    let analysis = analyse(tone_segment(samples, config.rate), config.rate);
+    let problems = match &analysis {
+        Some(analysis) => check_tone(analysis, config.frequency, &config.limits),
+        None => vec![format!(
+            "recording is too short ({} samples)",
+            samples.len()
+        )],
+    };
+    LoopbackResult { analysis, problems }
+}
+
+/// Records on the input with `arecord` while `aplay` plays the tone on the output, then
+/// analyses the recording. Both run mono S16_LE at the configured rate.
+pub fn run_loopback(config: &LoopbackConfig) -> Result<LoopbackResult> {
+    let format = ["-f", "S16_LE", "-c", "1", "-t", "raw", "-q"];
+    let rate = config.rate.to_string();
+    let seconds = (config.duration.as_secs_f64().ceil() as u64 + 1).to_string();
+    runtime::require(&Dependency::program("arecord"))?;
+    runtime::require(&Dependency::program("aplay"))?;
+    let mut recorder = Command::new("arecord")
+        .args(["-D", &config.input, "-r", &rate, "-d", &seconds])
+        .args(format)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("arecord: {}", e))?;
+    let mut stdout = recorder.stdout.take().expect("stdout is piped");
+    let reader = std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        stdout.read_to_end(&mut bytes).map(|_| bytes)
+    });
+
+    let samples = (config.duration.as_secs_f64() * f64::from(config.rate)) as usize;
+    let pcm: Vec<u8> = tone(
+        config.frequency,
+        config.amplitude_dbfs,
+        config.rate,
+        samples,
+    )
+    .iter()
+    .flat_map(|s| s.to_le_bytes())
+    .collect();
+    let mut player = Command::new("aplay")
+        .args(["-D", &config.output, "-r", &rate])
+        .args(format)
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("aplay: {}", e))?;
+    // The stdin handle is dropped, closing the pipe, once written
+    let written = player.stdin.take().expect("stdin is piped").write_all(&pcm);
+    let played = player.wait_with_output()?;
+
+    let bytes = reader
+        .join()
+        .map_err(|_| anyhow::anyhow!("arecord reader panicked"))??;
+    let recorded = recorder.wait()?;
+    if !played.status.success() {
+        anyhow::bail!(
+            "aplay -D {}: {}",
+            config.output,
+            String::from_utf8_lossy(&played.stderr).trim()
+        );
+    }
+    written?;
+    if !recorded.success() {
+        anyhow::bail!("arecord -D {} exited with {}", config.input, recorded);
+    }
+    Ok(check_recording(&parse_s16le(&bytes), config))
+}
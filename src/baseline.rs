@@ -0,0 +1,229 @@
+use crate::measurement::{Measurement, Unit};
+use anyhow::Result;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Which way a metric improves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Better {
+    Higher, // Throughput, bandwidth
+    Lower,  // Latency, boot time
+}
+
+/// Golden value of one performance metric, keyed by the measurement source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineEntry {
+    pub metric: String, // Matches `Measurement::source`, e.g. "mmcblk0/read"
+    pub value: f64,
+    pub unit: Unit,
+    pub better: Better,
+    pub tolerance_pct: f64, // Allowed degradation before it counts as a regression
+    pub limit: Option<f64>, // Hard limit in `unit`; crossing it is a failure
+}
+
+/// Golden baselines of one board type, stored as `<dir>/<board>.toml`:
+///
+/// ```toml
+/// [boot_time]
+/// value = 8.2
+/// unit = "s"
+/// better = "lower"
+/// tolerance = 10 # percent
+/// limit = 15
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Baseline {
+    pub entries: Vec<BaselineEntry>,
+}
+
+const DEFAULT_TOLERANCE_PCT: f64 = 5.0;
+
+impl Baseline {
+    pub fn from_toml_str(text: &str) -> Result<Baseline> {
+        let table: toml::Table = text.parse()?;
+        let mut baseline = Baseline::default();
+        for (metric, entry) in &table {
+            let number = |key: &str| {
+                entry
+                    .get(key)
+                    .and_then(|v| v.as_float().or_else(|| v.as_integer().map(|i| i as f64)))
+            };
+            let string = |key: &str| entry.get(key).and_then(|v| v.as_str());
+            let Some(value) = number("value") else {
+                anyhow::bail!("{}: missing number `value`", metric);
+            };
+            let unit = string("unit").unwrap_or("");
+            let Some(unit) = Unit::from_symbol(unit) else {
+                anyhow::bail!("{}: unknown unit `{}`", metric, unit);
+            };
+            let better = match string("better") {
+                Some("higher") | None => Better::Higher,
+                Some("lower") => Better::Lower,
+                Some(other) => anyhow::bail!(
+                    "{}: `better` must be higher or lower, not {}",
+                    metric,
+                    other
+                ),
+            };
+            baseline.entries.push(BaselineEntry {
+                metric: metric.clone(),
+                value,
+                unit,
+                better,
+                tolerance_pct: number("tolerance").unwrap_or(DEFAULT_TOLERANCE_PCT),
+                limit: number("limit"),
+            });
+        }
+        Ok(baseline)
+    }
+
+    /// Loads the baseline of `board` from a directory of `<board>.toml` files.
+    pub fn load_for_board(dir: &Path, board: &str) -> Result<Baseline> {
+        let path = dir.join(format!("{}.toml", board));
+        fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| Baseline::from_toml_str(&text))
+            .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+    }
+
+    /// Records a run as the new golden baseline; settings of existing entries are kept.
+    pub fn update(&mut self, measurements: &[Measurement]) {
+        for m in measurements {
+            match self.entries.iter_mut().find(|e| e.metric == m.source) {
+                Some(entry) => {
+                    if let Some(value) = m.value_in(entry.unit) {
+                        entry.value = value;
+                    }
+                }
+                None => self.entries.push(BaselineEntry {
+                    metric: m.source.clone(),
+                    value: m.value,
+                    unit: m.unit,
+                    better: Better::Higher,
+                    tolerance_pct: DEFAULT_TOLERANCE_PCT,
+                    limit: None,
+                }),
+            }
+        }
+    }
+
+    /// Inverse of [`Baseline::from_toml_str`].
+    pub fn to_toml_string(&self) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            out.push_str(&format!("[{:?}]\n", entry.metric));
+            out.push_str(&format!("value = {:?}\n", entry.value));
+            out.push_str(&format!("unit = {:?}\n", entry.unit.symbol()));
+            let better = match entry.better {
+                Better::Higher => "higher",
+                Better::Lower => "lower",
+            };
+            out.push_str(&format!("better = {:?}\n", better));
+            out.push_str(&format!("tolerance = {:?}\n", entry.tolerance_pct));
+            if let Some(limit) = entry.limit {
+                out.push_str(&format!("limit = {:?}\n", limit));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// How a run compares with the baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Regression, // Worse than the baseline beyond the tolerance, but within the hard limit
+    Failure(String),
+}
+
+/// One metric of a run against its baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub metric: String,
+    pub baseline: f64,
+    pub actual: Option<f64>, // In the baseline's unit; None if not measured
+    pub unit: Unit,
+    pub change_pct: f64, // Positive is better, whatever the direction of the metric
+    pub verdict: Verdict,
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = self.unit.symbol();
+        match (&self.verdict, self.actual) {
+            (Verdict::Failure(reason), _) => write!(f, "{}: FAILED, {}", self.metric, reason),
+            (verdict, Some(actual)) => write!(
+                f,
+                "{}: {:.2} {} vs baseline {:.2} {} ({:+.1}%){}",
+                self.metric,
+                actual,
+                symbol,
+                self.baseline,
+                symbol,
+                self.change_pct,
+                if *verdict == Verdict::Regression {
+                    ", REGRESSION"
+                } else {
+                    ""
+                }
+            ),
+            (_, None) => write!(f, "{}: not measured", self.metric),
+        }
+    }
+}
+
+/// Compares measurements with every baseline entry. A missing measurement is a failure.
+pub fn compare(baseline: &Baseline, measurements: &[Measurement]) -> Vec<Comparison> {
+    baseline
+        .entries
+        .iter()
+        .map(|entry| {
+            let actual = measurements
+                .iter()
+                .find(|m| m.source == entry.metric)
+                .and_then(|m| m.value_in(entry.unit));
+            let mut comparison = Comparison {
+                metric: entry.metric.clone(),
+                baseline: entry.value,
+                actual,
+                unit: entry.unit,
+                change_pct: 0.0,
+                verdict: Verdict::Pass,
+            };
+            let Some(actual) = actual else {
+                comparison.verdict = Verdict::Failure(format!(
+                    "no measurement convertible to {}",
+                    entry.unit.symbol()
+                ));
+                return comparison;
+            };
+            if entry.value != 0.0 {
+                let change = (actual - entry.value) / entry.value.abs() * 100.0;
+                comparison.change_pct = match entry.better {
+                    Better::Higher => change,
+                    Better::Lower => -change,
+                };
+            }
+            let beyond_limit = entry.limit.is_some_and(|limit| match entry.better {
+                Better::Higher => actual < limit,
+                Better::Lower => actual > limit,
+            });
+            comparison.verdict = if beyond_limit {
+                Verdict::Failure(format!(
+                    "{} {} beyond limit {} {}",
+                    actual,
+                    entry.unit.symbol(),
+                    entry.limit.unwrap_or_default(),
+                    entry.unit.symbol()
+                ))
+            } else if comparison.change_pct < -entry.tolerance_pct {
+                Verdict::Regression
+            } else {
+                Verdict::Pass
+            };
+            comparison
+        })
+        .collect()
+}
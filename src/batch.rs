@@ -0,0 +1,163 @@
+use crate::soc;
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Per-unit identifiers that must be unique across a production batch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnitIdentity {
+    pub unit: String, // Report name, e.g. the file stem
+    pub serials: Vec<String>,
+    pub macs: Vec<String>, // Lowercase, colon-separated
+    pub machine_id: Option<String>,
+}
+
+impl UnitIdentity {
+    /// Collects identifiers of the running unit: SoC/board serials, physical NIC MACs
+    /// and /etc/machine-id.
+    pub fn collect(unit: &str) -> UnitIdentity {
+        UnitIdentity::collect_in(unit, Path::new("/sys"), Path::new("/etc/machine-id"))
+    }
+
+    /// Same as [`UnitIdentity::collect`], with explicit sysfs root and machine-id file.
+    pub fn collect_in(unit: &str, sys_root: &Path, machine_id_path: &Path) -> UnitIdentity {
+        let mut identity = UnitIdentity {
+            unit: unit.to_string(),
+            ..Default::default()
+        };
+        let info = soc::read_soc_info_in(sys_root);
+        identity
+            .serials
+            .extend(info.serial_number.into_iter().chain(info.chip_id));
+        if let Ok(serial) =
+            fs::read_to_string(sys_root.join("firmware/devicetree/base/serial-number"))
+        {
+            identity
+                .serials
+                .push(serial.trim_end_matches('\0').trim().to_string());
+        }
+
+        // Only interfaces backed by a device; virtual ones get random MACs
+        if let Ok(entries) = fs::read_dir(sys_root.join("class/net")) {
+            for entry in entries.filter_map(|e| e.ok()) {
+                let dir = entry.path();
+                if dir.join("device").exists()
+                    && let Ok(mac) = fs::read_to_string(dir.join("address"))
+                {
+                    identity.macs.push(mac.trim().to_lowercase());
+                }
+            }
+        }
+        identity.macs.sort();
+        identity.machine_id = fs::read_to_string(machine_id_path)
+            .ok()
+            .map(|id| id.trim().to_string());
+        identity
+    }
+
+    /// The `identity` object stored in unit reports.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "serials": self.serials,
+            "macs": self.macs,
+            "machine_id": self.machine_id,
+        })
+    }
+
+    /// Reads the `identity` object of a unit report.
+    pub fn from_json(unit: &str, report: &Value) -> UnitIdentity {
+        let identity = &report["identity"];
+        let strings = |key: &str| -> Vec<String> {
+            identity[key]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        };
+        UnitIdentity {
+            unit: unit.to_string(),
+            serials: strings("serials"),
+            macs: strings("macs").iter().map(|m| m.to_lowercase()).collect(),
+            machine_id: identity["machine_id"].as_str().map(|s| s.to_string()),
+        }
+    }
+}
+
+/// Adds the `identity` object to a unit report.
+pub fn record(report: &mut Value, identity: &UnitIdentity) {
+    if let Some(report) = report.as_object_mut() {
+        report.insert("identity".to_string(), identity.to_json());
+    }
+}
+
+/// Loads every `*.json` unit report in a directory, sorted by file name.
+///
+/// Reports without an `identity` object, e.g. from older versions, are loaded empty and
+/// warned about: they can't take part in [`find_duplicates`].
+pub fn load_reports(dir: &Path) -> Result<Vec<UnitIdentity>> {
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .collect();
+    paths.sort();
+
+    let mut units = Vec::new();
+    for path in paths {
+        let report: Value = serde_json::from_slice(&fs::read(&path)?)
+            .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+        let unit = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if report["identity"].is_null() {
+            tracing::warn!(report = %path.display(), "no `identity` object, skipped in duplicate checks");
+        }
+        units.push(UnitIdentity::from_json(&unit, &report));
+    }
+    Ok(units)
+}
+
+/// An identifier shared by more than one unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duplicate {
+    pub kind: &'static str, // "serial", "mac" or "machine_id"
+    pub value: String,
+    pub units: Vec<String>,
+}
+
+/// Values that carry no identity (unprogrammed or placeholder).
+fn is_placeholder(value: &str) -> bool {
+    value.is_empty()
+        || value
+            .chars()
+            .all(|c| c == '0' || c == ':' || c == '-' || c == 'f' || c == 'F')
+}
+
+/// Finds serials, MACs and machine-ids shared between units.
+///
+/// Unprogrammed values (all zeros/ones) are reported too, since they're the usual cause.
+pub fn find_duplicates(units: &[UnitIdentity]) -> Vec<Duplicate> {
+    let mut seen: BTreeMap<(&'static str, String), Vec<String>> = BTreeMap::new();
+    for unit in units {
+        let values = unit
+            .serials
+            .iter()
+            .map(|s| ("serial", s.clone()))
+            .chain(unit.macs.iter().map(|m| ("mac", m.clone())))
+            .chain(unit.machine_id.iter().map(|m| ("machine_id", m.clone())));
+        for key in values {
+            let owners = seen.entry(key).or_default();
+            if !owners.contains(&unit.unit) {
+                owners.push(unit.unit.clone());
+            }
+        }
+    }
+    seen.into_iter()
+        .filter(|((_, value), owners)| owners.len() > 1 || is_placeholder(value))
+        .map(|((kind, value), units)| Duplicate { kind, value, units })
+        .collect()
+}
@@ -0,0 +1,565 @@
+use clap::{Parser, Subcommand};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use tux_validation::annotation::{self, Annotation};
+use tux_validation::batch::{self, UnitIdentity};
+use tux_validation::i2c::{self, LinuxI2cScanner};
+use tux_validation::manifest::{self, Manifest};
+use tux_validation::quarantine::{self, Quarantine, Target};
+use tux_validation::seed::{self, Seed};
+use tux_validation::system_root::SystemRoot;
+use tux_validation::{
+    container_mode, devicetree, discovery, hwmon, kmsg, os_release, report, thermal,
+};
+
+#[derive(Parser)]
+#[command(author, version, about = "Linux board validation")]
+struct Cli {
+    /// Log to stderr: -v for device enumeration, -vv for every probe attempt
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Lists the devices on every bus of every registered subsystem
+    Scan {
+        /// Perform hardware probe (quick write, or receive byte for EEPROM and RTC addresses)
+        #[arg(long)]
+        hw_probe: bool,
+    },
+    /// Checks that devices are (or are not) present on one I2C bus
+    Verify {
+        /// I2C BUS ID (e.g., 0)
+        #[arg(short, long)]
+        bus_id: u32,
+
+        /// One or more device addresses (e.g., 0x1b 0x50)
+        #[arg(value_parser = parse_hex, num_args = 1..)]
+        addresses: Vec<u16>,
+
+        /// Addresses that must NOT be present, within the scanned range (e.g., --forbid 0x51)
+        #[arg(long, value_parser = parse_hex, num_args = 1..)]
+        forbid: Vec<u16>,
+
+        /// Perform hardware probe (quick write, or receive byte for EEPROM and RTC addresses)
+        #[arg(long)]
+        hw_probe: bool,
+
+        /// Only scan these addresses (e.g., 0x50-0x57)
+        #[arg(long, value_parser = parse_hex_range)]
+        range: Option<RangeInclusive<u16>>,
+
+        /// Also write the result as JUnit XML to this file
+        #[arg(long)]
+        junit: Option<PathBuf>,
+    },
+    /// Writes a JSON board report of all buses
+    Report {
+        /// Perform hardware probe (quick write, or receive byte for EEPROM and RTC addresses)
+        #[arg(long)]
+        hw_probe: bool,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Print the device changes since this earlier report instead
+        #[arg(long)]
+        diff: Option<PathBuf>,
+
+        /// Also write the report as an HTML page to this file
+        #[arg(long)]
+        html: Option<PathBuf>,
+
+        /// Seed of the randomized test patterns, e.g. from an earlier report
+        #[arg(long)]
+        seed: Option<Seed>,
+    },
+    /// Checks the board against a manifest (.toml, or .yaml/.yml)
+    Audit {
+        manifest: PathBuf,
+
+        /// Perform hardware probe (quick write, or receive byte for EEPROM and RTC addresses)
+        #[arg(long)]
+        hw_probe: bool,
+
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Also write the result as JUnit XML to this file
+        #[arg(long)]
+        junit: Option<PathBuf>,
+
+        /// Also write the buses and the result as an HTML page to this file
+        #[arg(long)]
+        html: Option<PathBuf>,
+
+        /// Seed of the randomized test patterns, e.g. from an earlier report
+        #[arg(long)]
+        seed: Option<Seed>,
+
+        /// Run host-only checks even inside a container
+        #[arg(long)]
+        host: bool,
+    },
+    /// Renders a manifest as a test plan for review and sign-off (Markdown, or HTML)
+    Plan {
+        manifest: PathBuf,
+
+        /// Render a self-contained HTML page instead of Markdown
+        #[arg(long)]
+        html: bool,
+
+        /// Write the plan to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Adds an operator comment, and optionally photos, to a check result of a stored report
+    Annotate {
+        /// JSON report of the run, as written by `report` or `audit --json`
+        report: PathBuf,
+
+        /// The result commented on, e.g. "i2c-1/1-0050"
+        check: String,
+
+        comment: String,
+
+        /// Defaults to $USER
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Files to attach, e.g. photos; need --evidence
+        #[arg(long)]
+        attach: Vec<PathBuf>,
+
+        /// Evidence bundle of the run, where attachments are copied to
+        #[arg(long)]
+        evidence: Option<PathBuf>,
+    },
+    /// Lists quarantined buses and devices, or lifts their quarantine
+    Quarantine {
+        /// Targets to clear, e.g. "i2c-1" or "i2c-1/1-0050"
+        #[arg(long)]
+        clear: Vec<Target>,
+
+        /// Quarantine list to use
+        #[arg(long, default_value = quarantine::QUARANTINE_PATH)]
+        path: PathBuf,
+    },
+    /// Cross-references the device tree's I2C clients with what the kernel and bus show
+    DtI2c {
+        /// Perform hardware probe (quick write, or receive byte for EEPROM and RTC addresses)
+        #[arg(long)]
+        hw_probe: bool,
+    },
+    /// Lists sensor readings and cooling states, and checks them against a manifest's
+    /// `[hwmon]` and `[thermal]` sections
+    Sensors { manifest: Option<PathBuf> },
+    /// Checks the OS ID and version codename
+    OsRelease {
+        /// Expected OS ID (e.g., debian)
+        #[arg(short, long)]
+        id: String,
+
+        /// Expected Version Codename (e.g., forky)
+        #[arg(short, long)]
+        codename: String,
+
+        #[arg(long, default_value = "/etc/os-release")]
+        path: String,
+    },
+}
+
+/// Helper to parse hex strings into u16
+fn parse_hex(s: &str) -> Result<u16, String> {
+    u16::from_str_radix(s.trim_start_matches("0x"), 16)
+        .map_err(|e| format!("Invalid hex address '{}': {}", s, e))
+}
+
+/// Helper to parse "0x50-0x57" into an address range
+fn parse_hex_range(s: &str) -> Result<RangeInclusive<u16>, String> {
+    let (start, end) = s
+        .split_once('-')
+        .ok_or_else(|| format!("Invalid address range '{}': expected START-END", s))?;
+    let (start, end) = (parse_hex(start)?, parse_hex(end)?);
+    if start > end {
+        return Err(format!("Invalid address range '{}': START above END", s));
+    }
+    Ok(start..=end)
+}
+
+fn hex_list(addrs: &[u16]) -> String {
+    let addrs: Vec<String> = addrs.iter().map(|a| format!("0x{:02x}", a)).collect();
+    addrs.join(", ")
+}
+
+/// Returns whether the checks passed; failures exit with status 1.
+fn run(command: Command) -> anyhow::Result<bool> {
+    match command {
+        Command::Scan { hw_probe } => {
+            for bus in discovery::discover_board(hw_probe)?.buses {
+                println!("{} {}", bus.id, bus.name);
+                for device in &bus.devices {
+                    let driver = device.driver.as_deref().unwrap_or("-");
+                    let probed = if device.hw_responded {
+                        " (probed)"
+                    } else if device.probe_deferred {
+                        " (probe deferred)"
+                    } else {
+                        ""
+                    };
+                    println!(
+                        "  {:<10} {:<20} {}{}",
+                        device.address.to_string(),
+                        device.name,
+                        driver,
+                        probed
+                    );
+                }
+            }
+            Ok(true)
+        }
+        Command::Verify {
+            bus_id,
+            addresses,
+            forbid,
+            hw_probe,
+            range,
+            junit,
+        } => {
+            let mut scanner = LinuxI2cScanner::new(bus_id);
+            if let Some(range) = range {
+                scanner = scanner.with_addresses(range);
+            }
+            let result = i2c::validate_bus_with_forbidden(&scanner, &addresses, &forbid, hw_probe)?;
+            if let Some(path) = junit {
+                std::fs::write(path, report::junit(&[result.junit_suite(bus_id)]))?;
+            }
+            println!("Bus {}: present {}", bus_id, hex_list(&result.present));
+            if !result.missing.is_empty() {
+                println!("FAILED: missing {}", hex_list(&result.missing));
+            }
+            if !result.forbidden.is_empty() {
+                println!("FAILED: forbidden present {}", hex_list(&result.forbidden));
+            }
+            if !result.unexpected.is_empty() {
+                println!("Extra/unknown devices: {}", hex_list(&result.unexpected));
+            }
+            Ok(result.is_ok())
+        }
+        Command::Report {
+            hw_probe,
+            output,
+            diff,
+            html,
+            seed,
+        } => {
+            let seed = Seed::resolve(seed, None)?;
+            let buses = discovery::discover_board(hw_probe)?.buses;
+            if let Some(path) = html {
+                std::fs::write(path, report::html::render("Board report", &buses, None))?;
+            }
+            if let Some(old) = diff {
+                let old = report::parse(&std::fs::read_to_string(&old)?)
+                    .map_err(|e| anyhow::anyhow!("{}: {}", old.display(), e))?;
+                for change in report::diff(&old, &buses) {
+                    println!("{}", change);
+                }
+                return Ok(true);
+            }
+            let mut report = report::to_json(&buses);
+            seed::record(&mut report, seed);
+            batch::record(&mut report, &UnitIdentity::collect(""));
+            let text = serde_json::to_string_pretty(&report)?;
+            match output {
+                Some(path) => std::fs::write(path, text + "\n")?,
+                None => println!("{}", text),
+            }
+            Ok(true)
+        }
+        Command::Audit {
+            manifest,
+            hw_probe,
+            json,
+            junit,
+            html,
+            seed,
+            host,
+        } => {
+            let title = format!("Audit against {}", manifest.display());
+            let mut manifest = Manifest::load(&manifest)?;
+            let mut skipped = Vec::new();
+            let mut warnings = Vec::new();
+            if !host && let Some(container) = container_mode::detect() {
+                let engine = container.engine.as_deref().unwrap_or("unknown");
+                tracing::debug!(engine, evidence = ?container.evidence, "running in a container");
+                skipped = container.adjust(&mut manifest);
+                warnings = container_mode::warnings_in(&SystemRoot::live());
+                if !container.privileged {
+                    warnings.push("container is unprivileged: bus access will fail".to_string());
+                }
+            }
+            let seed = Seed::resolve(seed, Some(&manifest))?;
+            let board = discovery::discover_board_in(&discovery::DiscoveryContext {
+                hw_probe,
+                manifest: Some(manifest.clone()),
+                ..Default::default()
+            })?;
+            let mut buses = board.buses;
+            // Probe errors explain missing and unbound devices; the log may be restricted
+            let kernel_findings = kmsg::scan().unwrap_or_else(|e| {
+                tracing::debug!(error = %e, "kernel log not readable");
+                Vec::new()
+            });
+            kmsg::attach(&mut buses, &kernel_findings);
+            let mut result = manifest::validate(&manifest, &buses);
+            if hw_probe {
+                manifest::verify_identities(&manifest, &buses, &mut result, |device| {
+                    i2c::verify_registers(device.bus, device.address, &device.registers)
+                });
+            }
+            if let Some(path) = junit {
+                let mut suites = report::manifest_suites(&result);
+                if !skipped.is_empty() {
+                    suites.push(report::TestSuite {
+                        name: "container".to_string(),
+                        cases: skipped.clone(),
+                    });
+                }
+                std::fs::write(path, report::junit(&suites))?;
+            }
+            if let Some(path) = html {
+                std::fs::write(path, report::html::render(&title, &buses, Some(&result)))?;
+            }
+            if json {
+                let mut report = result.to_json();
+                seed::record(&mut report, seed);
+                batch::record(&mut report, &UnitIdentity::collect(""));
+                let kernel_log: serde_json::Map<String, serde_json::Value> = result
+                    .findings
+                    .iter()
+                    .map(|f| {
+                        (
+                            f.address.to_string(),
+                            kmsg::for_device(&kernel_findings, &f.address),
+                        )
+                    })
+                    .filter(|(_, lines)| !lines.is_empty())
+                    .map(|(address, lines)| (address, serde_json::json!(lines)))
+                    .collect();
+                if !kernel_log.is_empty() {
+                    report["kernel_log"] = serde_json::Value::Object(kernel_log);
+                }
+                if !skipped.is_empty() || !warnings.is_empty() {
+                    report["container"] = serde_json::json!({
+                        "skipped": skipped.iter().map(|c| &c.name).collect::<Vec<_>>(),
+                        "warnings": warnings,
+                    });
+                }
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("Seed: {}", seed);
+                for warning in &warnings {
+                    println!("WARNING: {}", warning);
+                }
+                for case in &skipped {
+                    let reason = case.skipped.as_deref().unwrap_or_default();
+                    println!("SKIPPED: {} ({})", case.name, reason);
+                }
+                for device in &result.present {
+                    println!(
+                        "OK: {} at {}-{:04x}",
+                        device.name, device.bus, device.address
+                    );
+                }
+                for finding in &result.findings {
+                    println!(
+                        "{}: {} ({})",
+                        finding.severity.to_uppercase(),
+                        finding.message,
+                        finding.address
+                    );
+                    for line in kmsg::for_device(&kernel_findings, &finding.address) {
+                        println!("  {}", line);
+                    }
+                }
+            }
+            Ok(result.is_ok())
+        }
+        Command::Plan {
+            manifest,
+            html,
+            output,
+        } => {
+            let title = format!("Test plan: {}", manifest.display());
+            let manifest = Manifest::load(&manifest)?;
+            let text = if html {
+                report::plan::html(&title, &manifest)
+            } else {
+                report::plan::markdown(&title, &manifest)
+            };
+            match output {
+                Some(path) => std::fs::write(path, text)?,
+                None => print!("{}", text),
+            }
+            Ok(report::plan::planned_checks(&manifest)
+                .iter()
+                .all(|c| c.missing().is_empty()))
+        }
+        Command::Annotate {
+            report,
+            check,
+            comment,
+            author,
+            attach,
+            evidence,
+        } => {
+            let author = author
+                .or_else(|| std::env::var("USER").ok())
+                .unwrap_or_else(|| "unknown".to_string());
+            let annotation = annotation::annotate_stored(
+                &report,
+                evidence.as_deref(),
+                Annotation::new(&check, &author, &comment),
+                &attach,
+            )?;
+            println!("Annotated {} in {}", annotation.check, report.display());
+            for path in &annotation.attachments {
+                println!("  attached {}", path.display());
+            }
+            Ok(true)
+        }
+        Command::Quarantine { clear, path } => {
+            let mut list = Quarantine::load(&path)?;
+            if !clear.is_empty() {
+                for target in &clear {
+                    if !list.clear(target) {
+                        println!("{}: not in quarantine", target);
+                    }
+                }
+                list.save(&path)?;
+            }
+            for (target, entry) in &list.entries {
+                let state = if entry.is_quarantined() {
+                    "QUARANTINED"
+                } else {
+                    "counting"
+                };
+                println!(
+                    "{:<12} {} {}/{} ({})",
+                    state, target, entry.failures, list.threshold, entry.last_failure
+                );
+            }
+            Ok(true)
+        }
+        Command::DtI2c { hw_probe } => {
+            let declared = devicetree::read_i2c_devices_in(Path::new("/sys"))?;
+            let buses = i2c::audit_all_i2c_buses(hw_probe)?;
+            let xref = devicetree::cross_reference_i2c(&declared, &buses);
+            let sections = [
+                ("Bound", &xref.bound),
+                ("Declared but never probed", &xref.not_probed),
+                ("Declared on a bus without an adapter", &xref.unmapped),
+                ("Disabled but responding", &xref.disabled),
+            ];
+            for (title, devices) in sections {
+                println!("{} ({}):", title, devices.len());
+                for device in devices {
+                    let address = device
+                        .address()
+                        .map(|a| a.to_string())
+                        .unwrap_or_else(|| format!("0x{:02x}", device.addr));
+                    println!("  {:<10} {}", address, device.path);
+                }
+            }
+            println!(
+                "Unexpected, not in the device tree ({}):",
+                xref.undeclared.len()
+            );
+            for address in &xref.undeclared {
+                println!("  {}", address);
+            }
+            Ok(xref.not_probed.is_empty() && xref.undeclared.is_empty())
+        }
+        Command::Sensors { manifest } => {
+            for device in hwmon::find_hwmon_devices()? {
+                println!("{} {}", device.address, device.name);
+                for (name, value) in &device.attributes {
+                    println!("  {:<16} {}", name, value);
+                }
+            }
+            let zones = thermal::list_zones()?;
+            let cooling = thermal::list_cooling_devices()?;
+            for zone in &zones {
+                let temp = zone.temperature.map(|t| format!("{} °C", t));
+                println!(
+                    "{} {} {}",
+                    zone.name,
+                    zone.kind,
+                    temp.as_deref().unwrap_or("?")
+                );
+            }
+            for device in &cooling {
+                println!(
+                    "{} {} {}/{}",
+                    device.name, device.kind, device.cur_state, device.max_state
+                );
+            }
+            let Some(manifest) = manifest else {
+                return Ok(true);
+            };
+            let manifest = Manifest::load(&manifest)?;
+            let limits = hwmon::HwmonLimit::from_manifest(&manifest)?;
+            let mut problems = hwmon::validate_limits(&limits);
+            let expected = thermal::ExpectedThermal::from_manifest(&manifest)?;
+            problems.extend(thermal::validate_thermal(&zones, &cooling, &expected));
+            for problem in &problems {
+                println!("FAIL: {}", problem);
+            }
+            if problems.is_empty() {
+                println!("Sensors OK");
+            }
+            Ok(problems.is_empty())
+        }
+        Command::OsRelease { id, codename, path } => {
+            let osr = os_release::parse_os_release(&path)?;
+            let actual_id = osr.get("ID").map(|s| s.as_str()).unwrap_or("unknown");
+            let actual_code = osr
+                .get("VERSION_CODENAME")
+                .map(|s| s.as_str())
+                .unwrap_or("unknown");
+            let passed =
+                id.eq_ignore_ascii_case(actual_id) && codename.eq_ignore_ascii_case(actual_code);
+            if passed {
+                println!("Validation Passed: {} ({})", actual_id, actual_code);
+            } else {
+                println!("Validation Failed!");
+                println!("Expected: {} ({})", id, codename);
+                println!("Actual:   {} ({})", actual_id, actual_code);
+            }
+            Ok(passed)
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let level = match cli.verbose {
+        0 => tracing::Level::WARN,
+        1 => tracing::Level::DEBUG,
+        _ => tracing::Level::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_max_level(level)
+        .init();
+    if !run(cli.command)? {
+        std::process::exit(1);
+    }
+    Ok(())
+}
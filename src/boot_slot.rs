@@ -0,0 +1,252 @@
+use crate::os_release;
+use crate::runtime::{self, Dependency};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// State of one A/B slot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotState {
+    pub name: String, // e.g. "A", "B" or "_a", "_b"
+    pub bootable: bool,
+    pub attempts_left: Option<u32>,
+}
+
+/// Boot slot state as seen by the bootloader.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbState {
+    pub active_slot: String,
+    pub slots: Vec<SlotState>,
+    pub boot_count: Option<u32>, // Boots since the last successful-boot mark
+}
+
+impl AbState {
+    /// The first other slot, i.e. the one we'd fall back to.
+    pub fn fallback_slot(&self) -> Option<&SlotState> {
+        self.slots.iter().find(|s| s.name != self.active_slot)
+    }
+}
+
+pub trait BootSlotSource {
+    fn read_state(&self) -> Result<AbState>;
+}
+
+/// Parses `fw_printenv` output (`name=value` lines). Values are kept verbatim.
+pub fn parse_env_from_reader<R: BufRead>(reader: R) -> Result<HashMap<String, String>> {
+    let mut env = HashMap::new();
+    for line_result in reader.lines() {
+        let line = line_result?;
+        if let Some((k, v)) = line.split_once('=') {
+            env.insert(k.to_string(), v.to_string());
+        }
+    }
+    Ok(env)
+}
+
+/// Returns the value of `key=value` on the kernel command line.
+pub fn cmdline_value(cmdline: &str, key: &str) -> Option<String> {
+    cmdline
+        .split_whitespace()
+        .find_map(|arg| arg.strip_prefix(key)?.strip_prefix('='))
+        .map(|v| v.to_string())
+}
+
+/// U-Boot environment in the RAUC layout: `BOOT_ORDER="A B"`, `BOOT_A_LEFT=3`, ..
+pub struct UBootEnv {
+    pub order_var: String,           // "BOOT_ORDER"
+    pub attempts_var_prefix: String, // "BOOT_" -> BOOT_A_LEFT
+    pub attempts_var_suffix: String, // "_LEFT"
+    pub bootcount_var: Option<String>,
+    pub cmdline_slot_key: Option<String>, // e.g. "rauc.slot"; falls back to BOOT_ORDER
+}
+
+impl Default for UBootEnv {
+    fn default() -> Self {
+        UBootEnv {
+            order_var: "BOOT_ORDER".to_string(),
+            attempts_var_prefix: "BOOT_".to_string(),
+            attempts_var_suffix: "_LEFT".to_string(),
+            bootcount_var: Some("bootcount".to_string()),
+            cmdline_slot_key: Some("rauc.slot".to_string()),
+        }
+    }
+}
+
+impl UBootEnv {
+    /// Builds the slot state from a parsed environment and the kernel command line.
+    pub fn state_from_env(&self, env: &HashMap<String, String>, cmdline: &str) -> Result<AbState> {
+        let Some(order) = env.get(&self.order_var) else {
+            anyhow::bail!("{} not set in U-Boot environment", self.order_var);
+        };
+
+        let slots: Vec<SlotState> = order
+            .split_whitespace()
+            .map(|name| {
+                let var = format!(
+                    "{}{}{}",
+                    self.attempts_var_prefix, name, self.attempts_var_suffix
+                );
+                let attempts_left = env.get(&var).and_then(|v| v.trim().parse::<u32>().ok());
+                SlotState {
+                    name: name.to_string(),
+                    bootable: attempts_left.is_none_or(|a| a > 0),
+                    attempts_left,
+                }
+            })
+            .collect();
+
+        let active_slot = self
+            .cmdline_slot_key
+            .as_ref()
+            .and_then(|key| cmdline_value(cmdline, key))
+            .or_else(|| slots.iter().find(|s| s.bootable).map(|s| s.name.clone()))
+            .unwrap_or_default();
+
+        Ok(AbState {
+            active_slot,
+            slots,
+            boot_count: self
+                .bootcount_var
+                .as_ref()
+                .and_then(|var| env.get(var))
+                .and_then(|v| v.trim().parse().ok()),
+        })
+    }
+}
+
+impl BootSlotSource for UBootEnv {
+    /// Reads the environment via `fw_printenv` (needs a valid /etc/fw_env.config).
+    fn read_state(&self) -> Result<AbState> {
+        runtime::require(&Dependency::program("fw_printenv"))?;
+        let output = Command::new("fw_printenv").output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "fw_printenv failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let env = parse_env_from_reader(output.stdout.as_slice())?;
+        let cmdline = std::fs::read_to_string("/proc/cmdline")?;
+        self.state_from_env(&env, &cmdline)
+    }
+}
+
+/// Android-style `bootctl` HAL client (slots `_a`, `_b`, ..).
+pub struct Bootctl {
+    pub program: String, // "bootctl"
+}
+
+impl Bootctl {
+    fn run(&self, args: &[&str]) -> Result<(bool, String)> {
+        runtime::require(&Dependency::program(&self.program))?;
+        let output = Command::new(&self.program).args(args).output()?;
+        Ok((
+            output.status.success(),
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ))
+    }
+}
+
+impl BootSlotSource for Bootctl {
+    fn read_state(&self) -> Result<AbState> {
+        let (ok, count) = self.run(&["get-number-slots"])?;
+        let count: u32 = if ok { count.parse()? } else { 2 };
+        let (ok, current) = self.run(&["get-current-slot"])?;
+        if !ok {
+            anyhow::bail!("{} get-current-slot failed", self.program);
+        }
+        let current: u32 = current.parse()?;
+
+        let mut slots = Vec::new();
+        let mut active_slot = String::new();
+        for i in 0..count {
+            let index = i.to_string();
+            let (_, suffix) = self.run(&["get-suffix", &index])?;
+            // `is-slot-bootable` reports via exit status only
+            let (bootable, _) = self.run(&["is-slot-bootable", &index])?;
+            if i == current {
+                active_slot = suffix.clone();
+            }
+            slots.push(SlotState {
+                name: suffix,
+                bootable,
+                attempts_left: None,
+            });
+        }
+        Ok(AbState {
+            active_slot,
+            slots,
+            boot_count: None,
+        })
+    }
+}
+
+/// Expected A/B state. Unset fields are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedAbState {
+    pub active_slot: Option<String>,
+    pub min_attempts_left: Option<u32>, // For the active slot
+    pub max_boot_count: Option<u32>,
+    /// Where the fallback slot's rootfs is mounted; its os-release must have a VERSION_ID.
+    pub fallback_mount: Option<PathBuf>,
+}
+
+/// Returns human-readable descriptions of every mismatch.
+///
+/// Checks the fallback slot is bootable and, if mounted, contains a versioned image.
+pub fn validate_state(state: &AbState, expected: &ExpectedAbState) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    if let Some(slot) = &expected.active_slot
+        && &state.active_slot != slot
+    {
+        mismatches.push(format!(
+            "active slot {} (expected {})",
+            state.active_slot, slot
+        ));
+    }
+
+    if let Some(min) = expected.min_attempts_left
+        && let Some(active) = state.slots.iter().find(|s| s.name == state.active_slot)
+        && active.attempts_left.is_some_and(|a| a < min)
+    {
+        mismatches.push(format!(
+            "slot {}: {:?} boot attempts left (expected at least {})",
+            active.name, active.attempts_left, min
+        ));
+    }
+
+    if let Some(max) = expected.max_boot_count
+        && state.boot_count.is_some_and(|c| c > max)
+    {
+        mismatches.push(format!(
+            "boot count {:?} (expected at most {})",
+            state.boot_count, max
+        ));
+    }
+
+    match state.fallback_slot() {
+        None => mismatches.push("no fallback slot".to_string()),
+        Some(fallback) if !fallback.bootable => {
+            mismatches.push(format!("fallback slot {} is not bootable", fallback.name))
+        }
+        Some(fallback) => {
+            if let Some(mount) = &expected.fallback_mount {
+                let path = mount.join("etc/os-release");
+                let version = os_release::parse_os_release(&path.to_string_lossy())
+                    .ok()
+                    .and_then(|osr| osr.get("VERSION_ID").cloned());
+                if version.is_none() {
+                    mismatches.push(format!(
+                        "fallback slot {}: no image version in {}",
+                        fallback.name,
+                        path.display()
+                    ));
+                }
+            }
+        }
+    }
+    mismatches
+}
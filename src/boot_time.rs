@@ -0,0 +1,185 @@
+pub use crate::kmsg::read_kmsg;
+use crate::measurement::{Measurement, Unit};
+use crate::runtime::{self, Dependency};
+use anyhow::Result;
+use std::process::Command;
+use std::time::Duration;
+
+/// Boot phase durations. Phases the source can't measure are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BootTiming {
+    pub firmware: Option<Duration>,
+    pub loader: Option<Duration>,
+    pub kernel: Duration,
+    pub initrd: Option<Duration>,
+    pub userspace: Option<Duration>, // None if boot hasn't finished yet
+    pub total: Duration,
+    pub source: String, // "systemd" or "uptime"
+}
+
+impl BootTiming {
+    /// The measured phases as typed measurements, in seconds.
+    pub fn measurements(&self) -> Vec<Measurement> {
+        [
+            ("firmware", self.firmware),
+            ("loader", self.loader),
+            ("kernel", Some(self.kernel)),
+            ("initrd", self.initrd),
+            ("userspace", self.userspace),
+            ("total", Some(self.total)),
+        ]
+        .into_iter()
+        .filter_map(|(phase, d)| {
+            d.map(|d| {
+                Measurement::new(
+                    d.as_secs_f64(),
+                    Unit::Second,
+                    &format!("boot_time/{}/{}", self.source, phase),
+                )
+            })
+        })
+        .collect()
+    }
+}
+
+const MANAGER_PROPERTIES: [&str; 5] = [
+    "FirmwareTimestampMonotonic",
+    "LoaderTimestampMonotonic",
+    "InitRDTimestampMonotonic",
+    "UserspaceTimestampMonotonic",
+    "FinishTimestampMonotonic",
+];
+
+/// Computes phases the same way `systemd-analyze time` does.
+///
+/// Takes the Manager timestamps in µs, in the order of `MANAGER_PROPERTIES`.
+/// Firmware/loader are "µs before kernel start"; the rest are monotonic.
+pub fn timing_from_systemd(timestamps: [u64; 5]) -> BootTiming {
+    let [firmware, loader, initrd, userspace, finish] = timestamps;
+    let us = Duration::from_micros;
+    let nonzero = |v: u64| (v > 0).then_some(v);
+
+    let kernel_end = nonzero(initrd).unwrap_or(userspace);
+    let userspace_time = nonzero(finish).map(|f| us(f.saturating_sub(userspace)));
+    BootTiming {
+        firmware: nonzero(firmware).map(|f| us(f.saturating_sub(loader))),
+        loader: nonzero(loader).map(us),
+        kernel: us(kernel_end),
+        initrd: nonzero(initrd).map(|i| us(userspace.saturating_sub(i))),
+        userspace: userspace_time,
+        total: us(firmware.max(loader) + nonzero(finish).unwrap_or(userspace)),
+        source: "systemd".to_string(),
+    }
+}
+
+/// Parses `busctl get-property` output: one `t <value>` line per property.
+pub fn parse_busctl_u64s(output: &str) -> Result<Vec<u64>> {
+    output
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("t "))
+        .map(|v| Ok(v.trim().parse::<u64>()?))
+        .collect()
+}
+
+/// Reads boot timestamps from systemd's Manager object over D-Bus.
+pub fn read_systemd_timing() -> Result<BootTiming> {
+    runtime::require(&Dependency::DBus)?;
+    let output = Command::new("busctl")
+        .args([
+            "get-property",
+            "org.freedesktop.systemd1",
+            "/org/freedesktop/systemd1",
+            "org.freedesktop.systemd1.Manager",
+        ])
+        .args(MANAGER_PROPERTIES)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "busctl get-property failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let values = parse_busctl_u64s(&String::from_utf8_lossy(&output.stdout))?;
+    let Ok(timestamps) = <[u64; 5]>::try_from(values) else {
+        anyhow::bail!("Unexpected busctl output for boot timestamps");
+    };
+    Ok(timing_from_systemd(timestamps))
+}
+
+/// Finds the kernel-to-init handover timestamp in `/dev/kmsg`-format records.
+///
+/// Records look like `6,339,5140900,-;Run /sbin/init as init process`.
+pub fn kernel_handover_from_kmsg(records: &str) -> Option<Duration> {
+    records.lines().find_map(|line| {
+        let (prefix, message) = line.split_once(';')?;
+        if !message.starts_with("Run ") || !message.ends_with("as init process") {
+            return None;
+        }
+        let usec = prefix.split(',').nth(2)?.parse::<u64>().ok()?;
+        Some(Duration::from_micros(usec))
+    })
+}
+
+/// Fallback for non-systemd images: total from /proc/uptime, kernel from kmsg.
+///
+/// Meant to be called from the last service in the boot sequence.
+pub fn read_uptime_timing() -> Result<BootTiming> {
+    let uptime = std::fs::read_to_string("/proc/uptime")?;
+    let total: f64 = uptime
+        .split_whitespace()
+        .next()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| anyhow::anyhow!("Malformed /proc/uptime"))?;
+    let total = Duration::from_secs_f64(total);
+    let kernel = read_kmsg()
+        .ok()
+        .and_then(|records| kernel_handover_from_kmsg(&records))
+        .unwrap_or_default();
+
+    Ok(BootTiming {
+        firmware: None,
+        loader: None,
+        kernel,
+        initrd: None,
+        userspace: Some(total.saturating_sub(kernel)),
+        total,
+        source: "uptime".to_string(),
+    })
+}
+
+/// Reads boot timing from systemd, falling back to /proc/uptime.
+pub fn read_boot_timing() -> Result<BootTiming> {
+    read_systemd_timing().or_else(|_| read_uptime_timing())
+}
+
+/// Maximum allowed durations per phase. Unset budgets are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct BootBudget {
+    pub kernel: Option<Duration>,
+    pub userspace: Option<Duration>,
+    pub total: Option<Duration>,
+}
+
+/// Returns human-readable descriptions of every exceeded budget.
+pub fn validate_timing(timing: &BootTiming, budget: &BootBudget) -> Vec<String> {
+    let mut violations = Vec::new();
+    let phases = [
+        ("kernel", Some(timing.kernel), budget.kernel),
+        ("userspace", timing.userspace, budget.userspace),
+        ("total", Some(timing.total), budget.total),
+    ];
+    for (name, actual, limit) in phases {
+        let Some(limit) = limit else { continue };
+        match actual {
+            Some(actual) if actual > limit => violations.push(format!(
+                "{} boot time {:.3}s exceeds budget {:.3}s",
+                name,
+                actual.as_secs_f64(),
+                limit.as_secs_f64()
+            )),
+            Some(_) => {}
+            None => violations.push(format!("{} boot time not available", name)),
+        }
+    }
+    violations
+}
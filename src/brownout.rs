@@ -0,0 +1,341 @@
+use crate::manifest::Manifest;
+use crate::report::TestCase;
+use anyhow::Result;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Sampling interval of the monitor unless the manifest sets one.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Regulator fault flags of the regulator sysfs ABI that mean the supply sagged.
+const REGULATOR_FLAGS: [&str; 4] = [
+    "under_voltage",
+    "under_voltage_warn",
+    "regulation_out",
+    "fail",
+];
+
+/// A source of power-fault conditions, sampled throughout a run.
+pub trait PowerIndicator: Send {
+    fn name(&self) -> String;
+
+    /// Conditions active right now, e.g. "under_voltage"; empty while power is good.
+    fn active(&mut self) -> Vec<String>;
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn entries(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Fault flags of every regulator in /sys/class/regulator.
+pub struct RegulatorFlags {
+    pub sys_root: PathBuf,
+}
+
+impl PowerIndicator for RegulatorFlags {
+    fn name(&self) -> String {
+        "regulators".to_string()
+    }
+
+    fn active(&mut self) -> Vec<String> {
+        let mut active = Vec::new();
+        for dir in entries(&self.sys_root.join("class/regulator")) {
+            let name = read_trimmed(&dir.join("name")).unwrap_or_else(|| {
+                dir.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            });
+            for flag in REGULATOR_FLAGS {
+                if read_trimmed(&dir.join(flag)).as_deref() == Some("1") {
+                    active.push(format!("{} {}", name, flag));
+                }
+            }
+        }
+        active
+    }
+}
+
+/// Low-voltage alarms (`in*_lcrit_alarm`, `in*_min_alarm`) of every hwmon, e.g. rpi_volt.
+pub struct HwmonAlarms {
+    pub sys_root: PathBuf,
+}
+
+impl PowerIndicator for HwmonAlarms {
+    fn name(&self) -> String {
+        "hwmon".to_string()
+    }
+
+    fn active(&mut self) -> Vec<String> {
+        let mut active = Vec::new();
+        for dir in entries(&self.sys_root.join("class/hwmon")) {
+            let chip = read_trimmed(&dir.join("name")).unwrap_or_default();
+            for path in entries(&dir) {
+                let file = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                if file.starts_with("in")
+                    && (file.ends_with("_lcrit_alarm") || file.ends_with("_min_alarm"))
+                    && read_trimmed(&path).as_deref() == Some("1")
+                {
+                    active.push(format!("{} {}", chip, file));
+                }
+            }
+        }
+        active
+    }
+}
+
+/// A PMIC status register bit, read through regmap debugfs, e.g. a brownout flag.
+pub struct PmicStatus {
+    pub regmap_dir: PathBuf, // /sys/kernel/debug/regmap
+    pub device: String,      // Regmap device, e.g. "0-001b"
+    pub register: u32,
+    pub mask: u32, // Active if any of these bits is set
+    pub condition: String,
+}
+
+impl PowerIndicator for PmicStatus {
+    fn name(&self) -> String {
+        format!("pmic {}", self.device)
+    }
+
+    fn active(&mut self) -> Vec<String> {
+        crate::pmic::read_regmap(&self.regmap_dir, &self.device)
+            .and_then(|regmap| regmap.get(&self.register).copied())
+            .filter(|value| value & self.mask != 0)
+            .map(|_| vec![self.condition.clone()])
+            .unwrap_or_default()
+    }
+}
+
+/// The Pi firmware's live under-voltage bit.
+#[cfg(feature = "rpi")]
+pub struct RpiUndervoltage {
+    pub sys_root: PathBuf,
+}
+
+#[cfg(feature = "rpi")]
+impl PowerIndicator for RpiUndervoltage {
+    fn name(&self) -> String {
+        "rpi firmware".to_string()
+    }
+
+    fn active(&mut self) -> Vec<String> {
+        let status = crate::rpi::read_status_in(&self.sys_root);
+        match status.throttled {
+            Some(bits) if bits & 1 != 0 => vec!["under-voltage".to_string()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// The indicators every board may have: regulator flags, hwmon alarms and, with the `rpi`
+/// feature, the Pi firmware.
+pub fn default_indicators_in(sys_root: &Path) -> Vec<Box<dyn PowerIndicator>> {
+    vec![
+        Box::new(RegulatorFlags {
+            sys_root: sys_root.to_path_buf(),
+        }),
+        Box::new(HwmonAlarms {
+            sys_root: sys_root.to_path_buf(),
+        }),
+        #[cfg(feature = "rpi")]
+        Box::new(RpiUndervoltage {
+            sys_root: sys_root.to_path_buf(),
+        }),
+    ]
+}
+
+/// The default indicators plus the `[brownout]` section's PMIC status bits, and its
+/// sampling interval.
+pub fn indicators_from_manifest(
+    manifest: &Manifest,
+    sys_root: &Path,
+) -> Result<(Vec<Box<dyn PowerIndicator>>, Duration)> {
+    let mut indicators = default_indicators_in(sys_root);
+    let Some(section) = manifest.sections.get("brownout") else {
+        return Ok((indicators, DEFAULT_INTERVAL));
+    };
+    let interval = match section.get("interval") {
+        Some(value) => crate::manifest::parse_duration(value)
+            .map_err(|e| anyhow::anyhow!("brownout.interval: {}", e))?,
+        None => DEFAULT_INTERVAL,
+    };
+    for (i, pmic) in section["pmic"].as_array().into_iter().flatten().enumerate() {
+        let int = |key: &str| -> Result<u32> {
+            pmic[key]
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| anyhow::anyhow!("brownout pmic {}: missing integer `{}`", i, key))
+        };
+        let Some(device) = pmic["device"].as_str() else {
+            anyhow::bail!("brownout pmic {}: missing `device`", i);
+        };
+        indicators.push(Box::new(PmicStatus {
+            regmap_dir: sys_root.join("kernel/debug/regmap"),
+            device: device.to_string(),
+            register: int("register")?,
+            mask: int("mask")?,
+            condition: pmic["condition"].as_str().unwrap_or("brownout").to_string(),
+        }));
+    }
+    Ok((indicators, interval))
+}
+
+/// A condition of one indicator, active over a span of the run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerEvent {
+    pub indicator: String,
+    pub condition: String,
+    pub start: Duration,       // Since the monitor started
+    pub end: Option<Duration>, // None if still active when the monitor stopped
+}
+
+impl PowerEvent {
+    /// Whether the event was active at any time during `span`.
+    pub fn overlaps(&self, span: &Range<Duration>) -> bool {
+        self.start <= span.end && self.end.is_none_or(|end| end >= span.start)
+    }
+}
+
+impl std::fmt::Display for PowerEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} from {:.1}s",
+            self.indicator,
+            self.condition,
+            self.start.as_secs_f64()
+        )?;
+        match self.end {
+            Some(end) => write!(f, " to {:.1}s", end.as_secs_f64()),
+            None => write!(f, " on"),
+        }
+    }
+}
+
+/// Turns samples into events: a condition starts when first seen and ends when a sample
+/// no longer shows it.
+#[derive(Debug, Clone, Default)]
+pub struct EventLog {
+    pub events: Vec<PowerEvent>,
+}
+
+impl EventLog {
+    pub fn record(&mut self, at: Duration, indicator: &str, active: &[String]) {
+        for event in &mut self.events {
+            if event.indicator == indicator
+                && event.end.is_none()
+                && !active.contains(&event.condition)
+            {
+                event.end = Some(at);
+            }
+        }
+        for condition in active {
+            let ongoing = self
+                .events
+                .iter()
+                .any(|e| e.indicator == indicator && &e.condition == condition && e.end.is_none());
+            if !ongoing {
+                self.events.push(PowerEvent {
+                    indicator: indicator.to_string(),
+                    condition: condition.clone(),
+                    start: at,
+                    end: None,
+                });
+            }
+        }
+    }
+}
+
+/// Samples power indicators on a background thread for the length of a validation run.
+pub struct BrownoutMonitor {
+    started: Instant,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<EventLog>,
+}
+
+impl BrownoutMonitor {
+    pub fn start(mut indicators: Vec<Box<dyn PowerIndicator>>, interval: Duration) -> Self {
+        let started = Instant::now();
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                let mut log = EventLog::default();
+                loop {
+                    let done = stop.load(Ordering::SeqCst);
+                    for indicator in &mut indicators {
+                        let active = indicator.active();
+                        log.record(started.elapsed(), &indicator.name(), &active);
+                    }
+                    if done {
+                        return log;
+                    }
+                    std::thread::park_timeout(interval);
+                }
+            })
+        };
+        BrownoutMonitor {
+            started,
+            stop,
+            handle,
+        }
+    }
+
+    /// Time since the monitor started, the clock of [`PowerEvent`]s.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Runs a check and returns its span on the monitor's clock.
+    pub fn time<T>(&self, check: impl FnOnce() -> T) -> (T, Range<Duration>) {
+        let start = self.elapsed();
+        let result = check();
+        (result, start..self.elapsed())
+    }
+
+    /// Takes a last sample and returns every event of the run.
+    pub fn stop(self) -> Vec<PowerEvent> {
+        self.stop.store(true, Ordering::SeqCst);
+        self.handle.thread().unpark();
+        self.handle.join().map(|log| log.events).unwrap_or_default()
+    }
+}
+
+/// Events active during `span`.
+pub fn events_during<'a>(events: &'a [PowerEvent], span: &Range<Duration>) -> Vec<&'a PowerEvent> {
+    events.iter().filter(|e| e.overlaps(span)).collect()
+}
+
+/// Marks a failed case that ran during a power event, so a sagging supply isn't taken for a
+/// device fault. Returns whether it did.
+pub fn annotate_case(case: &mut TestCase, span: &Range<Duration>, events: &[PowerEvent]) -> bool {
+    let during = events_during(events, span);
+    let Some(failure) = &mut case.failure else {
+        return false;
+    };
+    if during.is_empty() {
+        return false;
+    }
+    let during: Vec<String> = during.iter().map(|e| e.to_string()).collect();
+    failure.push_str(&format!(" [during power event: {}]", during.join("; ")));
+    true
+}
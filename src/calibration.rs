@@ -0,0 +1,170 @@
+use anyhow::Result;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// A check on a calibration blob.
+#[derive(Debug, Clone)]
+pub enum CalibrationCheck {
+    /// CRC-32 (IEEE) over `data`, stored little- or big-endian at `crc_offset`.
+    Crc32 {
+        data: Range<usize>,
+        crc_offset: usize,
+        little_endian: bool,
+    },
+    /// A per-unit field that must be programmed: not all 0x00, not all 0xff, not `default`.
+    Field {
+        name: String,
+        range: Range<usize>,
+        default: Option<Vec<u8>>,
+    },
+    /// A `key=value` entry of a text blob (e.g. brcmfmac NVRAM) that must be set per unit.
+    Key {
+        key: String,
+        default: Option<String>,
+    },
+}
+
+/// A calibration blob: a file, nvmem cell or EEPROM `eeprom` attribute, optionally a slice of it.
+#[derive(Debug, Clone, Default)]
+pub struct CalibrationSpec {
+    pub name: String,
+    pub path: PathBuf,
+    pub offset: u64,
+    pub len: Option<usize>, // Rest of the file when unset
+    pub min_size: usize,
+    pub checks: Vec<CalibrationCheck>,
+}
+
+/// CRC-32 as used by zlib/Ethernet (reflected, polynomial 0xedb88320).
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Reads the blob described by `spec`.
+pub fn read_blob(spec: &CalibrationSpec) -> Result<Vec<u8>> {
+    let mut file = File::open(&spec.path)?;
+    file.seek(SeekFrom::Start(spec.offset))?;
+    let mut data = Vec::new();
+    match spec.len {
+        Some(len) => {
+            data.resize(len, 0);
+            file.read_exact(&mut data)?;
+        }
+        None => {
+            file.read_to_end(&mut data)?;
+        }
+    }
+    Ok(data)
+}
+
+fn key_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.lines()
+        .filter(|l| !l.trim_start().starts_with('#'))
+        .filter_map(|l| l.split_once('='))
+        .find(|(k, _)| k.trim() == key)
+        .map(|(_, v)| v.trim())
+}
+
+/// Returns human-readable descriptions of every failed check on the blob.
+pub fn validate_blob(spec: &CalibrationSpec, data: &[u8]) -> Vec<String> {
+    let mut failures = Vec::new();
+    if data.len() < spec.min_size {
+        failures.push(format!(
+            "{}: {} bytes (expected at least {})",
+            spec.name,
+            data.len(),
+            spec.min_size
+        ));
+    }
+
+    for check in &spec.checks {
+        match check {
+            CalibrationCheck::Crc32 {
+                data: range,
+                crc_offset,
+                little_endian,
+            } => {
+                let (Some(covered), Some(stored)) = (
+                    data.get(range.clone()),
+                    data.get(*crc_offset..crc_offset + 4),
+                ) else {
+                    failures.push(format!("{}: too short for CRC check", spec.name));
+                    continue;
+                };
+                let stored: [u8; 4] = stored.try_into().expect("slice of 4 bytes");
+                let stored = if *little_endian {
+                    u32::from_le_bytes(stored)
+                } else {
+                    u32::from_be_bytes(stored)
+                };
+                let computed = crc32(covered);
+                if computed != stored {
+                    failures.push(format!(
+                        "{}: CRC {:08x} does not match stored {:08x}",
+                        spec.name, computed, stored
+                    ));
+                }
+            }
+            CalibrationCheck::Field {
+                name,
+                range,
+                default,
+            } => {
+                let Some(field) = data.get(range.clone()) else {
+                    failures.push(format!("{}: field {} out of range", spec.name, name));
+                    continue;
+                };
+                if field.iter().all(|&b| b == 0x00) || field.iter().all(|&b| b == 0xff) {
+                    failures.push(format!("{}: field {} is not programmed", spec.name, name));
+                } else if default.as_deref() == Some(field) {
+                    failures.push(format!(
+                        "{}: field {} still has the default value",
+                        spec.name, name
+                    ));
+                }
+            }
+            CalibrationCheck::Key { key, default } => {
+                let text = String::from_utf8_lossy(data);
+                match key_value(&text, key) {
+                    None | Some("") => failures.push(format!("{}: {} is not set", spec.name, key)),
+                    Some(value) if default.as_deref() == Some(value) => failures.push(format!(
+                        "{}: {} still has the default value {}",
+                        spec.name, key, value
+                    )),
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+    failures
+}
+
+/// Reads and validates every blob; unreadable blobs count as missing.
+pub fn check_calibration(specs: &[CalibrationSpec]) -> Vec<String> {
+    let mut failures = Vec::new();
+    for spec in specs {
+        match read_blob(spec) {
+            Ok(data) => failures.extend(validate_blob(spec, &data)),
+            Err(e) => failures.push(format!(
+                "{}: not present at {} ({})",
+                spec.name,
+                spec.path.display(),
+                e
+            )),
+        }
+    }
+    failures
+}
@@ -0,0 +1,215 @@
+use crate::manifest::Manifest;
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// A clock from debugfs `clk/clk_summary`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Clock {
+    pub name: String,
+    pub parent: Option<String>,
+    pub enable_count: u32,
+    pub prepare_count: u32,
+    pub rate: u64,                // Hz
+    pub hw_enabled: Option<bool>, // The gate as read back from hardware, since Linux 6.7
+    pub consumers: Vec<String>,   // Devices holding it, since Linux 6.7
+}
+
+impl Clock {
+    /// Running, or prepared by a consumer that gates it while idle, e.g. through
+    /// runtime PM between I2C transfers.
+    pub fn is_in_use(&self) -> bool {
+        self.enable_count > 0 || self.prepare_count > 0
+    }
+}
+
+/// Parses `clk_summary`. Each level of the tree is indented by three more spaces; kernels
+/// before 4.16 have no `protect` or `duty` columns, and since 6.7 every consumer after
+/// the first is on a line of its own.
+pub fn parse_clk_summary(text: &str) -> Result<Vec<Clock>> {
+    let mut clocks: Vec<Clock> = Vec::new();
+    let mut path: Vec<(usize, String)> = Vec::new(); // (indent, name) of the current branch
+    let mut in_body = false;
+    let mut has_protect = false;
+    for line in text.lines() {
+        if !in_body {
+            has_protect |= line.contains("protect");
+            in_body = line.starts_with("---");
+            continue;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() {
+            continue;
+        }
+        let numbers: Vec<i64> = words[1..]
+            .iter()
+            .map_while(|w| w.parse::<i64>().ok())
+            .collect();
+        if numbers.is_empty() {
+            // A further consumer of the clock above: "<device> <connection id>"
+            if let Some(clock) = clocks.last_mut()
+                && words[0] != "deviceless"
+            {
+                clock.consumers.push(words[0].to_string());
+            }
+            continue;
+        }
+        // enable prepare [protect] rate accuracy phase [duty]; phase reads "-----" on errors
+        let rate_index = if has_protect { 3 } else { 2 };
+        if numbers.len() <= rate_index {
+            anyhow::bail!("invalid clk_summary line {:?}", line);
+        }
+        let count = |i: usize| u32::try_from(numbers[i]).unwrap_or(0);
+        let indent = line.len() - line.trim_start().len();
+        while path.last().is_some_and(|(i, _)| *i >= indent) {
+            path.pop();
+        }
+        // Then the hardware gate, "Y", "N" or "?", and the first consumer
+        let gate = words.iter().position(|w| ["Y", "N", "?"].contains(w));
+        let hw_enabled = gate.and_then(|g| match words[g] {
+            "Y" => Some(true),
+            "N" => Some(false),
+            _ => None,
+        });
+        let consumers = gate
+            .and_then(|g| words.get(g + 1))
+            .filter(|consumer| **consumer != "deviceless")
+            .map(|consumer| vec![consumer.to_string()])
+            .unwrap_or_default();
+        clocks.push(Clock {
+            name: words[0].to_string(),
+            parent: path.last().map(|(_, name)| name.clone()),
+            enable_count: count(0),
+            prepare_count: count(1),
+            rate: u64::try_from(numbers[rate_index]).unwrap_or(0),
+            hw_enabled,
+            consumers,
+        });
+        path.push((indent, words[0].to_string()));
+    }
+    Ok(clocks)
+}
+
+/// Reads the clock tree of the running system.
+pub fn read_clocks() -> Result<Option<Vec<Clock>>> {
+    read_clocks_in(Path::new("/sys"))
+}
+
+/// Same as [`read_clocks`], below an arbitrary sysfs root. `None` without debugfs
+/// mounted at sys/kernel/debug; reading it needs root.
+pub fn read_clocks_in(sys_root: &Path) -> Result<Option<Vec<Clock>>> {
+    let path = sys_root.join("kernel/debug/clk/clk_summary");
+    match fs::read_to_string(&path) {
+        Ok(text) => parse_clk_summary(&text)
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => anyhow::bail!("{}: {}", path.display(), e),
+    }
+}
+
+/// An expected clock. Each check applies only when set; clocks must be in use unless
+/// `enabled` is false.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedClock {
+    pub name: String,
+    pub enabled: Option<bool>,
+    pub rate: Option<u64>, // Hz
+    pub tolerance_percent: u64,
+    pub parent: Option<String>,
+}
+
+/// Expectations of the `[clocks]` manifest section.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedClocks {
+    pub clocks: Vec<ExpectedClock>,
+}
+
+impl ExpectedClocks {
+    pub fn from_manifest(manifest: &Manifest) -> Result<ExpectedClocks> {
+        let Some(section) = manifest.sections.get("clocks") else {
+            return Ok(ExpectedClocks::default());
+        };
+        let clocks = match &section["clocks"] {
+            Value::Null => Vec::new(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let number = |key: &str| -> Result<Option<u64>> {
+                        match &item[key] {
+                            Value::Null => Ok(None),
+                            value => value.as_u64().map(Some).ok_or_else(|| {
+                                anyhow::anyhow!(
+                                    "clocks.clocks {}: `{}` must be a positive integer",
+                                    i,
+                                    key
+                                )
+                            }),
+                        }
+                    };
+                    let tolerance_percent = number("tolerance_percent")?.unwrap_or(1);
+                    if tolerance_percent > 100 {
+                        anyhow::bail!("clocks.clocks {}: tolerance_percent above 100", i);
+                    }
+                    Ok(ExpectedClock {
+                        name: item["name"].as_str().map(str::to_string).ok_or_else(|| {
+                            anyhow::anyhow!("clocks.clocks {}: missing `name`", i)
+                        })?,
+                        enabled: Some(item["enabled"].as_bool().unwrap_or(true)),
+                        rate: number("rate")?,
+                        tolerance_percent,
+                        parent: item["parent"].as_str().map(str::to_string),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => anyhow::bail!("clocks.clocks must be a list"),
+        };
+        Ok(ExpectedClocks { clocks })
+    }
+}
+
+/// Returns human-readable descriptions of every problem against the expectations.
+///
+/// A bus controller whose clock is off or far from its rate never drives SCL right, so
+/// devices behind it look absent; a wrong parent usually means an assigned-clocks error.
+pub fn validate_clocks(clocks: &[Clock], expected: &ExpectedClocks) -> Vec<String> {
+    let mut problems = Vec::new();
+    for want in &expected.clocks {
+        let Some(clock) = clocks.iter().find(|c| c.name == want.name) else {
+            problems.push(format!("{}: not found", want.name));
+            continue;
+        };
+        match want.enabled {
+            Some(true) if !clock.is_in_use() => {
+                problems.push(format!("{}: disabled (expected enabled)", want.name))
+            }
+            Some(false) if clock.enable_count > 0 => problems.push(format!(
+                "{}: enabled {} time(s) (expected disabled)",
+                want.name, clock.enable_count
+            )),
+            _ => {}
+        }
+        if let Some(rate) = want.rate {
+            let tolerance = rate * want.tolerance_percent / 100;
+            if clock.rate.abs_diff(rate) > tolerance {
+                problems.push(format!(
+                    "{}: {} Hz (expected {} Hz, within {}%)",
+                    want.name, clock.rate, rate, want.tolerance_percent
+                ));
+            }
+        }
+        if let Some(parent) = &want.parent
+            && clock.parent.as_ref() != Some(parent)
+        {
+            problems.push(format!(
+                "{}: parent {} (expected {})",
+                want.name,
+                clock.parent.as_deref().unwrap_or("none"),
+                parent
+            ));
+        }
+    }
+    problems
+}
@@ -0,0 +1,205 @@
+use crate::manifest::Manifest;
+use crate::report::TestCase;
+use crate::rootfs::{self, MountEntry};
+use crate::system_root::SystemRoot;
+use std::fs;
+
+/// Checks whose results inside a container describe the container, not the unit, with why.
+pub const HOST_ONLY: &[(&str, &str)] = &[
+    (
+        "boot_time",
+        "the container has its own boot, not the unit's",
+    ),
+    ("containers", "the container runtime runs on the host"),
+    (
+        "crash_artifacts",
+        "coredumps and shutdown markers are on the host filesystem",
+    ),
+    (
+        "hardening_accounts",
+        "the accounts are those of the container image",
+    ),
+    (
+        "hardening_permissions",
+        "the files are those of the container image",
+    ),
+    ("integrity", "the files are those of the container image"),
+    ("journal", "the unit's journal is outside the container"),
+    (
+        "kernel_threads",
+        "kernel threads are outside the container's PID namespace",
+    ),
+    (
+        "lsm",
+        "the container sees its own profile, not the unit's policy",
+    ),
+    ("ota", "the OTA client runs on the host"),
+    ("rootfs", "the root filesystem is the container's overlay"),
+];
+
+/// cgroup path fragments that name the engine that created them.
+const CGROUP_ENGINES: &[(&str, &str)] = &[
+    ("docker", "docker"),
+    ("kubepods", "kubernetes"),
+    ("libpod", "podman"),
+    ("lxc", "lxc"),
+    ("containerd", "containerd"),
+];
+
+/// Device classes whose sysfs entries each need a node in /dev: (sysfs directory, prefix).
+const DEVICE_NODES: &[(&str, &str)] =
+    &[("class/i2c-dev", "i2c-"), ("bus/gpio/devices", "gpiochip")];
+
+const CAP_SYS_ADMIN: u32 = 21;
+
+/// How this process is containerized.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Container {
+    pub engine: Option<String>, // e.g. "docker", "podman" or "kubernetes", if it shows
+    pub privileged: bool,       // CAP_SYS_ADMIN in the effective set
+    pub evidence: Vec<String>,  // What gave it away, e.g. "/.dockerenv exists"
+}
+
+impl Container {
+    /// Removes the sections of [`HOST_ONLY`] checks from `manifest`, returning them as
+    /// skipped test cases for the report.
+    pub fn adjust(&self, manifest: &mut Manifest) -> Vec<TestCase> {
+        let mut skipped = Vec::new();
+        for (id, reason) in HOST_ONLY {
+            if manifest.sections.remove(*id).is_some() {
+                skipped.push(TestCase {
+                    classname: "container".to_string(),
+                    name: id.to_string(),
+                    skipped: Some(format!("host-only check: {}", reason)),
+                    ..Default::default()
+                });
+            }
+        }
+        skipped
+    }
+}
+
+/// Why `check` is skipped inside a container, if it is.
+pub fn host_only_reason(check: &str) -> Option<&'static str> {
+    HOST_ONLY
+        .iter()
+        .find(|(id, _)| *id == check)
+        .map(|(_, reason)| *reason)
+}
+
+/// Whether this process runs in a container, and which.
+pub fn detect() -> Option<Container> {
+    detect_in(&SystemRoot::live())
+}
+
+/// Same as [`detect`], for the system at `root`.
+pub fn detect_in(root: &SystemRoot) -> Option<Container> {
+    let mut container = Container::default();
+    let mut found = |engine: Option<&str>, evidence: String| {
+        if container.engine.is_none() {
+            container.engine = engine.map(str::to_string);
+        }
+        container.evidence.push(evidence);
+    };
+    for (marker, engine) in [("/.dockerenv", "docker"), ("/run/.containerenv", "podman")] {
+        if root.file(marker).exists() {
+            found(Some(engine), format!("{} exists", marker));
+        }
+    }
+    // systemd's convention, followed by podman, lxc and systemd-nspawn
+    if let Ok(environ) = fs::read(root.proc.join("1/environ"))
+        && let Some(engine) = environ
+            .split(|b| *b == 0)
+            .find_map(|var| var.strip_prefix(b"container="))
+    {
+        let engine = String::from_utf8_lossy(engine).to_string();
+        found(Some(&engine), format!("init has container={}", engine));
+    }
+    if let Ok(cgroups) = fs::read_to_string(root.proc.join("self/cgroup")) {
+        let path = cgroups.lines().find_map(|line| {
+            let path = line.splitn(3, ':').nth(2)?;
+            let (_, engine) = CGROUP_ENGINES
+                .iter()
+                .find(|(fragment, _)| path.contains(fragment))?;
+            Some((path, *engine))
+        });
+        if let Some((path, engine)) = path {
+            found(Some(engine), format!("cgroup {}", path));
+        }
+    }
+    // The initial user namespace maps every UID onto itself
+    if let Ok(map) = fs::read_to_string(root.proc.join("self/uid_map")) {
+        let map: Vec<&str> = map.split_whitespace().collect();
+        if !map.is_empty() && map != ["0", "0", "4294967295"] {
+            found(None, format!("user namespace maps {}", map.join(" ")));
+        }
+    }
+    if container.evidence.is_empty() {
+        return None;
+    }
+    container.privileged = fs::read_to_string(root.proc.join("self/status"))
+        .ok()
+        .and_then(|status| {
+            let caps = status.lines().find_map(|l| l.strip_prefix("CapEff:"))?;
+            u64::from_str_radix(caps.trim(), 16).ok()
+        })
+        .is_some_and(|caps| caps & (1 << CAP_SYS_ADMIN) != 0);
+    Some(container)
+}
+
+/// What the container hides of the unit: sysfs and procfs paths masked by another mount,
+/// a read-only sysfs, and devices in sysfs without a node in /dev.
+pub fn warnings_in(root: &SystemRoot) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mounts: Vec<MountEntry> = fs::File::open(root.proc.join("self/mountinfo"))
+        .ok()
+        .and_then(|file| rootfs::parse_mountinfo_from_reader(std::io::BufReader::new(file)).ok())
+        .unwrap_or_default();
+    for mount in &mounts {
+        let expected = match mount.mount_point.as_str() {
+            "/sys" => "sysfs",
+            "/proc" => "proc",
+            point if point.starts_with("/sys/") => "sysfs",
+            point if point.starts_with("/proc/") => "proc",
+            _ => continue,
+        };
+        // Control files are mounted over sysfs on purpose
+        let nested = [
+            "cgroup",
+            "cgroup2",
+            "debugfs",
+            "tracefs",
+            "securityfs",
+            "configfs",
+        ];
+        if mount.fs_type != expected && !nested.contains(&mount.fs_type.as_str()) {
+            warnings.push(format!(
+                "{} is masked ({}): checks reading it see nothing",
+                mount.mount_point, mount.fs_type
+            ));
+        } else if mount.mount_point == "/sys" && mount.is_read_only() {
+            warnings.push(
+                "/sys is read-only: checks that bind drivers or write attributes will fail"
+                    .to_string(),
+            );
+        }
+    }
+    for (dir, prefix) in DEVICE_NODES {
+        let Ok(entries) = fs::read_dir(root.sys.join(dir)) else {
+            continue;
+        };
+        let mut missing: Vec<String> = entries
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with(prefix) && !root.dev.join(name).exists())
+            .collect();
+        missing.sort();
+        for name in missing {
+            warnings.push(format!(
+                "{} is in sysfs but /dev/{} is not passed into the container",
+                name, name
+            ));
+        }
+    }
+    warnings
+}
@@ -0,0 +1,210 @@
+use crate::runtime::{self, Dependency};
+use anyhow::Result;
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// A running (or stopped) container as reported by a runtime.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workload {
+    pub name: String, // Container name, or "<pod>/<container>" for Kubernetes
+    pub image: String,
+    pub digests: Vec<String>, // "sha256:..", image ID and/or repo digests
+    pub running: bool,
+}
+
+pub trait ContainerRuntime {
+    fn list_workloads(&self) -> Result<Vec<Workload>>;
+}
+
+/// Docker Engine API, also served by Podman's compat socket.
+pub struct DockerApi {
+    pub socket: PathBuf, // /var/run/docker.sock or /run/podman/podman.sock
+}
+
+impl DockerApi {
+    /// Performs a plain HTTP/1.0 GET over the unix socket and returns the JSON body.
+    fn get(&self, path: &str) -> Result<Value> {
+        let mut stream = UnixStream::connect(&self.socket)?;
+        write!(stream, "GET {} HTTP/1.0\r\nHost: localhost\r\n\r\n", path)?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+
+        let Some((head, body)) = response.split_once("\r\n\r\n") else {
+            anyhow::bail!("Malformed response from {}", self.socket.display());
+        };
+        let status_line = head.lines().next().unwrap_or("");
+        if !status_line.contains(" 200 ") {
+            anyhow::bail!("GET {} on {}: {}", path, self.socket.display(), status_line);
+        }
+        Ok(serde_json::from_str(body)?)
+    }
+
+    /// Builds workloads from `/containers/json` and a lookup of image repo digests.
+    pub fn workloads_from_json(
+        containers: &Value,
+        repo_digests: impl Fn(&str) -> Vec<String>,
+    ) -> Vec<Workload> {
+        let mut workloads = Vec::new();
+        for container in containers.as_array().into_iter().flatten() {
+            let image_id = container["ImageID"].as_str().unwrap_or("").to_string();
+            let mut digests = vec![image_id.clone()];
+            // Repo digests look like "nginx@sha256:.."
+            digests.extend(
+                repo_digests(&image_id)
+                    .iter()
+                    .filter_map(|d| d.split_once('@').map(|(_, digest)| digest.to_string())),
+            );
+            workloads.push(Workload {
+                // Docker prefixes names with '/'
+                name: container["Names"][0]
+                    .as_str()
+                    .unwrap_or("")
+                    .trim_start_matches('/')
+                    .to_string(),
+                image: container["Image"].as_str().unwrap_or("").to_string(),
+                digests,
+                running: container["State"] == "running",
+            });
+        }
+        workloads
+    }
+}
+
+impl ContainerRuntime for DockerApi {
+    fn list_workloads(&self) -> Result<Vec<Workload>> {
+        let containers = self.get("/containers/json?all=true")?;
+        Ok(DockerApi::workloads_from_json(&containers, |image_id| {
+            self.get(&format!("/images/{}/json", image_id))
+                .ok()
+                .and_then(|image| {
+                    image["RepoDigests"].as_array().map(|digests| {
+                        digests
+                            .iter()
+                            .filter_map(|d| d.as_str().map(|s| s.to_string()))
+                            .collect()
+                    })
+                })
+                .unwrap_or_default()
+        }))
+    }
+}
+
+/// Kubernetes (e.g. k3s) pods via `kubectl get pods -o json`.
+pub struct Kubectl {
+    pub program: Vec<String>, // e.g. ["kubectl"] or ["k3s", "kubectl"]
+    pub kubeconfig: Option<PathBuf>,
+    pub namespace: Option<String>, // All namespaces when unset
+}
+
+impl Kubectl {
+    /// Builds one workload per container status of each pod.
+    pub fn workloads_from_json(pods: &Value) -> Vec<Workload> {
+        let mut workloads = Vec::new();
+        for pod in pods["items"].as_array().into_iter().flatten() {
+            let pod_name = pod["metadata"]["name"].as_str().unwrap_or("");
+            let phase_running = pod["status"]["phase"] == "Running";
+            let statuses = pod["status"]["containerStatuses"].as_array();
+            for status in statuses.into_iter().flatten() {
+                // imageID looks like "docker.io/library/nginx@sha256:.."
+                let digest = status["imageID"]
+                    .as_str()
+                    .and_then(|id| id.rsplit_once('@'))
+                    .map(|(_, d)| d.to_string());
+                workloads.push(Workload {
+                    name: format!("{}/{}", pod_name, status["name"].as_str().unwrap_or("")),
+                    image: status["image"].as_str().unwrap_or("").to_string(),
+                    digests: digest.into_iter().collect(),
+                    running: phase_running && status["ready"] == true,
+                });
+            }
+        }
+        workloads
+    }
+}
+
+impl ContainerRuntime for Kubectl {
+    fn list_workloads(&self) -> Result<Vec<Workload>> {
+        let Some((program, args)) = self.program.split_first() else {
+            anyhow::bail!("No kubectl program configured");
+        };
+        runtime::require(&Dependency::program(program))?;
+        let mut cmd = Command::new(program);
+        cmd.args(args).args(["get", "pods", "-o", "json"]);
+        match &self.namespace {
+            Some(ns) => cmd.args(["-n", ns]),
+            None => cmd.arg("-A"),
+        };
+        if let Some(kubeconfig) = &self.kubeconfig {
+            cmd.env("KUBECONFIG", kubeconfig);
+        }
+
+        let output = cmd.output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "kubectl get pods failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(Kubectl::workloads_from_json(&serde_json::from_slice(
+            &output.stdout,
+        )?))
+    }
+}
+
+/// An expected workload.
+///
+/// `name` matches exactly or as a prefix followed by `-` or `/`, so generated
+/// pod suffixes (`app-7d9f8-x2z`) and container names (`app/app`) still match.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedWorkload {
+    pub name: String,
+    pub image_digest: Option<String>, // "sha256:.."
+}
+
+impl ExpectedWorkload {
+    pub fn matches(&self, workload: &Workload) -> bool {
+        workload.name == self.name
+            || workload
+                .name
+                .strip_prefix(self.name.as_str())
+                .is_some_and(|rest| rest.starts_with('-') || rest.starts_with('/'))
+    }
+}
+
+/// Holds results of a workload validation.
+#[derive(Debug, Default)]
+pub struct WorkloadValidationResult {
+    pub running: Vec<String>,
+    pub missing: Vec<String>, // Not found, or found but not running
+    pub mismatched: Vec<String>,
+}
+
+/// Checks that every expected workload is running with the expected image digest.
+pub fn validate_workloads(
+    workloads: &[Workload],
+    expected: &[ExpectedWorkload],
+) -> WorkloadValidationResult {
+    let mut result = WorkloadValidationResult::default();
+
+    for exp in expected {
+        let matching: Vec<&Workload> = workloads.iter().filter(|w| exp.matches(w)).collect();
+        let Some(workload) = matching.iter().find(|w| w.running) else {
+            result.missing.push(exp.name.clone());
+            continue;
+        };
+        result.running.push(workload.name.clone());
+
+        if let Some(digest) = &exp.image_digest
+            && !workload.digests.contains(digest)
+        {
+            result.mismatched.push(format!(
+                "{}: image {} has digest {:?} (expected {})",
+                workload.name, workload.image, workload.digests, digest
+            ));
+        }
+    }
+    result
+}
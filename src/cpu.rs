@@ -0,0 +1,248 @@
+use crate::manifest::Manifest;
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// One logical CPU, from /sys/devices/system/cpu/cpuN.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cpu {
+    pub id: u32,
+    pub online: bool,
+    pub cluster: Option<u32>,      // `topology/cluster_id`, else the package
+    pub min_freq_khz: Option<u64>, // `cpufreq/cpuinfo_min_freq`: what the hardware can do
+    pub max_freq_khz: Option<u64>, // `cpufreq/cpuinfo_max_freq`
+    pub governor: Option<String>,  // `cpufreq/scaling_governor`
+}
+
+/// The CPUs the kernel knows of. A CPU that is `possible` but not `present` never came out
+/// of reset, or was left out of the device tree.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuTopology {
+    pub possible: Vec<u32>,
+    pub present: Vec<u32>,
+    pub cpus: Vec<Cpu>, // Present ones
+}
+
+impl CpuTopology {
+    pub fn online(&self) -> Vec<u32> {
+        self.cpus
+            .iter()
+            .filter(|c| c.online)
+            .map(|c| c.id)
+            .collect()
+    }
+}
+
+/// Parses a kernel CPU list such as "0-3,6".
+pub fn parse_cpu_list(list: &str) -> Result<Vec<u32>> {
+    let mut cpus = Vec::new();
+    for part in list.trim().split(',').filter(|p| !p.is_empty()) {
+        let bad = || anyhow::anyhow!("invalid CPU list {:?}", list.trim());
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let (first, last): (u32, u32) = (
+                    first.parse().map_err(|_| bad())?,
+                    last.parse().map_err(|_| bad())?,
+                );
+                if first > last {
+                    return Err(bad());
+                }
+                cpus.extend(first..=last);
+            }
+            None => cpus.push(part.parse().map_err(|_| bad())?),
+        }
+    }
+    cpus.sort();
+    cpus.dedup();
+    Ok(cpus)
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_number<T: std::str::FromStr>(path: &Path) -> Option<T> {
+    read_trimmed(path)?.parse().ok()
+}
+
+/// Reads the CPU topology of the running system.
+pub fn read_topology() -> Result<CpuTopology> {
+    read_topology_in(Path::new("/sys"))
+}
+
+/// Same as [`read_topology`], with an explicit sysfs root.
+pub fn read_topology_in(sys_root: &Path) -> Result<CpuTopology> {
+    let dir = sys_root.join("devices/system/cpu");
+    let list = |name: &str| -> Result<Vec<u32>> {
+        let path = dir.join(name);
+        let text =
+            fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+        parse_cpu_list(&text)
+    };
+    let possible = list("possible")?;
+    let present = list("present")?;
+    let online = list("online")?;
+    let cpus = present
+        .iter()
+        .map(|&id| {
+            let cpu = dir.join(format!("cpu{}", id));
+            Cpu {
+                id,
+                online: online.contains(&id),
+                cluster: read_number(&cpu.join("topology/cluster_id"))
+                    .filter(|c: &i64| *c >= 0)
+                    .or_else(|| read_number(&cpu.join("topology/physical_package_id")))
+                    .and_then(|c| u32::try_from(c).ok()),
+                min_freq_khz: read_number(&cpu.join("cpufreq/cpuinfo_min_freq")),
+                max_freq_khz: read_number(&cpu.join("cpufreq/cpuinfo_max_freq")),
+                governor: read_trimmed(&cpu.join("cpufreq/scaling_governor")),
+            }
+        })
+        .collect();
+    Ok(CpuTopology {
+        possible,
+        present,
+        cpus,
+    })
+}
+
+/// Frequency bounds of a group of CPUs, e.g. the big cluster. Bounds are in kHz.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedCpuGroup {
+    pub cpus: Vec<u32>,
+    pub min_freq_khz: Option<u64>,
+    pub max_freq_khz: Option<u64>,
+}
+
+/// Expectations of the `[cpu]` manifest section.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedCpus {
+    pub count: Option<usize>,     // Online CPUs
+    pub governor: Option<String>, // Of every online CPU with cpufreq
+    pub allow_offline: Vec<u32>,  // e.g. cores the image takes offline for isolation
+    pub groups: Vec<ExpectedCpuGroup>,
+}
+
+impl ExpectedCpus {
+    pub fn from_manifest(manifest: &Manifest) -> Result<ExpectedCpus> {
+        let Some(section) = manifest.sections.get("cpu") else {
+            return Ok(ExpectedCpus::default());
+        };
+        let cpu_list = |value: &Value, key: &str| -> Result<Vec<u32>> {
+            match value {
+                Value::Null => Ok(Vec::new()),
+                Value::String(list) => parse_cpu_list(list),
+                _ => anyhow::bail!("cpu.{} must be a CPU list such as \"0-3,6\"", key),
+            }
+        };
+        let number = |value: &Value, key: &str| -> Result<Option<u64>> {
+            match value {
+                Value::Null => Ok(None),
+                value => value
+                    .as_u64()
+                    .map(Some)
+                    .ok_or_else(|| anyhow::anyhow!("cpu.{} must be a positive integer", key)),
+            }
+        };
+        let groups = match &section["groups"] {
+            Value::Null => Vec::new(),
+            Value::Array(groups) => groups
+                .iter()
+                .map(|group| {
+                    Ok(ExpectedCpuGroup {
+                        cpus: cpu_list(&group["cpus"], "groups.cpus")?,
+                        min_freq_khz: number(&group["min_freq_khz"], "groups.min_freq_khz")?,
+                        max_freq_khz: number(&group["max_freq_khz"], "groups.max_freq_khz")?,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => anyhow::bail!("cpu.groups must be a list"),
+        };
+        Ok(ExpectedCpus {
+            count: number(&section["count"], "count")?.map(|c| c as usize),
+            governor: section["governor"].as_str().map(str::to_string),
+            allow_offline: cpu_list(&section["allow_offline"], "allow_offline")?,
+            groups,
+        })
+    }
+}
+
+/// "CPU 3" or "CPUs 4,5,6,7".
+fn cpus_phrase(cpus: &[u32]) -> String {
+    let list: Vec<String> = cpus.iter().map(|c| c.to_string()).collect();
+    match list.len() {
+        1 => format!("CPU {}", list[0]),
+        _ => format!("CPUs {}", list.join(",")),
+    }
+}
+
+/// Returns human-readable descriptions of every problem against the expectations.
+///
+/// Any CPU that is possible but not present, or present but offline, is a problem unless
+/// allowed: that is how a cluster held in reset shows up.
+pub fn validate_cpus(topology: &CpuTopology, expected: &ExpectedCpus) -> Vec<String> {
+    let mut problems = Vec::new();
+    let online = topology.online();
+    if let Some(count) = expected.count
+        && online.len() != count
+    {
+        problems.push(format!("{} CPUs online (expected {})", online.len(), count));
+    }
+    let allowed = |id: &u32| expected.allow_offline.contains(id);
+    let missing: Vec<u32> = topology
+        .possible
+        .iter()
+        .filter(|id| !topology.present.contains(id) && !allowed(id))
+        .copied()
+        .collect();
+    if !missing.is_empty() {
+        problems.push(format!("{} not present", cpus_phrase(&missing)));
+    }
+    let offline: Vec<u32> = topology
+        .cpus
+        .iter()
+        .filter(|c| !c.online && !allowed(&c.id))
+        .map(|c| c.id)
+        .collect();
+    if !offline.is_empty() {
+        problems.push(format!("{} offline", cpus_phrase(&offline)));
+    }
+    if let Some(governor) = &expected.governor {
+        for cpu in topology.cpus.iter().filter(|c| c.online) {
+            if let Some(actual) = &cpu.governor
+                && actual != governor
+            {
+                problems.push(format!(
+                    "cpu{} governor is {} (expected {})",
+                    cpu.id, actual, governor
+                ));
+            }
+        }
+    }
+    for group in &expected.groups {
+        for id in &group.cpus {
+            let Some(cpu) = topology.cpus.iter().find(|c| c.id == *id) else {
+                continue; // Reported as not present
+            };
+            let bounds = [
+                ("min", cpu.min_freq_khz, group.min_freq_khz),
+                ("max", cpu.max_freq_khz, group.max_freq_khz),
+            ];
+            for (name, actual, want) in bounds {
+                match (actual, want) {
+                    (_, None) => {}
+                    (None, Some(_)) => {
+                        problems.push(format!("cpu{}: no cpufreq {} frequency", id, name))
+                    }
+                    (Some(actual), Some(want)) if actual != want => problems.push(format!(
+                        "cpu{} {} frequency {} kHz (expected {} kHz)",
+                        id, name, actual, want
+                    )),
+                    _ => {}
+                }
+            }
+        }
+    }
+    problems
+}
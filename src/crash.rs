@@ -0,0 +1,177 @@
+use crate::evidence::EvidenceBundle;
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const CHECK_ID: &str = "crash_artifacts";
+
+/// Kind of crash evidence found on the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrashKind {
+    Coredump,
+    Pstore,
+    UncleanShutdown,
+}
+
+/// A crash artifact and a short human-readable summary of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrashArtifact {
+    pub kind: CrashKind,
+    pub path: PathBuf,
+    pub modified: SystemTime,
+    pub summary: String,
+}
+
+/// Where to look for crash artifacts.
+#[derive(Debug, Clone)]
+pub struct CrashScanConfig {
+    pub coredump_dirs: Vec<PathBuf>,
+    pub pstore_dir: PathBuf,
+    pub unclean_markers: Vec<PathBuf>, // Files an app/init script leaves behind on unclean shutdown
+    pub since: SystemTime,             // Coredumps and markers older than this are stale
+}
+
+impl Default for CrashScanConfig {
+    fn default() -> Self {
+        CrashScanConfig {
+            coredump_dirs: vec![PathBuf::from("/var/lib/systemd/coredump")],
+            pstore_dir: PathBuf::from("/sys/fs/pstore"),
+            unclean_markers: Vec::new(),
+            since: SystemTime::UNIX_EPOCH,
+        }
+    }
+}
+
+/// Wall-clock time of the current boot (now - /proc/uptime).
+pub fn boot_timestamp() -> Result<SystemTime> {
+    let uptime = fs::read_to_string("/proc/uptime")?;
+    let secs: f64 = uptime
+        .split_whitespace()
+        .next()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| anyhow::anyhow!("Malformed /proc/uptime"))?;
+    Ok(SystemTime::now() - Duration::from_secs_f64(secs))
+}
+
+/// Summarises a systemd-coredump file name: `core.<comm>.<uid>.<boot id>.<pid>.<usec>[.zst]`.
+pub fn summarize_coredump_name(name: &str) -> String {
+    let fields: Vec<&str> = name.split('.').collect();
+    match fields.as_slice() {
+        ["core", comm, uid, _boot_id, pid, ..] => {
+            format!("coredump of {} (pid {}, uid {})", comm, pid, uid)
+        }
+        _ => format!("core file {}", name),
+    }
+}
+
+/// Extracts the interesting lines of a pstore console/dmesg record.
+///
+/// Keeps panic/oops/BUG/WARNING lines, or the last few lines if there are none.
+pub fn summarize_pstore(contents: &str) -> String {
+    const MARKERS: [&str; 5] = [
+        "Kernel panic",
+        "Oops",
+        "BUG:",
+        "WARNING:",
+        "Unable to handle",
+    ];
+    let interesting: Vec<&str> = contents
+        .lines()
+        .filter(|l| MARKERS.iter().any(|m| l.contains(m)))
+        .take(10)
+        .collect();
+    if !interesting.is_empty() {
+        interesting.join("\n")
+    } else {
+        let lines: Vec<&str> = contents.lines().collect();
+        lines[lines.len().saturating_sub(5)..].join("\n")
+    }
+}
+
+fn modified(path: &Path) -> SystemTime {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Finds fresh crash artifacts. pstore records are always fresh: they're cleared once collected.
+pub fn scan_crash_artifacts(config: &CrashScanConfig) -> Result<Vec<CrashArtifact>> {
+    let mut artifacts = Vec::new();
+
+    for dir in &config.coredump_dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let modified = modified(&path);
+            if !name.starts_with("core") || modified < config.since {
+                continue;
+            }
+            artifacts.push(CrashArtifact {
+                kind: CrashKind::Coredump,
+                summary: summarize_coredump_name(&name),
+                path,
+                modified,
+            });
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir(&config.pstore_dir) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let contents = fs::read(&path).unwrap_or_default();
+            artifacts.push(CrashArtifact {
+                kind: CrashKind::Pstore,
+                summary: summarize_pstore(&String::from_utf8_lossy(&contents)),
+                modified: modified(&path),
+                path,
+            });
+        }
+    }
+
+    for marker in &config.unclean_markers {
+        if marker.exists() && modified(marker) >= config.since {
+            artifacts.push(CrashArtifact {
+                kind: CrashKind::UncleanShutdown,
+                summary: format!("unclean shutdown marker {}", marker.display()),
+                path: marker.clone(),
+                modified: modified(marker),
+            });
+        }
+    }
+
+    artifacts.sort_by_key(|a| a.modified);
+    Ok(artifacts)
+}
+
+/// Scans for fresh artifacts and attaches summaries (and pstore records) to the bundle.
+///
+/// Validation fails if the returned list is non-empty. Coredumps themselves aren't copied.
+pub fn check_crash_artifacts(
+    config: &CrashScanConfig,
+    bundle: Option<&mut EvidenceBundle>,
+) -> Result<Vec<CrashArtifact>> {
+    let artifacts = scan_crash_artifacts(config)?;
+
+    if let Some(bundle) = bundle
+        && !artifacts.is_empty()
+    {
+        let summary: Vec<String> = artifacts
+            .iter()
+            .map(|a| format!("[{:?}] {}\n{}\n", a.kind, a.path.display(), a.summary))
+            .collect();
+        bundle.add_text(
+            CHECK_ID,
+            "summary.txt",
+            &summary.join("\n"),
+            "Crash artifact summaries",
+        )?;
+        for artifact in artifacts.iter().filter(|a| a.kind == CrashKind::Pstore) {
+            bundle.add_file(CHECK_ID, &artifact.path, "pstore record")?;
+        }
+    }
+    Ok(artifacts)
+}
@@ -0,0 +1,63 @@
+use crate::device::TuxBus;
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+/// A device whose driver returned -EPROBE_DEFER and is waiting to be probed again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeferredDevice {
+    pub name: String,           // Kernel name, e.g. "1-0050" or "fe5a0000.i2c"
+    pub reason: Option<String>, // e.g. "supplier regulator-vcc3v3 not ready", since Linux 5.10
+}
+
+/// Parses debugfs `devices_deferred`: one device per line, then a tab and the reason.
+pub fn parse_devices_deferred(text: &str) -> Vec<DeferredDevice> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (name, reason) = match line.split_once('\t') {
+                Some((name, reason)) => (name, Some(reason.trim())),
+                None => (line, None),
+            };
+            DeferredDevice {
+                name: name.trim().to_string(),
+                reason: reason.filter(|r| !r.is_empty()).map(str::to_string),
+            }
+        })
+        .collect()
+}
+
+/// Reads the devices waiting on a dependency of the running system.
+pub fn read_deferred() -> Result<Option<Vec<DeferredDevice>>> {
+    read_deferred_in(Path::new("/sys"))
+}
+
+/// Same as [`read_deferred`], below an arbitrary sysfs root. `None` without debugfs
+/// mounted at sys/kernel/debug; reading it needs root.
+pub fn read_deferred_in(sys_root: &Path) -> Result<Option<Vec<DeferredDevice>>> {
+    let path = sys_root.join("kernel/debug/devices_deferred");
+    match fs::read_to_string(&path) {
+        Ok(text) => Ok(Some(parse_devices_deferred(&text))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => anyhow::bail!("{}: {}", path.display(), e),
+    }
+}
+
+/// Sets `probe_deferred` on the devices in `deferred`, and adds the reason to their
+/// `kernel_log`; returns how many were marked.
+pub fn mark(buses: &mut [TuxBus], deferred: &[DeferredDevice]) -> usize {
+    let mut marked = 0;
+    for device in buses.iter_mut().flat_map(|b| b.devices.iter_mut()) {
+        let name = device.address.to_string();
+        if let Some(entry) = deferred.iter().find(|d| d.name == name) {
+            device.probe_deferred = true;
+            if let Some(reason) = &entry.reason {
+                device
+                    .kernel_log
+                    .push(format!("deferred probe pending: {}", reason));
+            }
+            marked += 1;
+        }
+    }
+    marked
+}
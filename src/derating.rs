@@ -0,0 +1,122 @@
+#[cfg(feature = "sensors")]
+use crate::hwmon;
+#[cfg(feature = "sensors")]
+use crate::measurement::{Measurement, Quantity, Unit};
+use anyhow::Result;
+use serde_json::Value;
+use std::path::Path;
+
+/// A limit as a function of temperature: linear between points, flat beyond the ends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeratingCurve {
+    pub points: Vec<(f64, f64)>, // (°C, limit), sorted by temperature
+}
+
+impl DeratingCurve {
+    pub fn new(mut points: Vec<(f64, f64)>) -> Result<DeratingCurve> {
+        if points.is_empty() {
+            anyhow::bail!("derating curve needs at least one point");
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if points.windows(2).any(|w| w[0].0 == w[1].0) {
+            anyhow::bail!("derating curve has two points at the same temperature");
+        }
+        Ok(DeratingCurve { points })
+    }
+
+    pub fn at(&self, celsius: f64) -> f64 {
+        let first = self.points[0];
+        let last = self.points[self.points.len() - 1];
+        if celsius <= first.0 {
+            return first.1;
+        }
+        if celsius >= last.0 {
+            return last.1;
+        }
+        let i = self.points.iter().position(|p| p.0 > celsius).unwrap_or(1);
+        let ((t0, v0), (t1, v1)) = (self.points[i - 1], self.points[i]);
+        v0 + (v1 - v0) * (celsius - t0) / (t1 - t0)
+    }
+}
+
+/// A numeric pass criterion from a manifest.
+///
+/// Either a plain number, or a table naming the hwmon channel to read the temperature from:
+/// `{ channel = "SOC_THERM", curve = [[25, 2400], [60, 2016]] }`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Threshold {
+    Fixed(f64),
+    Derated {
+        channel: String, // See `hwmon::resolve_channel`
+        curve: DeratingCurve,
+    },
+}
+
+impl Threshold {
+    /// Parses the manifest form; TOML and YAML manifests are both read as JSON values.
+    pub fn from_value(value: &Value) -> Result<Threshold> {
+        if let Some(number) = value.as_f64() {
+            return Ok(Threshold::Fixed(number));
+        }
+        let Some(channel) = value["channel"].as_str() else {
+            anyhow::bail!("threshold must be a number or a table with `channel` and `curve`");
+        };
+        let Some(points) = value["curve"].as_array() else {
+            anyhow::bail!("derated threshold needs a `curve` of [°C, limit] pairs");
+        };
+        let points = points
+            .iter()
+            .map(|p| match (p[0].as_f64(), p[1].as_f64()) {
+                (Some(t), Some(v)) => Ok((t, v)),
+                _ => Err(anyhow::anyhow!(
+                    "curve point {} is not a [°C, limit] pair",
+                    p
+                )),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Threshold::Derated {
+            channel: channel.to_string(),
+            curve: DeratingCurve::new(points)?,
+        })
+    }
+
+    /// The limit at a temperature; fixed thresholds ignore it.
+    pub fn at(&self, celsius: f64) -> f64 {
+        match self {
+            Threshold::Fixed(value) => *value,
+            Threshold::Derated { curve, .. } => curve.at(celsius),
+        }
+    }
+
+    /// Reads the designated temperature, if any, and returns the limit that applies now.
+    pub fn resolve(&self) -> Result<f64> {
+        self.resolve_in(Path::new("/sys"))
+    }
+
+    /// Same as [`Threshold::resolve`], with an explicit sysfs root.
+    pub fn resolve_in(&self, sys_root: &Path) -> Result<f64> {
+        match self {
+            Threshold::Fixed(value) => Ok(*value),
+            #[cfg(feature = "sensors")]
+            Threshold::Derated { channel, curve } => {
+                let temperature = read_temperature_in(sys_root, channel)?;
+                Ok(curve.at(temperature.value_in(Unit::Celsius).unwrap_or_default()))
+            }
+            #[cfg(not(feature = "sensors"))]
+            Threshold::Derated { channel, .. } => {
+                let _ = sys_root;
+                anyhow::bail!("derating by {} needs the `sensors` feature", channel)
+            }
+        }
+    }
+}
+
+/// Reads a hwmon temperature channel, by label ("SOC_THERM") or path ("hwmon1/temp1_input").
+#[cfg(feature = "sensors")]
+pub fn read_temperature_in(sys_root: &Path, channel: &str) -> Result<Measurement> {
+    let measurement = hwmon::read_channel_in(sys_root, channel)?;
+    if measurement.quantity() != Quantity::Temperature {
+        anyhow::bail!("hwmon channel {} is not a temperature", channel);
+    }
+    Ok(measurement)
+}
@@ -0,0 +1,449 @@
+use anyhow::Result;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Kernel subsystem a bus or device belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Subsystem {
+    I2c,
+    Usb,
+    Pci,
+    Gpio,
+    Spi,
+    Other(&'static str), // Registered by another crate, see crate::discovery
+}
+
+impl fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Subsystem::I2c => "i2c",
+            Subsystem::Usb => "usb",
+            Subsystem::Pci => "pci",
+            Subsystem::Gpio => "gpio",
+            Subsystem::Spi => "spi",
+            Subsystem::Other(name) => name,
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Serialized as the `Display` name.
+impl Serialize for Subsystem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Subsystem {
+    /// Inverse of the `Display` name; other subsystems must be registered first.
+    pub fn from_name(name: &str) -> Option<Subsystem> {
+        match name {
+            "i2c" => Some(Subsystem::I2c),
+            "usb" => Some(Subsystem::Usb),
+            "pci" => Some(Subsystem::Pci),
+            "gpio" => Some(Subsystem::Gpio),
+            "spi" => Some(Subsystem::Spi),
+            _ => crate::discovery::find(name).map(|d| d.subsystem()),
+        }
+    }
+}
+
+/// Subsystem-specific address of a device.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DeviceAddress {
+    I2c {
+        bus: u32, // Dynamic numbering, e.g. behind muxes, can go well above 255
+        addr: u16,
+    },
+    Usb {
+        bus: u8,
+        port: String, // Port path, e.g. "1.2"
+    },
+    Pci {
+        domain: u16,
+        bus: u8,
+        device: u8,
+        function: u8,
+    },
+    Gpio {
+        chip: u32, // N of /dev/gpiochipN
+    },
+    Spi {
+        bus: u32,
+        cs: u16, // Chip select
+    },
+    Other {
+        subsystem: &'static str,
+        name: String, // The device's sysfs name
+    },
+}
+
+impl fmt::Display for DeviceAddress {
+    /// Formats the address the way the kernel names the device in sysfs.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceAddress::I2c { bus, addr } => write!(f, "{}-{:04x}", bus, addr),
+            DeviceAddress::Usb { bus, port } => write!(f, "{}-{}", bus, port),
+            DeviceAddress::Pci {
+                domain,
+                bus,
+                device,
+                function,
+            } => write!(f, "{:04x}:{:02x}:{:02x}.{}", domain, bus, device, function),
+            DeviceAddress::Gpio { chip } => write!(f, "gpiochip{}", chip),
+            DeviceAddress::Spi { bus, cs } => write!(f, "spi{}.{}", bus, cs),
+            DeviceAddress::Other { name, .. } => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Serialized in sysfs naming, like `Display`.
+impl Serialize for DeviceAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl DeviceAddress {
+    /// Parses the sysfs naming used by `Display`; the format depends on the subsystem.
+    pub fn parse(subsystem: Subsystem, name: &str) -> Option<DeviceAddress> {
+        match subsystem {
+            Subsystem::I2c => {
+                let (bus, addr) = name.split_once('-')?;
+                Some(DeviceAddress::I2c {
+                    bus: bus.parse().ok()?,
+                    addr: u16::from_str_radix(addr, 16).ok()?,
+                })
+            }
+            Subsystem::Usb => {
+                let (bus, port) = name.split_once('-')?;
+                Some(DeviceAddress::Usb {
+                    bus: bus.parse().ok()?,
+                    port: port.to_string(),
+                })
+            }
+            Subsystem::Pci => {
+                let (domain, rest) = name.split_once(':')?;
+                let (bus, rest) = rest.split_once(':')?;
+                let (device, function) = rest.split_once('.')?;
+                Some(DeviceAddress::Pci {
+                    domain: u16::from_str_radix(domain, 16).ok()?,
+                    bus: u8::from_str_radix(bus, 16).ok()?,
+                    device: u8::from_str_radix(device, 16).ok()?,
+                    function: function.parse().ok()?,
+                })
+            }
+            Subsystem::Gpio => Some(DeviceAddress::Gpio {
+                chip: name.strip_prefix("gpiochip")?.parse().ok()?,
+            }),
+            Subsystem::Spi => {
+                let (bus, cs) = name.strip_prefix("spi")?.split_once('.')?;
+                Some(DeviceAddress::Spi {
+                    bus: bus.parse().ok()?,
+                    cs: cs.parse().ok()?,
+                })
+            }
+            Subsystem::Other(subsystem) => Some(DeviceAddress::Other {
+                subsystem,
+                name: name.to_string(),
+            }),
+        }
+    }
+}
+
+/// A single device, as seen by sysfs/udev and (optionally) a hardware probe.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TuxDevice {
+    pub subsystem: Subsystem,
+    pub address: DeviceAddress,
+    pub name: String,
+    pub sysfs_path: Option<PathBuf>, // None for devices only seen by a hardware probe
+    pub driver: Option<String>,
+    pub modalias: Option<String>,
+    pub in_udev: bool,                        // udev has a database entry for it
+    pub hw_responded: bool,                   // ACKed a hardware probe
+    pub probe_deferred: bool, // Its driver waits on a dependency, see crate::deferred_probe
+    pub attributes: BTreeMap<String, String>, // uevent and udev properties
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub kernel_log: Vec<String>, // Kernel messages about it, e.g. probe errors, see crate::kmsg
+}
+
+impl TuxDevice {
+    /// A device with no sysfs/udev information yet.
+    pub fn new(subsystem: Subsystem, address: DeviceAddress, name: &str) -> TuxDevice {
+        TuxDevice {
+            subsystem,
+            address,
+            name: name.to_string(),
+            sysfs_path: None,
+            driver: None,
+            modalias: None,
+            in_udev: false,
+            hw_responded: false,
+            probe_deferred: false,
+            attributes: BTreeMap::new(),
+            kernel_log: Vec::new(),
+        }
+    }
+
+    /// Builds a device from its sysfs directory and the udev database in /run/udev/data.
+    pub fn from_udev(sysfs_path: &Path, subsystem: Subsystem, address: DeviceAddress) -> TuxDevice {
+        TuxDevice::from_udev_in(sysfs_path, Path::new("/run/udev/data"), subsystem, address)
+    }
+
+    /// Same as [`TuxDevice::from_udev`], with an explicit udev database directory.
+    ///
+    /// Devices without a device node are stored as `+<subsystem>:<sysname>`, character
+    /// devices as `c<major>:<minor>`. Without an entry, e.g. with no udev running in a
+    /// container or early boot, this is [`TuxDevice::from_sysfs`].
+    pub fn from_udev_in(
+        sysfs_path: &Path,
+        udev_db: &Path,
+        subsystem: Subsystem,
+        address: DeviceAddress,
+    ) -> TuxDevice {
+        let mut device = TuxDevice::from_sysfs(sysfs_path, subsystem, address);
+        let sysname = sysfs_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let db_name = match (
+            device.attributes.get("MAJOR"),
+            device.attributes.get("MINOR"),
+        ) {
+            (Some(major), Some(minor)) => format!("c{}:{}", major, minor),
+            _ => format!("+{}:{}", subsystem, sysname),
+        };
+        if let Ok(data) = fs::read_to_string(udev_db.join(db_name)) {
+            device.in_udev = true;
+            for property in data.lines().filter_map(|l| l.strip_prefix("E:")) {
+                if let Some((key, value)) = property.split_once('=') {
+                    device.attributes.insert(key.to_string(), value.to_string());
+                }
+            }
+        }
+        device
+    }
+
+    /// Builds a device from its sysfs directory alone: the uevent properties, plus what
+    /// they lack and sysfs has (`DRIVER`, `MODALIAS`, `OF_*`), as udev reports them.
+    ///
+    /// Enumerators call it through [`TuxDevice::from_udev_in`]; use it directly for
+    /// devices udev never sees.
+    pub fn from_sysfs(
+        sysfs_path: &Path,
+        subsystem: Subsystem,
+        address: DeviceAddress,
+    ) -> TuxDevice {
+        let mut device = TuxDevice::new(subsystem, address, "");
+        device.sysfs_path = Some(sysfs_path.to_path_buf());
+
+        if let Ok(uevent) = fs::read_to_string(sysfs_path.join("uevent")) {
+            for (key, value) in uevent.lines().filter_map(|l| l.split_once('=')) {
+                device.attributes.insert(key.to_string(), value.to_string());
+            }
+        }
+        let driver = fs::read_link(sysfs_path.join("driver"))
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()));
+        if let Some(driver) = &driver {
+            device
+                .attributes
+                .entry("DRIVER".to_string())
+                .or_insert_with(|| driver.clone());
+        }
+        device.driver = driver.or_else(|| device.attributes.get("DRIVER").cloned());
+        if !device.attributes.contains_key("MODALIAS")
+            && let Ok(modalias) = fs::read_to_string(sysfs_path.join("modalias"))
+        {
+            device
+                .attributes
+                .insert("MODALIAS".to_string(), modalias.trim().to_string());
+        }
+        device.modalias = device.attributes.get("MODALIAS").cloned();
+        if !device.attributes.contains_key("OF_COMPATIBLE_N")
+            && let Ok(compatible) = fs::read(sysfs_path.join("of_node/compatible"))
+        {
+            let compatible: Vec<String> = compatible
+                .split(|b| *b == 0)
+                .filter(|c| !c.is_empty())
+                .map(|c| String::from_utf8_lossy(c).to_string())
+                .collect();
+            for (i, c) in compatible.iter().enumerate() {
+                device
+                    .attributes
+                    .insert(format!("OF_COMPATIBLE_{}", i), c.clone());
+            }
+            device
+                .attributes
+                .insert("OF_COMPATIBLE_N".to_string(), compatible.len().to_string());
+            if let Ok(name) = fs::read(sysfs_path.join("of_node/name")) {
+                let name = String::from_utf8_lossy(&name);
+                device.attributes.insert(
+                    "OF_NAME".to_string(),
+                    name.trim_end_matches(['\0', '\n']).to_string(),
+                );
+            }
+        }
+        device.name = fs::read_to_string(sysfs_path.join("name"))
+            .map(|n| n.trim().to_string())
+            .ok()
+            .or_else(|| {
+                // e.g. get 'rk808' from 'rockchip,rk808'
+                device
+                    .attributes
+                    .get("OF_COMPATIBLE_0")
+                    .map(|c| c.rsplit(',').next().unwrap_or(c).to_string())
+            })
+            .unwrap_or_else(|| "Unidentified".to_string());
+        device
+    }
+
+    /// Bound to a driver.
+    pub fn is_bound(&self) -> bool {
+        self.driver.is_some()
+    }
+
+    /// Responds on the bus but the kernel knows nothing about it.
+    pub fn is_ghost(&self) -> bool {
+        self.hw_responded && self.sysfs_path.is_none()
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    /// Inverse of [`TuxDevice::to_json`].
+    pub fn from_json(value: &Value) -> Result<TuxDevice> {
+        let string = |key: &str| value[key].as_str().map(String::from);
+        let subsystem = string("subsystem")
+            .and_then(|s| Subsystem::from_name(&s))
+            .ok_or_else(|| anyhow::anyhow!("device without a known subsystem"))?;
+        let address = string("address")
+            .and_then(|a| DeviceAddress::parse(subsystem, &a))
+            .ok_or_else(|| anyhow::anyhow!("device without a valid {} address", subsystem))?;
+        Ok(TuxDevice {
+            subsystem,
+            address,
+            name: string("name").unwrap_or_default(),
+            sysfs_path: string("sysfs_path").map(PathBuf::from),
+            driver: string("driver"),
+            modalias: string("modalias"),
+            in_udev: value["in_udev"].as_bool().unwrap_or(false),
+            hw_responded: value["hw_responded"].as_bool().unwrap_or(false),
+            probe_deferred: value["probe_deferred"].as_bool().unwrap_or(false),
+            attributes: value["attributes"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect(),
+            kernel_log: value["kernel_log"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|line| Some(line.as_str()?.to_string()))
+                .collect(),
+        })
+    }
+}
+
+/// A bus (I2C adapter, USB root hub, ..) and the devices found on it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TuxBus {
+    pub subsystem: Subsystem,
+    pub id: String,   // e.g. "i2c-1"
+    pub name: String, // Adapter name, e.g. "rk3x-i2c"
+    pub devices: Vec<TuxDevice>,
+    pub metadata: BTreeMap<String, String>, // Analysis results, e.g. bus health
+}
+
+impl TuxBus {
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    /// Inverse of [`TuxBus::to_json`].
+    pub fn from_json(value: &Value) -> Result<TuxBus> {
+        let subsystem = value["subsystem"]
+            .as_str()
+            .and_then(Subsystem::from_name)
+            .ok_or_else(|| anyhow::anyhow!("bus without a known subsystem"))?;
+        let id = value["id"].as_str().unwrap_or_default().to_string();
+        let devices = value["devices"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(TuxDevice::from_json)
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| anyhow::anyhow!("{}: {}", id, e))?;
+        Ok(TuxBus {
+            subsystem,
+            name: value["name"].as_str().unwrap_or_default().to_string(),
+            devices,
+            metadata: value["metadata"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect(),
+            id,
+        })
+    }
+}
+
+/// A relation between two devices of the board, e.g. an I2C expander and its gpiochip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceLink {
+    pub from: DeviceAddress,
+    pub to: DeviceAddress,
+    pub relation: String, // e.g. "provides"
+}
+
+/// Everything discovered about a board.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Board {
+    pub buses: Vec<TuxBus>,
+    pub links: Vec<DeviceLink>,
+}
+
+impl Board {
+    pub fn devices(&self) -> impl Iterator<Item = &TuxDevice> {
+        self.buses.iter().flat_map(|b| b.devices.iter())
+    }
+
+    pub fn find_device(&self, address: &DeviceAddress) -> Option<&TuxDevice> {
+        self.devices().find(|d| &d.address == address)
+    }
+
+    /// Returns the bus with `id`, creating an empty one if needed.
+    pub fn bus_mut(&mut self, subsystem: Subsystem, id: &str) -> &mut TuxBus {
+        let index = match self.buses.iter().position(|b| b.id == id) {
+            Some(index) => index,
+            None => {
+                self.buses.push(TuxBus {
+                    subsystem,
+                    id: id.to_string(),
+                    name: String::new(),
+                    devices: Vec::new(),
+                    metadata: BTreeMap::new(),
+                });
+                self.buses.len() - 1
+            }
+        };
+        &mut self.buses[index]
+    }
+
+    /// Devices linked from `address`, with the relation name.
+    pub fn linked_from(&self, address: &DeviceAddress) -> Vec<(&str, &DeviceAddress)> {
+        self.links
+            .iter()
+            .filter(|l| &l.from == address)
+            .map(|l| (l.relation.as_str(), &l.to))
+            .collect()
+    }
+}
@@ -0,0 +1,397 @@
+use crate::device::{DeviceAddress, TuxBus};
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// A node of the live device tree, with its raw property values.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DtNode {
+    pub path: String, // e.g. "/soc/i2c@fe5a0000"
+    pub properties: BTreeMap<String, Vec<u8>>,
+}
+
+impl DtNode {
+    /// Name without the unit address, e.g. "i2c".
+    pub fn name(&self) -> &str {
+        let last = self.path.rsplit('/').next().unwrap_or("");
+        last.split('@').next().unwrap_or(last)
+    }
+
+    /// The part after '@', e.g. "fe5a0000".
+    pub fn unit_address(&self) -> Option<&str> {
+        self.path
+            .rsplit('/')
+            .next()?
+            .split_once('@')
+            .map(|(_, a)| a)
+    }
+
+    /// A string-list property such as `compatible`.
+    pub fn strings(&self, name: &str) -> Vec<String> {
+        self.properties
+            .get(name)
+            .map(|v| {
+                v.split(|b| *b == 0)
+                    .filter(|s| !s.is_empty())
+                    .map(|s| String::from_utf8_lossy(s).to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// A property as big-endian 32-bit cells.
+    pub fn cells(&self, name: &str) -> Option<Vec<u32>> {
+        let value = self.properties.get(name)?;
+        Some(
+            value
+                .chunks_exact(4)
+                .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+                .collect(),
+        )
+    }
+
+    pub fn phandle(&self) -> Option<u32> {
+        self.cells("phandle")
+            .or_else(|| self.cells("linux,phandle"))?
+            .first()
+            .copied()
+    }
+
+    /// Nodes without `status`, or with "okay"/"ok", are enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.strings("status")
+            .first()
+            .is_none_or(|s| s == "okay" || s == "ok")
+    }
+}
+
+/// Reads the whole tree below `root` (e.g. /sys/firmware/devicetree/base), sorted by path.
+pub fn read_tree_in(root: &Path) -> Result<Vec<DtNode>> {
+    let mut nodes = Vec::new();
+    read_node(root, "/", &mut nodes).map_err(|e| anyhow::anyhow!("{}: {}", root.display(), e))?;
+    nodes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(nodes)
+}
+
+fn read_node(dir: &Path, path: &str, nodes: &mut Vec<DtNode>) -> Result<()> {
+    let mut node = DtNode {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut children = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type()?.is_dir() {
+            children.push(name);
+        } else {
+            node.properties.insert(name, fs::read(entry.path())?);
+        }
+    }
+    nodes.push(node);
+    for child in children {
+        let child_path = match path {
+            "/" => format!("/{}", child),
+            _ => format!("{}/{}", path, child),
+        };
+        read_node(&dir.join(&child), &child_path, nodes)?;
+    }
+    Ok(())
+}
+
+/// Properties holding `<phandle args..>` specifiers, and the cells property of the
+/// provider that gives the number of args.
+const SPECIFIER_PROPERTIES: [(&str, &str); 10] = [
+    ("clocks", "#clock-cells"),
+    ("resets", "#reset-cells"),
+    ("phys", "#phy-cells"),
+    ("power-domains", "#power-domain-cells"),
+    ("dmas", "#dma-cells"),
+    ("iommus", "#iommu-cells"),
+    ("interrupts-extended", "#interrupt-cells"),
+    ("pwms", "#pwm-cells"),
+    ("mboxes", "#mbox-cells"),
+    ("io-channels", "#io-channel-cells"),
+];
+
+/// Properties that are plain phandle lists.
+const PHANDLE_PROPERTIES: [&str; 6] = [
+    "interrupt-parent",
+    "phy-handle",
+    "remote-endpoint",
+    "memory-region",
+    "operating-points-v2",
+    "nvmem-cells",
+];
+
+/// Cells property giving the specifier length of a reference property, None if `name` is
+/// a plain phandle list, or no reference at all.
+fn reference_kind(name: &str) -> Option<Option<&'static str>> {
+    if let Some((_, cells)) = SPECIFIER_PROPERTIES.iter().find(|(p, _)| *p == name) {
+        return Some(Some(cells));
+    }
+    if name == "gpios" || name.ends_with("-gpios") || name.ends_with("-gpio") {
+        return Some(Some("#gpio-cells"));
+    }
+    let pinctrl = name
+        .strip_prefix("pinctrl-")
+        .is_some_and(|n| n.parse::<u32>().is_ok());
+    if pinctrl || PHANDLE_PROPERTIES.contains(&name) {
+        return Some(None);
+    }
+    None
+}
+
+/// A required-property rule for nodes of a binding.
+#[derive(Debug, Clone)]
+pub struct BindingRule {
+    pub compatible: String,    // Trailing '*' matches a prefix, e.g. "rockchip,*"
+    pub required: Vec<String>, // "interrupts" is also satisfied by "interrupts-extended"
+}
+
+/// A problem found by [`lint`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DtWarning {
+    pub path: String,
+    pub kind: &'static str, // "broken-phandle", "duplicate-unit-address" or "missing-property"
+    pub message: String,
+}
+
+impl fmt::Display for DtWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} ({})", self.path, self.message, self.kind)
+    }
+}
+
+fn matches(pattern: &str, compatible: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => compatible.starts_with(prefix),
+        None => pattern == compatible,
+    }
+}
+
+/// Lints the live tree at /sys/firmware/devicetree/base.
+pub fn lint(rules: &[BindingRule]) -> Result<Vec<DtWarning>> {
+    lint_in(Path::new("/sys"), rules)
+}
+
+/// Same as [`lint`], with an explicit sysfs root.
+pub fn lint_in(sys_root: &Path, rules: &[BindingRule]) -> Result<Vec<DtWarning>> {
+    let nodes = read_tree_in(&sys_root.join("firmware/devicetree/base"))?;
+    Ok(lint_nodes(&nodes, rules))
+}
+
+/// Checks phandle references, sibling unit addresses and, on enabled nodes, the
+/// properties required by `rules`.
+pub fn lint_nodes(nodes: &[DtNode], rules: &[BindingRule]) -> Vec<DtWarning> {
+    let by_phandle: BTreeMap<u32, &DtNode> = nodes
+        .iter()
+        .filter_map(|n| Some((n.phandle()?, n)))
+        .collect();
+    let mut warnings = Vec::new();
+    let mut warn = |node: &DtNode, kind: &'static str, message: String| {
+        warnings.push(DtWarning {
+            path: node.path.clone(),
+            kind,
+            message,
+        })
+    };
+
+    for node in nodes {
+        for name in node.properties.keys() {
+            let Some(kind) = reference_kind(name) else {
+                continue;
+            };
+            let cells = node.cells(name).unwrap_or_default();
+            let mut i = 0;
+            while i < cells.len() {
+                let phandle = cells[i];
+                i += 1;
+                // A zero phandle is an intentional gap, e.g. an unused GPIO in a list
+                if phandle == 0 && kind.is_some() {
+                    continue;
+                }
+                let Some(target) = by_phandle.get(&phandle) else {
+                    warn(
+                        node,
+                        "broken-phandle",
+                        format!("{} references missing phandle 0x{:x}", name, phandle),
+                    );
+                    break; // Specifier lengths are unknown from here on
+                };
+                if let Some(cells_name) = kind {
+                    match target.cells(cells_name).and_then(|c| c.first().copied()) {
+                        Some(args) => i += args as usize,
+                        None => {
+                            warn(
+                                node,
+                                "broken-phandle",
+                                format!("{} target {} has no {}", name, target.path, cells_name),
+                            );
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        if node.is_enabled() {
+            let compatible = node.strings("compatible");
+            for rule in rules {
+                if !compatible.iter().any(|c| matches(&rule.compatible, c)) {
+                    continue;
+                }
+                for property in &rule.required {
+                    let present = node.properties.contains_key(property)
+                        || (property == "interrupts"
+                            && node.properties.contains_key("interrupts-extended"));
+                    if !present {
+                        warn(
+                            node,
+                            "missing-property",
+                            format!("{} requires `{}`", compatible[0], property),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    let mut seen: BTreeMap<(&str, &str), &str> = BTreeMap::new();
+    for node in nodes {
+        let (Some(address), Some((parent, _))) = (node.unit_address(), node.path.rsplit_once('/'))
+        else {
+            continue;
+        };
+        if let Some(first) = seen.insert((parent, address), &node.path) {
+            warn(
+                node,
+                "duplicate-unit-address",
+                format!("unit address {} also used by {}", address, first),
+            );
+        }
+    }
+    warnings
+}
+
+/// Flags the kernel ORs into an I2C `reg`: ten-bit and own-slave addresses.
+const I2C_REG_FLAGS: u32 = 0xc000_0000;
+
+/// An I2C client declared in the device tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DtI2cDevice {
+    pub path: String,       // e.g. "/soc/i2c@fe5a0000/pmic@20"
+    pub controller: String, // The bus node, e.g. "/soc/i2c@fe5a0000"
+    pub bus: Option<u32>,   // Linux bus number; None if no adapter was registered for it
+    pub addr: u16,
+    pub compatible: Vec<String>,
+    pub enabled: bool,
+}
+
+impl DtI2cDevice {
+    pub fn address(&self) -> Option<DeviceAddress> {
+        Some(DeviceAddress::I2c {
+            bus: self.bus?,
+            addr: self.addr,
+        })
+    }
+}
+
+/// Children with a `reg` of every I2C bus node, i.e. a node named "i2c" (controllers and
+/// mux channels alike). `buses` maps bus node paths to Linux bus numbers.
+pub fn i2c_devices(nodes: &[DtNode], buses: &BTreeMap<String, u32>) -> Vec<DtI2cDevice> {
+    let controllers: BTreeMap<&str, &DtNode> = nodes
+        .iter()
+        .filter(|n| n.name() == "i2c")
+        .map(|n| (n.path.as_str(), n))
+        .collect();
+    nodes
+        .iter()
+        .filter_map(|node| {
+            let (parent, _) = node.path.rsplit_once('/')?;
+            let controller = controllers.get(parent)?;
+            let reg = *node.cells("reg")?.first()?;
+            Some(DtI2cDevice {
+                path: node.path.clone(),
+                controller: parent.to_string(),
+                bus: buses.get(parent).copied(),
+                addr: u16::try_from(reg & !I2C_REG_FLAGS).ok()?,
+                compatible: node.strings("compatible"),
+                enabled: node.is_enabled() && controller.is_enabled(),
+            })
+        })
+        .collect()
+}
+
+/// Bus node paths of the registered adapters, from their `of_node` links.
+pub fn i2c_bus_nodes_in(sys_root: &Path) -> BTreeMap<String, u32> {
+    let Ok(entries) = fs::read_dir(sys_root.join("bus/i2c/devices")) else {
+        return BTreeMap::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let bus: u32 = name.strip_prefix("i2c-")?.parse().ok()?;
+            let target = fs::read_link(e.path().join("of_node")).ok()?;
+            let target = target.to_string_lossy();
+            let (_, path) = target.split_once("devicetree/base")?;
+            let path = if path.is_empty() { "/" } else { path };
+            Some((path.to_string(), bus))
+        })
+        .collect()
+}
+
+/// The device tree's I2C clients, mapped to Linux bus numbers.
+pub fn read_i2c_devices_in(sys_root: &Path) -> Result<Vec<DtI2cDevice>> {
+    let nodes = read_tree_in(&sys_root.join("firmware/devicetree/base"))?;
+    Ok(i2c_devices(&nodes, &i2c_bus_nodes_in(sys_root)))
+}
+
+/// The device tree's I2C declarations against an audit, see [`cross_reference_i2c`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct I2cCrossRef {
+    pub bound: Vec<DtI2cDevice>, // Declared, instantiated and bound to a driver
+    pub not_probed: Vec<DtI2cDevice>, // Declared and enabled, but no driver bound
+    pub unmapped: Vec<DtI2cDevice>, // Enabled, on a bus node with no registered adapter
+    pub disabled: Vec<DtI2cDevice>, // Disabled in the tree; listed if the hardware answers
+    pub undeclared: Vec<DeviceAddress>, // Found on the bus with no declaration: unexpected
+}
+
+/// Sorts every declared client by what the audit saw of it, and every audited device the
+/// tree doesn't declare, so "declared but never probed" stands apart from genuinely
+/// unexpected devices.
+pub fn cross_reference_i2c(declared: &[DtI2cDevice], buses: &[TuxBus]) -> I2cCrossRef {
+    let mut result = I2cCrossRef::default();
+    let audited = || buses.iter().flat_map(|b| b.devices.iter());
+    for dt in declared {
+        let Some(address) = dt.address() else {
+            if dt.enabled {
+                result.unmapped.push(dt.clone());
+            }
+            continue;
+        };
+        let found = audited().find(|d| d.address == address);
+        if !dt.enabled {
+            if found.is_some_and(|d| d.hw_responded || d.is_bound()) {
+                result.disabled.push(dt.clone());
+            }
+        } else if found.is_some_and(|d| d.is_bound()) {
+            result.bound.push(dt.clone());
+        } else {
+            result.not_probed.push(dt.clone());
+        }
+    }
+    result.undeclared = audited()
+        .filter(|d| matches!(d.address, DeviceAddress::I2c { .. }))
+        .filter(|d| {
+            !declared
+                .iter()
+                .any(|dt| dt.address().as_ref() == Some(&d.address))
+        })
+        .map(|d| d.address.clone())
+        .collect();
+    result
+}
@@ -0,0 +1,173 @@
+use crate::deferred_probe;
+use crate::device::{Board, Subsystem, TuxBus};
+use crate::manifest::Manifest;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// Where and how discovery looks.
+#[derive(Debug, Clone)]
+pub struct DiscoveryContext {
+    pub sys_root: PathBuf,
+    pub udev_db: PathBuf,
+    pub hw_probe: bool, // Subsystems that can probe their buses may, e.g. I2C
+    pub manifest: Option<Manifest>, // Per-board settings, e.g. `[i2c_probe]` overrides
+}
+
+impl Default for DiscoveryContext {
+    fn default() -> Self {
+        DiscoveryContext {
+            sys_root: PathBuf::from("/sys"),
+            udev_db: PathBuf::from("/run/udev/data"),
+            hw_probe: false,
+            manifest: None,
+        }
+    }
+}
+
+/// Discovers the buses and devices of one subsystem for the Board model.
+///
+/// Other crates implement this for their own subsystems, e.g. a vendor NPU as
+/// `Subsystem::Other("npu")`, and [`register`] it.
+pub trait Discoverer: Send + Sync {
+    fn subsystem(&self) -> Subsystem;
+
+    /// Buses with their devices. A subsystem the board doesn't have yields none.
+    fn discover(&self, context: &DiscoveryContext) -> Result<Vec<TuxBus>>;
+}
+
+/// Built-in discoverer over an `audit_*_buses_in` function, skipped if `dir` is missing.
+struct SysfsDiscoverer {
+    subsystem: Subsystem,
+    dir: &'static str, // Relative to the sysfs root
+    audit: fn(&Path, &Path) -> Result<Vec<TuxBus>>,
+}
+
+impl Discoverer for SysfsDiscoverer {
+    fn subsystem(&self) -> Subsystem {
+        self.subsystem
+    }
+
+    fn discover(&self, context: &DiscoveryContext) -> Result<Vec<TuxBus>> {
+        if !context.sys_root.join(self.dir).exists() {
+            return Ok(Vec::new());
+        }
+        (self.audit)(&context.sys_root, &context.udev_db)
+    }
+}
+
+/// I2C adapters as listed in sysfs, hardware-probed if the context asks for it.
+#[cfg(feature = "i2c")]
+struct I2cDiscoverer;
+
+#[cfg(feature = "i2c")]
+impl Discoverer for I2cDiscoverer {
+    fn subsystem(&self) -> Subsystem {
+        Subsystem::I2c
+    }
+
+    fn discover(&self, context: &DiscoveryContext) -> Result<Vec<TuxBus>> {
+        use crate::i2c;
+        let Ok(entries) = std::fs::read_dir(context.sys_root.join("bus/i2c/devices")) else {
+            return Ok(Vec::new());
+        };
+        let mut bus_ids: Vec<u32> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                name.strip_prefix("i2c-")?.parse().ok()
+            })
+            .collect();
+        bus_ids.sort();
+        i2c::scan_buses_in_parallel(&context.sys_root, &bus_ids, |bus_id| {
+            let (modes, policy) = match &context.manifest {
+                Some(manifest) => (
+                    i2c::ProbeModes::from_manifest(manifest, bus_id)?,
+                    i2c::ProbePolicy::from_manifest(manifest)?,
+                ),
+                None => Default::default(),
+            };
+            i2c::audit_i2c_bus_in(
+                &i2c::LinuxI2cScanner::new(bus_id)
+                    .with_probe_modes(modes)
+                    .with_policy(policy)
+                    .with_sys_root(&context.sys_root),
+                &context.sys_root,
+                &context.udev_db,
+                bus_id,
+                context.hw_probe,
+            )
+        })
+    }
+}
+
+fn builtin() -> Vec<Arc<dyn Discoverer>> {
+    vec![
+        #[cfg(feature = "i2c")]
+        Arc::new(I2cDiscoverer),
+        #[cfg(feature = "usb")]
+        Arc::new(SysfsDiscoverer {
+            subsystem: Subsystem::Usb,
+            dir: "bus/usb/devices",
+            audit: crate::usb::audit_usb_buses_in,
+        }),
+        #[cfg(feature = "pci")]
+        Arc::new(SysfsDiscoverer {
+            subsystem: Subsystem::Pci,
+            dir: "bus/pci/devices",
+            audit: crate::pci::audit_pci_buses_in,
+        }),
+        Arc::new(SysfsDiscoverer {
+            subsystem: Subsystem::Spi,
+            dir: "class/spi_master",
+            audit: crate::spi::audit_spi_buses_in,
+        }),
+    ]
+}
+
+static REGISTRY: LazyLock<RwLock<Vec<Arc<dyn Discoverer>>>> =
+    LazyLock::new(|| RwLock::new(builtin()));
+
+/// Adds a discoverer, replacing any registered for the same subsystem.
+pub fn register(discoverer: impl Discoverer + 'static) {
+    let mut registry = REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+    let subsystem = discoverer.subsystem();
+    registry.retain(|d| d.subsystem() != subsystem);
+    registry.push(Arc::new(discoverer));
+}
+
+/// Every registered discoverer, built-ins first.
+pub fn discoverers() -> Vec<Arc<dyn Discoverer>> {
+    REGISTRY.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// The discoverer of the subsystem with this `Display` name.
+pub fn find(name: &str) -> Option<Arc<dyn Discoverer>> {
+    discoverers()
+        .into_iter()
+        .find(|d| d.subsystem().to_string() == name)
+}
+
+/// Builds the Board model from every registered subsystem, in registration order.
+pub fn discover_board_in(context: &DiscoveryContext) -> Result<Board> {
+    let mut board = Board::default();
+    for discoverer in discoverers() {
+        let buses = discoverer
+            .discover(context)
+            .with_context(|| format!("{} discovery", discoverer.subsystem()))?;
+        board.buses.extend(buses);
+    }
+    // Tells a driver waiting on a dependency from a missing device; needs root and debugfs
+    if let Ok(Some(deferred)) = deferred_probe::read_deferred_in(&context.sys_root) {
+        deferred_probe::mark(&mut board.buses, &deferred);
+    }
+    Ok(board)
+}
+
+/// Same as [`discover_board_in`] on the live system.
+pub fn discover_board(hw_probe: bool) -> Result<Board> {
+    discover_board_in(&DiscoveryContext {
+        hw_probe,
+        ..Default::default()
+    })
+}
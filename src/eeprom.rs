@@ -0,0 +1,379 @@
+use crate::calibration::crc32;
+use crate::device::TuxBus;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes read from an EEPROM; ONIE caps its TLV area at 2 KiB and FRUs are smaller still.
+pub const MAX_READ: usize = 2048;
+
+/// Board-ID format of an EEPROM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EepromFormat {
+    OnieTlv,
+    IpmiFru,
+    KeyValue, // `key=value` lines, e.g. written by a factory script
+}
+
+impl EepromFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            EepromFormat::OnieTlv => "onie_tlv",
+            EepromFormat::IpmiFru => "ipmi_fru",
+            EepromFormat::KeyValue => "key_value",
+        }
+    }
+}
+
+/// Decoded board identity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardId {
+    pub format: EepromFormat,
+    pub fields: BTreeMap<String, String>, // e.g. "product_name", "serial_number"
+}
+
+impl BoardId {
+    /// The fields as device attributes: `eeprom_format` and an `eeprom_` key per field.
+    pub fn attributes(&self) -> BTreeMap<String, String> {
+        let mut attributes: BTreeMap<String, String> = self
+            .fields
+            .iter()
+            .map(|(key, value)| (format!("eeprom_{}", key), value.clone()))
+            .collect();
+        attributes.insert("eeprom_format".to_string(), self.format.name().to_string());
+        attributes
+    }
+}
+
+const ONIE_MAGIC: &[u8; 8] = b"TlvInfo\0";
+const ONIE_CRC_TYPE: u8 = 0xfe;
+
+/// Field names of the ONIE TLV types.
+const ONIE_TYPES: [(u8, &str); 16] = [
+    (0x21, "product_name"),
+    (0x22, "part_number"),
+    (0x23, "serial_number"),
+    (0x24, "base_mac_address"),
+    (0x25, "manufacture_date"),
+    (0x26, "device_version"),
+    (0x27, "label_revision"),
+    (0x28, "platform_name"),
+    (0x29, "onie_version"),
+    (0x2a, "mac_addresses"),
+    (0x2b, "manufacturer"),
+    (0x2c, "country_code"),
+    (0x2d, "vendor"),
+    (0x2e, "diag_version"),
+    (0x2f, "service_tag"),
+    (0xfd, "vendor_extension"),
+];
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decodes an ONIE TlvInfo EEPROM, checking its CRC-32.
+pub fn decode_onie_tlv(data: &[u8]) -> Result<BTreeMap<String, String>> {
+    let Some(header) = data.get(..11).filter(|h| h.starts_with(ONIE_MAGIC)) else {
+        anyhow::bail!("no TlvInfo header");
+    };
+    let total = u16::from_be_bytes([header[9], header[10]]) as usize;
+    let Some(area) = data.get(11..11 + total) else {
+        anyhow::bail!("TLV area of {} bytes is truncated", total);
+    };
+    let mut fields = BTreeMap::new();
+    let mut pos = 0;
+    while pos + 2 <= area.len() {
+        let (kind, len) = (area[pos], area[pos + 1] as usize);
+        let Some(value) = area.get(pos + 2..pos + 2 + len) else {
+            anyhow::bail!("TLV 0x{:02x} at {} overruns the area", kind, pos);
+        };
+        if kind == ONIE_CRC_TYPE {
+            let covered = &data[..11 + pos + 2];
+            let stored = value.try_into().map(u32::from_be_bytes).unwrap_or_default();
+            if len != 4 || stored != crc32(covered) {
+                anyhow::bail!("CRC-32 mismatch");
+            }
+            return Ok(fields);
+        }
+        let text = || {
+            String::from_utf8_lossy(value)
+                .trim_end_matches('\0')
+                .to_string()
+        };
+        let decoded = match kind {
+            0x24 => value
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(":"),
+            0x26 => value.first().map(|v| v.to_string()).unwrap_or_default(),
+            0x2a if len == 2 => u16::from_be_bytes([value[0], value[1]]).to_string(),
+            0xfd => hex(value),
+            _ => text(),
+        };
+        let name = ONIE_TYPES
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, name)| name.to_string())
+            .unwrap_or_else(|| format!("tlv_0x{:02x}", kind));
+        // Vendor extensions may repeat
+        let mut key = name.clone();
+        let mut n = 1;
+        while fields.contains_key(&key) {
+            n += 1;
+            key = format!("{}_{}", name, n);
+        }
+        fields.insert(key, decoded);
+        pos += 2 + len;
+    }
+    anyhow::bail!("no CRC-32 TLV")
+}
+
+/// Days since 1970-01-01 as a civil (year, month, day).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// A FRU type/length field; `None` at the 0xc1 end marker.
+fn fru_field(area: &[u8], pos: &mut usize) -> Result<Option<String>> {
+    let Some(&type_length) = area.get(*pos) else {
+        anyhow::bail!("FRU area ends without an end marker");
+    };
+    if type_length == 0xc1 {
+        return Ok(None);
+    }
+    let len = (type_length & 0x3f) as usize;
+    let Some(value) = area.get(*pos + 1..*pos + 1 + len) else {
+        anyhow::bail!("FRU field at {} overruns its area", *pos);
+    };
+    *pos += 1 + len;
+    Ok(Some(
+        match type_length >> 6 {
+            0 => hex(value),
+            1 => value
+                .iter()
+                .flat_map(|b| [b >> 4, b & 0x0f])
+                .map(|digit| match digit {
+                    0..=9 => (b'0' + digit) as char,
+                    0x0a => ' ',
+                    0x0b => '-',
+                    _ => '.',
+                })
+                .collect(),
+            2 => {
+                // 6-bit ASCII, four characters per three bytes, least significant first
+                let mut text = String::new();
+                for chunk in value.chunks(3) {
+                    let bits: u32 = chunk
+                        .iter()
+                        .rev()
+                        .fold(0, |acc, b| (acc << 8) | u32::from(*b));
+                    for i in 0..(chunk.len() * 8 / 6) {
+                        text.push((((bits >> (6 * i)) & 0x3f) as u8 + 0x20) as char);
+                    }
+                }
+                text
+            }
+            _ => String::from_utf8_lossy(value).to_string(),
+        }
+        .trim_end()
+        .to_string(),
+    ))
+}
+
+/// Reads the named fields of a FRU area, then any custom fields up to the end marker.
+fn fru_area(
+    fields: &mut BTreeMap<String, String>,
+    area: &[u8],
+    mut pos: usize,
+    prefix: &str,
+    names: &[&str],
+) -> Result<()> {
+    for index in 0.. {
+        let Some(value) = fru_field(area, &mut pos)? else {
+            break;
+        };
+        let key = match names.get(index) {
+            Some(name) => format!("{}_{}", prefix, name),
+            None => format!("{}_custom_{}", prefix, index - names.len() + 1),
+        };
+        if !value.is_empty() {
+            fields.insert(key, value);
+        }
+    }
+    Ok(())
+}
+
+/// Decodes the chassis, board and product info areas of an IPMI FRU, checking checksums.
+pub fn decode_ipmi_fru(data: &[u8]) -> Result<BTreeMap<String, String>> {
+    let Some(header) = data.get(..8).filter(|h| h[0] == 0x01) else {
+        anyhow::bail!("no FRU common header");
+    };
+    if header.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) != 0 {
+        anyhow::bail!("FRU common header checksum mismatch");
+    }
+    let mut fields = BTreeMap::new();
+    for (index, name) in [(2, "chassis"), (3, "board"), (4, "product")] {
+        let offset = header[index] as usize * 8;
+        if offset == 0 {
+            continue;
+        }
+        let len = data.get(offset + 1).map(|l| *l as usize * 8).unwrap_or(0);
+        let Some(area) = data.get(offset..offset + len).filter(|a| a.len() >= 2) else {
+            anyhow::bail!("FRU {} area is truncated", name);
+        };
+        if area.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) != 0 {
+            anyhow::bail!("FRU {} area checksum mismatch", name);
+        }
+        match name {
+            "chassis" => {
+                fields.insert("chassis_type".to_string(), area[2].to_string());
+                fru_area(&mut fields, area, 3, "chassis", &["part_number", "serial"])?;
+            }
+            "board" => {
+                let minutes = u32::from_le_bytes([area[3], area[4], area[5], 0]);
+                if minutes != 0 {
+                    // Minutes since 1996-01-01 00:00 UTC
+                    let minutes = i64::from(minutes) + 9496 * 24 * 60;
+                    let (year, month, day) = civil_from_days(minutes / (24 * 60));
+                    fields.insert(
+                        "board_mfg_date".to_string(),
+                        format!(
+                            "{:04}-{:02}-{:02} {:02}:{:02}",
+                            year,
+                            month,
+                            day,
+                            minutes / 60 % 24,
+                            minutes % 60
+                        ),
+                    );
+                }
+                let names = [
+                    "manufacturer",
+                    "product_name",
+                    "serial",
+                    "part_number",
+                    "fru_file_id",
+                ];
+                fru_area(&mut fields, area, 6, "board", &names)?;
+            }
+            _ => {
+                let names = [
+                    "manufacturer",
+                    "name",
+                    "part_number",
+                    "version",
+                    "serial",
+                    "asset_tag",
+                    "fru_file_id",
+                ];
+                fru_area(&mut fields, area, 3, "product", &names)?;
+            }
+        }
+    }
+    Ok(fields)
+}
+
+/// Decodes `key=value` lines up to the first erased (0xff) or NUL byte; `None` unless every
+/// non-empty line is one.
+pub fn decode_key_value(data: &[u8]) -> Option<BTreeMap<String, String>> {
+    let end = data
+        .iter()
+        .position(|b| *b == 0x00 || *b == 0xff)
+        .unwrap_or(data.len());
+    let text = std::str::from_utf8(&data[..end]).ok()?;
+    let mut fields = BTreeMap::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (key, value) = line.split_once('=')?;
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+        fields.insert(key.to_ascii_lowercase(), value.trim().to_string());
+    }
+    (!fields.is_empty()).then_some(fields)
+}
+
+/// Decodes whichever known format the EEPROM holds; `None` if blank or unrecognised, an
+/// error if recognised but corrupt.
+pub fn decode(data: &[u8]) -> Result<Option<BoardId>> {
+    let (format, fields) = if data.starts_with(ONIE_MAGIC) {
+        (EepromFormat::OnieTlv, decode_onie_tlv(data)?)
+    } else if data.first() == Some(&0x01) && data.len() >= 8 && data[1..8].iter().any(|b| *b != 0) {
+        (EepromFormat::IpmiFru, decode_ipmi_fru(data)?)
+    } else if let Some(fields) = decode_key_value(data) {
+        (EepromFormat::KeyValue, fields)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(BoardId { format, fields }))
+}
+
+/// Reads up to [`MAX_READ`] bytes of the `eeprom` attribute of an at24 device directory.
+pub fn read_sysfs(device_dir: &Path) -> Result<Vec<u8>> {
+    let path = device_dir.join("eeprom");
+    let mut data = Vec::new();
+    File::open(&path)
+        .and_then(|f| f.take(MAX_READ as u64).read_to_end(&mut data))
+        .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+    Ok(data)
+}
+
+/// Reads `len` bytes from offset 0 of an EEPROM without a driver; `wide` parts (above
+/// 2 KiB, e.g. 24c32) take a two-byte offset.
+#[cfg(feature = "i2c")]
+pub fn read_i2c(bus_id: u32, addr: u16, len: usize, wide: bool) -> Result<Vec<u8>> {
+    use crate::lock::{Resource, ResourceLock};
+    use i2cdev::core::I2CDevice;
+    use i2cdev::linux::LinuxI2CDevice;
+
+    crate::safety::require(
+        crate::safety::Access::BusTraffic,
+        &format!("Reading an EEPROM on i2c-{}", bus_id),
+    )?;
+    let _lock = ResourceLock::acquire(Resource::I2cBus(bus_id))?;
+    let bus_path = format!("/dev/i2c-{}", bus_id);
+    let context = |e: i2cdev::linux::LinuxI2CError| {
+        anyhow::anyhow!("EEPROM 0x{:02x} on {}: {}", addr, bus_path, e)
+    };
+    let mut dev = LinuxI2CDevice::new(&bus_path, addr).map_err(context)?;
+    let offset: &[u8] = if wide { &[0, 0] } else { &[0] };
+    dev.write(offset).map_err(context)?;
+    let mut data = vec![0; len.min(MAX_READ)];
+    dev.read(&mut data).map_err(context)?;
+    Ok(data)
+}
+
+/// Decodes the EEPROM of every device on the bus that has an `eeprom` attribute into its
+/// attributes; a corrupt one gets `eeprom_error` instead.
+pub fn annotate_bus(bus: &mut TuxBus) {
+    for device in &mut bus.devices {
+        let Some(dir) = &device.sysfs_path else {
+            continue;
+        };
+        if !dir.join("eeprom").exists() {
+            continue;
+        }
+        match read_sysfs(dir).and_then(|data| decode(&data)) {
+            Ok(Some(id)) => device.attributes.extend(id.attributes()),
+            Ok(None) => {}
+            Err(e) => {
+                device
+                    .attributes
+                    .insert("eeprom_error".to_string(), e.to_string());
+            }
+        }
+    }
+}
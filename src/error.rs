@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+/// Failures a caller handles differently, e.g. "rerun with sudo" against "wrong bus number".
+///
+/// The typed entry points return [`Result`]; the rest of the API returns `anyhow::Result`,
+/// which carries these through unchanged: find them with [`Error::of`] instead of matching
+/// messages.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Bus {bus} not found at {path}")]
+    BusNotFound { bus: String, path: String },
+
+    #[error("Permission denied accessing {}. Try sudo.", path.display())]
+    PermissionDenied { path: PathBuf },
+
+    #[error("Cannot address 0x{addr:02x} on {bus}: {message}")]
+    ProbeFailed {
+        bus: String,
+        addr: u16,
+        message: String,
+    },
+
+    #[error("{what}: {message}")]
+    ParseError { what: String, message: String }, // `what` names the input, e.g. its path
+
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// An I/O error on `path`, as [`Error::PermissionDenied`] if it is one.
+    pub fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Error {
+        let path = path.into();
+        match source.kind() {
+            std::io::ErrorKind::PermissionDenied => Error::PermissionDenied { path },
+            _ => Error::Io { path, source },
+        }
+    }
+
+    /// The typed error somewhere in an `anyhow` error's chain, if any.
+    pub fn of(error: &anyhow::Error) -> Option<&Error> {
+        error.chain().find_map(|e| e.downcast_ref::<Error>())
+    }
+}
+
+/// Keeps a typed error that went through `anyhow` typed, instead of nesting it in `Other`.
+impl From<anyhow::Error> for Error {
+    fn from(error: anyhow::Error) -> Error {
+        match error.downcast::<Error>() {
+            Ok(typed) => typed,
+            Err(error) => Error::Other(error),
+        }
+    }
+}
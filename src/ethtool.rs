@@ -0,0 +1,155 @@
+use anyhow::Result;
+
+// Linux ethtool ABI (include/uapi/linux/ethtool.h)
+const SIOCETHTOOL: libc::c_ulong = 0x8946;
+const ETHTOOL_GSET: u32 = 0x01;
+const ETHTOOL_GDRVINFO: u32 = 0x03;
+const SPEED_UNKNOWN: u32 = u32::MAX;
+const DUPLEX_HALF: u8 = 0;
+const DUPLEX_FULL: u8 = 1;
+
+/// Runs one ethtool command on `iface`; `data` is the command's struct, starting with `cmd`.
+pub(crate) fn ethtool_ioctl<T>(iface: &str, data: &mut T) -> Result<()> {
+    let mut ifr: libc::ifreq = unsafe { std::mem::zeroed() };
+    if iface.len() >= ifr.ifr_name.len() {
+        anyhow::bail!("Interface name too long: {}", iface);
+    }
+    for (dst, src) in ifr.ifr_name.iter_mut().zip(iface.bytes()) {
+        *dst = src as libc::c_char;
+    }
+    ifr.ifr_ifru.ifru_data = data as *mut T as *mut libc::c_char;
+
+    let sock = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0) };
+    if sock < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let rc = unsafe { libc::ioctl(sock, SIOCETHTOOL as _, &mut ifr) };
+    let err = std::io::Error::last_os_error();
+    unsafe { libc::close(sock) };
+    if rc < 0 {
+        anyhow::bail!("ethtool ioctl on {} failed: {}", iface, err);
+    }
+    Ok(())
+}
+
+#[repr(C)]
+struct EthtoolDrvinfo {
+    cmd: u32,
+    driver: [u8; 32],
+    version: [u8; 32],
+    fw_version: [u8; 32],
+    bus_info: [u8; 32],
+    erom_version: [u8; 32],
+    reserved2: [u8; 12],
+    n_priv_flags: u32,
+    n_stats: u32,
+    testinfo_len: u32,
+    eedump_len: u32,
+    regdump_len: u32,
+}
+
+// `struct ethtool_cmd`: deprecated, but answered for every driver through link ksettings
+#[repr(C)]
+struct EthtoolCmd {
+    cmd: u32,
+    supported: u32,
+    advertising: u32,
+    speed: u16,
+    duplex: u8,
+    port: u8,
+    phy_address: u8,
+    transceiver: u8,
+    autoneg: u8,
+    mdio_support: u8,
+    maxtxpkt: u32,
+    maxrxpkt: u32,
+    speed_hi: u16,
+    eth_tp_mdix: u8,
+    eth_tp_mdix_ctrl: u8,
+    lp_advertising: u32,
+    reserved: [u32; 2],
+}
+
+fn c_string(bytes: &[u8]) -> Option<String> {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    let text = String::from_utf8_lossy(&bytes[..end]).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Driver identity, as `ethtool -i`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DriverInfo {
+    pub driver: Option<String>,
+    pub version: Option<String>,
+    pub firmware: Option<String>,
+    pub bus_info: Option<String>, // e.g. "0000:01:00.0" or "fe2a0000.ethernet"
+}
+
+/// Link settings, as `ethtool <iface>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkSettings {
+    pub speed: Option<u32>,        // Mb/s, None without a link
+    pub full_duplex: Option<bool>, // None without a link
+    pub autoneg: bool,
+    pub phy_address: u8,
+}
+
+/// Reads the driver identity of `iface`.
+pub fn driver_info(iface: &str) -> Result<DriverInfo> {
+    let mut info = EthtoolDrvinfo {
+        cmd: ETHTOOL_GDRVINFO,
+        driver: [0; 32],
+        version: [0; 32],
+        fw_version: [0; 32],
+        bus_info: [0; 32],
+        erom_version: [0; 32],
+        reserved2: [0; 12],
+        n_priv_flags: 0,
+        n_stats: 0,
+        testinfo_len: 0,
+        eedump_len: 0,
+        regdump_len: 0,
+    };
+    ethtool_ioctl(iface, &mut info)?;
+    Ok(DriverInfo {
+        driver: c_string(&info.driver),
+        version: c_string(&info.version),
+        firmware: c_string(&info.fw_version),
+        bus_info: c_string(&info.bus_info),
+    })
+}
+
+/// Reads the link settings of `iface`.
+pub fn link_settings(iface: &str) -> Result<LinkSettings> {
+    let mut cmd = EthtoolCmd {
+        cmd: ETHTOOL_GSET,
+        supported: 0,
+        advertising: 0,
+        speed: 0,
+        duplex: 0,
+        port: 0,
+        phy_address: 0,
+        transceiver: 0,
+        autoneg: 0,
+        mdio_support: 0,
+        maxtxpkt: 0,
+        maxrxpkt: 0,
+        speed_hi: 0,
+        eth_tp_mdix: 0,
+        eth_tp_mdix_ctrl: 0,
+        lp_advertising: 0,
+        reserved: [0; 2],
+    };
+    ethtool_ioctl(iface, &mut cmd)?;
+    let speed = (cmd.speed_hi as u32) << 16 | cmd.speed as u32;
+    Ok(LinkSettings {
+        speed: (speed != SPEED_UNKNOWN && speed != 0).then_some(speed),
+        full_duplex: match cmd.duplex {
+            DUPLEX_FULL => Some(true),
+            DUPLEX_HALF => Some(false),
+            _ => None,
+        },
+        autoneg: cmd.autoneg != 0,
+        phy_address: cmd.phy_address,
+    })
+}
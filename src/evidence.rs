@@ -0,0 +1,129 @@
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A file attached to the bundle by a check.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvidenceEntry {
+    pub check: String, // ID of the check that produced it
+    pub path: PathBuf, // Relative to the bundle directory
+    pub description: String,
+}
+
+/// A directory collecting raw evidence from a validation run, with an `index.json`.
+pub struct EvidenceBundle {
+    pub dir: PathBuf,
+    entries: Vec<EvidenceEntry>,
+}
+
+/// Keeps file names portable: anything but `[A-Za-z0-9._-]` becomes `_`.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "._-".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+impl EvidenceBundle {
+    /// Creates (or reuses) the bundle directory.
+    pub fn create(dir: &Path) -> Result<EvidenceBundle> {
+        fs::create_dir_all(dir)?;
+        Ok(EvidenceBundle {
+            dir: dir.to_path_buf(),
+            entries: Vec::new(),
+        })
+    }
+
+    /// Opens an existing bundle with the entries of its `index.json`, e.g. to add to it
+    /// after the run; a directory without an index opens empty.
+    pub fn open(dir: &Path) -> Result<EvidenceBundle> {
+        let mut bundle = EvidenceBundle::create(dir)?;
+        let index_path = dir.join("index.json");
+        let index: Value = match fs::read_to_string(&index_path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| anyhow::anyhow!("{}: {}", index_path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(bundle),
+            Err(e) => anyhow::bail!("{}: {}", index_path.display(), e),
+        };
+        for entry in index["entries"].as_array().into_iter().flatten() {
+            let field = |key: &str| entry[key].as_str().unwrap_or_default().to_string();
+            bundle.entries.push(EvidenceEntry {
+                check: field("check"),
+                path: PathBuf::from(field("path")),
+                description: field("description"),
+            });
+        }
+        Ok(bundle)
+    }
+
+    pub fn entries(&self) -> &[EvidenceEntry] {
+        &self.entries
+    }
+
+    /// Stores `contents` as `<check>/<name>` and returns the absolute path.
+    pub fn add_bytes(
+        &mut self,
+        check: &str,
+        name: &str,
+        contents: &[u8],
+        description: &str,
+    ) -> Result<PathBuf> {
+        let relative = Path::new(&sanitize(check)).join(sanitize(name));
+        let path = self.dir.join(&relative);
+        fs::create_dir_all(path.parent().expect("joined path has a parent"))?;
+        fs::write(&path, contents)?;
+        self.entries.retain(|e| e.path != relative);
+        self.entries.push(EvidenceEntry {
+            check: check.to_string(),
+            path: relative,
+            description: description.to_string(),
+        });
+        Ok(path)
+    }
+
+    pub fn add_text(
+        &mut self,
+        check: &str,
+        name: &str,
+        contents: &str,
+        description: &str,
+    ) -> Result<PathBuf> {
+        self.add_bytes(check, name, contents.as_bytes(), description)
+    }
+
+    /// Copies an existing file (e.g. a pstore record) into the bundle.
+    pub fn add_file(&mut self, check: &str, source: &Path, description: &str) -> Result<PathBuf> {
+        let name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "file".to_string());
+        self.add_bytes(check, &name, &fs::read(source)?, description)
+    }
+
+    /// Writes `index.json` listing every entry.
+    pub fn write_index(&self) -> Result<PathBuf> {
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|e| {
+                json!({
+                    "check": e.check,
+                    "path": e.path.to_string_lossy(),
+                    "description": e.description,
+                })
+            })
+            .collect();
+        let path = self.dir.join("index.json");
+        fs::write(
+            &path,
+            serde_json::to_string_pretty(&json!({ "entries": entries }))?,
+        )?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,80 @@
+use crate::device::{Board, DeviceAddress, TuxDevice};
+use std::fs;
+
+/// How to instantiate a device the kernel doesn't know about (e.g. a ghost found by hw probe).
+#[derive(Debug, Clone)]
+pub struct ForceBind {
+    pub address: DeviceAddress,
+    pub chip: String,           // i2c `new_device` name, e.g. "pca9555"
+    pub module: Option<String>, // Module to load first, if not built in
+}
+
+/// Kernel module providing the device's bound driver (None if built in or unbound).
+pub fn driver_module(device: &TuxDevice) -> Option<String> {
+    let link = fs::read_link(device.sysfs_path.as_ref()?.join("driver/module")).ok()?;
+    Some(link.file_name()?.to_string_lossy().to_string())
+}
+
+/// Renders udev rules that create each force-bound I2C device when its adapter appears.
+pub fn udev_rules(board: &Board, bindings: &[ForceBind]) -> String {
+    let mut rules = String::from("# Generated by tux-validation from the validated board model\n");
+    for binding in bindings {
+        let DeviceAddress::I2c { bus, addr } = binding.address else {
+            rules.push_str(&format!(
+                "# {}: only I2C devices can be force-bound\n",
+                binding.address
+            ));
+            continue;
+        };
+        let state = match board.find_device(&binding.address) {
+            Some(d) if d.is_ghost() => "responds to probe, not declared",
+            Some(d) if d.probe_deferred => "declared, probe deferred",
+            Some(d) if !d.is_bound() => "declared, no driver bound",
+            Some(_) => "already bound",
+            None => "not seen during validation",
+        };
+        rules.push_str(&format!(
+            "# {} ({}): {}\n",
+            binding.address, binding.chip, state
+        ));
+
+        let mut run = String::new();
+        if let Some(module) = &binding.module {
+            run.push_str(&format!("RUN+=\"/sbin/modprobe {}\", ", module));
+        }
+        rules.push_str(&format!(
+            "ACTION==\"add\", SUBSYSTEM==\"i2c\", KERNEL==\"i2c-{bus}\", {run}\
+             RUN+=\"/bin/sh -c 'echo {chip} 0x{addr:02x} > /sys/bus/i2c/devices/i2c-{bus}/new_device'\"\n",
+            bus = bus,
+            run = run,
+            chip = binding.chip,
+            addr = addr
+        ));
+    }
+    rules
+}
+
+/// Renders modprobe.d aliases so each bound device's module autoloads from its modalias.
+///
+/// Built-in drivers and devices without a modalias are listed as comments.
+pub fn modprobe_config(board: &Board) -> String {
+    let mut config = String::from("# Generated by tux-validation from the validated board model\n");
+    for device in board.devices().filter(|d| d.is_bound()) {
+        let driver = device.driver.as_deref().unwrap_or_default();
+        match (&device.modalias, driver_module(device)) {
+            (Some(modalias), Some(module)) => config.push_str(&format!(
+                "# {} ({})\nalias {} {}\n",
+                device.address, device.name, modalias, module
+            )),
+            (None, _) => config.push_str(&format!(
+                "# {} ({}): no modalias, driver {}\n",
+                device.address, device.name, driver
+            )),
+            (_, None) => config.push_str(&format!(
+                "# {} ({}): driver {} is built in\n",
+                device.address, device.name, driver
+            )),
+        }
+    }
+    config
+}
@@ -0,0 +1,110 @@
+//! C API for embedding the validator, e.g. in a C++ test executive.
+//!
+//! Generate the header with `cbindgen --config cbindgen.toml --output tux_validation.h`.
+//! Strings returned by the library are owned by the caller and must be released with
+//! [`tux_string_free`].
+
+use crate::error::Error;
+use crate::manifest::{self, Manifest};
+use anyhow::Result;
+use std::cell::{Cell, RefCell};
+use std::ffi::{CStr, CString, c_char};
+use std::path::Path;
+use std::ptr;
+
+/// What the last error was, so a caller can tell "run as root" from "no such bus".
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuxErrorKind {
+    TuxErrorNone = 0,
+    TuxErrorOther,
+    TuxErrorBusNotFound,
+    TuxErrorPermissionDenied,
+    TuxErrorProbeFailed,
+    TuxErrorParse,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+    static LAST_ERROR_KIND: Cell<TuxErrorKind> = const { Cell::new(TuxErrorKind::TuxErrorNone) };
+}
+
+fn set_last_error(message: String, kind: TuxErrorKind) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+    LAST_ERROR_KIND.with(|k| k.set(kind));
+}
+
+fn error_kind(e: &anyhow::Error) -> TuxErrorKind {
+    match Error::of(e) {
+        Some(Error::BusNotFound { .. }) => TuxErrorKind::TuxErrorBusNotFound,
+        Some(Error::PermissionDenied { .. }) => TuxErrorKind::TuxErrorPermissionDenied,
+        Some(Error::ProbeFailed { .. }) => TuxErrorKind::TuxErrorProbeFailed,
+        Some(Error::ParseError { .. }) => TuxErrorKind::TuxErrorParse,
+        _ => TuxErrorKind::TuxErrorOther,
+    }
+}
+
+fn into_c_string(result: Result<String>) -> *mut c_char {
+    match result.and_then(|s| Ok(CString::new(s)?)) {
+        Ok(s) => s.into_raw(),
+        Err(e) => {
+            set_last_error(e.to_string(), error_kind(&e));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Library version, a static string.
+#[unsafe(no_mangle)]
+pub extern "C" fn tux_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Audits all I2C buses, checks them against the manifest at `path` and returns the JSON
+/// report, or NULL on error (see [`tux_last_error`]).
+///
+/// # Safety
+///
+/// `path` must be NULL or a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tux_run_manifest(path: *const c_char, hw_probe: bool) -> *mut c_char {
+    if path.is_null() {
+        set_last_error("path is NULL".to_string(), TuxErrorKind::TuxErrorOther);
+        return ptr::null_mut();
+    }
+    // SAFETY: checked for NULL above, validity is the caller's contract
+    let path = unsafe { CStr::from_ptr(path) }
+        .to_string_lossy()
+        .to_string();
+    into_c_string((|| {
+        let manifest = Manifest::load(Path::new(&path))?;
+        let report = manifest::run(&manifest, hw_probe)?.to_json();
+        Ok(serde_json::to_string(&report)?)
+    })())
+}
+
+/// Message of the last error on this thread, or NULL. Valid until the next failing call.
+#[unsafe(no_mangle)]
+pub extern "C" fn tux_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+/// Kind of the last error on this thread; `TuxErrorNone` if nothing failed yet.
+#[unsafe(no_mangle)]
+pub extern "C" fn tux_last_error_kind() -> TuxErrorKind {
+    LAST_ERROR_KIND.with(|k| k.get())
+}
+
+/// Frees a string returned by the library. NULL is ignored.
+///
+/// # Safety
+///
+/// `s` must be NULL or a pointer returned by this library that wasn't freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tux_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: allocated by CString::into_raw in into_c_string
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
@@ -0,0 +1,217 @@
+use crate::crash::{self, CrashScanConfig};
+use crate::evidence::EvidenceBundle;
+use crate::safety::SafetyPolicy;
+use crate::{kthreads, soc, usb_serial};
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A check that only reads from the unit.
+///
+/// Sealed: only the curated checks in this module implement it, so a [`FieldDiagnostics`]
+/// run can't contain anything that probes buses or writes to the device.
+pub trait ReadOnlyCheck: sealed::Sealed {
+    fn id(&self) -> &'static str;
+    /// Gathers evidence below `root` (normally `/`) and returns findings worth a look.
+    fn collect(&self, root: &Path, bundle: &mut EvidenceBundle) -> Result<Vec<String>>;
+}
+
+/// Copies kernel and OS state files verbatim.
+pub struct SystemSnapshot;
+
+impl sealed::Sealed for SystemSnapshot {}
+
+impl ReadOnlyCheck for SystemSnapshot {
+    fn id(&self) -> &'static str {
+        "system_snapshot"
+    }
+
+    fn collect(&self, root: &Path, bundle: &mut EvidenceBundle) -> Result<Vec<String>> {
+        let files = [
+            "proc/cmdline",
+            "proc/version",
+            "proc/modules",
+            "proc/interrupts",
+            "proc/self/mountinfo",
+            "etc/os-release",
+        ];
+        let mut findings = Vec::new();
+        for file in files {
+            let path = root.join(file);
+            if bundle.add_file(self.id(), &path, file).is_err() {
+                findings.push(format!("{} not readable", path.display()));
+            }
+        }
+        Ok(findings)
+    }
+}
+
+/// SoC identity and revision.
+pub struct SocIdentity;
+
+impl sealed::Sealed for SocIdentity {}
+
+impl ReadOnlyCheck for SocIdentity {
+    fn id(&self) -> &'static str {
+        "soc_identity"
+    }
+
+    fn collect(&self, root: &Path, bundle: &mut EvidenceBundle) -> Result<Vec<String>> {
+        let facts = soc::read_soc_info_in(&root.join("sys")).facts();
+        let text: String = facts
+            .iter()
+            .map(|(k, v)| format!("{}={}\n", k, v))
+            .collect();
+        bundle.add_text(self.id(), "soc.txt", &text, "SoC identification")?;
+        Ok(Vec::new())
+    }
+}
+
+/// Kernel threads; ones in uninterruptible sleep are reported.
+pub struct KernelThreads;
+
+impl sealed::Sealed for KernelThreads {}
+
+impl ReadOnlyCheck for KernelThreads {
+    fn id(&self) -> &'static str {
+        "kernel_threads"
+    }
+
+    fn collect(&self, root: &Path, bundle: &mut EvidenceBundle) -> Result<Vec<String>> {
+        let threads = kthreads::list_kernel_threads_in(&root.join("proc"))?;
+        let text: String = threads
+            .iter()
+            .map(|t| format!("{} {} {}\n", t.pid, t.state, t.comm))
+            .collect();
+        bundle.add_text(self.id(), "kthreads.txt", &text, "Kernel threads")?;
+        Ok(threads
+            .iter()
+            .filter(|t| t.state == 'D')
+            .map(|t| format!("{} (pid {}) in uninterruptible sleep", t.comm, t.pid))
+            .collect())
+    }
+}
+
+/// USB serial channels and their udev links.
+pub struct UsbSerialPorts;
+
+impl sealed::Sealed for UsbSerialPorts {}
+
+impl ReadOnlyCheck for UsbSerialPorts {
+    fn id(&self) -> &'static str {
+        "usb_serial"
+    }
+
+    fn collect(&self, root: &Path, bundle: &mut EvidenceBundle) -> Result<Vec<String>> {
+        let ports =
+            usb_serial::list_usb_serial_ports_in(&root.join("sys"), &root.join("run/udev/data"))?;
+        let text: String = ports
+            .iter()
+            .map(|p| {
+                format!(
+                    "{} {:04x}:{:04x} {} if{} {}\n",
+                    p.tty,
+                    p.vendor_id,
+                    p.product_id,
+                    p.usb_path,
+                    p.interface,
+                    p.symlinks.join(" ")
+                )
+            })
+            .collect();
+        bundle.add_text(self.id(), "ports.txt", &text, "USB serial ports")?;
+        Ok(Vec::new())
+    }
+}
+
+/// Coredumps and pstore records, regardless of age.
+pub struct CrashArtifacts;
+
+impl sealed::Sealed for CrashArtifacts {}
+
+impl ReadOnlyCheck for CrashArtifacts {
+    fn id(&self) -> &'static str {
+        "crash_artifacts"
+    }
+
+    fn collect(&self, root: &Path, bundle: &mut EvidenceBundle) -> Result<Vec<String>> {
+        let config = CrashScanConfig {
+            coredump_dirs: vec![root.join("var/lib/systemd/coredump")],
+            pstore_dir: root.join("sys/fs/pstore"),
+            unclean_markers: Vec::new(),
+            since: SystemTime::UNIX_EPOCH,
+        };
+        Ok(crash::check_crash_artifacts(&config, Some(bundle))?
+            .into_iter()
+            .map(|a| a.summary)
+            .collect())
+    }
+}
+
+/// Outcome of a field diagnostics run.
+#[derive(Debug, Clone, Default)]
+pub struct FieldReport {
+    pub findings: Vec<(String, String)>, // (check ID, finding)
+    pub errors: Vec<(String, String)>,   // Checks that couldn't run
+    pub index: PathBuf,                  // index.json of the support bundle
+}
+
+/// A set of read-only checks producing a support bundle, safe to run on customer units.
+pub struct FieldDiagnostics {
+    pub checks: Vec<Box<dyn ReadOnlyCheck>>,
+}
+
+impl FieldDiagnostics {
+    /// The default curated set.
+    pub fn curated() -> FieldDiagnostics {
+        FieldDiagnostics {
+            checks: vec![
+                Box::new(SystemSnapshot),
+                Box::new(SocIdentity),
+                Box::new(KernelThreads),
+                Box::new(UsbSerialPorts),
+                Box::new(CrashArtifacts),
+            ],
+        }
+    }
+
+    /// The policy [`FieldDiagnostics::run`] enforces on the whole process while it runs.
+    pub fn policy(&self) -> SafetyPolicy {
+        SafetyPolicy::READ_ONLY
+    }
+
+    /// Runs every check against `root` and writes the support bundle to `bundle_dir`.
+    ///
+    /// A failing check doesn't stop the run; it's listed in [`FieldReport::errors`].
+    pub fn run(&self, root: &Path, bundle_dir: &Path) -> Result<FieldReport> {
+        let _policy = self.policy().enforce();
+        let mut bundle = EvidenceBundle::create(bundle_dir)?;
+        let mut report = FieldReport::default();
+        for check in &self.checks {
+            match check.collect(root, &mut bundle) {
+                Ok(findings) => report
+                    .findings
+                    .extend(findings.into_iter().map(|f| (check.id().to_string(), f))),
+                Err(e) => report.errors.push((check.id().to_string(), e.to_string())),
+            }
+        }
+        let summary: String = report
+            .findings
+            .iter()
+            .map(|(id, f)| format!("{}: {}\n", id, f))
+            .chain(
+                report
+                    .errors
+                    .iter()
+                    .map(|(id, e)| format!("{}: error: {}\n", id, e)),
+            )
+            .collect();
+        bundle.add_text("field_diagnostics", "summary.txt", &summary, "Findings")?;
+        report.index = bundle.write_index()?;
+        Ok(report)
+    }
+}
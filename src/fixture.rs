@@ -0,0 +1,126 @@
+use crate::safety::{self, Access};
+use anyhow::Result;
+use gpiocdev::Request;
+use gpiocdev::line::Value;
+use std::fs;
+use std::io::{BufRead, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Where the test jig (not the DUT) stores its fixture ID.
+#[derive(Debug, Clone)]
+pub enum FixtureIdSource {
+    /// Strap resistors on gpio lines, least significant bit first.
+    GpioStraps { chip: u32, lines: Vec<u32> },
+    /// An ASCII ID in an EEPROM exposed by at24, e.g. /sys/bus/i2c/devices/3-0050/eeprom.
+    Eeprom {
+        path: PathBuf,
+        offset: u64,
+        len: usize,
+    },
+}
+
+/// Combines strap levels (least significant bit first) into an ID.
+pub fn strap_value(bits: &[bool]) -> u32 {
+    bits.iter()
+        .enumerate()
+        .filter(|(_, high)| **high)
+        .fold(0, |acc, (i, _)| acc | (1 << i))
+}
+
+/// Extracts an ASCII ID, stopping at the first NUL or erased (0xff) byte.
+pub fn eeprom_id_from_bytes(bytes: &[u8]) -> Option<String> {
+    let end = bytes
+        .iter()
+        .position(|b| *b == 0 || *b == 0xff)
+        .unwrap_or(bytes.len());
+    let id = std::str::from_utf8(&bytes[..end]).ok()?.trim();
+    if id.is_empty() {
+        None
+    } else {
+        Some(id.to_string())
+    }
+}
+
+fn read_straps(chip: u32, lines: &[u32]) -> Result<Vec<bool>> {
+    // Requesting the lines as inputs reconfigures any that were outputs
+    safety::require(
+        Access::Write,
+        &format!("Reading straps on gpiochip{}", chip),
+    )?;
+    let path = format!("/dev/gpiochip{}", chip);
+    let mut bits = Vec::new();
+    for &line in lines {
+        let req = Request::builder()
+            .on_chip(&path)
+            .with_consumer("tux-validation")
+            .with_line(line)
+            .as_input()
+            .request()?;
+        bits.push(req.lone_value()? == Value::Active);
+    }
+    Ok(bits)
+}
+
+/// Reads the fixture ID; strap IDs are rendered in decimal.
+pub fn read_fixture_id(source: &FixtureIdSource) -> Result<String> {
+    match source {
+        FixtureIdSource::GpioStraps { chip, lines } => {
+            Ok(strap_value(&read_straps(*chip, lines)?).to_string())
+        }
+        FixtureIdSource::Eeprom { path, offset, len } => {
+            let mut file = fs::File::open(path)?;
+            file.seek(SeekFrom::Start(*offset))?;
+            let mut bytes = vec![0; *len];
+            let read = file.read(&mut bytes)?;
+            eeprom_id_from_bytes(&bytes[..read])
+                .ok_or_else(|| anyhow::anyhow!("{}: no fixture ID programmed", path.display()))
+        }
+    }
+}
+
+/// A manifest overlay to apply on a given fixture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixtureProfile {
+    pub id: String, // "*" is the fallback for unknown fixtures
+    pub overlay: PathBuf,
+}
+
+/// Parses a profile map: `<fixture id> = <overlay path>` lines, `#` comments.
+///
+/// Relative overlay paths are resolved against `base_dir`.
+pub fn parse_profiles_from_reader<R: BufRead>(
+    reader: R,
+    base_dir: &Path,
+) -> Result<Vec<FixtureProfile>> {
+    let mut profiles = Vec::new();
+    for line_result in reader.lines() {
+        let line = line_result?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((id, overlay)) = line.split_once('=') else {
+            anyhow::bail!("Malformed profile line: {}", line);
+        };
+        profiles.push(FixtureProfile {
+            id: id.trim().to_string(),
+            overlay: base_dir.join(overlay.trim()),
+        });
+    }
+    Ok(profiles)
+}
+
+/// Picks the profile for a fixture ID, falling back to `*`.
+pub fn select_profile<'a>(profiles: &'a [FixtureProfile], id: &str) -> Result<&'a FixtureProfile> {
+    profiles
+        .iter()
+        .find(|p| p.id == id)
+        .or_else(|| profiles.iter().find(|p| p.id == "*"))
+        .ok_or_else(|| anyhow::anyhow!("No manifest overlay for fixture {}", id))
+}
+
+/// Reads the fixture ID and returns the overlay to apply.
+pub fn detect_overlay(source: &FixtureIdSource, profiles: &[FixtureProfile]) -> Result<PathBuf> {
+    let id = read_fixture_id(source)?;
+    Ok(select_profile(profiles, &id)?.overlay.clone())
+}
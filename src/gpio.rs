@@ -0,0 +1,163 @@
+use crate::device::DeviceAddress;
+#[cfg(feature = "hardware")]
+use crate::device::Subsystem;
+#[cfg(feature = "hardware")]
+use anyhow::Result;
+#[cfg(feature = "hardware")]
+use gpiocdev::Request;
+#[cfg(feature = "hardware")]
+use gpiocdev::chip::Chip;
+#[cfg(feature = "hardware")]
+use gpiocdev::line::{Direction, Value};
+
+/// One line of a GPIO chip, as reported by the character device.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpioLine {
+    pub offset: u32,
+    pub name: String,             // From gpio-line-names; may be empty
+    pub consumer: Option<String>, // Set by whoever requested the line
+    pub used: bool,
+    pub output: bool,
+    pub active_low: bool,
+    pub value: Option<bool>, // Only read for unused lines; used ones can't be requested
+}
+
+/// A gpiochip and its lines.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpioChip {
+    pub chip: u32,     // N of /dev/gpiochipN
+    pub label: String, // Driver label, e.g. "gpio0" or "pinctrl-bcm2711"
+    pub lines: Vec<GpioLine>,
+}
+
+impl GpioChip {
+    pub fn address(&self) -> DeviceAddress {
+        DeviceAddress::Gpio { chip: self.chip }
+    }
+
+    /// Chip numbers are dynamic; expectations may name the chip by label instead.
+    pub fn matches(&self, chip: &str) -> bool {
+        chip == self.label || chip == self.address().to_string()
+    }
+
+    /// A line by name or, if `line` is a number, by offset.
+    pub fn find_line(&self, line: &str) -> Option<&GpioLine> {
+        match line.parse::<u32>() {
+            Ok(offset) => self.lines.iter().find(|l| l.offset == offset),
+            Err(_) => self.lines.iter().find(|l| l.name == line),
+        }
+    }
+}
+
+/// Lists every gpiochip with its lines.
+#[cfg(feature = "hardware")]
+pub fn find_gpio_chips() -> Result<Vec<GpioChip>> {
+    let mut chips = Vec::new();
+    for path in gpiocdev::chip::chips()? {
+        let chip = Chip::from_path(&path)?;
+        let info = chip.info()?;
+        let Some(DeviceAddress::Gpio { chip: number }) =
+            DeviceAddress::parse(Subsystem::Gpio, &info.name)
+        else {
+            continue;
+        };
+        let mut lines = Vec::new();
+        for line in chip.line_info_iter()? {
+            let line = line?;
+            lines.push(GpioLine {
+                offset: line.offset,
+                name: line.name,
+                consumer: Some(line.consumer).filter(|c| !c.is_empty()),
+                used: line.used,
+                output: line.direction == Direction::Output,
+                active_low: line.active_low,
+                value: if line.used {
+                    None
+                } else {
+                    read_value(&path, line.offset).ok()
+                },
+            });
+        }
+        chips.push(GpioChip {
+            chip: number,
+            label: info.label,
+            lines,
+        });
+    }
+    chips.sort_by_key(|c| c.chip);
+    Ok(chips)
+}
+
+/// Requests a free line as-is, so its direction and level are left alone.
+#[cfg(feature = "hardware")]
+fn read_value(path: &std::path::Path, offset: u32) -> Result<bool> {
+    let req = Request::builder()
+        .on_chip(path)
+        .with_consumer("tux-validation")
+        .with_line(offset)
+        .as_is()
+        .request()?;
+    Ok(req.lone_value()? == Value::Active)
+}
+
+/// A line that should be held by a given consumer, e.g. a reset GPIO claimed by its driver.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedGpioLine {
+    pub chip: String,     // Label or "gpiochipN"
+    pub line: String,     // Line name, or offset
+    pub consumer: String, // As shown by gpioinfo, e.g. "reset" or "spi0 CS0"
+}
+
+/// Holds results of checking GPIO line consumers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpioValidationResult {
+    pub present: Vec<String>,    // "chip/line"
+    pub missing: Vec<String>,    // Chip or line not found
+    pub mismatched: Vec<String>, // Unrequested, or held by another consumer
+}
+
+impl GpioValidationResult {
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+/// Checks that the expected lines are requested by the expected consumers.
+pub fn validate_gpio_lines(
+    chips: &[GpioChip],
+    expected: &[ExpectedGpioLine],
+) -> GpioValidationResult {
+    let mut result = GpioValidationResult::default();
+    for exp in expected {
+        let id = format!("{}/{}", exp.chip, exp.line);
+        let Some(chip) = chips.iter().find(|c| c.matches(&exp.chip)) else {
+            result.missing.push(format!("{}: no such gpiochip", id));
+            continue;
+        };
+        let Some(line) = chip.find_line(&exp.line) else {
+            result.missing.push(format!("{}: no such line", id));
+            continue;
+        };
+        match (&line.consumer, line.used) {
+            (Some(consumer), true) if *consumer == exp.consumer => result.present.push(id),
+            (Some(consumer), _) => result.mismatched.push(format!(
+                "{}: held by {} (expected {})",
+                id, consumer, exp.consumer
+            )),
+            (None, true) => result.mismatched.push(format!(
+                "{}: held by an unnamed consumer (expected {})",
+                id, exp.consumer
+            )),
+            (None, false) => result
+                .mismatched
+                .push(format!("{}: not requested (expected {})", id, exp.consumer)),
+        }
+    }
+    result
+}
+
+/// Enumerates the gpiochips and checks the expected lines.
+#[cfg(feature = "hardware")]
+pub fn validate_gpio(expected: &[ExpectedGpioLine]) -> Result<GpioValidationResult> {
+    Ok(validate_gpio_lines(&find_gpio_chips()?, expected))
+}
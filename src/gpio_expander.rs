@@ -0,0 +1,275 @@
+use crate::device::{Board, DeviceAddress, DeviceLink, Subsystem, TuxDevice};
+use crate::safety::{self, Access};
+use anyhow::Result;
+use gpiocdev::Request;
+use gpiocdev::line::Value;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Expander drivers/chips we know, with their line count.
+const KNOWN_EXPANDERS: [(&str, u32); 14] = [
+    ("pca9534", 8),
+    ("pca9535", 16),
+    ("pca9554", 8),
+    ("pca9555", 16),
+    ("pca9557", 8),
+    ("pca9575", 16),
+    ("pcal6416", 16),
+    ("pcal6524", 24),
+    ("pcal9555a", 16),
+    ("tca6408", 8),
+    ("tca6416", 16),
+    ("tca9539", 16),
+    ("mcp23008", 8),
+    ("mcp23017", 16),
+];
+
+/// Line count of a known expander chip, matched on the device name.
+pub fn known_line_count(device: &TuxDevice) -> Option<u32> {
+    KNOWN_EXPANDERS
+        .iter()
+        .find(|(name, _)| device.name.eq_ignore_ascii_case(name))
+        .map(|(_, lines)| *lines)
+}
+
+/// A gpiochip registered by a device driver.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpioChipInfo {
+    pub chip: u32, // N of /dev/gpiochipN
+    pub label: Option<String>,
+    pub base: Option<u32>, // Legacy sysfs number space; needs CONFIG_GPIO_SYSFS
+    pub ngpio: Option<u32>, // From the legacy attributes, else the character device
+}
+
+fn read_u32(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Finds the gpiochip a device registered, from its sysfs directory.
+///
+/// The chip shows up as a `gpiochipN` child; `gpio/gpiochip<base>` holds the legacy attributes.
+/// Without them, label and line count come from /dev/gpiochipN.
+pub fn find_gpiochip(device_dir: &Path) -> Option<GpioChipInfo> {
+    let chip = fs::read_dir(device_dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .find_map(|e| {
+            e.file_name()
+                .to_str()?
+                .strip_prefix("gpiochip")?
+                .parse::<u32>()
+                .ok()
+        })?;
+    let legacy = fs::read_dir(device_dir.join("gpio"))
+        .ok()
+        .and_then(|mut entries| entries.find_map(|e| e.ok()))
+        .map(|e| e.path());
+
+    // Kernels without CONFIG_GPIO_SYSFS, the default since it was deprecated, only have this
+    let chardev = || {
+        gpiocdev::chip::Chip::from_path(format!("/dev/gpiochip{}", chip))
+            .and_then(|c| c.info())
+            .ok()
+    };
+    let label = legacy
+        .as_ref()
+        .and_then(|dir| fs::read_to_string(dir.join("label")).ok())
+        .map(|l| l.trim().to_string());
+    let ngpio = legacy.as_ref().and_then(|dir| read_u32(&dir.join("ngpio")));
+    let info = (label.is_none() || ngpio.is_none()).then(chardev).flatten();
+    Some(GpioChipInfo {
+        chip,
+        label: label.or_else(|| info.as_ref().map(|i| i.label.clone())),
+        base: legacy.as_ref().and_then(|dir| read_u32(&dir.join("base"))),
+        ngpio: ngpio.or_else(|| info.map(|i| i.num_lines)),
+    })
+}
+
+/// Adds a gpiochip entry for every I2C device that registered one, linked to the I2C device.
+///
+/// Returns the number of links added.
+pub fn link_gpiochips(board: &mut Board) -> usize {
+    let mut found = Vec::new();
+    for device in board.devices().filter(|d| d.subsystem == Subsystem::I2c) {
+        if let Some(dir) = &device.sysfs_path
+            && let Some(info) = find_gpiochip(dir)
+        {
+            found.push((
+                device.address.clone(),
+                dir.join(format!("gpiochip{}", info.chip)),
+                info,
+            ));
+        }
+    }
+
+    let count = found.len();
+    for (from, chip_dir, info) in found {
+        let to = DeviceAddress::Gpio { chip: info.chip };
+        let mut chip = TuxDevice::new(
+            Subsystem::Gpio,
+            to.clone(),
+            &format!("gpiochip{}", info.chip),
+        );
+        chip.sysfs_path = Some(chip_dir);
+        if let Some(label) = info.label {
+            chip.attributes.insert("label".to_string(), label);
+        }
+        if let Some(base) = info.base {
+            chip.attributes.insert("base".to_string(), base.to_string());
+        }
+        if let Some(ngpio) = info.ngpio {
+            chip.attributes
+                .insert("ngpio".to_string(), ngpio.to_string());
+        }
+
+        let bus = board.bus_mut(Subsystem::Gpio, "gpio");
+        bus.devices.retain(|d| d.address != to);
+        bus.devices.push(chip);
+        board.links.retain(|l| !(l.from == from && l.to == to));
+        board.links.push(DeviceLink {
+            from,
+            to,
+            relation: "provides".to_string(),
+        });
+    }
+    count
+}
+
+/// An expected I2C GPIO expander.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedExpander {
+    pub bus: u32,
+    pub addr: u16,
+    pub base: Option<u32>,
+    pub lines: Option<u32>, // Defaults to the known line count of the chip
+    pub loopbacks: Vec<(u32, u32)>, // (output line, input line) pairs wired together on the fixture
+}
+
+/// The gpiochip linked to an I2C device in the Board model.
+pub fn linked_gpiochip<'a>(board: &'a Board, address: &DeviceAddress) -> Option<&'a TuxDevice> {
+    board
+        .linked_from(address)
+        .into_iter()
+        .find(|(relation, to)| *relation == "provides" && matches!(to, DeviceAddress::Gpio { .. }))
+        .and_then(|(_, to)| board.find_device(to))
+}
+
+/// Checks each expected expander has a gpiochip with the right base and line count.
+///
+/// Expects [`link_gpiochips`] to have run on the board.
+pub fn validate_expanders(board: &Board, expected: &[ExpectedExpander]) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    for exp in expected {
+        let address = DeviceAddress::I2c {
+            bus: exp.bus,
+            addr: exp.addr,
+        };
+        let Some(device) = board.find_device(&address) else {
+            mismatches.push(format!("{}: expander not found", address));
+            continue;
+        };
+        let Some(chip) = linked_gpiochip(board, &address) else {
+            mismatches.push(format!(
+                "{} ({}): no gpiochip registered (driver {})",
+                address,
+                device.name,
+                device.driver.as_deref().unwrap_or("none")
+            ));
+            continue;
+        };
+        let attr = |name: &str| {
+            chip.attributes
+                .get(name)
+                .and_then(|v| v.parse::<u32>().ok())
+        };
+
+        if let Some(base) = exp.base
+            && attr("base") != Some(base)
+        {
+            mismatches.push(format!(
+                "{}: {} base is {:?} (expected {})",
+                address,
+                chip.name,
+                attr("base"),
+                base
+            ));
+        }
+        if let Some(lines) = exp.lines.or_else(|| known_line_count(device)) {
+            match attr("ngpio") {
+                Some(ngpio) if ngpio == lines => {}
+                Some(ngpio) => mismatches.push(format!(
+                    "{}: {} has {} lines (expected {})",
+                    address, chip.name, ngpio, lines
+                )),
+                None => mismatches.push(format!(
+                    "{}: {} line count unavailable (expected {})",
+                    address, chip.name, lines
+                )),
+            }
+        }
+    }
+    mismatches
+}
+
+/// Drives `output` high and low and checks `input` follows, via the gpio character device.
+pub fn loopback_test(chip: u32, output: u32, input: u32) -> Result<()> {
+    safety::require(
+        Access::Write,
+        &format!("Driving gpiochip{} line {}", chip, output),
+    )?;
+    let path = format!("/dev/gpiochip{}", chip);
+    let out = Request::builder()
+        .on_chip(&path)
+        .with_consumer("tux-validation")
+        .with_line(output)
+        .as_output(Value::Inactive)
+        .request()?;
+    let inp = Request::builder()
+        .on_chip(&path)
+        .with_consumer("tux-validation")
+        .with_line(input)
+        .as_input()
+        .request()?;
+
+    for value in [Value::Active, Value::Inactive] {
+        out.set_lone_value(value)?;
+        // Expanders are slow; give the I2C write time to land
+        std::thread::sleep(Duration::from_millis(10));
+        let read = inp.lone_value()?;
+        if read != value {
+            anyhow::bail!(
+                "{} line {} -> {}: drove {:?}, read {:?}",
+                path,
+                output,
+                input,
+                value,
+                read
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Runs the loopback pairs of every expected expander through its linked gpiochip.
+pub fn run_loopbacks(board: &Board, expected: &[ExpectedExpander]) -> Vec<String> {
+    let mut failures = Vec::new();
+    for exp in expected.iter().filter(|e| !e.loopbacks.is_empty()) {
+        let address = DeviceAddress::I2c {
+            bus: exp.bus,
+            addr: exp.addr,
+        };
+        let Some(DeviceAddress::Gpio { chip }) =
+            linked_gpiochip(board, &address).map(|c| &c.address)
+        else {
+            failures.push(format!("{}: no gpiochip for loopback test", address));
+            continue;
+        };
+        for &(output, input) in &exp.loopbacks {
+            if let Err(e) = loopback_test(*chip, output, input) {
+                failures.push(format!("{}: {}", address, e));
+            }
+        }
+    }
+    failures
+}
@@ -0,0 +1,233 @@
+use anyhow::Result;
+use std::io::BufRead;
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
+
+/// An entry of /etc/passwd.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserEntry {
+    pub name: String,
+    pub uid: u32,
+    pub gid: u32,
+    pub home: String,
+    pub shell: String,
+}
+
+/// An entry of /etc/group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupEntry {
+    pub name: String,
+    pub gid: u32,
+    pub members: Vec<String>,
+}
+
+/// Name and password hash from /etc/shadow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowEntry {
+    pub name: String,
+    pub hash: String, // "" means no password; "!" / "*" prefixes mean locked
+}
+
+/// Splits colon-separated database lines, skipping blanks and comments.
+fn colon_records<R: BufRead>(reader: R, min_fields: usize) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    for line_result in reader.lines() {
+        let line = line_result?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<String> = line.split(':').map(|f| f.to_string()).collect();
+        if fields.len() >= min_fields {
+            records.push(fields);
+        }
+    }
+    Ok(records)
+}
+
+pub fn parse_passwd_from_reader<R: BufRead>(reader: R) -> Result<Vec<UserEntry>> {
+    Ok(colon_records(reader, 7)?
+        .into_iter()
+        .filter_map(|f| {
+            Some(UserEntry {
+                uid: f[2].parse().ok()?,
+                gid: f[3].parse().ok()?,
+                home: f[5].clone(),
+                shell: f[6].clone(),
+                name: f[0].clone(),
+            })
+        })
+        .collect())
+}
+
+pub fn parse_group_from_reader<R: BufRead>(reader: R) -> Result<Vec<GroupEntry>> {
+    Ok(colon_records(reader, 4)?
+        .into_iter()
+        .filter_map(|f| {
+            Some(GroupEntry {
+                gid: f[2].parse().ok()?,
+                members: f[3]
+                    .split(',')
+                    .filter(|m| !m.is_empty())
+                    .map(|m| m.to_string())
+                    .collect(),
+                name: f[0].clone(),
+            })
+        })
+        .collect())
+}
+
+pub fn parse_shadow_from_reader<R: BufRead>(reader: R) -> Result<Vec<ShadowEntry>> {
+    Ok(colon_records(reader, 2)?
+        .into_iter()
+        .map(|f| ShadowEntry {
+            name: f[0].clone(),
+            hash: f[1].clone(),
+        })
+        .collect())
+}
+
+fn open_reader(path: &str) -> Result<std::io::BufReader<std::fs::File>> {
+    Ok(std::io::BufReader::new(std::fs::File::open(path)?))
+}
+
+pub fn read_passwd() -> Result<Vec<UserEntry>> {
+    parse_passwd_from_reader(open_reader("/etc/passwd")?)
+}
+
+pub fn read_group() -> Result<Vec<GroupEntry>> {
+    parse_group_from_reader(open_reader("/etc/group")?)
+}
+
+/// Reads /etc/shadow. Requires root.
+pub fn read_shadow() -> Result<Vec<ShadowEntry>> {
+    parse_shadow_from_reader(open_reader("/etc/shadow")?)
+}
+
+/// An expected system user. `uid` and `shell` are checked only when set.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedUser {
+    pub name: String,
+    pub uid: Option<u32>,
+    pub shell: Option<String>,
+    pub groups: Vec<String>, // Supplementary groups the user must be a member of
+}
+
+/// Returns human-readable descriptions of missing users/groups and mismatches.
+pub fn validate_accounts(
+    users: &[UserEntry],
+    groups: &[GroupEntry],
+    expected_users: &[ExpectedUser],
+    expected_groups: &[String],
+) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    for name in expected_groups {
+        if !groups.iter().any(|g| &g.name == name) {
+            mismatches.push(format!("group {} missing", name));
+        }
+    }
+
+    for exp in expected_users {
+        let Some(user) = users.iter().find(|u| u.name == exp.name) else {
+            mismatches.push(format!("user {} missing", exp.name));
+            continue;
+        };
+        if let Some(uid) = exp.uid
+            && user.uid != uid
+        {
+            mismatches.push(format!(
+                "user {}: uid {} (expected {})",
+                user.name, user.uid, uid
+            ));
+        }
+        if let Some(shell) = &exp.shell
+            && &user.shell != shell
+        {
+            mismatches.push(format!(
+                "user {}: shell {} (expected {})",
+                user.name, user.shell, shell
+            ));
+        }
+        for group_name in &exp.groups {
+            let is_member = groups.iter().any(|g| {
+                &g.name == group_name && (g.gid == user.gid || g.members.contains(&user.name))
+            });
+            if !is_member {
+                mismatches.push(format!("user {}: not in group {}", user.name, group_name));
+            }
+        }
+    }
+    mismatches
+}
+
+/// Flags accounts with an empty password or a hash from the known-default list.
+///
+/// Locked accounts (`!`/`*` prefixed hashes) are never flagged.
+pub fn check_default_passwords(shadow: &[ShadowEntry], default_hashes: &[String]) -> Vec<String> {
+    let mut violations = Vec::new();
+    for entry in shadow {
+        if entry.hash.is_empty() {
+            violations.push(format!("user {}: no password set", entry.name));
+        } else if default_hashes.contains(&entry.hash) {
+            violations.push(format!("user {}: default password hash", entry.name));
+        }
+    }
+    violations
+}
+
+/// Expected ownership and permissions of a file or device node. Unset fields are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedPermissions {
+    pub path: PathBuf,
+    pub mode: Option<u32>, // Permission bits incl. setuid/setgid/sticky, e.g. 0o660
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+}
+
+/// Returns human-readable descriptions of every ownership/permission mismatch.
+pub fn check_permissions(expected: &[ExpectedPermissions]) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    for exp in expected {
+        let meta = match std::fs::metadata(&exp.path) {
+            Ok(meta) => meta,
+            Err(e) => {
+                mismatches.push(format!("{}: {}", exp.path.display(), e));
+                continue;
+            }
+        };
+        let mode = meta.mode() & 0o7777;
+        if let Some(expected_mode) = exp.mode
+            && mode != expected_mode
+        {
+            mismatches.push(format!(
+                "{}: mode {:04o} (expected {:04o})",
+                exp.path.display(),
+                mode,
+                expected_mode
+            ));
+        }
+        if let Some(uid) = exp.uid
+            && meta.uid() != uid
+        {
+            mismatches.push(format!(
+                "{}: owner uid {} (expected {})",
+                exp.path.display(),
+                meta.uid(),
+                uid
+            ));
+        }
+        if let Some(gid) = exp.gid
+            && meta.gid() != gid
+        {
+            mismatches.push(format!(
+                "{}: group gid {} (expected {})",
+                exp.path.display(),
+                meta.gid(),
+                gid
+            ));
+        }
+    }
+    mismatches
+}
@@ -0,0 +1,316 @@
+use crate::derating::Threshold;
+use crate::device::{DeviceAddress, Subsystem, TuxDevice};
+use crate::manifest::Manifest;
+use crate::measurement::{Measurement, Unit};
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Subsystem of the devices [`find_hwmon_devices`] returns, one per sensor chip.
+pub const SUBSYSTEM: Subsystem = Subsystem::Other("hwmon");
+
+/// Channel types, the unit the kernel reports their `_input` in, and the unit limits and
+/// device attributes use.
+const KINDS: [(&str, Unit, Unit); 5] = [
+    ("temp", Unit::MilliCelsius, Unit::Celsius),
+    ("in", Unit::Millivolt, Unit::Volt),
+    ("curr", Unit::Milliampere, Unit::Ampere),
+    ("power", Unit::Microwatt, Unit::Watt),
+    ("fan", Unit::Count, Unit::Count), // RPM
+];
+
+/// One sensor channel of a hwmon device, e.g. hwmon3/in1_input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HwmonChannel {
+    pub hwmon: String,              // e.g. "hwmon3"; numbering changes between boots
+    pub chip: String,               // The `name` attribute, e.g. "ina3221"
+    pub chip_label: Option<String>, // `label` of the chip's device-tree node
+    pub kind: &'static str,         // "temp", "in", "curr", "power" or "fan"
+    pub index: u32,
+    pub label: Option<String>, // `<kind><index>_label`, e.g. "VDD_GPU"
+}
+
+impl HwmonChannel {
+    /// Path of the reading relative to /sys/class/hwmon, e.g. "hwmon3/in1_input".
+    pub fn input(&self) -> String {
+        format!("{}/{}{}_input", self.hwmon, self.kind, self.index)
+    }
+
+    pub fn unit(&self) -> Unit {
+        KINDS
+            .iter()
+            .find(|(kind, ..)| *kind == self.kind)
+            .map(|(_, unit, _)| *unit)
+            .unwrap_or(Unit::Count)
+    }
+
+    /// Unit of the channel's limits: °C, V, A, W or RPM.
+    pub fn limit_unit(&self) -> Unit {
+        KINDS
+            .iter()
+            .find(|(kind, ..)| *kind == self.kind)
+            .map(|(.., unit)| *unit)
+            .unwrap_or(Unit::Count)
+    }
+
+    /// Whether a manifest reference names this channel; see [`resolve_channel_in`].
+    pub fn matches(&self, reference: &str) -> bool {
+        let (chip, channel) = match reference.split_once('/') {
+            Some((chip, channel)) => (Some(chip), channel),
+            None => (None, reference),
+        };
+        let chip_matches = chip.is_none_or(|c| {
+            c == self.hwmon || c == self.chip || self.chip_label.as_deref() == Some(c)
+        });
+        let raw = format!("{}{}", self.kind, self.index);
+        let channel_matches = self.label.as_deref() == Some(channel)
+            || (chip.is_some() && (channel == raw || channel == format!("{}_input", raw)));
+        chip_matches && channel_matches
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Lists the channels of every hwmon device.
+pub fn list_channels() -> Result<Vec<HwmonChannel>> {
+    list_channels_in(Path::new("/sys"))
+}
+
+/// Same as [`list_channels`], with an explicit sysfs root.
+pub fn list_channels_in(sys_root: &Path) -> Result<Vec<HwmonChannel>> {
+    let class = sys_root.join("class/hwmon");
+    let mut channels = Vec::new();
+    for entry in fs::read_dir(&class).map_err(|e| anyhow::anyhow!("{}: {}", class.display(), e))? {
+        let dir = entry?.path();
+        let hwmon = dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let chip = read_trimmed(&dir.join("name")).unwrap_or_default();
+        let chip_label = fs::read(dir.join("device/of_node/label")).ok().map(|l| {
+            String::from_utf8_lossy(&l)
+                .trim_end_matches('\0')
+                .to_string()
+        });
+        for file in fs::read_dir(&dir)? {
+            let name = file?.file_name().to_string_lossy().to_string();
+            let Some(channel) = name.strip_suffix("_input") else {
+                continue;
+            };
+            let Some((kind, index)) = KINDS.iter().find_map(|(kind, ..)| {
+                let index = channel.strip_prefix(kind)?.parse::<u32>().ok()?;
+                Some((*kind, index))
+            }) else {
+                continue;
+            };
+            channels.push(HwmonChannel {
+                hwmon: hwmon.clone(),
+                chip: chip.clone(),
+                chip_label: chip_label.clone(),
+                kind,
+                index,
+                label: read_trimmed(&dir.join(format!("{}_label", channel))),
+            });
+        }
+    }
+    channels.sort_by(|a, b| (&a.hwmon, a.kind, a.index).cmp(&(&b.hwmon, b.kind, b.index)));
+    Ok(channels)
+}
+
+/// Finds the channel a manifest reference names. References are stable across kernels:
+///
+/// - `VDD_GPU`: a channel label, on any chip
+/// - `ina3221/VDD_GPU`: a label on a chip, named by `name`, DT label or hwmonN
+/// - `cpu_thermal/temp1`: a raw channel of a chip
+///
+/// A label that several chips carry is an error rather than a guess.
+pub fn resolve_channel(reference: &str) -> Result<HwmonChannel> {
+    resolve_channel_in(Path::new("/sys"), reference)
+}
+
+/// Same as [`resolve_channel`], with an explicit sysfs root.
+pub fn resolve_channel_in(sys_root: &Path, reference: &str) -> Result<HwmonChannel> {
+    let channels = list_channels_in(sys_root)?;
+    let matching: Vec<&HwmonChannel> = channels.iter().filter(|c| c.matches(reference)).collect();
+    match matching.as_slice() {
+        [channel] => Ok((*channel).clone()),
+        [] => anyhow::bail!("no hwmon channel matches {}", reference),
+        _ => anyhow::bail!(
+            "hwmon channel {} is ambiguous: {}",
+            reference,
+            matching
+                .iter()
+                .map(|c| c.input())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Reads a channel by reference; the measurement's source is the reference.
+pub fn read_channel_in(sys_root: &Path, reference: &str) -> Result<Measurement> {
+    let channel = resolve_channel_in(sys_root, reference)?;
+    read_input_in(sys_root, &channel, reference)
+}
+
+fn read_input_in(sys_root: &Path, channel: &HwmonChannel, source: &str) -> Result<Measurement> {
+    let path = sys_root.join("class/hwmon").join(channel.input());
+    let text =
+        fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+    let value: f64 = text
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("{}: not a reading: {:?}", path.display(), text.trim()))?;
+    Ok(Measurement::new(value, channel.unit(), source))
+}
+
+/// A reading in the channel's limit unit, rounded to the kernel's resolution.
+fn in_limit_unit(channel: &HwmonChannel, measurement: &Measurement) -> f64 {
+    let value = measurement
+        .value_in(channel.limit_unit())
+        .unwrap_or(measurement.value);
+    (value * 1e6).round() / 1e6
+}
+
+/// Lists sensor chips as devices, e.g. "hwmon3" named "ina3221", with every reading in
+/// `attributes` named as in sysfs: `"in2" = "0.812 V"`, `"in2_label" = "VDD_GPU"`.
+/// Unreadable channels are left out.
+pub fn find_hwmon_devices() -> Result<Vec<TuxDevice>> {
+    find_hwmon_devices_in(Path::new("/sys"))
+}
+
+/// Same as [`find_hwmon_devices`], with an explicit sysfs root.
+pub fn find_hwmon_devices_in(sys_root: &Path) -> Result<Vec<TuxDevice>> {
+    let mut devices: Vec<TuxDevice> = Vec::new();
+    for channel in list_channels_in(sys_root)? {
+        if devices
+            .last()
+            .is_none_or(|d| d.address.to_string() != channel.hwmon)
+        {
+            let address = DeviceAddress::Other {
+                subsystem: "hwmon",
+                name: channel.hwmon.clone(),
+            };
+            let mut device = TuxDevice::new(SUBSYSTEM, address, &channel.chip);
+            device.sysfs_path = Some(sys_root.join("class/hwmon").join(&channel.hwmon));
+            if let Some(label) = &channel.chip_label {
+                device.attributes.insert("label".to_string(), label.clone());
+            }
+            devices.push(device);
+        }
+        let Ok(measurement) = read_input_in(sys_root, &channel, &channel.input()) else {
+            continue;
+        };
+        let value = in_limit_unit(&channel, &measurement);
+        let reading = Measurement::new(value, channel.limit_unit(), &channel.input());
+        let raw = format!("{}{}", channel.kind, channel.index);
+        if let Some(device) = devices.last_mut() {
+            if let Some(label) = &channel.label {
+                device
+                    .attributes
+                    .insert(format!("{}_label", raw), label.clone());
+            }
+            device.attributes.insert(raw, reading.to_string());
+        }
+    }
+    Ok(devices)
+}
+
+/// Bounds on one channel from the `[hwmon]` section, e.g.
+/// `{ channel = "VDD_GPU", min = 0.75, max = 0.9 }`, in the channel's limit unit (°C, V, A,
+/// W or RPM). Either bound may be derated by temperature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HwmonLimit {
+    pub channel: String, // A reference, see [`resolve_channel`]
+    pub min: Option<Threshold>,
+    pub max: Option<Threshold>,
+}
+
+impl HwmonLimit {
+    /// The `limits` of the `[hwmon]` section; none without one.
+    pub fn from_manifest(manifest: &Manifest) -> Result<Vec<HwmonLimit>> {
+        let Some(section) = manifest.sections.get("hwmon") else {
+            return Ok(Vec::new());
+        };
+        let limits = match &section["limits"] {
+            Value::Null => return Ok(Vec::new()),
+            Value::Array(limits) => limits,
+            _ => anyhow::bail!("hwmon.limits must be a list"),
+        };
+        limits
+            .iter()
+            .enumerate()
+            .map(|(i, limit)| {
+                let Some(channel) = limit["channel"].as_str() else {
+                    anyhow::bail!("hwmon limit {}: missing `channel`", i);
+                };
+                let bound = |key: &str| -> Result<Option<Threshold>> {
+                    match &limit[key] {
+                        Value::Null => Ok(None),
+                        value => Threshold::from_value(value)
+                            .map(Some)
+                            .map_err(|e| anyhow::anyhow!("hwmon {}.{}: {}", channel, key, e)),
+                    }
+                };
+                let (min, max) = (bound("min")?, bound("max")?);
+                if min.is_none() && max.is_none() {
+                    anyhow::bail!("hwmon {}: needs `min`, `max` or both", channel);
+                }
+                Ok(HwmonLimit {
+                    channel: channel.to_string(),
+                    min,
+                    max,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Returns human-readable descriptions of every reading out of its limits, or missing.
+pub fn validate_limits(limits: &[HwmonLimit]) -> Vec<String> {
+    validate_limits_in(Path::new("/sys"), limits)
+}
+
+/// Same as [`validate_limits`], with an explicit sysfs root.
+pub fn validate_limits_in(sys_root: &Path, limits: &[HwmonLimit]) -> Vec<String> {
+    let mut problems = Vec::new();
+    for limit in limits {
+        let reading = resolve_channel_in(sys_root, &limit.channel).and_then(|channel| {
+            let measurement = read_input_in(sys_root, &channel, &limit.channel)?;
+            Ok((in_limit_unit(&channel, &measurement), channel.limit_unit()))
+        });
+        let (value, unit) = match reading {
+            Ok(reading) => reading,
+            Err(e) => {
+                problems.push(format!("{}: {}", limit.channel, e));
+                continue;
+            }
+        };
+        let shown = |v: f64| Measurement::new(v, unit, &limit.channel).to_string();
+        let bounds = [("min", "below", &limit.min), ("max", "above", &limit.max)];
+        for (name, side, bound) in bounds {
+            let Some(bound) = bound else {
+                continue;
+            };
+            match bound.resolve_in(sys_root) {
+                Err(e) => problems.push(format!("{}: {}: {}", limit.channel, name, e)),
+                Ok(bound)
+                    if (name == "min" && value < bound) || (name == "max" && value > bound) =>
+                {
+                    problems.push(format!(
+                        "{}: {} {} {}",
+                        limit.channel,
+                        shown(value),
+                        side,
+                        shown(bound)
+                    ))
+                }
+                Ok(_) => {}
+            }
+        }
+    }
+    problems
+}
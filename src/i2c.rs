@@ -1,9 +1,21 @@
+use crate::device::{DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use crate::error::{self, Error};
+use crate::lock::{Resource, ResourceLock};
+use crate::manifest::{Manifest, RegisterCheck, RegisterMismatch};
+use crate::messages::Message;
+use crate::report::{TestCase, TestSuite};
+use crate::safety::{self, Access};
 use anyhow::Result;
 use i2cdev::core::*;
 use i2cdev::linux::{LinuxI2CDevice, LinuxI2CError};
 use nix::errno::Errno;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
 use std::fs;
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 /// Finds all available i2c devices in /dev.
 ///
@@ -24,85 +36,926 @@ pub fn discover_buses() -> Result<Vec<PathBuf>> {
         p.file_name()
             .and_then(|n| n.to_str())
             .and_then(|s| s.strip_prefix("i2c-"))
-            .and_then(|x| x.parse::<u8>().ok())
+            .and_then(|x| x.parse::<u32>().ok())
             .unwrap_or(0)
     });
     Ok(buses)
 }
 
+/// Addresses a full scan sweeps; 0x00-0x07 and 0x78-0x7f are reserved.
+pub const DEFAULT_ADDRESSES: RangeInclusive<u16> = 0x08..=0x77;
+
+/// Restricts a range to [`DEFAULT_ADDRESSES`], so reserved addresses are never probed.
+pub fn clamp_addresses(range: RangeInclusive<u16>) -> RangeInclusive<u16> {
+    let (start, end) = (*DEFAULT_ADDRESSES.start(), *DEFAULT_ADDRESSES.end());
+    (*range.start()).max(start)..=(*range.end()).min(end)
+}
+
 pub trait I2cScanner {
-    fn scan_hw_probe(&self) -> Result<(Vec<u16>, Vec<u16>)>; // TODO: add address range as parameter
-    fn scan_sysfs(&self) -> Result<Vec<u16>>; // TODO: add address range as parameter
+    /// The addresses the scans cover.
+    fn addresses(&self) -> RangeInclusive<u16> {
+        DEFAULT_ADDRESSES
+    }
+
+    /// Returns (unbound, bound) addresses that responded within the scanner's range.
+    fn scan_hw_probe(&self) -> Result<(Vec<u16>, Vec<u16>)>;
+    /// Returns the addresses within the scanner's range the kernel has devices for.
+    fn scan_sysfs(&self) -> Result<Vec<u16>>;
+
+    /// Hardware probe with per-address error statistics, for [`assess_health`].
+    ///
+    /// Empty if the scanner can't provide them; callers then fall back to `scan_hw_probe`.
+    fn probe_stats(&self) -> Result<Vec<ProbeStat>> {
+        Ok(Vec::new())
+    }
+}
+
+/// SMBus transaction used to probe an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeMode {
+    QuickWrite, // SMBus quick write, as `i2cdetect -q`
+    ReadByte,   // SMBus receive byte, as `i2cdetect -r`
+    Skip,       // Never addressed; reported absent
+}
+
+impl ProbeMode {
+    /// Parses the manifest name: "quick_write", "read_byte" or "skip".
+    pub fn from_name(name: &str) -> Option<ProbeMode> {
+        match name {
+            "quick_write" => Some(ProbeMode::QuickWrite),
+            "read_byte" => Some(ProbeMode::ReadByte),
+            "skip" => Some(ProbeMode::Skip),
+            _ => None,
+        }
+    }
+}
+
+/// Address classes where a quick write is unsafe: those `i2cdetect` knows, and RTCs.
+pub const ADDRESS_CLASSES: [(RangeInclusive<u16>, &str, ProbeMode); 3] = [
+    // Some write-protect latches of SPD EEPROMs set on any write
+    (0x30..=0x37, "EEPROM write protection", ProbeMode::ReadByte),
+    // A quick write 0 is a data write to e.g. the 24RF08, corrupting it
+    (0x50..=0x5f, "EEPROM/flash", ProbeMode::ReadByte),
+    // A bare write sets the register pointer of e.g. the DS1307 and has been seen to
+    // corrupt its time; IMUs sharing these addresses answer a receive byte as well
+    (0x68..=0x6f, "RTC", ProbeMode::ReadByte),
+];
+
+/// Probe mode per address range. Later ranges override earlier ones.
+///
+/// The default is receive byte for [`ADDRESS_CLASSES`] and quick write elsewhere, as
+/// `i2cdetect` does plus the RTC range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbeModes {
+    ranges: Vec<(RangeInclusive<u16>, ProbeMode)>,
+}
+
+impl Default for ProbeModes {
+    fn default() -> Self {
+        ADDRESS_CLASSES.into_iter().fold(
+            ProbeModes::uniform(ProbeMode::QuickWrite),
+            |modes, (range, _, mode)| modes.with_range(range, mode),
+        )
+    }
+}
+
+impl ProbeModes {
+    /// The same mode for every address.
+    pub fn uniform(mode: ProbeMode) -> ProbeModes {
+        ProbeModes {
+            ranges: vec![(DEFAULT_ADDRESSES, mode)],
+        }
+    }
+
+    /// Overrides the mode for a range.
+    pub fn with_range(mut self, range: RangeInclusive<u16>, mode: ProbeMode) -> ProbeModes {
+        self.ranges.push((range, mode));
+        self
+    }
+
+    pub fn mode_for(&self, addr: u16) -> ProbeMode {
+        self.ranges
+            .iter()
+            .rev()
+            .find(|(range, _)| range.contains(&addr))
+            .map(|(_, mode)| *mode)
+            .unwrap_or(ProbeMode::QuickWrite)
+    }
+
+    /// The defaults with the overrides of a manifest's `[i2c_probe]` section that apply to
+    /// `bus_id`, e.g. `ranges = [{ start = 0x68, end = 0x68, mode = "skip" }]`. Ranges may
+    /// name a `bus`; those without apply to every bus.
+    pub fn from_manifest(manifest: &Manifest, bus_id: u32) -> Result<ProbeModes> {
+        let mut modes = ProbeModes::default();
+        let Some(ranges) = manifest
+            .sections
+            .get("i2c_probe")
+            .map(|s| &s["ranges"])
+            .filter(|r| !r.is_null())
+        else {
+            return Ok(modes);
+        };
+        let Some(ranges) = ranges.as_array() else {
+            anyhow::bail!("i2c_probe.ranges must be an array of tables");
+        };
+        for (i, range) in ranges.iter().enumerate() {
+            let int = |key: &str| -> Result<u16> {
+                range[key]
+                    .as_u64()
+                    .and_then(|v| u16::try_from(v).ok())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("i2c_probe range {}: missing address `{}`", i, key)
+                    })
+            };
+            let (start, end) = (int("start")?, int("end")?);
+            let mode = range["mode"]
+                .as_str()
+                .and_then(ProbeMode::from_name)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "i2c_probe range {}: `mode` must be quick_write, read_byte or skip",
+                        i
+                    )
+                })?;
+            if range["bus"]
+                .as_u64()
+                .is_none_or(|bus| bus == u64::from(bus_id))
+            {
+                modes = modes.with_range(start..=end, mode);
+            }
+        }
+        Ok(modes)
+    }
+}
+
+/// `I2C_TIMEOUT` ioctl of i2c-dev; its argument is in units of 10 ms.
+const I2C_TIMEOUT: libc::c_ulong = 0x0702;
+
+/// How persistently a hardware probe asks an address before declaring it absent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProbePolicy {
+    pub retries: u32,                           // Further attempts after a NACK or error
+    pub retry_delay: Duration,                  // Before each retry, e.g. for a part waking up
+    pub per_transfer_timeout: Option<Duration>, // Adapter timeout; the driver's default if None
+}
+
+impl Default for ProbePolicy {
+    /// One attempt, as i2cdetect does.
+    fn default() -> Self {
+        ProbePolicy {
+            retries: 0,
+            retry_delay: Duration::from_millis(10),
+            per_transfer_timeout: None,
+        }
+    }
+}
+
+impl ProbePolicy {
+    /// Reads `retries`, `retry_delay` and `per_transfer_timeout` of the manifest's
+    /// `[i2c_probe]` section; unset keys keep their defaults.
+    pub fn from_manifest(manifest: &Manifest) -> Result<ProbePolicy> {
+        let mut policy = ProbePolicy::default();
+        let Some(section) = manifest.sections.get("i2c_probe") else {
+            return Ok(policy);
+        };
+        let retries = &section["retries"];
+        if !retries.is_null() {
+            policy.retries = retries
+                .as_u64()
+                .and_then(|r| u32::try_from(r).ok())
+                .ok_or_else(|| anyhow::anyhow!("i2c_probe.retries must be a count"))?;
+        }
+        let duration = |key: &str| -> Result<Option<Duration>> {
+            match &section[key] {
+                Value::Null => Ok(None),
+                value => crate::manifest::parse_duration(value)
+                    .map(Some)
+                    .map_err(|e| anyhow::anyhow!("i2c_probe.{}: {}", key, e)),
+            }
+        };
+        if let Some(delay) = duration("retry_delay")? {
+            policy.retry_delay = delay;
+        }
+        policy.per_transfer_timeout = duration("per_transfer_timeout")?;
+        Ok(policy)
+    }
+
+    /// Runs `transaction` until it succeeds or the retries are used up, waiting
+    /// `retry_delay` before each retry. Returns whether it succeeded.
+    pub fn attempt(&self, mut transaction: impl FnMut() -> bool) -> bool {
+        for attempt in 0..=self.retries {
+            if attempt > 0 {
+                std::thread::sleep(self.retry_delay);
+            }
+            if transaction() {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Sets the adapter timeout of an open device, rounded up to the 10 ms the kernel counts in.
+    fn apply(&self, dev: &LinuxI2CDevice) -> Result<()> {
+        use std::os::unix::io::AsRawFd;
+        let Some(timeout) = self.per_transfer_timeout else {
+            return Ok(());
+        };
+        let ticks = timeout.as_millis().div_ceil(10).max(1) as libc::c_ulong;
+        if unsafe { libc::ioctl(dev.as_raw_fd(), I2C_TIMEOUT as _, ticks) } < 0 {
+            anyhow::bail!("I2C_TIMEOUT: {}", std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+fn probe_transaction(dev: &mut LinuxI2CDevice, mode: ProbeMode) -> Result<(), LinuxI2CError> {
+    match mode {
+        ProbeMode::QuickWrite => dev.smbus_write_quick(false),
+        ProbeMode::ReadByte => dev.smbus_read_byte().map(|_| ()),
+        ProbeMode::Skip => unreachable!("skipped addresses are never addressed"),
+    }
+}
+
+/// Result of probing a single address.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProbeOutcome {
+    Absent,
+    Unbound, // ACKed the probe transaction
+    Bound,   // EBUSY: a kernel driver owns the address
+}
+
+/// Why a bus node can't be opened, for errors other than a driver owning the address.
+fn open_error(bus_id: u32, bus_path: &str, addr: u16, e: LinuxI2CError) -> Error {
+    match e {
+        LinuxI2CError::Io(io_err) if io_err.kind() == std::io::ErrorKind::NotFound => {
+            Error::BusNotFound {
+                bus: format!("i2c-{}", bus_id),
+                path: bus_path.to_string(),
+            }
+        }
+        LinuxI2CError::Io(io_err) if io_err.kind() == std::io::ErrorKind::PermissionDenied => {
+            Error::PermissionDenied {
+                path: PathBuf::from(bus_path),
+            }
+        }
+        e => Error::ProbeFailed {
+            bus: format!("i2c-{}", bus_id),
+            addr,
+            message: e.to_string(),
+        },
+    }
+}
+
+/// Probes one address with a quick write or a receive byte.
+pub fn probe_address(bus_id: u32, addr: u16, mode: ProbeMode) -> error::Result<ProbeOutcome> {
+    probe_address_with(bus_id, addr, mode, &ProbePolicy::default())
+}
+
+/// Same as [`probe_address`], retrying and timing out transactions as `policy` says.
+pub fn probe_address_with(
+    bus_id: u32,
+    addr: u16,
+    mode: ProbeMode,
+    policy: &ProbePolicy,
+) -> error::Result<ProbeOutcome> {
+    if mode == ProbeMode::Skip {
+        return Ok(ProbeOutcome::Absent);
+    }
+    safety::require(Access::BusTraffic, &format!("Probing i2c-{}", bus_id))?;
+    let bus_path = format!("/dev/i2c-{}", bus_id);
+    match LinuxI2CDevice::new(&bus_path, addr) {
+        Ok(mut dev) => {
+            policy.apply(&dev).map_err(|e| Error::ProbeFailed {
+                bus: format!("i2c-{}", bus_id),
+                addr,
+                message: e.to_string(),
+            })?;
+            if policy.attempt(|| probe_transaction(&mut dev, mode).is_ok()) {
+                tracing::trace!(bus = bus_id, addr, ?mode, "probe ACKed");
+                return Ok(ProbeOutcome::Unbound);
+            }
+        }
+        Err(e) => match e {
+            LinuxI2CError::Errno(code) => {
+                let errno = Errno::from_i32(code);
+                if errno == Errno::EBUSY {
+                    tracing::trace!(bus = bus_id, addr, "address owned by a driver");
+                    return Ok(ProbeOutcome::Bound);
+                } else {
+                    tracing::warn!(bus = bus_id, addr, %errno, "unexpected errno opening address");
+                }
+            }
+            LinuxI2CError::Io(io_err) => match io_err.kind() {
+                std::io::ErrorKind::NotFound | std::io::ErrorKind::PermissionDenied => {
+                    return Err(open_error(
+                        bus_id,
+                        &bus_path,
+                        addr,
+                        LinuxI2CError::Io(io_err),
+                    ));
+                }
+                _ => {
+                    tracing::warn!(bus = bus_id, addr, error = %io_err, "I/O error opening address");
+                }
+            },
+        },
+    }
+    Ok(ProbeOutcome::Absent)
+}
+
+/// Reads identity registers, e.g. WHO_AM_I, of the device at `addr` and returns those that
+/// don't match.
+///
+/// Reads even if a driver owns the address, as `i2cget -f` does; the bus lock keeps other
+/// probes of this process family off the bus meanwhile.
+pub fn verify_registers(
+    bus_id: u32,
+    addr: u16,
+    checks: &[RegisterCheck],
+) -> Result<Vec<RegisterMismatch>> {
+    safety::require(
+        Access::BusTraffic,
+        &format!("Reading registers on i2c-{}", bus_id),
+    )?;
+    let _lock = ResourceLock::acquire(Resource::I2cBus(bus_id))?;
+    let bus_path = format!("/dev/i2c-{}", bus_id);
+    let mut dev = unsafe { LinuxI2CDevice::force_new(&bus_path, addr) }
+        .map_err(|e| open_error(bus_id, &bus_path, addr, e))?;
+    crate::manifest::verify_registers_with(checks, |register| {
+        dev.smbus_read_byte_data(register)
+            .map_err(|e| anyhow::anyhow!("0x{:02x} register 0x{:02x}: {}", addr, register, e))
+    })
+}
+
+/// Attempts per address when collecting [`ProbeStat`]s.
+pub const HEALTH_PROBE_ATTEMPTS: u32 = 3;
+
+/// Outcome and error history of probing one address.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbeStat {
+    pub addr: u16,
+    pub outcome: ProbeOutcome,
+    pub attempts: u32, // Bus transactions; 0 if a driver owns the address or it's skipped
+    pub errnos: Vec<i32>, // Electrical errors of the failed attempts, in order
+    pub latency: Duration, // Of the final attempt
+}
+
+/// Whether an errno points at the bus itself rather than an absent device.
+///
+/// A NACK (ENXIO/EREMOTEIO) is the normal answer of an empty address; lost arbitration,
+/// timeouts and protocol errors on a quiet bus usually mean slow edges, i.e. weak pull-ups.
+pub fn is_electrical_errno(errno: i32) -> bool {
+    [Errno::EAGAIN, Errno::ETIMEDOUT, Errno::EIO, Errno::EPROTO]
+        .iter()
+        .any(|e| *e as i32 == errno)
+}
+
+/// Probes one address like [`probe_address_with`], timing the transactions and keeping
+/// their electrical errors.
+pub fn probe_address_stats(
+    bus_id: u32,
+    addr: u16,
+    mode: ProbeMode,
+    policy: &ProbePolicy,
+) -> error::Result<ProbeStat> {
+    let bus_path = format!("/dev/i2c-{}", bus_id);
+    if mode == ProbeMode::Skip {
+        return Ok(ProbeStat {
+            addr,
+            outcome: ProbeOutcome::Absent,
+            attempts: 0,
+            errnos: Vec::new(),
+            latency: Duration::ZERO,
+        });
+    }
+    safety::require(Access::BusTraffic, &format!("Probing i2c-{}", bus_id))?;
+    let mut dev = match LinuxI2CDevice::new(&bus_path, addr) {
+        Ok(dev) => dev,
+        Err(LinuxI2CError::Errno(code)) if Errno::from_i32(code) == Errno::EBUSY => {
+            return Ok(ProbeStat {
+                addr,
+                outcome: ProbeOutcome::Bound,
+                attempts: 0,
+                errnos: Vec::new(),
+                latency: Duration::ZERO,
+            });
+        }
+        Err(e) => return Err(open_error(bus_id, &bus_path, addr, e)),
+    };
+    policy.apply(&dev).map_err(|e| Error::ProbeFailed {
+        bus: format!("i2c-{}", bus_id),
+        addr,
+        message: e.to_string(),
+    })?;
+    Ok(probe_stat_with(addr, policy, || {
+        match probe_transaction(&mut dev, mode) {
+            Ok(()) => Ok(()),
+            Err(LinuxI2CError::Errno(code)) => Err(code),
+            Err(LinuxI2CError::Io(io_err)) => Err(io_err.raw_os_error().unwrap_or(0)),
+        }
+    }))
+}
+
+/// Runs the probe `transaction` of `addr`, which fails with an errno, as
+/// [`probe_address_stats`] does: NACKs are retried `policy.retries` times, electrical
+/// errors at least [`HEALTH_PROBE_ATTEMPTS`] times in all, `policy.retry_delay` apart.
+pub fn probe_stat_with(
+    addr: u16,
+    policy: &ProbePolicy,
+    mut transaction: impl FnMut() -> std::result::Result<(), i32>,
+) -> ProbeStat {
+    let mut stat = ProbeStat {
+        addr,
+        outcome: ProbeOutcome::Absent,
+        attempts: 0,
+        errnos: Vec::new(),
+        latency: Duration::ZERO,
+    };
+    let attempts = policy.retries + 1;
+    let electrical_attempts = attempts.max(HEALTH_PROBE_ATTEMPTS);
+    for attempt in 1..=electrical_attempts {
+        if attempt > 1 {
+            std::thread::sleep(policy.retry_delay);
+        }
+        let start = Instant::now();
+        let result = transaction();
+        stat.latency = start.elapsed();
+        stat.attempts = attempt;
+        let errno = match result {
+            Ok(()) => {
+                stat.outcome = ProbeOutcome::Unbound;
+                break;
+            }
+            Err(errno) => errno,
+        };
+        tracing::trace!(addr, attempt, errno, "probe attempt failed");
+        if is_electrical_errno(errno) {
+            stat.errnos.push(errno);
+        } else if attempt >= attempts {
+            break; // Plain NACK
+        }
+    }
+    stat
+}
+
+/// Electrical health verdict for a bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BusHealth {
+    Good,
+    Marginal,
+    Bad, // Weak or missing pull-ups suspected
+}
+
+impl BusHealth {
+    pub fn name(self) -> &'static str {
+        match self {
+            BusHealth::Good => "good",
+            BusHealth::Marginal => "marginal",
+            BusHealth::Bad => "bad",
+        }
+    }
+}
+
+/// Aggregated probe statistics of one bus and the verdict derived from them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthReport {
+    pub health: BusHealth,
+    pub transactions: u32,
+    pub errors: BTreeMap<i32, u32>, // Electrical errno -> count
+    pub retried: Vec<u16>,          // Addresses that needed more than one attempt
+    pub latency_median: Duration,
+    pub latency_max: Duration,
+    pub reasons: Vec<String>,
+}
+
+impl HealthReport {
+    /// Share of transactions that failed with an electrical error.
+    pub fn error_rate(&self) -> f64 {
+        if self.transactions == 0 {
+            return 0.0;
+        }
+        self.errors.values().sum::<u32>() as f64 / self.transactions as f64
+    }
+
+    /// Records the verdict in bus metadata under `health*` keys.
+    pub fn annotate(&self, metadata: &mut BTreeMap<String, String>) {
+        let errors: Vec<String> = self
+            .errors
+            .iter()
+            .map(|(errno, count)| format!("{:?}={}", Errno::from_i32(*errno), count))
+            .collect();
+        let retried: Vec<String> = self
+            .retried
+            .iter()
+            .map(|a| format!("0x{:02x}", a))
+            .collect();
+        let entries = [
+            ("health", self.health.name().to_string()),
+            ("health_error_rate", format!("{:.3}", self.error_rate())),
+            ("health_errors", errors.join(",")),
+            ("health_retried", retried.join(",")),
+            (
+                "health_latency_median_us",
+                self.latency_median.as_micros().to_string(),
+            ),
+            (
+                "health_latency_max_us",
+                self.latency_max.as_micros().to_string(),
+            ),
+            ("health_reasons", self.reasons.join("; ")),
+        ];
+        for (key, value) in entries {
+            metadata.insert(key.to_string(), value);
+        }
+    }
+}
+
+/// Bad from 5% electrical errors or a timeout on several addresses.
+const BAD_ERROR_RATE: f64 = 0.05;
+const BAD_TIMEOUT_ADDRS: usize = 3;
+/// A transaction this many times slower than the median counts as a stall.
+const LATENCY_OUTLIER_FACTOR: u32 = 10;
+
+/// Scores a bus from its probe statistics.
+///
+/// Any electrical error, retry or latency stall makes a bus marginal; a high error rate or
+/// repeated timeouts make it bad.
+pub fn assess_health(stats: &[ProbeStat]) -> HealthReport {
+    let mut errors = BTreeMap::new();
+    for errno in stats.iter().flat_map(|s| &s.errnos) {
+        *errors.entry(*errno).or_insert(0) += 1;
+    }
+    let transactions = stats.iter().map(|s| s.attempts).sum();
+    let retried: Vec<u16> = stats
+        .iter()
+        .filter(|s| s.attempts > 1)
+        .map(|s| s.addr)
+        .collect();
+    let mut latencies: Vec<Duration> = stats
+        .iter()
+        .filter(|s| s.attempts > 0)
+        .map(|s| s.latency)
+        .collect();
+    latencies.sort();
+    let latency_median = latencies
+        .get(latencies.len() / 2)
+        .copied()
+        .unwrap_or_default();
+    let latency_max = latencies.last().copied().unwrap_or_default();
+
+    let mut report = HealthReport {
+        health: BusHealth::Good,
+        transactions,
+        errors,
+        retried,
+        latency_median,
+        latency_max,
+        reasons: Vec::new(),
+    };
+    let timeouts = stats
+        .iter()
+        .filter(|s| s.errnos.contains(&(Errno::ETIMEDOUT as i32)))
+        .count();
+    if report.error_rate() >= BAD_ERROR_RATE {
+        report.health = BusHealth::Bad;
+        report.reasons.push(format!(
+            "{:.1}% of transactions hit electrical errors",
+            report.error_rate() * 100.0
+        ));
+    } else if !report.errors.is_empty() {
+        report.health = BusHealth::Marginal;
+        report.reasons.push(format!(
+            "{} transient electrical errors",
+            report.errors.values().sum::<u32>()
+        ));
+    }
+    if timeouts >= BAD_TIMEOUT_ADDRS {
+        report.health = BusHealth::Bad;
+        report
+            .reasons
+            .push(format!("timeouts on {} addresses", timeouts));
+    }
+    if !latency_median.is_zero() && latency_max > latency_median * LATENCY_OUTLIER_FACTOR {
+        report.health = report.health.max(BusHealth::Marginal);
+        report.reasons.push(format!(
+            "slowest transaction {}us vs median {}us",
+            latency_max.as_micros(),
+            latency_median.as_micros()
+        ));
+    }
+    report
+}
+
+/// Pacing and checkpointing for probing live, shared buses.
+#[derive(Debug, Clone)]
+pub struct PacedProbeConfig {
+    pub delay: Duration, // Between transactions
+    pub checkpoint: Option<PathBuf>,
+    pub addresses: RangeInclusive<u16>, // Clamped to DEFAULT_ADDRESSES
+    pub modes: ProbeModes,
+    pub policy: ProbePolicy,
+}
+
+/// Progress of a paced probe, saved after every address.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProbeCheckpoint {
+    pub bus_id: u32,
+    pub next_addr: u16,
+    pub unbound: Vec<u16>,
+    pub bound: Vec<u16>,
+}
+
+impl ProbeCheckpoint {
+    pub fn load(path: &Path) -> Result<ProbeCheckpoint> {
+        let value: Value = serde_json::from_slice(&fs::read(path)?)?;
+        let addrs = |key: &str| -> Vec<u16> {
+            value[key]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_u64().and_then(|a| u16::try_from(a).ok()))
+                .collect()
+        };
+        let (Some(bus_id), Some(next_addr)) = (
+            value["bus_id"].as_u64().and_then(|b| u32::try_from(b).ok()),
+            value["next_addr"]
+                .as_u64()
+                .and_then(|a| u16::try_from(a).ok()),
+        ) else {
+            anyhow::bail!("{}: malformed probe checkpoint", path.display());
+        };
+        Ok(ProbeCheckpoint {
+            bus_id,
+            next_addr,
+            unbound: addrs("unbound"),
+            bound: addrs("bound"),
+        })
+    }
+
+    /// Writes via a temporary file so an interruption never leaves a truncated checkpoint.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let value = json!({
+            "bus_id": self.bus_id,
+            "next_addr": self.next_addr,
+            "unbound": self.unbound,
+            "bound": self.bound,
+        });
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(&value)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+/// Probes the configured addresses one at a time with `probe`, resuming from the checkpoint if
+/// one exists for this bus. The checkpoint is removed once the scan completes.
+pub fn paced_probe(
+    bus_id: u32,
+    config: &PacedProbeConfig,
+    mut probe: impl FnMut(u16) -> Result<ProbeOutcome>,
+) -> Result<(Vec<u16>, Vec<u16>)> {
+    let mut state = match &config.checkpoint {
+        Some(path) if path.exists() => {
+            let saved = ProbeCheckpoint::load(path)?;
+            if saved.bus_id != bus_id {
+                anyhow::bail!(
+                    "{}: checkpoint is for bus {}, not {}",
+                    path.display(),
+                    saved.bus_id,
+                    bus_id
+                );
+            }
+            saved
+        }
+        _ => ProbeCheckpoint {
+            bus_id,
+            next_addr: *config.addresses.start(),
+            ..Default::default()
+        },
+    };
+
+    let addresses = clamp_addresses(config.addresses.clone());
+    let last = *addresses.end();
+    for addr in state.next_addr.max(*addresses.start())..=last {
+        match probe(addr)? {
+            ProbeOutcome::Unbound => state.unbound.push(addr),
+            ProbeOutcome::Bound => state.bound.push(addr),
+            ProbeOutcome::Absent => {}
+        }
+        state.next_addr = addr + 1;
+        if let Some(path) = &config.checkpoint {
+            state.save(path)?;
+        }
+        if addr < last {
+            std::thread::sleep(config.delay);
+        }
+    }
+    if let Some(path) = &config.checkpoint {
+        let _ = fs::remove_file(path);
+    }
+    Ok((state.unbound, state.bound))
+}
+
+/// Same as [`I2cScanner::scan_hw_probe`], but paced and resumable.
+pub fn scan_hw_probe_paced(bus_id: u32, config: &PacedProbeConfig) -> Result<(Vec<u16>, Vec<u16>)> {
+    let _lock = ResourceLock::acquire(Resource::I2cBus(bus_id))?;
+    paced_probe(bus_id, config, |addr| {
+        Ok(probe_address_with(
+            bus_id,
+            addr,
+            config.modes.mode_for(addr),
+            &config.policy,
+        )?)
+    })
 }
 
 /// A specific I2C bus scanner.
+#[derive(Debug, Clone)]
 pub struct LinuxI2cScanner {
-    pub bus_id: u8,
+    pub bus_id: u32,
+    pub addresses: RangeInclusive<u16>, // Clamped to DEFAULT_ADDRESSES
+    pub modes: ProbeModes,
+    pub policy: ProbePolicy,
+    pub sys_root: PathBuf, // Where `scan_sysfs` looks, /sys unless testing off-target
+}
+
+impl LinuxI2cScanner {
+    /// Scans the full [`DEFAULT_ADDRESSES`] range.
+    pub fn new(bus_id: u32) -> LinuxI2cScanner {
+        LinuxI2cScanner {
+            bus_id,
+            addresses: DEFAULT_ADDRESSES,
+            modes: ProbeModes::default(),
+            policy: ProbePolicy::default(),
+            sys_root: PathBuf::from("/sys"),
+        }
+    }
+
+    /// Lists kernel-known devices from this sysfs root instead, e.g. a
+    /// [`crate::testing::FakeSysfs`] tree.
+    pub fn with_sys_root(mut self, sys_root: &Path) -> LinuxI2cScanner {
+        self.sys_root = sys_root.to_path_buf();
+        self
+    }
+
+    /// Retries and times out probes as `policy` says, e.g. for parts slow to wake.
+    pub fn with_policy(mut self, policy: ProbePolicy) -> LinuxI2cScanner {
+        self.policy = policy;
+        self
+    }
+
+    /// Probes with these modes instead of the defaults, e.g. [`ProbeModes::from_manifest`].
+    pub fn with_probe_modes(mut self, modes: ProbeModes) -> LinuxI2cScanner {
+        self.modes = modes;
+        self
+    }
+
+    /// Overrides how a range is probed, e.g. quick write for a part that NACKs reads.
+    pub fn with_probe_mode(
+        mut self,
+        range: RangeInclusive<u16>,
+        mode: ProbeMode,
+    ) -> LinuxI2cScanner {
+        self.modes = self.modes.with_range(range, mode);
+        self
+    }
+
+    /// Restricts the scan, e.g. to the EEPROMs at 0x50..=0x57; quicker, and sensitive parts
+    /// elsewhere on the bus are never touched.
+    pub fn with_addresses(mut self, addresses: RangeInclusive<u16>) -> LinuxI2cScanner {
+        self.addresses = addresses;
+        self
+    }
 }
 
 impl I2cScanner for LinuxI2cScanner {
-    /// Scans a given I2C bus ID via hardware probe (smbus_write_quick).
+    fn addresses(&self) -> RangeInclusive<u16> {
+        clamp_addresses(self.addresses.clone())
+    }
+
+    /// Scans a given I2C bus ID via hardware probe, quick write or receive byte per address.
     ///
-    /// Might potentially be disruptive for the bus.
-    /// TODO: add some kind of safety check?
+    /// Might potentially be disruptive for the bus; the scanner's [`ProbeModes`] keep quick
+    /// writes away from EEPROM-class addresses and skip any the manifest marks; its
+    /// [`ProbePolicy`] retries addresses that don't answer the first time.
+    /// Holds the bus lock while probing, so two processes never probe the same bus at once.
     fn scan_hw_probe(&self) -> Result<(Vec<u16>, Vec<u16>)> {
+        let _span = tracing::debug_span!("scan_hw_probe", bus = self.bus_id).entered();
+        let _lock = ResourceLock::acquire(Resource::I2cBus(self.bus_id))?;
         let mut unbound = Vec::new();
         let mut bound = Vec::new();
-        let bus_path = format!("/dev/i2c-{}", self.bus_id);
-
-        for addr in 0x08..=0x77 {
-            match LinuxI2CDevice::new(&bus_path, addr) {
-                Ok(mut dev) => {
-                    if dev.smbus_write_quick(false).is_ok() {
-                        unbound.push(addr);
-                    }
-                }
-                Err(e) => match e {
-                    LinuxI2CError::Errno(code) => {
-                        let errno = Errno::from_i32(code);
-                        if errno == Errno::EBUSY {
-                            bound.push(addr);
-                        } else {
-                            eprintln!("Unexpected Errno at 0x{:02x}: {}", addr, errno);
-                        }
-                    }
-                    LinuxI2CError::Io(io_err) => match io_err.kind() {
-                        std::io::ErrorKind::NotFound => {
-                            anyhow::bail!("Bus {} not found at {}", self.bus_id, bus_path);
-                        }
-                        std::io::ErrorKind::PermissionDenied => {
-                            anyhow::bail!("Permission denied accessing {}. Try sudo.", bus_path);
-                        }
-                        _ => {
-                            eprintln!("IO Error at 0x{:02x}: {}", addr, io_err);
-                        }
-                    },
-                },
+        for addr in self.addresses() {
+            match probe_address_with(self.bus_id, addr, self.modes.mode_for(addr), &self.policy)? {
+                ProbeOutcome::Unbound => unbound.push(addr),
+                ProbeOutcome::Bound => bound.push(addr),
+                ProbeOutcome::Absent => {}
             }
         }
+        tracing::debug!(?unbound, ?bound, "hardware probe done");
         Ok((unbound, bound))
     }
 
+    /// Same probe as `scan_hw_probe`, with the same [`ProbePolicy`], timing each address.
+    fn probe_stats(&self) -> Result<Vec<ProbeStat>> {
+        let _span = tracing::debug_span!("probe_stats", bus = self.bus_id).entered();
+        let _lock = ResourceLock::acquire(Resource::I2cBus(self.bus_id))?;
+        clamp_addresses(self.addresses.clone())
+            .map(|addr| {
+                let mode = self.modes.mode_for(addr);
+                Ok(probe_address_stats(self.bus_id, addr, mode, &self.policy)?)
+            })
+            .collect()
+    }
+
     /// Scans /sys/bus/i2c-xxx for kernel-recognised devices.
+    /// One directory read for the whole bus, rather than a lookup per address.
     fn scan_sysfs(&self) -> Result<Vec<u16>> {
-        let mut detected = Vec::new();
-
-        for addr in 0x08..=0x77 {
-            let base_path = format!("/sys/bus/i2c/devices/{}-{:04x}", &self.bus_id, addr);
-            if Path::new(&base_path).exists() {
-                detected.push(addr);
-            }
-        }
+        let Ok(entries) = fs::read_dir(self.sys_root.join("bus/i2c/devices")) else {
+            return Ok(Vec::new());
+        };
+        let range = clamp_addresses(self.addresses.clone());
+        let prefix = format!("{}-", self.bus_id);
+        let mut detected: Vec<u16> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name();
+                let addr = name.to_str()?.strip_prefix(&prefix)?;
+                if addr.len() != 4 {
+                    return None; // Sysfs always names clients "<bus>-%04x"
+                }
+                u16::from_str_radix(addr, 16).ok()
+            })
+            .filter(|addr| range.contains(addr))
+            .collect();
+        detected.sort_unstable();
         Ok(detected)
     }
 }
 
 /// Holds results of an I2C bus scan for specific addresses.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 pub struct I2cValidationResult {
     pub missing: Vec<u16>,
     pub unexpected: Vec<u16>,
     pub present: Vec<u16>,
     pub probed: Vec<u16>,
+    pub forbidden: Vec<u16>, // Found although listed as must-be-absent
+}
+
+impl I2cValidationResult {
+    /// Missing expected devices and present forbidden ones fail; `unexpected` is informational.
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.forbidden.is_empty()
+    }
+
+    /// Findings as catalog messages, for operator-facing output.
+    pub fn messages(&self, bus_id: u32) -> Vec<Message> {
+        let kinds = [
+            ("i2c.missing", &self.missing),
+            ("i2c.forbidden", &self.forbidden),
+            ("i2c.unexpected", &self.unexpected),
+        ];
+        kinds
+            .into_iter()
+            .flat_map(|(id, addrs)| {
+                addrs.iter().map(move |addr| {
+                    Message::new(id)
+                        .arg("bus", bus_id)
+                        .arg("addr", format!("{:02x}", addr))
+                })
+            })
+            .collect()
+    }
+
+    /// The result as a JUnit suite: a case per expected, forbidden and unexpected address.
+    pub fn junit_suite(&self, bus_id: u32) -> TestSuite {
+        let classname = format!("i2c-{}", bus_id);
+        let case = |addr: &u16| format!("0x{:02x}", addr);
+        let mut cases = Vec::new();
+        for addr in &self.present {
+            cases.push(TestCase::passed(&classname, &case(addr)));
+        }
+        for addr in &self.missing {
+            cases.push(TestCase::failed(&classname, &case(addr), "missing"));
+        }
+        for addr in &self.forbidden {
+            cases.push(TestCase::failed(
+                &classname,
+                &case(addr),
+                "forbidden device present",
+            ));
+        }
+        for addr in &self.unexpected {
+            cases.push(TestCase {
+                output: Some("not expected".to_string()),
+                ..TestCase::passed(&classname, &case(addr))
+            });
+        }
+        TestSuite {
+            name: classname,
+            cases,
+        }
+    }
 }
 
 /// Scan an I2C bus and check for specific device addresses.
@@ -111,6 +964,35 @@ pub fn validate_bus(
     expected_addresses: &[u16],
     enable_hw_probe: bool,
 ) -> Result<I2cValidationResult> {
+    validate_bus_with_forbidden(scanner, expected_addresses, &[], enable_hw_probe)
+}
+
+/// Same as [`validate_bus`], but also reports devices that must NOT be on the bus.
+///
+/// Forbidden addresses found on the bus are listed in `forbidden` instead of `unexpected`.
+/// Fails for an expected or forbidden address outside the scanner's range, which is never
+/// looked at.
+pub fn validate_bus_with_forbidden(
+    scanner: &impl I2cScanner,
+    expected_addresses: &[u16],
+    forbidden_addresses: &[u16],
+    enable_hw_probe: bool,
+) -> Result<I2cValidationResult> {
+    let range = scanner.addresses();
+    for (kind, addrs) in [
+        ("Expected", expected_addresses),
+        ("Forbidden", forbidden_addresses),
+    ] {
+        if let Some(addr) = addrs.iter().find(|a| !range.contains(a)) {
+            anyhow::bail!(
+                "{} address 0x{:02x} is outside the scanned range 0x{:02x}-0x{:02x}",
+                kind,
+                addr,
+                range.start(),
+                range.end()
+            );
+        }
+    }
     let (hw_unbound, hw_bound) = if enable_hw_probe {
         scanner.scan_hw_probe()?
     } else {
@@ -123,6 +1005,7 @@ pub fn validate_bus(
         unexpected: Vec::new(),
         present: Vec::new(),
         probed: Vec::new(),
+        forbidden: Vec::new(),
     };
 
     for &addr in expected_addresses {
@@ -156,22 +1039,146 @@ pub fn validate_bus(
         }
     }
 
+    result.unexpected.retain(|addr| {
+        if forbidden_addresses.contains(addr) {
+            result.forbidden.push(*addr);
+            false
+        } else {
+            true
+        }
+    });
+
     Ok(result)
 }
 
 /// Holds results of the I2C subsystem full scan (both hw probe and sysfs).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 pub struct I2cBusReport {
     pub bus_path: String,
-    pub kernel_detected: Vec<u16>,  // From /sys
-    pub hardware_unbound: Vec<u16>, // From smbus_write_quick - unbound
-    pub hardware_bound: Vec<u16>,   // From smbus_write_quick - bound to a driver
+    pub kernel_detected: Vec<u16>,   // From /sys
+    pub hardware_unbound: Vec<u16>,  // From the hardware probe - unbound
+    pub hardware_bound: Vec<u16>,    // From the hardware probe - bound to a driver
+    pub stats: Option<AdapterStats>, // Counter deltas over the scan, if the kernel has them
+}
+
+/// Where adapters expose counters: the i2c core's statistics, then the driver's debugfs.
+const STATS_DIRS: [&str; 2] = [
+    "bus/i2c/devices/i2c-{}/statistics",
+    "kernel/debug/i2c/i2c-{}",
+];
+
+/// Transfer and error counters of one adapter, by file name, e.g. "transfers" or "nacks".
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AdapterStats {
+    pub counters: BTreeMap<String, u64>,
+}
+
+/// Whether a counter counts failures: errors, NACKs, timeouts, lost arbitration, recoveries.
+pub fn is_error_counter(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    ["err", "nack", "timeout", "arb", "recover"]
+        .iter()
+        .any(|k| name.contains(k))
+}
+
+impl AdapterStats {
+    /// Reads the counters of an adapter; `None` if the kernel exposes none for it.
+    pub fn read_in(sys_root: &Path, bus_id: u32) -> Option<AdapterStats> {
+        STATS_DIRS.iter().find_map(|dir| {
+            let dir = sys_root.join(dir.replace("{}", &bus_id.to_string()));
+            let counters: BTreeMap<String, u64> = fs::read_dir(dir)
+                .ok()?
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let value = fs::read_to_string(e.path()).ok()?.trim().parse().ok()?;
+                    Some((e.file_name().to_string_lossy().to_string(), value))
+                })
+                .collect();
+            (!counters.is_empty()).then_some(AdapterStats { counters })
+        })
+    }
+
+    /// Counts since `before`. A counter that went backwards was reset, so counts from zero.
+    pub fn since(&self, before: &AdapterStats) -> AdapterStats {
+        let counters = self
+            .counters
+            .iter()
+            .map(|(name, after)| {
+                let delta = match before.counters.get(name) {
+                    Some(before) if before <= after => after - before,
+                    _ => *after,
+                };
+                (name.clone(), delta)
+            })
+            .collect();
+        AdapterStats { counters }
+    }
+
+    /// Sum of the error counters.
+    pub fn errors(&self) -> u64 {
+        self.counters
+            .iter()
+            .filter(|(name, _)| is_error_counter(name))
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Sum of the other counters, i.e. transfers or messages.
+    pub fn transfers(&self) -> u64 {
+        self.counters
+            .iter()
+            .filter(|(name, _)| !is_error_counter(name))
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Records the counters in bus metadata under `stats*` keys, with a warning if any
+    /// error counter moved.
+    pub fn annotate(&self, metadata: &mut BTreeMap<String, String>) {
+        let counters: Vec<String> = self
+            .counters
+            .iter()
+            .map(|(name, count)| format!("{}={}", name, count))
+            .collect();
+        metadata.insert("stats".to_string(), counters.join(","));
+        metadata.insert("stats_errors".to_string(), self.errors().to_string());
+        if self.errors() > 0 {
+            metadata.insert(
+                "stats_warning".to_string(),
+                format!(
+                    "{} adapter errors in {} transfers",
+                    self.errors(),
+                    self.transfers()
+                ),
+            );
+        }
+    }
+}
+
+/// Counter deltas of an adapter over `run`, which itself is unchanged.
+pub fn with_adapter_stats_in<T>(
+    sys_root: &Path,
+    bus_id: u32,
+    run: impl FnOnce() -> Result<T>,
+) -> Result<(T, Option<AdapterStats>)> {
+    let before = AdapterStats::read_in(sys_root, bus_id);
+    let result = run()?;
+    let stats = before.and_then(|before| {
+        AdapterStats::read_in(sys_root, bus_id).map(|after| after.since(&before))
+    });
+    Ok((result, stats))
 }
 
 /// Returns either `name` or entry from `uevent` of a particular I2C device.
 pub fn get_device_info(bus_id: u32, addr: u16) -> String {
-    let base_path = format!("/sys/bus/i2c/devices/{}-{:04x}", bus_id, addr);
-    let name_path = format!("{}/name", base_path);
-    let uevent_path = format!("{}/uevent", base_path);
+    get_device_info_in(Path::new("/sys"), bus_id, addr)
+}
+
+/// Same as [`get_device_info`], below an explicit sysfs root.
+pub fn get_device_info_in(sys_root: &Path, bus_id: u32, addr: u16) -> String {
+    let base_path = sys_root.join(format!("bus/i2c/devices/{}-{:04x}", bus_id, addr));
+    let name_path = base_path.join("name");
+    let uevent_path = base_path.join("uevent");
 
     // 1. Try the 'name' file first
     if let Ok(name) = fs::read_to_string(name_path) {
@@ -197,37 +1204,255 @@ pub fn get_device_info(bus_id: u32, addr: u16) -> String {
     "Unidentified".to_string()
 }
 
+/// The bus an adapter's traffic finally goes out on: itself, or the root of its mux tree.
+pub fn root_bus_in(sys_root: &Path, bus_id: u32) -> u32 {
+    let mut bus = bus_id;
+    for _ in 0..8 {
+        // Mux trees are shallow; the limit guards against a link loop
+        let link = sys_root.join(format!("bus/i2c/devices/i2c-{}/mux_device", bus));
+        let Some(parent) = fs::read_link(link).ok().and_then(|target| {
+            let name = target.file_name()?.to_str()?.to_string();
+            name.split_once('-')?.0.parse().ok()
+        }) else {
+            break;
+        };
+        bus = parent;
+    }
+    bus
+}
+
+/// Runs `scan` on every bus, concurrently across physical buses and one after another on
+/// the channels of a mux tree. Results are in the order of `bus_ids`; so is the error
+/// returned if several buses fail.
+pub fn scan_buses_in_parallel<T: Send>(
+    sys_root: &Path,
+    bus_ids: &[u32],
+    scan: impl Fn(u32) -> Result<T> + Sync,
+) -> Result<Vec<T>> {
+    let mut groups: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+    for (i, bus_id) in bus_ids.iter().enumerate() {
+        groups
+            .entry(root_bus_in(sys_root, *bus_id))
+            .or_default()
+            .push(i);
+    }
+    let mut results: Vec<Option<Result<T>>> = bus_ids.iter().map(|_| None).collect();
+    let scan = &scan;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = groups
+            .into_values()
+            .map(|indexes| {
+                scope.spawn(move || {
+                    indexes
+                        .into_iter()
+                        .map(|i| (i, scan(bus_ids[i])))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for handle in handles {
+            let scanned = handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            for (i, result) in scanned {
+                results[i] = Some(result);
+            }
+        }
+    });
+    results
+        .into_iter()
+        .map(|r| r.expect("every bus is scanned"))
+        .collect()
+}
+
+/// Bus IDs of the /dev/i2c-N nodes, in [`discover_buses`] order.
+fn discover_bus_ids() -> Result<Vec<u32>> {
+    Ok(discover_buses()?
+        .iter()
+        .filter_map(|path| {
+            path.to_str()
+                .and_then(|p| p.strip_prefix("/dev/i2c-"))
+                .and_then(|x| x.parse::<u32>().ok())
+        })
+        .collect())
+}
+
 /// Performs full scan of I2C subsystem for the full range of addresses.
 ///
-/// Both sysfs scan and harware probes (optional, via smbus_quick_write) are performed.
+/// Both sysfs scan and harware probes (optional) are performed, on independent buses
+/// concurrently, see [`scan_buses_in_parallel`].
 pub fn full_system_scan(enable_hw_probe: bool) -> Result<Vec<I2cBusReport>> {
-    let busses = discover_buses()?;
-    let mut reports = Vec::new();
-
-    for path in busses {
-        let bus_str = path.to_string_lossy().to_string();
-        let bus_id: u8 = bus_str
-            .strip_prefix("/dev/i2c-")
-            .and_then(|x| x.parse::<u8>().ok())
-            .expect("invalid bus string");
-        let scanner = LinuxI2cScanner { bus_id };
+    let bus_ids = discover_bus_ids()?;
+    let sys_root = Path::new("/sys");
+    scan_buses_in_parallel(sys_root, &bus_ids, |bus_id| {
+        let scanner = LinuxI2cScanner::new(bus_id);
 
         // 1. Live Hardware Probe - not super Rust-idiomatic but will do
-        let (hw_unbound, hw_bound) = if enable_hw_probe {
-            scanner.scan_hw_probe()?
-        } else {
-            (Vec::new(), Vec::new())
-        };
+        let ((hw_unbound, hw_bound), stats) = with_adapter_stats_in(sys_root, bus_id, || {
+            if enable_hw_probe {
+                scanner.scan_hw_probe()
+            } else {
+                Ok((Vec::new(), Vec::new()))
+            }
+        })?;
 
         // 2. Sysfs check
         let knl_detected = scanner.scan_sysfs()?;
 
-        reports.push(I2cBusReport {
-            bus_path: bus_str,
+        Ok(I2cBusReport {
+            bus_path: format!("/dev/i2c-{}", bus_id),
             kernel_detected: knl_detected,
             hardware_unbound: hw_unbound,
             hardware_bound: hw_bound,
-        });
+            stats,
+        })
+    })
+}
+
+/// Lists kernel-known devices on a bus, enriched with udev data where udev runs.
+pub fn find_i2c_slaves_with_udev(bus_id: u32) -> Result<Vec<TuxDevice>> {
+    find_i2c_slaves_with_udev_in(Path::new("/sys"), Path::new("/run/udev/data"), bus_id)
+}
+
+/// Same as [`find_i2c_slaves_with_udev`], with explicit sysfs and udev database roots.
+pub fn find_i2c_slaves_with_udev_in(
+    sys_root: &Path,
+    udev_db: &Path,
+    bus_id: u32,
+) -> Result<Vec<TuxDevice>> {
+    let _span = tracing::debug_span!("udev_enumeration", bus = bus_id).entered();
+    if !udev_db.is_dir() {
+        tracing::debug!(udev_db = %udev_db.display(), "no udev database, using sysfs only");
+    }
+    let prefix = format!("{}-", bus_id);
+    let mut devices = Vec::new();
+    for entry in fs::read_dir(sys_root.join("bus/i2c/devices"))? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(addr) = name
+            .to_str()
+            .and_then(|n| n.strip_prefix(&prefix))
+            .and_then(|a| u16::from_str_radix(a, 16).ok())
+        else {
+            continue;
+        };
+        let device = TuxDevice::from_udev_in(
+            &entry.path(),
+            udev_db,
+            Subsystem::I2c,
+            DeviceAddress::I2c { bus: bus_id, addr },
+        );
+        tracing::debug!(
+            device = %device.address,
+            name = %device.name,
+            driver = ?device.driver,
+            in_udev = device.in_udev,
+            "kernel-known device"
+        );
+        devices.push(device);
+    }
+    devices.sort_by_key(|d| d.address.clone());
+    Ok(devices)
+}
+
+/// Kernel-known devices on a bus, keyed by address.
+pub fn get_i2c_udev_map(bus_id: u32) -> Result<BTreeMap<u16, TuxDevice>> {
+    Ok(find_i2c_slaves_with_udev(bus_id)?
+        .into_iter()
+        .filter_map(|d| match d.address {
+            DeviceAddress::I2c { addr, .. } => Some((addr, d)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Builds the Board model entry for one bus from sysfs/udev and an optional hardware probe.
+///
+/// Addresses that respond to the probe but have no sysfs entry are added as ghost devices.
+/// If the scanner provides [`ProbeStat`]s, the bus health verdict goes into the metadata.
+pub fn audit_i2c_bus_in(
+    scanner: &impl I2cScanner,
+    sys_root: &Path,
+    udev_db: &Path,
+    bus_id: u32,
+    enable_hw_probe: bool,
+) -> Result<TuxBus> {
+    let mut devices = find_i2c_slaves_with_udev_in(sys_root, udev_db, bus_id)?;
+    let mut health = None;
+    let before = AdapterStats::read_in(sys_root, bus_id);
+    if enable_hw_probe {
+        let stats = scanner.probe_stats()?;
+        let (hw_unbound, hw_bound) = if stats.is_empty() {
+            scanner.scan_hw_probe()?
+        } else {
+            let with = |outcome| -> Vec<u16> {
+                stats
+                    .iter()
+                    .filter(|s| s.outcome == outcome)
+                    .map(|s| s.addr)
+                    .collect()
+            };
+            health = Some(assess_health(&stats));
+            (with(ProbeOutcome::Unbound), with(ProbeOutcome::Bound))
+        };
+        for addr in hw_unbound.into_iter().chain(hw_bound) {
+            let address = DeviceAddress::I2c { bus: bus_id, addr };
+            match devices.iter_mut().find(|d| d.address == address) {
+                Some(device) => device.hw_responded = true,
+                None => {
+                    let mut ghost = TuxDevice::new(Subsystem::I2c, address, "Unidentified");
+                    ghost.hw_responded = true;
+                    devices.push(ghost);
+                }
+            }
+        }
+        devices.sort_by_key(|d| d.address.clone());
     }
-    Ok(reports)
+
+    let id = format!("i2c-{}", bus_id);
+    let bus_dir = sys_root.join("bus/i2c/devices").join(&id);
+    let name = fs::read_to_string(bus_dir.join("name"))
+        .map(|n| n.trim().to_string())
+        .unwrap_or_default();
+    let mut metadata = BTreeMap::new();
+    // Adapters behind an I2C mux link to the mux device, e.g. "1-0070"
+    if let Ok(mux) = fs::read_link(bus_dir.join("mux_device"))
+        && let Some(mux) = mux.file_name()
+    {
+        metadata.insert("mux_device".to_string(), mux.to_string_lossy().to_string());
+    }
+    if let Some(health) = health {
+        health.annotate(&mut metadata);
+    }
+    if let Some(before) = before
+        && let Some(after) = AdapterStats::read_in(sys_root, bus_id)
+    {
+        after.since(&before).annotate(&mut metadata);
+    }
+    let mut bus = TuxBus {
+        subsystem: Subsystem::I2c,
+        id,
+        name,
+        devices,
+        metadata,
+    };
+    // Board-ID EEPROMs are read with the probe, as reading them is bus traffic too
+    if enable_hw_probe {
+        crate::eeprom::annotate_bus(&mut bus);
+    }
+    Ok(bus)
+}
+
+/// Audits every I2C bus in /dev into Board model buses, independent buses concurrently.
+pub fn audit_all_i2c_buses(enable_hw_probe: bool) -> Result<Vec<TuxBus>> {
+    let sys_root = Path::new("/sys");
+    scan_buses_in_parallel(sys_root, &discover_bus_ids()?, |bus_id| {
+        audit_i2c_bus_in(
+            &LinuxI2cScanner::new(bus_id),
+            sys_root,
+            Path::new("/run/udev/data"),
+            bus_id,
+            enable_hw_probe,
+        )
+    })
 }
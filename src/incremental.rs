@@ -0,0 +1,130 @@
+use crate::device::{Subsystem, TuxBus};
+#[cfg(feature = "i2c")]
+use crate::i2c;
+#[cfg(feature = "i2c")]
+use anyhow::Result;
+use std::collections::BTreeSet;
+#[cfg(feature = "i2c")]
+use std::path::Path;
+
+/// A kernel/udev uevent, as printed by `udevadm monitor`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UdevEvent {
+    pub action: String,  // "add", "remove", "bind", "unbind", "change", ..
+    pub devpath: String, // e.g. "/devices/platform/fe5a0000.i2c/i2c-1/1-0050"
+    pub subsystem: String,
+}
+
+/// Parses `udevadm monitor` lines: `KERNEL[123.456] add /devices/.. (i2c)`.
+///
+/// Headers and other lines are skipped; KERNEL and UDEV copies of an event both show up.
+pub fn parse_udevadm_monitor(output: &str) -> Vec<UdevEvent> {
+    output
+        .lines()
+        .filter(|l| l.starts_with("KERNEL") || l.starts_with("UDEV"))
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(']')?;
+            let mut fields = rest.split_whitespace();
+            let action = fields.next()?.to_string();
+            let devpath = fields.next()?.to_string();
+            let subsystem = fields.next()?.trim_matches(['(', ')']).to_string();
+            Some(UdevEvent {
+                action,
+                devpath,
+                subsystem,
+            })
+        })
+        .collect()
+}
+
+/// The I2C bus an event belongs to: the innermost `i2c-N` in its devpath, so a device behind
+/// a mux maps to the mux channel's bus.
+pub fn i2c_bus_of(event: &UdevEvent) -> Option<u32> {
+    event
+        .devpath
+        .rsplit('/')
+        .find_map(|c| c.strip_prefix("i2c-").and_then(|n| n.parse().ok()))
+}
+
+/// What to re-validate.
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    pub events: Vec<UdevEvent>,
+    pub subsystems: Vec<Subsystem>, // Re-run everything of these
+}
+
+/// I2C buses to re-audit: those touched by an event, or all known ones if I2C is in the
+/// subsystem filter. Only I2C has audits so far; other subsystems select nothing.
+pub fn affected_i2c_buses(previous: &[TuxBus], scope: &Scope) -> BTreeSet<u32> {
+    let mut buses: BTreeSet<u32> = scope.events.iter().filter_map(i2c_bus_of).collect();
+    if scope.subsystems.contains(&Subsystem::I2c) {
+        buses.extend(
+            previous
+                .iter()
+                .filter(|b| b.subsystem == Subsystem::I2c)
+                .filter_map(|b| b.id.strip_prefix("i2c-")?.parse::<u32>().ok()),
+        );
+    }
+    buses
+}
+
+/// Replaces buses of `previous` by their re-audited versions and drops the ones in `removed`.
+///
+/// New buses are appended; the order of the rest is kept.
+pub fn merge(previous: &[TuxBus], updated: Vec<TuxBus>, removed: &[String]) -> Vec<TuxBus> {
+    let mut merged: Vec<TuxBus> = previous
+        .iter()
+        .filter(|b| !removed.contains(&b.id))
+        .cloned()
+        .collect();
+    for bus in updated {
+        match merged.iter_mut().find(|b| b.id == bus.id) {
+            Some(existing) => *existing = bus,
+            None => merged.push(bus),
+        }
+    }
+    merged
+}
+
+/// Re-audits only the affected I2C buses and merges them into the previous report.
+#[cfg(feature = "i2c")]
+pub fn revalidate(previous: &[TuxBus], scope: &Scope, hw_probe: bool) -> Result<Vec<TuxBus>> {
+    revalidate_in(
+        previous,
+        scope,
+        Path::new("/sys"),
+        Path::new("/run/udev/data"),
+        i2c::LinuxI2cScanner::new,
+        hw_probe,
+    )
+}
+
+/// Same as [`revalidate`], with explicit roots and scanners.
+#[cfg(feature = "i2c")]
+pub fn revalidate_in<S: i2c::I2cScanner>(
+    previous: &[TuxBus],
+    scope: &Scope,
+    sys_root: &Path,
+    udev_db: &Path,
+    scanner_for: impl Fn(u32) -> S,
+    hw_probe: bool,
+) -> Result<Vec<TuxBus>> {
+    let mut updated = Vec::new();
+    let mut removed = Vec::new();
+    for bus_id in affected_i2c_buses(previous, scope) {
+        let id = format!("i2c-{}", bus_id);
+        // The adapter itself went away, e.g. a mux was unbound
+        if !sys_root.join("bus/i2c/devices").join(&id).exists() {
+            removed.push(id);
+            continue;
+        }
+        updated.push(i2c::audit_i2c_bus_in(
+            &scanner_for(bus_id),
+            sys_root,
+            udev_db,
+            bus_id,
+            hw_probe,
+        )?);
+    }
+    Ok(merge(previous, updated, &removed))
+}
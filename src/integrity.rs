@@ -0,0 +1,132 @@
+use anyhow::Result;
+use ed25519_dalek::{Signature, VerifyingKey};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{BufRead, Read};
+use std::path::{Path, PathBuf};
+
+/// A file and its expected SHA-256, as listed in the image manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashEntry {
+    pub path: PathBuf, // Absolute path on the target
+    pub sha256: String,
+}
+
+/// Parses a `sha256sum`-format manifest (`<hex>  <path>` or `<hex> *<path>`).
+pub fn parse_hash_manifest_from_reader<R: BufRead>(reader: R) -> Result<Vec<HashEntry>> {
+    let mut entries = Vec::new();
+    for line_result in reader.lines() {
+        let line = line_result?;
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((hash, path)) = line.split_once(' ') else {
+            anyhow::bail!("Malformed manifest line: {}", line);
+        };
+        if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            anyhow::bail!("Invalid SHA-256 in manifest line: {}", line);
+        }
+        let path = path.trim_start_matches([' ', '*']);
+        entries.push(HashEntry {
+            path: PathBuf::from(path),
+            sha256: hash.to_ascii_lowercase(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Verifies a detached Ed25519 signature over the raw manifest bytes.
+pub fn verify_signature(manifest: &[u8], signature: &[u8], public_key: &[u8; 32]) -> Result<()> {
+    let key = VerifyingKey::from_bytes(public_key)?;
+    let signature = Signature::from_slice(signature)?;
+    key.verify_strict(manifest, &signature)
+        .map_err(|_| anyhow::anyhow!("Manifest signature does not match"))
+}
+
+/// Reads a manifest and its detached signature, refusing to parse it unless the signature holds.
+pub fn load_signed_manifest(
+    manifest_path: &Path,
+    signature_path: &Path,
+    public_key: &[u8; 32],
+) -> Result<Vec<HashEntry>> {
+    let manifest = fs::read(manifest_path)?;
+    let signature = fs::read(signature_path)?;
+    verify_signature(&manifest, &signature, public_key)?;
+    parse_hash_manifest_from_reader(manifest.as_slice())
+}
+
+/// Hex-encoded SHA-256 of a file's contents.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Holds results of a file integrity spot-check.
+#[derive(Debug, Default)]
+pub struct IntegrityResult {
+    pub verified: Vec<PathBuf>,
+    pub missing: Vec<PathBuf>,
+    pub mismatched: Vec<String>,
+}
+
+impl IntegrityResult {
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+/// Hashes the `critical` files (all manifest entries if empty) under `root` and compares.
+///
+/// A critical file that the manifest doesn't cover counts as a mismatch.
+pub fn check_files(root: &Path, entries: &[HashEntry], critical: &[PathBuf]) -> IntegrityResult {
+    let mut result = IntegrityResult::default();
+    let selected: Vec<&HashEntry> = if critical.is_empty() {
+        entries.iter().collect()
+    } else {
+        let mut selected = Vec::new();
+        for path in critical {
+            match entries.iter().find(|e| &e.path == path) {
+                Some(entry) => selected.push(entry),
+                None => result
+                    .mismatched
+                    .push(format!("{}: not covered by manifest", path.display())),
+            }
+        }
+        selected
+    };
+
+    for entry in selected {
+        let on_disk = root.join(entry.path.strip_prefix("/").unwrap_or(&entry.path));
+        if !on_disk.exists() {
+            result.missing.push(entry.path.clone());
+            continue;
+        }
+        match sha256_file(&on_disk) {
+            Ok(hash) if hash == entry.sha256 => result.verified.push(entry.path.clone()),
+            Ok(hash) => result.mismatched.push(format!(
+                "{}: sha256 {} (expected {})",
+                entry.path.display(),
+                hash,
+                entry.sha256
+            )),
+            Err(e) => result
+                .mismatched
+                .push(format!("{}: {}", entry.path.display(), e)),
+        }
+    }
+    result
+}
@@ -0,0 +1,165 @@
+use crate::runtime::{self, Dependency};
+use anyhow::Result;
+use serde_json::Value;
+use std::io::BufRead;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// systemd's "unit entered failed state" catalog message ID.
+const UNIT_FAILED_MESSAGE_ID: &str = "be02cf6855d2428ba40df7e9d022f03d";
+
+/// A journal entry, reduced to the fields we correlate on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    pub message: String,
+    pub priority: u8,         // 0 (emerg) .. 7 (debug)
+    pub unit: Option<String>, // The unit it is about: UNIT, USER_UNIT, else _SYSTEMD_UNIT
+    pub identifier: Option<String>,
+    pub kernel_device: Option<String>, // e.g. "+i2c:1-0050", "c189:1", "b8:0", "n2"
+    pub message_id: Option<String>,
+    pub timestamp_us: u64, // __REALTIME_TIMESTAMP
+}
+
+impl JournalEntry {
+    /// Maps `_KERNEL_DEVICE` (`+<subsystem>:<sysname>`) to a sysfs device path.
+    ///
+    /// Character/block (`c`/`b`) and netdev (`n`) IDs are not resolved.
+    pub fn sysfs_path(&self) -> Option<PathBuf> {
+        let (subsystem, sysname) = self
+            .kernel_device
+            .as_ref()?
+            .strip_prefix('+')?
+            .split_once(':')?;
+        Some(PathBuf::from(format!(
+            "/sys/bus/{}/devices/{}",
+            subsystem, sysname
+        )))
+    }
+}
+
+fn field(entry: &Value, name: &str) -> Option<String> {
+    // Binary-safe fields are emitted as byte arrays
+    match &entry[name] {
+        Value::String(s) => Some(s.clone()),
+        Value::Array(bytes) => Some(
+            String::from_utf8_lossy(
+                &bytes
+                    .iter()
+                    .filter_map(|b| b.as_u64().map(|b| b as u8))
+                    .collect::<Vec<u8>>(),
+            )
+            .to_string(),
+        ),
+        _ => None,
+    }
+}
+
+/// Parses `journalctl -o json` output (one JSON object per line).
+pub fn parse_journal_json_from_reader<R: BufRead>(reader: R) -> Result<Vec<JournalEntry>> {
+    let mut entries = Vec::new();
+    for line_result in reader.lines() {
+        let line = line_result?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: Value = serde_json::from_str(&line)?;
+        entries.push(JournalEntry {
+            message: field(&entry, "MESSAGE").unwrap_or_default(),
+            priority: field(&entry, "PRIORITY")
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(6),
+            // PID 1 and the user managers log about other units under their own scope
+            unit: field(&entry, "UNIT")
+                .or_else(|| field(&entry, "USER_UNIT"))
+                .or_else(|| field(&entry, "_SYSTEMD_UNIT")),
+            identifier: field(&entry, "SYSLOG_IDENTIFIER"),
+            kernel_device: field(&entry, "_KERNEL_DEVICE"),
+            message_id: field(&entry, "MESSAGE_ID"),
+            timestamp_us: field(&entry, "__REALTIME_TIMESTAMP")
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(0),
+        });
+    }
+    Ok(entries)
+}
+
+/// Reads the current boot's journal up to `max_priority` (e.g. 4 = warning).
+pub fn read_current_boot(max_priority: u8) -> Result<Vec<JournalEntry>> {
+    runtime::require(&Dependency::program("journalctl"))?;
+    let output = Command::new("journalctl")
+        .args(["-b", "-o", "json", "--no-pager", "-p"])
+        .arg(max_priority.to_string())
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "journalctl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    parse_journal_json_from_reader(output.stdout.as_slice())
+}
+
+/// A configurable error pattern. `pattern` is a plain substring of MESSAGE.
+#[derive(Debug, Clone, Default)]
+pub struct JournalRule {
+    pub id: String,
+    pub pattern: String,
+    pub max_priority: Option<u8>, // Only match entries at least this severe
+    pub unit: Option<String>,     // Only match entries from this unit
+}
+
+/// A journal entry matched by a rule, with the device it refers to (if any).
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalFinding {
+    pub rule_id: String,
+    pub entry: JournalEntry,
+    pub device: Option<PathBuf>,
+}
+
+/// Matches entries against rules; every entry/rule match is a finding.
+pub fn scan_entries(entries: &[JournalEntry], rules: &[JournalRule]) -> Vec<JournalFinding> {
+    let mut findings = Vec::new();
+    for entry in entries {
+        for rule in rules {
+            let priority_ok = rule.max_priority.is_none_or(|p| entry.priority <= p);
+            let unit_ok = rule.unit.is_none() || rule.unit == entry.unit;
+            if priority_ok && unit_ok && entry.message.contains(rule.pattern.as_str()) {
+                findings.push(JournalFinding {
+                    rule_id: rule.id.clone(),
+                    entry: entry.clone(),
+                    device: entry.sysfs_path(),
+                });
+            }
+        }
+    }
+    findings
+}
+
+/// Returns units that entered the failed state during this boot (deduplicated, in order).
+pub fn failed_units(entries: &[JournalEntry]) -> Vec<String> {
+    let mut units = Vec::new();
+    for entry in entries {
+        if entry.message_id.as_deref() == Some(UNIT_FAILED_MESSAGE_ID)
+            && let Some(unit) = &entry.unit
+            && !units.contains(unit)
+        {
+            units.push(unit.clone());
+        }
+    }
+    units
+}
+
+/// Findings for a given sysfs device, e.g. to attach to an I2C validation result.
+pub fn findings_for_device<'a>(
+    findings: &'a [JournalFinding],
+    sysfs_path: &str,
+) -> Vec<&'a JournalFinding> {
+    findings
+        .iter()
+        .filter(|f| {
+            f.device
+                .as_ref()
+                .is_some_and(|d| d.as_os_str() == sysfs_path)
+        })
+        .collect()
+}
@@ -0,0 +1,273 @@
+use crate::device::{DeviceAddress, TuxBus};
+#[cfg(feature = "hardware")]
+use anyhow::Result;
+use std::fmt;
+use std::time::Duration;
+
+/// One kernel log record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KmsgRecord {
+    pub level: u8, // Syslog priority, 0 (emerg) to 7 (debug); 6 for plain dmesg text
+    pub timestamp: Duration, // Since boot
+    pub message: String,
+    pub device: Option<String>, // Kernel name of the device it is about, e.g. "1-0050"
+}
+
+/// Parses `/dev/kmsg` records, e.g. `3,812,5140900,-;at24 1-0050: ...` followed by
+/// ` SUBSYSTEM=i2c` and ` DEVICE=+i2c:1-0050` lines. Plain `dmesg` output, with or
+/// without `[    5.140900]` timestamps, parses too.
+///
+/// Without a `DEVICE` line, the device is taken from the `dev_printk` prefix of the
+/// message: `<driver> <device>: ...`.
+pub fn parse_records(text: &str) -> Vec<KmsgRecord> {
+    let mut records: Vec<KmsgRecord> = Vec::new();
+    for line in text.lines() {
+        if let Some(property) = line.strip_prefix(' ') {
+            // Devices with a node are "c89:1" or "b8:0", which don't name them
+            if let Some(device) = property.strip_prefix("DEVICE=+")
+                && let Some((_, name)) = device.split_once(':')
+                && let Some(record) = records.last_mut()
+            {
+                record.device = Some(name.to_string());
+            }
+            continue;
+        }
+        let (level, timestamp, message) = match line.split_once(';') {
+            Some((prefix, message)) if prefix.split(',').count() >= 4 => {
+                let mut fields = prefix.split(',');
+                let priority: u32 = fields.next().and_then(|p| p.parse().ok()).unwrap_or(6);
+                let usec = fields.nth(1).and_then(|t| t.parse().ok()).unwrap_or(0);
+                ((priority & 7) as u8, Duration::from_micros(usec), message)
+            }
+            _ => match line
+                .trim_start()
+                .strip_prefix('[')
+                .and_then(|l| l.split_once("] "))
+            {
+                Some((seconds, message)) => (
+                    6,
+                    seconds
+                        .trim()
+                        .parse::<f64>()
+                        .map(Duration::from_secs_f64)
+                        .unwrap_or_default(),
+                    message,
+                ),
+                None => (6, Duration::ZERO, line),
+            },
+        };
+        if message.trim().is_empty() {
+            continue;
+        }
+        let mut words = message.splitn(3, ' ');
+        let device = match (words.next(), words.next()) {
+            (Some(_), Some(name)) if name.len() > 1 && name.ends_with(':') => {
+                Some(name.trim_end_matches(':').to_string())
+            }
+            _ => None,
+        };
+        records.push(KmsgRecord {
+            level,
+            timestamp,
+            message: message.to_string(),
+            device,
+        });
+    }
+    records
+}
+
+/// What a record reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Issue {
+    ProbeDeferred, // -EPROBE_DEFER: waiting on a supplier, e.g. a regulator or GPIO
+    ProbeFailed,
+    TransferFailed, // A bus transfer the device didn't answer, e.g. an I2C NACK or timeout
+    Oops,
+    Warning, // WARN() splat
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Issue::ProbeDeferred => "probe deferred",
+            Issue::ProbeFailed => "probe failed",
+            Issue::TransferFailed => "transfer failed",
+            Issue::Oops => "oops",
+            Issue::Warning => "warning",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Errno names for the codes drivers return from probe and transfers.
+fn errno_name(code: i32) -> Option<&'static str> {
+    Some(match code {
+        -2 => "ENOENT",
+        -5 => "EIO",
+        -6 => "ENXIO",
+        -11 => "EAGAIN",
+        -12 => "ENOMEM",
+        -16 => "EBUSY",
+        -19 => "ENODEV",
+        -22 => "EINVAL",
+        -95 => "EOPNOTSUPP",
+        -110 => "ETIMEDOUT",
+        -121 => "EREMOTEIO",
+        -517 => "EPROBE_DEFER",
+        _ => return None,
+    })
+}
+
+/// The first negative errno in a message, e.g. -517 in "failed with error -517".
+pub fn error_code(message: &str) -> Option<i32> {
+    message
+        .split(|c: char| c.is_whitespace() || "(),=:".contains(c))
+        .filter(|word| word.starts_with('-'))
+        .filter_map(|word| word.parse::<i32>().ok())
+        .find(|code| (-4095..0).contains(code))
+}
+
+fn classify(message: &str, code: Option<i32>) -> Option<Issue> {
+    let lower = message.to_ascii_lowercase();
+    if message.starts_with("Unable to handle kernel")
+        || message.starts_with("Internal error:")
+        || message.starts_with("BUG:")
+        || message.starts_with("Kernel panic")
+        || message.contains("Oops")
+    {
+        return Some(Issue::Oops);
+    }
+    if message.starts_with("WARNING:") || message.contains("[ cut here ]") {
+        return Some(Issue::Warning);
+    }
+    if code == Some(-517) || lower.contains("deferred probe pending") {
+        return Some(Issue::ProbeDeferred);
+    }
+    if lower.contains("probe") && (lower.contains("fail") || lower.contains("error")) {
+        return Some(Issue::ProbeFailed);
+    }
+    let transfer = [
+        "timeout",
+        "timed out",
+        "nack",
+        "no ack",
+        "arbitration lost",
+        "transfer failed",
+        "failed reading",
+        "failed writing",
+        "read failed",
+        "write failed",
+    ];
+    if transfer.iter().any(|t| lower.contains(t)) || matches!(code, Some(-6 | -110 | -121)) {
+        return Some(Issue::TransferFailed);
+    }
+    None
+}
+
+/// A record reporting a problem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KmsgFinding {
+    pub issue: Issue,
+    pub error: Option<i32>, // Negative errno, if the message has one
+    pub record: KmsgRecord,
+}
+
+impl KmsgFinding {
+    /// About the device named `name`: by its `DEVICE` property or `dev_printk` prefix,
+    /// or naming it anywhere in an oops or warning.
+    pub fn is_about(&self, name: &str) -> bool {
+        if self.record.device.as_deref() == Some(name) {
+            return true;
+        }
+        matches!(self.issue, Issue::Oops | Issue::Warning)
+            && self
+                .record
+                .message
+                .split(|c: char| c.is_whitespace() || c == ':')
+                .any(|word| word == name)
+    }
+}
+
+/// Formatted like `dmesg`, with the errno name, e.g.
+/// "[    2.184625] at24 1-0050: probe failed with error -517 (EPROBE_DEFER)".
+impl fmt::Display for KmsgFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{:5}.{:06}] {}",
+            self.record.timestamp.as_secs(),
+            self.record.timestamp.subsec_micros(),
+            self.record.message
+        )?;
+        if let Some(name) = self.error.and_then(errno_name) {
+            write!(f, " ({})", name)?;
+        }
+        Ok(())
+    }
+}
+
+/// The records reporting probe errors, transfer failures, oopses and warnings.
+pub fn findings(records: &[KmsgRecord]) -> Vec<KmsgFinding> {
+    records
+        .iter()
+        .filter_map(|record| {
+            let error = error_code(&record.message);
+            Some(KmsgFinding {
+                issue: classify(&record.message, error)?,
+                error,
+                record: record.clone(),
+            })
+        })
+        .collect()
+}
+
+/// The findings about a device, formatted, e.g. to explain why it is missing from sysfs.
+pub fn for_device(findings: &[KmsgFinding], address: &DeviceAddress) -> Vec<String> {
+    let name = address.to_string();
+    findings
+        .iter()
+        .filter(|f| f.is_about(&name))
+        .map(|f| f.to_string())
+        .collect()
+}
+
+/// Adds the findings about each device to its `kernel_log`.
+pub fn attach(buses: &mut [TuxBus], findings: &[KmsgFinding]) {
+    for device in buses.iter_mut().flat_map(|b| b.devices.iter_mut()) {
+        device
+            .kernel_log
+            .extend(for_device(findings, &device.address));
+    }
+}
+
+/// Reads the whole kernel ring buffer from /dev/kmsg without blocking.
+#[cfg(feature = "hardware")]
+pub fn read_kmsg() -> Result<String> {
+    use std::io::Read;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut kmsg = std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open("/dev/kmsg")?;
+    let mut records = String::new();
+    // Each read() returns exactly one record; EAGAIN marks the end
+    let mut buf = vec![0u8; 8192];
+    loop {
+        match kmsg.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => records.push_str(&String::from_utf8_lossy(&buf[..n])),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+            // Overwritten records; just keep reading
+            Err(e) if e.raw_os_error() == Some(libc::EPIPE) => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(records)
+}
+
+/// Reads and scans the kernel log of the running system.
+#[cfg(feature = "hardware")]
+pub fn scan() -> Result<Vec<KmsgFinding>> {
+    Ok(findings(&parse_records(&read_kmsg()?)))
+}
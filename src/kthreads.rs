@@ -0,0 +1,148 @@
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// task_struct flag marking kernel threads (include/linux/sched.h).
+const PF_KTHREAD: u64 = 0x0020_0000;
+
+/// A kernel thread from /proc/<pid>/stat.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KernelThread {
+    pub pid: u32,
+    pub comm: String, // e.g. "irq/45-mmc0", "kworker/u8:2-events_unbound"
+    pub state: char,  // 'S', 'R', 'D', 'I', ..
+}
+
+/// Parses a /proc/<pid>/stat line into (thread, flags).
+///
+/// `comm` is wrapped in parentheses and may itself contain spaces and parentheses.
+pub fn parse_stat(line: &str) -> Option<(KernelThread, u64)> {
+    let (pid, rest) = line.split_once(" (")?;
+    let (comm, rest) = rest.rsplit_once(") ")?;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    // After comm: state(3) ppid(4) pgrp(5) session(6) tty_nr(7) tpgid(8) flags(9)
+    let flags = fields.get(6)?.parse().ok()?;
+    Some((
+        KernelThread {
+            pid: pid.trim().parse().ok()?,
+            comm: comm.to_string(),
+            state: fields.first()?.chars().next()?,
+        },
+        flags,
+    ))
+}
+
+/// Lists kernel threads.
+pub fn list_kernel_threads() -> Result<Vec<KernelThread>> {
+    list_kernel_threads_in(Path::new("/proc"))
+}
+
+/// Same as [`list_kernel_threads`], but for an arbitrary procfs directory.
+pub fn list_kernel_threads_in(proc_dir: &Path) -> Result<Vec<KernelThread>> {
+    let mut threads = Vec::new();
+    for entry in fs::read_dir(proc_dir)? {
+        let entry = entry?;
+        if entry
+            .file_name()
+            .to_str()
+            .is_none_or(|s| s.parse::<u32>().is_err())
+        {
+            continue;
+        }
+        // Threads can exit between readdir and read
+        if let Ok(stat) = fs::read_to_string(entry.path().join("stat"))
+            && let Some((thread, flags)) = parse_stat(stat.trim_end())
+            && flags & PF_KTHREAD != 0
+        {
+            threads.push(thread);
+        }
+    }
+    threads.sort_by_key(|t| t.pid);
+    Ok(threads)
+}
+
+/// Pids in uninterruptible sleep in both snapshots, i.e. likely stuck rather than busy.
+pub fn blocked_in_both(first: &[KernelThread], second: &[KernelThread]) -> Vec<u32> {
+    second
+        .iter()
+        .filter(|t| t.state == 'D')
+        .filter(|t| first.iter().any(|f| f.pid == t.pid && f.state == 'D'))
+        .map(|t| t.pid)
+        .collect()
+}
+
+/// Takes two snapshots `interval` apart; returns the second one and the pids blocked in both.
+pub fn sample_kernel_threads(interval: Duration) -> Result<(Vec<KernelThread>, Vec<u32>)> {
+    let first = list_kernel_threads()?;
+    std::thread::sleep(interval);
+    let second = list_kernel_threads()?;
+    let blocked = blocked_in_both(&first, &second);
+    Ok((second, blocked))
+}
+
+/// Tasks reported by the hung task detector: `INFO: task <comm>:<pid> blocked for more than`.
+pub fn hung_tasks_from_kmsg(records: &str) -> Vec<(String, u32)> {
+    records
+        .lines()
+        .filter_map(|line| {
+            let rest = line.split_once("INFO: task ")?.1;
+            let (task, _) = rest.split_once(" blocked for more than")?;
+            let (comm, pid) = task.rsplit_once(':')?;
+            Some((comm.to_string(), pid.parse().ok()?))
+        })
+        .collect()
+}
+
+/// An expected kernel thread. A trailing `*` in `name` matches any suffix, e.g. `mmcqd/*`.
+#[derive(Debug, Clone)]
+pub struct ExpectedKthread {
+    pub name: String,
+    pub min_count: usize, // e.g. one per CPU for "ksoftirqd/*"
+}
+
+impl ExpectedKthread {
+    pub fn matches(&self, comm: &str) -> bool {
+        match self.name.strip_suffix('*') {
+            Some(prefix) => comm.starts_with(prefix),
+            None => comm == self.name,
+        }
+    }
+}
+
+/// Checks expected threads exist and none of them is stuck or reported hung.
+pub fn validate_kthreads(
+    threads: &[KernelThread],
+    blocked: &[u32],
+    hung: &[(String, u32)],
+    expected: &[ExpectedKthread],
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    for exp in expected {
+        let matching: Vec<&KernelThread> =
+            threads.iter().filter(|t| exp.matches(&t.comm)).collect();
+        if matching.len() < exp.min_count.max(1) {
+            problems.push(format!(
+                "{}: {} thread(s) found (expected at least {})",
+                exp.name,
+                matching.len(),
+                exp.min_count.max(1)
+            ));
+        }
+        for thread in matching {
+            if blocked.contains(&thread.pid) {
+                problems.push(format!(
+                    "{} (pid {}): stuck in uninterruptible sleep",
+                    thread.comm, thread.pid
+                ));
+            }
+            if hung.iter().any(|(_, pid)| *pid == thread.pid) {
+                problems.push(format!(
+                    "{} (pid {}): reported by the hung task detector",
+                    thread.comm, thread.pid
+                ));
+            }
+        }
+    }
+    problems
+}
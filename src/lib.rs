@@ -1,2 +1,111 @@
+// Modules outside the `hardware` feature build for wasm32 too (`--no-default-features`),
+// e.g. for parsing, checking and diffing reports in the dashboard. Subsystem modules are behind
+// features of their own (`i2c`, `usb`, `pci`, `net`, `sensors`) that don't depend on each other.
+pub mod absence;
+pub mod annotation;
+pub mod audio;
+pub mod baseline;
+pub mod batch;
+pub mod boot_slot;
+#[cfg(feature = "hardware")]
+pub mod boot_time;
+pub mod brownout;
+pub mod calibration;
+pub mod clocks;
+#[cfg(feature = "hardware")]
+pub mod container_mode;
+#[cfg(feature = "hardware")]
+pub mod containers;
+pub mod cpu;
+pub mod crash;
+pub mod deferred_probe;
+pub mod derating;
+pub mod device;
+pub mod devicetree;
+pub mod discovery;
+pub mod eeprom;
+pub mod error;
+#[cfg(feature = "hardware")]
+pub mod ethtool;
+pub mod evidence;
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod field;
+#[cfg(feature = "hardware")]
+pub mod fixture;
+pub mod gpio;
+#[cfg(feature = "hardware")]
+pub mod gpio_expander;
+#[cfg(feature = "hardware")]
+pub mod hardening;
+#[cfg(feature = "sensors")]
+pub mod hwmon;
+#[cfg(feature = "i2c")]
 pub mod i2c;
+pub mod incremental;
+pub mod integrity;
+#[cfg(feature = "journald")]
+pub mod journal;
+pub mod kmsg;
+pub mod kthreads;
+pub mod lock;
+pub mod lsm;
+pub mod manifest;
+pub mod manifest_gen;
+pub mod measurement;
+pub mod media;
+pub mod memory;
+pub mod messages;
+#[cfg(feature = "hardware")]
+pub mod modem;
+pub mod modules;
+#[cfg(feature = "net")]
+pub mod net;
 pub mod os_release;
+pub mod ota;
+#[cfg(feature = "otel")]
+pub mod otel;
+#[cfg(feature = "pci")]
+pub mod pci;
+pub mod pinctrl;
+pub mod pmic;
+pub mod ptp;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod quarantine;
+pub mod registry;
+pub mod regulator;
+pub mod report;
+#[cfg(feature = "hardware")]
+pub mod rootfs;
+#[cfg(feature = "rpi")]
+pub mod rpi;
+pub mod rtc;
+pub mod runtime;
+pub mod safety;
+pub mod sampling;
+pub mod seed;
+#[cfg(feature = "hardware")]
+pub mod sfp;
+pub mod signing;
+pub mod soak;
+pub mod soc;
+pub mod sockets;
+pub mod spi;
+#[cfg(feature = "storage")]
+pub mod storage;
+pub mod system_root;
+pub mod teardown;
+#[cfg(feature = "hardware")]
+pub mod testing;
+#[cfg(feature = "sensors")]
+pub mod thermal;
+pub mod topology;
+#[cfg(feature = "i2c")]
+pub mod touch;
+#[cfg(feature = "usb")]
+pub mod usb;
+pub mod usb_serial;
+
+pub use error::Error;
@@ -0,0 +1,184 @@
+use crate::error::Error;
+use anyhow::Result;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Where lock files live; /run is a tmpfs, so stale files vanish on reboot.
+pub const LOCK_DIR: &str = "/run/tux-validation";
+
+/// Lock directory below `$XDG_RUNTIME_DIR`, for users who may probe a bus but not write
+/// [`LOCK_DIR`], e.g. members of the `i2c` group.
+pub fn user_lock_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join("tux-validation"))
+}
+
+/// Something only one process may drive at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    Global, // Excludes every other lock holder
+    I2cBus(u32),
+}
+
+impl Resource {
+    fn file_name(self) -> String {
+        match self {
+            Resource::Global => "global.lock".to_string(),
+            Resource::I2cBus(bus) => format!("i2c-{}.lock", bus),
+        }
+    }
+
+    fn label(self) -> String {
+        match self {
+            Resource::Global => "global lock".to_string(),
+            Resource::I2cBus(bus) => format!("i2c-{}", bus),
+        }
+    }
+}
+
+/// An advisory (flock) claim on a resource, released on drop or process exit.
+///
+/// Bus locks also hold the global lock shared, so a global claim waits for all of them.
+#[derive(Debug)]
+pub struct ResourceLock {
+    pub resource: Resource,
+    files: Vec<File>, // Exclusively held file last
+}
+
+impl Drop for ResourceLock {
+    /// Clears the PID so a later "held by" error can't name a process that is long gone.
+    fn drop(&mut self) {
+        if let Some(file) = self.files.last() {
+            let _ = file.set_len(0);
+        }
+    }
+}
+
+impl ResourceLock {
+    /// Claims `resource` under [`LOCK_DIR`] without blocking.
+    ///
+    /// Without write access there, falls back to [`user_lock_dir`]; those locks only exclude
+    /// processes of the same user. Fails with [`Error::PermissionDenied`] if neither works.
+    pub fn acquire(resource: Resource) -> Result<ResourceLock> {
+        match ResourceLock::acquire_in(Path::new(LOCK_DIR), resource) {
+            Err(e) if matches!(Error::of(&e), Some(Error::PermissionDenied { .. })) => {
+                let Some(dir) = user_lock_dir() else {
+                    return Err(e);
+                };
+                tracing::debug!(dir = %dir.display(), "no access to {}, locking per user", LOCK_DIR);
+                ResourceLock::acquire_in(&dir, resource).map_err(|fallback| {
+                    match Error::of(&fallback) {
+                        Some(Error::PermissionDenied { .. } | Error::Io { .. }) => e,
+                        _ => fallback, // Busy
+                    }
+                })
+            }
+            result => result,
+        }
+    }
+
+    /// Same as [`ResourceLock::acquire`], with an explicit lock directory and no fallback.
+    pub fn acquire_in(dir: &Path, resource: Resource) -> Result<ResourceLock> {
+        fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+        let global_path = dir.join(Resource::Global.file_name());
+        let global = open(&global_path)?;
+        let mut files = Vec::new();
+        match resource {
+            Resource::Global => {
+                if let Err(e) = global.try_lock() {
+                    let mut holders = bus_holders(dir);
+                    if holders.is_empty() {
+                        holders.extend(holder_pid(&global_path).map(|pid| format!("PID {}", pid)));
+                    }
+                    return Err(busy(resource, e, &holders));
+                }
+                write_pid(&global, &global_path)?;
+                files.push(global);
+            }
+            Resource::I2cBus(_) => {
+                if let Err(e) = global.try_lock_shared() {
+                    let holders: Vec<String> = holder_pid(&global_path)
+                        .map(|pid| format!("PID {} (global lock)", pid))
+                        .into_iter()
+                        .collect();
+                    return Err(busy(resource, e, &holders));
+                }
+                let path = dir.join(resource.file_name());
+                let file = open(&path)?;
+                if let Err(e) = file.try_lock() {
+                    let holders: Vec<String> = holder_pid(&path)
+                        .map(|pid| format!("PID {}", pid))
+                        .into_iter()
+                        .collect();
+                    return Err(busy(resource, e, &holders));
+                }
+                write_pid(&file, &path)?;
+                files.extend([global, file]);
+            }
+        }
+        Ok(ResourceLock { resource, files })
+    }
+}
+
+fn open(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|e| Error::io(path, e).into())
+}
+
+/// Records the holder for "held by" errors; only ever called with the lock held exclusively.
+fn write_pid(mut file: &File, path: &Path) -> Result<()> {
+    file.set_len(0)
+        .and_then(|()| file.write_all(format!("{}\n", std::process::id()).as_bytes()))
+        .map_err(|e| Error::io(path, e).into())
+}
+
+fn holder_pid(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// PIDs holding bus locks, for explaining why the global lock is busy.
+fn bus_holders(dir: &Path) -> Vec<String> {
+    let mut holders: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.file_name().is_some_and(|n| n != "global.lock"))
+        .filter(|p| {
+            open(p).is_ok_and(|f| matches!(f.try_lock_shared(), Err(TryLockError::WouldBlock)))
+        })
+        .collect();
+    holders.sort();
+    holders
+        .iter()
+        .map(|p| {
+            let name = p.file_stem().unwrap_or_default().to_string_lossy();
+            match holder_pid(p) {
+                Some(pid) => format!("PID {} ({})", pid, name),
+                None => name.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn busy(resource: Resource, error: TryLockError, holders: &[String]) -> anyhow::Error {
+    match error {
+        TryLockError::WouldBlock if holders.is_empty() => {
+            anyhow::anyhow!("{} is busy, held by another process", resource.label())
+        }
+        TryLockError::WouldBlock => {
+            anyhow::anyhow!(
+                "{} is busy, held by {}",
+                resource.label(),
+                holders.join(", ")
+            )
+        }
+        TryLockError::Error(e) => anyhow::anyhow!("Cannot lock {}: {}", resource.label(), e),
+    }
+}
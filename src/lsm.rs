@@ -0,0 +1,149 @@
+use anyhow::Result;
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+
+/// SELinux state from selinuxfs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelinuxStatus {
+    pub enforcing: bool,
+    pub policy_version: Option<u32>,
+}
+
+/// A loaded AppArmor profile and its mode ("enforce", "complain", "kill", ..).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppArmorProfile {
+    pub name: String,
+    pub mode: String,
+}
+
+/// AppArmor state from securityfs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppArmorStatus {
+    pub profiles: Vec<AppArmorProfile>,
+    pub policy_revision: Option<u64>, // Bumped on every policy load/replace
+}
+
+/// Active Linux Security Modules and their state.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LsmStatus {
+    pub active: Vec<String>, // e.g. ["lockdown", "capability", "apparmor"]
+    pub selinux: Option<SelinuxStatus>,
+    pub apparmor: Option<AppArmorStatus>,
+}
+
+fn read_attr(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Parses /sys/kernel/security/apparmor/profiles (`name (mode)` per line).
+pub fn parse_apparmor_profiles_from_reader<R: BufRead>(reader: R) -> Result<Vec<AppArmorProfile>> {
+    let mut profiles = Vec::new();
+    for line_result in reader.lines() {
+        let line = line_result?;
+        // Profile names may contain spaces, the mode is always the last parenthesised word
+        if let Some((name, mode)) = line.trim().rsplit_once(" (") {
+            profiles.push(AppArmorProfile {
+                name: name.to_string(),
+                mode: mode.trim_end_matches(')').to_string(),
+            });
+        }
+    }
+    Ok(profiles)
+}
+
+/// Reads the LSM status from the running kernel. Requires securityfs/selinuxfs.
+pub fn read_status() -> Result<LsmStatus> {
+    read_status_in(Path::new("/sys"))
+}
+
+/// Same as [`read_status`], but below an arbitrary sysfs root.
+pub fn read_status_in(sys_dir: &Path) -> Result<LsmStatus> {
+    let security_dir = sys_dir.join("kernel/security");
+    let mut status = LsmStatus {
+        active: read_attr(&security_dir.join("lsm"))
+            .map(|s| s.split(',').map(|l| l.to_string()).collect())
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let selinux_dir = sys_dir.join("fs/selinux");
+    if let Some(enforce) = read_attr(&selinux_dir.join("enforce")) {
+        status.selinux = Some(SelinuxStatus {
+            enforcing: enforce == "1",
+            policy_version: read_attr(&selinux_dir.join("policyvers")).and_then(|s| s.parse().ok()),
+        });
+    }
+
+    let apparmor_dir = security_dir.join("apparmor");
+    if apparmor_dir.is_dir() {
+        let profiles = match fs::File::open(apparmor_dir.join("profiles")) {
+            Ok(f) => parse_apparmor_profiles_from_reader(std::io::BufReader::new(f))?,
+            Err(_) => Vec::new(),
+        };
+        status.apparmor = Some(AppArmorStatus {
+            profiles,
+            policy_revision: read_attr(&apparmor_dir.join("revision")).and_then(|s| s.parse().ok()),
+        });
+    }
+    Ok(status)
+}
+
+/// Which LSM a rule applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lsm {
+    Selinux,
+    AppArmor,
+}
+
+/// A rule such as "AppArmor must be enforcing with profile set X loaded".
+#[derive(Debug, Clone)]
+pub struct LsmRule {
+    pub lsm: Lsm,
+    pub enforcing: bool,
+    pub required_profiles: Vec<String>,  // AppArmor only
+    pub min_policy_version: Option<u32>, // SELinux only
+}
+
+/// Returns human-readable descriptions of every rule violation.
+pub fn validate_status(status: &LsmStatus, rules: &[LsmRule]) -> Vec<String> {
+    let mut violations = Vec::new();
+
+    for rule in rules {
+        match rule.lsm {
+            Lsm::Selinux => {
+                let Some(selinux) = &status.selinux else {
+                    violations.push("SELinux not active".to_string());
+                    continue;
+                };
+                if rule.enforcing && !selinux.enforcing {
+                    violations.push("SELinux is permissive".to_string());
+                }
+                if let Some(min) = rule.min_policy_version
+                    && selinux.policy_version.is_none_or(|v| v < min)
+                {
+                    violations.push(format!(
+                        "SELinux policy version {:?} (expected at least {})",
+                        selinux.policy_version, min
+                    ));
+                }
+            }
+            Lsm::AppArmor => {
+                let Some(apparmor) = &status.apparmor else {
+                    violations.push("AppArmor not active".to_string());
+                    continue;
+                };
+                for name in &rule.required_profiles {
+                    match apparmor.profiles.iter().find(|p| &p.name == name) {
+                        None => violations.push(format!("AppArmor profile {} not loaded", name)),
+                        Some(p) if rule.enforcing && p.mode != "enforce" => {
+                            violations.push(format!("AppArmor profile {} in {} mode", name, p.mode))
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+    }
+    violations
+}
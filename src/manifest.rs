@@ -0,0 +1,458 @@
+use crate::derating::Threshold;
+use crate::device::{DeviceAddress, TuxBus};
+use crate::error::{self, Error};
+use crate::registry;
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use yaml_rust2::{Yaml, YamlLoader};
+
+/// An I2C device the board must (or may) have, as in the `[[i2c]]` tables of a manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestDevice {
+    pub bus: u32,
+    pub address: u16,
+    pub name: String,
+    pub driver: Option<String>,
+    pub severity: String,              // "error", "warning" or "info"
+    pub required: bool,                // An optional device may be absent, e.g. a population option
+    pub registers: Vec<RegisterCheck>, // Identity registers, e.g. WHO_AM_I
+}
+
+/// A register the right part answers with a known value, e.g. 0x71 from an MPU-9250's
+/// WHO_AM_I at 0x75; only the `mask` bits are compared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegisterCheck {
+    pub register: u8,
+    pub value: u8,
+    pub mask: u8, // 0xff unless the register also holds a revision
+}
+
+impl RegisterCheck {
+    pub fn matches(&self, actual: u8) -> bool {
+        actual & self.mask == self.value & self.mask
+    }
+}
+
+/// An identity register that read back something else.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegisterMismatch {
+    pub check: RegisterCheck,
+    pub actual: u8,
+}
+
+impl std::fmt::Display for RegisterMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "register 0x{:02x} reads 0x{:02x} (expected 0x{:02x}",
+            self.check.register, self.actual, self.check.value
+        )?;
+        if self.check.mask != 0xff {
+            write!(f, " under mask 0x{:02x}", self.check.mask)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Checks every register with `read`, stopping at the first read that fails.
+pub fn verify_registers_with(
+    checks: &[RegisterCheck],
+    mut read: impl FnMut(u8) -> Result<u8>,
+) -> Result<Vec<RegisterMismatch>> {
+    let mut mismatches = Vec::new();
+    for check in checks {
+        let actual = read(check.register)?;
+        if !check.matches(actual) {
+            mismatches.push(RegisterMismatch {
+                check: *check,
+                actual,
+            });
+        }
+    }
+    Ok(mismatches)
+}
+
+fn parse_register_checks(i: usize, value: Option<&Value>) -> Result<Vec<RegisterCheck>> {
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+    let Some(checks) = value.as_array() else {
+        anyhow::bail!("i2c entry {}: `registers` must be an array of tables", i);
+    };
+    checks
+        .iter()
+        .map(|check| {
+            let byte = |key: &str| -> Result<Option<u8>> {
+                match check.get(key) {
+                    None => Ok(None),
+                    Some(v) => v
+                        .as_u64()
+                        .and_then(|v| u8::try_from(v).ok())
+                        .map(Some)
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("i2c entry {}: register `{}` must be a byte", i, key)
+                        }),
+                }
+            };
+            let (Some(register), Some(value)) = (byte("register")?, byte("value")?) else {
+                anyhow::bail!("i2c entry {}: registers need `register` and `value`", i);
+            };
+            Ok(RegisterCheck {
+                register,
+                value,
+                mask: byte("mask")?.unwrap_or(0xff),
+            })
+        })
+        .collect()
+}
+
+fn parse_error(e: anyhow::Error) -> Error {
+    Error::ParseError {
+        what: "manifest".to_string(),
+        message: e.to_string(),
+    }
+}
+
+/// An expected-hardware description of a board.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Manifest {
+    pub i2c: Vec<ManifestDevice>,
+    pub sections: BTreeMap<String, Value>, // Raw tables of the other checks, by check ID
+}
+
+impl Manifest {
+    /// Parses the TOML format written by [`crate::manifest_gen::i2c_manifest_fragment`].
+    ///
+    /// Every top-level section must be the ID of a check in [`registry::CHECKS`].
+    pub fn from_toml_str(text: &str) -> error::Result<Manifest> {
+        let parse = || -> Result<Manifest> {
+            let table: toml::Table = text.parse()?;
+            Manifest::from_value(&serde_json::to_value(table)?)
+        };
+        parse().map_err(parse_error)
+    }
+
+    /// Same layout as the TOML format, written as YAML (`i2c:` holding a list of mappings).
+    pub fn from_yaml_str(text: &str) -> error::Result<Manifest> {
+        let parse = || -> Result<Manifest> {
+            let docs = YamlLoader::load_from_str(text)?;
+            match docs.first() {
+                Some(doc) => Manifest::from_value(&yaml_to_json(doc)?),
+                None => Ok(Manifest::default()),
+            }
+        };
+        parse().map_err(parse_error)
+    }
+
+    fn from_value(value: &Value) -> Result<Manifest> {
+        let Some(table) = value.as_object() else {
+            anyhow::bail!("manifest must be a table of checks");
+        };
+        let unknown = registry::unknown_ids(table.keys().map(|k| k.as_str()));
+        if !unknown.is_empty() {
+            anyhow::bail!("Unknown check(s) in manifest: {}", unknown.join(", "));
+        }
+        let mut manifest = Manifest {
+            sections: table
+                .iter()
+                .filter(|(id, _)| id.as_str() != "i2c")
+                .map(|(id, section)| (id.clone(), section.clone()))
+                .collect(),
+            ..Default::default()
+        };
+        let Some(devices) = table.get("i2c") else {
+            return Ok(manifest);
+        };
+        let Some(devices) = devices.as_array() else {
+            anyhow::bail!("`i2c` must be an array of tables");
+        };
+        for (i, device) in devices.iter().enumerate() {
+            let int = |key: &str| -> Result<u64> {
+                device
+                    .get(key)
+                    .and_then(|v| v.as_u64())
+                    .ok_or_else(|| anyhow::anyhow!("i2c entry {}: missing integer `{}`", i, key))
+            };
+            let string = |key: &str| device.get(key).and_then(|v| v.as_str()).map(String::from);
+            let required = match device.get("required") {
+                None => true,
+                Some(v) => v.as_bool().ok_or_else(|| {
+                    anyhow::anyhow!("i2c entry {}: `required` must be a boolean", i)
+                })?,
+            };
+            manifest.i2c.push(ManifestDevice {
+                bus: u32::try_from(int("bus")?)?,
+                address: u16::try_from(int("address")?)?,
+                name: string("name").unwrap_or_default(),
+                driver: string("driver"),
+                severity: string("severity").unwrap_or_else(|| "error".to_string()),
+                required,
+                registers: parse_register_checks(i, device.get("registers"))?,
+            });
+        }
+        Ok(manifest)
+    }
+
+    /// Loads a manifest; `.yaml`/`.yml` files are YAML, anything else TOML.
+    pub fn load(path: &Path) -> error::Result<Manifest> {
+        let yaml = matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yaml" | "yml")
+        );
+        let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        let manifest = if yaml {
+            Manifest::from_yaml_str(&text)
+        } else {
+            Manifest::from_toml_str(&text)
+        };
+        manifest.map_err(|e| match e {
+            Error::ParseError { message, .. } => Error::ParseError {
+                what: path.display().to_string(),
+                message,
+            },
+            e => e,
+        })
+    }
+
+    /// A numeric parameter of a check section, possibly derated by temperature.
+    pub fn threshold(&self, check: &str, param: &str) -> Result<Option<Threshold>> {
+        match self.sections.get(check).and_then(|s| s.get(param)) {
+            Some(value) => Threshold::from_value(value)
+                .map(Some)
+                .map_err(|e| anyhow::anyhow!("{}.{}: {}", check, param, e)),
+            None => Ok(None),
+        }
+    }
+
+    /// The `timeout` of a check section, after which its runner gives up and tears down.
+    pub fn timeout(&self, check: &str) -> Result<Option<Duration>> {
+        match self.sections.get(check).and_then(|s| s.get("timeout")) {
+            Some(value) => parse_duration(value)
+                .map(Some)
+                .map_err(|e| anyhow::anyhow!("{}.timeout: {}", check, e)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Parses a `duration` parameter: seconds as a number, or a string such as "500ms", "30s",
+/// "5m" or "1h".
+pub fn parse_duration(value: &Value) -> Result<Duration> {
+    if let Some(seconds) = value.as_f64() {
+        return Duration::try_from_secs_f64(seconds).map_err(|e| anyhow::anyhow!("{}", e));
+    }
+    let Some(text) = value.as_str() else {
+        anyhow::bail!("duration must be seconds or a string such as \"30s\"");
+    };
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("not a duration: {:?}", text))?;
+    let scale = match unit.trim() {
+        "ms" => 0.001,
+        "s" | "" => 1.0,
+        "m" | "min" => 60.0,
+        "h" => 3600.0,
+        unit => anyhow::bail!("unknown duration unit {:?} in {:?}", unit, text),
+    };
+    Duration::try_from_secs_f64(number * scale).map_err(|e| anyhow::anyhow!("{}: {}", text, e))
+}
+
+fn yaml_to_json(yaml: &Yaml) -> Result<Value> {
+    Ok(match yaml {
+        Yaml::Null => Value::Null,
+        Yaml::Boolean(b) => json!(b),
+        Yaml::Integer(i) => json!(i),
+        Yaml::Real(_) => json!(yaml.as_f64()),
+        Yaml::String(s) => json!(s),
+        Yaml::Array(items) => Value::Array(items.iter().map(yaml_to_json).collect::<Result<_>>()?),
+        Yaml::Hash(hash) => {
+            let mut map = serde_json::Map::new();
+            for (key, value) in hash {
+                let Some(key) = key.as_str() else {
+                    anyhow::bail!("manifest keys must be strings");
+                };
+                map.insert(key.to_string(), yaml_to_json(value)?);
+            }
+            Value::Object(map)
+        }
+        _ => anyhow::bail!("unsupported YAML value in manifest"),
+    })
+}
+
+/// A manifest entry the audited board doesn't satisfy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestFinding {
+    pub address: DeviceAddress,
+    pub severity: String,
+    pub message: String,
+}
+
+/// Outcome of checking a board against a manifest.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ManifestResult {
+    pub present: Vec<ManifestDevice>, // Found with the expected driver
+    pub absent_optional: Vec<ManifestDevice>,
+    pub findings: Vec<ManifestFinding>,
+}
+
+impl ManifestResult {
+    /// Fails on any finding with severity "error".
+    pub fn is_ok(&self) -> bool {
+        !self.findings.iter().any(|f| f.severity == "error")
+    }
+
+    /// [`report_json`] plus the addresses that passed or were optional and absent.
+    pub fn to_json(&self) -> Value {
+        let addresses = |devices: &[ManifestDevice]| -> Vec<String> {
+            devices
+                .iter()
+                .map(|d| {
+                    DeviceAddress::I2c {
+                        bus: d.bus,
+                        addr: d.address,
+                    }
+                    .to_string()
+                })
+                .collect()
+        };
+        let mut report = report_json(&self.findings);
+        report["present"] = json!(addresses(&self.present));
+        report["absent_optional"] = json!(addresses(&self.absent_optional));
+        report
+    }
+}
+
+/// Checks every manifest device against audited buses, see [`crate::i2c::audit_all_i2c_buses`].
+///
+/// A missing optional device is not a finding; a wrong driver is, even on an optional device.
+pub fn validate(manifest: &Manifest, buses: &[TuxBus]) -> ManifestResult {
+    let mut result = ManifestResult::default();
+    for expected in &manifest.i2c {
+        let address = DeviceAddress::I2c {
+            bus: expected.bus,
+            addr: expected.address,
+        };
+        let finding = |message: String| ManifestFinding {
+            address: address.clone(),
+            severity: expected.severity.clone(),
+            message,
+        };
+        let bus_id = format!("i2c-{}", expected.bus);
+        let found = buses
+            .iter()
+            .flat_map(|b| b.devices.iter())
+            .find(|d| d.address == address);
+        match found {
+            None if !expected.required => result.absent_optional.push(expected.clone()),
+            None if !buses.iter().any(|b| b.id == bus_id) => result.findings.push(finding(
+                format!("{} not found ({} missing)", expected.name, bus_id),
+            )),
+            None => result
+                .findings
+                .push(finding(format!("{} not found", expected.name))),
+            Some(device) => match &expected.driver {
+                Some(driver) if device.driver.is_none() && device.probe_deferred => {
+                    result.findings.push(finding(format!(
+                        "{} probe deferred: {} is waiting on a dependency",
+                        expected.name, driver
+                    )))
+                }
+                Some(driver) if device.driver.as_ref() != Some(driver) => {
+                    result.findings.push(finding(format!(
+                        "{} bound to {} (expected {})",
+                        expected.name,
+                        device.driver.as_deref().unwrap_or("no driver"),
+                        driver
+                    )))
+                }
+                _ => result.present.push(expected.clone()),
+            },
+        }
+    }
+    result
+}
+
+/// Reads the identity registers of every found manifest device that has them, see
+/// [`crate::i2c::verify_registers`]; a mismatch or failed read turns a present device into
+/// a finding, since address presence alone doesn't prove the right part is fitted.
+pub fn verify_identities(
+    manifest: &Manifest,
+    buses: &[TuxBus],
+    result: &mut ManifestResult,
+    mut verify: impl FnMut(&ManifestDevice) -> Result<Vec<RegisterMismatch>>,
+) {
+    for expected in manifest.i2c.iter().filter(|d| !d.registers.is_empty()) {
+        let address = DeviceAddress::I2c {
+            bus: expected.bus,
+            addr: expected.address,
+        };
+        if !buses
+            .iter()
+            .any(|b| b.devices.iter().any(|d| d.address == address))
+        {
+            continue; // Already a finding, or an optional device not fitted
+        }
+        let problems: Vec<String> = match verify(expected) {
+            Ok(mismatches) => mismatches.iter().map(|m| m.to_string()).collect(),
+            Err(e) => vec![format!("identity not readable: {}", e)],
+        };
+        if problems.is_empty() {
+            continue;
+        }
+        result.present.retain(|d| d != expected);
+        result.findings.push(ManifestFinding {
+            address,
+            severity: expected.severity.clone(),
+            message: format!("{} {}", expected.name, problems.join("; ")),
+        });
+    }
+}
+
+/// Findings of [`validate`].
+pub fn check_buses(manifest: &Manifest, buses: &[TuxBus]) -> Vec<ManifestFinding> {
+    validate(manifest, buses).findings
+}
+
+/// Discovers the board, probing with the manifest's `[i2c_probe]` modes, and validates it
+/// against the manifest. Identity registers are read only with hardware probing, as they
+/// too put traffic on the bus.
+#[cfg(feature = "i2c")]
+pub fn run(manifest: &Manifest, enable_hw_probe: bool) -> Result<ManifestResult> {
+    let board = crate::discovery::discover_board_in(&crate::discovery::DiscoveryContext {
+        hw_probe: enable_hw_probe,
+        manifest: Some(manifest.clone()),
+        ..Default::default()
+    })?;
+    let mut result = validate(manifest, &board.buses);
+    if enable_hw_probe {
+        verify_identities(manifest, &board.buses, &mut result, |device| {
+            crate::i2c::verify_registers(device.bus, device.address, &device.registers)
+        });
+    }
+    Ok(result)
+}
+
+/// JSON report of a manifest run; it fails on any finding with severity "error".
+pub fn report_json(findings: &[ManifestFinding]) -> Value {
+    let passed = !findings.iter().any(|f| f.severity == "error");
+    json!({
+        "passed": passed,
+        "findings": findings
+            .iter()
+            .map(|f| json!({
+                "address": f.address.to_string(),
+                "severity": f.severity,
+                "message": f.message,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
@@ -0,0 +1,62 @@
+use crate::device::{DeviceAddress, TuxBus, TuxDevice};
+
+/// Suggested severity for a device found in a live scan.
+///
+/// Bound devices are what the board is meant to have, so losing one is an error; declared
+/// but unbound devices usually point at a driver issue; ghosts need a human to decide.
+pub fn suggested_severity(device: &TuxDevice) -> &'static str {
+    if device.is_bound() {
+        "error"
+    } else if device.sysfs_path.is_some() {
+        "warning"
+    } else {
+        "info"
+    }
+}
+
+fn toml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Renders the audited I2C buses as a ready-to-edit TOML manifest fragment.
+///
+/// One `[[i2c]]` table per device; comments explain the suggested severity.
+pub fn i2c_manifest_fragment(buses: &[TuxBus]) -> String {
+    let mut out = String::from(
+        "# Generated from a live scan; review names, drivers and severities before use.\n",
+    );
+    for bus in buses {
+        out.push_str(&format!(
+            "\n# {}{}\n",
+            bus.id,
+            if bus.name.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", bus.name)
+            }
+        ));
+        for device in &bus.devices {
+            let DeviceAddress::I2c { bus: bus_id, addr } = device.address else {
+                continue;
+            };
+            out.push_str("[[i2c]]\n");
+            out.push_str(&format!("bus = {}\naddress = 0x{:02x}\n", bus_id, addr));
+            out.push_str(&format!("name = {}\n", toml_string(&device.name)));
+            match &device.driver {
+                Some(driver) => out.push_str(&format!("driver = {}\n", toml_string(driver))),
+                None => out.push_str("# driver = \"\"  # no driver bound during scan\n"),
+            }
+            let note = if device.is_ghost() {
+                "  # responds to probe but not declared to the kernel"
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "severity = \"{}\"{}\n\n",
+                suggested_severity(device),
+                note
+            ));
+        }
+    }
+    out
+}
@@ -0,0 +1,218 @@
+use std::fmt;
+
+/// Physical quantity a unit measures; measurements only convert within a quantity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Quantity {
+    Temperature,
+    Voltage,
+    Current,
+    Power,
+    Frequency,
+    Time,
+    Throughput,
+    Data,
+    Ratio,
+    Count,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    MilliCelsius,
+    Celsius,
+    Millivolt,
+    Volt,
+    Microampere,
+    Milliampere,
+    Ampere,
+    Microwatt,
+    Milliwatt,
+    Watt,
+    Dbm, // Logarithmic; converts to/from the linear power units
+    Hertz,
+    Kilohertz,
+    Megahertz,
+    Nanosecond,
+    Microsecond,
+    Millisecond,
+    Second,
+    BitsPerSecond,
+    MegabitsPerSecond,
+    Byte,
+    Kibibyte,
+    Mebibyte,
+    Percent,
+    Count,
+}
+
+impl Unit {
+    /// Quantity and factor to the quantity's base unit (°C, V, A, W, Hz, s, bit/s, B, %, 1).
+    fn scale(self) -> (Quantity, f64) {
+        match self {
+            Unit::MilliCelsius => (Quantity::Temperature, 1e-3),
+            Unit::Celsius => (Quantity::Temperature, 1.0),
+            Unit::Millivolt => (Quantity::Voltage, 1e-3),
+            Unit::Volt => (Quantity::Voltage, 1.0),
+            Unit::Microampere => (Quantity::Current, 1e-6),
+            Unit::Milliampere => (Quantity::Current, 1e-3),
+            Unit::Ampere => (Quantity::Current, 1.0),
+            Unit::Microwatt => (Quantity::Power, 1e-6),
+            Unit::Milliwatt | Unit::Dbm => (Quantity::Power, 1e-3),
+            Unit::Watt => (Quantity::Power, 1.0),
+            Unit::Hertz => (Quantity::Frequency, 1.0),
+            Unit::Kilohertz => (Quantity::Frequency, 1e3),
+            Unit::Megahertz => (Quantity::Frequency, 1e6),
+            Unit::Nanosecond => (Quantity::Time, 1e-9),
+            Unit::Microsecond => (Quantity::Time, 1e-6),
+            Unit::Millisecond => (Quantity::Time, 1e-3),
+            Unit::Second => (Quantity::Time, 1.0),
+            Unit::BitsPerSecond => (Quantity::Throughput, 1.0),
+            Unit::MegabitsPerSecond => (Quantity::Throughput, 1e6),
+            Unit::Byte => (Quantity::Data, 1.0),
+            Unit::Kibibyte => (Quantity::Data, 1024.0),
+            Unit::Mebibyte => (Quantity::Data, 1024.0 * 1024.0),
+            Unit::Percent => (Quantity::Ratio, 1.0),
+            Unit::Count => (Quantity::Count, 1.0),
+        }
+    }
+
+    pub fn quantity(self) -> Quantity {
+        self.scale().0
+    }
+
+    /// Every unit, e.g. for looking one up by symbol.
+    pub const ALL: [Unit; 25] = [
+        Unit::MilliCelsius,
+        Unit::Celsius,
+        Unit::Millivolt,
+        Unit::Volt,
+        Unit::Microampere,
+        Unit::Milliampere,
+        Unit::Ampere,
+        Unit::Microwatt,
+        Unit::Milliwatt,
+        Unit::Watt,
+        Unit::Dbm,
+        Unit::Hertz,
+        Unit::Kilohertz,
+        Unit::Megahertz,
+        Unit::Nanosecond,
+        Unit::Microsecond,
+        Unit::Millisecond,
+        Unit::Second,
+        Unit::BitsPerSecond,
+        Unit::MegabitsPerSecond,
+        Unit::Byte,
+        Unit::Kibibyte,
+        Unit::Mebibyte,
+        Unit::Percent,
+        Unit::Count,
+    ];
+
+    /// Inverse of [`Unit::symbol`]; "us" is accepted for µs.
+    pub fn from_symbol(symbol: &str) -> Option<Unit> {
+        let symbol = match symbol {
+            "us" => "µs",
+            "uA" => "µA",
+            "uW" => "µW",
+            s => s,
+        };
+        Unit::ALL.into_iter().find(|u| u.symbol() == symbol)
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Unit::MilliCelsius => "m°C",
+            Unit::Celsius => "°C",
+            Unit::Millivolt => "mV",
+            Unit::Volt => "V",
+            Unit::Microampere => "µA",
+            Unit::Milliampere => "mA",
+            Unit::Ampere => "A",
+            Unit::Microwatt => "µW",
+            Unit::Milliwatt => "mW",
+            Unit::Watt => "W",
+            Unit::Dbm => "dBm",
+            Unit::Hertz => "Hz",
+            Unit::Kilohertz => "kHz",
+            Unit::Megahertz => "MHz",
+            Unit::Nanosecond => "ns",
+            Unit::Microsecond => "µs",
+            Unit::Millisecond => "ms",
+            Unit::Second => "s",
+            Unit::BitsPerSecond => "bit/s",
+            Unit::MegabitsPerSecond => "Mbit/s",
+            Unit::Byte => "B",
+            Unit::Kibibyte => "KiB",
+            Unit::Mebibyte => "MiB",
+            Unit::Percent => "%",
+            Unit::Count => "",
+        }
+    }
+}
+
+/// A value with its unit and where it came from (e.g. "hwmon2/in1_input").
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub value: f64,
+    pub unit: Unit,
+    pub source: String,
+}
+
+impl Measurement {
+    pub fn new(value: f64, unit: Unit, source: &str) -> Measurement {
+        Measurement {
+            value,
+            unit,
+            source: source.to_string(),
+        }
+    }
+
+    pub fn quantity(&self) -> Quantity {
+        self.unit.quantity()
+    }
+
+    /// Converts to another unit of the same quantity.
+    pub fn convert_to(&self, unit: Unit) -> Option<Measurement> {
+        let (from_quantity, from_scale) = self.unit.scale();
+        let (to_quantity, to_scale) = unit.scale();
+        if from_quantity != to_quantity {
+            return None;
+        }
+        // dBm is 10*log10(P / 1 mW)
+        let linear = match self.unit {
+            Unit::Dbm => 10f64.powf(self.value / 10.0),
+            _ => self.value,
+        } * from_scale;
+        let value = match unit {
+            Unit::Dbm => 10.0 * (linear / to_scale).log10(),
+            _ => linear / to_scale,
+        };
+        Some(Measurement {
+            value,
+            unit,
+            source: self.source.clone(),
+        })
+    }
+
+    /// Value in `unit`, if convertible.
+    pub fn value_in(&self, unit: Unit) -> Option<f64> {
+        self.convert_to(unit).map(|m| m.value)
+    }
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.unit {
+            Unit::Count => write!(f, "{}", self.value),
+            unit => write!(f, "{} {}", self.value, unit.symbol()),
+        }
+    }
+}
+
+/// Measurements of one quantity, e.g. every voltage in a report.
+pub fn of_quantity(measurements: &[Measurement], quantity: Quantity) -> Vec<&Measurement> {
+    measurements
+        .iter()
+        .filter(|m| m.quantity() == quantity)
+        .collect()
+}
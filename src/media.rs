@@ -0,0 +1,851 @@
+use std::fmt;
+
+/// Colour bars of [`test_pattern`], left to right.
+pub const BARS: [(&str, [u8; 3]); 8] = [
+    ("white", [255, 255, 255]),
+    ("yellow", [255, 255, 0]),
+    ("cyan", [0, 255, 255]),
+    ("green", [0, 255, 0]),
+    ("magenta", [255, 0, 255]),
+    ("red", [255, 0, 0]),
+    ("blue", [0, 0, 255]),
+    ("black", [0, 0, 0]),
+];
+
+/// Default per-channel tolerance when matching captured bars; captures are scaled and
+/// compressed.
+pub const DEFAULT_TOLERANCE: u8 = 64;
+
+/// Layout of a captured or rendered frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    Yuyv,     // V4L2_PIX_FMT_YUYV, packed 4:2:2
+    Rgb24,    // V4L2_PIX_FMT_RGB24
+    Xrgb8888, // DRM_FORMAT_XRGB8888, little-endian B, G, R, X
+}
+
+impl PixelFormat {
+    /// V4L2 fourcc.
+    pub fn fourcc(self) -> u32 {
+        let code = match self {
+            PixelFormat::Yuyv => b"YUYV",
+            PixelFormat::Rgb24 => b"RGB3",
+            PixelFormat::Xrgb8888 => b"XR24",
+        };
+        u32::from_le_bytes(*code)
+    }
+
+    pub fn from_fourcc(fourcc: u32) -> Option<PixelFormat> {
+        [PixelFormat::Yuyv, PixelFormat::Rgb24, PixelFormat::Xrgb8888]
+            .into_iter()
+            .find(|f| f.fourcc() == fourcc)
+    }
+}
+
+/// One frame of pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub stride: u32, // Bytes per line
+    pub format: PixelFormat,
+    pub data: Vec<u8>,
+}
+
+fn clamp(value: f64) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+impl Frame {
+    /// Colour of a pixel; None outside the frame or the data.
+    pub fn rgb_at(&self, x: u32, y: u32) -> Option<[u8; 3]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let line = (y * self.stride) as usize;
+        match self.format {
+            PixelFormat::Rgb24 => {
+                let i = line + x as usize * 3;
+                Some([
+                    *self.data.get(i)?,
+                    *self.data.get(i + 1)?,
+                    *self.data.get(i + 2)?,
+                ])
+            }
+            PixelFormat::Xrgb8888 => {
+                let i = line + x as usize * 4;
+                Some([
+                    *self.data.get(i + 2)?,
+                    *self.data.get(i + 1)?,
+                    *self.data.get(i)?,
+                ])
+            }
+            PixelFormat::Yuyv => {
+                // Y0 U Y1 V covers two pixels; BT.601 limited range
+                let i = line + (x as usize & !1) * 2;
+                let luma = *self.data.get(i + (x as usize & 1) * 2)?;
+                let (u, v) = (*self.data.get(i + 1)?, *self.data.get(i + 3)?);
+                let y = (f64::from(luma) - 16.0) * 1.164;
+                let (u, v) = (f64::from(u) - 128.0, f64::from(v) - 128.0);
+                Some([
+                    clamp(y + 1.596 * v),
+                    clamp(y - 0.392 * u - 0.813 * v),
+                    clamp(y + 2.017 * u),
+                ])
+            }
+        }
+    }
+
+    /// Mean colour of a rectangle.
+    fn mean_rgb(&self, x0: u32, x1: u32, y0: u32, y1: u32) -> [u8; 3] {
+        let mut sum = [0u64; 3];
+        let mut count = 0u64;
+        for y in y0..y1 {
+            for x in x0..x1 {
+                if let Some(rgb) = self.rgb_at(x, y) {
+                    for (s, c) in sum.iter_mut().zip(rgb) {
+                        *s += u64::from(c);
+                    }
+                    count += 1;
+                }
+            }
+        }
+        sum.map(|s| s.checked_div(count).unwrap_or(0) as u8)
+    }
+
+    /// Mean and standard deviation of luma, 0-255.
+    pub fn luma_stats(&self) -> (f64, f64) {
+        let lumas: Vec<f64> = (0..self.height)
+            .step_by(4)
+            .flat_map(|y| (0..self.width).step_by(4).map(move |x| (x, y)))
+            .filter_map(|(x, y)| self.rgb_at(x, y))
+            .map(|[r, g, b]| 0.299 * f64::from(r) + 0.587 * f64::from(g) + 0.114 * f64::from(b))
+            .collect();
+        if lumas.is_empty() {
+            return (0.0, 0.0);
+        }
+        let mean = lumas.iter().sum::<f64>() / lumas.len() as f64;
+        let variance = lumas.iter().map(|l| (l - mean).powi(2)).sum::<f64>() / lumas.len() as f64;
+        (mean, variance.sqrt())
+    }
+}
+
+/// Renders [`BARS`] as vertical bars, in XRGB8888 with a packed stride.
+pub fn test_pattern(width: u32, height: u32) -> Frame {
+    let stride = width * 4;
+    let mut data = vec![0; (stride * height) as usize];
+    for (x, pixel) in (0..width).cycle().zip(data.chunks_exact_mut(4)) {
+        let [r, g, b] = BARS[(x * BARS.len() as u32 / width) as usize].1;
+        pixel.copy_from_slice(&[b, g, r, 0]);
+    }
+    Frame {
+        width,
+        height,
+        stride,
+        format: PixelFormat::Xrgb8888,
+        data,
+    }
+}
+
+/// How one bar of a captured pattern compares with the rendered one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BarCheck {
+    pub bar: &'static str,
+    pub expected: [u8; 3],
+    pub captured: [u8; 3], // Mean over the middle of the bar
+    pub within_tolerance: bool,
+}
+
+impl fmt::Display for BarCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [r, g, b] = self.captured;
+        write!(
+            f,
+            "{} bar captured as #{:02x}{:02x}{:02x}",
+            self.bar, r, g, b
+        )
+    }
+}
+
+/// Compares a capture of [`test_pattern`] bar by bar. Only the middle half of each bar is
+/// sampled, so scaling and blurred edges don't count.
+pub fn check_bars(frame: &Frame, tolerance: u8) -> Vec<BarCheck> {
+    let bars = BARS.len() as u32;
+    let (y0, y1) = (frame.height / 4, frame.height * 3 / 4);
+    BARS.iter()
+        .enumerate()
+        .map(|(i, (bar, expected))| {
+            let i = i as u32;
+            let x0 = (frame.width * (4 * i + 1)) / (4 * bars);
+            let x1 = (frame.width * (4 * i + 3)) / (4 * bars);
+            let captured = frame.mean_rgb(x0, x1.max(x0 + 1), y0, y1.max(y0 + 1));
+            BarCheck {
+                bar,
+                expected: *expected,
+                captured,
+                within_tolerance: expected
+                    .iter()
+                    .zip(captured)
+                    .all(|(e, c)| e.abs_diff(c) <= tolerance),
+            }
+        })
+        .collect()
+}
+
+/// Luma spread below which a frame counts as blank (a lens cap, a dead sensor).
+const MIN_LUMA_STDDEV: f64 = 2.0;
+
+/// Describes why a camera frame looks dead, if it does.
+pub fn blank_frame_problem(frame: &Frame) -> Option<String> {
+    let (mean, stddev) = frame.luma_stats();
+    (stddev < MIN_LUMA_STDDEV).then(|| {
+        format!(
+            "captured frame is uniform (mean luma {:.0}, stddev {:.1})",
+            mean, stddev
+        )
+    })
+}
+
+/// Outcome of [`run_pipeline`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PipelineResult {
+    pub capture: Option<String>, // e.g. "uvcvideo 1280x720 YUYV"
+    pub display: Option<String>, // e.g. "connector 77 1920x1080"
+    pub bars: Vec<BarCheck>,     // Empty without a loopback
+    pub problems: Vec<String>,
+}
+
+impl PipelineResult {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty() && self.bars.iter().all(|b| b.within_tolerance)
+    }
+}
+
+#[cfg(feature = "hardware")]
+pub use hw::{PipelineConfig, ShownPattern, capture_frame, run_pipeline, show_pattern};
+
+#[cfg(feature = "hardware")]
+mod hw {
+    use super::{
+        Frame, PipelineResult, PixelFormat, blank_frame_problem, check_bars, test_pattern,
+    };
+    use anyhow::Result;
+    use std::fs::{File, OpenOptions};
+    use std::os::fd::{AsRawFd, RawFd};
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    const fn ioc(dir: u32, kind: u8, nr: u8, size: usize) -> u64 {
+        ((dir << 30) | ((size as u32) << 16) | ((kind as u32) << 8) | nr as u32) as u64
+    }
+    const fn iowr<T>(kind: u8, nr: u8) -> u64 {
+        ioc(3, kind, nr, size_of::<T>())
+    }
+    const fn iow<T>(kind: u8, nr: u8) -> u64 {
+        ioc(1, kind, nr, size_of::<T>())
+    }
+
+    fn ioctl<T>(fd: RawFd, request: u64, arg: &mut T, what: &str) -> Result<()> {
+        let rc = unsafe { libc::ioctl(fd, request as _, arg as *mut T) };
+        if rc < 0 {
+            anyhow::bail!("{} failed: {}", what, std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// A shared memory mapping, unmapped on drop.
+    struct Mapping {
+        ptr: *mut libc::c_void,
+        len: usize,
+    }
+
+    impl Mapping {
+        fn new(fd: RawFd, len: usize, offset: u64) -> Result<Mapping> {
+            let ptr = unsafe {
+                libc::mmap(
+                    std::ptr::null_mut(),
+                    len,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    fd,
+                    offset as libc::off_t,
+                )
+            };
+            if ptr == libc::MAP_FAILED {
+                anyhow::bail!("mmap failed: {}", std::io::Error::last_os_error());
+            }
+            Ok(Mapping { ptr, len })
+        }
+
+        fn bytes(&mut self) -> &mut [u8] {
+            unsafe { std::slice::from_raw_parts_mut(self.ptr as *mut u8, self.len) }
+        }
+    }
+
+    impl Drop for Mapping {
+        fn drop(&mut self) {
+            unsafe { libc::munmap(self.ptr, self.len) };
+        }
+    }
+
+    // linux/videodev2.h
+    const V4L2_BUF_TYPE_VIDEO_CAPTURE: u32 = 1;
+    const V4L2_MEMORY_MMAP: u32 = 1;
+    const V4L2_FIELD_NONE: u32 = 1;
+    const V4L2_CAP_VIDEO_CAPTURE: u32 = 0x1;
+    const V4L2_CAP_STREAMING: u32 = 0x0400_0000;
+    const V4L2_CAP_DEVICE_CAPS: u32 = 0x8000_0000;
+
+    #[repr(C)]
+    struct V4l2Capability {
+        driver: [u8; 16],
+        card: [u8; 32],
+        bus_info: [u8; 32],
+        version: u32,
+        capabilities: u32,
+        device_caps: u32,
+        reserved: [u32; 3],
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct V4l2PixFormat {
+        width: u32,
+        height: u32,
+        pixelformat: u32,
+        field: u32,
+        bytesperline: u32,
+        sizeimage: u32,
+        colorspace: u32,
+        private: u32,
+        flags: u32,
+        ycbcr_enc: u32,
+        quantization: u32,
+        xfer_func: u32,
+    }
+
+    #[repr(C)]
+    union V4l2FormatUnion {
+        pix: V4l2PixFormat,
+        _raw_data: [u64; 25], // 200 bytes, pointer-aligned like the kernel union
+    }
+
+    #[repr(C)]
+    struct V4l2Format {
+        kind: u32,
+        fmt: V4l2FormatUnion,
+    }
+
+    #[repr(C)]
+    struct V4l2RequestBuffers {
+        count: u32,
+        kind: u32,
+        memory: u32,
+        capabilities: u32,
+        flags: u8,
+        reserved: [u8; 3],
+    }
+
+    #[repr(C)]
+    struct V4l2Timecode {
+        kind: u32,
+        flags: u32,
+        frames: u8,
+        seconds: u8,
+        minutes: u8,
+        hours: u8,
+        userbits: [u8; 4],
+    }
+
+    #[repr(C)]
+    struct V4l2Buffer {
+        index: u32,
+        kind: u32,
+        bytesused: u32,
+        flags: u32,
+        field: u32,
+        timestamp: libc::timeval,
+        timecode: V4l2Timecode,
+        sequence: u32,
+        memory: u32,
+        offset: libc::c_ulong, // Union with userptr, planes and fd
+        length: u32,
+        reserved2: u32,
+        request_fd: i32,
+    }
+
+    const VIDIOC_QUERYCAP: u64 = ioc(2, b'V', 0, size_of::<V4l2Capability>());
+    const VIDIOC_S_FMT: u64 = iowr::<V4l2Format>(b'V', 5);
+    const VIDIOC_REQBUFS: u64 = iowr::<V4l2RequestBuffers>(b'V', 8);
+    const VIDIOC_QUERYBUF: u64 = iowr::<V4l2Buffer>(b'V', 9);
+    const VIDIOC_QBUF: u64 = iowr::<V4l2Buffer>(b'V', 15);
+    const VIDIOC_DQBUF: u64 = iowr::<V4l2Buffer>(b'V', 17);
+    const VIDIOC_STREAMON: u64 = iow::<i32>(b'V', 18);
+    const VIDIOC_STREAMOFF: u64 = iow::<i32>(b'V', 19);
+
+    /// Frames dropped before the one returned, so auto-exposure settles and stale buffers
+    /// from before the pattern went up are flushed.
+    const WARMUP_FRAMES: u32 = 5;
+
+    fn c_string(bytes: &[u8]) -> String {
+        let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).to_string()
+    }
+
+    /// Captures one frame by mmap streaming; the driver may adjust the requested size and
+    /// format. Returns the frame and a description such as "uvcvideo 1280x720 YUYV".
+    pub fn capture_frame(
+        device: &Path,
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+    ) -> Result<(Frame, String)> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(device)
+            .map_err(|e| anyhow::anyhow!("{}: {}", device.display(), e))?;
+        let fd = file.as_raw_fd();
+        let context = |e: anyhow::Error| anyhow::anyhow!("{}: {}", device.display(), e);
+
+        let mut cap: V4l2Capability = unsafe { std::mem::zeroed() };
+        ioctl(fd, VIDIOC_QUERYCAP, &mut cap, "VIDIOC_QUERYCAP").map_err(context)?;
+        let caps = if cap.capabilities & V4L2_CAP_DEVICE_CAPS != 0 {
+            cap.device_caps
+        } else {
+            cap.capabilities
+        };
+        if caps & V4L2_CAP_VIDEO_CAPTURE == 0 || caps & V4L2_CAP_STREAMING == 0 {
+            anyhow::bail!("{}: not a streaming capture device", device.display());
+        }
+
+        let mut fmt: V4l2Format = unsafe { std::mem::zeroed() };
+        fmt.kind = V4L2_BUF_TYPE_VIDEO_CAPTURE;
+        fmt.fmt.pix = V4l2PixFormat {
+            width,
+            height,
+            pixelformat: format.fourcc(),
+            field: V4L2_FIELD_NONE,
+            ..unsafe { std::mem::zeroed() }
+        };
+        ioctl(fd, VIDIOC_S_FMT, &mut fmt, "VIDIOC_S_FMT").map_err(context)?;
+        let pix = unsafe { fmt.fmt.pix };
+        let Some(format) = PixelFormat::from_fourcc(pix.pixelformat) else {
+            anyhow::bail!(
+                "{}: driver chose unsupported format {:?}",
+                device.display(),
+                String::from_utf8_lossy(&pix.pixelformat.to_le_bytes())
+            );
+        };
+
+        let mut req = V4l2RequestBuffers {
+            count: 4,
+            kind: V4L2_BUF_TYPE_VIDEO_CAPTURE,
+            memory: V4L2_MEMORY_MMAP,
+            capabilities: 0,
+            flags: 0,
+            reserved: [0; 3],
+        };
+        ioctl(fd, VIDIOC_REQBUFS, &mut req, "VIDIOC_REQBUFS").map_err(context)?;
+        let mut mappings = Vec::new();
+        for index in 0..req.count {
+            let mut buf = new_buffer(index);
+            ioctl(fd, VIDIOC_QUERYBUF, &mut buf, "VIDIOC_QUERYBUF").map_err(context)?;
+            mappings.push(Mapping::new(fd, buf.length as usize, buf.offset)?);
+            ioctl(fd, VIDIOC_QBUF, &mut buf, "VIDIOC_QBUF").map_err(context)?;
+        }
+
+        let mut kind = V4L2_BUF_TYPE_VIDEO_CAPTURE as i32;
+        ioctl(fd, VIDIOC_STREAMON, &mut kind, "VIDIOC_STREAMON").map_err(context)?;
+        let mut captured = Err(anyhow::anyhow!("no frame"));
+        for frame in 0..=WARMUP_FRAMES {
+            let mut buf = new_buffer(0);
+            if let Err(e) = ioctl(fd, VIDIOC_DQBUF, &mut buf, "VIDIOC_DQBUF") {
+                captured = Err(context(e));
+                break;
+            }
+            if frame == WARMUP_FRAMES {
+                let bytes = &mappings[buf.index as usize].bytes()[..buf.bytesused as usize];
+                captured = Ok(bytes.to_vec());
+            } else if let Err(e) = ioctl(fd, VIDIOC_QBUF, &mut buf, "VIDIOC_QBUF") {
+                captured = Err(context(e));
+                break;
+            }
+        }
+        let _ = ioctl(fd, VIDIOC_STREAMOFF, &mut kind, "VIDIOC_STREAMOFF");
+        drop(mappings);
+
+        let description = format!(
+            "{} {}x{} {}",
+            c_string(&cap.driver),
+            pix.width,
+            pix.height,
+            String::from_utf8_lossy(&pix.pixelformat.to_le_bytes())
+        );
+        let frame = Frame {
+            width: pix.width,
+            height: pix.height,
+            stride: pix.bytesperline,
+            format,
+            data: captured?,
+        };
+        Ok((frame, description))
+    }
+
+    fn new_buffer(index: u32) -> V4l2Buffer {
+        let mut buf: V4l2Buffer = unsafe { std::mem::zeroed() };
+        buf.index = index;
+        buf.kind = V4L2_BUF_TYPE_VIDEO_CAPTURE;
+        buf.memory = V4L2_MEMORY_MMAP;
+        buf
+    }
+
+    // drm/drm.h and drm/drm_mode.h
+    const DRM_MODE_CONNECTED: u32 = 1;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct DrmModeCardRes {
+        fb_id_ptr: u64,
+        crtc_id_ptr: u64,
+        connector_id_ptr: u64,
+        encoder_id_ptr: u64,
+        count_fbs: u32,
+        count_crtcs: u32,
+        count_connectors: u32,
+        count_encoders: u32,
+        min_width: u32,
+        max_width: u32,
+        min_height: u32,
+        max_height: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct DrmModeModeinfo {
+        clock: u32,
+        hdisplay: u16,
+        hsync_start: u16,
+        hsync_end: u16,
+        htotal: u16,
+        hskew: u16,
+        vdisplay: u16,
+        vsync_start: u16,
+        vsync_end: u16,
+        vtotal: u16,
+        vscan: u16,
+        vrefresh: u32,
+        flags: u32,
+        kind: u32,
+        name: [u8; 32],
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct DrmModeGetConnector {
+        encoders_ptr: u64,
+        modes_ptr: u64,
+        props_ptr: u64,
+        prop_values_ptr: u64,
+        count_modes: u32,
+        count_props: u32,
+        count_encoders: u32,
+        encoder_id: u32,
+        connector_id: u32,
+        connector_type: u32,
+        connector_type_id: u32,
+        connection: u32,
+        mm_width: u32,
+        mm_height: u32,
+        subpixel: u32,
+        pad: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct DrmModeGetEncoder {
+        encoder_id: u32,
+        encoder_type: u32,
+        crtc_id: u32,
+        possible_crtcs: u32,
+        possible_clones: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct DrmModeCreateDumb {
+        height: u32,
+        width: u32,
+        bpp: u32,
+        flags: u32,
+        handle: u32,
+        pitch: u32,
+        size: u64,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct DrmModeMapDumb {
+        handle: u32,
+        pad: u32,
+        offset: u64,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct DrmModeFbCmd {
+        fb_id: u32,
+        width: u32,
+        height: u32,
+        pitch: u32,
+        bpp: u32,
+        depth: u32,
+        handle: u32,
+    }
+
+    #[repr(C)]
+    struct DrmModeCrtc {
+        set_connectors_ptr: u64,
+        count_connectors: u32,
+        crtc_id: u32,
+        fb_id: u32,
+        x: u32,
+        y: u32,
+        gamma_size: u32,
+        mode_valid: u32,
+        mode: DrmModeModeinfo,
+    }
+
+    const DRM_IOCTL_MODE_GETRESOURCES: u64 = iowr::<DrmModeCardRes>(b'd', 0xa0);
+    const DRM_IOCTL_MODE_SETCRTC: u64 = iowr::<DrmModeCrtc>(b'd', 0xa2);
+    const DRM_IOCTL_MODE_GETENCODER: u64 = iowr::<DrmModeGetEncoder>(b'd', 0xa6);
+    const DRM_IOCTL_MODE_GETCONNECTOR: u64 = iowr::<DrmModeGetConnector>(b'd', 0xa7);
+    const DRM_IOCTL_MODE_ADDFB: u64 = iowr::<DrmModeFbCmd>(b'd', 0xae);
+    const DRM_IOCTL_MODE_RMFB: u64 = iowr::<u32>(b'd', 0xaf);
+    const DRM_IOCTL_MODE_CREATE_DUMB: u64 = iowr::<DrmModeCreateDumb>(b'd', 0xb2);
+    const DRM_IOCTL_MODE_MAP_DUMB: u64 = iowr::<DrmModeMapDumb>(b'd', 0xb3);
+    const DRM_IOCTL_MODE_DESTROY_DUMB: u64 = iowr::<u32>(b'd', 0xb4);
+
+    /// A test pattern on a display; the framebuffer is removed on drop, and the kernel
+    /// restores the previous console configuration once the card is closed.
+    pub struct ShownPattern {
+        card: File,
+        fb_id: u32,
+        handle: u32,
+        pub description: String, // e.g. "connector 77 1920x1080"
+    }
+
+    impl Drop for ShownPattern {
+        fn drop(&mut self) {
+            let fd = self.card.as_raw_fd();
+            let _ = ioctl(fd, DRM_IOCTL_MODE_RMFB, &mut self.fb_id, "RMFB");
+            let _ = ioctl(
+                fd,
+                DRM_IOCTL_MODE_DESTROY_DUMB,
+                &mut self.handle,
+                "DESTROY_DUMB",
+            );
+        }
+    }
+
+    fn get_ids(fd: RawFd) -> Result<(Vec<u32>, Vec<u32>)> {
+        let mut res = DrmModeCardRes::default();
+        ioctl(fd, DRM_IOCTL_MODE_GETRESOURCES, &mut res, "GETRESOURCES")?;
+        let mut crtcs = vec![0u32; res.count_crtcs as usize];
+        let mut connectors = vec![0u32; res.count_connectors as usize];
+        res = DrmModeCardRes {
+            crtc_id_ptr: crtcs.as_mut_ptr() as u64,
+            connector_id_ptr: connectors.as_mut_ptr() as u64,
+            count_crtcs: crtcs.len() as u32,
+            count_connectors: connectors.len() as u32,
+            ..Default::default()
+        };
+        ioctl(fd, DRM_IOCTL_MODE_GETRESOURCES, &mut res, "GETRESOURCES")?;
+        Ok((crtcs, connectors))
+    }
+
+    /// Preferred (first) mode and current encoder of a connector, if it is connected.
+    fn connected_output(fd: RawFd, id: u32) -> Result<Option<(DrmModeModeinfo, u32)>> {
+        let mut conn = DrmModeGetConnector {
+            connector_id: id,
+            ..Default::default()
+        };
+        ioctl(fd, DRM_IOCTL_MODE_GETCONNECTOR, &mut conn, "GETCONNECTOR")?;
+        if conn.connection != DRM_MODE_CONNECTED || conn.count_modes == 0 {
+            return Ok(None);
+        }
+        let mut modes: Vec<DrmModeModeinfo> =
+            vec![unsafe { std::mem::zeroed() }; conn.count_modes as usize];
+        conn = DrmModeGetConnector {
+            connector_id: id,
+            modes_ptr: modes.as_mut_ptr() as u64,
+            count_modes: modes.len() as u32,
+            ..Default::default()
+        };
+        ioctl(fd, DRM_IOCTL_MODE_GETCONNECTOR, &mut conn, "GETCONNECTOR")?;
+        // The mode list can change between the two calls, e.g. on hotplug
+        modes.truncate(conn.count_modes as usize);
+        Ok(modes.first().map(|mode| (*mode, conn.encoder_id)))
+    }
+
+    /// Puts [`test_pattern`] on a connected output of `card` through a dumb buffer, in the
+    /// connector's preferred mode. `connector` picks one; otherwise the first connected is
+    /// used. The pattern stays up as long as the returned value lives.
+    pub fn show_pattern(card: &Path, connector: Option<u32>) -> Result<ShownPattern> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(card)
+            .map_err(|e| anyhow::anyhow!("{}: {}", card.display(), e))?;
+        let fd = file.as_raw_fd();
+        let context = |e: anyhow::Error| anyhow::anyhow!("{}: {}", card.display(), e);
+
+        let (crtcs, connectors) = get_ids(fd).map_err(context)?;
+        let mut output = None;
+        for id in connectors
+            .into_iter()
+            .filter(|id| connector.is_none_or(|c| c == *id))
+        {
+            if let Some((mode, encoder)) = connected_output(fd, id).map_err(context)? {
+                output = Some((id, mode, encoder));
+                break;
+            }
+        }
+        let Some((mut connector_id, mode, encoder_id)) = output else {
+            anyhow::bail!("{}: no connected output", card.display());
+        };
+        let mut encoder = DrmModeGetEncoder {
+            encoder_id,
+            ..Default::default()
+        };
+        let crtc_id = if encoder_id != 0
+            && ioctl(fd, DRM_IOCTL_MODE_GETENCODER, &mut encoder, "GETENCODER").is_ok()
+            && encoder.crtc_id != 0
+        {
+            encoder.crtc_id
+        } else {
+            // Without an active encoder, take the first CRTC the encoder can drive
+            let possible = if encoder_id != 0 {
+                encoder.possible_crtcs
+            } else {
+                u32::MAX
+            };
+            let Some(crtc) = crtcs
+                .iter()
+                .enumerate()
+                .find(|(i, _)| possible & (1 << i) != 0)
+            else {
+                anyhow::bail!("{}: no CRTC for connector {}", card.display(), connector_id);
+            };
+            *crtc.1
+        };
+
+        let (width, height) = (u32::from(mode.hdisplay), u32::from(mode.vdisplay));
+        let mut dumb = DrmModeCreateDumb {
+            width,
+            height,
+            bpp: 32,
+            ..Default::default()
+        };
+        ioctl(fd, DRM_IOCTL_MODE_CREATE_DUMB, &mut dumb, "CREATE_DUMB").map_err(context)?;
+        let mut fb = DrmModeFbCmd {
+            width,
+            height,
+            pitch: dumb.pitch,
+            bpp: 32,
+            depth: 24,
+            handle: dumb.handle,
+            ..Default::default()
+        };
+        let mut shown = ShownPattern {
+            card: file,
+            fb_id: 0,
+            handle: dumb.handle,
+            description: format!("connector {} {}x{}", connector_id, width, height),
+        };
+        ioctl(fd, DRM_IOCTL_MODE_ADDFB, &mut fb, "ADDFB").map_err(context)?;
+        shown.fb_id = fb.fb_id;
+
+        let mut map = DrmModeMapDumb {
+            handle: dumb.handle,
+            ..Default::default()
+        };
+        ioctl(fd, DRM_IOCTL_MODE_MAP_DUMB, &mut map, "MAP_DUMB").map_err(context)?;
+        let mut mapping = Mapping::new(fd, dumb.size as usize, map.offset)?;
+        let pattern = test_pattern(width, height);
+        let stride = dumb.pitch as usize;
+        for (line, pixels) in mapping
+            .bytes()
+            .chunks_exact_mut(stride)
+            .zip(pattern.data.chunks_exact(pattern.stride as usize))
+        {
+            line[..pixels.len()].copy_from_slice(pixels);
+        }
+        drop(mapping);
+
+        let mut crtc = DrmModeCrtc {
+            set_connectors_ptr: &mut connector_id as *mut u32 as u64,
+            count_connectors: 1,
+            crtc_id,
+            fb_id: fb.fb_id,
+            x: 0,
+            y: 0,
+            gamma_size: 0,
+            mode_valid: 1,
+            mode,
+        };
+        ioctl(fd, DRM_IOCTL_MODE_SETCRTC, &mut crtc, "SETCRTC").map_err(context)?;
+        Ok(shown)
+    }
+
+    /// A camera-to-display smoke test.
+    #[derive(Debug, Clone)]
+    pub struct PipelineConfig {
+        pub camera: PathBuf,        // e.g. /dev/video0; a camera or an HDMI capture
+        pub card: Option<PathBuf>,  // e.g. /dev/dri/card0; None skips the display
+        pub connector: Option<u32>, // None uses the first connected output
+        pub width: u32,             // Requested capture size
+        pub height: u32,
+        pub format: PixelFormat,
+        pub loopback: bool, // The camera sees the display (fixture camera or HDMI capture)
+        pub settle: Duration, // Between putting up the pattern and capturing
+        pub tolerance: u8,
+    }
+
+    /// Captures a frame, puts the test pattern on the display and, with a loopback, checks
+    /// that the capture shows the pattern.
+    pub fn run_pipeline(config: &PipelineConfig) -> Result<PipelineResult> {
+        let mut result = PipelineResult::default();
+        let capture = || capture_frame(&config.camera, config.width, config.height, config.format);
+
+        let (frame, description) = capture()?;
+        result.capture = Some(description);
+        if !config.loopback
+            && let Some(problem) = blank_frame_problem(&frame)
+        {
+            result.problems.push(problem);
+        }
+
+        let Some(card) = &config.card else {
+            return Ok(result);
+        };
+        let shown = match show_pattern(card, config.connector) {
+            Ok(shown) => shown,
+            Err(e) => {
+                result.problems.push(e.to_string());
+                return Ok(result);
+            }
+        };
+        result.display = Some(shown.description.clone());
+        if config.loopback {
+            std::thread::sleep(config.settle);
+            let (frame, _) = capture()?;
+            result.bars = check_bars(&frame, config.tolerance);
+            for bar in result.bars.iter().filter(|b| !b.within_tolerance) {
+                result.problems.push(bar.to_string());
+            }
+        }
+        drop(shown);
+        Ok(result)
+    }
+}
@@ -0,0 +1,254 @@
+use crate::manifest::Manifest;
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const MIB: u64 = 1024 * 1024;
+
+/// The fields of /proc/meminfo, in bytes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemInfo {
+    pub fields: BTreeMap<String, u64>, // e.g. "MemTotal", "CmaTotal"
+}
+
+impl MemInfo {
+    pub fn get(&self, field: &str) -> Option<u64> {
+        self.fields.get(field).copied()
+    }
+
+    /// RAM the kernel manages: what was fitted, less the kernel image and no-map regions.
+    pub fn total(&self) -> Option<u64> {
+        self.get("MemTotal")
+    }
+}
+
+/// One line of /proc/iomem, e.g. "  8e000000-8fffffff : optee".
+#[derive(Debug, Clone, PartialEq)]
+pub struct IomemRegion {
+    pub start: u64,
+    pub end: u64, // Inclusive
+    pub name: String,
+    pub depth: usize, // 0 for top-level regions, 1 for the regions inside them, ...
+}
+
+impl IomemRegion {
+    pub fn size(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// Parses /proc/meminfo, e.g. "MemTotal:  3891508 kB".
+pub fn parse_meminfo(text: &str) -> MemInfo {
+    let fields = text
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let mut value = value.split_whitespace();
+            let number: u64 = value.next()?.parse().ok()?;
+            let scale = match value.next() {
+                Some("kB") => 1024,
+                _ => 1, // Counts such as HugePages_Total
+            };
+            Some((name.to_string(), number * scale))
+        })
+        .collect();
+    MemInfo { fields }
+}
+
+/// Parses /proc/iomem; nesting is two spaces of indentation per level.
+pub fn parse_iomem(text: &str) -> Result<Vec<IomemRegion>> {
+    let mut regions = Vec::new();
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let bad = || anyhow::anyhow!("invalid iomem line {:?}", line);
+        let body = line.trim_start();
+        let (range, name) = body.split_once(" : ").ok_or_else(bad)?;
+        let (start, end) = range.split_once('-').ok_or_else(bad)?;
+        let (start, end) = (
+            u64::from_str_radix(start, 16).map_err(|_| bad())?,
+            u64::from_str_radix(end, 16).map_err(|_| bad())?,
+        );
+        if end < start {
+            return Err(bad());
+        }
+        regions.push(IomemRegion {
+            start,
+            end,
+            name: name.to_string(),
+            depth: (line.len() - body.len()) / 2,
+        });
+    }
+    Ok(regions)
+}
+
+/// Reads /proc/meminfo of the running system.
+pub fn read_meminfo() -> Result<MemInfo> {
+    read_meminfo_in(Path::new("/proc"))
+}
+
+/// Same as [`read_meminfo`], with an explicit procfs root.
+pub fn read_meminfo_in(proc_root: &Path) -> Result<MemInfo> {
+    let path = proc_root.join("meminfo");
+    let text =
+        fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+    Ok(parse_meminfo(&text))
+}
+
+/// Reads /proc/iomem of the running system. Addresses read as zero unless run as root.
+pub fn read_iomem() -> Result<Vec<IomemRegion>> {
+    read_iomem_in(Path::new("/proc"))
+}
+
+/// Same as [`read_iomem`], with an explicit procfs root.
+pub fn read_iomem_in(proc_root: &Path) -> Result<Vec<IomemRegion>> {
+    let path = proc_root.join("iomem");
+    let text =
+        fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+    parse_iomem(&text).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+}
+
+/// A reserved region the board must have. The name "cma" stands for the CMA pool of
+/// /proc/meminfo; any other is the name of an /proc/iomem region, e.g. "optee".
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedRegion {
+    pub name: String,
+    pub start: Option<u64>,
+    pub size: Option<u64>, // Bytes
+}
+
+/// Expectations of the `[memory]` manifest section.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedMemory {
+    pub fitted_mb: Option<u64>, // RAM on the board, in MiB
+    pub tolerance_percent: u64, // How much less MemTotal may be (default 10)
+    pub regions: Vec<ExpectedRegion>,
+}
+
+impl ExpectedMemory {
+    pub fn from_manifest(manifest: &Manifest) -> Result<ExpectedMemory> {
+        let Some(section) = manifest.sections.get("memory") else {
+            return Ok(ExpectedMemory::default());
+        };
+        let number = |value: &Value, key: &str| -> Result<Option<u64>> {
+            match value {
+                Value::Null => Ok(None),
+                value => value
+                    .as_u64()
+                    .map(Some)
+                    .ok_or_else(|| anyhow::anyhow!("memory.{} must be a positive integer", key)),
+            }
+        };
+        let regions = match &section["regions"] {
+            Value::Null => Vec::new(),
+            Value::Array(regions) => regions
+                .iter()
+                .enumerate()
+                .map(|(i, region)| {
+                    Ok(ExpectedRegion {
+                        name: region["name"].as_str().map(str::to_string).ok_or_else(|| {
+                            anyhow::anyhow!("memory.regions {}: missing `name`", i)
+                        })?,
+                        start: number(&region["start"], "regions.start")?,
+                        size: number(&region["size"], "regions.size")?,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => anyhow::bail!("memory.regions must be a list"),
+        };
+        let tolerance_percent = number(&section["tolerance_percent"], "tolerance_percent")?;
+        if tolerance_percent.is_some_and(|t| t > 100) {
+            anyhow::bail!("memory.tolerance_percent must be at most 100");
+        }
+        Ok(ExpectedMemory {
+            fitted_mb: number(&section["fitted_mb"], "fitted_mb")?,
+            tolerance_percent: tolerance_percent.unwrap_or(10),
+            regions,
+        })
+    }
+}
+
+/// Returns human-readable descriptions of every problem against the expectations.
+///
+/// MemTotal is never more than what is fitted; falling short by more than the tolerance
+/// usually means a rank didn't train, or the device tree declares the wrong size.
+pub fn validate_memory(
+    meminfo: &MemInfo,
+    iomem: &[IomemRegion],
+    expected: &ExpectedMemory,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    if let Some(fitted_mb) = expected.fitted_mb {
+        let fitted = fitted_mb * MIB;
+        match meminfo.total() {
+            None => problems.push("no MemTotal in /proc/meminfo".to_string()),
+            Some(total)
+                if total > fitted || total < fitted - fitted * expected.tolerance_percent / 100 =>
+            {
+                problems.push(format!(
+                    "{} MiB of RAM (expected {} MiB, at most {}% less)",
+                    total / MIB,
+                    fitted_mb,
+                    expected.tolerance_percent
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    let hidden = !iomem.is_empty() && iomem.iter().all(|r| r.start == 0 && r.end == 0);
+    for want in &expected.regions {
+        if want.name == "cma" {
+            match meminfo.get("CmaTotal") {
+                None | Some(0) => problems.push("no CMA pool".to_string()),
+                Some(size) if want.size.is_some_and(|s| s != size) => problems.push(format!(
+                    "CMA pool is {} MiB (expected {} MiB)",
+                    size / MIB,
+                    want.size.unwrap_or_default() / MIB
+                )),
+                Some(_) => {}
+            }
+            continue;
+        }
+        let matching: Vec<&IomemRegion> = iomem.iter().filter(|r| r.name == want.name).collect();
+        if matching.is_empty() {
+            problems.push(format!("reserved region {} not found", want.name));
+            continue;
+        }
+        if hidden && (want.start.is_some() || want.size.is_some()) {
+            problems.push(format!(
+                "{}: /proc/iomem addresses are hidden, run as root",
+                want.name
+            ));
+            continue;
+        }
+        let region = match want.start {
+            Some(start) => match matching.iter().find(|r| r.start == start) {
+                Some(region) => region,
+                None => {
+                    let starts: Vec<String> =
+                        matching.iter().map(|r| format!("{:#x}", r.start)).collect();
+                    problems.push(format!(
+                        "{} at {} (expected {:#x})",
+                        want.name,
+                        starts.join(", "),
+                        start
+                    ));
+                    continue;
+                }
+            },
+            None => matching[0],
+        };
+        if let Some(size) = want.size
+            && region.size() != size
+        {
+            problems.push(format!(
+                "{} at {:#x} is {:#x} bytes (expected {:#x})",
+                want.name,
+                region.start,
+                region.size(),
+                size
+            ));
+        }
+    }
+    problems
+}
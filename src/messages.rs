@@ -0,0 +1,123 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::BufRead;
+
+/// English texts for the stable message IDs. Placeholders are `{name}`.
+const ENGLISH: [(&str, &str); 14] = [
+    ("i2c.title", "I2C bus {bus}"),
+    (
+        "i2c.missing",
+        "Expected device 0x{addr} on bus {bus} not found",
+    ),
+    ("i2c.unexpected", "Unexpected device 0x{addr} on bus {bus}"),
+    (
+        "i2c.forbidden",
+        "Device 0x{addr} on bus {bus} must not be present",
+    ),
+    ("absence.title", "Components that must be absent"),
+    ("absence.violation", "{item} is present: {evidence}"),
+    ("crash.title", "Crash artifacts"),
+    ("crash.found", "Crash artifact found: {summary}"),
+    ("integrity.title", "Filesystem integrity"),
+    ("integrity.missing", "File {path} is missing"),
+    ("integrity.mismatch", "File {path} is corrupted"),
+    ("soc.title", "SoC identification"),
+    ("check.passed", "{check}: passed"),
+    ("check.failed", "{check}: failed"),
+];
+
+/// A message with a stable ID and its arguments; rendered per locale, logged as-is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub id: String, // e.g. "i2c.missing"
+    pub args: Vec<(String, String)>,
+}
+
+impl Message {
+    pub fn new(id: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, name: &str, value: impl ToString) -> Message {
+        self.args.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Locale-neutral form for logs, e.g. `i2c.missing bus=1 addr=50`.
+    pub fn to_log_string(&self) -> String {
+        let mut line = self.id.clone();
+        for (name, value) in &self.args {
+            line.push_str(&format!(" {}={}", name, value));
+        }
+        line
+    }
+}
+
+/// Message texts of one locale.
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    pub locale: String,
+    texts: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// The built-in English catalog.
+    pub fn english() -> Catalog {
+        Catalog {
+            locale: "en".to_string(),
+            texts: ENGLISH
+                .iter()
+                .map(|(id, text)| (id.to_string(), text.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Parses a catalog file: `<id> = <text>` lines, `#` comments.
+    pub fn parse_from_reader<R: BufRead>(locale: &str, reader: R) -> Result<Catalog> {
+        let mut texts = HashMap::new();
+        for line_result in reader.lines() {
+            let line = line_result?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((id, text)) = line.split_once('=') else {
+                anyhow::bail!("Malformed catalog line: {}", line);
+            };
+            texts.insert(id.trim().to_string(), text.trim().to_string());
+        }
+        Ok(Catalog {
+            locale: locale.to_string(),
+            texts,
+        })
+    }
+
+    pub fn text(&self, id: &str) -> Option<&str> {
+        self.texts.get(id).map(|t| t.as_str())
+    }
+
+    /// IDs the English catalog has but this one doesn't.
+    pub fn missing_ids(&self) -> Vec<&'static str> {
+        ENGLISH
+            .iter()
+            .map(|(id, _)| *id)
+            .filter(|id| !self.texts.contains_key(*id))
+            .collect()
+    }
+
+    /// Renders a message, falling back to English and then to the log form.
+    pub fn render(&self, message: &Message) -> String {
+        let english = Catalog::english();
+        let Some(template) = self.text(&message.id).or_else(|| english.text(&message.id)) else {
+            return message.to_log_string();
+        };
+        let mut text = template.to_string();
+        for (name, value) in &message.args {
+            text = text.replace(&format!("{{{}}}", name), value);
+        }
+        text
+    }
+}
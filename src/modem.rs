@@ -0,0 +1,269 @@
+use crate::runtime::{self, Dependency};
+use anyhow::Result;
+use nix::poll::{PollFd, PollFlags, poll};
+use nix::sys::termios::{SetArg, cfmakeraw, tcgetattr, tcsetattr};
+use std::collections::HashMap;
+use std::io::{BufRead, Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// A SIM slot of a modem. `sim` is `None` for an empty slot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimSlot {
+    pub slot: u32, // 1-based, as reported by ModemManager
+    pub primary: bool,
+    pub sim: Option<SimInfo>,
+}
+
+/// Identity of a fitted SIM (physical or eSIM profile).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimInfo {
+    pub iccid: String,
+    pub imsi: Option<String>,
+    pub operator_name: Option<String>,
+    pub sim_type: Option<String>, // "physical" or "esim"
+}
+
+/// Parses `mmcli -K` output (`key : value` lines) into a map.
+///
+/// Empty values are reported by mmcli as `--` and are skipped.
+pub fn parse_mmcli_from_reader<R: BufRead>(reader: R) -> Result<HashMap<String, String>> {
+    let mut map = HashMap::new();
+
+    for line_result in reader.lines() {
+        let line = line_result?;
+        if let Some((k, v)) = line.split_once(" : ") {
+            let v = v.trim();
+            if !v.is_empty() && v != "--" {
+                map.insert(k.trim().to_string(), v.to_string());
+            }
+        }
+    }
+    Ok(map)
+}
+
+/// Collects the values of a multi-value mmcli key (`key.value[1]`, `key.value[2]`, ..) in order.
+pub fn mmcli_values(map: &HashMap<String, String>, key: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    for i in 1.. {
+        match map.get(&format!("{}.value[{}]", key, i)) {
+            Some(v) => values.push(v.clone()),
+            None => break,
+        }
+    }
+    values
+}
+
+fn run_mmcli(args: &[&str]) -> Result<HashMap<String, String>> {
+    runtime::require(&Dependency::program("mmcli"))?;
+    let output = Command::new("mmcli").args(args).arg("-K").output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "mmcli {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    parse_mmcli_from_reader(output.stdout.as_slice())
+}
+
+/// Lists ModemManager modem object paths.
+pub fn list_modems() -> Result<Vec<String>> {
+    Ok(mmcli_values(&run_mmcli(&["-L"])?, "modem-list"))
+}
+
+/// Builds a [`SimInfo`] from `mmcli -i <sim> -K` output.
+pub fn sim_info_from_mmcli(map: &HashMap<String, String>) -> Option<SimInfo> {
+    Some(SimInfo {
+        iccid: map.get("sim.properties.iccid")?.clone(),
+        imsi: map.get("sim.properties.imsi").cloned(),
+        operator_name: map.get("sim.properties.operator-name").cloned(),
+        sim_type: map.get("sim.properties.sim-type").cloned(),
+    })
+}
+
+/// Enumerates SIM slots of a modem through ModemManager.
+///
+/// Modems without multi-slot support report a single slot with the active SIM.
+pub fn read_sim_slots(modem: &str) -> Result<Vec<SimSlot>> {
+    let modem_info = run_mmcli(&["-m", modem])?;
+    let mut slot_paths = mmcli_values(&modem_info, "modem.generic.sim-slots");
+    let primary = modem_info
+        .get("modem.generic.primary-sim-slot")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(1);
+    if slot_paths.is_empty() {
+        slot_paths.push(
+            modem_info
+                .get("modem.generic.sim")
+                .cloned()
+                .unwrap_or_default(),
+        );
+    }
+
+    let mut slots = Vec::new();
+    for (i, path) in slot_paths.iter().enumerate() {
+        let slot = i as u32 + 1;
+        // Empty slots are listed as "/"
+        let sim = if path.is_empty() || path == "/" {
+            None
+        } else {
+            sim_info_from_mmcli(&run_mmcli(&["-i", path])?)
+        };
+        slots.push(SimSlot {
+            slot,
+            primary: slot == primary,
+            sim,
+        });
+    }
+    Ok(slots)
+}
+
+/// Extracts the ICCID from an `AT+CCID`/`AT+ICCID`/`AT^ICCID?` response.
+pub fn parse_ccid_response(response: &str) -> Option<String> {
+    for line in response.lines() {
+        let line = line.trim();
+        let value = ["+CCID:", "+ICCID:", "^ICCID:", "+QCCID:"]
+            .iter()
+            .find_map(|prefix| line.strip_prefix(prefix))
+            .unwrap_or(line);
+        // Some modems pad odd-length ICCIDs with a trailing `F`
+        let digits = value.trim().trim_matches('"').trim_end_matches(['F', 'f']);
+        if digits.len() >= 18 && digits.chars().all(|c| c.is_ascii_digit()) {
+            return Some(digits.to_string());
+        }
+    }
+    None
+}
+
+/// Extracts the IMSI from an `AT+CIMI` response.
+pub fn parse_cimi_response(response: &str) -> Option<String> {
+    response
+        .lines()
+        .map(|l| l.trim())
+        .find(|l| (6..=15).contains(&l.len()) && l.chars().all(|c| c.is_ascii_digit()))
+        .map(|l| l.to_string())
+}
+
+/// Sends an AT command to a modem control tty and returns the raw response.
+///
+/// Reads until a final `OK`/`ERROR` result code or until `timeout` expires.
+pub fn at_command(tty: &str, command: &str, timeout: Duration) -> Result<String> {
+    let mut port = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(tty)?;
+    let mut termios = tcgetattr(port.as_raw_fd())?;
+    cfmakeraw(&mut termios);
+    tcsetattr(port.as_raw_fd(), SetArg::TCSANOW, &termios)?;
+
+    port.write_all(format!("{}\r", command).as_bytes())?;
+
+    let deadline = Instant::now() + timeout;
+    let mut response = String::new();
+    let mut buf = [0u8; 256];
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        let mut fds = [PollFd::new(port.as_raw_fd(), PollFlags::POLLIN)];
+        if poll(&mut fds, remaining.as_millis() as i32)? == 0 {
+            break;
+        }
+        let n = port.read(&mut buf)?;
+        response.push_str(&String::from_utf8_lossy(&buf[..n]));
+        if response
+            .lines()
+            .any(|l| l.trim() == "OK" || l.contains("ERROR"))
+        {
+            return Ok(response);
+        }
+    }
+    anyhow::bail!("No final result code from {} for {}", tty, command)
+}
+
+/// Reads ICCID and IMSI over AT commands, for modems not managed by ModemManager.
+pub fn read_sim_via_at(tty: &str) -> Result<SimInfo> {
+    let timeout = Duration::from_secs(2);
+    let mut iccid = parse_ccid_response(&at_command(tty, "AT+CCID", timeout)?);
+    if iccid.is_none() {
+        iccid = parse_ccid_response(&at_command(tty, "AT+ICCID", timeout)?);
+    }
+    let Some(iccid) = iccid else {
+        anyhow::bail!("Modem at {} did not report an ICCID (no SIM fitted?)", tty);
+    };
+    Ok(SimInfo {
+        iccid,
+        imsi: parse_cimi_response(&at_command(tty, "AT+CIMI", timeout)?),
+        operator_name: None,
+        sim_type: None,
+    })
+}
+
+/// Checks the ICCID Luhn check digit (ITU-T E.118).
+pub fn iccid_checksum_ok(iccid: &str) -> bool {
+    if !(18..=20).contains(&iccid.len()) || !iccid.chars().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+    let sum: u32 = iccid
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| {
+            if i % 2 == 1 {
+                let d = d * 2;
+                if d > 9 { d - 9 } else { d }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Expected MNO profile: accepted ICCID issuer prefixes and IMSI MCC+MNC prefixes.
+///
+/// An empty list accepts anything.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedSimProfile {
+    pub iccid_prefixes: Vec<String>, // e.g. "894430" (89 + country + issuer)
+    pub mcc_mnc: Vec<String>,        // e.g. "23430"
+}
+
+/// Returns human-readable descriptions of every mismatch for the SIM in `slot`.
+pub fn validate_sim(slot: &SimSlot, expected: &ExpectedSimProfile) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    let Some(sim) = &slot.sim else {
+        mismatches.push(format!("slot {}: no SIM fitted", slot.slot));
+        return mismatches;
+    };
+
+    if !iccid_checksum_ok(&sim.iccid) {
+        mismatches.push(format!("slot {}: invalid ICCID {}", slot.slot, sim.iccid));
+    }
+    if !expected.iccid_prefixes.is_empty()
+        && !expected
+            .iccid_prefixes
+            .iter()
+            .any(|p| sim.iccid.starts_with(p.as_str()))
+    {
+        mismatches.push(format!(
+            "slot {}: ICCID {} not from expected issuer {:?}",
+            slot.slot, sim.iccid, expected.iccid_prefixes
+        ));
+    }
+    if !expected.mcc_mnc.is_empty() {
+        match &sim.imsi {
+            Some(imsi)
+                if expected
+                    .mcc_mnc
+                    .iter()
+                    .any(|p| imsi.starts_with(p.as_str())) => {}
+            Some(imsi) => mismatches.push(format!(
+                "slot {}: IMSI {} not on expected network {:?}",
+                slot.slot, imsi, expected.mcc_mnc
+            )),
+            None => mismatches.push(format!("slot {}: IMSI not readable", slot.slot)),
+        }
+    }
+    mismatches
+}
@@ -0,0 +1,262 @@
+use crate::manifest::Manifest;
+use crate::system_root::SystemRoot;
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+
+/// Module taint flags (kernel/panic.c), with what they mean for a unit.
+const TAINT_FLAGS: &[(char, &str)] = &[
+    ('P', "proprietary"),
+    ('O', "out-of-tree"),
+    ('E', "unsigned"),
+    ('F', "force-loaded"),
+    ('C', "staging"),
+    ('X', "auxiliary"),
+    ('K', "live-patched"),
+    ('N', "test"),
+    ('T', "randstruct"),
+];
+
+/// A loadable module from /proc/modules, e.g.
+/// "snd_soc_wm8960 45056 1 snd_soc_simple_card, Live 0xffffffc000a00000 (O)".
+#[derive(Debug, Clone, PartialEq)]
+pub struct KernelModule {
+    pub name: String,
+    pub size: u64,          // Bytes
+    pub refcount: u32,      // Modules and devices holding it
+    pub users: Vec<String>, // Modules depending on it
+    pub state: String,      // "Live", or "Loading" / "Unloading" while stuck in init or exit
+    pub taint: String,      // e.g. "OE"; empty if clean
+}
+
+impl KernelModule {
+    /// The taint flags with their meaning, e.g. "O (out-of-tree), E (unsigned)".
+    pub fn taint_description(&self) -> String {
+        self.taint
+            .chars()
+            .map(|flag| match TAINT_FLAGS.iter().find(|(f, _)| *f == flag) {
+                Some((_, meaning)) => format!("{} ({})", flag, meaning),
+                None => flag.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// The modules of the running kernel: the loaded ones and those built into the image.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleInventory {
+    pub loaded: Vec<KernelModule>,
+    pub builtin: BTreeSet<String>,
+}
+
+impl ModuleInventory {
+    pub fn get(&self, name: &str) -> Option<&KernelModule> {
+        let name = normalize(name);
+        self.loaded.iter().find(|m| m.name == name)
+    }
+
+    pub fn is_builtin(&self, name: &str) -> bool {
+        self.builtin.contains(&normalize(name))
+    }
+}
+
+/// The kernel treats `-` and `_` in module names alike; /proc and /sys use `_`.
+fn normalize(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// Parses /proc/modules. The load address reads as zero unless run as root.
+pub fn parse_proc_modules(text: &str) -> Result<Vec<KernelModule>> {
+    let mut modules = Vec::new();
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let bad = || anyhow::anyhow!("invalid /proc/modules line {:?}", line);
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 5 {
+            return Err(bad());
+        }
+        let taint = fields
+            .iter()
+            .skip(5)
+            .find_map(|f| f.strip_prefix('(')?.strip_suffix(')'))
+            .unwrap_or("");
+        modules.push(KernelModule {
+            name: fields[0].to_string(),
+            size: fields[1].parse().map_err(|_| bad())?,
+            refcount: fields[2].parse().map_err(|_| bad())?,
+            users: fields[3]
+                .split(',')
+                .filter(|u| !u.is_empty() && *u != "-")
+                .map(str::to_string)
+                .collect(),
+            state: fields[4].to_string(),
+            taint: taint.to_string(),
+        });
+    }
+    Ok(modules)
+}
+
+/// Module names in a modules.builtin file, e.g. "kernel/drivers/net/phy/realtek.ko".
+pub fn parse_modules_builtin(text: &str) -> BTreeSet<String> {
+    text.lines()
+        .filter_map(|line| {
+            let file = line.trim().rsplit('/').next()?;
+            Some(normalize(file.strip_suffix(".ko")?))
+        })
+        .collect()
+}
+
+/// Reads the module inventory of the running kernel.
+pub fn read_modules() -> Result<ModuleInventory> {
+    read_modules_in(&SystemRoot::live())
+}
+
+/// Same as [`read_modules`], for another system root.
+///
+/// Built-in modules come from /lib/modules/<release>/modules.builtin, and from the
+/// /sys/module entries without an `initstate`, which built-in modules with parameters have.
+pub fn read_modules_in(root: &SystemRoot) -> Result<ModuleInventory> {
+    let path = root.proc.join("modules");
+    let text =
+        fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+    let loaded =
+        parse_proc_modules(&text).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+
+    let mut builtin = BTreeSet::new();
+    if let Ok(release) = fs::read_to_string(root.proc.join("sys/kernel/osrelease")) {
+        let path = root.file(&format!("/lib/modules/{}/modules.builtin", release.trim()));
+        if let Ok(text) = fs::read_to_string(path) {
+            builtin = parse_modules_builtin(&text);
+        }
+    }
+    if let Ok(entries) = fs::read_dir(root.sys.join("module")) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !entry.path().join("initstate").exists() && !loaded.iter().any(|m| m.name == name) {
+                builtin.insert(name);
+            }
+        }
+    }
+    Ok(ModuleInventory { loaded, builtin })
+}
+
+/// A module the board needs. Each check applies only when set.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedModule {
+    pub name: String,
+    pub builtin: Option<bool>, // Must be built in (true) or loadable (false)
+    pub min_refcount: Option<u32>, // e.g. 1 for a codec the sound card must have bound
+}
+
+/// Expectations of the `[modules]` manifest section.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedModules {
+    pub required: Vec<ExpectedModule>,
+    pub allowed_taint: Option<String>, // Taint flags loaded modules may carry, e.g. "O"
+}
+
+impl ExpectedModules {
+    pub fn from_manifest(manifest: &Manifest) -> Result<ExpectedModules> {
+        let Some(section) = manifest.sections.get("modules") else {
+            return Ok(ExpectedModules::default());
+        };
+        let required = match &section["required"] {
+            Value::Null => Vec::new(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| match item {
+                    // A bare name: loaded or built in
+                    Value::String(name) => Ok(ExpectedModule {
+                        name: name.clone(),
+                        ..Default::default()
+                    }),
+                    item => Ok(ExpectedModule {
+                        name: item["name"].as_str().map(str::to_string).ok_or_else(|| {
+                            anyhow::anyhow!("modules.required {}: missing `name`", i)
+                        })?,
+                        builtin: item["builtin"].as_bool(),
+                        min_refcount: match &item["min_refcount"] {
+                            Value::Null => None,
+                            value => Some(
+                                value
+                                    .as_u64()
+                                    .and_then(|r| u32::try_from(r).ok())
+                                    .ok_or_else(|| {
+                                        anyhow::anyhow!(
+                                            "modules.required {}: `min_refcount` must be a positive integer",
+                                            i
+                                        )
+                                    })?,
+                            ),
+                        },
+                    }),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => anyhow::bail!("modules.required must be a list"),
+        };
+        let allowed_taint = match &section["allowed_taint"] {
+            Value::Null => None,
+            Value::String(flags) => Some(flags.to_ascii_uppercase()),
+            _ => anyhow::bail!("modules.allowed_taint must be a string of taint flags"),
+        };
+        Ok(ExpectedModules {
+            required,
+            allowed_taint,
+        })
+    }
+}
+
+/// Returns human-readable descriptions of every problem against the expectations.
+///
+/// A module stuck in "Loading" is usually a probe waiting on hardware that never answers;
+/// a refcount of zero means nothing bound to it, e.g. a codec the sound card didn't pick up.
+pub fn validate_modules(inventory: &ModuleInventory, expected: &ExpectedModules) -> Vec<String> {
+    let mut problems = Vec::new();
+    for want in &expected.required {
+        let module = inventory.get(&want.name);
+        let builtin = inventory.is_builtin(&want.name);
+        match (module, want.builtin) {
+            (None, _) if !builtin => {
+                problems.push(format!("{}: not loaded or built in", want.name));
+                continue;
+            }
+            (None, Some(false)) => problems.push(format!(
+                "{}: built in (expected a loadable module)",
+                want.name
+            )),
+            (Some(_), Some(true)) => problems.push(format!(
+                "{}: loaded as a module (expected built in)",
+                want.name
+            )),
+            _ => {}
+        }
+        let Some(module) = module else {
+            continue;
+        };
+        if let Some(min) = want.min_refcount
+            && module.refcount < min
+        {
+            problems.push(format!(
+                "{}: refcount {} (expected at least {})",
+                want.name, module.refcount, min
+            ));
+        }
+    }
+    for module in &inventory.loaded {
+        if module.state != "Live" {
+            problems.push(format!("{}: state {}", module.name, module.state));
+        }
+        if let Some(allowed) = &expected.allowed_taint
+            && module.taint.chars().any(|flag| !allowed.contains(flag))
+        {
+            problems.push(format!(
+                "{}: tainted {}",
+                module.name,
+                module.taint_description()
+            ));
+        }
+    }
+    problems
+}
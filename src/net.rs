@@ -0,0 +1,847 @@
+use crate::manifest::Manifest;
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+
+const SYS_CLASS_NET: &str = "/sys/class/net";
+const PROC_NET_VLAN_CONFIG: &str = "/proc/net/vlan/config";
+const RUN_NETNS: &str = "/run/netns";
+
+/// A Linux bridge and the ports enslaved to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BridgeInfo {
+    pub name: String,
+    pub ports: Vec<String>,
+    pub stp_enabled: bool,
+    pub vlan_filtering: bool,
+}
+
+/// An 802.1Q VLAN sub-interface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VlanInfo {
+    pub name: String,
+    pub vlan_id: u16,
+    pub parent: String,
+}
+
+/// A bonding master and its slaves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BondInfo {
+    pub name: String,
+    pub mode: String, // e.g. "802.3ad" (numeric suffix stripped)
+    pub slaves: Vec<String>,
+    pub active_slave: Option<String>,
+}
+
+/// Virtual network topology as seen in sysfs/procfs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetTopology {
+    pub bridges: Vec<BridgeInfo>,
+    pub vlans: Vec<VlanInfo>,
+    pub bonds: Vec<BondInfo>,
+    pub namespaces: Vec<String>,
+}
+
+/// Reads a sysfs attribute, trimmed. Returns `None` if missing or unreadable.
+fn read_attr(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Returns sorted names of the entries in a directory (empty if it doesn't exist).
+fn list_dir_names(path: &Path) -> Vec<String> {
+    let mut names: Vec<String> = match fs::read_dir(path) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    names.sort();
+    names
+}
+
+/// Lists all network interfaces in /sys/class/net.
+pub fn list_interfaces() -> Result<Vec<String>> {
+    list_interfaces_in(Path::new(SYS_CLASS_NET))
+}
+
+/// Same as [`list_interfaces`], but for an arbitrary sysfs `class/net` directory.
+pub fn list_interfaces_in(net_dir: &Path) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(net_dir)? {
+        let entry = entry?;
+        // Skip regular files such as `bonding_masters`
+        if entry.path().is_dir()
+            && let Some(name) = entry.file_name().to_str()
+        {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Discovers bridges, bonds, VLANs and named network namespaces.
+pub fn discover_topology() -> Result<NetTopology> {
+    let vlans = match fs::File::open(PROC_NET_VLAN_CONFIG) {
+        Ok(file) => parse_vlan_config_from_reader(std::io::BufReader::new(file))?,
+        // 8021q not loaded means no VLANs
+        Err(_) => Vec::new(),
+    };
+    let mut topology = discover_topology_in(Path::new(SYS_CLASS_NET))?;
+    topology.vlans = vlans;
+    topology.namespaces = list_dir_names(Path::new(RUN_NETNS));
+    Ok(topology)
+}
+
+/// Discovers bridges and bonds below a sysfs `class/net` directory.
+///
+/// VLANs and namespaces are not part of sysfs and are left empty.
+pub fn discover_topology_in(net_dir: &Path) -> Result<NetTopology> {
+    let mut topology = NetTopology::default();
+
+    for name in list_interfaces_in(net_dir)? {
+        let iface_dir = net_dir.join(&name);
+
+        if iface_dir.join("bridge").is_dir() {
+            topology.bridges.push(BridgeInfo {
+                ports: list_dir_names(&iface_dir.join("brif")),
+                stp_enabled: read_attr(&iface_dir.join("bridge/stp_state"))
+                    .is_some_and(|s| s != "0"),
+                vlan_filtering: read_attr(&iface_dir.join("bridge/vlan_filtering"))
+                    .is_some_and(|s| s == "1"),
+                name: name.clone(),
+            });
+        }
+
+        if iface_dir.join("bonding").is_dir() {
+            let mode = read_attr(&iface_dir.join("bonding/mode")).unwrap_or_default();
+            let slaves = read_attr(&iface_dir.join("bonding/slaves")).unwrap_or_default();
+            topology.bonds.push(BondInfo {
+                // The kernel reports e.g. "802.3ad 4"
+                mode: mode.split_whitespace().next().unwrap_or("").to_string(),
+                slaves: slaves.split_whitespace().map(|s| s.to_string()).collect(),
+                active_slave: read_attr(&iface_dir.join("bonding/active_slave"))
+                    .filter(|s| !s.is_empty()),
+                name,
+            });
+        }
+    }
+    Ok(topology)
+}
+
+/// Parses /proc/net/vlan/config.
+///
+/// The first two lines are headers; the rest are `name | vid | parent`.
+pub fn parse_vlan_config_from_reader<R: BufRead>(reader: R) -> Result<Vec<VlanInfo>> {
+    let mut vlans = Vec::new();
+
+    for line_result in reader.lines() {
+        let line = line_result?;
+        let fields: Vec<&str> = line.split('|').map(|f| f.trim()).collect();
+        if fields.len() != 3 {
+            continue;
+        }
+        // Header lines ("VLAN Dev name | VLAN ID", "Name-Type: ...") don't have a numeric id
+        if let Ok(vlan_id) = fields[1].parse::<u16>() {
+            vlans.push(VlanInfo {
+                name: fields[0].to_string(),
+                vlan_id,
+                parent: fields[2].to_string(),
+            });
+        }
+    }
+    Ok(vlans)
+}
+
+/// An expected bridge. Listed ports must all be enslaved; extra ports are reported.
+pub struct ExpectedBridge {
+    pub name: String,
+    pub ports: Vec<String>,
+}
+
+/// An expected VLAN sub-interface.
+pub struct ExpectedVlan {
+    pub name: String,
+    pub vlan_id: u16,
+    pub parent: String,
+}
+
+/// An expected bond. `mode` is checked only when set.
+pub struct ExpectedBond {
+    pub name: String,
+    pub mode: Option<String>,
+    pub slaves: Vec<String>,
+}
+
+/// Expected virtual network topology of a board.
+#[derive(Default)]
+pub struct ExpectedTopology {
+    pub bridges: Vec<ExpectedBridge>,
+    pub vlans: Vec<ExpectedVlan>,
+    pub bonds: Vec<ExpectedBond>,
+    pub namespaces: Vec<String>,
+}
+
+/// Holds results of a network topology validation.
+#[derive(Debug, Default)]
+pub struct NetValidationResult {
+    pub present: Vec<String>,
+    pub missing: Vec<String>,
+    pub mismatched: Vec<String>, // Human-readable description of each mismatch
+}
+
+impl NetValidationResult {
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+/// Reports members that are missing from or extra to the `actual` list.
+fn compare_members(
+    result: &mut NetValidationResult,
+    owner: &str,
+    kind: &str,
+    expected: &[String],
+    actual: &[String],
+) {
+    for member in expected {
+        if !actual.contains(member) {
+            result.mismatched.push(format!(
+                "{}: expected {} {} not attached",
+                owner, kind, member
+            ));
+        }
+    }
+    for member in actual {
+        if !expected.contains(member) {
+            result
+                .mismatched
+                .push(format!("{}: unexpected {} {}", owner, kind, member));
+        }
+    }
+}
+
+/// Checks a discovered topology against the expected one.
+pub fn validate_topology(
+    topology: &NetTopology,
+    expected: &ExpectedTopology,
+) -> NetValidationResult {
+    let mut result = NetValidationResult::default();
+
+    for exp in &expected.bridges {
+        match topology.bridges.iter().find(|b| b.name == exp.name) {
+            Some(bridge) => {
+                result.present.push(exp.name.clone());
+                compare_members(&mut result, &exp.name, "port", &exp.ports, &bridge.ports);
+            }
+            None => result.missing.push(exp.name.clone()),
+        }
+    }
+
+    for exp in &expected.vlans {
+        match topology.vlans.iter().find(|v| v.name == exp.name) {
+            Some(vlan) => {
+                result.present.push(exp.name.clone());
+                if vlan.vlan_id != exp.vlan_id {
+                    result.mismatched.push(format!(
+                        "{}: VLAN ID {} (expected {})",
+                        exp.name, vlan.vlan_id, exp.vlan_id
+                    ));
+                }
+                if vlan.parent != exp.parent {
+                    result.mismatched.push(format!(
+                        "{}: parent {} (expected {})",
+                        exp.name, vlan.parent, exp.parent
+                    ));
+                }
+            }
+            None => result.missing.push(exp.name.clone()),
+        }
+    }
+
+    for exp in &expected.bonds {
+        match topology.bonds.iter().find(|b| b.name == exp.name) {
+            Some(bond) => {
+                result.present.push(exp.name.clone());
+                if let Some(mode) = &exp.mode
+                    && &bond.mode != mode
+                {
+                    result.mismatched.push(format!(
+                        "{}: mode {} (expected {})",
+                        exp.name, bond.mode, mode
+                    ));
+                }
+                compare_members(&mut result, &exp.name, "slave", &exp.slaves, &bond.slaves);
+            }
+            None => result.missing.push(exp.name.clone()),
+        }
+    }
+
+    for ns in &expected.namespaces {
+        if topology.namespaces.contains(ns) {
+            result.present.push(format!("netns:{}", ns));
+        } else {
+            result.missing.push(format!("netns:{}", ns));
+        }
+    }
+
+    result
+}
+
+/// Link state of an interface, from `operstate`, `carrier` and `speed`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkState {
+    pub operstate: String,
+    pub carrier: Option<bool>, // None if the interface is down
+    pub speed: Option<u32>,    // Mb/s, None if unknown
+}
+
+impl LinkState {
+    pub fn is_up(&self) -> bool {
+        self.carrier == Some(true)
+    }
+}
+
+/// The name of the driver bound to a device directory.
+fn driver_name(device_dir: &Path) -> Option<String> {
+    fs::read_link(device_dir.join("driver"))
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+}
+
+fn read_link_state(iface_dir: &Path) -> LinkState {
+    LinkState {
+        operstate: read_attr(&iface_dir.join("operstate")).unwrap_or_else(|| "unknown".into()),
+        carrier: read_attr(&iface_dir.join("carrier")).map(|s| s == "1"),
+        // Reads as -1 (or fails with EINVAL) when there is no link
+        speed: read_attr(&iface_dir.join("speed")).and_then(|s| s.parse::<u32>().ok()),
+    }
+}
+
+/// A user port of a DSA/switchdev switch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchPort {
+    pub name: String,      // Netdev name, e.g. "lan1"
+    pub port_name: String, // phys_port_name, e.g. "p1"
+    pub link: LinkState,
+}
+
+/// An Ethernet switch, grouped by `phys_switch_id`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchInfo {
+    pub switch_id: String,
+    pub driver: String,           // e.g. "ksz9477-switch", "mv88e6085"
+    pub compatible: Vec<String>,  // From the switch's DT node, if any
+    pub cpu_port: Option<String>, // DSA conduit interface, e.g. "eth0"
+    pub tagging: Option<String>,  // DSA tag protocol on the conduit
+    pub ports: Vec<SwitchPort>,
+}
+
+fn read_ifindex(iface_dir: &Path, attr: &str) -> Option<u32> {
+    read_attr(&iface_dir.join(attr)).and_then(|s| s.parse::<u32>().ok())
+}
+
+/// Discovers DSA/switchdev switches in /sys/class/net.
+pub fn discover_switches() -> Result<Vec<SwitchInfo>> {
+    discover_switches_in(Path::new(SYS_CLASS_NET))
+}
+
+/// Same as [`discover_switches`], but for an arbitrary sysfs `class/net` directory.
+pub fn discover_switches_in(net_dir: &Path) -> Result<Vec<SwitchInfo>> {
+    let interfaces = list_interfaces_in(net_dir)?;
+    let mut switches: Vec<SwitchInfo> = Vec::new();
+
+    for name in &interfaces {
+        let iface_dir = net_dir.join(name);
+        // Reading phys_switch_id fails with EOPNOTSUPP on non-switch netdevs
+        let Some(switch_id) =
+            read_attr(&iface_dir.join("phys_switch_id")).filter(|s| !s.is_empty())
+        else {
+            continue;
+        };
+
+        // DSA user ports have iflink pointing at the conduit interface
+        let mut cpu_port = None;
+        let mut tagging = None;
+        if let (Some(ifindex), Some(iflink)) = (
+            read_ifindex(&iface_dir, "ifindex"),
+            read_ifindex(&iface_dir, "iflink"),
+        ) && ifindex != iflink
+        {
+            cpu_port = interfaces
+                .iter()
+                .find(|other| read_ifindex(&net_dir.join(other), "ifindex") == Some(iflink))
+                .cloned();
+            tagging = cpu_port
+                .as_ref()
+                .and_then(|c| read_attr(&net_dir.join(c).join("dsa/tagging")));
+        }
+
+        let port = SwitchPort {
+            name: name.clone(),
+            port_name: read_attr(&iface_dir.join("phys_port_name")).unwrap_or_default(),
+            link: read_link_state(&iface_dir),
+        };
+
+        match switches.iter_mut().find(|s| s.switch_id == switch_id) {
+            Some(switch) => switch.ports.push(port),
+            None => {
+                let device_dir = iface_dir.join("device");
+                let driver = driver_name(&device_dir).unwrap_or_default();
+                let compatible = fs::read(device_dir.join("of_node/compatible"))
+                    .map(|raw| {
+                        raw.split(|&b| b == 0)
+                            .filter(|s| !s.is_empty())
+                            .map(|s| String::from_utf8_lossy(s).to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                switches.push(SwitchInfo {
+                    switch_id,
+                    driver,
+                    compatible,
+                    cpu_port,
+                    tagging,
+                    ports: vec![port],
+                });
+            }
+        }
+    }
+    Ok(switches)
+}
+
+/// An expected switch user port. `speed` is checked only when set.
+pub struct ExpectedSwitchPort {
+    pub name: String,
+    pub link_up: bool,
+    pub speed: Option<u32>,
+}
+
+/// An expected switch; `model` is matched against the driver name and DT compatibles.
+pub struct ExpectedSwitch {
+    pub model: String,
+    pub port_count: usize,
+    pub cpu_port: Option<String>,
+    pub ports: Vec<ExpectedSwitchPort>,
+}
+
+impl SwitchInfo {
+    pub fn matches_model(&self, model: &str) -> bool {
+        self.driver.contains(model) || self.compatible.iter().any(|c| c.contains(model))
+    }
+}
+
+/// Checks discovered switches for expected model, port count, CPU port and per-port link.
+pub fn validate_switches(
+    switches: &[SwitchInfo],
+    expected: &[ExpectedSwitch],
+) -> NetValidationResult {
+    let mut result = NetValidationResult::default();
+
+    for exp in expected {
+        let Some(switch) = switches.iter().find(|s| s.matches_model(&exp.model)) else {
+            result.missing.push(exp.model.clone());
+            continue;
+        };
+        result.present.push(exp.model.clone());
+
+        if switch.ports.len() != exp.port_count {
+            result.mismatched.push(format!(
+                "{}: {} user ports (expected {})",
+                exp.model,
+                switch.ports.len(),
+                exp.port_count
+            ));
+        }
+        if let Some(cpu_port) = &exp.cpu_port
+            && switch.cpu_port.as_ref() != Some(cpu_port)
+        {
+            result.mismatched.push(format!(
+                "{}: CPU port {} (expected {})",
+                exp.model,
+                switch.cpu_port.as_deref().unwrap_or("none"),
+                cpu_port
+            ));
+        }
+
+        for exp_port in &exp.ports {
+            let Some(port) = switch.ports.iter().find(|p| p.name == exp_port.name) else {
+                result
+                    .mismatched
+                    .push(format!("{}: port {} not found", exp.model, exp_port.name));
+                continue;
+            };
+            if exp_port.link_up && !port.link.is_up() {
+                result.mismatched.push(format!(
+                    "{}: port {} has no link ({})",
+                    exp.model, port.name, port.link.operstate
+                ));
+            }
+            if let Some(speed) = exp_port.speed
+                && port.link.speed != Some(speed)
+            {
+                result.mismatched.push(format!(
+                    "{}: port {} speed {:?} (expected {})",
+                    exp.model, port.name, port.link.speed, speed
+                ));
+            }
+        }
+    }
+    result
+}
+
+/// An Ethernet (EUI-48) hardware address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MacAddress(pub [u8; 6]);
+
+impl MacAddress {
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0; 6]
+    }
+
+    /// Group addresses, broadcast included, are never a valid interface address.
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+
+    /// Not from a vendor's OUI: set in software, e.g. a driver's fallback or a placeholder.
+    pub fn is_locally_administered(&self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+
+    /// Whether the address starts with `prefix`, e.g. the OUI "00:1b:c5", or an MA-M
+    /// block such as "70:b3:d5:1".
+    pub fn has_prefix(&self, prefix: &str) -> bool {
+        self.to_string().starts_with(&prefix.to_ascii_lowercase())
+    }
+
+    /// Whether the address matches a provisioning pattern such as "00:1b:c5:0a:xx:xx",
+    /// where each `x` stands for any hex digit.
+    pub fn matches_pattern(&self, pattern: &str) -> bool {
+        let text = self.to_string();
+        pattern.len() == text.len()
+            && pattern
+                .to_ascii_lowercase()
+                .chars()
+                .zip(text.chars())
+                .all(|(p, c)| (p == 'x' && c != ':') || p == c)
+    }
+}
+
+impl std::str::FromStr for MacAddress {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<MacAddress> {
+        let bad = || anyhow::anyhow!("invalid MAC address {:?}", s);
+        let mut bytes = [0u8; 6];
+        let mut parts = s.trim().split(':');
+        for byte in &mut bytes {
+            let part = parts.next().filter(|p| p.len() == 2).ok_or_else(bad)?;
+            *byte = u8::from_str_radix(part, 16).map_err(|_| bad())?;
+        }
+        if parts.next().is_some() {
+            return Err(bad());
+        }
+        Ok(MacAddress(bytes))
+    }
+}
+
+impl std::fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            a, b, c, d, e, g
+        )
+    }
+}
+
+/// A network interface with its hardware address and link.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interface {
+    pub name: String,
+    pub mac: Option<MacAddress>, // None for interfaces without an Ethernet address, e.g. wwan0
+    pub random_mac: bool,        // `addr_assign_type` 1: the driver made one up
+    pub physical: bool,          // Has a `device`, unlike bridges, VLANs and tunnels
+    pub link: LinkState,
+    pub full_duplex: Option<bool>,  // None without a link
+    pub driver: Option<String>,     // The MAC's driver, e.g. "st_gmac"
+    pub phy_driver: Option<String>, // e.g. "RTL8211F Gigabit Ethernet", or "Generic PHY"
+    pub phy_id: Option<String>,     // e.g. "0x001cc916"
+    pub autoneg: Option<bool>,      // From ethtool; None where it can't be asked
+    pub firmware: Option<String>,   // From ethtool
+}
+
+/// Lists the interfaces in /sys/class/net with their addresses, link state and drivers.
+///
+/// With the `hardware` feature, autoneg and firmware versions come from ethtool ioctls.
+pub fn read_interfaces() -> Result<Vec<Interface>> {
+    #[allow(unused_mut)]
+    let mut interfaces = read_interfaces_in(Path::new(SYS_CLASS_NET))?;
+    #[cfg(feature = "hardware")]
+    for iface in interfaces.iter_mut().filter(|i| i.physical) {
+        // Drivers without ethtool support, e.g. some USB gadgets, leave these None
+        iface.autoneg = crate::ethtool::link_settings(&iface.name)
+            .ok()
+            .map(|s| s.autoneg);
+        iface.firmware = crate::ethtool::driver_info(&iface.name)
+            .ok()
+            .and_then(|i| i.firmware);
+    }
+    Ok(interfaces)
+}
+
+/// Same as [`read_interfaces`], but for an arbitrary sysfs `class/net` directory, and
+/// from sysfs alone.
+pub fn read_interfaces_in(net_dir: &Path) -> Result<Vec<Interface>> {
+    Ok(list_interfaces_in(net_dir)?
+        .into_iter()
+        .map(|name| {
+            let iface_dir = net_dir.join(&name);
+            let phy_dir = iface_dir.join("phydev");
+            Interface {
+                mac: read_attr(&iface_dir.join("address")).and_then(|a| a.parse().ok()),
+                random_mac: read_attr(&iface_dir.join("addr_assign_type"))
+                    .is_some_and(|t| t == "1"),
+                physical: iface_dir.join("device").exists(),
+                link: read_link_state(&iface_dir),
+                // Reads as "unknown" (or fails with EINVAL) when there is no link
+                full_duplex: match read_attr(&iface_dir.join("duplex")).as_deref() {
+                    Some("full") => Some(true),
+                    Some("half") => Some(false),
+                    _ => None,
+                },
+                driver: driver_name(&iface_dir.join("device")),
+                phy_driver: driver_name(&phy_dir),
+                phy_id: read_attr(&phy_dir.join("phy_id")),
+                autoneg: None,
+                firmware: None,
+                name,
+            }
+        })
+        .collect())
+}
+
+/// An expected interface. Each check applies only when set.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedInterface {
+    pub name: String,
+    pub mac_prefix: Option<String>, // The vendor OUI, or a longer prefix
+    pub mac_pattern: Option<String>, // e.g. "00:1b:c5:0a:xx:xx"
+    pub allow_local: bool,          // Locally administered or random MACs are fine
+    pub carrier: Option<bool>,
+    pub speed: Option<u32>, // Mb/s
+    pub full_duplex: Option<bool>,
+    pub autoneg: Option<bool>,
+    pub driver: Option<String>,
+    pub phy_driver: Option<String>,
+    pub firmware: Option<String>,
+}
+
+/// Expectations of the `[net_interfaces]` manifest section.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedInterfaces {
+    pub interfaces: Vec<ExpectedInterface>,
+}
+
+impl ExpectedInterfaces {
+    pub fn from_manifest(manifest: &Manifest) -> Result<ExpectedInterfaces> {
+        let Some(section) = manifest.sections.get("net_interfaces") else {
+            return Ok(ExpectedInterfaces::default());
+        };
+        let interfaces = match &section["interfaces"] {
+            Value::Null => Vec::new(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let string = |key: &str| item[key].as_str().map(str::to_string);
+                    let interface = ExpectedInterface {
+                        name: string("name").ok_or_else(|| {
+                            anyhow::anyhow!("net_interfaces.interfaces {}: missing `name`", i)
+                        })?,
+                        mac_prefix: string("mac_prefix"),
+                        mac_pattern: string("mac_pattern"),
+                        allow_local: item["allow_local"].as_bool().unwrap_or(false),
+                        carrier: item["carrier"].as_bool(),
+                        speed: match &item["speed"] {
+                            Value::Null => None,
+                            value => Some(
+                                value
+                                    .as_u64()
+                                    .and_then(|s| u32::try_from(s).ok())
+                                    .ok_or_else(|| {
+                                        anyhow::anyhow!(
+                                            "net_interfaces.interfaces {}: `speed` must be in Mb/s",
+                                            i
+                                        )
+                                    })?,
+                            ),
+                        },
+                        full_duplex: match string("duplex").as_deref() {
+                            None => None,
+                            Some("full") => Some(true),
+                            Some("half") => Some(false),
+                            Some(other) => anyhow::bail!(
+                                "net_interfaces.interfaces {}: duplex {:?}, expected full or half",
+                                i,
+                                other
+                            ),
+                        },
+                        autoneg: item["autoneg"].as_bool(),
+                        driver: string("driver"),
+                        phy_driver: string("phy_driver"),
+                        firmware: string("firmware"),
+                    };
+                    if let Some(pattern) = &interface.mac_pattern
+                        && pattern
+                            .replace(['x', 'X'], "0")
+                            .parse::<MacAddress>()
+                            .is_err()
+                    {
+                        anyhow::bail!(
+                            "net_interfaces.interfaces {}: invalid mac_pattern {:?}",
+                            i,
+                            pattern
+                        );
+                    }
+                    Ok(interface)
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => anyhow::bail!("net_interfaces.interfaces must be a list"),
+        };
+        Ok(ExpectedInterfaces { interfaces })
+    }
+}
+
+/// Checks that expected interfaces exist, have a provisioned MAC address, the expected
+/// carrier state and negotiated link, and are bound to the expected drivers.
+///
+/// An all-zero, multicast, locally administered or driver-generated address means the
+/// unit's address was never provisioned, e.g. an empty EEPROM or a missing DT property.
+pub fn validate_interfaces(
+    interfaces: &[Interface],
+    expected: &ExpectedInterfaces,
+) -> NetValidationResult {
+    let mut result = NetValidationResult::default();
+
+    for exp in &expected.interfaces {
+        let Some(iface) = interfaces.iter().find(|i| i.name == exp.name) else {
+            result.missing.push(exp.name.clone());
+            continue;
+        };
+        result.present.push(exp.name.clone());
+
+        match iface.mac {
+            None => result
+                .mismatched
+                .push(format!("{}: no MAC address", exp.name)),
+            Some(mac) if mac.is_zero() => result
+                .mismatched
+                .push(format!("{}: MAC address is all zeros", exp.name)),
+            Some(mac) if mac.is_multicast() => result
+                .mismatched
+                .push(format!("{}: {} is a multicast address", exp.name, mac)),
+            Some(mac) => {
+                if !exp.allow_local && iface.random_mac {
+                    result
+                        .mismatched
+                        .push(format!("{}: random MAC address {}", exp.name, mac));
+                } else if !exp.allow_local && mac.is_locally_administered() {
+                    result.mismatched.push(format!(
+                        "{}: locally administered MAC address {}",
+                        exp.name, mac
+                    ));
+                }
+                if let Some(prefix) = &exp.mac_prefix
+                    && !mac.has_prefix(prefix)
+                {
+                    result.mismatched.push(format!(
+                        "{}: MAC address {} (expected prefix {})",
+                        exp.name, mac, prefix
+                    ));
+                }
+                if let Some(pattern) = &exp.mac_pattern
+                    && !mac.matches_pattern(pattern)
+                {
+                    result.mismatched.push(format!(
+                        "{}: MAC address {} does not match {}",
+                        exp.name, mac, pattern
+                    ));
+                }
+            }
+        }
+
+        if let Some(carrier) = exp.carrier
+            && iface.link.is_up() != carrier
+        {
+            let state = if carrier { "no carrier" } else { "carrier" };
+            result.mismatched.push(format!(
+                "{}: {} ({})",
+                exp.name, state, iface.link.operstate
+            ));
+        }
+        compare_link(&mut result, iface, exp);
+    }
+    result
+}
+
+/// Negotiated link and drivers. A PHY bound to "Generic PHY" instead of its own driver
+/// usually means bad strapping: the PHY answered at another address or with another ID.
+fn compare_link(result: &mut NetValidationResult, iface: &Interface, exp: &ExpectedInterface) {
+    let duplex = |full: bool| if full { "full" } else { "half" };
+    let state = |on: bool| if on { "on" } else { "off" };
+    if let Some(speed) = exp.speed
+        && iface.link.speed != Some(speed)
+    {
+        let actual = iface.link.speed.map(|s| format!("{} Mb/s", s));
+        result.mismatched.push(format!(
+            "{}: speed {} (expected {} Mb/s)",
+            exp.name,
+            actual.as_deref().unwrap_or("unknown"),
+            speed
+        ));
+    }
+    if let Some(full) = exp.full_duplex
+        && iface.full_duplex != Some(full)
+    {
+        result.mismatched.push(format!(
+            "{}: {} duplex (expected {})",
+            exp.name,
+            iface.full_duplex.map(duplex).unwrap_or("unknown"),
+            duplex(full)
+        ));
+    }
+    if let Some(autoneg) = exp.autoneg
+        && iface.autoneg != Some(autoneg)
+    {
+        result.mismatched.push(format!(
+            "{}: autoneg {} (expected {})",
+            exp.name,
+            iface.autoneg.map(state).unwrap_or("unknown"),
+            state(autoneg)
+        ));
+    }
+    let drivers = [
+        ("driver", &exp.driver, &iface.driver),
+        ("PHY driver", &exp.phy_driver, &iface.phy_driver),
+        ("firmware", &exp.firmware, &iface.firmware),
+    ];
+    for (what, want, actual) in drivers {
+        if let Some(want) = want
+            && actual.as_ref() != Some(want)
+        {
+            result.mismatched.push(format!(
+                "{}: {} {} (expected {})",
+                exp.name,
+                what,
+                actual.as_deref().unwrap_or("none"),
+                want
+            ));
+        }
+    }
+}
@@ -1,11 +1,21 @@
+use crate::error::{self, Error};
+use crate::system_root::SystemRoot;
 use anyhow::Result;
 use std::collections::HashMap;
 use std::io::BufRead;
 
-pub fn parse_os_release(path: &str) -> Result<HashMap<String, String>> {
-    let file = std::fs::File::open(path)?;
+pub fn parse_os_release(path: &str) -> error::Result<HashMap<String, String>> {
+    let file = std::fs::File::open(path).map_err(|e| Error::io(path, e))?;
     let reader = std::io::BufReader::new(file);
-    parse_os_release_from_reader(reader)
+    parse_os_release_from_reader(reader).map_err(|e| Error::ParseError {
+        what: path.to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// The os-release of a system, see [`SystemRoot::os_release`].
+pub fn read_os_release_in(root: &SystemRoot) -> error::Result<HashMap<String, String>> {
+    parse_os_release(&root.os_release().to_string_lossy())
 }
 
 pub fn parse_os_release_from_reader<R: BufRead>(reader: R) -> Result<HashMap<String, String>> {
@@ -0,0 +1,163 @@
+use crate::boot_slot;
+use crate::runtime::{self, Dependency};
+use anyhow::Result;
+use serde_json::Value;
+use std::process::Command;
+
+/// An OTA client's view of the installed software.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtaStatus {
+    pub client: String, // "rauc" or "mender"
+    pub installed_version: Option<String>,
+    pub booted_slot: Option<String>,
+    pub healthy: bool,
+    pub problems: Vec<String>, // Why `healthy` is false
+}
+
+pub trait OtaClient {
+    fn status(&self) -> Result<OtaStatus>;
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String> {
+    runtime::require(&Dependency::program(program))?;
+    let output = Command::new(program).args(args).output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// RAUC, queried through the `rauc` CLI, which talks to the service over D-Bus.
+pub struct Rauc;
+
+impl Rauc {
+    /// Builds the status from `rauc status --detailed --output-format=json`.
+    ///
+    /// The booted slot must report `boot_status: good` and a bundle version.
+    pub fn status_from_json(json: &str) -> Result<OtaStatus> {
+        let root: Value = serde_json::from_str(json)?;
+        let booted = root["booted"].as_str().map(|s| s.to_string());
+        let mut status = OtaStatus {
+            client: "rauc".to_string(),
+            installed_version: None,
+            booted_slot: booted.clone(),
+            healthy: true,
+            problems: Vec::new(),
+        };
+
+        // "slots" is a list of single-key objects: [{"rootfs.0": {..}}, ..]
+        let slots = root["slots"].as_array().cloned().unwrap_or_default();
+        let booted_slot = slots
+            .iter()
+            .filter_map(|s| s.as_object())
+            .flat_map(|s| s.iter())
+            .find(|(_, slot)| slot["state"] == "booted");
+
+        match booted_slot {
+            Some((name, slot)) => {
+                status.installed_version = slot["slot_status"]["bundle"]["version"]
+                    .as_str()
+                    .map(|s| s.to_string());
+                if status.installed_version.is_none() {
+                    status.problems.push(format!("{}: no bundle version", name));
+                }
+                let boot_status = slot["boot_status"].as_str().unwrap_or("unknown");
+                if boot_status != "good" {
+                    status
+                        .problems
+                        .push(format!("{}: boot status {}", name, boot_status));
+                }
+            }
+            None => status.problems.push("no slot in booted state".to_string()),
+        }
+
+        status.healthy = status.problems.is_empty();
+        Ok(status)
+    }
+}
+
+impl OtaClient for Rauc {
+    fn status(&self) -> Result<OtaStatus> {
+        Rauc::status_from_json(&run(
+            "rauc",
+            &["status", "--detailed", "--output-format=json"],
+        )?)
+    }
+}
+
+/// Mender client (v4 `mender-update` or v3 `mender`).
+pub struct Mender {
+    pub program: String, // "mender-update" or "mender"
+}
+
+impl Mender {
+    /// Builds the status from `show-artifact` output and the U-Boot environment.
+    ///
+    /// `upgrade_available=1` means an installed update was never committed.
+    pub fn status_from_output(
+        artifact: &str,
+        env: &std::collections::HashMap<String, String>,
+    ) -> OtaStatus {
+        let artifact = artifact.trim();
+        let mut problems = Vec::new();
+        if artifact.is_empty() || artifact == "unknown" {
+            problems.push("no artifact installed".to_string());
+        }
+        if env.get("upgrade_available").map(|v| v.trim()) == Some("1") {
+            problems.push("update installed but not committed".to_string());
+        }
+        OtaStatus {
+            client: "mender".to_string(),
+            installed_version: (!artifact.is_empty()).then(|| artifact.to_string()),
+            booted_slot: env.get("mender_boot_part").map(|v| v.trim().to_string()),
+            healthy: problems.is_empty(),
+            problems,
+        }
+    }
+}
+
+impl OtaClient for Mender {
+    fn status(&self) -> Result<OtaStatus> {
+        let artifact = run(&self.program, &["show-artifact"])?;
+        // Not all boards have fw_printenv (e.g. GRUB integration); treat as committed
+        let env = run("fw_printenv", &[])
+            .ok()
+            .and_then(|out| boot_slot::parse_env_from_reader(out.as_bytes()).ok())
+            .unwrap_or_default();
+        Ok(Mender::status_from_output(&artifact, &env))
+    }
+}
+
+/// Expected post-update state. `version` is checked only when set.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedOta {
+    pub version: Option<String>,
+    pub require_healthy: bool,
+}
+
+/// Returns human-readable descriptions of every mismatch.
+pub fn validate_ota(status: &OtaStatus, expected: &ExpectedOta) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    if let Some(version) = &expected.version
+        && status.installed_version.as_ref() != Some(version)
+    {
+        mismatches.push(format!(
+            "{}: installed version {} (expected {})",
+            status.client,
+            status.installed_version.as_deref().unwrap_or("none"),
+            version
+        ));
+    }
+    if expected.require_healthy {
+        for problem in &status.problems {
+            mismatches.push(format!("{}: {}", status.client, problem));
+        }
+    }
+    mismatches
+}
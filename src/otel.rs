@@ -0,0 +1,201 @@
+use crate::measurement::Measurement;
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// An OpenTelemetry attribute value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    String(String),
+    Bool(bool),
+    Int(i64),
+    Double(f64),
+}
+
+impl AttributeValue {
+    fn to_otlp(&self) -> Value {
+        match self {
+            AttributeValue::String(s) => json!({ "stringValue": s }),
+            AttributeValue::Bool(b) => json!({ "boolValue": b }),
+            // OTLP/JSON encodes 64-bit integers as strings
+            AttributeValue::Int(i) => json!({ "intValue": i.to_string() }),
+            AttributeValue::Double(d) => json!({ "doubleValue": d }),
+        }
+    }
+}
+
+impl From<&str> for AttributeValue {
+    fn from(s: &str) -> Self {
+        AttributeValue::String(s.to_string())
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(b: bool) -> Self {
+        AttributeValue::Bool(b)
+    }
+}
+
+impl From<i64> for AttributeValue {
+    fn from(i: i64) -> Self {
+        AttributeValue::Int(i)
+    }
+}
+
+impl From<f64> for AttributeValue {
+    fn from(d: f64) -> Self {
+        AttributeValue::Double(d)
+    }
+}
+
+fn attributes_to_otlp(attributes: &[(String, AttributeValue)]) -> Vec<Value> {
+    attributes
+        .iter()
+        .map(|(k, v)| json!({ "key": k, "value": v.to_otlp() }))
+        .collect()
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+        .to_string()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    fs::File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// A check in progress; becomes a span of the run's trace.
+#[derive(Debug, Clone)]
+pub struct CheckSpan {
+    pub name: String,
+    pub start: SystemTime,
+    pub end: Option<SystemTime>,
+    pub attributes: Vec<(String, AttributeValue)>,
+    pub passed: Option<bool>,
+}
+
+impl CheckSpan {
+    pub fn start(name: &str) -> CheckSpan {
+        CheckSpan {
+            name: name.to_string(),
+            start: SystemTime::now(),
+            end: None,
+            attributes: Vec::new(),
+            passed: None,
+        }
+    }
+
+    pub fn attr(mut self, key: &str, value: impl Into<AttributeValue>) -> CheckSpan {
+        self.attributes.push((key.to_string(), value.into()));
+        self
+    }
+
+    /// Adds `measurement.<source>` (the value) and `measurement.<source>.unit`.
+    pub fn measurement(self, measurement: &Measurement) -> CheckSpan {
+        let key = format!("measurement.{}", measurement.source);
+        let unit_key = format!("{}.unit", key);
+        self.attr(&key, measurement.value)
+            .attr(&unit_key, measurement.unit.symbol())
+    }
+
+    /// Ends the span now with the check result.
+    pub fn finish(mut self, passed: bool) -> CheckSpan {
+        self.end = Some(SystemTime::now());
+        self.passed = Some(passed);
+        self
+    }
+}
+
+/// A validation run exported as one trace: a root span with a child span per check.
+#[derive(Debug, Clone)]
+pub struct RunTrace {
+    pub trace_id: [u8; 16],
+    pub root: CheckSpan,
+    pub resource: Vec<(String, AttributeValue)>, // e.g. service.name, host.name
+    pub checks: Vec<([u8; 8], CheckSpan)>,
+    root_id: [u8; 8],
+}
+
+impl RunTrace {
+    /// Starts a trace with random IDs; `station` becomes the `host.name` resource attribute.
+    pub fn start(run_name: &str, station: &str) -> Result<RunTrace> {
+        Ok(RunTrace {
+            trace_id: random_bytes()?,
+            root: CheckSpan::start(run_name),
+            resource: vec![
+                ("service.name".to_string(), "tux-validation".into()),
+                ("host.name".to_string(), station.into()),
+            ],
+            checks: Vec::new(),
+            root_id: random_bytes()?,
+        })
+    }
+
+    /// Adds a check span; unfinished spans are ended now without a result.
+    pub fn record(&mut self, mut check: CheckSpan) -> Result<()> {
+        if check.end.is_none() {
+            check.end = Some(SystemTime::now());
+        }
+        self.checks.push((random_bytes()?, check));
+        Ok(())
+    }
+
+    fn span_to_otlp(&self, id: &[u8; 8], parent: Option<&[u8; 8]>, span: &CheckSpan) -> Value {
+        // STATUS_CODE_UNSET = 0, OK = 1, ERROR = 2
+        let status = match span.passed {
+            None => json!({ "code": 0 }),
+            Some(true) => json!({ "code": 1 }),
+            Some(false) => json!({ "code": 2, "message": "check failed" }),
+        };
+        let mut value = json!({
+            "traceId": hex(&self.trace_id),
+            "spanId": hex(id),
+            "name": span.name,
+            "kind": 1, // SPAN_KIND_INTERNAL
+            "startTimeUnixNano": unix_nanos(span.start),
+            "endTimeUnixNano": unix_nanos(span.end.unwrap_or_else(SystemTime::now)),
+            "attributes": attributes_to_otlp(&span.attributes),
+            "status": status,
+        });
+        if let Some(parent) = parent {
+            value["parentSpanId"] = json!(hex(parent));
+        }
+        value
+    }
+
+    /// Ends the root span and renders the trace as an OTLP/JSON `ExportTraceServiceRequest`.
+    pub fn finish(mut self, passed: bool) -> Value {
+        self.root = self.root.clone().finish(passed);
+        let mut spans = vec![self.span_to_otlp(&self.root_id, None, &self.root)];
+        for (id, check) in &self.checks {
+            spans.push(self.span_to_otlp(id, Some(&self.root_id), check));
+        }
+        json!({
+            "resourceSpans": [{
+                "resource": { "attributes": attributes_to_otlp(&self.resource) },
+                "scopeSpans": [{
+                    "scope": { "name": "tux-validation", "version": env!("CARGO_PKG_VERSION") },
+                    "spans": spans,
+                }],
+            }],
+        })
+    }
+}
+
+/// Appends a trace as one line, the format the collector's `otlpjsonfile` receiver reads.
+pub fn append_otlp_json(path: &Path, trace: &Value) -> Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(trace)?)?;
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use crate::device::{DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Sysfs attributes copied into [`TuxDevice::attributes`], as the kernel prints them
+/// (IDs and class are "0x"-prefixed hex).
+const ATTRIBUTES: [&str; 8] = [
+    "vendor",
+    "device",
+    "subsystem_vendor",
+    "subsystem_device",
+    "class",
+    "revision",
+    "current_link_speed",
+    "current_link_width",
+];
+
+fn hex_attribute(device: &TuxDevice, attribute: &str) -> Option<u32> {
+    let value = device.attributes.get(attribute)?;
+    u32::from_str_radix(value.trim_start_matches("0x"), 16).ok()
+}
+
+/// Vendor and device ID of a device found by [`find_pci_devices`].
+pub fn ids(device: &TuxDevice) -> Option<(u16, u16)> {
+    Some((
+        u16::try_from(hex_attribute(device, "vendor")?).ok()?,
+        u16::try_from(hex_attribute(device, "device")?).ok()?,
+    ))
+}
+
+/// 24-bit class code (class, subclass, prog-if), e.g. 0x028000 for a network controller.
+pub fn class(device: &TuxDevice) -> Option<u32> {
+    hex_attribute(device, "class")
+}
+
+/// Lists PCI functions, enriched with udev data.
+pub fn find_pci_devices() -> Result<Vec<TuxDevice>> {
+    find_pci_devices_in(Path::new("/sys"), Path::new("/run/udev/data"))
+}
+
+/// Same as [`find_pci_devices`], with explicit sysfs and udev database roots.
+pub fn find_pci_devices_in(sys_root: &Path, udev_db: &Path) -> Result<Vec<TuxDevice>> {
+    let mut devices = Vec::new();
+    for entry in fs::read_dir(sys_root.join("bus/pci/devices"))? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(address) = DeviceAddress::parse(Subsystem::Pci, &name) else {
+            continue;
+        };
+        let dir = entry.path();
+        let mut device = TuxDevice::from_udev_in(&dir, udev_db, Subsystem::Pci, address);
+        for attribute in ATTRIBUTES {
+            if let Some(value) = read_trimmed(&dir.join(attribute)) {
+                device.attributes.insert(attribute.to_string(), value);
+            }
+        }
+        // The kernel has no name for PCI functions; hwdb does, if udev ran
+        device.name = match (
+            device.attributes.get("ID_MODEL_FROM_DATABASE"),
+            ids(&device),
+        ) {
+            (Some(model), _) => model.clone(),
+            (None, Some((vendor, id))) => format!("{:04x}:{:04x}", vendor, id),
+            (None, None) => device.name,
+        };
+        devices.push(device);
+    }
+    devices.sort_by_key(|d| d.address.clone());
+    Ok(devices)
+}
+
+/// Builds one Board model bus per PCI bus ("pci0000:01", ..) with its functions.
+pub fn audit_pci_buses() -> Result<Vec<TuxBus>> {
+    audit_pci_buses_in(Path::new("/sys"), Path::new("/run/udev/data"))
+}
+
+/// Same as [`audit_pci_buses`], with explicit sysfs and udev database roots.
+pub fn audit_pci_buses_in(sys_root: &Path, udev_db: &Path) -> Result<Vec<TuxBus>> {
+    let mut buses: Vec<TuxBus> = Vec::new();
+    for device in find_pci_devices_in(sys_root, udev_db)? {
+        let DeviceAddress::Pci { domain, bus, .. } = device.address else {
+            continue;
+        };
+        let id = format!("pci{:04x}:{:02x}", domain, bus);
+        match buses.iter_mut().find(|b| b.id == id) {
+            Some(tux_bus) => tux_bus.devices.push(device),
+            None => buses.push(TuxBus {
+                subsystem: Subsystem::Pci,
+                id,
+                name: String::new(),
+                devices: vec![device],
+                metadata: BTreeMap::new(),
+            }),
+        }
+    }
+    Ok(buses)
+}
+
+/// An expected PCI function.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedPciDevice {
+    pub slot: Option<String>, // e.g. "0000:01:00.0"; None matches the IDs anywhere
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub class: Option<u32>,
+    pub driver: Option<String>,
+}
+
+/// Holds results of checking PCI functions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PciValidationResult {
+    pub present: Vec<DeviceAddress>,
+    pub missing: Vec<String>, // Slot, or "vendor:device" for slot-less entries
+    pub mismatched: Vec<String>, // Wrong IDs, class or driver
+}
+
+impl PciValidationResult {
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+/// Checks the expected functions against the devices found.
+pub fn validate_pci_devices(
+    devices: &[TuxDevice],
+    expected: &[ExpectedPciDevice],
+) -> PciValidationResult {
+    let mut result = PciValidationResult::default();
+    for exp in expected {
+        let wanted = (exp.vendor_id, exp.device_id);
+        let found = match &exp.slot {
+            Some(slot) => devices.iter().find(|d| &d.address.to_string() == slot),
+            None => devices.iter().find(|d| ids(d) == Some(wanted)),
+        };
+        let Some(device) = found else {
+            result.missing.push(
+                exp.slot
+                    .clone()
+                    .unwrap_or_else(|| format!("{:04x}:{:04x}", exp.vendor_id, exp.device_id)),
+            );
+            continue;
+        };
+
+        let mut problems = Vec::new();
+        match ids(device) {
+            Some(actual) if actual == wanted => {}
+            actual => {
+                let (vendor, id) = actual.unwrap_or_default();
+                problems.push(format!(
+                    "found {:04x}:{:04x} (expected {:04x}:{:04x})",
+                    vendor, id, exp.vendor_id, exp.device_id
+                ));
+            }
+        }
+        if let Some(expected_class) = exp.class
+            && class(device) != Some(expected_class)
+        {
+            problems.push(format!(
+                "class {:06x} (expected {:06x})",
+                class(device).unwrap_or(0),
+                expected_class
+            ));
+        }
+        if let Some(driver) = &exp.driver
+            && device.driver.as_ref() != Some(driver)
+        {
+            problems.push(format!(
+                "bound to {} (expected {})",
+                device.driver.as_deref().unwrap_or("no driver"),
+                driver
+            ));
+        }
+        if problems.is_empty() {
+            result.present.push(device.address.clone());
+        } else {
+            result
+                .mismatched
+                .push(format!("{}: {}", device.address, problems.join(", ")));
+        }
+    }
+    result
+}
+
+/// Enumerates the PCI functions and checks the expected ones.
+pub fn validate_pci(expected: &[ExpectedPciDevice]) -> Result<PciValidationResult> {
+    Ok(validate_pci_devices(&find_pci_devices()?, expected))
+}
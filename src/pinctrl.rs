@@ -0,0 +1,248 @@
+use crate::manifest::Manifest;
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// A pin from debugfs `pinctrl/<controller>/pinmux-pins`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pin {
+    pub controller: String, // The debugfs directory, e.g. "pinctrl-rockchip-pinctrl"
+    pub number: u32,
+    pub name: String,               // e.g. "GPIO0_B1"
+    pub mux_owner: Option<String>,  // Device holding the mux, e.g. "fe5a0000.i2c"
+    pub gpio_owner: Option<String>, // e.g. "gpio0:9"
+    pub function: Option<String>,   // e.g. "i2c1"
+    pub group: Option<String>,      // e.g. "i2c1m0-xfer"
+    pub hog: bool,                  // Claimed by the controller itself, from `pinctrl-0` on it
+}
+
+/// Parses a `pinmux-pins` file, e.g.
+/// "pin 9 (GPIO0_B1): fe5a0000.i2c (GPIO UNCLAIMED) function i2c1 group i2c1m0-xfer".
+///
+/// Strict controllers print "device <owner>", "GPIO <owner>" or "UNCLAIMED" instead
+/// of both owners.
+pub fn parse_pinmux_pins(controller: &str, text: &str) -> Result<Vec<Pin>> {
+    let mut pins = Vec::new();
+    for line in text.lines().filter(|l| l.starts_with("pin ")) {
+        let bad = || anyhow::anyhow!("invalid pinmux-pins line {:?}", line);
+        let (number, rest) = line["pin ".len()..].split_once(" (").ok_or_else(bad)?;
+        let (name, rest) = rest.split_once("): ").ok_or_else(bad)?;
+        let (owners, setting) = match rest.split_once(" function ") {
+            Some((owners, setting)) => (owners, Some(setting)),
+            None => (rest, None),
+        };
+        let (function, group) = match setting.map(|s| s.split_once(" group ")) {
+            Some(Some((function, group))) => (Some(function), Some(group)),
+            Some(None) => (setting, None),
+            None => (None, None),
+        };
+        let hog = owners.ends_with(" (HOG)");
+        let owners = owners.trim_end_matches(" (HOG)").trim();
+        let owner = |o: &str| Some(o.to_string()).filter(|o| !o.is_empty());
+        let (mux_owner, gpio_owner) = if let Some(device) = owners.strip_prefix("device ") {
+            (owner(device), None)
+        } else if let Some(gpio) = owners.strip_prefix("GPIO ") {
+            (None, owner(gpio))
+        } else if owners == "UNCLAIMED" {
+            (None, None)
+        } else {
+            let (mux, gpio) = match owners.strip_prefix("(MUX UNCLAIMED)") {
+                Some(gpio) => ("", gpio),
+                None => owners.split_once(' ').unwrap_or((owners, "")),
+            };
+            (
+                owner(mux),
+                owner(gpio.trim()).filter(|g| g != "(GPIO UNCLAIMED)"),
+            )
+        };
+        pins.push(Pin {
+            controller: controller.to_string(),
+            number: number.parse().map_err(|_| bad())?,
+            name: name.to_string(),
+            mux_owner,
+            gpio_owner,
+            function: function.map(str::to_string),
+            group: group.map(|g| g.trim().to_string()),
+            hog,
+        });
+    }
+    Ok(pins)
+}
+
+/// Reads the pins of every pin controller of the running system.
+pub fn read_pins() -> Result<Option<Vec<Pin>>> {
+    read_pins_in(Path::new("/sys"))
+}
+
+/// Same as [`read_pins`], below an arbitrary sysfs root. `None` without debugfs
+/// mounted at sys/kernel/debug; reading it needs root.
+pub fn read_pins_in(sys_root: &Path) -> Result<Option<Vec<Pin>>> {
+    let pinctrl_dir = sys_root.join("kernel/debug/pinctrl");
+    let entries = match fs::read_dir(&pinctrl_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => anyhow::bail!("{}: {}", pinctrl_dir.display(), e),
+    };
+    let mut controllers: Vec<String> = entries
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    controllers.sort();
+    let mut pins = Vec::new();
+    for controller in controllers {
+        let path = pinctrl_dir.join(&controller).join("pinmux-pins");
+        // Controllers without pinmux, e.g. pinconf-only ones, have no such file
+        let Ok(text) = fs::read_to_string(&path) else {
+            continue;
+        };
+        pins.extend(
+            parse_pinmux_pins(&controller, &text)
+                .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?,
+        );
+    }
+    Ok(Some(pins))
+}
+
+/// How the manifest names a pin: by name, or by number on a controller.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PinId {
+    Name(String),
+    Number(u32),
+}
+
+impl std::fmt::Display for PinId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PinId::Name(name) => write!(f, "{}", name),
+            PinId::Number(number) => write!(f, "pin {}", number),
+        }
+    }
+}
+
+/// An expected pin. Each check applies only when set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedPin {
+    pub pin: PinId,
+    pub controller: Option<String>, // Needed when pins are given by number
+    pub function: Option<String>,
+    pub group: Option<String>,
+    pub owner: Option<String>, // Device holding the mux, e.g. "fe5a0000.i2c"
+}
+
+impl ExpectedPin {
+    pub fn matches(&self, pin: &Pin) -> bool {
+        self.controller
+            .as_ref()
+            .is_none_or(|c| *c == pin.controller)
+            && match &self.pin {
+                PinId::Name(name) => *name == pin.name,
+                PinId::Number(number) => *number == pin.number,
+            }
+    }
+}
+
+/// Expectations of the `[pinctrl]` manifest section.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedPins {
+    pub pins: Vec<ExpectedPin>,
+}
+
+impl ExpectedPins {
+    pub fn from_manifest(manifest: &Manifest) -> Result<ExpectedPins> {
+        let Some(section) = manifest.sections.get("pinctrl") else {
+            return Ok(ExpectedPins::default());
+        };
+        let pins = match &section["pins"] {
+            Value::Null => Vec::new(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let string = |key: &str| item[key].as_str().map(str::to_string);
+                    let pin = match &item["pin"] {
+                        Value::String(name) => PinId::Name(name.clone()),
+                        value => PinId::Number(
+                            value
+                                .as_u64()
+                                .and_then(|n| u32::try_from(n).ok())
+                                .ok_or_else(|| {
+                                    anyhow::anyhow!(
+                                        "pinctrl.pins {}: `pin` must be a name or number",
+                                        i
+                                    )
+                                })?,
+                        ),
+                    };
+                    if matches!(pin, PinId::Number(_)) && item["controller"].is_null() {
+                        anyhow::bail!("pinctrl.pins {}: a pin number needs a `controller`", i);
+                    }
+                    Ok(ExpectedPin {
+                        pin,
+                        controller: string("controller"),
+                        function: string("function"),
+                        group: string("group"),
+                        owner: string("owner"),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => anyhow::bail!("pinctrl.pins must be a list"),
+        };
+        Ok(ExpectedPins { pins })
+    }
+}
+
+/// Returns human-readable descriptions of every problem against the expectations.
+///
+/// An I2C or SPI pad left claimed as a GPIO, or not claimed at all, is the usual outcome
+/// of an overlay that names the wrong pinctrl group or never gets applied.
+pub fn validate_pins(pins: &[Pin], expected: &ExpectedPins) -> Vec<String> {
+    let mut problems = Vec::new();
+    for want in &expected.pins {
+        let Some(pin) = pins.iter().find(|p| want.matches(p)) else {
+            problems.push(format!("{}: not found", want.pin));
+            continue;
+        };
+        let label = &pin.name;
+        if let Some(function) = &want.function {
+            match (&pin.function, &pin.gpio_owner) {
+                (Some(actual), _) if actual == function => {}
+                (Some(actual), _) => problems.push(format!(
+                    "{}: function {} (expected {})",
+                    label, actual, function
+                )),
+                (None, Some(gpio)) => problems.push(format!(
+                    "{}: claimed as GPIO by {} (expected function {})",
+                    label, gpio, function
+                )),
+                (None, None) => problems.push(format!(
+                    "{}: unclaimed (expected function {})",
+                    label, function
+                )),
+            }
+        }
+        // An unmuxed pin already failed the function check
+        if let Some(group) = &want.group
+            && pin.group.as_ref() != Some(group)
+            && (pin.group.is_some() || want.function.is_none())
+        {
+            problems.push(format!(
+                "{}: group {} (expected {})",
+                label,
+                pin.group.as_deref().unwrap_or("none"),
+                group
+            ));
+        }
+        if let Some(owner) = &want.owner
+            && pin.mux_owner.as_ref() != Some(owner)
+        {
+            problems.push(format!(
+                "{}: muxed by {} (expected {})",
+                label,
+                pin.mux_owner.as_deref().unwrap_or("nothing"),
+                owner
+            ));
+        }
+    }
+    problems
+}
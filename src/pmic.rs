@@ -0,0 +1,319 @@
+use crate::device::{Board, DeviceAddress, TuxDevice};
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+/// System state PMIC checks read from, captured once per run.
+#[derive(Debug, Clone, Default)]
+pub struct PmicContext {
+    pub interrupts: String,  // /proc/interrupts
+    pub regmap_dir: PathBuf, // /sys/kernel/debug/regmap
+}
+
+impl PmicContext {
+    pub fn read() -> Result<PmicContext> {
+        Ok(PmicContext {
+            interrupts: fs::read_to_string("/proc/interrupts")?,
+            regmap_dir: PathBuf::from("/sys/kernel/debug/regmap"),
+        })
+    }
+}
+
+/// What a plugin found for one PMIC.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PmicReport {
+    pub plugin: String,
+    pub regulators: Vec<String>, // Regulator names registered below the device
+    pub irq_registered: bool,
+    pub rtc: Option<String>, // e.g. "rtc0"
+    pub registers: BTreeMap<String, u32>,
+    pub problems: Vec<String>,
+}
+
+/// Board-specific expectations for a PMIC.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedPmic {
+    pub bus: u32,
+    pub addr: u16,
+    pub regulators: Vec<String>,
+    pub registers: Vec<(String, u32, u32)>, // (register name, mask, expected value)
+}
+
+pub trait PmicPlugin {
+    fn name(&self) -> &str;
+    fn matches(&self, device: &TuxDevice) -> bool;
+    fn validate(
+        &self,
+        device: &TuxDevice,
+        expected: Option<&ExpectedPmic>,
+        ctx: &PmicContext,
+    ) -> PmicReport;
+}
+
+/// Walks real (non-symlink) subdirectories up to `depth` levels, collecting matching paths.
+fn find_dirs(dir: &Path, depth: usize, pred: &dyn Fn(&str) -> bool, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        if !is_dir {
+            continue;
+        }
+        let path = entry.path();
+        if pred(&entry.file_name().to_string_lossy()) {
+            out.push(path.clone());
+        }
+        if depth > 1 {
+            find_dirs(&path, depth - 1, pred, out);
+        }
+    }
+}
+
+fn is_numbered(name: &str, prefix: &str) -> bool {
+    name.strip_prefix(prefix)
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Names of regulators registered by the device's MFD children.
+pub fn find_regulators(device_dir: &Path) -> Vec<String> {
+    let mut dirs = Vec::new();
+    find_dirs(device_dir, 4, &|n| is_numbered(n, "regulator."), &mut dirs);
+    let mut names: Vec<String> = dirs
+        .iter()
+        .filter_map(|d| fs::read_to_string(d.join("name")).ok())
+        .map(|n| n.trim().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+/// The RTC class device registered below the device, if any.
+pub fn find_rtc(device_dir: &Path) -> Option<String> {
+    let mut dirs = Vec::new();
+    find_dirs(device_dir, 4, &|n| is_numbered(n, "rtc"), &mut dirs);
+    dirs.first()
+        .and_then(|d| d.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+}
+
+/// Whether any /proc/interrupts action names one of `names`.
+pub fn irq_registered(interrupts: &str, names: &[&str]) -> bool {
+    interrupts.lines().skip(1).any(|line| {
+        // Skip "<irq>:" and the per-CPU counts; the rest is chip, hwirq, type and actions
+        line.split_whitespace()
+            .skip(1)
+            .skip_while(|t| t.bytes().all(|b| b.is_ascii_digit()))
+            .flat_map(|t| t.split(','))
+            .any(|t| names.contains(&t))
+    })
+}
+
+/// Parses a regmap debugfs `registers` file (`<hex reg>: <hex value>` lines).
+pub fn parse_regmap_from_reader<R: BufRead>(reader: R) -> Result<BTreeMap<u32, u32>> {
+    let mut registers = BTreeMap::new();
+    for line_result in reader.lines() {
+        let line = line_result?;
+        let Some((reg, value)) = line.split_once(':') else {
+            continue;
+        };
+        if let (Ok(reg), Ok(value)) = (
+            u32::from_str_radix(reg.trim(), 16),
+            u32::from_str_radix(value.trim(), 16),
+        ) {
+            registers.insert(reg, value);
+        }
+    }
+    Ok(registers)
+}
+
+/// Reads the regmap of a device; the directory is `<sysname>` or `<sysname>-<name>`.
+pub fn read_regmap(regmap_dir: &Path, sysname: &str) -> Option<BTreeMap<u32, u32>> {
+    let dir = fs::read_dir(regmap_dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .find(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            name == sysname || name.starts_with(&format!("{}-", sysname))
+        })?
+        .path();
+    let file = fs::File::open(dir.join("registers")).ok()?;
+    parse_regmap_from_reader(std::io::BufReader::new(file)).ok()
+}
+
+/// A PMIC family described by data: matching names, sub-functions and status registers.
+pub struct PmicFamily {
+    pub name: &'static str,
+    pub chips: &'static [&'static str], // Device names, e.g. "rk808"
+    pub has_rtc: bool,
+    pub status_registers: &'static [(&'static str, u32)],
+}
+
+pub const RK808: PmicFamily = PmicFamily {
+    name: "rk808",
+    chips: &["rk805", "rk808", "rk818"],
+    has_rtc: true,
+    status_registers: &[("ON_SOURCE", 0xae), ("OFF_SOURCE", 0xaf)],
+};
+
+pub const RK817: PmicFamily = PmicFamily {
+    name: "rk817",
+    chips: &["rk809", "rk817"],
+    has_rtc: true,
+    status_registers: &[
+        ("ID_MSB", 0xed),
+        ("ID_LSB", 0xee),
+        ("ON_SOURCE", 0xf5),
+        ("OFF_SOURCE", 0xf6),
+    ],
+};
+
+pub const AXP: PmicFamily = PmicFamily {
+    name: "axp20x",
+    chips: &[
+        "axp209", "axp221", "axp223", "axp313a", "axp717", "axp803", "axp806", "axp813",
+    ],
+    has_rtc: false,
+    status_registers: &[
+        ("PWR_INPUT_STATUS", 0x00),
+        ("PWR_OP_MODE", 0x01),
+        ("IC_TYPE", 0x03),
+    ],
+};
+
+pub const BD718XX: PmicFamily = PmicFamily {
+    name: "bd718xx",
+    chips: &["bd71837", "bd71847", "bd71850"],
+    has_rtc: false,
+    status_registers: &[("REV", 0x00)],
+};
+
+impl PmicPlugin for PmicFamily {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn matches(&self, device: &TuxDevice) -> bool {
+        matches!(device.address, DeviceAddress::I2c { .. })
+            && self
+                .chips
+                .iter()
+                .any(|c| device.name.eq_ignore_ascii_case(c))
+    }
+
+    fn validate(
+        &self,
+        device: &TuxDevice,
+        expected: Option<&ExpectedPmic>,
+        ctx: &PmicContext,
+    ) -> PmicReport {
+        let mut report = PmicReport {
+            plugin: self.name.to_string(),
+            ..Default::default()
+        };
+        let sysname = device.address.to_string();
+        let Some(dir) = &device.sysfs_path else {
+            report
+                .problems
+                .push(format!("{}: not known to the kernel", sysname));
+            return report;
+        };
+        if device.driver.is_none() {
+            report
+                .problems
+                .push(format!("{}: no driver bound", sysname));
+        }
+
+        report.regulators = find_regulators(dir);
+        if report.regulators.is_empty() {
+            report
+                .problems
+                .push(format!("{}: no regulators registered", sysname));
+        }
+        for name in expected.iter().flat_map(|e| &e.regulators) {
+            if !report.regulators.contains(name) {
+                report
+                    .problems
+                    .push(format!("{}: regulator {} missing", sysname, name));
+            }
+        }
+
+        let mut irq_names = vec![device.name.as_str(), sysname.as_str()];
+        if let Some(driver) = &device.driver {
+            irq_names.push(driver);
+        }
+        report.irq_registered = irq_registered(&ctx.interrupts, &irq_names);
+        if !report.irq_registered {
+            report
+                .problems
+                .push(format!("{}: no interrupt registered", sysname));
+        }
+
+        report.rtc = find_rtc(dir);
+        if self.has_rtc && report.rtc.is_none() {
+            report
+                .problems
+                .push(format!("{}: RTC not registered", sysname));
+        }
+
+        match read_regmap(&ctx.regmap_dir, &sysname) {
+            Some(regmap) => {
+                for (name, reg) in self.status_registers {
+                    if let Some(value) = regmap.get(reg) {
+                        report.registers.insert(name.to_string(), *value);
+                    }
+                }
+            }
+            None => report.problems.push(format!(
+                "{}: regmap not readable (debugfs mounted?)",
+                sysname
+            )),
+        }
+        for (name, mask, value) in expected.iter().flat_map(|e| &e.registers) {
+            match report.registers.get(name) {
+                Some(actual) if actual & mask == *value => {}
+                actual => report.problems.push(format!(
+                    "{}: register {} is {:x?} (expected {:#x} under mask {:#x})",
+                    sysname, name, actual, value, mask
+                )),
+            }
+        }
+        report
+    }
+}
+
+/// The plugins shipped with the crate.
+pub fn builtin_plugins() -> Vec<Box<dyn PmicPlugin>> {
+    vec![
+        Box::new(RK808),
+        Box::new(RK817),
+        Box::new(AXP),
+        Box::new(BD718XX),
+    ]
+}
+
+/// Runs the first matching plugin on every PMIC found in the audit.
+pub fn validate_pmics(
+    board: &Board,
+    plugins: &[Box<dyn PmicPlugin>],
+    expected: &[ExpectedPmic],
+    ctx: &PmicContext,
+) -> Vec<(DeviceAddress, PmicReport)> {
+    let mut reports = Vec::new();
+    for device in board.devices() {
+        let Some(plugin) = plugins.iter().find(|p| p.matches(device)) else {
+            continue;
+        };
+        let exp = expected.iter().find(|e| {
+            device.address
+                == DeviceAddress::I2c {
+                    bus: e.bus,
+                    addr: e.addr,
+                }
+        });
+        reports.push((device.address.clone(), plugin.validate(device, exp, ctx)));
+    }
+    reports
+}
@@ -0,0 +1,229 @@
+use crate::runtime::{self, Dependency};
+use anyhow::Result;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+const SYS_CLASS: &str = "/sys/class";
+
+/// A PTP hardware clock (/dev/ptpN) and its capabilities.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PtpClock {
+    pub name: String,       // e.g. "ptp0"
+    pub clock_name: String, // Driver-provided, e.g. "stmmac_ptp_clock"
+    pub interfaces: Vec<String>,
+    pub max_adjustment: u64, // ppb
+    pub pps_available: bool,
+    pub n_pins: u32,
+    pub n_alarms: u32,
+    pub n_external_timestamps: u32,
+    pub n_periodic_outputs: u32,
+}
+
+fn read_attr(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_num<T: std::str::FromStr + Default>(path: &Path) -> T {
+    read_attr(path)
+        .and_then(|s| s.parse::<T>().ok())
+        .unwrap_or_default()
+}
+
+/// Finds all PTP clocks and maps them to their network interfaces.
+pub fn discover_clocks() -> Result<Vec<PtpClock>> {
+    discover_clocks_in(Path::new(SYS_CLASS))
+}
+
+/// Same as [`discover_clocks`], but below an arbitrary sysfs `class` directory.
+pub fn discover_clocks_in(class_dir: &Path) -> Result<Vec<PtpClock>> {
+    let ptp_dir = class_dir.join("ptp");
+    let mut clocks = Vec::new();
+    if !ptp_dir.is_dir() {
+        return Ok(clocks);
+    }
+
+    for entry in fs::read_dir(&ptp_dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with("ptp") {
+            continue;
+        }
+        let dir = entry.path();
+        clocks.push(PtpClock {
+            clock_name: read_attr(&dir.join("clock_name")).unwrap_or_default(),
+            interfaces: Vec::new(),
+            max_adjustment: read_num(&dir.join("max_adjustment")),
+            pps_available: read_attr(&dir.join("pps_available")).is_some_and(|s| s == "1"),
+            n_pins: read_num(&dir.join("n_programmable_pins")),
+            n_alarms: read_num(&dir.join("n_alarms")),
+            n_external_timestamps: read_num(&dir.join("n_external_timestamps")),
+            n_periodic_outputs: read_num(&dir.join("n_periodic_outputs")),
+            name,
+        });
+    }
+
+    // Network drivers expose their PHC under /sys/class/net/<iface>/device/ptp/ptpN
+    let net_dir = class_dir.join("net");
+    if net_dir.is_dir() {
+        for entry in fs::read_dir(&net_dir)? {
+            let entry = entry?;
+            let iface = entry.file_name().to_string_lossy().to_string();
+            let Ok(ptp_entries) = fs::read_dir(entry.path().join("device/ptp")) else {
+                continue;
+            };
+            for ptp in ptp_entries.filter_map(|e| e.ok()) {
+                let ptp_name = ptp.file_name().to_string_lossy().to_string();
+                if let Some(clock) = clocks.iter_mut().find(|c| c.name == ptp_name) {
+                    clock.interfaces.push(iface.clone());
+                }
+            }
+        }
+    }
+
+    for clock in &mut clocks {
+        clock.interfaces.sort();
+    }
+    // Sort them so they appear as ptp0, ptp1, .. ptp10
+    clocks.sort_by_key(|c| {
+        c.name
+            .strip_prefix("ptp")
+            .and_then(|x| x.parse::<u32>().ok())
+            .unwrap_or(0)
+    });
+    Ok(clocks)
+}
+
+/// An expected PHC, identified by the network interface it belongs to.
+pub struct ExpectedPtpClock {
+    pub interface: String,
+    pub pps: bool,
+    pub min_pins: u32,
+}
+
+/// Holds results of a PTP clock validation.
+#[derive(Debug, Default)]
+pub struct PtpValidationResult {
+    pub present: Vec<String>,
+    pub missing: Vec<String>,
+    pub mismatched: Vec<String>,
+}
+
+/// Checks that each expected interface has a PHC with the required capabilities.
+pub fn validate_clocks(clocks: &[PtpClock], expected: &[ExpectedPtpClock]) -> PtpValidationResult {
+    let mut result = PtpValidationResult::default();
+
+    for exp in expected {
+        let Some(clock) = clocks
+            .iter()
+            .find(|c| c.interfaces.contains(&exp.interface))
+        else {
+            result.missing.push(exp.interface.clone());
+            continue;
+        };
+        result.present.push(exp.interface.clone());
+        if exp.pps && !clock.pps_available {
+            result.mismatched.push(format!(
+                "{} ({}): PPS not available",
+                exp.interface, clock.name
+            ));
+        }
+        if clock.n_pins < exp.min_pins {
+            result.mismatched.push(format!(
+                "{} ({}): {} programmable pins (expected at least {})",
+                exp.interface, clock.name, clock.n_pins, exp.min_pins
+            ));
+        }
+    }
+    result
+}
+
+/// Extracts the offset (ns) from a ptp4l/phc2sys log line, if it is a locked (s2) sample.
+///
+/// Handles e.g. `ptp4l[..]: master offset -12 s2 freq +1234 path delay 567`
+/// and `phc2sys[..]: CLOCK_REALTIME phc offset -5 s2 freq -12 delay 123`.
+pub fn parse_offset_line(line: &str) -> Option<i64> {
+    let mut tokens = line.split_whitespace();
+    while let Some(token) = tokens.next() {
+        if token == "offset" {
+            let value = tokens.next()?.parse::<i64>().ok()?;
+            // Servo has to be locked, otherwise offsets are meaningless
+            return (tokens.next()? == "s2").then_some(value);
+        }
+    }
+    None
+}
+
+/// Statistics over a window of PHC offset samples.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OffsetStats {
+    pub samples: usize,
+    pub min: i64,
+    pub max: i64,
+    pub max_abs: u64,
+}
+
+impl OffsetStats {
+    pub fn from_samples(samples: &[i64]) -> Option<OffsetStats> {
+        Some(OffsetStats {
+            samples: samples.len(),
+            min: *samples.iter().min()?,
+            max: *samples.iter().max()?,
+            max_abs: samples.iter().map(|s| s.unsigned_abs()).max()?,
+        })
+    }
+
+    pub fn within(&self, max_abs_ns: u64) -> bool {
+        self.max_abs <= max_abs_ns
+    }
+}
+
+/// Collects locked offset samples from ptp4l/phc2sys output.
+pub fn offsets_from_reader<R: BufRead>(reader: R) -> Result<Vec<i64>> {
+    let mut offsets = Vec::new();
+    for line in reader.lines() {
+        if let Some(offset) = parse_offset_line(&line?) {
+            offsets.push(offset);
+        }
+    }
+    Ok(offsets)
+}
+
+/// Runs e.g. `phc2sys -m -s eth0 -c CLOCK_REALTIME -O 0` for `window` and collects offsets.
+///
+/// The command must print its log to stdout (`-m`). It is killed after the window.
+pub fn sample_offsets(program: &str, args: &[&str], window: Duration) -> Result<Vec<i64>> {
+    runtime::require(&Dependency::program(program))?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(|l| l.ok()) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let deadline = Instant::now() + window;
+    let mut offsets = Vec::new();
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        match rx.recv_timeout(remaining) {
+            Ok(line) => offsets.extend(parse_offset_line(&line)),
+            Err(mpsc::RecvTimeoutError::Timeout) => break,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break, // Process exited early
+        }
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+    Ok(offsets)
+}
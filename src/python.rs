@@ -0,0 +1,218 @@
+//! Python bindings for lab scripting.
+//!
+//! Build the extension with `maturin build` (see pyproject.toml); it imports as `tux_validation`.
+
+use crate::device::{TuxBus, TuxDevice};
+use crate::error::Error;
+use crate::i2c::{self, LinuxI2cScanner};
+use crate::manifest::{self, Manifest, ManifestFinding};
+use crate::os_release;
+use pyo3::exceptions::{PyFileNotFoundError, PyPermissionError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Typed errors become the matching built-in exception, e.g. PermissionError for "need sudo".
+fn to_py_err(e: impl Into<anyhow::Error>) -> PyErr {
+    let e = e.into();
+    let message = e.to_string();
+    match Error::of(&e) {
+        Some(Error::PermissionDenied { .. }) => PyPermissionError::new_err(message),
+        Some(Error::BusNotFound { .. }) => PyFileNotFoundError::new_err(message),
+        Some(Error::ParseError { .. }) => PyValueError::new_err(message),
+        _ => PyRuntimeError::new_err(message),
+    }
+}
+
+/// A device found during an audit.
+#[pyclass(name = "Device", get_all, frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub struct PyDevice {
+    pub subsystem: String,
+    pub address: String, // sysfs naming, e.g. "1-0050"
+    pub name: String,
+    pub sysfs_path: Option<String>,
+    pub driver: Option<String>,
+    pub modalias: Option<String>,
+    pub in_udev: bool,
+    pub hw_responded: bool,
+    pub probe_deferred: bool,
+    pub is_ghost: bool,
+}
+
+impl From<&TuxDevice> for PyDevice {
+    fn from(device: &TuxDevice) -> Self {
+        PyDevice {
+            subsystem: device.subsystem.to_string(),
+            address: device.address.to_string(),
+            name: device.name.clone(),
+            sysfs_path: device
+                .sysfs_path
+                .as_ref()
+                .map(|p| p.to_string_lossy().to_string()),
+            driver: device.driver.clone(),
+            modalias: device.modalias.clone(),
+            in_udev: device.in_udev,
+            hw_responded: device.hw_responded,
+            probe_deferred: device.probe_deferred,
+            is_ghost: device.is_ghost(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyDevice {
+    fn __repr__(&self) -> String {
+        format!(
+            "Device({} {:?} driver={:?})",
+            self.address, self.name, self.driver
+        )
+    }
+}
+
+/// An audited bus and its devices.
+#[pyclass(name = "Bus", get_all, frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub struct PyBus {
+    pub subsystem: String,
+    pub id: String,
+    pub name: String,
+    pub devices: Vec<PyDevice>,
+    pub metadata: HashMap<String, String>,
+}
+
+impl From<&TuxBus> for PyBus {
+    fn from(bus: &TuxBus) -> Self {
+        PyBus {
+            subsystem: bus.subsystem.to_string(),
+            id: bus.id.clone(),
+            name: bus.name.clone(),
+            devices: bus.devices.iter().map(PyDevice::from).collect(),
+            metadata: bus.metadata.clone().into_iter().collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyBus {
+    fn __repr__(&self) -> String {
+        format!(
+            "Bus({} {:?}, {} devices)",
+            self.id,
+            self.name,
+            self.devices.len()
+        )
+    }
+}
+
+/// Result of checking one I2C bus against expected addresses.
+#[pyclass(name = "I2cValidation", get_all, frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub struct PyI2cValidation {
+    pub missing: Vec<u16>,
+    pub unexpected: Vec<u16>,
+    pub present: Vec<u16>,
+    pub probed: Vec<u16>,
+    pub forbidden: Vec<u16>,
+    pub ok: bool,
+}
+
+/// A manifest entry the board doesn't satisfy.
+#[pyclass(name = "Finding", get_all, frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub struct PyFinding {
+    pub address: String,
+    pub severity: String,
+    pub message: String,
+}
+
+impl From<&ManifestFinding> for PyFinding {
+    fn from(finding: &ManifestFinding) -> Self {
+        PyFinding {
+            address: finding.address.to_string(),
+            severity: finding.severity.clone(),
+            message: finding.message.clone(),
+        }
+    }
+}
+
+/// Paths of the /dev/i2c-* buses.
+#[pyfunction]
+fn discover_buses() -> PyResult<Vec<String>> {
+    Ok(i2c::discover_buses()
+        .map_err(to_py_err)?
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect())
+}
+
+/// Audits every I2C bus via sysfs/udev, plus a hardware probe if asked.
+#[pyfunction]
+#[pyo3(signature = (hw_probe = false))]
+fn audit_i2c_buses(hw_probe: bool) -> PyResult<Vec<PyBus>> {
+    Ok(i2c::audit_all_i2c_buses(hw_probe)
+        .map_err(to_py_err)?
+        .iter()
+        .map(PyBus::from)
+        .collect())
+}
+
+/// Checks one I2C bus against expected and must-be-absent addresses.
+#[pyfunction]
+#[pyo3(signature = (bus_id, expected, forbidden = Vec::new(), hw_probe = false))]
+fn validate_i2c_bus(
+    bus_id: u32,
+    expected: Vec<u16>,
+    forbidden: Vec<u16>,
+    hw_probe: bool,
+) -> PyResult<PyI2cValidation> {
+    let result = i2c::validate_bus_with_forbidden(
+        &LinuxI2cScanner::new(bus_id),
+        &expected,
+        &forbidden,
+        hw_probe,
+    )
+    .map_err(to_py_err)?;
+    Ok(PyI2cValidation {
+        ok: result.is_ok(),
+        missing: result.missing,
+        unexpected: result.unexpected,
+        present: result.present,
+        probed: result.probed,
+        forbidden: result.forbidden,
+    })
+}
+
+/// Audits the I2C buses and checks them against a manifest file.
+#[pyfunction]
+#[pyo3(signature = (path, hw_probe = false))]
+fn run_manifest(path: &str, hw_probe: bool) -> PyResult<Vec<PyFinding>> {
+    let manifest = Manifest::load(Path::new(path)).map_err(to_py_err)?;
+    Ok(manifest::run(&manifest, hw_probe)
+        .map_err(to_py_err)?
+        .findings
+        .iter()
+        .map(PyFinding::from)
+        .collect())
+}
+
+/// Key/value pairs of an os-release file.
+#[pyfunction]
+#[pyo3(signature = (path = "/etc/os-release"))]
+fn read_os_release(path: &str) -> PyResult<HashMap<String, String>> {
+    os_release::parse_os_release(path).map_err(to_py_err)
+}
+
+#[pymodule]
+fn tux_validation(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyDevice>()?;
+    m.add_class::<PyBus>()?;
+    m.add_class::<PyI2cValidation>()?;
+    m.add_class::<PyFinding>()?;
+    m.add_function(wrap_pyfunction!(discover_buses, m)?)?;
+    m.add_function(wrap_pyfunction!(audit_i2c_buses, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_i2c_bus, m)?)?;
+    m.add_function(wrap_pyfunction!(run_manifest, m)?)?;
+    m.add_function(wrap_pyfunction!(read_os_release, m)?)?;
+    Ok(())
+}
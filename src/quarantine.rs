@@ -0,0 +1,237 @@
+use crate::manifest::Manifest;
+use crate::report::TestCase;
+use crate::safety::Access;
+use crate::teardown::{self, GuardedRun, Teardown};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+/// Where the list lives; unlike the locks in /run it must survive reboots.
+pub const QUARANTINE_PATH: &str = "/var/lib/tux-validation/quarantine.json";
+
+/// Consecutive failures of intrusive checks before a target is quarantined.
+pub const DEFAULT_THRESHOLD: u32 = 3;
+
+/// A bus, e.g. "i2c-1", or a device on one, e.g. "i2c-1/1-0050"; the IDs are those of
+/// [`crate::device::TuxBus`] and the sysfs name of the device address.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Target {
+    Bus(String),
+    Device { bus: String, device: String },
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Bus(bus) => write!(f, "{}", bus),
+            Target::Device { bus, device } => write!(f, "{}/{}", bus, device),
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Target> {
+        if s.is_empty() {
+            anyhow::bail!("empty quarantine target");
+        }
+        match s.split_once('/') {
+            Some((bus, device)) if !bus.is_empty() && !device.is_empty() => Ok(Target::Device {
+                bus: bus.to_string(),
+                device: device.to_string(),
+            }),
+            Some(_) => anyhow::bail!("invalid quarantine target {:?}", s),
+            None => Ok(Target::Bus(s.to_string())),
+        }
+    }
+}
+
+impl Target {
+    /// The bus a device sits on; quarantining the bus covers it.
+    pub fn bus(&self) -> Target {
+        match self {
+            Target::Bus(_) => self.clone(),
+            Target::Device { bus, .. } => Target::Bus(bus.clone()),
+        }
+    }
+}
+
+/// The failure history of one target.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    pub failures: u32,                  // Consecutive, since the last pass or clear
+    pub last_failure: String,           // Message of the latest one
+    pub quarantined_since: Option<u64>, // Seconds since the epoch; None while only counting
+}
+
+impl QuarantineEntry {
+    pub fn is_quarantined(&self) -> bool {
+        self.quarantined_since.is_some()
+    }
+}
+
+/// Targets whose intrusive checks keep failing, kept across runs so a flaky or damaged part
+/// isn't hammered on every run until someone looks at it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quarantine {
+    pub threshold: u32,
+    pub entries: BTreeMap<String, QuarantineEntry>, // By Target, as displayed
+}
+
+impl Default for Quarantine {
+    fn default() -> Self {
+        Quarantine {
+            threshold: DEFAULT_THRESHOLD,
+            entries: BTreeMap::new(),
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl Quarantine {
+    /// Loads the list; a missing file is an empty list.
+    pub fn load(path: &Path) -> Result<Quarantine> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Quarantine::default()),
+            Err(e) => Err(anyhow::anyhow!("{}: {}", path.display(), e)),
+        }
+    }
+
+    /// Writes the list through a temporary file, so a power cut doesn't lose it.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| anyhow::anyhow!("{}: {}", dir.display(), e))?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)? + "\n")
+            .and_then(|_| fs::rename(&tmp, path))
+            .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+    }
+
+    /// The list at the `[quarantine]` section's path, or [`QUARANTINE_PATH`], with its
+    /// threshold.
+    pub fn load_for_manifest(manifest: &Manifest) -> Result<(Quarantine, PathBuf)> {
+        let section = manifest.sections.get("quarantine");
+        let path = section
+            .and_then(|s| s["path"].as_str())
+            .unwrap_or(QUARANTINE_PATH);
+        let path = PathBuf::from(path);
+        let mut quarantine = Quarantine::load(&path)?;
+        if let Some(threshold) = section.map(|s| &s["threshold"]).filter(|t| !t.is_null()) {
+            quarantine.threshold = threshold
+                .as_u64()
+                .and_then(|t| u32::try_from(t).ok())
+                .filter(|t| *t > 0)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("quarantine.threshold must be a positive integer")
+                })?;
+        }
+        Ok((quarantine, path))
+    }
+
+    /// The entry that keeps `target` quarantined: its own, or its bus's.
+    pub fn quarantined(&self, target: &Target) -> Option<(Target, &QuarantineEntry)> {
+        [target.clone(), target.bus()].into_iter().find_map(|t| {
+            let entry = self.entries.get(&t.to_string())?;
+            entry.is_quarantined().then_some((t, entry))
+        })
+    }
+
+    /// Records a failed intrusive check; returns whether it put the target in quarantine.
+    pub fn record_failure(&mut self, target: &Target, message: &str) -> bool {
+        let threshold = self.threshold;
+        let entry = self.entries.entry(target.to_string()).or_default();
+        entry.failures += 1;
+        entry.last_failure = message.to_string();
+        if entry.quarantined_since.is_none() && entry.failures >= threshold {
+            entry.quarantined_since = Some(now());
+            return true;
+        }
+        false
+    }
+
+    /// Records a passing intrusive check, resetting the count of a target not yet quarantined.
+    pub fn record_pass(&mut self, target: &Target) {
+        let key = target.to_string();
+        if self.entries.get(&key).is_some_and(|e| !e.is_quarantined()) {
+            self.entries.remove(&key);
+        }
+    }
+
+    /// Lifts the quarantine of a target and forgets its failures; returns whether it had any.
+    pub fn clear(&mut self, target: &Target) -> bool {
+        self.entries.remove(&target.to_string()).is_some()
+    }
+
+    /// Errors out if `operation` would be intrusive on a quarantined target; reads are
+    /// always allowed.
+    pub fn require(&self, target: &Target, access: Access, operation: &str) -> Result<()> {
+        if access == Access::ReadOnly {
+            return Ok(());
+        }
+        if let Some((by, entry)) = self.quarantined(target) {
+            anyhow::bail!("{}: {}", operation, annotation(&by, entry));
+        }
+        Ok(())
+    }
+
+    /// Runs an intrusive check as [`teardown::run_guarded`] does, unless the target is
+    /// quarantined, and records the outcome. A check whose teardown failed counts as failed.
+    ///
+    /// Returns None for a quarantined target; annotate its case with [`skip_case`].
+    pub fn run_guarded<T: Send + 'static>(
+        &mut self,
+        target: &Target,
+        id: &str,
+        timeout: Option<Duration>,
+        check: impl FnOnce(&Teardown) -> Result<T> + Send + 'static,
+    ) -> Option<GuardedRun<T>> {
+        if self.quarantined(target).is_some() {
+            return None;
+        }
+        let run = teardown::run_guarded(id, timeout, check);
+        match (&run.result, run.is_clean()) {
+            (Ok(_), true) => self.record_pass(target),
+            (Err(e), _) => {
+                self.record_failure(target, &format!("{}: {:#}", id, e));
+            }
+            (Ok(_), false) => {
+                let message = format!("{}: teardown failed: {}", id, run.teardown.join("; "));
+                self.record_failure(target, &message);
+            }
+        }
+        Some(run)
+    }
+}
+
+/// The loud line reports carry for a quarantined target.
+pub fn annotation(target: &Target, entry: &QuarantineEntry) -> String {
+    format!(
+        "QUARANTINED: {} after {} consecutive failures (last: {}); intrusive checks skipped \
+         until `tux-validate quarantine --clear {}`",
+        target, entry.failures, entry.last_failure, target
+    )
+}
+
+/// Marks a case as skipped because its target is quarantined; returns whether it was.
+pub fn skip_case(case: &mut TestCase, quarantine: &Quarantine, target: &Target) -> bool {
+    let Some((by, entry)) = quarantine.quarantined(target) else {
+        return false;
+    };
+    case.skipped = Some(annotation(&by, entry));
+    true
+}
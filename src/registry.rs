@@ -0,0 +1,918 @@
+use crate::safety::Access;
+use serde_json::{Value, json};
+
+/// A parameter a check accepts in a manifest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Param {
+    pub name: &'static str,
+    pub kind: &'static str, // "integer", "string", "bool", "list", "duration" or "table"
+    pub required: bool,
+    pub description: &'static str,
+}
+
+/// A built-in check type. `id` is stable: manifest sections, evidence bundles and reports
+/// use it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CheckInfo {
+    pub id: &'static str,
+    pub module: &'static str,
+    pub description: &'static str,
+    pub access: Access, // Most intrusive operation the check can perform
+    pub params: &'static [Param],
+}
+
+const fn param(
+    name: &'static str,
+    kind: &'static str,
+    required: bool,
+    description: &'static str,
+) -> Param {
+    Param {
+        name,
+        kind,
+        required,
+        description,
+    }
+}
+
+/// Parameters every check section accepts.
+pub const COMMON_PARAMS: &[Param] = &[param(
+    "timeout",
+    "duration",
+    false,
+    "Give up on the check after this long and run its teardown",
+)];
+
+/// Every built-in check, sorted by ID.
+pub const CHECKS: &[CheckInfo] = &[
+    CheckInfo {
+        id: "absence",
+        module: "absence",
+        description: "Components that must not be present (sockets, files, devices)",
+        access: Access::ReadOnly,
+        params: &[param("items", "list", true, "Items that must be absent")],
+    },
+    CheckInfo {
+        id: "audio_loopback",
+        module: "audio",
+        description: "A tone played on an output and recorded on an input through a jig loop",
+        access: Access::Write,
+        params: &[
+            param(
+                "output",
+                "string",
+                true,
+                "ALSA playback device, e.g. hw:0,0",
+            ),
+            param("input", "string", true, "ALSA capture device"),
+            param("frequency", "integer", false, "Tone frequency in Hz"),
+            param("amplitude", "integer", false, "Tone level in dBFS"),
+            param("rate", "integer", false, "Sample rate in Hz"),
+            param("duration", "duration", false, "Length of the tone"),
+            param(
+                "limits",
+                "table",
+                false,
+                "frequency_tolerance_hz, min/max level_dbfs, min_snr_db; numbers or derating tables",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "boot_slot",
+        module: "boot_slot",
+        description: "A/B boot slot state",
+        access: Access::ReadOnly,
+        params: &[
+            param("active_slot", "string", false, "Slot that must be active"),
+            param(
+                "min_attempts_left",
+                "integer",
+                false,
+                "Boot attempts left on the active slot",
+            ),
+            param(
+                "max_boot_count",
+                "integer",
+                false,
+                "Highest acceptable boot count",
+            ),
+            param(
+                "fallback_mount",
+                "string",
+                false,
+                "Mount point of the fallback slot",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "boot_time",
+        module: "boot_time",
+        description: "Boot time budget",
+        access: Access::ReadOnly,
+        params: &[
+            param("kernel", "duration", false, "Kernel budget"),
+            param("userspace", "duration", false, "Userspace budget"),
+            param("total", "duration", false, "Total budget"),
+        ],
+    },
+    CheckInfo {
+        id: "brownout",
+        module: "brownout",
+        description: "Under-voltage and brownout events while the other checks run",
+        access: Access::ReadOnly,
+        params: &[
+            param(
+                "interval",
+                "duration",
+                false,
+                "Sampling interval (default 200ms)",
+            ),
+            param(
+                "pmic",
+                "list",
+                false,
+                "PMIC status bits: tables of device, register, mask and condition",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "calibration",
+        module: "calibration",
+        description: "Factory calibration blobs (CRC, field ranges, keys)",
+        access: Access::ReadOnly,
+        params: &[
+            param("name", "string", true, "Blob name"),
+            param(
+                "path",
+                "string",
+                true,
+                "File or nvmem device holding the blob",
+            ),
+            param("offset", "integer", false, "Byte offset of the blob"),
+            param("len", "integer", false, "Blob length"),
+            param("checks", "list", false, "CRC, field and key checks"),
+        ],
+    },
+    CheckInfo {
+        id: "clocks",
+        module: "clocks",
+        description: "Clocks in the debugfs clock tree, enabled at their rates and from their parents",
+        access: Access::ReadOnly,
+        params: &[param(
+            "clocks",
+            "list",
+            true,
+            "Tables of name, enabled (default true), rate in Hz, tolerance_percent (default 1) and parent",
+        )],
+    },
+    CheckInfo {
+        id: "containers",
+        module: "containers",
+        description: "Expected container workloads and image digests",
+        access: Access::ReadOnly,
+        params: &[
+            param("name", "string", true, "Container name"),
+            param(
+                "image_digest",
+                "string",
+                false,
+                "Required image digest, e.g. sha256:..",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "cpu",
+        module: "cpu",
+        description: "Online CPU count, offline or missing cores, cpufreq governor and frequencies",
+        access: Access::ReadOnly,
+        params: &[
+            param("count", "integer", false, "Expected online CPUs"),
+            param("governor", "string", false, "Expected cpufreq governor"),
+            param(
+                "allow_offline",
+                "string",
+                false,
+                "CPUs that may be offline, e.g. \"6-7\"",
+            ),
+            param(
+                "groups",
+                "list",
+                false,
+                "Tables of cpus and min_freq_khz/max_freq_khz, e.g. per cluster",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "crash_artifacts",
+        module: "crash",
+        description: "Coredumps, pstore records and unclean shutdown markers",
+        access: Access::ReadOnly,
+        params: &[param(
+            "unclean_markers",
+            "list",
+            false,
+            "Files left behind on unclean shutdown",
+        )],
+    },
+    CheckInfo {
+        id: "devicetree",
+        module: "devicetree",
+        description: "Broken phandles, duplicate unit addresses and missing binding properties",
+        access: Access::ReadOnly,
+        params: &[param(
+            "required",
+            "table",
+            false,
+            "Required properties by compatible, e.g. { \"ti,tmp102\" = [\"interrupts\"] }",
+        )],
+    },
+    CheckInfo {
+        id: "gpio",
+        module: "gpio",
+        description: "GPIO lines held by their expected consumers",
+        access: Access::ReadOnly,
+        params: &[
+            param("chip", "string", true, "Chip label or \"gpiochipN\""),
+            param("line", "string", true, "Line name, or offset"),
+            param("consumer", "string", true, "Expected consumer"),
+        ],
+    },
+    CheckInfo {
+        id: "gpio_expander",
+        module: "gpio_expander",
+        description: "I2C GPIO expanders and their gpiochips",
+        access: Access::ReadOnly,
+        params: &[
+            param("bus", "integer", true, "I2C bus number"),
+            param("addr", "integer", true, "I2C address"),
+            param("base", "integer", false, "Expected gpiochip base"),
+            param("lines", "integer", false, "Expected line count"),
+        ],
+    },
+    CheckInfo {
+        id: "gpio_loopback",
+        module: "gpio_expander",
+        description: "Drives fixture-wired GPIO pairs and checks the input follows",
+        access: Access::Write,
+        params: &[
+            param("bus", "integer", true, "I2C bus number of the expander"),
+            param("addr", "integer", true, "I2C address of the expander"),
+            param("loopbacks", "list", true, "(output line, input line) pairs"),
+        ],
+    },
+    CheckInfo {
+        id: "hardening_accounts",
+        module: "hardening",
+        description: "User accounts, shells, groups and default passwords",
+        access: Access::ReadOnly,
+        params: &[
+            param("name", "string", true, "User name"),
+            param("uid", "integer", false, "Expected UID"),
+            param("shell", "string", false, "Expected login shell"),
+            param("groups", "list", false, "Required supplementary groups"),
+        ],
+    },
+    CheckInfo {
+        id: "hardening_permissions",
+        module: "hardening",
+        description: "File modes and ownership",
+        access: Access::ReadOnly,
+        params: &[
+            param("path", "string", true, "File path"),
+            param("mode", "integer", false, "Permission bits, e.g. 0o660"),
+            param("uid", "integer", false, "Owner UID"),
+            param("gid", "integer", false, "Owner GID"),
+        ],
+    },
+    CheckInfo {
+        id: "hwmon",
+        module: "hwmon",
+        description: "Temperatures, rail voltages, currents and fan speeds within limits",
+        access: Access::ReadOnly,
+        params: &[param(
+            "limits",
+            "list",
+            true,
+            "Tables of channel, min and max in °C, V, A, W or RPM; bounds may be derated",
+        )],
+    },
+    CheckInfo {
+        id: "i2c",
+        module: "i2c",
+        description: "Devices on I2C buses, their drivers and hardware presence",
+        access: Access::BusTraffic,
+        params: &[
+            param("bus", "integer", true, "I2C bus number"),
+            param("address", "integer", true, "7-bit device address"),
+            param("name", "string", false, "Human-readable name"),
+            param("driver", "string", false, "Driver that must be bound"),
+            param(
+                "severity",
+                "string",
+                false,
+                "error (default), warning or info",
+            ),
+            param(
+                "registers",
+                "list",
+                false,
+                "Identity registers: tables of register, value and an optional mask",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "i2c_probe",
+        module: "i2c",
+        description: "How hardware probes address I2C devices, per address range",
+        access: Access::BusTraffic,
+        params: &[
+            param(
+                "ranges",
+                "list",
+                false,
+                "Tables of start, end, mode (quick_write, read_byte or skip) and an optional bus",
+            ),
+            param(
+                "retries",
+                "integer",
+                false,
+                "Further attempts before an address is absent",
+            ),
+            param(
+                "retry_delay",
+                "duration",
+                false,
+                "Wait before each retry (default 10ms)",
+            ),
+            param(
+                "per_transfer_timeout",
+                "duration",
+                false,
+                "Adapter timeout per transfer (driver default if unset)",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "integrity",
+        module: "integrity",
+        description: "File hashes against a signed manifest",
+        access: Access::ReadOnly,
+        params: &[
+            param("manifest", "string", true, "sha256sum-format manifest"),
+            param("signature", "string", true, "Detached Ed25519 signature"),
+            param("critical", "list", false, "Files to check (all if empty)"),
+        ],
+    },
+    CheckInfo {
+        id: "journal",
+        module: "journal",
+        description: "Journal entries matching failure patterns",
+        access: Access::ReadOnly,
+        params: &[
+            param("id", "string", true, "Rule ID"),
+            param("pattern", "string", true, "Message substring"),
+            param(
+                "max_priority",
+                "integer",
+                false,
+                "Only entries at least this severe",
+            ),
+            param("unit", "string", false, "Only entries from this unit"),
+        ],
+    },
+    CheckInfo {
+        id: "kernel_threads",
+        module: "kthreads",
+        description: "Expected kernel threads exist and aren't hung",
+        access: Access::ReadOnly,
+        params: &[
+            param(
+                "name",
+                "string",
+                true,
+                "Thread name, trailing * matches a prefix",
+            ),
+            param(
+                "min_count",
+                "integer",
+                false,
+                "Minimum number of matching threads",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "lsm",
+        module: "lsm",
+        description: "SELinux/AppArmor mode and policy",
+        access: Access::ReadOnly,
+        params: &[
+            param("lsm", "string", true, "selinux or apparmor"),
+            param("enforcing", "bool", false, "Must be enforcing"),
+            param(
+                "required_profiles",
+                "list",
+                false,
+                "AppArmor profiles that must be loaded",
+            ),
+            param(
+                "min_policy_version",
+                "integer",
+                false,
+                "Minimum SELinux policy version",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "media_pipeline",
+        module: "media",
+        description: "Camera capture, a DRM test pattern and, with a loopback, the pattern seen by the camera",
+        access: Access::Write,
+        params: &[
+            param(
+                "camera",
+                "string",
+                true,
+                "V4L2 capture device, e.g. /dev/video0",
+            ),
+            param("card", "string", false, "DRM card, e.g. /dev/dri/card0"),
+            param("connector", "integer", false, "DRM connector ID"),
+            param("loopback", "bool", false, "The camera sees the display"),
+            param(
+                "settle",
+                "duration",
+                false,
+                "Wait before capturing the pattern",
+            ),
+            param(
+                "tolerance",
+                "integer",
+                false,
+                "Per-channel colour tolerance",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "memory",
+        module: "memory",
+        description: "Total RAM against the fitted amount, CMA pool and reserved regions",
+        access: Access::ReadOnly,
+        params: &[
+            param(
+                "fitted_mb",
+                "integer",
+                false,
+                "RAM fitted on the board in MiB",
+            ),
+            param(
+                "tolerance_percent",
+                "integer",
+                false,
+                "How much less MemTotal may be (default 10)",
+            ),
+            param(
+                "regions",
+                "list",
+                false,
+                "Tables of name, start and size in bytes; \"cma\" is the CMA pool",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "modem",
+        module: "modem",
+        description: "SIM profile of the cellular modem",
+        access: Access::ReadOnly,
+        params: &[
+            param("iccid_prefixes", "list", false, "Accepted ICCID prefixes"),
+            param("mcc_mnc", "list", false, "Accepted operators"),
+        ],
+    },
+    CheckInfo {
+        id: "modules",
+        module: "modules",
+        description: "Kernel modules loaded or built in, their refcounts, state and taint",
+        access: Access::ReadOnly,
+        params: &[
+            param(
+                "required",
+                "list",
+                true,
+                "Module names, or tables of name, builtin and min_refcount",
+            ),
+            param(
+                "allowed_taint",
+                "string",
+                false,
+                "Taint flags loaded modules may carry, e.g. \"O\" for out-of-tree",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "net_interfaces",
+        module: "net",
+        description: "Interfaces present, provisioned MAC addresses, carrier, link and drivers",
+        access: Access::ReadOnly,
+        params: &[param(
+            "interfaces",
+            "list",
+            true,
+            "Tables of name, mac_prefix, mac_pattern (x for any digit), allow_local, carrier, speed, duplex, autoneg, driver, phy_driver and firmware",
+        )],
+    },
+    CheckInfo {
+        id: "net_switches",
+        module: "net",
+        description: "DSA switch model and ports",
+        access: Access::ReadOnly,
+        params: &[
+            param("model", "string", true, "Switch model"),
+            param("port_count", "integer", true, "Number of user ports"),
+            param("cpu_port", "string", false, "CPU port name"),
+            param("ports", "list", false, "Port names and link expectations"),
+        ],
+    },
+    CheckInfo {
+        id: "net_topology",
+        module: "net",
+        description: "Bridges, VLANs, bonds and network namespaces",
+        access: Access::ReadOnly,
+        params: &[
+            param("bridges", "list", false, "Bridges and their members"),
+            param("vlans", "list", false, "VLAN interfaces"),
+            param("bonds", "list", false, "Bonds and their slaves"),
+            param("namespaces", "list", false, "Network namespaces"),
+        ],
+    },
+    CheckInfo {
+        id: "ota",
+        module: "ota",
+        description: "OTA client version and health",
+        access: Access::ReadOnly,
+        params: &[
+            param("version", "string", false, "Installed version"),
+            param(
+                "require_healthy",
+                "bool",
+                false,
+                "Client must report healthy",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "patterns",
+        module: "seed",
+        description: "Seed of the randomized test patterns, recorded in the report",
+        access: Access::ReadOnly,
+        params: &[param(
+            "seed",
+            "integer",
+            false,
+            "Fixed run seed, e.g. to reproduce a failure; drawn at random otherwise",
+        )],
+    },
+    CheckInfo {
+        id: "pci",
+        module: "pci",
+        description: "PCI functions, their IDs, class and driver",
+        access: Access::ReadOnly,
+        params: &[
+            param("slot", "string", false, "Address, e.g. \"0000:01:00.0\""),
+            param("vendor_id", "integer", true, "PCI vendor ID"),
+            param("device_id", "integer", true, "PCI device ID"),
+            param("class", "integer", false, "24-bit class code"),
+            param("driver", "string", false, "Expected bound driver"),
+        ],
+    },
+    CheckInfo {
+        id: "pinctrl",
+        module: "pinctrl",
+        description: "Pins muxed to their functions and groups, from debugfs pinmux-pins",
+        access: Access::ReadOnly,
+        params: &[param(
+            "pins",
+            "list",
+            true,
+            "Tables of pin (name, or number with controller), function, group and owner",
+        )],
+    },
+    CheckInfo {
+        id: "pmic",
+        module: "pmic",
+        description: "PMIC regulators, interrupts and registers",
+        access: Access::ReadOnly,
+        params: &[
+            param("bus", "integer", true, "I2C bus number"),
+            param("addr", "integer", true, "I2C address"),
+            param(
+                "regulators",
+                "list",
+                false,
+                "Regulators that must be registered",
+            ),
+            param(
+                "registers",
+                "list",
+                false,
+                "(register, mask, value) triples",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "ptp",
+        module: "ptp",
+        description: "PTP hardware clocks",
+        access: Access::ReadOnly,
+        params: &[
+            param("interface", "string", true, "Network interface"),
+            param("pps", "bool", false, "Clock must support PPS"),
+            param("min_pins", "integer", false, "Minimum number of pins"),
+        ],
+    },
+    CheckInfo {
+        id: "quarantine",
+        module: "quarantine",
+        description: "Buses and devices whose intrusive checks are skipped after repeated failures",
+        access: Access::ReadOnly,
+        params: &[
+            param(
+                "threshold",
+                "integer",
+                false,
+                "Consecutive intrusive-check failures before quarantine (default 3)",
+            ),
+            param(
+                "path",
+                "string",
+                false,
+                "Quarantine list, kept across runs (default /var/lib/tux-validation/quarantine.json)",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "regulator",
+        module: "regulator",
+        description: "Regulator rails registered, enabled, within voltage limits and supplying their consumers",
+        access: Access::ReadOnly,
+        params: &[param(
+            "rails",
+            "list",
+            true,
+            "Tables of name, enabled (default true), min_mv, max_mv, consumers and pmic",
+        )],
+    },
+    CheckInfo {
+        id: "rootfs",
+        module: "rootfs",
+        description: "Root filesystem mount, overlay and dm-verity",
+        access: Access::ReadOnly,
+        params: &[
+            param("read_only", "bool", false, "Root must be read-only"),
+            param("overlay", "bool", false, "Root must be an overlayfs"),
+            param(
+                "upper_source",
+                "string",
+                false,
+                "Device of the overlay upperdir",
+            ),
+            param("verity_device", "string", false, "dm-verity device name"),
+            param(
+                "min_free_bytes",
+                "integer",
+                false,
+                "Free space on the writable side",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "rpi_firmware",
+        module: "rpi",
+        description: "Raspberry Pi firmware revision, under-voltage and throttling flags",
+        access: Access::ReadOnly,
+        params: &[
+            param(
+                "min_revision",
+                "integer",
+                false,
+                "Oldest accepted firmware build time",
+            ),
+            param(
+                "allow_undervoltage",
+                "bool",
+                false,
+                "Pass despite under-voltage, e.g. on a bench supply",
+            ),
+            param(
+                "allow_throttling",
+                "bool",
+                false,
+                "Pass despite frequency capping or throttling",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "rtc",
+        module: "rtc",
+        description: "RTC device node, drift from system time and retention over an interval",
+        access: Access::ReadOnly,
+        params: &[
+            param("device", "string", false, "RTC to check (default rtc0)"),
+            param("name", "string", false, "Expected driver or device name"),
+            param(
+                "max_drift_s",
+                "integer",
+                false,
+                "Allowed difference from system time (default 2)",
+            ),
+            param(
+                "retention_s",
+                "integer",
+                false,
+                "Interval over which the RTC must keep counting",
+            ),
+            param(
+                "hctosys",
+                "bool",
+                false,
+                "Whether it sets the system clock at boot",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "sampling",
+        module: "sampling",
+        description: "Repeated measurements against statistical limits",
+        access: Access::ReadOnly,
+        params: &[
+            param("samples", "integer", true, "Number of samples"),
+            param("interval", "duration", false, "Time between samples"),
+            param(
+                "limits",
+                "table",
+                true,
+                "min/max mean, max stddev, min/max sample; numbers or derating tables",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "sfp",
+        module: "sfp",
+        description: "SFP module identity and DDM readings",
+        access: Access::ReadOnly,
+        params: &[
+            param("vendor", "string", false, "Vendor name"),
+            param("part_number_prefix", "string", false, "Part number prefix"),
+            param("wavelength_nm", "integer", false, "Laser wavelength"),
+            param("ddm", "table", false, "DDM limits"),
+        ],
+    },
+    CheckInfo {
+        id: "soc",
+        module: "soc",
+        description: "SoC family, ID and silicon revision",
+        access: Access::ReadOnly,
+        params: &[
+            param("family", "string", false, "SoC family"),
+            param("soc_id", "string", false, "SoC ID"),
+            param("min_revision", "string", false, "Oldest accepted revision"),
+            param("max_revision", "string", false, "Newest accepted revision"),
+            param(
+                "forbidden_revisions",
+                "list",
+                false,
+                "Rejected revisions, e.g. ES1.0",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "storage",
+        module: "storage",
+        description: "eMMC, SD and NVMe presence, capacity and the part fitted",
+        access: Access::ReadOnly,
+        params: &[param(
+            "disks",
+            "list",
+            true,
+            "Tables of kind, name, min/max_size_mb and, from the BOM, manfid and product",
+        )],
+    },
+    CheckInfo {
+        id: "thermal",
+        module: "thermal",
+        description: "Thermal zones within limits and cooling devices registered, not pinned at max",
+        access: Access::ReadOnly,
+        params: &[
+            param(
+                "zones",
+                "list",
+                false,
+                "Tables of zone type and optional min_temp/max_temp in °C",
+            ),
+            param(
+                "cooling",
+                "list",
+                false,
+                "Tables of cooling device type and allow_max",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "touch",
+        module: "touch",
+        description: "Touch controller, firmware version and input device",
+        access: Access::BusTraffic,
+        params: &[
+            param("bus", "integer", true, "I2C bus number"),
+            param("addr", "integer", true, "I2C address"),
+            param(
+                "firmware_version",
+                "string",
+                false,
+                "Expected firmware version",
+            ),
+            param("input_name", "string", false, "Expected input device name"),
+        ],
+    },
+    CheckInfo {
+        id: "usb",
+        module: "usb",
+        description: "USB devices (VID:PID) at their expected ports and their firmware",
+        access: Access::ReadOnly,
+        params: &[
+            param("bus", "integer", true, "USB bus number"),
+            param("port", "string", true, "Port path, e.g. \"1.2\""),
+            param("vendor_id", "integer", true, "USB vendor ID"),
+            param("product_id", "integer", true, "USB product ID"),
+            param(
+                "min_bcd_device",
+                "integer",
+                false,
+                "Oldest acceptable bcdDevice, e.g. 0x0120",
+            ),
+            param(
+                "firmware_attribute",
+                "string",
+                false,
+                "udev property or sysfs attribute holding a firmware version",
+            ),
+            param(
+                "min_firmware",
+                "string",
+                false,
+                "Oldest acceptable firmware",
+            ),
+        ],
+    },
+    CheckInfo {
+        id: "usb_serial",
+        module: "usb_serial",
+        description: "USB-UART bridge channels and their by-path links",
+        access: Access::ReadOnly,
+        params: &[
+            param("vendor_id", "integer", true, "USB vendor ID"),
+            param("product_id", "integer", true, "USB product ID"),
+            param("interface", "integer", true, "Interface (channel) number"),
+            param("serial", "string", false, "Bridge serial number"),
+            param("tty", "string", false, "Expected tty name"),
+            param("by_path", "string", false, "Link under /dev/serial/by-path"),
+        ],
+    },
+];
+
+pub fn find(id: &str) -> Option<&'static CheckInfo> {
+    CHECKS.iter().find(|c| c.id == id)
+}
+
+/// IDs that aren't built-in checks, in the order given.
+pub fn unknown_ids<'a>(ids: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    ids.into_iter().filter(|id| find(id).is_none()).collect()
+}
+
+/// The registry as JSON, for generating station documentation.
+pub fn to_json() -> Value {
+    let params = |params: &[Param]| -> Vec<Value> {
+        params
+            .iter()
+            .map(|p| {
+                json!({
+                    "name": p.name,
+                    "kind": p.kind,
+                    "required": p.required,
+                    "description": p.description,
+                })
+            })
+            .collect()
+    };
+    let checks: Vec<Value> = CHECKS
+        .iter()
+        .map(|c| {
+            json!({
+                "id": c.id,
+                "module": c.module,
+                "description": c.description,
+                "access": format!("{:?}", c.access),
+                "params": params(c.params),
+            })
+        })
+        .collect();
+    json!({ "checks": checks, "common_params": params(COMMON_PARAMS) })
+}
@@ -0,0 +1,251 @@
+use crate::manifest::Manifest;
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A regulator from /sys/class/regulator/regulator.N.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Regulator {
+    pub id: String,            // e.g. "regulator.3"
+    pub name: String,          // The DT `regulator-name`, e.g. "vdd_gpu"
+    pub kind: String,          // "voltage" or "current"
+    pub enabled: Option<bool>, // None if the driver can't tell
+    pub microvolts: Option<u32>,
+    pub min_microvolts: Option<u32>, // Constraints, if the rail may be changed at runtime
+    pub max_microvolts: Option<u32>,
+    pub microamps: Option<u32>,
+    pub users: Option<u32>,        // Consumers holding it enabled
+    pub consumers: Vec<String>,    // "<device>-<supply>", e.g. "fe2c0000.mmc-vqmmc"
+    pub provider: Option<PathBuf>, // The device registering it, e.g. below a PMIC
+}
+
+impl Regulator {
+    /// Whether `consumer` uses it: a "<device>-<supply>" link or just the device name.
+    pub fn has_consumer(&self, consumer: &str) -> bool {
+        self.consumers.iter().any(|c| {
+            c == consumer
+                || c.strip_prefix(consumer)
+                    .is_some_and(|supply| supply.starts_with('-'))
+        })
+    }
+
+    /// Whether the device named `device`, e.g. a PMIC's "0-001b", provides it.
+    pub fn provided_by(&self, device: &str) -> bool {
+        self.provider
+            .as_ref()
+            .is_some_and(|p| p.iter().any(|component| component == device))
+    }
+}
+
+fn read_attr(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_number(path: &Path) -> Option<u32> {
+    read_attr(path).and_then(|s| s.parse().ok())
+}
+
+/// Lists the regulators of the running system.
+pub fn list_regulators() -> Result<Vec<Regulator>> {
+    list_regulators_in(Path::new("/sys"))
+}
+
+/// Same as [`list_regulators`], below an arbitrary sysfs root.
+pub fn list_regulators_in(sys_root: &Path) -> Result<Vec<Regulator>> {
+    let class_dir = sys_root.join("class/regulator");
+    let entries = match fs::read_dir(&class_dir) {
+        Ok(entries) => entries,
+        // No regulator framework, e.g. x86 boards or a kernel without CONFIG_REGULATOR
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => anyhow::bail!("{}: {}", class_dir.display(), e),
+    };
+    let mut regulators = Vec::new();
+    for entry in entries.flatten() {
+        let id = entry.file_name().to_string_lossy().to_string();
+        let Some(index) = id
+            .strip_prefix("regulator.")
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let dir = class_dir.join(&id);
+        // Consumers are links named after the device and supply; the rest are attributes
+        let mut consumers: Vec<String> = fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_symlink()))
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|name| !["device", "subsystem", "supply"].contains(&name.as_str()))
+            .collect();
+        consumers.sort();
+        regulators.push((
+            index,
+            Regulator {
+                name: read_attr(&dir.join("name")).unwrap_or_default(),
+                kind: read_attr(&dir.join("type")).unwrap_or_default(),
+                enabled: match read_attr(&dir.join("state")).as_deref() {
+                    Some("enabled") => Some(true),
+                    Some("disabled") => Some(false),
+                    _ => None,
+                },
+                microvolts: read_number(&dir.join("microvolts")),
+                min_microvolts: read_number(&dir.join("min_microvolts")),
+                max_microvolts: read_number(&dir.join("max_microvolts")),
+                microamps: read_number(&dir.join("microamps")),
+                users: read_number(&dir.join("num_users")),
+                consumers,
+                provider: fs::canonicalize(dir.join("device")).ok(),
+                id,
+            },
+        ));
+    }
+    regulators.sort_by_key(|(index, _)| *index);
+    Ok(regulators.into_iter().map(|(_, r)| r).collect())
+}
+
+/// An expected rail. Each check applies only when set; rails must be enabled unless
+/// `enabled` is false.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedRail {
+    pub name: String,
+    pub enabled: Option<bool>,
+    pub min_mv: Option<u32>,
+    pub max_mv: Option<u32>,
+    pub consumers: Vec<String>, // Devices, or "<device>-<supply>" links
+    pub pmic: Option<String>,   // Device providing it, e.g. "0-001b"
+}
+
+/// Expectations of the `[regulator]` manifest section.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedRegulators {
+    pub rails: Vec<ExpectedRail>,
+}
+
+impl ExpectedRegulators {
+    pub fn from_manifest(manifest: &Manifest) -> Result<ExpectedRegulators> {
+        let Some(section) = manifest.sections.get("regulator") else {
+            return Ok(ExpectedRegulators::default());
+        };
+        let rails = match &section["rails"] {
+            Value::Null => Vec::new(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let millivolts = |key: &str| -> Result<Option<u32>> {
+                        match &item[key] {
+                            Value::Null => Ok(None),
+                            value => value
+                                .as_u64()
+                                .and_then(|mv| u32::try_from(mv).ok())
+                                .map(Some)
+                                .ok_or_else(|| {
+                                    anyhow::anyhow!(
+                                        "regulator.rails {}: `{}` must be in millivolts",
+                                        i,
+                                        key
+                                    )
+                                }),
+                        }
+                    };
+                    let rail = ExpectedRail {
+                        name: item["name"].as_str().map(str::to_string).ok_or_else(|| {
+                            anyhow::anyhow!("regulator.rails {}: missing `name`", i)
+                        })?,
+                        enabled: Some(item["enabled"].as_bool().unwrap_or(true)),
+                        min_mv: millivolts("min_mv")?,
+                        max_mv: millivolts("max_mv")?,
+                        consumers: match &item["consumers"] {
+                            Value::Null => Vec::new(),
+                            Value::Array(consumers) => consumers
+                                .iter()
+                                .filter_map(|c| c.as_str().map(str::to_string))
+                                .collect(),
+                            _ => anyhow::bail!("regulator.rails {}: `consumers` must be a list", i),
+                        },
+                        pmic: item["pmic"].as_str().map(str::to_string),
+                    };
+                    if let (Some(min), Some(max)) = (rail.min_mv, rail.max_mv)
+                        && min > max
+                    {
+                        anyhow::bail!("regulator.rails {}: min_mv above max_mv", i);
+                    }
+                    Ok(rail)
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => anyhow::bail!("regulator.rails must be a list"),
+        };
+        Ok(ExpectedRegulators { rails })
+    }
+}
+
+/// Returns human-readable descriptions of every problem against the expectations.
+///
+/// A rail out of limits with its consumers bound usually means a wrong DT constraint or
+/// a PMIC OTP variant; a missing consumer link, a driver that never asked for the supply.
+pub fn validate_regulators(regulators: &[Regulator], expected: &ExpectedRegulators) -> Vec<String> {
+    let mut problems = Vec::new();
+    for rail in &expected.rails {
+        let Some(regulator) = regulators.iter().find(|r| r.name == rail.name) else {
+            problems.push(format!("{}: not registered", rail.name));
+            continue;
+        };
+        if let Some(enabled) = rail.enabled
+            && regulator.enabled != Some(enabled)
+        {
+            let state = |on: bool| if on { "enabled" } else { "disabled" };
+            problems.push(format!(
+                "{}: {} (expected {})",
+                rail.name,
+                regulator.enabled.map(state).unwrap_or("state unknown"),
+                state(enabled)
+            ));
+        }
+        if rail.min_mv.is_some() || rail.max_mv.is_some() {
+            match regulator.microvolts {
+                None => problems.push(format!("{}: voltage not readable", rail.name)),
+                Some(uv) => {
+                    let min = rail.min_mv.unwrap_or(0);
+                    let max = rail.max_mv.unwrap_or(u32::MAX / 1000);
+                    if uv < min * 1000 || uv > max * 1000 {
+                        let limits = match (rail.min_mv, rail.max_mv) {
+                            (Some(min), Some(max)) => format!("{}-{} mV", min, max),
+                            (Some(min), None) => format!("at least {} mV", min),
+                            (None, _) => format!("at most {} mV", max),
+                        };
+                        problems.push(format!(
+                            "{}: {} mV (expected {})",
+                            rail.name,
+                            uv / 1000,
+                            limits
+                        ));
+                    }
+                }
+            }
+        }
+        for consumer in &rail.consumers {
+            if !regulator.has_consumer(consumer) {
+                problems.push(format!("{}: no consumer {}", rail.name, consumer));
+            }
+        }
+        if let Some(pmic) = &rail.pmic
+            && !regulator.provided_by(pmic)
+        {
+            problems.push(format!(
+                "{}: provided by {} (expected {})",
+                rail.name,
+                regulator
+                    .provider
+                    .as_ref()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+                    .as_deref()
+                    .unwrap_or("unknown"),
+                pmic
+            ));
+        }
+    }
+    problems
+}
@@ -0,0 +1,325 @@
+use crate::device::{DeviceAddress, TuxBus, TuxDevice};
+use crate::manifest::{ManifestDevice, ManifestResult};
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub mod html;
+pub mod plan;
+
+/// Board-level report of audited buses: `{"buses": [...]}`.
+pub fn to_json(buses: &[TuxBus]) -> Value {
+    json!({ "buses": buses })
+}
+
+/// Audits every I2C bus and returns the board-level report.
+#[cfg(feature = "i2c")]
+pub fn audit_i2c(enable_hw_probe: bool) -> Result<Value> {
+    Ok(to_json(&crate::i2c::audit_all_i2c_buses(enable_hw_probe)?))
+}
+
+/// Inverse of [`to_json`].
+pub fn from_json(report: &Value) -> Result<Vec<TuxBus>> {
+    let Some(buses) = report["buses"].as_array() else {
+        anyhow::bail!("report has no `buses` array");
+    };
+    buses.iter().map(TuxBus::from_json).collect()
+}
+
+pub fn parse(text: &str) -> Result<Vec<TuxBus>> {
+    from_json(&serde_json::from_str(text)?)
+}
+
+/// How a device differs between two reports.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added,
+    Removed,
+    DriverChanged {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    ProbeChanged {
+        responded: bool, // In the newer report
+    },
+}
+
+/// A device that changed between two reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceDiff {
+    pub address: DeviceAddress,
+    pub name: String,
+    pub change: Change,
+}
+
+impl fmt::Display for DeviceDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: ", self.address, self.name)?;
+        let driver = |d: &Option<String>| d.clone().unwrap_or_else(|| "no driver".to_string());
+        match &self.change {
+            Change::Added => write!(f, "added"),
+            Change::Removed => write!(f, "removed"),
+            Change::DriverChanged { from, to } => {
+                write!(f, "driver {} -> {}", driver(from), driver(to))
+            }
+            Change::ProbeChanged { responded: true } => write!(f, "now responds to probe"),
+            Change::ProbeChanged { responded: false } => write!(f, "no longer responds to probe"),
+        }
+    }
+}
+
+fn by_address(buses: &[TuxBus]) -> BTreeMap<&DeviceAddress, &TuxDevice> {
+    buses
+        .iter()
+        .flat_map(|b| b.devices.iter())
+        .map(|d| (&d.address, d))
+        .collect()
+}
+
+/// Device-level differences from `old` to `new`, ordered by address.
+pub fn diff(old: &[TuxBus], new: &[TuxBus]) -> Vec<DeviceDiff> {
+    let old = by_address(old);
+    let new = by_address(new);
+    let mut diffs = Vec::new();
+    let mut record = |device: &TuxDevice, change: Change| {
+        diffs.push(DeviceDiff {
+            address: device.address.clone(),
+            name: device.name.clone(),
+            change,
+        })
+    };
+    for (address, before) in &old {
+        let Some(after) = new.get(address) else {
+            record(before, Change::Removed);
+            continue;
+        };
+        if before.driver != after.driver {
+            record(
+                after,
+                Change::DriverChanged {
+                    from: before.driver.clone(),
+                    to: after.driver.clone(),
+                },
+            );
+        }
+        if before.hw_responded != after.hw_responded {
+            record(
+                after,
+                Change::ProbeChanged {
+                    responded: after.hw_responded,
+                },
+            );
+        }
+    }
+    for (address, after) in &new {
+        if !old.contains_key(address) {
+            record(after, Change::Added);
+        }
+    }
+    diffs.sort_by(|a, b| a.address.cmp(&b.address));
+    diffs
+}
+
+/// A JUnit test case. Passed cases have neither `failure` nor `skipped`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TestCase {
+    pub classname: String, // Groups cases in CI views, e.g. "i2c-1"
+    pub name: String,
+    pub failure: Option<String>,
+    pub skipped: Option<String>,
+    pub output: Option<String>, // Informational, e.g. a warning that doesn't fail the run
+}
+
+impl TestCase {
+    pub fn passed(classname: &str, name: &str) -> TestCase {
+        TestCase {
+            classname: classname.to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn failed(classname: &str, name: &str, message: &str) -> TestCase {
+        TestCase {
+            failure: Some(message.to_string()),
+            ..TestCase::passed(classname, name)
+        }
+    }
+}
+
+/// A JUnit test suite, e.g. the checks of one bus.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TestSuite {
+    pub name: String,
+    pub cases: Vec<TestCase>,
+}
+
+impl TestSuite {
+    pub fn failures(&self) -> usize {
+        self.cases.iter().filter(|c| c.failure.is_some()).count()
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.cases.iter().filter(|c| c.skipped.is_some()).count()
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Not allowed in XML 1.0, even escaped
+            c if c.is_control() && !matches!(c, '\n' | '\t' | '\r') => out.push('\u{fffd}'),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Renders suites as JUnit XML, as read by Jenkins and GitLab.
+pub fn junit(suites: &[TestSuite]) -> String {
+    let count = |f: fn(&TestSuite) -> usize| suites.iter().map(f).sum::<usize>();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"tux-validation\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">\n",
+        count(|s| s.cases.len()),
+        count(TestSuite::failures),
+        count(TestSuite::skipped)
+    ));
+    for suite in suites {
+        out.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">\n",
+            xml_escape(&suite.name),
+            suite.cases.len(),
+            suite.failures(),
+            suite.skipped()
+        ));
+        for case in &suite.cases {
+            out.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                xml_escape(&case.classname),
+                xml_escape(&case.name)
+            ));
+            if case.failure.is_none() && case.skipped.is_none() && case.output.is_none() {
+                out.push_str("/>\n");
+                continue;
+            }
+            out.push_str(">\n");
+            if let Some(message) = &case.failure {
+                let message = xml_escape(message);
+                out.push_str(&format!(
+                    "      <failure message=\"{}\">{}</failure>\n",
+                    message, message
+                ));
+            }
+            if let Some(message) = &case.skipped {
+                out.push_str(&format!(
+                    "      <skipped message=\"{}\"/>\n",
+                    xml_escape(message)
+                ));
+            }
+            if let Some(output) = &case.output {
+                out.push_str(&format!(
+                    "      <system-out>{}</system-out>\n",
+                    xml_escape(output)
+                ));
+            }
+            out.push_str("    </testcase>\n");
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+/// One suite per I2C bus of the manifest: passed, optional-and-absent (skipped) and
+/// failing devices. Findings below "error" severity pass with their message as output.
+pub fn manifest_suites(result: &ManifestResult) -> Vec<TestSuite> {
+    let name = |device: &ManifestDevice| format!("{} at 0x{:02x}", device.name, device.address);
+    let mut cases: Vec<(u32, TestCase)> = Vec::new();
+    for device in &result.present {
+        let classname = format!("i2c-{}", device.bus);
+        cases.push((device.bus, TestCase::passed(&classname, &name(device))));
+    }
+    for device in &result.absent_optional {
+        let classname = format!("i2c-{}", device.bus);
+        let case = TestCase {
+            skipped: Some("optional, not fitted".to_string()),
+            ..TestCase::passed(&classname, &name(device))
+        };
+        cases.push((device.bus, case));
+    }
+    for finding in &result.findings {
+        let DeviceAddress::I2c { bus, addr } = finding.address else {
+            continue;
+        };
+        let classname = format!("i2c-{}", bus);
+        let case_name = format!("0x{:02x}", addr);
+        let case = if finding.severity == "error" {
+            TestCase::failed(&classname, &case_name, &finding.message)
+        } else {
+            TestCase {
+                output: Some(format!("{}: {}", finding.severity, finding.message)),
+                ..TestCase::passed(&classname, &case_name)
+            }
+        };
+        cases.push((bus, case));
+    }
+
+    let mut suites: BTreeMap<u32, TestSuite> = BTreeMap::new();
+    for (bus, case) in cases {
+        suites
+            .entry(bus)
+            .or_insert_with(|| TestSuite {
+                name: format!("i2c-{}", bus),
+                cases: Vec::new(),
+            })
+            .cases
+            .push(case);
+    }
+    suites.into_values().collect()
+}
+
+/// One suite per audited bus, with a case per device and one for the bus health verdict
+/// when the audit assessed it. Ghost devices pass with a note; a bad bus fails.
+pub fn bus_suites(buses: &[TuxBus]) -> Vec<TestSuite> {
+    buses
+        .iter()
+        .map(|bus| {
+            let mut cases: Vec<TestCase> = bus
+                .devices
+                .iter()
+                .map(|device| TestCase {
+                    output: device
+                        .is_ghost()
+                        .then(|| "responds to probe, unknown to the kernel".to_string()),
+                    ..TestCase::passed(&bus.id, &format!("{} {}", device.address, device.name))
+                })
+                .collect();
+            if let Some(health) = bus.metadata.get("health") {
+                let reasons = bus.metadata.get("health_reasons").cloned();
+                cases.push(match health.as_str() {
+                    "bad" => TestCase::failed(
+                        &bus.id,
+                        "health",
+                        reasons.as_deref().unwrap_or("bus health is bad"),
+                    ),
+                    _ => TestCase {
+                        output: reasons,
+                        ..TestCase::passed(&bus.id, "health")
+                    },
+                });
+            }
+            TestSuite {
+                name: bus.id.clone(),
+                cases,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,180 @@
+use super::xml_escape as escape;
+use crate::device::{DeviceAddress, TuxBus, TuxDevice};
+use crate::manifest::{ManifestFinding, ManifestResult};
+
+/// Inline so the page has no external resources.
+pub(super) const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 1.5em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }
+th { background: #f0f0f0; }
+code { font-size: 0.9em; }
+.badge { border-radius: 0.6em; padding: 0.1em 0.6em; font-size: 0.85em; color: #fff; }
+.ok { background: #2e7d32; }
+.warning { background: #ef6c00; }
+.error { background: #c62828; }
+.info, .muted { background: #757575; }
+details table { margin: 0.3em 0 0; }
+";
+
+/// A status badge: a CSS class and its text.
+fn badge(class: &str, text: &str) -> String {
+    format!(
+        "<span class=\"badge {}\">{}</span>",
+        escape(class),
+        escape(text)
+    )
+}
+
+/// Severity of a finding as a badge class; unknown severities are shown as errors.
+fn severity_class(severity: &str) -> &str {
+    match severity {
+        "warning" | "info" => severity,
+        _ => "error",
+    }
+}
+
+/// Badge of a device: its manifest finding if it has one, otherwise what the audit saw.
+fn device_status(device: &TuxDevice, finding: Option<&ManifestFinding>) -> String {
+    if let Some(finding) = finding {
+        return badge(severity_class(&finding.severity), &finding.message);
+    }
+    if device.is_ghost() {
+        badge("warning", "ghost")
+    } else if device.is_bound() {
+        badge("ok", "bound")
+    } else if device.probe_deferred {
+        badge("warning", "probe deferred")
+    } else {
+        badge("muted", "no driver")
+    }
+}
+
+/// The uevent and udev properties of a device, collapsed.
+fn attributes(device: &TuxDevice) -> String {
+    if device.attributes.is_empty() {
+        return String::new();
+    }
+    let mut out = format!(
+        "<details><summary>Attributes ({})</summary><table>",
+        device.attributes.len()
+    );
+    for (key, value) in &device.attributes {
+        out.push_str(&format!(
+            "<tr><td><code>{}</code></td><td><code>{}</code></td></tr>",
+            escape(key),
+            escape(value)
+        ));
+    }
+    out.push_str("</table></details>");
+    out
+}
+
+/// The kernel log lines about a device, collapsed.
+fn kernel_log(device: &TuxDevice) -> String {
+    if device.kernel_log.is_empty() {
+        return String::new();
+    }
+    let lines: Vec<String> = device.kernel_log.iter().map(|l| escape(l)).collect();
+    format!(
+        "<details><summary>Kernel log ({})</summary><pre>{}</pre></details>",
+        device.kernel_log.len(),
+        lines.join("\n")
+    )
+}
+
+fn bus_section(bus: &TuxBus, findings: &[ManifestFinding]) -> String {
+    let mut out = format!(
+        "<h2>{} <small>{}</small></h2>\n",
+        escape(&bus.id),
+        escape(&bus.name)
+    );
+    if !bus.metadata.is_empty() {
+        let metadata: Vec<String> = bus
+            .metadata
+            .iter()
+            .map(|(key, value)| format!("{}: {}", escape(key), escape(value)))
+            .collect();
+        out.push_str(&format!("<p>{}</p>\n", metadata.join(" &middot; ")));
+    }
+    if bus.devices.is_empty() {
+        out.push_str("<p>No devices.</p>\n");
+        return out;
+    }
+    out.push_str(
+        "<table>\n<tr><th>Address</th><th>Name</th><th>Driver</th><th>Status</th>\
+         <th>Attributes</th></tr>\n",
+    );
+    for device in &bus.devices {
+        let finding = findings.iter().find(|f| f.address == device.address);
+        out.push_str(&format!(
+            "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape(&device.address.to_string()),
+            escape(&device.name),
+            escape(device.driver.as_deref().unwrap_or("-")),
+            device_status(device, finding),
+            attributes(device) + &kernel_log(device)
+        ));
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+/// The manifest verdict and the findings no audited device shows, e.g. missing devices.
+fn manifest_section(result: &ManifestResult, buses: &[TuxBus]) -> String {
+    let verdict = if result.is_ok() {
+        badge("ok", "PASSED")
+    } else {
+        badge("error", "FAILED")
+    };
+    let mut out = format!(
+        "<p>Manifest: {} &middot; {} present, {} optional not fitted, {} findings</p>\n",
+        verdict,
+        result.present.len(),
+        result.absent_optional.len(),
+        result.findings.len()
+    );
+    let seen = |address: &DeviceAddress| {
+        buses
+            .iter()
+            .any(|b| b.devices.iter().any(|d| &d.address == address))
+    };
+    let unseen: Vec<&ManifestFinding> = result
+        .findings
+        .iter()
+        .filter(|f| !seen(&f.address))
+        .collect();
+    if !unseen.is_empty() {
+        out.push_str("<h2>Not found</h2>\n<table>\n<tr><th>Address</th><th>Status</th></tr>\n");
+        for finding in unseen {
+            out.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td></tr>\n",
+                escape(&finding.address.to_string()),
+                badge(severity_class(&finding.severity), &finding.message)
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+    out
+}
+
+/// Renders audited buses, and optionally their manifest result, as one self-contained HTML
+/// page with a table per bus, e.g. to attach to a bring-up ticket.
+pub fn render(title: &str, buses: &[TuxBus], result: Option<&ManifestResult>) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>\n{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        escape(title),
+        STYLE,
+        escape(title)
+    );
+    let findings = result.map(|r| r.findings.as_slice()).unwrap_or_default();
+    if let Some(result) = result {
+        out.push_str(&manifest_section(result, buses));
+    }
+    for bus in buses {
+        out.push_str(&bus_section(bus, findings));
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
@@ -0,0 +1,249 @@
+use super::xml_escape as escape;
+use crate::manifest::{Manifest, ManifestDevice};
+use crate::registry::{self, COMMON_PARAMS};
+use crate::safety::Access;
+use serde_json::Value;
+
+/// Sign-off rows of the approval block.
+const SIGN_OFF: [&str; 3] = [
+    "Prepared by",
+    "Reviewed by (quality)",
+    "Approved for production",
+];
+
+/// One parameter of a planned check, as the manifest sets it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedParam {
+    pub name: String,
+    pub value: Option<String>, // None if the manifest leaves it unset
+    pub required: bool,
+    pub description: String,
+    pub known: bool, // False for keys the registry doesn't list, usually typos
+}
+
+/// A manifest section with what the registry says about its check.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedCheck {
+    pub id: String,
+    pub description: String,
+    pub access: Option<Access>,
+    pub params: Vec<PlannedParam>,
+}
+
+impl PlannedCheck {
+    /// Required parameters the manifest doesn't set; the plan can't be approved with any.
+    pub fn missing(&self) -> Vec<&str> {
+        self.params
+            .iter()
+            .filter(|p| p.required && p.value.is_none())
+            .map(|p| p.name.as_str())
+            .collect()
+    }
+}
+
+fn access_text(access: Option<Access>) -> &'static str {
+    match access {
+        Some(Access::ReadOnly) => "read-only",
+        Some(Access::BusTraffic) => "bus traffic",
+        Some(Access::Write) => "changes device state",
+        None => "unknown",
+    }
+}
+
+/// A parameter value as a reviewer reads it: strings bare, lists of scalars comma-separated,
+/// tables as JSON.
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.iter().all(|v| !v.is_array() && !v.is_object()) => items
+            .iter()
+            .map(format_value)
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// The checks of every manifest section in ID order, with every parameter the registry
+/// lists, set or not, followed by any it doesn't.
+pub fn planned_checks(manifest: &Manifest) -> Vec<PlannedCheck> {
+    manifest
+        .sections
+        .iter()
+        .map(|(id, section)| {
+            let info = registry::find(id);
+            let listed = info.map(|i| i.params).unwrap_or_default();
+            let mut params: Vec<PlannedParam> = listed
+                .iter()
+                .chain(COMMON_PARAMS)
+                .map(|p| PlannedParam {
+                    name: p.name.to_string(),
+                    value: section.get(p.name).map(format_value),
+                    required: p.required,
+                    description: p.description.to_string(),
+                    known: true,
+                })
+                .collect();
+            for (key, value) in section.as_object().into_iter().flatten() {
+                if !params.iter().any(|p| &p.name == key) {
+                    params.push(PlannedParam {
+                        name: key.clone(),
+                        value: Some(format_value(value)),
+                        required: false,
+                        description: "not a parameter of this check".to_string(),
+                        known: false,
+                    });
+                }
+            }
+            PlannedCheck {
+                id: id.clone(),
+                description: info.map(|i| i.description).unwrap_or_default().to_string(),
+                access: info.map(|i| i.access),
+                params,
+            }
+        })
+        .collect()
+}
+
+/// What an I2C entry passes on, e.g. "present, bound to at24, 0x75 & 0xff = 0x71".
+pub fn device_criteria(device: &ManifestDevice) -> String {
+    let mut criteria = vec![if device.required {
+        "present".to_string()
+    } else {
+        "may be absent".to_string()
+    }];
+    if let Some(driver) = &device.driver {
+        criteria.push(format!("bound to {}", driver));
+    }
+    for check in &device.registers {
+        criteria.push(format!(
+            "0x{:02x} & 0x{:02x} = 0x{:02x}",
+            check.register, check.mask, check.value
+        ));
+    }
+    criteria.join(", ")
+}
+
+fn value_text(param: &PlannedParam) -> String {
+    match &param.value {
+        Some(value) => value.clone(),
+        None if param.required => "MISSING".to_string(),
+        None => "default".to_string(),
+    }
+}
+
+/// A Markdown table cell: pipes escaped, on one line.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Renders a manifest as a Markdown test plan: every check with its parameters and
+/// pass criteria, and a sign-off block for the approvers.
+///
+/// Renders the manifest as given, so pass the one the station will run, with any overlay
+/// applied.
+pub fn markdown(title: &str, manifest: &Manifest) -> String {
+    let mut out = format!("# {}\n\n", title);
+    if !manifest.i2c.is_empty() {
+        out.push_str("## i2c\n\nExpected I2C devices.\n\n");
+        out.push_str("| Bus | Address | Name | Severity | Pass criteria |\n");
+        out.push_str("|---|---|---|---|---|\n");
+        for device in &manifest.i2c {
+            out.push_str(&format!(
+                "| {} | 0x{:02x} | {} | {} | {} |\n",
+                device.bus,
+                device.address,
+                cell(&device.name),
+                cell(&device.severity),
+                cell(&device_criteria(device))
+            ));
+        }
+        out.push('\n');
+    }
+    for check in planned_checks(manifest) {
+        out.push_str(&format!("## {}\n\n", check.id));
+        if !check.description.is_empty() {
+            out.push_str(&format!("{}.\n\n", check.description));
+        }
+        out.push_str(&format!("Access: {}\n\n", access_text(check.access)));
+        out.push_str("| Parameter | Value | Meaning |\n|---|---|---|\n");
+        for param in &check.params {
+            out.push_str(&format!(
+                "| {} | {} | {} |\n",
+                cell(&param.name),
+                cell(&value_text(param)),
+                cell(&param.description)
+            ));
+        }
+        out.push('\n');
+    }
+    out.push_str("## Approval\n\n| Role | Name | Signature | Date |\n|---|---|---|---|\n");
+    for role in SIGN_OFF {
+        out.push_str(&format!("| {} | | | |\n", role));
+    }
+    out
+}
+
+/// Same as [`markdown`], as one self-contained HTML page.
+pub fn html(title: &str, manifest: &Manifest) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>\n{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        escape(title),
+        super::html::STYLE,
+        escape(title)
+    );
+    if !manifest.i2c.is_empty() {
+        out.push_str("<h2>i2c</h2>\n<p>Expected I2C devices.</p>\n<table>\n");
+        out.push_str(
+            "<tr><th>Bus</th><th>Address</th><th>Name</th><th>Severity</th>\
+             <th>Pass criteria</th></tr>\n",
+        );
+        for device in &manifest.i2c {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td><code>0x{:02x}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                device.bus,
+                device.address,
+                escape(&device.name),
+                escape(&device.severity),
+                escape(&device_criteria(device))
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+    for check in planned_checks(manifest) {
+        out.push_str(&format!("<h2>{}</h2>\n", escape(&check.id)));
+        if !check.description.is_empty() {
+            out.push_str(&format!("<p>{}.</p>\n", escape(&check.description)));
+        }
+        out.push_str(&format!(
+            "<p>Access: {}</p>\n<table>\n<tr><th>Parameter</th><th>Value</th><th>Meaning</th></tr>\n",
+            access_text(check.access)
+        ));
+        for param in &check.params {
+            let value = match (&param.value, param.required) {
+                (None, true) => "<span class=\"badge error\">MISSING</span>".to_string(),
+                _ => escape(&value_text(param)),
+            };
+            out.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>\n",
+                escape(&param.name),
+                value,
+                escape(&param.description)
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str(
+        "<h2>Approval</h2>\n<table>\n\
+         <tr><th>Role</th><th>Name</th><th>Signature</th><th>Date</th></tr>\n",
+    );
+    for role in SIGN_OFF {
+        out.push_str(&format!(
+            "<tr><td>{}</td><td></td><td></td><td></td></tr>\n",
+            escape(role)
+        ));
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
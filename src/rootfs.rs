@@ -0,0 +1,218 @@
+use crate::runtime::{self, Dependency};
+use anyhow::Result;
+use std::io::BufRead;
+use std::path::Path;
+use std::process::Command;
+
+/// One line of /proc/self/mountinfo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MountEntry {
+    pub mount_id: u32,
+    pub parent_id: u32,
+    pub mount_point: String,
+    pub mount_options: Vec<String>,
+    pub fs_type: String,
+    pub source: String,
+    pub super_options: Vec<String>,
+}
+
+impl MountEntry {
+    pub fn is_read_only(&self) -> bool {
+        self.mount_options.iter().any(|o| o == "ro")
+    }
+
+    /// Returns the value of a `key=value` superblock option, e.g. overlay's `upperdir`.
+    pub fn super_option(&self, key: &str) -> Option<&str> {
+        self.super_options
+            .iter()
+            .find_map(|o| o.strip_prefix(key)?.strip_prefix('='))
+    }
+}
+
+/// Undoes the octal escaping of spaces etc. in mountinfo paths (`\040`).
+fn unescape(field: &str) -> String {
+    let mut out = String::new();
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let code: String = chars.by_ref().take(3).collect();
+            if let Ok(byte) = u8::from_str_radix(&code, 8) {
+                out.push(byte as char);
+                continue;
+            }
+            out.push(c);
+            out.push_str(&code);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Parses /proc/<pid>/mountinfo.
+///
+/// Format: `id parent maj:min root mountpoint options [optional..] - fstype source superoptions`.
+pub fn parse_mountinfo_from_reader<R: BufRead>(reader: R) -> Result<Vec<MountEntry>> {
+    let mut mounts = Vec::new();
+
+    for line_result in reader.lines() {
+        let line = line_result?;
+        let Some((left, right)) = line.split_once(" - ") else {
+            continue;
+        };
+        let left: Vec<&str> = left.split_whitespace().collect();
+        let right: Vec<&str> = right.split_whitespace().collect();
+        if left.len() < 6 || right.len() < 2 {
+            continue;
+        }
+        mounts.push(MountEntry {
+            mount_id: left[0].parse().unwrap_or(0),
+            parent_id: left[1].parse().unwrap_or(0),
+            mount_point: unescape(left[4]),
+            mount_options: left[5].split(',').map(|o| o.to_string()).collect(),
+            fs_type: right[0].to_string(),
+            source: unescape(right[1]),
+            super_options: right
+                .get(2)
+                .map(|o| o.split(',').map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
+        });
+    }
+    Ok(mounts)
+}
+
+pub fn read_mountinfo() -> Result<Vec<MountEntry>> {
+    let file = std::fs::File::open("/proc/self/mountinfo")?;
+    parse_mountinfo_from_reader(std::io::BufReader::new(file))
+}
+
+/// Returns the mount that `path` lives on (the longest matching mount point, last mount wins).
+pub fn mount_for_path<'a>(mounts: &'a [MountEntry], path: &str) -> Option<&'a MountEntry> {
+    let path = Path::new(path);
+    mounts
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| path.starts_with(&m.mount_point))
+        .max_by_key(|(i, m)| (m.mount_point.len(), *i))
+        .map(|(_, m)| m)
+}
+
+/// Parses `dmsetup status <name>` output for a verity target.
+///
+/// Returns `Some(true)` for `V` (verified), `Some(false)` for `C` (corruption detected).
+pub fn parse_verity_status(status: &str) -> Option<bool> {
+    let fields: Vec<&str> = status.split_whitespace().collect();
+    if fields.get(2) != Some(&"verity") {
+        return None;
+    }
+    match fields.get(3) {
+        Some(&"V") => Some(true),
+        Some(&"C") => Some(false),
+        _ => None,
+    }
+}
+
+/// Queries the verity state of a device-mapper device via `dmsetup`.
+pub fn verity_status(dm_name: &str) -> Result<Option<bool>> {
+    runtime::require(&Dependency::program("dmsetup"))?;
+    let output = Command::new("dmsetup").args(["status", dm_name]).output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "dmsetup status {} failed: {}",
+            dm_name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(parse_verity_status(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Free bytes available to unprivileged users on the filesystem holding `path`.
+pub fn free_space(path: &str) -> Result<u64> {
+    let stat = nix::sys::statvfs::statvfs(path)?;
+    Ok(stat.blocks_available() as u64 * stat.fragment_size() as u64)
+}
+
+/// Expected root filesystem layout. Unset fields are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedRootfs {
+    pub read_only: bool,               // Root (or overlay lower) must be read-only
+    pub overlay: bool,                 // Root must be an overlayfs
+    pub upper_source: Option<String>,  // Device the overlay upperdir lives on, e.g. /dev/mmcblk0p4
+    pub verity_device: Option<String>, // dm name whose hash must verify, e.g. "vroot"
+    pub min_free_bytes: Option<u64>,   // On the writable side
+}
+
+/// Checks the mount table against the expected layout (without dm-verity and free space).
+pub fn validate_mounts(mounts: &[MountEntry], expected: &ExpectedRootfs) -> Vec<String> {
+    let mut violations = Vec::new();
+    let Some(root) = mount_for_path(mounts, "/") else {
+        violations.push("no mount for /".to_string());
+        return violations;
+    };
+
+    if expected.overlay && root.fs_type != "overlay" {
+        violations.push(format!("root is {} (expected overlay)", root.fs_type));
+    }
+
+    if root.fs_type == "overlay" {
+        // The overlay itself is rw; read-only applies to the lower layer
+        if expected.read_only {
+            let lower = root.super_option("lowerdir").unwrap_or("");
+            for dir in lower.split(':').filter(|d| !d.is_empty()) {
+                match mount_for_path(mounts, dir) {
+                    Some(m) if !m.is_read_only() => {
+                        violations.push(format!("overlay lowerdir {} is writable", dir))
+                    }
+                    Some(_) => {}
+                    None => violations.push(format!("overlay lowerdir {} not mounted", dir)),
+                }
+            }
+        }
+        if let Some(source) = &expected.upper_source {
+            let upper = root.super_option("upperdir").unwrap_or("");
+            match mount_for_path(mounts, upper) {
+                Some(m) if &m.source == source => {}
+                Some(m) => violations.push(format!(
+                    "overlay upperdir {} on {} (expected {})",
+                    upper, m.source, source
+                )),
+                None => violations.push(format!("overlay upperdir {} not found", upper)),
+            }
+        }
+    } else if expected.read_only && !root.is_read_only() {
+        violations.push(format!("root ({}) is mounted read-write", root.source));
+    }
+    violations
+}
+
+/// Runs all root filesystem checks against the live system.
+pub fn check_rootfs(expected: &ExpectedRootfs) -> Result<Vec<String>> {
+    let mounts = read_mountinfo()?;
+    let mut violations = validate_mounts(&mounts, expected);
+
+    if let Some(dm_name) = &expected.verity_device {
+        match verity_status(dm_name)? {
+            Some(true) => {}
+            Some(false) => violations.push(format!("dm-verity {}: corruption detected", dm_name)),
+            None => violations.push(format!("{} is not a verity target", dm_name)),
+        }
+    }
+
+    if let Some(min_free) = expected.min_free_bytes {
+        let root = mount_for_path(&mounts, "/");
+        let writable = match root.and_then(|r| r.super_option("upperdir")) {
+            Some(upper) => upper.to_string(),
+            None => "/".to_string(),
+        };
+        let free = free_space(&writable)?;
+        if free < min_free {
+            violations.push(format!(
+                "{}: {} bytes free (expected at least {})",
+                writable, free, min_free
+            ));
+        }
+    }
+    Ok(violations)
+}
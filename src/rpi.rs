@@ -0,0 +1,200 @@
+use crate::manifest::Manifest;
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// `get_throttled` bits of the VideoCore firmware; the high half latches until reboot.
+pub const THROTTLED_FLAGS: [(u32, &str); 8] = [
+    (1 << 0, "under-voltage"),
+    (1 << 1, "ARM frequency capped"),
+    (1 << 2, "throttled"),
+    (1 << 3, "soft temperature limit"),
+    (1 << 16, "under-voltage has occurred"),
+    (1 << 17, "ARM frequency capping has occurred"),
+    (1 << 18, "throttling has occurred"),
+    (1 << 19, "soft temperature limit has occurred"),
+];
+
+/// Under-voltage now or since boot.
+pub const UNDERVOLTAGE_MASK: u32 = 1 << 0 | 1 << 16;
+
+/// What the Pi firmware reports, from sysfs or the mailbox.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FirmwareStatus {
+    pub revision: Option<u32>, // Build time of the firmware, seconds since the epoch
+    pub throttled: Option<u32>, // `get_throttled` bits
+    pub undervoltage_alarm: Option<bool>, // rpi_volt hwmon `in0_lcrit_alarm`
+}
+
+impl FirmwareStatus {
+    /// Names of the set `get_throttled` bits.
+    pub fn throttled_flags(&self) -> Vec<&'static str> {
+        let bits = self.throttled.unwrap_or(0);
+        THROTTLED_FLAGS
+            .iter()
+            .filter(|(bit, _)| bits & bit != 0)
+            .map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Reads the throttling bits exposed by the raspberrypi firmware driver and the
+/// under-voltage alarm of the rpi_volt hwmon.
+pub fn read_status_in(sys_root: &Path) -> FirmwareStatus {
+    let throttled = read_trimmed(&sys_root.join("devices/platform/soc/soc:firmware/get_throttled"))
+        .and_then(|s| u32::from_str_radix(s.trim_start_matches("0x"), 16).ok());
+    let undervoltage_alarm = fs::read_dir(sys_root.join("class/hwmon"))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|dir| read_trimmed(&dir.join("name")).as_deref() == Some("rpi_volt"))
+        .and_then(|dir| read_trimmed(&dir.join("in0_lcrit_alarm")))
+        .map(|alarm| alarm == "1");
+    FirmwareStatus {
+        revision: None,
+        throttled,
+        undervoltage_alarm,
+    }
+}
+
+/// Status of the live system: sysfs, with the mailbox filling in what it lacks.
+#[cfg(feature = "hardware")]
+pub fn read_status() -> FirmwareStatus {
+    let mut status = read_status_in(Path::new("/sys"));
+    if let Ok((revision, throttled)) = mailbox::query() {
+        status.revision = Some(revision);
+        status.throttled = status.throttled.or(Some(throttled));
+    }
+    status
+}
+
+/// The VideoCore mailbox property interface at /dev/vcio.
+#[cfg(feature = "hardware")]
+pub mod mailbox {
+    use anyhow::Result;
+    use std::os::unix::io::AsRawFd;
+
+    const TAG_FIRMWARE_REVISION: u32 = 0x0000_0001;
+    const TAG_GET_THROTTLED: u32 = 0x0003_0046;
+    const REQUEST: u32 = 0;
+    const RESPONSE_OK: u32 = 0x8000_0000;
+
+    /// `_IOWR(100, 0, char *)`
+    const IOCTL_MBOX_PROPERTY: libc::c_ulong =
+        (3 << 30) | ((std::mem::size_of::<*mut u8>() as libc::c_ulong) << 16) | (100 << 8);
+
+    /// Sends one property tag with a single 32-bit value and returns the reply.
+    fn property(tag: u32) -> Result<u32> {
+        let vcio = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/vcio")
+            .map_err(|e| anyhow::anyhow!("/dev/vcio: {}", e))?;
+        // Buffer size, request code, tag, value buffer size, value length, value, end tag
+        let mut buffer: [u32; 8] = [8 * 4, REQUEST, tag, 4, 0, 0, 0, 0];
+        let rc = unsafe {
+            libc::ioctl(
+                vcio.as_raw_fd(),
+                IOCTL_MBOX_PROPERTY as _,
+                buffer.as_mut_ptr(),
+            )
+        };
+        if rc < 0 {
+            anyhow::bail!("mailbox: {}", std::io::Error::last_os_error());
+        }
+        if buffer[1] != RESPONSE_OK {
+            anyhow::bail!("mailbox: tag 0x{:08x} failed (0x{:08x})", tag, buffer[1]);
+        }
+        Ok(buffer[5])
+    }
+
+    /// Firmware revision and `get_throttled` bits.
+    pub fn query() -> Result<(u32, u32)> {
+        Ok((
+            property(TAG_FIRMWARE_REVISION)?,
+            property(TAG_GET_THROTTLED)?,
+        ))
+    }
+}
+
+/// Expectations of the `[rpi_firmware]` manifest section.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedFirmware {
+    pub min_revision: Option<u32>,
+    pub allow_undervoltage: bool, // Bench supplies during bring-up; never in production
+    pub allow_throttling: bool,   // Frequency capping and thermal throttling
+}
+
+impl ExpectedFirmware {
+    pub fn from_manifest(manifest: &Manifest) -> Result<ExpectedFirmware> {
+        let Some(section) = manifest.sections.get("rpi_firmware") else {
+            return Ok(ExpectedFirmware::default());
+        };
+        let flag = |key: &str| -> Result<bool> {
+            match &section[key] {
+                Value::Null => Ok(false),
+                value => value
+                    .as_bool()
+                    .ok_or_else(|| anyhow::anyhow!("rpi_firmware.{} must be a bool", key)),
+            }
+        };
+        let min_revision = match &section["min_revision"] {
+            Value::Null => None,
+            value => Some(
+                value
+                    .as_u64()
+                    .and_then(|r| u32::try_from(r).ok())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("rpi_firmware.min_revision must be a firmware build time")
+                    })?,
+            ),
+        };
+        Ok(ExpectedFirmware {
+            min_revision,
+            allow_undervoltage: flag("allow_undervoltage")?,
+            allow_throttling: flag("allow_throttling")?,
+        })
+    }
+}
+
+/// Returns human-readable descriptions of every problem against the expectations.
+///
+/// A latched under-voltage fails even if the supply has since recovered: it is the usual
+/// cause of I2C and USB errors that nobody can reproduce on the bench.
+pub fn validate_firmware(status: &FirmwareStatus, expected: &ExpectedFirmware) -> Vec<String> {
+    let mut problems = Vec::new();
+    if let Some(min) = expected.min_revision {
+        match status.revision {
+            Some(revision) if revision < min => problems.push(format!(
+                "firmware revision {} is older than {}",
+                revision, min
+            )),
+            Some(_) => {}
+            None => problems.push("firmware revision not available".to_string()),
+        }
+    }
+    let bits = status.throttled.unwrap_or(0);
+    if !expected.allow_undervoltage
+        && (bits & UNDERVOLTAGE_MASK != 0 || status.undervoltage_alarm == Some(true))
+    {
+        problems.push(format!(
+            "under-voltage detected (get_throttled 0x{:x}); check the supply and cable",
+            bits
+        ));
+    }
+    if !expected.allow_throttling && bits & !UNDERVOLTAGE_MASK != 0 {
+        let flags: Vec<&str> = THROTTLED_FLAGS
+            .iter()
+            .filter(|(bit, _)| bits & bit & !UNDERVOLTAGE_MASK != 0)
+            .map(|(_, name)| *name)
+            .collect();
+        problems.push(format!("firmware reports {}", flags.join(", ")));
+    }
+    problems
+}
@@ -0,0 +1,207 @@
+use crate::manifest::Manifest;
+use crate::system_root::SystemRoot;
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+/// 2001-01-01T00:00:00Z. RTCs that lost their backup supply restart at their epoch,
+/// 1970 or 2000 depending on the part, so anything before is a reset.
+const RESET_BEFORE: u64 = 978_307_200;
+
+/// An RTC from /sys/class/rtc/rtcN.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rtc {
+    pub device: String,           // e.g. "rtc0"
+    pub name: String,             // e.g. "rtc-pcf8563 0-0051"
+    pub since_epoch: Option<u64>, // None if the time is invalid, e.g. after losing power
+    pub date: Option<String>,     // e.g. "2026-10-14"
+    pub hctosys: bool,            // Set the system clock at boot
+    pub has_node: bool,           // /dev/rtcN exists
+}
+
+fn read_attr(path: &std::path::Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Reads the time of one RTC; drivers fail the read while the time is invalid.
+fn read_since_epoch(root: &SystemRoot, device: &str) -> Option<u64> {
+    read_attr(&root.sys.join("class/rtc").join(device).join("since_epoch"))
+        .and_then(|s| s.parse().ok())
+}
+
+/// Lists the RTCs of the running system.
+pub fn list_rtcs() -> Result<Vec<Rtc>> {
+    list_rtcs_in(&SystemRoot::live())
+}
+
+/// Same as [`list_rtcs`], for another system root.
+pub fn list_rtcs_in(root: &SystemRoot) -> Result<Vec<Rtc>> {
+    let class_dir = root.sys.join("class/rtc");
+    let entries = match fs::read_dir(&class_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => anyhow::bail!("{}: {}", class_dir.display(), e),
+    };
+    let mut rtcs: Vec<Rtc> = entries
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with("rtc"))
+        .map(|device| {
+            let dir = class_dir.join(&device);
+            Rtc {
+                name: read_attr(&dir.join("name")).unwrap_or_default(),
+                since_epoch: read_since_epoch(root, &device),
+                date: read_attr(&dir.join("date")),
+                hctosys: read_attr(&dir.join("hctosys")).is_some_and(|h| h == "1"),
+                has_node: root.dev.join(&device).exists(),
+                device,
+            }
+        })
+        .collect();
+    rtcs.sort_by_key(|r| {
+        r.device
+            .strip_prefix("rtc")
+            .and_then(|n| n.parse::<u32>().ok())
+    });
+    Ok(rtcs)
+}
+
+/// Seconds the RTC `device` of the running system advanced over `interval`: a stopped
+/// oscillator, e.g. an unfitted crystal, keeps reading the same time.
+pub fn measure_retention(device: &str, interval: Duration) -> Result<u64> {
+    measure_retention_in(&SystemRoot::live(), device, interval)
+}
+
+/// Same as [`measure_retention`], for another system root.
+pub fn measure_retention_in(root: &SystemRoot, device: &str, interval: Duration) -> Result<u64> {
+    let read = || {
+        read_since_epoch(root, device)
+            .ok_or_else(|| anyhow::anyhow!("{}: time not readable", device))
+    };
+    let before = read()?;
+    std::thread::sleep(interval);
+    Ok(read()?.saturating_sub(before))
+}
+
+/// Expectations of the `[rtc]` manifest section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedRtc {
+    pub device: String,           // Default "rtc0"
+    pub name: Option<String>,     // Part of the driver or device name, e.g. "pcf8563"
+    pub max_drift_s: u64,         // Against system time (default 2)
+    pub retention_s: Option<u64>, // Interval to check the RTC keeps counting over
+    pub hctosys: Option<bool>,
+}
+
+impl Default for ExpectedRtc {
+    fn default() -> Self {
+        ExpectedRtc {
+            device: "rtc0".to_string(),
+            name: None,
+            max_drift_s: 2,
+            retention_s: None,
+            hctosys: None,
+        }
+    }
+}
+
+impl ExpectedRtc {
+    /// `None` without an `[rtc]` section.
+    pub fn from_manifest(manifest: &Manifest) -> Result<Option<ExpectedRtc>> {
+        let Some(section) = manifest.sections.get("rtc") else {
+            return Ok(None);
+        };
+        let number = |key: &str| -> Result<Option<u64>> {
+            match &section[key] {
+                Value::Null => Ok(None),
+                value => value
+                    .as_u64()
+                    .map(Some)
+                    .ok_or_else(|| anyhow::anyhow!("rtc.{} must be a positive integer", key)),
+            }
+        };
+        let defaults = ExpectedRtc::default();
+        Ok(Some(ExpectedRtc {
+            device: section["device"]
+                .as_str()
+                .map(|d| d.trim_start_matches("/dev/").to_string())
+                .unwrap_or(defaults.device),
+            name: section["name"].as_str().map(str::to_string),
+            max_drift_s: number("max_drift_s")?.unwrap_or(defaults.max_drift_s),
+            retention_s: number("retention_s")?,
+            hctosys: section["hctosys"].as_bool(),
+        }))
+    }
+}
+
+/// Returns human-readable descriptions of every problem against the expectations.
+///
+/// `retained` is the interval and the seconds the RTC advanced over it, see
+/// [`measure_retention_in`]. Drift is only meaningful once the system clock is
+/// synchronized, e.g. by NTP; an RTC that resets across power cycles points at a flat
+/// or missing coin cell.
+pub fn validate_rtc(
+    rtcs: &[Rtc],
+    system_time: SystemTime,
+    retained: Option<(Duration, u64)>,
+    expected: &ExpectedRtc,
+) -> Vec<String> {
+    let device = &expected.device;
+    let Some(rtc) = rtcs.iter().find(|r| r.device == *device) else {
+        return vec![format!("{}: not registered", device)];
+    };
+    let mut problems = Vec::new();
+    if !rtc.has_node {
+        problems.push(format!("{}: no device node /dev/{}", device, device));
+    }
+    if let Some(name) = &expected.name
+        && !rtc.name.contains(name.as_str())
+    {
+        problems.push(format!("{}: {} (expected {})", device, rtc.name, name));
+    }
+    if let Some(hctosys) = expected.hctosys
+        && rtc.hctosys != hctosys
+    {
+        let verb = if hctosys { "didn't set" } else { "set" };
+        problems.push(format!("{}: {} the system clock at boot", device, verb));
+    }
+    match rtc.since_epoch {
+        None => problems.push(format!(
+            "{}: time not readable (never set, or lost its backup supply)",
+            device
+        )),
+        Some(time) if time < RESET_BEFORE => problems.push(format!(
+            "{}: reads {} (reset, or lost its backup supply)",
+            device,
+            rtc.date.as_deref().unwrap_or("before 2001")
+        )),
+        Some(time) => {
+            let system = system_time
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            if time.abs_diff(system) > expected.max_drift_s {
+                let direction = if time < system { "behind" } else { "ahead of" };
+                problems.push(format!(
+                    "{}: {} s {} system time (at most {} s)",
+                    device,
+                    time.abs_diff(system),
+                    direction,
+                    expected.max_drift_s
+                ));
+            }
+        }
+    }
+    if let Some((interval, advanced)) = retained
+        && advanced.abs_diff(interval.as_secs()) > 1
+    {
+        problems.push(format!(
+            "{}: advanced {} s in {} s",
+            device,
+            advanced,
+            interval.as_secs()
+        ));
+    }
+    problems
+}
@@ -0,0 +1,64 @@
+use crate::system_root::SystemRoot;
+use anyhow::Result;
+use std::fmt;
+
+/// Whether this is an `initramfs` build, which uses only /sys, /proc and /dev: checks that
+/// need D-Bus or a helper program fail with a clear error instead of trying.
+///
+/// Device discovery walks sysfs either way; the udev database only adds properties when
+/// it is there.
+pub const INITRAMFS: bool = cfg!(feature = "initramfs");
+
+/// Search path for helper programs when PATH is unset, as in a bare rescue shell.
+const DEFAULT_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+/// Something a check needs beyond /sys, /proc and /dev.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dependency {
+    DBus,            // The system bus
+    Program(String), // A helper program on PATH, e.g. "dmsetup"
+}
+
+impl Dependency {
+    pub fn program(name: &str) -> Dependency {
+        Dependency::Program(name.to_string())
+    }
+}
+
+impl fmt::Display for Dependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dependency::DBus => write!(f, "D-Bus"),
+            Dependency::Program(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Whether the live system provides `dependency`; never in an `initramfs` build.
+pub fn available(dependency: &Dependency) -> bool {
+    !INITRAMFS && available_in(&SystemRoot::live(), dependency)
+}
+
+/// Whether the system at `root` provides `dependency`, whatever the build.
+pub fn available_in(root: &SystemRoot, dependency: &Dependency) -> bool {
+    match dependency {
+        Dependency::DBus => root.file("/run/dbus/system_bus_socket").exists(),
+        Dependency::Program(name) if name.contains('/') => root.file(name).is_file(),
+        Dependency::Program(name) => std::env::var("PATH")
+            .unwrap_or_else(|_| DEFAULT_PATH.to_string())
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .any(|dir| root.file(dir).join(name).is_file()),
+    }
+}
+
+/// Errors out unless [`available`]; call it before talking to D-Bus or starting a program.
+pub fn require(dependency: &Dependency) -> Result<()> {
+    if INITRAMFS {
+        anyhow::bail!("{} is not used by initramfs builds", dependency);
+    }
+    if !available(dependency) {
+        anyhow::bail!("{} is not available on this system", dependency);
+    }
+    Ok(())
+}
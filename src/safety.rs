@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// What an operation does to the unit, from least to most intrusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Access {
+    ReadOnly,   // procfs/sysfs/udev reads, copying logs
+    BusTraffic, // Generates transactions on a shared bus, e.g. I2C hw probes
+    Write,      // Changes device or system state, e.g. driving GPIOs, binding drivers
+}
+
+/// Restrictions in force per `max_access`, see [`SafetyPolicy::enforce`]; counted, so
+/// concurrent runs may lift theirs in any order.
+static RESTRICTIONS: [AtomicUsize; 3] = [const { AtomicUsize::new(0) }; 3];
+
+/// Errors out if the process-wide policy doesn't permit `operation`; called by every
+/// entry point that probes a bus, drives a GPIO or binds a driver.
+pub fn require(access: Access, operation: &str) -> anyhow::Result<()> {
+    SafetyPolicy::active().require(access, operation)
+}
+
+/// Lifts a restriction of the process-wide policy on drop.
+#[derive(Debug)]
+#[must_use = "the restriction is lifted again when the guard drops"]
+pub struct PolicyGuard {
+    max_access: Access,
+}
+
+impl Drop for PolicyGuard {
+    fn drop(&mut self) {
+        RESTRICTIONS[self.max_access as usize].fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The most intrusive access a run permits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafetyPolicy {
+    pub max_access: Access,
+}
+
+impl SafetyPolicy {
+    /// For deployed customer units.
+    pub const READ_ONLY: SafetyPolicy = SafetyPolicy {
+        max_access: Access::ReadOnly,
+    };
+    /// For units on a test station.
+    pub const STATION: SafetyPolicy = SafetyPolicy {
+        max_access: Access::Write,
+    };
+
+    /// The process-wide policy: the strictest one enforced, else [`SafetyPolicy::STATION`].
+    pub fn active() -> SafetyPolicy {
+        let max_access = [Access::ReadOnly, Access::BusTraffic]
+            .into_iter()
+            .find(|access| RESTRICTIONS[*access as usize].load(Ordering::SeqCst) > 0)
+            .unwrap_or(Access::Write);
+        SafetyPolicy { max_access }
+    }
+
+    /// Restricts the whole process to this policy until the guard drops. Only ever narrows:
+    /// a broader policy inside a restricted run keeps the restriction.
+    pub fn enforce(self) -> PolicyGuard {
+        RESTRICTIONS[self.max_access as usize].fetch_add(1, Ordering::SeqCst);
+        PolicyGuard {
+            max_access: self.max_access,
+        }
+    }
+
+    pub fn permits(&self, access: Access) -> bool {
+        access <= self.max_access
+    }
+
+    /// Errors out if `operation` needs more than the policy permits.
+    pub fn require(&self, access: Access, operation: &str) -> anyhow::Result<()> {
+        if !self.permits(access) {
+            anyhow::bail!(
+                "{} needs {:?} access, policy allows at most {:?}",
+                operation,
+                access,
+                self.max_access
+            );
+        }
+        Ok(())
+    }
+}
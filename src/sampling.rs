@@ -0,0 +1,172 @@
+use crate::derating::Threshold;
+use crate::evidence::EvidenceBundle;
+use crate::measurement::{Measurement, Unit};
+use anyhow::Result;
+use serde_json::Value;
+use std::path::Path;
+use std::time::Duration;
+
+/// Summary statistics of a series of samples. `stddev` is the sample standard deviation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleStats {
+    pub count: usize,
+    pub mean: f64,
+    pub stddev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl SampleStats {
+    pub fn from_samples(samples: &[f64]) -> Option<SampleStats> {
+        if samples.is_empty() {
+            return None;
+        }
+        let count = samples.len();
+        let mean = samples.iter().sum::<f64>() / count as f64;
+        let variance = if count > 1 {
+            samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (count - 1) as f64
+        } else {
+            0.0
+        };
+        Some(SampleStats {
+            count,
+            mean,
+            stddev: variance.sqrt(),
+            min: samples.iter().copied().fold(f64::INFINITY, f64::min),
+            max: samples.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+/// Limits on the statistics of a sampled value. Unset limits are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct SampleLimits {
+    pub min_mean: Option<f64>,
+    pub max_mean: Option<f64>,
+    pub max_stddev: Option<f64>,
+    pub min: Option<f64>, // Every sample must be at least this
+    pub max: Option<f64>, // Every sample must be at most this
+}
+
+impl SampleLimits {
+    /// Reads the `limits` table of a manifest; each limit may be derated by temperature, see
+    /// [`Threshold`], and is resolved against the temperature now.
+    pub fn resolve_in(limits: &Value, sys_root: &Path) -> Result<SampleLimits> {
+        let get = |key: &str| -> Result<Option<f64>> {
+            match limits.get(key) {
+                Some(value) => Threshold::from_value(value)
+                    .and_then(|t| t.resolve_in(sys_root))
+                    .map(Some)
+                    .map_err(|e| anyhow::anyhow!("limits.{}: {}", key, e)),
+                None => Ok(None),
+            }
+        };
+        Ok(SampleLimits {
+            min_mean: get("min_mean")?,
+            max_mean: get("max_mean")?,
+            max_stddev: get("max_stddev")?,
+            min: get("min")?,
+            max: get("max")?,
+        })
+    }
+}
+
+/// A check that samples a value repeatedly.
+#[derive(Debug, Clone, Default)]
+pub struct SampledCheck {
+    pub id: String, // Used for messages and the evidence file name
+    pub samples: usize,
+    pub interval: Duration,
+    pub limits: SampleLimits, // In `unit`
+    pub unit: Option<Unit>,   // Unit the sampler returns; None for plain numbers
+}
+
+/// Outcome of a sampled check.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleResult {
+    pub samples: Vec<f64>,
+    pub stats: Option<SampleStats>,
+    pub violations: Vec<String>,
+    pub unit: Option<Unit>,
+}
+
+impl SampleResult {
+    /// The mean as a measurement, if the check has a unit and took samples.
+    pub fn mean(&self, source: &str) -> Option<Measurement> {
+        Some(Measurement::new(self.stats?.mean, self.unit?, source))
+    }
+}
+
+/// Returns human-readable descriptions of every violated limit.
+pub fn check_stats(id: &str, stats: &SampleStats, limits: &SampleLimits) -> Vec<String> {
+    let mut violations = Vec::new();
+    let checks = [
+        ("mean", stats.mean, limits.min_mean, limits.max_mean),
+        ("stddev", stats.stddev, None, limits.max_stddev),
+        ("min", stats.min, limits.min, None),
+        ("max", stats.max, None, limits.max),
+    ];
+    for (name, value, low, high) in checks {
+        if let Some(low) = low
+            && value < low
+        {
+            violations.push(format!("{}: {} {} below {}", id, name, value, low));
+        }
+        if let Some(high) = high
+            && value > high
+        {
+            violations.push(format!("{}: {} {} above {}", id, name, value, high));
+        }
+    }
+    violations
+}
+
+/// Calls `sample` `check.samples` times, `check.interval` apart, and checks the statistics.
+///
+/// The raw samples go to the bundle as `<id>.csv`. A failing sample aborts the check.
+pub fn run_sampled(
+    check: &SampledCheck,
+    bundle: Option<&mut EvidenceBundle>,
+    mut sample: impl FnMut() -> Result<f64>,
+) -> Result<SampleResult> {
+    let mut samples = Vec::with_capacity(check.samples);
+    for i in 0..check.samples {
+        if i > 0 && !check.interval.is_zero() {
+            std::thread::sleep(check.interval);
+        }
+        samples.push(sample()?);
+    }
+
+    if let Some(bundle) = bundle {
+        let csv: String = samples
+            .iter()
+            .enumerate()
+            .map(|(i, s)| format!("{},{}\n", i, s))
+            .collect();
+        bundle.add_text(
+            &check.id,
+            &format!("{}.csv", check.id),
+            &format!(
+                "index,value{}\n{}",
+                check
+                    .unit
+                    .map(|u| format!(" ({})", u.symbol()))
+                    .unwrap_or_default(),
+                csv
+            ),
+            "Raw samples",
+        )?;
+    }
+
+    let stats = SampleStats::from_samples(&samples);
+    let violations = match &stats {
+        Some(stats) => check_stats(&check.id, stats, &check.limits),
+        None => vec![format!("{}: no samples taken", check.id)],
+    };
+    Ok(SampleResult {
+        samples,
+        stats,
+        violations,
+        unit: check.unit,
+    })
+}
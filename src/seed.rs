@@ -0,0 +1,143 @@
+use crate::manifest::Manifest;
+use anyhow::Result;
+use serde_json::Value;
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+
+/// Environment variable that fixes the run seed, e.g. on a station rerunning a failure.
+pub const SEED_ENV: &str = "TUX_SEED";
+
+/// The run-level seed every randomized pattern derives from: memory test patterns, SPI and
+/// serial loopback data, storage write blocks. Recorded in the report, so a failure can be
+/// reproduced exactly on the same unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Seed(pub u64);
+
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:016x}", self.0)
+    }
+}
+
+impl FromStr for Seed {
+    type Err = anyhow::Error;
+
+    /// Decimal, or hex with a `0x` prefix as [`Display`](fmt::Display) writes it.
+    fn from_str(s: &str) -> Result<Seed> {
+        let s = s.trim();
+        let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => s.parse(),
+        };
+        parsed
+            .map(Seed)
+            .map_err(|e| anyhow::anyhow!("invalid seed {:?}: {}", s, e))
+    }
+}
+
+impl Seed {
+    /// A seed from /dev/urandom, or the clock where there is none.
+    pub fn fresh() -> Seed {
+        let mut bytes = [0u8; 8];
+        let urandom =
+            std::fs::File::open("/dev/urandom").and_then(|mut f| f.read_exact(&mut bytes));
+        if urandom.is_ok() {
+            return Seed(u64::from_le_bytes(bytes));
+        }
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Seed(splitmix64(&mut (nanos ^ u64::from(std::process::id()))))
+    }
+
+    /// The `seed` of the `[patterns]` section, as an integer or a seed string.
+    pub fn from_manifest(manifest: &Manifest) -> Result<Option<Seed>> {
+        match manifest.sections.get("patterns").map(|s| &s["seed"]) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => s.parse().map(Some),
+            Some(value) => value
+                .as_u64()
+                .map(|seed| Some(Seed(seed)))
+                .ok_or_else(|| anyhow::anyhow!("patterns.seed must be an unsigned integer")),
+        }
+    }
+
+    /// The seed of a run: `explicit` (e.g. `--seed`), else [`SEED_ENV`], else the manifest,
+    /// else a fresh one.
+    pub fn resolve(explicit: Option<Seed>, manifest: Option<&Manifest>) -> Result<Seed> {
+        if let Some(seed) = explicit {
+            return Ok(seed);
+        }
+        if let Ok(value) = std::env::var(SEED_ENV) {
+            return value
+                .parse()
+                .map_err(|e| anyhow::anyhow!("{}: {}", SEED_ENV, e));
+        }
+        if let Some(seed) = manifest.map(Seed::from_manifest).transpose()?.flatten() {
+            return Ok(seed);
+        }
+        Ok(Seed::fresh())
+    }
+
+    /// The generator of one named pattern, e.g. "memory" or "spi_loopback bus 1".
+    ///
+    /// Each name gets its own stream, so adding or reordering checks doesn't change the data
+    /// another check writes.
+    pub fn stream(&self, name: &str) -> PatternRng {
+        // FNV-1a of the name
+        let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+            (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+        PatternRng {
+            state: self.0 ^ hash,
+        }
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// A SplitMix64 generator: fast, and the same sequence on every platform and release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternRng {
+    state: u64,
+}
+
+impl PatternRng {
+    pub fn next_u64(&mut self) -> u64 {
+        splitmix64(&mut self.state)
+    }
+
+    pub fn fill(&mut self, buffer: &mut [u8]) {
+        for chunk in buffer.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    /// The next `len` bytes, e.g. one block of a storage write test.
+    pub fn bytes(&mut self, len: usize) -> Vec<u8> {
+        let mut buffer = vec![0; len];
+        self.fill(&mut buffer);
+        buffer
+    }
+}
+
+/// Records the seed in a report, e.g. the `{"buses": [...]}` of [`crate::report::to_json`].
+pub fn record(report: &mut Value, seed: Seed) {
+    if let Some(report) = report.as_object_mut() {
+        report.insert("seed".to_string(), Value::String(seed.to_string()));
+    }
+}
+
+/// The seed a report was run with, to pass back as `--seed`.
+pub fn recorded(report: &Value) -> Option<Seed> {
+    report["seed"].as_str().and_then(|s| s.parse().ok())
+}
@@ -0,0 +1,281 @@
+use crate::ethtool::ethtool_ioctl;
+use crate::measurement::{Measurement, Unit};
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+const SFP_DRIVER_DIR: &str = "/sys/bus/platform/drivers/sfp";
+const SYS_CLASS_HWMON: &str = "/sys/class/hwmon";
+
+/// An SFP cage registered with the kernel `sfp` driver.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SfpCage {
+    pub name: String,          // Platform device name, e.g. "sfp-eth1" or "sfp0"
+    pub hwmon: Option<String>, // e.g. "hwmon3" (only present with a module that has DDM)
+}
+
+/// Lists SFP cages bound to the kernel `sfp` driver and their hwmon devices.
+pub fn discover_cages() -> Result<Vec<SfpCage>> {
+    discover_cages_in(Path::new(SFP_DRIVER_DIR), Path::new(SYS_CLASS_HWMON))
+}
+
+/// Same as [`discover_cages`], but for arbitrary driver and hwmon class directories.
+pub fn discover_cages_in(driver_dir: &Path, hwmon_dir: &Path) -> Result<Vec<SfpCage>> {
+    let mut cages = Vec::new();
+    if !driver_dir.is_dir() {
+        return Ok(cages);
+    }
+
+    for entry in fs::read_dir(driver_dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Bound devices are symlinks; skip bind/unbind/uevent/module
+        if !entry.file_type()?.is_symlink() || name == "module" {
+            continue;
+        }
+        cages.push(SfpCage { name, hwmon: None });
+    }
+
+    if let Ok(hwmons) = fs::read_dir(hwmon_dir) {
+        for hwmon in hwmons.filter_map(|e| e.ok()) {
+            let device = fs::read_link(hwmon.path().join("device")).ok();
+            let device_name = device
+                .as_ref()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string());
+            if let Some(cage) = cages
+                .iter_mut()
+                .find(|c| Some(&c.name) == device_name.as_ref())
+            {
+                cage.hwmon = Some(hwmon.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+
+    cages.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(cages)
+}
+
+/// Static module information decoded from the SFF-8472 A0h page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SfpModuleInfo {
+    pub identifier: u8, // 0x03 for SFP/SFP+
+    pub connector: u8,  // e.g. 0x07 LC, 0x21 copper pigtail
+    pub vendor: String,
+    pub vendor_oui: [u8; 3],
+    pub part_number: String,
+    pub revision: String,
+    pub serial: String,
+    pub date_code: String,
+    pub wavelength_nm: Option<u16>, // None for copper (DAC) modules
+    pub ddm_supported: bool,
+}
+
+/// Digital diagnostics readings, in SI-ish units.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DdmReadings {
+    pub temperature_c: Option<f64>,
+    pub vcc_v: Option<f64>,
+    pub tx_bias_ma: Option<f64>,
+    pub tx_power_mw: Option<f64>,
+    pub rx_power_mw: Option<f64>,
+}
+
+impl DdmReadings {
+    /// The available readings as typed measurements, tagged with `source` (e.g. "sfp0").
+    pub fn measurements(&self, source: &str) -> Vec<Measurement> {
+        [
+            ("temperature", self.temperature_c, Unit::Celsius),
+            ("vcc", self.vcc_v, Unit::Volt),
+            ("tx_bias", self.tx_bias_ma, Unit::Milliampere),
+            ("tx_power", self.tx_power_mw, Unit::Milliwatt),
+            ("rx_power", self.rx_power_mw, Unit::Milliwatt),
+        ]
+        .into_iter()
+        .filter_map(|(name, value, unit)| {
+            value.map(|v| Measurement::new(v, unit, &format!("{}/{}", source, name)))
+        })
+        .collect()
+    }
+}
+
+fn ascii_field(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).trim().to_string()
+}
+
+/// Decodes the A0h page (first 96+ bytes) of an SFP module EEPROM.
+pub fn decode_eeprom(eeprom: &[u8]) -> Result<SfpModuleInfo> {
+    if eeprom.len() < 96 {
+        anyhow::bail!("SFP EEPROM too short: {} bytes", eeprom.len());
+    }
+    // Byte 8 bit 2/3: passive/active copper cable, wavelength field holds cable compliance
+    let is_copper = eeprom[8] & 0x0c != 0;
+    let wavelength = u16::from_be_bytes([eeprom[60], eeprom[61]]);
+
+    Ok(SfpModuleInfo {
+        identifier: eeprom[0],
+        connector: eeprom[2],
+        vendor: ascii_field(&eeprom[20..36]),
+        vendor_oui: [eeprom[37], eeprom[38], eeprom[39]],
+        part_number: ascii_field(&eeprom[40..56]),
+        revision: ascii_field(&eeprom[56..60]),
+        serial: ascii_field(&eeprom[68..84]),
+        date_code: ascii_field(&eeprom[84..92]),
+        wavelength_nm: (!is_copper && wavelength != 0).then_some(wavelength),
+        ddm_supported: eeprom[92] & 0x40 != 0,
+    })
+}
+
+/// Decodes internally calibrated DDM values from the A2h page (bytes 96..106).
+///
+/// `a2` is the A2h page on its own, i.e. bytes 256.. of a full ethtool dump.
+pub fn decode_ddm(a2: &[u8]) -> Result<DdmReadings> {
+    if a2.len() < 106 {
+        anyhow::bail!("SFP A2h page too short: {} bytes", a2.len());
+    }
+    let word = |i: usize| u16::from_be_bytes([a2[i], a2[i + 1]]);
+
+    Ok(DdmReadings {
+        temperature_c: Some(i16::from_be_bytes([a2[96], a2[97]]) as f64 / 256.0),
+        vcc_v: Some(word(98) as f64 * 100e-6),
+        tx_bias_ma: Some(word(100) as f64 * 2e-3),
+        tx_power_mw: Some(word(102) as f64 * 1e-4),
+        rx_power_mw: Some(word(104) as f64 * 1e-4),
+    })
+}
+
+/// Reads DDM values from the SFP hwmon device (temp1, in0, curr1, power1/2).
+pub fn read_hwmon_ddm(hwmon: &str) -> DdmReadings {
+    read_hwmon_ddm_in(&Path::new(SYS_CLASS_HWMON).join(hwmon))
+}
+
+/// Same as [`read_hwmon_ddm`], but for an arbitrary hwmon device directory.
+pub fn read_hwmon_ddm_in(hwmon_dir: &Path) -> DdmReadings {
+    let read = |attr: &str, scale: f64| {
+        fs::read_to_string(hwmon_dir.join(attr))
+            .ok()
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .map(|v| v * scale)
+    };
+    DdmReadings {
+        temperature_c: read("temp1_input", 1e-3), // m°C
+        vcc_v: read("in0_input", 1e-3),           // mV
+        tx_bias_ma: read("curr1_input", 1.0),     // mA
+        tx_power_mw: read("power1_input", 1e-3),  // µW
+        rx_power_mw: read("power2_input", 1e-3),  // µW
+    }
+}
+
+// Linux ethtool ABI (include/uapi/linux/ethtool.h)
+const ETHTOOL_GMODULEINFO: u32 = 0x42;
+const ETHTOOL_GMODULEEEPROM: u32 = 0x43;
+
+#[repr(C)]
+struct EthtoolModinfo {
+    cmd: u32,
+    kind: u32,
+    eeprom_len: u32,
+    reserved: [u32; 8],
+}
+
+#[repr(C)]
+struct EthtoolEeprom {
+    cmd: u32,
+    magic: u32,
+    offset: u32,
+    len: u32,
+    data: [u8; 512],
+}
+
+/// Reads the raw module EEPROM of `iface` (same data as `ethtool -m <iface> raw on`).
+pub fn read_module_eeprom(iface: &str) -> Result<Vec<u8>> {
+    let mut modinfo = EthtoolModinfo {
+        cmd: ETHTOOL_GMODULEINFO,
+        kind: 0,
+        eeprom_len: 0,
+        reserved: [0; 8],
+    };
+    ethtool_ioctl(iface, &mut modinfo)?;
+
+    let mut eeprom = EthtoolEeprom {
+        cmd: ETHTOOL_GMODULEEEPROM,
+        magic: 0,
+        offset: 0,
+        len: modinfo.eeprom_len.min(512),
+        data: [0; 512],
+    };
+    ethtool_ioctl(iface, &mut eeprom)?;
+    Ok(eeprom.data[..eeprom.len as usize].to_vec())
+}
+
+/// Inclusive `(min, max)` thresholds for DDM values; unset limits are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct DdmLimits {
+    pub temperature_c: Option<(f64, f64)>,
+    pub vcc_v: Option<(f64, f64)>,
+    pub tx_bias_ma: Option<(f64, f64)>,
+    pub tx_power_mw: Option<(f64, f64)>,
+    pub rx_power_mw: Option<(f64, f64)>,
+}
+
+/// An expected module type. Unset fields are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedSfpModule {
+    pub vendor: Option<String>,
+    pub part_number_prefix: Option<String>,
+    pub wavelength_nm: Option<u16>,
+    pub ddm: DdmLimits,
+}
+
+/// Returns human-readable descriptions of every mismatch against `expected`.
+pub fn validate_module(
+    info: &SfpModuleInfo,
+    ddm: &DdmReadings,
+    expected: &ExpectedSfpModule,
+) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    if let Some(vendor) = &expected.vendor
+        && !info.vendor.eq_ignore_ascii_case(vendor)
+    {
+        mismatches.push(format!("vendor {} (expected {})", info.vendor, vendor));
+    }
+    if let Some(prefix) = &expected.part_number_prefix
+        && !info.part_number.starts_with(prefix.as_str())
+    {
+        mismatches.push(format!("part {} (expected {}*)", info.part_number, prefix));
+    }
+    if let Some(wavelength) = expected.wavelength_nm
+        && info.wavelength_nm != Some(wavelength)
+    {
+        mismatches.push(format!(
+            "wavelength {:?} nm (expected {} nm)",
+            info.wavelength_nm, wavelength
+        ));
+    }
+
+    let checks = [
+        (
+            "temperature",
+            ddm.temperature_c,
+            expected.ddm.temperature_c,
+            "C",
+        ),
+        ("vcc", ddm.vcc_v, expected.ddm.vcc_v, "V"),
+        ("tx bias", ddm.tx_bias_ma, expected.ddm.tx_bias_ma, "mA"),
+        ("tx power", ddm.tx_power_mw, expected.ddm.tx_power_mw, "mW"),
+        ("rx power", ddm.rx_power_mw, expected.ddm.rx_power_mw, "mW"),
+    ];
+    for (name, value, limits, unit) in checks {
+        let Some((min, max)) = limits else { continue };
+        match value {
+            Some(v) if v < min || v > max => mismatches.push(format!(
+                "{} {:.3} {} outside [{}, {}]",
+                name, v, unit, min, max
+            )),
+            Some(_) => {}
+            None => mismatches.push(format!("{} not reported", name)),
+        }
+    }
+    mismatches
+}
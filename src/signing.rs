@@ -0,0 +1,94 @@
+use crate::evidence::EvidenceBundle;
+use crate::integrity::{self, IntegrityResult};
+use anyhow::Result;
+use ed25519_dalek::{Signer, SigningKey};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the signed checksum list written into signed bundles.
+pub const BUNDLE_SUMS: &str = "SHA256SUMS";
+
+/// `<path>.sig`, where detached signatures live.
+pub fn signature_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".sig");
+    PathBuf::from(name)
+}
+
+/// Reads a 32-byte Ed25519 key stored raw or as 64 hex characters.
+pub fn read_key_file(path: &Path) -> Result<[u8; 32]> {
+    let contents = fs::read(path)?;
+    let bytes = match std::str::from_utf8(&contents).map(|s| s.trim()) {
+        Ok(hex) if hex.len() == 64 => (0..32)
+            .map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()?,
+        _ => contents,
+    };
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("{}: not a 32-byte key", path.display()))
+}
+
+/// Reads a file, refusing to return it unless `<path>.sig` verifies with `public_key`.
+///
+/// Stations use this on the manifest so they only run approved profiles.
+pub fn read_verified(path: &Path, public_key: &[u8; 32]) -> Result<Vec<u8>> {
+    let contents = fs::read(path)?;
+    let signature = fs::read(signature_path(path))?;
+    integrity::verify_signature(&contents, &signature, public_key)
+        .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+    Ok(contents)
+}
+
+/// The station's signing key.
+pub struct StationKey {
+    key: SigningKey,
+}
+
+impl StationKey {
+    pub fn from_bytes(secret: &[u8; 32]) -> StationKey {
+        StationKey {
+            key: SigningKey::from_bytes(secret),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<StationKey> {
+        Ok(StationKey::from_bytes(&read_key_file(path)?))
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        self.key.verifying_key().to_bytes()
+    }
+
+    /// Writes a detached signature of `path` to `<path>.sig` and returns its path.
+    pub fn sign_file(&self, path: &Path) -> Result<PathBuf> {
+        let signature = self.key.sign(&fs::read(path)?);
+        let sig_path = signature_path(path);
+        fs::write(&sig_path, signature.to_bytes())?;
+        Ok(sig_path)
+    }
+
+    /// Writes the bundle index plus a signed `SHA256SUMS` covering it and every entry.
+    pub fn sign_bundle(&self, bundle: &EvidenceBundle) -> Result<PathBuf> {
+        bundle.write_index()?;
+        let mut paths: Vec<PathBuf> = bundle.entries().iter().map(|e| e.path.clone()).collect();
+        paths.push(PathBuf::from("index.json"));
+
+        let mut sums = String::new();
+        for path in &paths {
+            let hash = integrity::sha256_file(&bundle.dir.join(path))?;
+            sums.push_str(&format!("{}  {}\n", hash, path.display()));
+        }
+        let sums_path = bundle.dir.join(BUNDLE_SUMS);
+        fs::write(&sums_path, sums)?;
+        self.sign_file(&sums_path)
+    }
+}
+
+/// Verifies a signed bundle: the checksum list signature, then every listed file.
+pub fn verify_bundle(dir: &Path, public_key: &[u8; 32]) -> Result<IntegrityResult> {
+    let sums_path = dir.join(BUNDLE_SUMS);
+    let entries =
+        integrity::load_signed_manifest(&sums_path, &signature_path(&sums_path), public_key)?;
+    Ok(integrity::check_files(dir, &entries, &[]))
+}
@@ -0,0 +1,234 @@
+use crate::measurement::{Measurement, Unit};
+use crate::sampling::SampleStats;
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Outcome of one run of a check.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CheckRun {
+    pub failures: Vec<String>, // Empty if the check passed
+    pub measurements: Vec<Measurement>,
+}
+
+/// A check the soak runner repeats.
+pub trait SoakCheck {
+    fn id(&self) -> &str;
+    /// An error counts as a failure of this iteration; the soak carries on.
+    fn run(&mut self) -> Result<CheckRun>;
+}
+
+/// How long to soak. The run stops at whichever limit is hit first.
+#[derive(Debug, Clone, Default)]
+pub struct SoakConfig {
+    pub duration: Option<Duration>,
+    pub iterations: Option<usize>,
+    pub interval: Duration,          // Pause between iterations
+    pub report_dir: Option<PathBuf>, // Per-iteration reports and the rollup go here
+}
+
+/// Pass/fail history of one check over the soak.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckRollup {
+    pub id: String,
+    pub runs: usize,
+    pub failures: usize,
+    pub first_failure: Option<usize>, // Iteration, from 1
+    pub last_failure_message: Option<String>,
+}
+
+impl CheckRollup {
+    pub fn failure_rate(&self) -> f64 {
+        if self.runs == 0 {
+            return 0.0;
+        }
+        self.failures as f64 / self.runs as f64
+    }
+}
+
+/// How a measurement moved over the soak, in the unit of its first reading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    pub check: String,
+    pub source: String,
+    pub unit: Unit,
+    pub first: f64,
+    pub last: f64,
+    pub stats: SampleStats,
+}
+
+impl Drift {
+    pub fn delta(&self) -> f64 {
+        self.last - self.first
+    }
+}
+
+/// Summary of a whole soak run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoakRollup {
+    pub iterations: usize,
+    pub elapsed: Duration,
+    pub first_failure: Option<usize>, // First iteration any check failed in
+    pub checks: Vec<CheckRollup>,
+    pub drift: Vec<Drift>,
+}
+
+impl SoakRollup {
+    pub fn passed(&self) -> bool {
+        self.first_failure.is_none()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "passed": self.passed(),
+            "iterations": self.iterations,
+            "elapsed_s": self.elapsed.as_secs_f64(),
+            "first_failure": self.first_failure,
+            "checks": self.checks.iter().map(|c| json!({
+                "id": c.id,
+                "runs": c.runs,
+                "failures": c.failures,
+                "failure_rate": c.failure_rate(),
+                "first_failure": c.first_failure,
+                "last_failure_message": c.last_failure_message,
+            })).collect::<Vec<_>>(),
+            "drift": self.drift.iter().map(|d| json!({
+                "check": d.check,
+                "source": d.source,
+                "unit": d.unit.symbol(),
+                "first": d.first,
+                "last": d.last,
+                "delta": d.delta(),
+                "mean": d.stats.mean,
+                "stddev": d.stats.stddev,
+                "min": d.stats.min,
+                "max": d.stats.max,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+fn iteration_json(iteration: usize, elapsed: Duration, results: &[(&str, CheckRun)]) -> Value {
+    json!({
+        "iteration": iteration,
+        "elapsed_s": elapsed.as_secs_f64(),
+        "checks": results.iter().map(|(id, run)| json!({
+            "id": id,
+            "passed": run.failures.is_empty(),
+            "failures": run.failures,
+            "measurements": run.measurements.iter().map(|m| json!({
+                "source": m.source,
+                "value": m.value,
+                "unit": m.unit.symbol(),
+            })).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn write_json(path: &Path, value: &Value) -> Result<()> {
+    fs::write(path, serde_json::to_vec_pretty(value)?)
+        .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+}
+
+/// Repeats `checks` until the configured duration or iteration count is reached.
+///
+/// With a report directory, each iteration is written to `iteration-NNNN.json` as it finishes
+/// (so an aborted overnight run still leaves its history) and the rollup to `rollup.json`.
+pub fn run_soak(config: &SoakConfig, checks: &mut [&mut dyn SoakCheck]) -> Result<SoakRollup> {
+    if config.duration.is_none() && config.iterations.is_none() {
+        anyhow::bail!("soak needs a duration or an iteration count");
+    }
+    if let Some(dir) = &config.report_dir {
+        fs::create_dir_all(dir)?;
+    }
+    let start = Instant::now();
+    let mut rollups: Vec<CheckRollup> = checks
+        .iter()
+        .map(|c| CheckRollup {
+            id: c.id().to_string(),
+            runs: 0,
+            failures: 0,
+            first_failure: None,
+            last_failure_message: None,
+        })
+        .collect();
+    // Per (check, source): unit of the first reading and every value converted to it
+    let mut series: Vec<(String, String, Unit, Vec<f64>)> = Vec::new();
+    let mut iteration = 0;
+
+    loop {
+        if config.iterations.is_some_and(|n| iteration >= n)
+            || config.duration.is_some_and(|d| start.elapsed() >= d)
+        {
+            break;
+        }
+        if iteration > 0 && !config.interval.is_zero() {
+            std::thread::sleep(config.interval);
+        }
+        iteration += 1;
+
+        let mut results = Vec::new();
+        for (check, rollup) in checks.iter_mut().zip(&mut rollups) {
+            let run = check.run().unwrap_or_else(|e| CheckRun {
+                failures: vec![format!("{:#}", e)],
+                measurements: Vec::new(),
+            });
+            rollup.runs += 1;
+            if let Some(message) = run.failures.last() {
+                rollup.failures += 1;
+                rollup.first_failure.get_or_insert(iteration);
+                rollup.last_failure_message = Some(message.clone());
+            }
+            for m in &run.measurements {
+                let entry = series
+                    .iter_mut()
+                    .find(|(id, source, ..)| id == &rollup.id && source == &m.source);
+                match entry {
+                    Some((.., unit, values)) => {
+                        // A reading in an incompatible unit is dropped rather than mixed in
+                        if let Some(value) = m.value_in(*unit) {
+                            values.push(value);
+                        }
+                    }
+                    None => {
+                        series.push((rollup.id.clone(), m.source.clone(), m.unit, vec![m.value]))
+                    }
+                }
+            }
+            results.push((check.id(), run));
+        }
+
+        if let Some(dir) = &config.report_dir {
+            write_json(
+                &dir.join(format!("iteration-{:04}.json", iteration)),
+                &iteration_json(iteration, start.elapsed(), &results),
+            )?;
+        }
+    }
+
+    let rollup = SoakRollup {
+        iterations: iteration,
+        elapsed: start.elapsed(),
+        first_failure: rollups.iter().filter_map(|c| c.first_failure).min(),
+        checks: rollups,
+        drift: series
+            .into_iter()
+            .filter_map(|(check, source, unit, values)| {
+                Some(Drift {
+                    stats: SampleStats::from_samples(&values)?,
+                    first: *values.first()?,
+                    last: *values.last()?,
+                    check,
+                    source,
+                    unit,
+                })
+            })
+            .collect(),
+    };
+    if let Some(dir) = &config.report_dir {
+        write_json(&dir.join("rollup.json"), &rollup.to_json())?;
+    }
+    Ok(rollup)
+}
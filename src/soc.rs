@@ -0,0 +1,158 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// SoC identity from /sys/bus/soc and vendor fallbacks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SocInfo {
+    pub machine: Option<String>,
+    pub family: Option<String>, // e.g. "Freescale i.MX"
+    pub soc_id: Option<String>, // e.g. "i.MX8MP"
+    pub revision: Option<String>,
+    pub serial_number: Option<String>,
+    pub chip_id: Option<String>, // Hex, from nvmem when the soc driver doesn't expose a serial
+}
+
+impl SocInfo {
+    /// Flattens the info into `soc.*` facts for conditional checks.
+    pub fn facts(&self) -> BTreeMap<String, String> {
+        let fields = [
+            ("soc.machine", &self.machine),
+            ("soc.family", &self.family),
+            ("soc.soc_id", &self.soc_id),
+            ("soc.revision", &self.revision),
+            ("soc.serial_number", &self.serial_number),
+            ("soc.chip_id", &self.chip_id),
+        ];
+        fields
+            .into_iter()
+            .filter_map(|(k, v)| v.clone().map(|v| (k.to_string(), v)))
+            .collect()
+    }
+}
+
+/// Where vendor chip IDs live in nvmem: (device name prefix, byte offset, length).
+const NVMEM_CHIP_IDS: [(&str, u64, usize); 3] = [
+    ("sunxi-sid", 0x00, 16),
+    ("rockchip-efuse", 0x07, 16),
+    ("rockchip-otp", 0x0a, 16),
+];
+
+fn read_attr(dir: &Path, name: &str) -> Option<String> {
+    let value = fs::read_to_string(dir.join(name)).ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Reads the chip ID from the first known vendor nvmem device.
+pub fn read_nvmem_chip_id_in(sys_root: &Path) -> Option<String> {
+    let entries = fs::read_dir(sys_root.join("bus/nvmem/devices")).ok()?;
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    for name in names {
+        let Some((_, offset, len)) = NVMEM_CHIP_IDS.iter().find(|(p, _, _)| name.starts_with(p))
+        else {
+            continue;
+        };
+        let data = fs::read(sys_root.join("bus/nvmem/devices").join(&name).join("nvmem")).ok()?;
+        let start = *offset as usize;
+        let id = data.get(start..start + len)?;
+        return Some(id.iter().map(|b| format!("{:02x}", b)).collect());
+    }
+    None
+}
+
+/// Reads SoC information from the first device in /sys/bus/soc/devices.
+pub fn read_soc_info() -> SocInfo {
+    read_soc_info_in(Path::new("/sys"))
+}
+
+/// Same as [`read_soc_info`], with an explicit sysfs root.
+pub fn read_soc_info_in(sys_root: &Path) -> SocInfo {
+    let mut soc_dirs: Vec<_> = fs::read_dir(sys_root.join("bus/soc/devices"))
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    soc_dirs.sort();
+
+    let mut info = match soc_dirs.first() {
+        Some(dir) => SocInfo {
+            machine: read_attr(dir, "machine"),
+            family: read_attr(dir, "family"),
+            soc_id: read_attr(dir, "soc_id"),
+            revision: read_attr(dir, "revision"),
+            serial_number: read_attr(dir, "serial_number"),
+            chip_id: None,
+        },
+        None => SocInfo::default(),
+    };
+    if info.serial_number.is_none() {
+        info.chip_id = read_nvmem_chip_id_in(sys_root);
+    }
+    info
+}
+
+/// Compares revision strings by their numeric parts, e.g. "1.1" < "1.10" < "2.0".
+///
+/// Non-numeric text ("v", "rev") is ignored; equal numbers fall back to string order.
+pub fn compare_revisions(a: &str, b: &str) -> Ordering {
+    let numbers = |s: &str| -> Vec<u64> {
+        s.split(|c: char| !c.is_ascii_digit())
+            .filter(|p| !p.is_empty())
+            .filter_map(|p| p.parse().ok())
+            .collect()
+    };
+    numbers(a).cmp(&numbers(b)).then_with(|| a.cmp(b))
+}
+
+/// Expected silicon. Revision bounds are inclusive.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedSoc {
+    pub family: Option<String>,
+    pub soc_id: Option<String>,
+    pub min_revision: Option<String>,
+    pub max_revision: Option<String>,
+    pub forbidden_revisions: Vec<String>, // e.g. engineering samples "ES1.0"
+}
+
+/// Returns human-readable descriptions of every mismatch against the expected silicon.
+pub fn validate_soc(info: &SocInfo, expected: &ExpectedSoc) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    for (name, actual, want) in [
+        ("family", &info.family, &expected.family),
+        ("soc_id", &info.soc_id, &expected.soc_id),
+    ] {
+        if let Some(want) = want
+            && actual.as_ref() != Some(want)
+        {
+            mismatches.push(format!("SoC {} is {:?} (expected {})", name, actual, want));
+        }
+    }
+
+    let needs_revision = expected.min_revision.is_some()
+        || expected.max_revision.is_some()
+        || !expected.forbidden_revisions.is_empty();
+    let Some(revision) = &info.revision else {
+        if needs_revision {
+            mismatches.push("SoC revision not available".to_string());
+        }
+        return mismatches;
+    };
+    if expected.forbidden_revisions.contains(revision) {
+        mismatches.push(format!("SoC revision {} is not allowed", revision));
+    }
+    if let Some(min) = &expected.min_revision
+        && compare_revisions(revision, min) == Ordering::Less
+    {
+        mismatches.push(format!("SoC revision {} is older than {}", revision, min));
+    }
+    if let Some(max) = &expected.max_revision
+        && compare_revisions(revision, max) == Ordering::Greater
+    {
+        mismatches.push(format!("SoC revision {} is newer than {}", revision, max));
+    }
+    mismatches
+}
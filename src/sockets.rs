@@ -0,0 +1,222 @@
+use anyhow::Result;
+use std::fs;
+use std::io::BufRead;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+const PROC_DIR: &str = "/proc";
+
+/// Transport protocol of a socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl std::fmt::Display for Protocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Protocol::Tcp => write!(f, "tcp"),
+            Protocol::Udp => write!(f, "udp"),
+        }
+    }
+}
+
+/// A listening TCP socket or a bound, unconnected UDP socket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListeningSocket {
+    pub protocol: Protocol,
+    pub address: IpAddr,
+    pub port: u16,
+    pub inode: u64,
+    pub process: Option<String>, // `comm` of an owning process, if resolvable
+}
+
+impl ListeningSocket {
+    pub fn is_loopback(&self) -> bool {
+        self.address.is_loopback()
+    }
+}
+
+// From include/net/tcp_states.h
+const TCP_LISTEN: u8 = 0x0a;
+const TCP_CLOSE: u8 = 0x07;
+
+/// Decodes a `/proc/net/*` hex address. Words are printed in host byte order.
+fn parse_hex_addr(hex: &str) -> Option<IpAddr> {
+    let words: Vec<u32> = (0..hex.len() / 8)
+        .map(|i| u32::from_str_radix(&hex[i * 8..i * 8 + 8], 16))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    match words.len() {
+        1 => Some(IpAddr::V4(Ipv4Addr::from(words[0].to_ne_bytes()))),
+        4 => {
+            let mut octets = [0u8; 16];
+            for (chunk, word) in octets.chunks_mut(4).zip(&words) {
+                chunk.copy_from_slice(&word.to_ne_bytes());
+            }
+            Some(IpAddr::V6(Ipv6Addr::from(octets)))
+        }
+        _ => None,
+    }
+}
+
+/// Parses one of /proc/net/{tcp,tcp6,udp,udp6}, keeping only listening sockets.
+pub fn parse_proc_net_from_reader<R: BufRead>(
+    reader: R,
+    protocol: Protocol,
+) -> Result<Vec<ListeningSocket>> {
+    let mut sockets = Vec::new();
+
+    // First line is the column header
+    for line_result in reader.lines().skip(1) {
+        let line = line_result?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+        let Some((local_addr, local_port)) = fields[1].split_once(':') else {
+            continue;
+        };
+        let Some((_, remote_port)) = fields[2].split_once(':') else {
+            continue;
+        };
+        let state = u8::from_str_radix(fields[3], 16).unwrap_or(0);
+        let listening = match protocol {
+            Protocol::Tcp => state == TCP_LISTEN,
+            Protocol::Udp => state == TCP_CLOSE && remote_port == "0000",
+        };
+        if !listening {
+            continue;
+        }
+
+        let (Some(address), Ok(port)) = (
+            parse_hex_addr(local_addr),
+            u16::from_str_radix(local_port, 16),
+        ) else {
+            continue;
+        };
+        sockets.push(ListeningSocket {
+            protocol,
+            address,
+            port,
+            inode: fields[9].parse().unwrap_or(0),
+            process: None,
+        });
+    }
+    Ok(sockets)
+}
+
+/// Maps socket inodes to the `comm` of their owning processes via /proc/<pid>/fd.
+///
+/// Without root, only the caller's own processes are visible.
+fn resolve_owners(proc_dir: &Path, sockets: &mut [ListeningSocket]) {
+    let Ok(entries) = fs::read_dir(proc_dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        if entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+            .is_none()
+        {
+            continue;
+        }
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.filter_map(|e| e.ok()) {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            // Socket fds link to "socket:[<inode>]"
+            let inode = target
+                .to_str()
+                .and_then(|t| t.strip_prefix("socket:["))
+                .and_then(|t| t.strip_suffix(']'))
+                .and_then(|t| t.parse::<u64>().ok());
+            for socket in sockets.iter_mut().filter(|s| Some(s.inode) == inode) {
+                if socket.process.is_none() {
+                    socket.process = fs::read_to_string(entry.path().join("comm"))
+                        .ok()
+                        .map(|c| c.trim().to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Lists all listening TCP and bound UDP sockets (IPv4 and IPv6).
+pub fn list_listening_sockets() -> Result<Vec<ListeningSocket>> {
+    list_listening_sockets_in(Path::new(PROC_DIR))
+}
+
+/// Same as [`list_listening_sockets`], but for an arbitrary procfs directory.
+pub fn list_listening_sockets_in(proc_dir: &Path) -> Result<Vec<ListeningSocket>> {
+    let mut sockets = Vec::new();
+    for (file, protocol) in [
+        ("net/tcp", Protocol::Tcp),
+        ("net/tcp6", Protocol::Tcp),
+        ("net/udp", Protocol::Udp),
+        ("net/udp6", Protocol::Udp),
+    ] {
+        // IPv6 tables are absent when IPv6 is disabled
+        let Ok(f) = fs::File::open(proc_dir.join(file)) else {
+            continue;
+        };
+        sockets.extend(parse_proc_net_from_reader(
+            std::io::BufReader::new(f),
+            protocol,
+        )?);
+    }
+    resolve_owners(proc_dir, &mut sockets);
+    Ok(sockets)
+}
+
+/// An allowed listener. `address: None` allows any bind address.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllowedSocket {
+    pub protocol: Protocol,
+    pub port: u16,
+    pub address: Option<IpAddr>,
+}
+
+/// Holds results of a listening port audit.
+#[derive(Debug, Default)]
+pub struct PortAuditResult {
+    pub allowed: Vec<ListeningSocket>,
+    pub unexpected: Vec<ListeningSocket>,
+}
+
+impl PortAuditResult {
+    pub fn is_ok(&self) -> bool {
+        self.unexpected.is_empty()
+    }
+}
+
+/// Validates listeners against an allow-list. Loopback-only listeners can be ignored.
+pub fn audit_ports(
+    sockets: &[ListeningSocket],
+    allowed: &[AllowedSocket],
+    ignore_loopback: bool,
+) -> PortAuditResult {
+    let mut result = PortAuditResult::default();
+
+    for socket in sockets {
+        if ignore_loopback && socket.is_loopback() {
+            continue;
+        }
+        let is_allowed = allowed.iter().any(|a| {
+            a.protocol == socket.protocol
+                && a.port == socket.port
+                && a.address.is_none_or(|addr| addr == socket.address)
+        });
+        if is_allowed {
+            result.allowed.push(socket.clone());
+        } else {
+            result.unexpected.push(socket.clone());
+        }
+    }
+    result
+}
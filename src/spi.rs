@@ -0,0 +1,194 @@
+use crate::device::{DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// SPI mode bits, as in linux/spi/spi.h.
+const SPI_CPHA: u32 = 0x01;
+const SPI_CPOL: u32 = 0x02;
+const SPI_CS_HIGH: u32 = 0x04;
+const SPI_LSB_FIRST: u32 = 0x08;
+const SPI_3WIRE: u32 = 0x10;
+
+/// Device-tree flags of a SPI peripheral and the mode bit each sets.
+const MODE_PROPERTIES: [(&str, u32); 5] = [
+    ("spi-cpha", SPI_CPHA),
+    ("spi-cpol", SPI_CPOL),
+    ("spi-cs-high", SPI_CS_HIGH),
+    ("spi-lsb-first", SPI_LSB_FIRST),
+    ("spi-3wire", SPI_3WIRE),
+];
+
+/// Clock and mode of a SPI device.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SpiConfig {
+    pub mode: u32, // SPI_* mode bits
+    pub max_speed_hz: Option<u32>,
+}
+
+impl SpiConfig {
+    /// Reads the configuration a device node asks for.
+    pub fn from_of_node(of_node: &Path) -> SpiConfig {
+        let mode = MODE_PROPERTIES
+            .iter()
+            .filter(|(property, _)| of_node.join(property).exists())
+            .fold(0, |mode, (_, bit)| mode | bit);
+        let max_speed_hz = fs::read(of_node.join("spi-max-frequency"))
+            .ok()
+            .and_then(|b| Some(u32::from_be_bytes(b.get(..4)?.try_into().ok()?)));
+        SpiConfig { mode, max_speed_hz }
+    }
+
+    /// Clock polarity and phase as SPI mode 0-3.
+    pub fn clock_mode(&self) -> u32 {
+        self.mode & (SPI_CPOL | SPI_CPHA)
+    }
+
+    /// Mode bits beyond the clock mode, e.g. ["cs-high", "3wire"].
+    pub fn flags(&self) -> Vec<&'static str> {
+        MODE_PROPERTIES
+            .iter()
+            .filter(|(_, bit)| *bit > SPI_CPOL && self.mode & bit != 0)
+            .map(|(property, _)| property.trim_start_matches("spi-"))
+            .collect()
+    }
+
+    fn annotate(&self, device: &mut TuxDevice) {
+        let attributes = &mut device.attributes;
+        attributes.insert("mode".to_string(), self.clock_mode().to_string());
+        let flags = self.flags();
+        if flags.is_empty() {
+            attributes.remove("mode_flags");
+        } else {
+            attributes.insert("mode_flags".to_string(), flags.join(","));
+        }
+        if let Some(hz) = self.max_speed_hz {
+            attributes.insert("max_speed_hz".to_string(), hz.to_string());
+        }
+    }
+}
+
+/// Reads the current configuration of /dev/spidevB.C.
+#[cfg(feature = "hardware")]
+pub fn read_spidev_config(bus: u32, cs: u16) -> Result<SpiConfig> {
+    use std::os::fd::AsRawFd;
+    // _IOR('k', 5, u32) and _IOR('k', 4, u32)
+    const SPI_IOC_RD_MODE32: u32 = 0x8004_6b05;
+    const SPI_IOC_RD_MAX_SPEED_HZ: u32 = 0x8004_6b04;
+
+    let path = format!("/dev/spidev{}.{}", bus, cs);
+    let file = fs::File::open(&path).map_err(|e| anyhow::anyhow!("{}: {}", path, e))?;
+    let read = |request: u32| -> Result<u32> {
+        let mut value: u32 = 0;
+        let rc = unsafe { libc::ioctl(file.as_raw_fd(), request as _, &mut value) };
+        if rc < 0 {
+            anyhow::bail!("{}: {}", path, std::io::Error::last_os_error());
+        }
+        Ok(value)
+    };
+    Ok(SpiConfig {
+        mode: read(SPI_IOC_RD_MODE32)?,
+        max_speed_hz: Some(read(SPI_IOC_RD_MAX_SPEED_HZ)?),
+    })
+}
+
+/// Lists SPI devices with their chip select, mode and clock, enriched with udev data.
+///
+/// Devices bound to spidev report what /dev/spidevB.C is currently set to, others what
+/// the device tree asks for.
+pub fn find_spi_devices() -> Result<Vec<TuxDevice>> {
+    let devices = find_spi_devices_in(Path::new("/sys"), Path::new("/run/udev/data"))?;
+    #[cfg(feature = "hardware")]
+    let devices = devices
+        .into_iter()
+        .map(|mut device| {
+            if let DeviceAddress::Spi { bus, cs } = device.address
+                && device.attributes.contains_key("spidev")
+                && let Ok(config) = read_spidev_config(bus, cs)
+            {
+                config.annotate(&mut device);
+            }
+            device
+        })
+        .collect();
+    Ok(devices)
+}
+
+/// Same as [`find_spi_devices`], with explicit sysfs and udev database roots; only the
+/// device tree is consulted.
+pub fn find_spi_devices_in(sys_root: &Path, udev_db: &Path) -> Result<Vec<TuxDevice>> {
+    let mut devices = Vec::new();
+    for entry in fs::read_dir(sys_root.join("bus/spi/devices"))? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(address) = DeviceAddress::parse(Subsystem::Spi, &name) else {
+            continue;
+        };
+        let dir = entry.path();
+        let mut device = TuxDevice::from_udev_in(&dir, udev_db, Subsystem::Spi, address);
+        SpiConfig::from_of_node(&dir.join("of_node")).annotate(&mut device);
+        let node = format!("spidev{}", name.trim_start_matches("spi"));
+        if dir.join("spidev").join(&node).exists() {
+            device
+                .attributes
+                .insert("spidev".to_string(), format!("/dev/{}", node));
+        }
+        devices.push(device);
+    }
+    devices.sort_by_key(|d| d.address.clone());
+    Ok(devices)
+}
+
+/// Builds one Board model bus per SPI controller ("spi0", ..) with its devices.
+pub fn audit_spi_buses() -> Result<Vec<TuxBus>> {
+    audit_spi_buses_in(Path::new("/sys"), Path::new("/run/udev/data"))
+}
+
+/// Same as [`audit_spi_buses`], with explicit sysfs and udev database roots.
+pub fn audit_spi_buses_in(sys_root: &Path, udev_db: &Path) -> Result<Vec<TuxBus>> {
+    let controllers = sys_root.join("class/spi_master");
+    let mut buses: Vec<(u32, TuxBus)> = Vec::new();
+    for entry in fs::read_dir(&controllers)? {
+        let name = entry?.file_name().to_string_lossy().to_string();
+        let Some(bus) = name.strip_prefix("spi").and_then(|n| n.parse::<u32>().ok()) else {
+            continue;
+        };
+        // The controller's device-tree node, e.g. "spi@fe610000"
+        let node = fs::read_link(controllers.join(&name).join("of_node"))
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()));
+        buses.push((
+            bus,
+            TuxBus {
+                subsystem: Subsystem::Spi,
+                id: name,
+                name: node.unwrap_or_default(),
+                devices: Vec::new(),
+                metadata: BTreeMap::new(),
+            },
+        ));
+    }
+    buses.sort_by_key(|(bus, _)| *bus);
+    for device in find_spi_devices_in(sys_root, udev_db)? {
+        let DeviceAddress::Spi { bus, .. } = device.address else {
+            continue;
+        };
+        if let Some((_, tux_bus)) = buses.iter_mut().find(|(b, _)| *b == bus) {
+            tux_bus.devices.push(device);
+        }
+    }
+    for (_, bus) in &mut buses {
+        let chip_selects: Vec<String> = bus
+            .devices
+            .iter()
+            .filter_map(|d| match d.address {
+                DeviceAddress::Spi { cs, .. } => Some(cs.to_string()),
+                _ => None,
+            })
+            .collect();
+        bus.metadata
+            .insert("chip_selects".to_string(), chip_selects.join(","));
+    }
+    Ok(buses.into_iter().map(|(_, bus)| bus).collect())
+}
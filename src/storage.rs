@@ -0,0 +1,376 @@
+use crate::manifest::Manifest;
+use anyhow::Result;
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+const MB: u64 = 1000 * 1000; // Storage sizes are decimal, as on the part's datasheet
+
+/// What kind of storage a block device is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    Emmc,
+    Sd,
+    Nvme,
+    Scsi, // SATA and USB mass storage
+    Virtio,
+    Virtual, // loop, ram, zram, device mapper, md and nbd devices
+    Other,
+}
+
+impl BlockKind {
+    /// Parses the manifest name: "emmc", "sd", "nvme", "scsi" or "virtio".
+    pub fn from_name(name: &str) -> Option<BlockKind> {
+        match name {
+            "emmc" => Some(BlockKind::Emmc),
+            "sd" => Some(BlockKind::Sd),
+            "nvme" => Some(BlockKind::Nvme),
+            "scsi" => Some(BlockKind::Scsi),
+            "virtio" => Some(BlockKind::Virtio),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BlockKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BlockKind::Emmc => "eMMC",
+            BlockKind::Sd => "SD card",
+            BlockKind::Nvme => "NVMe drive",
+            BlockKind::Scsi => "SCSI disk",
+            BlockKind::Virtio => "virtio disk",
+            BlockKind::Virtual => "virtual block device",
+            BlockKind::Other => "block device",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The identity fields of an MMC or SD card CID register.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cid {
+    pub manfid: u8,
+    pub oemid: u16,
+    pub product: String, // 6 characters on MMC, 5 on SD
+    pub revision: u8,    // PRV, e.g. 0x10 for 1.0
+    pub serial: u32,
+}
+
+impl Cid {
+    /// Decodes the 32 hex digits of sysfs `cid`; `sd` selects the SD card layout.
+    pub fn parse(hex: &str, sd: bool) -> Result<Cid> {
+        let hex = hex.trim();
+        let bad = || anyhow::anyhow!("invalid CID {:?}", hex);
+        if hex.len() != 32 || !hex.is_ascii() {
+            return Err(bad());
+        }
+        let bytes = (0..16)
+            .map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| bad()))
+            .collect::<Result<Vec<u8>>>()?;
+        let text = |range: std::ops::Range<usize>| {
+            String::from_utf8_lossy(&bytes[range])
+                .trim_end_matches(['\0', ' '])
+                .to_string()
+        };
+        let word = |at: usize| {
+            u32::from_be_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+        };
+        Ok(if sd {
+            Cid {
+                manfid: bytes[0],
+                oemid: u16::from_be_bytes([bytes[1], bytes[2]]),
+                product: text(3..8),
+                revision: bytes[8],
+                serial: word(9),
+            }
+        } else {
+            Cid {
+                manfid: bytes[0],
+                oemid: bytes[2] as u16,
+                product: text(3..9),
+                revision: bytes[9],
+                serial: word(10),
+            }
+        })
+    }
+
+    /// The manufacturer's name, for the IDs in common use.
+    pub fn manufacturer(&self, sd: bool) -> Option<&'static str> {
+        let known: &[(u8, &str)] = if sd {
+            &[
+                (0x02, "Kioxia"),
+                (0x03, "SanDisk"),
+                (0x1b, "Samsung"),
+                (0x27, "Phison"),
+                (0x74, "Transcend"),
+            ]
+        } else {
+            &[
+                (0x11, "Kioxia"),
+                (0x13, "Micron"),
+                (0x15, "Samsung"),
+                (0x45, "SanDisk"),
+                (0x70, "Kingston"),
+                (0x88, "Foresee"),
+                (0x90, "SK hynix"),
+                (0xfe, "Micron"),
+            ]
+        };
+        known
+            .iter()
+            .find(|(id, _)| *id == self.manfid)
+            .map(|(_, name)| *name)
+    }
+}
+
+/// A whole disk, from /sys/block; partitions and eMMC boot areas are left out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockDevice {
+    pub name: String, // e.g. "mmcblk0" or "nvme0n1"
+    pub kind: BlockKind,
+    pub size_bytes: u64,
+    pub removable: bool,
+    pub read_only: bool,
+    pub model: Option<String>, // NVMe and SCSI `model`, or the card's product name
+    pub cid: Option<Cid>,      // eMMC and SD cards
+    pub csd: Option<String>,   // Raw hex, for the record
+}
+
+impl BlockDevice {
+    /// The CSD_STRUCTURE field: 0 to 3, the version of the register layout.
+    pub fn csd_structure(&self) -> Option<u8> {
+        let first = self.csd.as_deref()?.get(..2)?;
+        u8::from_str_radix(first, 16).ok().map(|b| b >> 6)
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn is_boot_area(name: &str) -> bool {
+    let Some(rest) = name.strip_prefix("mmcblk") else {
+        return false;
+    };
+    let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+    rest.starts_with("boot") || rest.starts_with("rpmb")
+}
+
+fn kind_of(name: &str, device: &Path) -> BlockKind {
+    let virtual_prefixes = ["loop", "ram", "zram", "dm-", "md", "nbd"];
+    if name.starts_with("mmcblk") {
+        match read_trimmed(&device.join("type")).as_deref() {
+            Some("MMC") => BlockKind::Emmc,
+            Some("SD") => BlockKind::Sd,
+            _ => BlockKind::Other,
+        }
+    } else if name.starts_with("nvme") {
+        BlockKind::Nvme
+    } else if name.starts_with("sd") {
+        BlockKind::Scsi
+    } else if name.starts_with("vd") {
+        BlockKind::Virtio
+    } else if virtual_prefixes.iter().any(|p| name.starts_with(p)) {
+        BlockKind::Virtual
+    } else {
+        BlockKind::Other
+    }
+}
+
+/// Lists whole disks, by name.
+pub fn list_block_devices() -> Result<Vec<BlockDevice>> {
+    list_block_devices_in(Path::new("/sys"))
+}
+
+/// Same as [`list_block_devices`], with an explicit sysfs root.
+pub fn list_block_devices_in(sys_root: &Path) -> Result<Vec<BlockDevice>> {
+    let mut devices = Vec::new();
+    let Ok(entries) = fs::read_dir(sys_root.join("block")) else {
+        return Ok(devices);
+    };
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if is_boot_area(&name) {
+            continue;
+        }
+        let dir = entry.path();
+        // For NVMe namespaces `device` is the controller, which has the model
+        let device = dir.join("device");
+        let kind = kind_of(&name, &device);
+        let card = matches!(kind, BlockKind::Emmc | BlockKind::Sd);
+        let cid = match read_trimmed(&device.join("cid")) {
+            Some(hex) if card => Some(
+                Cid::parse(&hex, kind == BlockKind::Sd)
+                    .map_err(|e| anyhow::anyhow!("{}: {}", device.join("cid").display(), e))?,
+            ),
+            _ => None,
+        };
+        let flag = |file: &str| read_trimmed(&dir.join(file)).is_some_and(|v| v == "1");
+        devices.push(BlockDevice {
+            kind,
+            size_bytes: read_trimmed(&dir.join("size"))
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(0)
+                * 512,
+            removable: flag("removable"),
+            read_only: flag("ro"),
+            model: read_trimmed(&device.join("model"))
+                .or_else(|| cid.as_ref().map(|c| c.product.clone())),
+            cid,
+            csd: card.then(|| read_trimmed(&device.join("csd"))).flatten(),
+            name,
+        });
+    }
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(devices)
+}
+
+/// A disk the board must have, and the BOM part it must be.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedDisk {
+    pub kind: BlockKind,
+    pub name: Option<String>, // e.g. "mmcblk0", when the kind alone is ambiguous
+    pub min_size_mb: Option<u64>,
+    pub max_size_mb: Option<u64>,
+    pub manfid: Option<u8>,      // eMMC and SD only
+    pub product: Option<String>, // CID product name, or the NVMe/SCSI model
+}
+
+impl ExpectedDisk {
+    /// Every mismatch between `device` and this part.
+    fn problems(&self, device: &BlockDevice) -> Vec<String> {
+        let mut problems = Vec::new();
+        let size_mb = device.size_bytes / MB;
+        if let Some(min) = self.min_size_mb
+            && size_mb < min
+        {
+            problems.push(format!(
+                "{} is {} MB, below {} MB",
+                device.name, size_mb, min
+            ));
+        }
+        if let Some(max) = self.max_size_mb
+            && size_mb > max
+        {
+            problems.push(format!(
+                "{} is {} MB, above {} MB",
+                device.name, size_mb, max
+            ));
+        }
+        if let Some(manfid) = self.manfid {
+            match &device.cid {
+                None => problems.push(format!("{}: no CID to check the manufacturer", device.name)),
+                Some(cid) if cid.manfid != manfid => {
+                    let sd = device.kind == BlockKind::Sd;
+                    let name = cid
+                        .manufacturer(sd)
+                        .map(|n| format!(" ({})", n))
+                        .unwrap_or_default();
+                    problems.push(format!(
+                        "{}: manufacturer 0x{:02x}{} (expected 0x{:02x})",
+                        device.name, cid.manfid, name, manfid
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+        if let Some(product) = &self.product
+            && device.model.as_deref().map(str::trim) != Some(product.as_str())
+        {
+            problems.push(format!(
+                "{}: part {} (expected {})",
+                device.name,
+                device.model.as_deref().unwrap_or("unknown"),
+                product
+            ));
+        }
+        problems
+    }
+}
+
+/// Expectations of the `[storage]` manifest section.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedStorage {
+    pub disks: Vec<ExpectedDisk>,
+}
+
+impl ExpectedStorage {
+    pub fn from_manifest(manifest: &Manifest) -> Result<ExpectedStorage> {
+        let Some(section) = manifest.sections.get("storage") else {
+            return Ok(ExpectedStorage::default());
+        };
+        let disks = match &section["disks"] {
+            Value::Null => Vec::new(),
+            Value::Array(disks) => disks
+                .iter()
+                .enumerate()
+                .map(|(i, disk)| {
+                    let number = |key: &str| -> Result<Option<u64>> {
+                        match &disk[key] {
+                            Value::Null => Ok(None),
+                            value => value.as_u64().map(Some).ok_or_else(|| {
+                                anyhow::anyhow!(
+                                    "storage.disks {}: `{}` must be a positive integer",
+                                    i,
+                                    key
+                                )
+                            }),
+                        }
+                    };
+                    let kind = disk["kind"].as_str().unwrap_or_default();
+                    let manfid = number("manfid")?
+                        .map(|m| {
+                            u8::try_from(m).map_err(|_| {
+                                anyhow::anyhow!("storage.disks {}: `manfid` must fit a byte", i)
+                            })
+                        })
+                        .transpose()?;
+                    Ok(ExpectedDisk {
+                        kind: BlockKind::from_name(kind).ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "storage.disks {}: `kind` must be emmc, sd, nvme, scsi or virtio",
+                                i
+                            )
+                        })?,
+                        name: disk["name"].as_str().map(str::to_string),
+                        min_size_mb: number("min_size_mb")?,
+                        max_size_mb: number("max_size_mb")?,
+                        manfid,
+                        product: disk["product"].as_str().map(str::to_string),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => anyhow::bail!("storage.disks must be a list"),
+        };
+        Ok(ExpectedStorage { disks })
+    }
+}
+
+/// Returns human-readable descriptions of every problem against the expectations.
+///
+/// A part that matches none of the disks of its kind is reported against the first of them:
+/// with one eMMC per board that is the one fitted.
+pub fn validate_storage(devices: &[BlockDevice], expected: &ExpectedStorage) -> Vec<String> {
+    let mut problems = Vec::new();
+    for want in &expected.disks {
+        let candidates: Vec<&BlockDevice> = devices
+            .iter()
+            .filter(|d| d.kind == want.kind)
+            .filter(|d| want.name.as_ref().is_none_or(|n| &d.name == n))
+            .collect();
+        let Some(first) = candidates.first() else {
+            match &want.name {
+                Some(name) => problems.push(format!("{} {} not found", want.kind, name)),
+                None => problems.push(format!("no {} found", want.kind)),
+            }
+            continue;
+        };
+        if candidates.iter().all(|d| !want.problems(d).is_empty()) {
+            problems.extend(want.problems(first));
+        }
+    }
+    problems
+}
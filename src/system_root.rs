@@ -0,0 +1,64 @@
+use crate::discovery::DiscoveryContext;
+use std::path::{Path, PathBuf};
+
+/// Where a run finds the system it validates: the live one, a mounted image of another
+/// unit, or a fake tree such as [`crate::testing::FakeSysfs`].
+///
+/// Modules take the directories they read as `*_in` arguments; this is the one place that
+/// knows how they hang together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemRoot {
+    pub fs: PathBuf,      // Root of the regular filesystem, for /etc, /usr and /var files
+    pub sys: PathBuf,     // sysfs
+    pub proc: PathBuf,    // procfs
+    pub dev: PathBuf,     // Device nodes
+    pub udev_db: PathBuf, // udev database, /run/udev/data
+}
+
+impl Default for SystemRoot {
+    fn default() -> Self {
+        SystemRoot::under(Path::new("/"))
+    }
+}
+
+impl SystemRoot {
+    /// The live system.
+    pub fn live() -> SystemRoot {
+        SystemRoot::default()
+    }
+
+    /// Every directory at its usual place below `root`, e.g. a copy of a unit's tree.
+    pub fn under(root: &Path) -> SystemRoot {
+        SystemRoot {
+            fs: root.to_path_buf(),
+            sys: root.join("sys"),
+            proc: root.join("proc"),
+            dev: root.join("dev"),
+            udev_db: root.join("run/udev/data"),
+        }
+    }
+
+    /// A regular file by its absolute path on the unit, e.g. "/etc/os-release".
+    pub fn file(&self, path: &str) -> PathBuf {
+        self.fs.join(path.trim_start_matches('/'))
+    }
+
+    /// The os-release file: /etc/os-release, or /usr/lib/os-release if there is none.
+    pub fn os_release(&self) -> PathBuf {
+        let etc = self.file("/etc/os-release");
+        if etc.exists() {
+            etc
+        } else {
+            self.file("/usr/lib/os-release")
+        }
+    }
+
+    /// A discovery context reading this system, without hardware probes.
+    pub fn discovery_context(&self) -> DiscoveryContext {
+        DiscoveryContext {
+            sys_root: self.sys.clone(),
+            udev_db: self.udev_db.clone(),
+            ..Default::default()
+        }
+    }
+}
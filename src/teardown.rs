@@ -0,0 +1,245 @@
+use crate::safety::{self, Access};
+use anyhow::Result;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::{Duration, Instant};
+
+/// Set once the run is cancelled, e.g. by SIGINT; every guarded check then winds down.
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// How often a waiting runner looks for cancellation.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+type Undo = Box<dyn FnOnce() -> Result<()> + Send>;
+
+#[derive(Default)]
+struct State {
+    actions: Vec<(String, Undo)>,
+    done: bool, // Torn down; later registrations are undone at once
+}
+
+/// Cleanup actions registered by an intrusive check, run last-registered first.
+///
+/// Clones share the same actions, so the check's thread and the runner see one list.
+#[derive(Clone, Default)]
+pub struct Teardown {
+    state: Arc<Mutex<State>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+fn run_undo(description: &str, undo: Undo) -> Option<String> {
+    match panic::catch_unwind(AssertUnwindSafe(undo)) {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(format!("{}: {:#}", description, e)),
+        Err(_) => Some(format!("{}: panicked", description)),
+    }
+}
+
+impl Teardown {
+    pub fn new() -> Teardown {
+        Teardown::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Registers how to undo a change; register it before making the change.
+    pub fn register(&self, description: &str, undo: impl FnOnce() -> Result<()> + Send + 'static) {
+        let mut state = self.lock();
+        if state.done {
+            drop(state);
+            if let Some(failure) = run_undo(description, Box::new(undo)) {
+                tracing::error!(%failure, "teardown of a late registration failed");
+            }
+            return;
+        }
+        state
+            .actions
+            .push((description.to_string(), Box::new(undo)));
+    }
+
+    /// Descriptions of the actions still to run, in the order they will.
+    pub fn pending(&self) -> Vec<String> {
+        let state = self.lock();
+        state.actions.iter().rev().map(|(d, _)| d.clone()).collect()
+    }
+
+    /// Whether the runner gave up on the check; long checks poll this between steps.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst) || CANCELLED.load(Ordering::SeqCst)
+    }
+
+    /// Runs every action once, newest first. A failing or panicking action doesn't stop the
+    /// rest; returns their failures.
+    pub fn run(&self) -> Vec<String> {
+        let actions = {
+            let mut state = self.lock();
+            state.done = true;
+            std::mem::take(&mut state.actions)
+        };
+        actions
+            .into_iter()
+            .rev()
+            .filter_map(|(description, undo)| run_undo(&description, undo))
+            .collect()
+    }
+
+    /// Writes a sysfs attribute, registering a write of its current value back, e.g. for port
+    /// power or regulator margining.
+    pub fn write_attribute(&self, path: &Path, value: &str) -> Result<()> {
+        safety::require(Access::Write, &format!("Writing {}", path.display()))?;
+        let original =
+            fs::read_to_string(path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+        let restore = path.to_path_buf();
+        self.register(&format!("restore {}", path.display()), move || {
+            fs::write(&restore, original.trim_end())
+                .map_err(|e| anyhow::anyhow!("{}: {}", restore.display(), e))
+        });
+        fs::write(path, value).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+    }
+
+    /// Unbinds a device from its driver, registering the bind back; `device` is its sysfs
+    /// directory, e.g. /sys/bus/i2c/devices/1-0050.
+    pub fn unbind_driver(&self, device: &Path) -> Result<()> {
+        safety::require(Access::Write, &format!("Unbinding {}", device.display()))?;
+        let driver = fs::canonicalize(device.join("driver"))
+            .map_err(|e| anyhow::anyhow!("{}: not bound: {}", device.display(), e))?;
+        let Some(name) = device.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            anyhow::bail!("{}: not a device directory", device.display());
+        };
+        let bind = driver.join("bind");
+        let description = format!("bind {} to {}", name, driver.display());
+        let device_name = name.clone();
+        self.register(&description, move || {
+            fs::write(&bind, &device_name).map_err(|e| anyhow::anyhow!("{}: {}", bind.display(), e))
+        });
+        let unbind = driver.join("unbind");
+        fs::write(&unbind, &name).map_err(|e| anyhow::anyhow!("{}: {}", unbind.display(), e))
+    }
+
+    /// Instantiates an I2C device through `new_device` of an adapter, e.g.
+    /// /sys/bus/i2c/devices/i2c-1, registering its deletion.
+    pub fn new_i2c_device(&self, adapter: &Path, name: &str, addr: u16) -> Result<()> {
+        safety::require(
+            Access::Write,
+            &format!("Instantiating {} on {}", name, adapter.display()),
+        )?;
+        let delete = adapter.join("delete_device");
+        self.register(&format!("delete {} at 0x{:02x}", name, addr), move || {
+            fs::write(&delete, format!("0x{:02x}", addr))
+                .map_err(|e| anyhow::anyhow!("{}: {}", delete.display(), e))
+        });
+        let new_device = adapter.join("new_device");
+        fs::write(&new_device, format!("{} 0x{:02x}", name, addr))
+            .map_err(|e| anyhow::anyhow!("{}: {}", new_device.display(), e))
+    }
+}
+
+/// Outcome of [`run_guarded`].
+#[derive(Debug)]
+pub struct GuardedRun<T> {
+    pub result: Result<T>,     // Err on failure, timeout, panic or cancellation
+    pub teardown: Vec<String>, // Actions that failed; the DUT may not be in its original state
+}
+
+impl<T> GuardedRun<T> {
+    /// Whether teardown restored everything the check changed.
+    pub fn is_clean(&self) -> bool {
+        self.teardown.is_empty()
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Runs an intrusive check on its own thread and then its teardown, however the check
+/// ends: success, error, panic, `timeout` or cancellation.
+///
+/// A check that overruns is abandoned, not killed: it sees [`Teardown::is_cancelled`], and
+/// anything it registers after teardown ran is undone at once.
+pub fn run_guarded<T: Send + 'static>(
+    id: &str,
+    timeout: Option<Duration>,
+    check: impl FnOnce(&Teardown) -> Result<T> + Send + 'static,
+) -> GuardedRun<T> {
+    let teardown = Teardown::new();
+    if CANCELLED.load(Ordering::SeqCst) {
+        return GuardedRun {
+            result: Err(anyhow::anyhow!("{}: cancelled before start", id)),
+            teardown: Vec::new(),
+        };
+    }
+    let (tx, rx) = mpsc::channel();
+    let handle = {
+        let teardown = teardown.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(check(&teardown));
+        })
+    };
+
+    let deadline = timeout.map(|t| Instant::now() + t);
+    let result = loop {
+        let wait = match deadline {
+            Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                Some(remaining) => remaining.min(POLL_INTERVAL),
+                None => {
+                    break Err(anyhow::anyhow!(
+                        "{}: timed out after {:?}",
+                        id,
+                        timeout.unwrap_or_default()
+                    ));
+                }
+            },
+            None => POLL_INTERVAL,
+        };
+        match rx.recv_timeout(wait) {
+            Ok(result) => break result,
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                // The check panicked before sending
+                let message = handle
+                    .join()
+                    .err()
+                    .map(|p| panic_message(p.as_ref()))
+                    .unwrap_or_default();
+                break Err(anyhow::anyhow!("{}: panicked: {}", id, message));
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) if CANCELLED.load(Ordering::SeqCst) => {
+                break Err(anyhow::anyhow!("{}: cancelled", id));
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+        }
+    };
+    teardown.cancelled.store(true, Ordering::SeqCst);
+    GuardedRun {
+        result,
+        teardown: teardown.run(),
+    }
+}
+
+/// Cancels every guarded check of this process; their teardowns still run.
+pub fn cancel_all() {
+    CANCELLED.store(true, Ordering::SeqCst);
+}
+
+/// Turns SIGINT and SIGTERM into [`cancel_all`], so an operator's Ctrl-C or a station
+/// timeout leaves the DUT torn down rather than half-configured.
+#[cfg(feature = "hardware")]
+pub fn cancel_on_signals() {
+    extern "C" fn handle(_: libc::c_int) {
+        CANCELLED.store(true, Ordering::SeqCst);
+    }
+    let handler = handle as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    unsafe {
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    }
+}
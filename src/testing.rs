@@ -0,0 +1,113 @@
+use crate::system_root::SystemRoot;
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+/// Builds a fake system tree in a temporary directory, removed on drop, so validation
+/// logic can be tested off-target.
+///
+/// Paths are relative to the tree, e.g. "sys/bus/i2c/devices/1-0050/name". The builder
+/// panics on I/O errors: it is for tests.
+pub struct FakeSysfs {
+    dir: PathBuf,
+}
+
+impl FakeSysfs {
+    pub fn new() -> FakeSysfs {
+        let dir = std::env::temp_dir().join(format!(
+            "tux-fake-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap_or_else(|e| panic!("{}: {}", dir.display(), e));
+        FakeSysfs { dir }
+    }
+
+    /// Root directory of the tree.
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The tree as a [`SystemRoot`].
+    pub fn root(&self) -> SystemRoot {
+        SystemRoot::under(&self.dir)
+    }
+
+    /// Creates a directory and its parents.
+    pub fn dir(self, path: &str) -> FakeSysfs {
+        let dir = self.dir.join(path);
+        fs::create_dir_all(&dir).unwrap_or_else(|e| panic!("{}: {}", dir.display(), e));
+        self
+    }
+
+    /// Writes a file, creating its parents.
+    pub fn file(self, path: &str, contents: &str) -> FakeSysfs {
+        let file = self.dir.join(path);
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent).unwrap_or_else(|e| panic!("{}: {}", parent.display(), e));
+        }
+        fs::write(&file, contents).unwrap_or_else(|e| panic!("{}: {}", file.display(), e));
+        self
+    }
+
+    /// Creates a symlink at `path` pointing to `target`, relative to the link as in sysfs.
+    pub fn link(self, path: &str, target: &str) -> FakeSysfs {
+        let link = self.dir.join(path);
+        if let Some(parent) = link.parent() {
+            fs::create_dir_all(parent).unwrap_or_else(|e| panic!("{}: {}", parent.display(), e));
+        }
+        let _ = fs::remove_file(&link);
+        symlink(target, &link).unwrap_or_else(|e| panic!("{}: {}", link.display(), e));
+        self
+    }
+
+    pub fn os_release(self, contents: &str) -> FakeSysfs {
+        self.file("etc/os-release", contents)
+    }
+
+    /// An I2C adapter, e.g. `i2c_adapter(1, "rk3x-i2c")`.
+    pub fn i2c_adapter(self, bus: u32, name: &str) -> FakeSysfs {
+        self.file(
+            &format!("sys/bus/i2c/devices/i2c-{}/name", bus),
+            &format!("{}\n", name),
+        )
+    }
+
+    /// An I2C client as the kernel lists it, bound to `driver` if given.
+    pub fn i2c_device(self, bus: u32, addr: u16, name: &str, driver: Option<&str>) -> FakeSysfs {
+        let device = format!("sys/bus/i2c/devices/{}-{:04x}", bus, addr);
+        let mut uevent = format!("MODALIAS=i2c:{}\n", name);
+        let mut fake = self;
+        if let Some(driver) = driver {
+            uevent = format!("DRIVER={}\n{}", driver, uevent);
+            fake = fake.dir(&format!("sys/bus/i2c/drivers/{}", driver)).link(
+                &format!("{}/driver", device),
+                &format!("../../drivers/{}", driver),
+            );
+        }
+        fake.file(&format!("{}/name", device), &format!("{}\n", name))
+            .file(&format!("{}/uevent", device), &uevent)
+    }
+
+    /// A udev database entry, e.g. `udev_entry("+i2c:1-0050", &["ID_PATH=platform-i2c"])`.
+    pub fn udev_entry(self, db_name: &str, properties: &[&str]) -> FakeSysfs {
+        let data: String = properties.iter().map(|p| format!("E:{}\n", p)).collect();
+        self.file(&format!("run/udev/data/{}", db_name), &data)
+    }
+}
+
+impl Default for FakeSysfs {
+    fn default() -> Self {
+        FakeSysfs::new()
+    }
+}
+
+impl Drop for FakeSysfs {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
@@ -0,0 +1,238 @@
+use crate::manifest::Manifest;
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A thermal zone, e.g. /sys/class/thermal/thermal_zone0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThermalZone {
+    pub name: String, // e.g. "thermal_zone0"; numbering changes between kernels
+    pub kind: String, // The `type` attribute, e.g. "cpu-thermal"
+    pub temperature: Option<f64>, // °C; None if the sensor can't be read
+    pub enabled: bool, // `mode`; zones without one are always on
+    pub trips: Vec<(String, f64)>, // Trip point type and °C, e.g. ("critical", 110.0)
+}
+
+/// A cooling device, e.g. /sys/class/thermal/cooling_device0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoolingDevice {
+    pub name: String, // e.g. "cooling_device0"
+    pub kind: String, // The `type` attribute, e.g. "pwm-fan" or "cpufreq-cpu0"
+    pub cur_state: u64,
+    pub max_state: u64,
+}
+
+impl CoolingDevice {
+    /// Cooling as hard as it can, e.g. a fan at full speed or a CPU at its lowest frequency.
+    pub fn at_max(&self) -> bool {
+        self.max_state > 0 && self.cur_state >= self.max_state
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_celsius(path: &Path) -> Option<f64> {
+    read_trimmed(path)?
+        .parse::<i64>()
+        .ok()
+        .map(|m| m as f64 / 1000.0)
+}
+
+fn numbered_dirs(class: &Path, prefix: &str) -> Result<Vec<(u32, PathBuf)>> {
+    let mut dirs = Vec::new();
+    let Ok(entries) = fs::read_dir(class) else {
+        return Ok(dirs);
+    };
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(n) = name.strip_prefix(prefix).and_then(|n| n.parse().ok()) {
+            dirs.push((n, entry.path()));
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Lists thermal zones, in zone number order.
+pub fn list_zones() -> Result<Vec<ThermalZone>> {
+    list_zones_in(Path::new("/sys"))
+}
+
+/// Same as [`list_zones`], with an explicit sysfs root.
+pub fn list_zones_in(sys_root: &Path) -> Result<Vec<ThermalZone>> {
+    let mut zones = Vec::new();
+    for (n, dir) in numbered_dirs(&sys_root.join("class/thermal"), "thermal_zone")? {
+        let mut trips = Vec::new();
+        for i in 0.. {
+            let (Some(kind), Some(temp)) = (
+                read_trimmed(&dir.join(format!("trip_point_{}_type", i))),
+                read_celsius(&dir.join(format!("trip_point_{}_temp", i))),
+            ) else {
+                break;
+            };
+            trips.push((kind, temp));
+        }
+        zones.push(ThermalZone {
+            name: format!("thermal_zone{}", n),
+            kind: read_trimmed(&dir.join("type")).unwrap_or_default(),
+            temperature: read_celsius(&dir.join("temp")),
+            enabled: read_trimmed(&dir.join("mode")).is_none_or(|m| m != "disabled"),
+            trips,
+        });
+    }
+    Ok(zones)
+}
+
+/// Lists cooling devices, in device number order.
+pub fn list_cooling_devices() -> Result<Vec<CoolingDevice>> {
+    list_cooling_devices_in(Path::new("/sys"))
+}
+
+/// Same as [`list_cooling_devices`], with an explicit sysfs root.
+pub fn list_cooling_devices_in(sys_root: &Path) -> Result<Vec<CoolingDevice>> {
+    let number = |path: &Path| read_trimmed(path).and_then(|s| s.parse().ok()).unwrap_or(0);
+    let dirs = numbered_dirs(&sys_root.join("class/thermal"), "cooling_device")?;
+    Ok(dirs
+        .into_iter()
+        .map(|(n, dir)| CoolingDevice {
+            name: format!("cooling_device{}", n),
+            kind: read_trimmed(&dir.join("type")).unwrap_or_default(),
+            cur_state: number(&dir.join("cur_state")),
+            max_state: number(&dir.join("max_state")),
+        })
+        .collect())
+}
+
+/// A zone the board must have, by type. Bounds are in °C and inclusive.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedZone {
+    pub kind: String,
+    pub min_temp: Option<f64>,
+    pub max_temp: Option<f64>,
+}
+
+/// A cooling device the board must have, by type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedCooling {
+    pub kind: String,
+    pub allow_max: bool, // At max state is fine, e.g. a fan wired to always run
+}
+
+/// Expectations of the `[thermal]` manifest section.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedThermal {
+    pub zones: Vec<ExpectedZone>,
+    pub cooling: Vec<ExpectedCooling>,
+}
+
+impl ExpectedThermal {
+    pub fn from_manifest(manifest: &Manifest) -> Result<ExpectedThermal> {
+        let Some(section) = manifest.sections.get("thermal") else {
+            return Ok(ExpectedThermal::default());
+        };
+        let list = |key: &str| -> Result<Vec<Value>> {
+            match &section[key] {
+                Value::Null => Ok(Vec::new()),
+                Value::Array(items) => Ok(items.clone()),
+                _ => anyhow::bail!("thermal.{} must be a list", key),
+            }
+        };
+        let kind = |key: &str, i: usize, item: &Value| -> Result<String> {
+            item["type"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow::anyhow!("thermal.{} {}: missing `type`", key, i))
+        };
+        let zones = list("zones")?
+            .iter()
+            .enumerate()
+            .map(|(i, zone)| {
+                let temp = |key: &str| -> Result<Option<f64>> {
+                    match &zone[key] {
+                        Value::Null => Ok(None),
+                        value => value.as_f64().map(Some).ok_or_else(|| {
+                            anyhow::anyhow!("thermal.zones {}: `{}` must be a number", i, key)
+                        }),
+                    }
+                };
+                Ok(ExpectedZone {
+                    kind: kind("zones", i, zone)?,
+                    min_temp: temp("min_temp")?,
+                    max_temp: temp("max_temp")?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let cooling = list("cooling")?
+            .iter()
+            .enumerate()
+            .map(|(i, device)| {
+                Ok(ExpectedCooling {
+                    kind: kind("cooling", i, device)?,
+                    allow_max: device["allow_max"].as_bool().unwrap_or(false),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ExpectedThermal { zones, cooling })
+    }
+}
+
+/// Returns human-readable descriptions of every problem against the expectations.
+///
+/// A cooling device pinned at its max state is a problem even when temperatures look
+/// fine: it usually means a failed fan tachometer or a runaway governor holding the CPUs
+/// at their lowest frequency.
+pub fn validate_thermal(
+    zones: &[ThermalZone],
+    cooling: &[CoolingDevice],
+    expected: &ExpectedThermal,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    for want in &expected.zones {
+        let matching: Vec<&ThermalZone> = zones.iter().filter(|z| z.kind == want.kind).collect();
+        if matching.is_empty() {
+            problems.push(format!("thermal zone {} not found", want.kind));
+        }
+        for zone in matching {
+            if !zone.enabled {
+                problems.push(format!("{} ({}) is disabled", zone.kind, zone.name));
+            }
+            let Some(temp) = zone.temperature else {
+                problems.push(format!(
+                    "{} ({}): temperature unreadable",
+                    zone.kind, zone.name
+                ));
+                continue;
+            };
+            if let Some(min) = want.min_temp
+                && temp < min
+            {
+                problems.push(format!("{} at {} °C, below {} °C", zone.kind, temp, min));
+            }
+            if let Some(max) = want.max_temp
+                && temp > max
+            {
+                problems.push(format!("{} at {} °C, above {} °C", zone.kind, temp, max));
+            }
+        }
+    }
+    for want in &expected.cooling {
+        let matching: Vec<&CoolingDevice> =
+            cooling.iter().filter(|c| c.kind == want.kind).collect();
+        if matching.is_empty() {
+            problems.push(format!("cooling device {} not registered", want.kind));
+        }
+        for device in matching {
+            if !want.allow_max && device.at_max() {
+                problems.push(format!(
+                    "{} ({}) stuck at max state {}",
+                    device.kind, device.name, device.max_state
+                ));
+            }
+        }
+    }
+    problems
+}
@@ -0,0 +1,207 @@
+use crate::device::{Board, TuxDevice};
+use crate::manifest::ManifestFinding;
+use serde_json::{Value, json};
+
+/// Status a node is colored by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeStatus {
+    Ok,       // Bound to a driver
+    Unbound,  // Declared to the kernel, no driver
+    Deferred, // Its driver waits on a dependency
+    Ghost,    // Only responds to a hardware probe
+    Failed,   // Has a manifest finding
+    Neutral,  // SoC and buses
+}
+
+impl NodeStatus {
+    pub fn color(self) -> &'static str {
+        match self {
+            NodeStatus::Ok => "palegreen",
+            NodeStatus::Unbound => "orange",
+            NodeStatus::Deferred => "gold",
+            NodeStatus::Ghost => "lightgrey",
+            NodeStatus::Failed => "tomato",
+            NodeStatus::Neutral => "white",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            NodeStatus::Ok => "ok",
+            NodeStatus::Unbound => "unbound",
+            NodeStatus::Deferred => "deferred",
+            NodeStatus::Ghost => "ghost",
+            NodeStatus::Failed => "failed",
+            NodeStatus::Neutral => "neutral",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub id: String, // "soc", bus ID ("i2c-1") or "<subsystem>:<address>"
+    pub label: String,
+    pub kind: &'static str, // "soc", "bus", "mux" or "device"
+    pub status: NodeStatus,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub relation: String, // "bus", "device", "channel" or a Board link relation
+}
+
+/// The board as a graph: SoC -> buses -> (muxes ->) devices, plus device links.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TopologyGraph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+fn device_node_id(device: &TuxDevice) -> String {
+    format!("{}:{}", device.subsystem, device.address)
+}
+
+impl TopologyGraph {
+    /// Builds the graph; devices with a finding are marked failed.
+    pub fn from_board(board: &Board, soc_label: &str, findings: &[ManifestFinding]) -> Self {
+        let mut graph = TopologyGraph::default();
+        graph.nodes.push(Node {
+            id: "soc".to_string(),
+            label: soc_label.to_string(),
+            kind: "soc",
+            status: NodeStatus::Neutral,
+        });
+        let muxes: Vec<&str> = board
+            .buses
+            .iter()
+            .filter_map(|b| b.metadata.get("mux_device").map(|m| m.as_str()))
+            .collect();
+
+        for bus in &board.buses {
+            let label = if bus.name.is_empty() {
+                bus.id.clone()
+            } else {
+                format!("{}\n{}", bus.id, bus.name)
+            };
+            graph.nodes.push(Node {
+                id: bus.id.clone(),
+                label,
+                kind: "bus",
+                status: NodeStatus::Neutral,
+            });
+            // A muxed adapter hangs off its mux device, not the SoC
+            let (parent, relation) = match bus
+                .metadata
+                .get("mux_device")
+                .and_then(|m| board.devices().find(|d| &d.address.to_string() == m))
+            {
+                Some(mux) => (device_node_id(mux), "channel"),
+                None => ("soc".to_string(), "bus"),
+            };
+            graph.edges.push(Edge {
+                from: parent,
+                to: bus.id.clone(),
+                relation: relation.to_string(),
+            });
+
+            for device in &bus.devices {
+                let status = if findings.iter().any(|f| f.address == device.address) {
+                    NodeStatus::Failed
+                } else if device.is_bound() {
+                    NodeStatus::Ok
+                } else if device.probe_deferred {
+                    NodeStatus::Deferred
+                } else if device.sysfs_path.is_some() {
+                    NodeStatus::Unbound
+                } else {
+                    NodeStatus::Ghost
+                };
+                let address = device.address.to_string();
+                graph.nodes.push(Node {
+                    id: device_node_id(device),
+                    label: format!("{}\n{}", address, device.name),
+                    kind: if muxes.contains(&address.as_str()) {
+                        "mux"
+                    } else {
+                        "device"
+                    },
+                    status,
+                });
+                graph.edges.push(Edge {
+                    from: bus.id.clone(),
+                    to: device_node_id(device),
+                    relation: "device".to_string(),
+                });
+            }
+        }
+
+        for link in &board.links {
+            let (Some(from), Some(to)) =
+                (board.find_device(&link.from), board.find_device(&link.to))
+            else {
+                continue;
+            };
+            graph.edges.push(Edge {
+                from: device_node_id(from),
+                to: device_node_id(to),
+                relation: link.relation.clone(),
+            });
+        }
+        graph
+    }
+
+    /// Graphviz DOT, e.g. for `dot -Tsvg`.
+    pub fn to_dot(&self) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('"', "\\\"").replace('\n', "\\n"));
+        let mut out = String::from("digraph board {\n    rankdir=LR;\n    node [style=filled];\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                "soc" => "box3d",
+                "bus" => "box",
+                "mux" => "trapezium",
+                _ => "ellipse",
+            };
+            out.push_str(&format!(
+                "    {} [label={}, shape={}, fillcolor={}];\n",
+                quote(&node.id),
+                quote(&node.label),
+                shape,
+                node.status.color()
+            ));
+        }
+        for edge in &self.edges {
+            let style = match edge.relation.as_str() {
+                "bus" | "device" | "channel" => String::new(),
+                relation => format!(" [label={}, style=dashed]", quote(relation)),
+            };
+            out.push_str(&format!(
+                "    {} -> {}{};\n",
+                quote(&edge.from),
+                quote(&edge.to),
+                style
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// JSON graph: `{"nodes": [..], "edges": [..]}`.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "nodes": self.nodes.iter().map(|n| json!({
+                "id": n.id,
+                "label": n.label,
+                "kind": n.kind,
+                "status": n.status.name(),
+                "color": n.status.color(),
+            })).collect::<Vec<_>>(),
+            "edges": self.edges.iter().map(|e| json!({
+                "from": e.from,
+                "to": e.to,
+                "relation": e.relation,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
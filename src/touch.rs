@@ -0,0 +1,234 @@
+use crate::device::{Board, DeviceAddress, TuxDevice};
+use crate::safety::{self, Access};
+use anyhow::Result;
+use i2cdev::core::I2CDevice;
+use i2cdev::linux::LinuxI2CDevice;
+use nix::poll::{PollFd, PollFlags, poll};
+use std::fs::{self, File};
+use std::io::Read;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const EV_KEY: u16 = 0x01;
+const EV_ABS: u16 = 0x03;
+const BTN_TOUCH: u16 = 0x14a;
+
+/// Touch controller families we know how to query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchFamily {
+    Goodix,
+    EdtFt5x06,
+}
+
+impl TouchFamily {
+    /// Identifies the family from the device name or bound driver.
+    pub fn detect(device: &TuxDevice) -> Option<TouchFamily> {
+        let names = [Some(device.name.as_str()), device.driver.as_deref()];
+        let is = |candidates: &[&str]| {
+            names
+                .iter()
+                .flatten()
+                .any(|n| candidates.iter().any(|c| n.eq_ignore_ascii_case(c)))
+        };
+        if is(&[
+            "goodix",
+            "goodix-ts",
+            "gt911",
+            "gt9110",
+            "gt912",
+            "gt927",
+            "gt928",
+            "gt9271",
+            "gt967",
+        ]) {
+            Some(TouchFamily::Goodix)
+        } else if is(&[
+            "edt_ft5x06",
+            "edt-ft5x06",
+            "edt-ft5206",
+            "edt-ft5306",
+            "edt-ft5406",
+            "edt-ft5506",
+            "ft6236",
+        ]) {
+            Some(TouchFamily::EdtFt5x06)
+        } else {
+            None
+        }
+    }
+}
+
+/// What we found about a touch controller.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TouchInfo {
+    pub input: Option<String>,      // e.g. "input3"
+    pub input_name: Option<String>, // e.g. "Goodix Capacitive TouchScreen"
+    pub event: Option<PathBuf>,     // e.g. /dev/input/event2
+    pub firmware_version: Option<String>,
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Looks up the input device registered below the I2C device and the firmware version.
+///
+/// Goodix reports its firmware version as the input device version; edt-ft5x06 has a
+/// `fw_version` attribute.
+pub fn inspect_touch(device_dir: &Path, family: TouchFamily) -> TouchInfo {
+    let mut info = TouchInfo::default();
+    let input_dir = fs::read_dir(device_dir.join("input"))
+        .ok()
+        .and_then(|mut entries| entries.find_map(|e| e.ok()))
+        .map(|e| e.path());
+
+    if let Some(input_dir) = &input_dir {
+        info.input = input_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string());
+        info.input_name = read_trimmed(&input_dir.join("name"));
+        info.event = fs::read_dir(input_dir).ok().and_then(|entries| {
+            entries.filter_map(|e| e.ok()).find_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                name.starts_with("event")
+                    .then(|| PathBuf::from("/dev/input").join(name))
+            })
+        });
+    }
+
+    info.firmware_version = match family {
+        TouchFamily::Goodix => input_dir
+            .as_ref()
+            .and_then(|dir| read_trimmed(&dir.join("id/version"))),
+        TouchFamily::EdtFt5x06 => read_trimmed(&device_dir.join("fw_version")),
+    };
+    info
+}
+
+/// Reads the firmware version straight from the registers of an unbound controller.
+pub fn read_firmware_registers(bus_id: u32, addr: u16, family: TouchFamily) -> Result<String> {
+    safety::require(
+        Access::BusTraffic,
+        &format!("Reading registers on i2c-{}", bus_id),
+    )?;
+    let mut dev = LinuxI2CDevice::new(format!("/dev/i2c-{}", bus_id), addr)?;
+    match family {
+        TouchFamily::Goodix => {
+            // 16-bit register address, big-endian; version is little-endian at 0x8144
+            let mut version = [0u8; 2];
+            dev.write(&[0x81, 0x44])?;
+            dev.read(&mut version)?;
+            // Same format as the input device id/version attribute, "%04x"
+            Ok(format!("{:04x}", u16::from_le_bytes(version)))
+        }
+        TouchFamily::EdtFt5x06 => Ok(format!("{:02x}", dev.smbus_read_byte_data(0xa6)?)),
+    }
+}
+
+/// Whether two hex version strings are the same number, so "0x1060" matches the kernel's
+/// "1060"; other strings must be equal.
+fn same_version(actual: &str, expected: &str) -> bool {
+    let number = |v: &str| u32::from_str_radix(v.trim_start_matches("0x"), 16).ok();
+    match (number(actual), number(expected)) {
+        (Some(a), Some(e)) => a == e,
+        _ => actual == expected,
+    }
+}
+
+/// An expected touch controller.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedTouch {
+    pub bus: u32,
+    pub addr: u16,
+    pub firmware_version: Option<String>,
+    pub input_name: Option<String>,
+}
+
+/// Checks each expected controller is bound, registered an input device and runs the
+/// expected firmware.
+pub fn validate_touch(board: &Board, expected: &[ExpectedTouch]) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    for exp in expected {
+        let address = DeviceAddress::I2c {
+            bus: exp.bus,
+            addr: exp.addr,
+        };
+        let Some(device) = board.find_device(&address) else {
+            mismatches.push(format!("{}: touch controller not found", address));
+            continue;
+        };
+        let Some(family) = TouchFamily::detect(device) else {
+            mismatches.push(format!(
+                "{}: {} is not a known touch controller",
+                address, device.name
+            ));
+            continue;
+        };
+
+        let info = match &device.sysfs_path {
+            Some(dir) if device.is_bound() => inspect_touch(dir, family),
+            _ => {
+                // Driver didn't bind; the registers still tell us whether the chip is alive
+                let firmware_version = read_firmware_registers(exp.bus, exp.addr, family).ok();
+                mismatches.push(format!("{}: no driver bound", address));
+                TouchInfo {
+                    firmware_version,
+                    ..Default::default()
+                }
+            }
+        };
+        if device.is_bound() && info.input.is_none() {
+            mismatches.push(format!("{}: no input device registered", address));
+        }
+        if let Some(name) = &exp.input_name
+            && info.input.is_some()
+            && info.input_name.as_ref() != Some(name)
+        {
+            mismatches.push(format!(
+                "{}: input device name {:?} (expected {})",
+                address, info.input_name, name
+            ));
+        }
+        if let Some(version) = &exp.firmware_version
+            && !info
+                .firmware_version
+                .as_ref()
+                .is_some_and(|actual| same_version(actual, version))
+        {
+            mismatches.push(format!(
+                "{}: firmware version {:?} (expected {})",
+                address, info.firmware_version, version
+            ));
+        }
+    }
+    mismatches
+}
+
+/// Whether a raw `struct input_event` is a touch (BTN_TOUCH press or any absolute axis).
+pub fn is_touch_event(event: &libc::input_event) -> bool {
+    (event.type_ == EV_KEY && event.code == BTN_TOUCH && event.value == 1) || event.type_ == EV_ABS
+}
+
+/// Fixture mode: waits up to `timeout` for someone (or a robot finger) to touch the panel.
+pub fn wait_for_touch(event_node: &Path, timeout: Duration) -> Result<bool> {
+    let mut file = File::open(event_node)?;
+    let size = std::mem::size_of::<libc::input_event>();
+    let mut buf = vec![0u8; size];
+    let deadline = Instant::now() + timeout;
+
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        let mut fds = [PollFd::new(file.as_raw_fd(), PollFlags::POLLIN)];
+        if poll(&mut fds, left.as_millis().min(i32::MAX as u128) as i32)? == 0 {
+            break;
+        }
+        file.read_exact(&mut buf)?;
+        // SAFETY: buf holds exactly one input_event, which is plain old data
+        let event: libc::input_event =
+            unsafe { std::ptr::read_unaligned(buf.as_ptr() as *const libc::input_event) };
+        if is_touch_event(&event) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -0,0 +1,249 @@
+use crate::device::{DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Sysfs attributes copied into [`TuxDevice::attributes`].
+const ATTRIBUTES: [&str; 7] = [
+    "idVendor",
+    "idProduct",
+    "bcdDevice",
+    "manufacturer",
+    "product",
+    "serial",
+    "speed",
+];
+
+/// Parses a USB device name such as "1-1.2"; root hubs ("usb1") and interfaces
+/// ("1-1.2:1.0") are not devices on a port.
+fn parse_device_name(name: &str) -> Option<DeviceAddress> {
+    if name.contains(':') {
+        return None;
+    }
+    DeviceAddress::parse(Subsystem::Usb, name)
+}
+
+/// Lists USB devices (not root hubs or interfaces), enriched with udev data.
+pub fn find_usb_devices() -> Result<Vec<TuxDevice>> {
+    find_usb_devices_in(Path::new("/sys"), Path::new("/run/udev/data"))
+}
+
+/// Same as [`find_usb_devices`], with explicit sysfs and udev database roots.
+pub fn find_usb_devices_in(sys_root: &Path, udev_db: &Path) -> Result<Vec<TuxDevice>> {
+    let mut devices = Vec::new();
+    for entry in fs::read_dir(sys_root.join("bus/usb/devices"))? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(address) = parse_device_name(&name) else {
+            continue;
+        };
+        let dir = entry.path();
+        let mut device = TuxDevice::from_udev_in(&dir, udev_db, Subsystem::Usb, address);
+        for attribute in ATTRIBUTES {
+            if let Some(value) = read_trimmed(&dir.join(attribute)) {
+                device.attributes.insert(attribute.to_string(), value);
+            }
+        }
+        if let Some(product) = device.attributes.get("product") {
+            device.name = product.clone();
+        }
+        devices.push(device);
+    }
+    devices.sort_by_key(|d| d.address.clone());
+    Ok(devices)
+}
+
+/// Builds one Board model bus per root hub ("usb1", ..) with the devices below it.
+pub fn audit_usb_buses() -> Result<Vec<TuxBus>> {
+    audit_usb_buses_in(Path::new("/sys"), Path::new("/run/udev/data"))
+}
+
+/// Same as [`audit_usb_buses`], with explicit sysfs and udev database roots.
+pub fn audit_usb_buses_in(sys_root: &Path, udev_db: &Path) -> Result<Vec<TuxBus>> {
+    let devices_dir = sys_root.join("bus/usb/devices");
+    let mut buses: Vec<(u8, TuxBus)> = Vec::new();
+    for entry in fs::read_dir(&devices_dir)? {
+        let name = entry?.file_name().to_string_lossy().to_string();
+        let Some(bus) = name.strip_prefix("usb").and_then(|n| n.parse::<u8>().ok()) else {
+            continue;
+        };
+        let hub = devices_dir.join(&name);
+        let mut metadata = BTreeMap::new();
+        if let Some(speed) = read_trimmed(&hub.join("speed")) {
+            metadata.insert("speed".to_string(), speed);
+        }
+        buses.push((
+            bus,
+            TuxBus {
+                subsystem: Subsystem::Usb,
+                id: name,
+                name: read_trimmed(&hub.join("product")).unwrap_or_default(),
+                devices: Vec::new(),
+                metadata,
+            },
+        ));
+    }
+    buses.sort_by_key(|(bus, _)| *bus);
+    for device in find_usb_devices_in(sys_root, udev_db)? {
+        let DeviceAddress::Usb { bus, .. } = device.address else {
+            continue;
+        };
+        if let Some((_, tux_bus)) = buses.iter_mut().find(|(b, _)| *b == bus) {
+            tux_bus.devices.push(device);
+        }
+    }
+    Ok(buses.into_iter().map(|(_, bus)| bus).collect())
+}
+
+/// A USB device expected at a port.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedUsbDevice {
+    pub bus: u8,
+    pub port: String, // Port path, e.g. "1.2"
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub min_bcd_device: Option<u16>, // e.g. 0x0120 for 1.20; hubs report firmware here
+    pub min_firmware: Option<MinFirmware>,
+}
+
+/// A vendor firmware version string and the oldest acceptable version.
+#[derive(Debug, Clone, Default)]
+pub struct MinFirmware {
+    /// A udev property, or a sysfs attribute relative to the device, e.g. "1-1.4:1.0/fw_version".
+    pub attribute: String,
+    pub version: String,
+}
+
+/// Compares dotted versions such as "1.10" and "v1.9-rc2" by component, numbers numerically.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let components = |v: &str| {
+        v.trim_start_matches(['v', 'V'])
+            .split(['.', '-', '_', ' '])
+            .map(|c| (c.parse::<u64>().ok(), c.to_string()))
+            .collect::<Vec<_>>()
+    };
+    let (a, b) = (components(a), components(b));
+    for (x, y) in a.iter().zip(&b) {
+        let order = match (x.0, y.0) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => Ordering::Greater,
+            (None, Some(_)) => Ordering::Less,
+            (None, None) => x.1.cmp(&y.1),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// bcdDevice as "major.minor", e.g. 0x0120 as "1.20".
+fn bcd_version(bcd: u16) -> String {
+    format!("{:x}.{:02x}", bcd >> 8, bcd & 0xff)
+}
+
+fn firmware_version(device: &TuxDevice, attribute: &str) -> Option<String> {
+    device.attributes.get(attribute).cloned().or_else(|| {
+        read_trimmed(&device.sysfs_path.as_ref()?.join(attribute)).filter(|v| !v.is_empty())
+    })
+}
+
+/// Holds results of checking USB devices at their ports.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsbValidationResult {
+    pub present: Vec<DeviceAddress>,
+    pub missing: Vec<DeviceAddress>,
+    pub mismatched: Vec<String>, // Another device sits at the port, or outdated firmware
+}
+
+impl UsbValidationResult {
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+fn id_of(device: &TuxDevice, attribute: &str) -> Option<u16> {
+    u16::from_str_radix(device.attributes.get(attribute)?, 16).ok()
+}
+
+/// Firmware below the expected minimums, as mismatch descriptions.
+fn firmware_problems(device: &TuxDevice, exp: &ExpectedUsbDevice) -> Vec<String> {
+    let mut problems = Vec::new();
+    if let Some(min) = exp.min_bcd_device {
+        match id_of(device, "bcdDevice") {
+            Some(bcd) if bcd >= min => {}
+            Some(bcd) => problems.push(format!(
+                "bcdDevice {} below minimum {}",
+                bcd_version(bcd),
+                bcd_version(min)
+            )),
+            None => problems.push("no bcdDevice".to_string()),
+        }
+    }
+    if let Some(min) = &exp.min_firmware {
+        match firmware_version(device, &min.attribute) {
+            Some(version) if compare_versions(&version, &min.version).is_ge() => {}
+            Some(version) => problems.push(format!(
+                "{} {} below minimum {}",
+                min.attribute, version, min.version
+            )),
+            None => problems.push(format!("no {}", min.attribute)),
+        }
+    }
+    problems
+}
+
+/// Checks the expected VID:PID pairs at their ports, and firmware versions where given,
+/// against the devices found.
+pub fn validate_usb_devices(
+    devices: &[TuxDevice],
+    expected: &[ExpectedUsbDevice],
+) -> UsbValidationResult {
+    let mut result = UsbValidationResult::default();
+    for exp in expected {
+        let address = DeviceAddress::Usb {
+            bus: exp.bus,
+            port: exp.port.clone(),
+        };
+        let Some(device) = devices.iter().find(|d| d.address == address) else {
+            result.missing.push(address);
+            continue;
+        };
+        let (vendor, product) = (id_of(device, "idVendor"), id_of(device, "idProduct"));
+        if vendor == Some(exp.vendor_id) && product == Some(exp.product_id) {
+            let problems = firmware_problems(device, exp);
+            if problems.is_empty() {
+                result.present.push(address);
+            } else {
+                result.mismatched.push(format!(
+                    "{}: {} {}",
+                    address,
+                    device.name,
+                    problems.join(", ")
+                ));
+            }
+        } else {
+            result.mismatched.push(format!(
+                "{}: found {:04x}:{:04x} {} (expected {:04x}:{:04x})",
+                address,
+                vendor.unwrap_or(0),
+                product.unwrap_or(0),
+                device.name,
+                exp.vendor_id,
+                exp.product_id
+            ));
+        }
+    }
+    result
+}
+
+/// Enumerates the USB devices and checks the expected ones.
+pub fn validate_usb(expected: &[ExpectedUsbDevice]) -> Result<UsbValidationResult> {
+    Ok(validate_usb_devices(&find_usb_devices()?, expected))
+}
@@ -0,0 +1,143 @@
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A USB-UART channel (ttyUSB/ttyACM) and the USB interface behind it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsbSerialPort {
+    pub tty: String, // e.g. "ttyUSB2"
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub serial: Option<String>,
+    pub usb_path: String,      // e.g. "1-1.2"
+    pub interface: u8,         // bInterfaceNumber, i.e. the channel of a multi-channel bridge
+    pub symlinks: Vec<String>, // udev links relative to /dev, e.g. "serial/by-path/..-port0"
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_hex<T: TryFrom<u32>>(path: &Path) -> Option<T> {
+    u32::from_str_radix(&read_trimmed(path)?, 16)
+        .ok()
+        .and_then(|v| T::try_from(v).ok())
+}
+
+/// Lists USB serial ports with their bridge, channel and udev symlinks.
+pub fn list_usb_serial_ports() -> Result<Vec<UsbSerialPort>> {
+    list_usb_serial_ports_in(Path::new("/sys"), Path::new("/run/udev/data"))
+}
+
+/// Same as [`list_usb_serial_ports`], with explicit sysfs and udev database roots.
+pub fn list_usb_serial_ports_in(sys_root: &Path, udev_db: &Path) -> Result<Vec<UsbSerialPort>> {
+    let mut ports = Vec::new();
+    for entry in fs::read_dir(sys_root.join("class/tty"))? {
+        let entry = entry?;
+        let tty = entry.file_name().to_string_lossy().to_string();
+        if !tty.starts_with("ttyUSB") && !tty.starts_with("ttyACM") {
+            continue;
+        }
+        let tty_dir = entry.path();
+        let Ok(device) = fs::canonicalize(tty_dir.join("device")) else {
+            continue;
+        };
+        // ttyUSB devices sit below a usb-serial port device, ttyACM directly on the interface
+        let Some(interface_dir) = device
+            .ancestors()
+            .find(|d| d.join("bInterfaceNumber").exists())
+            .map(PathBuf::from)
+        else {
+            continue;
+        };
+        let Some(usb_dir) = interface_dir.parent() else {
+            continue;
+        };
+
+        let symlinks = read_trimmed(&tty_dir.join("dev"))
+            .and_then(|dev| fs::read_to_string(udev_db.join(format!("c{}", dev))).ok())
+            .map(|data| {
+                data.lines()
+                    .filter_map(|l| l.strip_prefix("S:"))
+                    .map(|l| l.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        ports.push(UsbSerialPort {
+            tty,
+            vendor_id: read_hex(&usb_dir.join("idVendor")).unwrap_or(0),
+            product_id: read_hex(&usb_dir.join("idProduct")).unwrap_or(0),
+            serial: read_trimmed(&usb_dir.join("serial")),
+            usb_path: usb_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            interface: read_hex(&interface_dir.join("bInterfaceNumber")).unwrap_or(0),
+            symlinks,
+        });
+    }
+    ports.sort_by(|a, b| (&a.usb_path, a.interface).cmp(&(&b.usb_path, b.interface)));
+    Ok(ports)
+}
+
+/// An expected channel of a USB-UART bridge, e.g. FT4232H interface 2 -> ttyUSB2.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedSerialChannel {
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub serial: Option<String>, // To tell identical bridges apart
+    pub interface: u8,
+    pub tty: Option<String>,
+    pub by_path: Option<String>, // Link under /dev/serial/by-path that must point at the channel
+}
+
+/// Checks every expected channel enumerated as the expected tty with a working by-path link.
+///
+/// `dev_root` is normally `/dev`.
+pub fn validate_channels(
+    ports: &[UsbSerialPort],
+    expected: &[ExpectedSerialChannel],
+    dev_root: &Path,
+) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    for exp in expected {
+        let label = format!(
+            "{:04x}:{:04x} interface {}",
+            exp.vendor_id, exp.product_id, exp.interface
+        );
+        let Some(port) = ports.iter().find(|p| {
+            p.vendor_id == exp.vendor_id
+                && p.product_id == exp.product_id
+                && p.interface == exp.interface
+                && (exp.serial.is_none() || p.serial == exp.serial)
+        }) else {
+            mismatches.push(format!("{}: not found", label));
+            continue;
+        };
+
+        if let Some(tty) = &exp.tty
+            && &port.tty != tty
+        {
+            mismatches.push(format!(
+                "{}: enumerated as {} (expected {})",
+                label, port.tty, tty
+            ));
+        }
+        if let Some(by_path) = &exp.by_path {
+            let link = dev_root.join("serial/by-path").join(by_path);
+            match fs::read_link(&link) {
+                Ok(target) if target.file_name().is_some_and(|n| n == port.tty.as_str()) => {}
+                Ok(target) => mismatches.push(format!(
+                    "{}: {} points to {} (expected {})",
+                    label,
+                    link.display(),
+                    target.display(),
+                    port.tty
+                )),
+                Err(_) => mismatches.push(format!("{}: {} missing", label, link.display())),
+            }
+        }
+    }
+    mismatches
+}
@@ -0,0 +1,43 @@
+use std::fs;
+use std::os::unix::fs::symlink;
+use tux_validation::absence::{self, MustBeAbsent};
+
+#[test]
+fn gadget_function_only_reported_when_bound() {
+    let dir = std::env::temp_dir().join(format!("tux-gadget-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let gadget = dir.join("g1");
+    fs::create_dir_all(gadget.join("functions/acm.usb0")).unwrap();
+    fs::create_dir_all(gadget.join("configs/c.1")).unwrap();
+    symlink(
+        gadget.join("functions/acm.usb0"),
+        gadget.join("configs/c.1/acm.usb0"),
+    )
+    .unwrap();
+    fs::write(gadget.join("UDC"), "\n").unwrap();
+
+    assert_eq!(
+        absence::find_bound_gadget_function(&dir, "acm").unwrap(),
+        None
+    );
+
+    fs::write(gadget.join("UDC"), "fe800000.usb\n").unwrap();
+    let found = absence::find_bound_gadget_function(&dir, "acm").unwrap();
+    assert_eq!(found, Some(gadget.join("configs/c.1/acm.usb0")));
+    assert_eq!(
+        absence::find_bound_gadget_function(&dir, "ecm").unwrap(),
+        None
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_path_is_not_a_violation() {
+    let items = vec![
+        MustBeAbsent::Path("/nonexistent/debug-uart".into()),
+        MustBeAbsent::Path(std::env::temp_dir()),
+    ];
+    let violations = absence::check_absent(&items).unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].item, items[1]);
+}
@@ -0,0 +1,81 @@
+use serde_json::json;
+use std::fs;
+use tux_validation::annotation::{self, Annotation};
+use tux_validation::evidence::EvidenceBundle;
+
+#[test]
+fn annotations_round_trip_through_reports() {
+    let mut report = json!({ "buses": [] });
+    assert!(annotation::from_report(&report).unwrap().is_empty());
+    let first = Annotation::new("i2c-1/1-0050", "kim", "cold joint on U12, reflowed");
+    let second = Annotation::new("rpi_firmware", "kim", "bench supply sagging");
+    annotation::add_to_report(&mut report, &first).unwrap();
+    annotation::add_to_report(&mut report, &second).unwrap();
+    assert!(report["annotations"][0].get("attachments").is_none());
+
+    let annotations = annotation::from_report(&report).unwrap();
+    assert_eq!(annotations, vec![first.clone(), second]);
+    assert_eq!(
+        annotation::for_check(&annotations, "i2c-1/1-0050"),
+        vec![&first]
+    );
+    assert!(annotation::add_to_report(&mut json!([]), &first).is_err());
+}
+
+#[test]
+fn stored_run_gets_comment_and_photos() {
+    let root = std::env::temp_dir().join(format!("tux-annotation-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let bundle_dir = root.join("evidence");
+    let mut bundle = EvidenceBundle::create(&bundle_dir).unwrap();
+    bundle
+        .add_text("i2c", "i2cdetect-1.txt", "50: UU", "i2cdetect -y 1")
+        .unwrap();
+    bundle.write_index().unwrap();
+    let report_path = root.join("report.json");
+    fs::write(&report_path, r#"{"buses": [], "seed": "0x1"}"#).unwrap();
+    let photo = root.join("u12.jpg");
+    fs::write(&photo, b"\xff\xd8 jpeg").unwrap();
+
+    let stored = annotation::annotate_stored(
+        &report_path,
+        Some(&bundle_dir),
+        Annotation::new("i2c-1/1-0050", "kim", "no ACK, U12 tombstoned"),
+        std::slice::from_ref(&photo),
+    )
+    .unwrap();
+    assert_eq!(
+        stored.attachments,
+        vec![std::path::PathBuf::from("i2c-1_1-0050/u12.jpg")]
+    );
+    assert_eq!(
+        fs::read(bundle_dir.join(&stored.attachments[0])).unwrap(),
+        b"\xff\xd8 jpeg"
+    );
+
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+    assert_eq!(report["seed"], "0x1");
+    assert_eq!(annotation::from_report(&report).unwrap(), vec![stored]);
+
+    // The earlier evidence stays indexed next to the photo
+    let reopened = EvidenceBundle::open(&bundle_dir).unwrap();
+    let checks: Vec<&str> = reopened
+        .entries()
+        .iter()
+        .map(|e| e.check.as_str())
+        .collect();
+    assert_eq!(checks, vec!["i2c", "i2c-1/1-0050"]);
+    assert!(reopened.entries()[1].description.contains("U12 tombstoned"));
+
+    // Photos without a bundle have nowhere to go
+    let err = annotation::annotate_stored(
+        &report_path,
+        None,
+        Annotation::new("i2c", "kim", "ok"),
+        &[photo],
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("evidence bundle"));
+    fs::remove_dir_all(&root).unwrap();
+}
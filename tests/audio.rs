@@ -0,0 +1,107 @@
+use std::path::Path;
+use tux_validation::audio::{self, LoopbackConfig, LoopbackLimits};
+use tux_validation::manifest::Manifest;
+
+/// A recording of `tone` through a jig: silence, the tone with a little noise, silence.
+fn recording(tone: &[i16]) -> Vec<i16> {
+    let mut noise: u32 = 1;
+    let mut hiss = || {
+        noise = noise.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        ((noise >> 16) % 64) as i16 - 32
+    };
+    let mut samples: Vec<i16> = (0..12000).map(|_| hiss()).collect();
+    samples.extend(tone.iter().map(|s| s.saturating_add(hiss())));
+    samples.extend((0..12000).map(|_| hiss()));
+    samples
+}
+
+#[test]
+fn analyses_a_recorded_tone() {
+    let tone = audio::tone(1000.0, -6.0, 48000, 48000);
+    assert!((audio::level_dbfs(&tone) + 6.0).abs() < 0.1);
+    let bytes: Vec<u8> = tone.iter().flat_map(|s| s.to_le_bytes()).collect();
+    assert_eq!(audio::parse_s16le(&bytes), tone);
+
+    let samples = recording(&tone);
+    let segment = audio::tone_segment(&samples, 48000);
+    assert!(
+        segment.len() > 36000 && segment.len() < 48000,
+        "{}",
+        segment.len()
+    );
+
+    let analysis = audio::analyse(segment, 48000).unwrap();
+    assert!(
+        (analysis.frequency_hz - 1000.0).abs() < 2.0,
+        "{:?}",
+        analysis
+    );
+    assert!((analysis.level_dbfs + 6.0).abs() < 0.5, "{:?}", analysis);
+    assert!(analysis.snr_db > 40.0, "{:?}", analysis);
+    assert_eq!(audio::analyse(&tone[..100], 48000), None);
+}
+
+#[test]
+fn checks_a_recording_against_the_manifest_limits() {
+    let manifest = Manifest::from_toml_str(
+        r#"
+[audio_loopback]
+output = "hw:0,0"
+input = "hw:0,0"
+
+[audio_loopback.limits]
+min_level_dbfs = -12
+max_level_dbfs = -3
+min_snr_db = 40
+"#,
+    )
+    .unwrap();
+    let limits = LoopbackLimits::resolve_in(
+        &manifest.sections["audio_loopback"]["limits"],
+        Path::new("/nonexistent"),
+    )
+    .unwrap();
+    assert_eq!(
+        limits.frequency_tolerance_hz,
+        audio::DEFAULT_FREQUENCY_TOLERANCE_HZ
+    );
+    assert_eq!(limits.min_snr_db, Some(40.0));
+    let config = LoopbackConfig {
+        limits,
+        ..Default::default()
+    };
+
+    let played = audio::tone(1000.0, -6.0, 48000, 48000);
+    let result = audio::check_recording(&recording(&played), &config);
+    assert!(result.is_ok(), "{:?}", result);
+
+    // An amplifier that does not come up: only the noise floor is recorded
+    let result = audio::check_recording(&recording(&vec![0; 48000]), &config);
+    assert!(!result.is_ok());
+    assert!(
+        result
+            .problems
+            .iter()
+            .any(|p| p.contains("below -12.0 dBFS")),
+        "{:?}",
+        result.problems
+    );
+
+    // Routed through the wrong clock: the tone comes back at another frequency
+    let wrong = audio::tone(1088.4, -6.0, 48000, 48000);
+    let result = audio::check_recording(&recording(&wrong), &config);
+    assert_eq!(result.problems.len(), 1, "{:?}", result.problems);
+    assert!(result.problems[0].starts_with("recorded tone is 1088"));
+
+    // Clipping distorts the tone
+    let clipped: Vec<i16> = audio::tone(1000.0, 6.0, 48000, 48000)
+        .into_iter()
+        .map(|s| s.clamp(-20000, 20000))
+        .collect();
+    let result = audio::check_recording(&recording(&clipped), &config);
+    assert!(
+        result.problems.iter().any(|p| p.starts_with("SNR")),
+        "{:?}",
+        result.problems
+    );
+}
@@ -0,0 +1,58 @@
+use std::fs;
+use tux_validation::baseline::{self, Baseline, Verdict};
+use tux_validation::measurement::{Measurement, Unit};
+
+const GOLDEN: &str = r#"
+[boot_time]
+value = 8.0
+unit = "s"
+better = "lower"
+tolerance = 10
+limit = 15
+
+["eth0/iperf"]
+value = 940
+unit = "Mbit/s"
+tolerance = 5
+
+["mmcblk0/read"]
+value = 80
+unit = "MiB"
+limit = 40
+"#;
+
+#[test]
+fn regressions_are_flagged_apart_from_failures() {
+    let baseline = Baseline::from_toml_str(GOLDEN).unwrap();
+    assert_eq!(baseline.entries.len(), 3);
+
+    let run = [
+        Measurement::new(9200.0, Unit::Millisecond, "boot_time"),
+        Measurement::new(950.0, Unit::MegabitsPerSecond, "eth0/iperf"),
+        Measurement::new(30.0, Unit::Mebibyte, "mmcblk0/read"),
+    ];
+    let results = baseline::compare(&baseline, &run);
+    assert_eq!(results[0].verdict, Verdict::Regression);
+    assert!((results[0].change_pct + 15.0).abs() < 1e-9);
+    assert_eq!(
+        results[0].to_string(),
+        "boot_time: 9.20 s vs baseline 8.00 s (-15.0%), REGRESSION"
+    );
+    assert_eq!(results[1].verdict, Verdict::Pass);
+    assert!(matches!(results[2].verdict, Verdict::Failure(_)));
+
+    let missing = baseline::compare(&baseline, &run[..1]);
+    assert!(matches!(missing[1].verdict, Verdict::Failure(_)));
+
+    let mut updated = baseline.clone();
+    updated.update(&run);
+    assert!((updated.entries[0].value - 9.2).abs() < 1e-9);
+    let dir = std::env::temp_dir().join(format!("tux-baseline-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("rock5b.toml"), updated.to_toml_string()).unwrap();
+    assert_eq!(Baseline::load_for_board(&dir, "rock5b").unwrap(), updated);
+    assert!(Baseline::load_for_board(&dir, "rpi5").is_err());
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(Baseline::from_toml_str("[x]\nvalue = 1\nunit = \"furlong\"\n").is_err());
+}
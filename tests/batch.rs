@@ -0,0 +1,63 @@
+use std::fs;
+use tux_validation::batch::{self, UnitIdentity};
+
+#[test]
+fn flags_duplicates_across_unit_reports() {
+    let root = std::env::temp_dir().join(format!("tux-batch-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let sys = root.join("sys");
+    fs::create_dir_all(sys.join("class/net/eth0/device")).unwrap();
+    fs::write(sys.join("class/net/eth0/address"), "D8:3A:DD:01:02:03\n").unwrap();
+    fs::create_dir_all(sys.join("class/net/docker0")).unwrap();
+    fs::write(sys.join("class/net/docker0/address"), "02:42:ac:11:00:02\n").unwrap();
+    fs::write(
+        root.join("machine-id"),
+        "4f1c2a9e0b7d4e22a1f3c5d6e7f80911\n",
+    )
+    .unwrap();
+
+    let live = UnitIdentity::collect_in("unit-003", &sys, &root.join("machine-id"));
+    assert_eq!(live.macs, vec!["d8:3a:dd:01:02:03"]);
+
+    let reports = root.join("reports");
+    fs::create_dir_all(&reports).unwrap();
+    let mut report = serde_json::json!({ "identity": live.to_json() });
+    report["identity"]["serials"] = serde_json::json!(["SN1003"]);
+    fs::write(reports.join("unit-003.json"), report.to_string()).unwrap();
+    fs::write(
+        reports.join("unit-001.json"),
+        r#"{"identity": {"serials": ["SN1001"], "macs": ["D8:3A:DD:01:02:03"],
+            "machine_id": "4f1c2a9e0b7d4e22a1f3c5d6e7f80911"}}"#,
+    )
+    .unwrap();
+    fs::write(
+        reports.join("unit-002.json"),
+        r#"{"identity": {"serials": ["SN1001"], "macs": ["00:00:00:00:00:00"]}}"#,
+    )
+    .unwrap();
+    fs::write(reports.join("notes.txt"), "not a report").unwrap();
+
+    let units = batch::load_reports(&reports).unwrap();
+    assert_eq!(units.len(), 3);
+    let duplicates = batch::find_duplicates(&units);
+    let summary: Vec<(&str, Vec<String>)> = duplicates
+        .iter()
+        .map(|d| (d.kind, d.units.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("mac", vec!["unit-002".to_string()]),
+            ("mac", vec!["unit-001".to_string(), "unit-003".to_string()]),
+            (
+                "machine_id",
+                vec!["unit-001".to_string(), "unit-003".to_string()]
+            ),
+            (
+                "serial",
+                vec!["unit-001".to_string(), "unit-002".to_string()]
+            ),
+        ]
+    );
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,25 @@
+use std::io::Cursor;
+use tux_validation::boot_slot::{self, ExpectedAbState, UBootEnv};
+
+#[test]
+fn rauc_style_uboot_env() {
+    let mock_env = "BOOT_ORDER=B A\nBOOT_A_LEFT=0\nBOOT_B_LEFT=2\nbootcount=1\n";
+    let env = boot_slot::parse_env_from_reader(Cursor::new(mock_env)).unwrap();
+    let cmdline = "console=ttyS2,1500000 root=/dev/mmcblk0p3 rauc.slot=B rootwait";
+
+    let state = UBootEnv::default().state_from_env(&env, cmdline).unwrap();
+    assert_eq!(state.active_slot, "B");
+    assert_eq!(state.boot_count, Some(1));
+    assert_eq!(state.slots[0].attempts_left, Some(2));
+
+    let expected = ExpectedAbState {
+        active_slot: Some("B".to_string()),
+        min_attempts_left: Some(1),
+        ..Default::default()
+    };
+    // Slot A used up its attempts, so there's nothing to fall back to
+    assert_eq!(
+        boot_slot::validate_state(&state, &expected),
+        vec!["fallback slot A is not bootable"]
+    );
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "hardware")]
+
+use std::time::Duration;
+use tux_validation::boot_time::{self, BootBudget};
+
+#[test]
+fn systemd_timestamps_against_budget() {
+    let busctl_output = "t 0\nt 1500000\nt 2100000\nt 3000000\nt 9000000\n";
+    let values = boot_time::parse_busctl_u64s(busctl_output).unwrap();
+    let timing = boot_time::timing_from_systemd(values.try_into().unwrap());
+
+    assert_eq!(timing.loader, Some(Duration::from_millis(1500)));
+    assert_eq!(timing.kernel, Duration::from_millis(2100));
+    assert_eq!(timing.initrd, Some(Duration::from_millis(900)));
+    assert_eq!(timing.userspace, Some(Duration::from_secs(6)));
+    assert_eq!(timing.total, Duration::from_millis(10500));
+
+    let budget = BootBudget {
+        kernel: Some(Duration::from_secs(3)),
+        total: Some(Duration::from_secs(10)),
+        ..Default::default()
+    };
+    assert_eq!(
+        boot_time::validate_timing(&timing, &budget),
+        vec!["total boot time 10.500s exceeds budget 10.000s"]
+    );
+}
+
+#[test]
+fn kernel_handover_from_kmsg_records() {
+    let records = "6,338,5139000,-;Freeing unused kernel memory: 1024K\n\
+                   6,339,5140900,-;Run /sbin/init as init process\n";
+    assert_eq!(
+        boot_time::kernel_handover_from_kmsg(records),
+        Some(Duration::from_micros(5140900))
+    );
+}
@@ -0,0 +1,97 @@
+use std::fs;
+use std::time::Duration;
+use tux_validation::brownout::{self, BrownoutMonitor, EventLog};
+use tux_validation::manifest::Manifest;
+use tux_validation::report::TestCase;
+
+fn secs(s: u64) -> Duration {
+    Duration::from_secs(s)
+}
+
+#[test]
+fn samples_become_events_that_annotate_failed_checks() {
+    let mut log = EventLog::default();
+    let dip = ["vdd_arm under_voltage".to_string()];
+    log.record(secs(0), "regulators", &[]);
+    log.record(secs(10), "regulators", &dip);
+    log.record(secs(11), "regulators", &dip);
+    log.record(secs(12), "regulators", &[]);
+    log.record(secs(30), "hwmon", &["rpi_volt in0_lcrit_alarm".to_string()]);
+    assert_eq!(log.events.len(), 2);
+    assert_eq!(log.events[0].start, secs(10));
+    assert_eq!(log.events[0].end, Some(secs(12)));
+    assert_eq!(log.events[1].end, None);
+
+    let mut i2c = TestCase::failed("i2c-1", "0x50", "missing");
+    assert!(brownout::annotate_case(
+        &mut i2c,
+        &(secs(9)..secs(10)),
+        &log.events
+    ));
+    assert_eq!(
+        i2c.failure.as_deref(),
+        Some("missing [during power event: regulators: vdd_arm under_voltage from 10.0s to 12.0s]")
+    );
+    // Before the dip, or passed: left alone
+    let mut early = TestCase::failed("i2c-1", "0x51", "missing");
+    assert!(!brownout::annotate_case(
+        &mut early,
+        &(secs(1)..secs(5)),
+        &log.events
+    ));
+    let mut passed = TestCase::passed("i2c-1", "0x52");
+    assert!(!brownout::annotate_case(
+        &mut passed,
+        &(secs(10)..secs(11)),
+        &log.events
+    ));
+    // Still active at the end of the run
+    assert_eq!(
+        brownout::events_during(&log.events, &(secs(40)..secs(41))).len(),
+        1
+    );
+}
+
+#[test]
+fn monitor_reads_regulator_flags_and_pmic_bits() {
+    let root = std::env::temp_dir().join(format!("tux-brownout-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let regulator = root.join("class/regulator/regulator.3");
+    fs::create_dir_all(&regulator).unwrap();
+    fs::write(regulator.join("name"), "vdd_gpu\n").unwrap();
+    fs::write(regulator.join("under_voltage"), "0\n").unwrap();
+    let regmap = root.join("kernel/debug/regmap/0-001b");
+    fs::create_dir_all(&regmap).unwrap();
+    fs::write(regmap.join("registers"), "00: 00\n04: 41\n").unwrap();
+
+    let manifest = Manifest::from_toml_str(
+        r#"
+[brownout]
+interval = "5ms"
+pmic = [{ device = "0-001b", register = 4, mask = 0x40, condition = "VSYS low" }]
+"#,
+    )
+    .unwrap();
+    let (mut indicators, interval) = brownout::indicators_from_manifest(&manifest, &root).unwrap();
+    assert_eq!(interval, Duration::from_millis(5));
+    assert_eq!(indicators.last_mut().unwrap().active(), ["VSYS low"]);
+
+    let monitor = BrownoutMonitor::start(indicators, interval);
+    let ((), span) = monitor.time(|| {
+        fs::write(regulator.join("under_voltage"), "1\n").unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+    });
+    let events = monitor.stop();
+    assert!(
+        events
+            .iter()
+            .any(|e| e.condition == "vdd_gpu under_voltage" && e.overlaps(&span)),
+        "{:?}",
+        events
+    );
+    assert!(events.iter().any(|e| e.indicator == "pmic 0-001b"));
+
+    let bad = Manifest::from_toml_str("[brownout]\npmic = [{ register = 4, mask = 1 }]\n").unwrap();
+    assert!(brownout::indicators_from_manifest(&bad, &root).is_err());
+    fs::remove_dir_all(&root).unwrap();
+}
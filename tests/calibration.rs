@@ -0,0 +1,65 @@
+use std::fs;
+use tux_validation::calibration::{self, CalibrationCheck, CalibrationSpec};
+
+#[test]
+fn detects_bad_crc_and_unprogrammed_fields() {
+    assert_eq!(calibration::crc32(b"123456789"), 0xcbf43926);
+
+    let root = std::env::temp_dir().join(format!("tux-calibration-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    // 8 bytes of data, CRC-32 LE, then a 4 byte serial that was never written
+    let mut blob = vec![0x10, 0x20, 0x30, 0x40, 0x50, 0x60, 0x70, 0x80];
+    blob.extend(calibration::crc32(&blob).to_le_bytes());
+    blob.extend([0xff; 4]);
+    fs::write(root.join("eeprom"), &blob).unwrap();
+    fs::write(
+        root.join("nvram.txt"),
+        "# board\nboardtype=0x0726\nmacaddr=00:90:4c:c5:12:38\n",
+    )
+    .unwrap();
+
+    let specs = [
+        CalibrationSpec {
+            name: "sensor".to_string(),
+            path: root.join("eeprom"),
+            min_size: 16,
+            checks: vec![
+                CalibrationCheck::Crc32 {
+                    data: 0..8,
+                    crc_offset: 8,
+                    little_endian: true,
+                },
+                CalibrationCheck::Field {
+                    name: "serial".to_string(),
+                    range: 12..16,
+                    default: None,
+                },
+            ],
+            ..Default::default()
+        },
+        CalibrationSpec {
+            name: "wifi".to_string(),
+            path: root.join("nvram.txt"),
+            checks: vec![CalibrationCheck::Key {
+                key: "macaddr".to_string(),
+                default: Some("00:90:4c:c5:12:38".to_string()),
+            }],
+            ..Default::default()
+        },
+        CalibrationSpec {
+            name: "bdf".to_string(),
+            path: root.join("board-2.bin"),
+            ..Default::default()
+        },
+    ];
+    let failures = calibration::check_calibration(&specs);
+    assert_eq!(failures.len(), 3);
+    assert_eq!(failures[0], "sensor: field serial is not programmed");
+    assert_eq!(
+        failures[1],
+        "wifi: macaddr still has the default value 00:90:4c:c5:12:38"
+    );
+    assert!(failures[2].starts_with("bdf: not present at"));
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn os_release_subcommand_sets_exit_status() {
+    let dir = std::env::temp_dir().join(format!("tux-cli-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("os-release");
+    fs::write(&path, "ID=debian\nVERSION_CODENAME=forky\n").unwrap();
+
+    let run = |codename: &str| {
+        Command::new(env!("CARGO_BIN_EXE_tux-validate"))
+            .args(["os-release", "--id", "debian", "--codename", codename])
+            .arg("--path")
+            .arg(&path)
+            .output()
+            .unwrap()
+    };
+    let passed = run("forky");
+    assert!(passed.status.success());
+    assert!(String::from_utf8_lossy(&passed.stdout).contains("Validation Passed: debian (forky)"));
+    assert_eq!(run("trixie").status.code(), Some(1));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn verify_rejects_addresses_outside_the_range() {
+    let verify = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_tux-validate"))
+            .args(["verify", "--bus-id", "99"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let outside = verify(&["--range", "0x50-0x57", "0x1b"]);
+    assert!(!outside.status.success());
+    assert!(String::from_utf8_lossy(&outside.stderr).contains("0x1b is outside the scanned range"));
+    assert!(!String::from_utf8_lossy(&outside.stdout).contains("missing"));
+    let reversed = verify(&["--range", "0x57-0x50", "0x50"]);
+    assert_eq!(reversed.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&reversed.stderr).contains("START above END"));
+}
+
+#[test]
+fn reports_carry_the_unit_identity() {
+    use tux_validation::batch::{self, UnitIdentity};
+
+    let dir = std::env::temp_dir().join(format!("tux-cli-identity-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let status = Command::new(env!("CARGO_BIN_EXE_tux-validate"))
+        .arg("report")
+        .arg("--output")
+        .arg(dir.join("unit-a.json"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+    // The report's own identity, so batch checks of a unit's reports find it again
+    assert_eq!(
+        batch::load_reports(&dir).unwrap(),
+        [UnitIdentity::collect("unit-a")]
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,90 @@
+use tux_validation::clocks::{self, ExpectedClocks};
+use tux_validation::manifest::Manifest;
+
+// Linux 6.7 and later: a hardware gate column and consumers
+const SUMMARY: &str = "\
+                                 enable  prepare  protect                                duty  hardware                            connection
+   clock                          count    count    count        rate   accuracy phase  cycle    enable   consumer                         id
+---------------------------------------------------------------------------------------------------------------------------------------------
+ xin24m                              7       7        0        24000000    0          0     50000      Y   deviceless                      no_connection_id
+    gpll                             2       2        0        1188000000  0          0     50000      Y   deviceless                      no_connection_id
+       clk_i2c1                      0       1        0        99000000    0          0     50000      N   fe5a0000.i2c                    i2c
+                                                                                                           fe5a0000.i2c                    pclk
+       clk_uart2                     1       1        0        23760000    0          -----  50000      Y   fe660000.serial                 baudclk
+    cpll                             0       0        0        1000000000  0          0     50000      N   deviceless                      no_connection_id
+       clk_spi0                      0       0        0        200000000   0          0     50000      N   deviceless                      no_connection_id
+ xin32k                              1       1        0        32768       0          0     50000      Y   deviceless                      no_connection_id
+";
+
+#[test]
+fn parses_the_clock_tree() {
+    let clocks = clocks::parse_clk_summary(SUMMARY).unwrap();
+    let names: Vec<&str> = clocks.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "xin24m",
+            "gpll",
+            "clk_i2c1",
+            "clk_uart2",
+            "cpll",
+            "clk_spi0",
+            "xin32k"
+        ]
+    );
+    assert_eq!(clocks[0].parent, None);
+    assert_eq!(clocks[2].parent.as_deref(), Some("gpll"));
+    assert_eq!(clocks[5].parent.as_deref(), Some("cpll"));
+    assert_eq!(clocks[6].parent, None);
+    assert_eq!(clocks[2].rate, 99_000_000);
+    assert_eq!(clocks[2].hw_enabled, Some(false));
+    assert_eq!(clocks[2].consumers, ["fe5a0000.i2c", "fe5a0000.i2c"]);
+    // Gated by runtime PM between transfers, but prepared by its driver
+    assert!(clocks[2].is_in_use());
+    assert_eq!(clocks[3].rate, 23_760_000);
+    assert!(clocks[0].consumers.is_empty());
+
+    // Before Linux 4.16: no protect or duty cycle columns
+    let old = clocks::parse_clk_summary(
+        "   clock                         enable_cnt  prepare_cnt        rate   accuracy   phase\n\
+         ----------------------------------------------------------------------------------------\n \
+         xin24m                                 5            5    24000000          0 0\n    \
+         clk_i2c1                               1            1   100000000          0 0\n",
+    )
+    .unwrap();
+    assert_eq!(old[1].rate, 100_000_000);
+    assert_eq!(old[1].parent.as_deref(), Some("xin24m"));
+    assert_eq!(old[1].hw_enabled, None);
+}
+
+#[test]
+fn checks_clocks_against_the_manifest() {
+    let clocks = clocks::parse_clk_summary(SUMMARY).unwrap();
+    let manifest = Manifest::from_toml_str(
+        "[[clocks.clocks]]\nname = \"clk_i2c1\"\nrate = 100000000\nparent = \"gpll\"\n\n\
+         [[clocks.clocks]]\nname = \"clk_uart2\"\nrate = 24000000\ntolerance_percent = 0\n\n\
+         [[clocks.clocks]]\nname = \"clk_spi0\"\nrate = 200000000\nparent = \"gpll\"\n\n\
+         [[clocks.clocks]]\nname = \"xin32k\"\nenabled = false\n\n\
+         [[clocks.clocks]]\nname = \"clk_i2c3\"\n",
+    )
+    .unwrap();
+    let expected = ExpectedClocks::from_manifest(&manifest).unwrap();
+    assert_eq!(
+        clocks::validate_clocks(&clocks, &expected),
+        [
+            "clk_uart2: 23760000 Hz (expected 24000000 Hz, within 0%)",
+            "clk_spi0: disabled (expected enabled)",
+            "clk_spi0: parent cpll (expected gpll)",
+            "xin32k: enabled 1 time(s) (expected disabled)",
+            "clk_i2c3: not found",
+        ]
+    );
+
+    let bad =
+        Manifest::from_toml_str("[[clocks.clocks]]\nname = \"xin24m\"\nrate = \"24M\"\n").unwrap();
+    assert!(ExpectedClocks::from_manifest(&bad).is_err());
+    assert_eq!(
+        clocks::read_clocks_in(std::path::Path::new("/nonexistent")).unwrap(),
+        None
+    );
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "hardware")]
+
+use std::fs;
+use tux_validation::container_mode::{self, HOST_ONLY};
+use tux_validation::manifest::Manifest;
+use tux_validation::registry;
+use tux_validation::system_root::SystemRoot;
+
+#[test]
+fn detects_a_privileged_docker_container_and_what_it_hides() {
+    let root = std::env::temp_dir().join(format!("tux-container-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for dir in ["proc/1", "proc/self", "sys/class/i2c-dev/i2c-1", "dev"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    fs::create_dir_all(root.join("sys/class/i2c-dev/i2c-2")).unwrap();
+    fs::write(root.join("dev/i2c-1"), "").unwrap();
+    let system = SystemRoot::under(&root);
+    fs::write(
+        root.join("proc/self/uid_map"),
+        "         0          0 4294967295\n",
+    )
+    .unwrap();
+    fs::write(root.join("proc/self/cgroup"), "0::/\n").unwrap();
+    assert_eq!(container_mode::detect_in(&system), None);
+
+    fs::write(root.join(".dockerenv"), "").unwrap();
+    fs::write(
+        root.join("proc/self/cgroup"),
+        "0::/system.slice/docker-3f2a.scope\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("proc/self/status"),
+        "Name:\tsh\nCapEff:\t000001ffffffffff\n",
+    )
+    .unwrap();
+    let container = container_mode::detect_in(&system).unwrap();
+    assert_eq!(container.engine.as_deref(), Some("docker"));
+    assert!(container.privileged);
+    assert_eq!(container.evidence.len(), 2);
+
+    // Rootless podman: the engine from init's environment, no CAP_SYS_ADMIN
+    fs::remove_file(root.join(".dockerenv")).unwrap();
+    fs::write(root.join("proc/self/cgroup"), "0::/\n").unwrap();
+    fs::write(root.join("proc/1/environ"), "PATH=/bin\0container=podman\0").unwrap();
+    fs::write(root.join("proc/self/uid_map"), "0 1000 1\n1 100000 65536\n").unwrap();
+    fs::write(root.join("proc/self/status"), "CapEff:\t00000000a80425fb\n").unwrap();
+    let container = container_mode::detect_in(&system).unwrap();
+    assert_eq!(container.engine.as_deref(), Some("podman"));
+    assert!(!container.privileged);
+
+    fs::write(
+        root.join("proc/self/mountinfo"),
+        "20 1 0:20 / / rw - overlay overlay rw\n\
+         21 20 0:21 / /sys ro,nosuid - sysfs sysfs ro\n\
+         22 21 0:22 / /sys/fs/cgroup ro - cgroup2 cgroup rw\n\
+         23 21 0:23 / /sys/firmware ro - tmpfs tmpfs ro\n\
+         24 20 0:24 / /proc/kcore rw - devtmpfs udev rw\n",
+    )
+    .unwrap();
+    assert_eq!(
+        container_mode::warnings_in(&system),
+        [
+            "/sys is read-only: checks that bind drivers or write attributes will fail",
+            "/sys/firmware is masked (tmpfs): checks reading it see nothing",
+            "/proc/kcore is masked (devtmpfs): checks reading it see nothing",
+            "i2c-2 is in sysfs but /dev/i2c-2 is not passed into the container",
+        ]
+    );
+}
+
+#[test]
+fn skips_host_only_sections() {
+    for (id, _) in HOST_ONLY {
+        assert!(registry::find(id).is_some(), "{} is not a check", id);
+    }
+    let mut manifest =
+        Manifest::from_toml_str("[kernel_threads]\nname = \"kworker*\"\n\n[cpu]\ncount = 4\n")
+            .unwrap();
+    let skipped = container_mode::Container::default().adjust(&mut manifest);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].name, "kernel_threads");
+    assert_eq!(
+        skipped[0].skipped.as_deref(),
+        Some("host-only check: kernel threads are outside the container's PID namespace")
+    );
+    assert!(manifest.sections.contains_key("cpu"));
+    assert!(!manifest.sections.contains_key("kernel_threads"));
+    assert_eq!(container_mode::host_only_reason("cpu"), None);
+}
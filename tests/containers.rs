@@ -0,0 +1,38 @@
+#![cfg(feature = "hardware")]
+
+use tux_validation::containers::{self, ExpectedWorkload, Kubectl};
+
+#[test]
+fn k3s_pods_with_digest_checks() {
+    let mock_pods: serde_json::Value = serde_json::from_str(
+        r#"{"items": [
+            {"metadata": {"name": "edge-agent-7d9f8-x2z"},
+             "status": {"phase": "Running", "containerStatuses": [
+                {"name": "agent", "image": "registry.local/agent:2.1", "ready": true,
+                 "imageID": "registry.local/agent@sha256:aaaa"}]}},
+            {"metadata": {"name": "mqtt-0"},
+             "status": {"phase": "Pending", "containerStatuses": [
+                {"name": "broker", "image": "eclipse-mosquitto:2", "ready": false,
+                 "imageID": ""}]}}
+        ]}"#,
+    )
+    .unwrap();
+    let workloads = Kubectl::workloads_from_json(&mock_pods);
+    assert_eq!(workloads[0].name, "edge-agent-7d9f8-x2z/agent");
+    assert_eq!(workloads[0].digests, vec!["sha256:aaaa"]);
+
+    let expected = vec![
+        ExpectedWorkload {
+            name: "edge-agent".to_string(),
+            image_digest: Some("sha256:bbbb".to_string()),
+        },
+        ExpectedWorkload {
+            name: "mqtt".to_string(),
+            image_digest: None,
+        },
+    ];
+    let result = containers::validate_workloads(&workloads, &expected);
+    assert_eq!(result.running, vec!["edge-agent-7d9f8-x2z/agent"]);
+    assert_eq!(result.missing, vec!["mqtt"]);
+    assert_eq!(result.mismatched.len(), 1);
+}
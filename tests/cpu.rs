@@ -0,0 +1,77 @@
+use std::fs;
+use tux_validation::cpu::{self, ExpectedCpus};
+use tux_validation::manifest::Manifest;
+
+#[test]
+fn parses_kernel_cpu_lists() {
+    assert_eq!(cpu::parse_cpu_list("0-3,6\n").unwrap(), vec![0, 1, 2, 3, 6]);
+    assert_eq!(cpu::parse_cpu_list("0").unwrap(), vec![0]);
+    assert_eq!(cpu::parse_cpu_list("\n").unwrap(), Vec::<u32>::new());
+    assert!(cpu::parse_cpu_list("3-1").is_err());
+    assert!(cpu::parse_cpu_list("a").is_err());
+}
+
+#[test]
+fn finds_a_cluster_held_in_reset() {
+    let root = std::env::temp_dir().join(format!("tux-cpu-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let dir = root.join("devices/system/cpu");
+    fs::create_dir_all(&dir).unwrap();
+    // Eight possible CPUs; the big cluster (4-7) never came up and cpu3 was unplugged
+    fs::write(dir.join("possible"), "0-7\n").unwrap();
+    fs::write(dir.join("present"), "0-3\n").unwrap();
+    fs::write(dir.join("online"), "0-2\n").unwrap();
+    for id in 0..4 {
+        let cpu = dir.join(format!("cpu{}", id));
+        fs::create_dir_all(cpu.join("cpufreq")).unwrap();
+        fs::create_dir_all(cpu.join("topology")).unwrap();
+        fs::write(cpu.join("topology/cluster_id"), "0\n").unwrap();
+        fs::write(cpu.join("cpufreq/cpuinfo_min_freq"), "408000\n").unwrap();
+        let max = if id == 2 { "1416000\n" } else { "1800000\n" };
+        fs::write(cpu.join("cpufreq/cpuinfo_max_freq"), max).unwrap();
+        let governor = if id == 1 {
+            "performance\n"
+        } else {
+            "schedutil\n"
+        };
+        fs::write(cpu.join("cpufreq/scaling_governor"), governor).unwrap();
+    }
+
+    let topology = cpu::read_topology_in(&root).unwrap();
+    assert_eq!(topology.present, vec![0, 1, 2, 3]);
+    assert_eq!(topology.online(), vec![0, 1, 2]);
+    assert_eq!(topology.cpus[0].cluster, Some(0));
+    assert_eq!(topology.cpus[0].max_freq_khz, Some(1_800_000));
+
+    let manifest = Manifest::from_toml_str(
+        r#"
+        [cpu]
+        count = 8
+        governor = "schedutil"
+        groups = [
+            { cpus = "0-3", min_freq_khz = 408000, max_freq_khz = 1800000 },
+            { cpus = "4-7", max_freq_khz = 2256000 },
+        ]
+        "#,
+    )
+    .unwrap();
+    let expected = ExpectedCpus::from_manifest(&manifest).unwrap();
+    assert_eq!(
+        cpu::validate_cpus(&topology, &expected),
+        vec![
+            "3 CPUs online (expected 8)",
+            "CPUs 4,5,6,7 not present",
+            "CPU 3 offline",
+            "cpu1 governor is performance (expected schedutil)",
+            "cpu2 max frequency 1416000 kHz (expected 1800000 kHz)",
+        ]
+    );
+
+    let expected = ExpectedCpus {
+        allow_offline: cpu::parse_cpu_list("3-7").unwrap(),
+        ..Default::default()
+    };
+    assert!(cpu::validate_cpus(&topology, &expected).is_empty());
+
+    let _ = fs::remove_dir_all(&root);
+}
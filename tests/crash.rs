@@ -0,0 +1,54 @@
+use std::fs;
+use tux_validation::crash::{self, CrashKind, CrashScanConfig};
+use tux_validation::evidence::EvidenceBundle;
+
+#[test]
+fn fresh_artifacts_are_attached_to_bundle() {
+    let root = std::env::temp_dir().join(format!("tux-crash-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("coredump")).unwrap();
+    fs::create_dir_all(root.join("pstore")).unwrap();
+    fs::write(
+        root.join("coredump/core.app-agent.0.1f2e3d.812.1700000000000000.zst"),
+        b"\0",
+    )
+    .unwrap();
+    fs::write(
+        root.join("pstore/console-ramoops-0"),
+        "[  12.0] rk808 0-001b: probe\n[  99.1] Kernel panic - not syncing: Fatal exception\n",
+    )
+    .unwrap();
+
+    let config = CrashScanConfig {
+        coredump_dirs: vec![root.join("coredump")],
+        pstore_dir: root.join("pstore"),
+        unclean_markers: vec![root.join("unclean")],
+        ..Default::default()
+    };
+    let mut bundle = EvidenceBundle::create(&root.join("bundle")).unwrap();
+    let artifacts = crash::check_crash_artifacts(&config, Some(&mut bundle)).unwrap();
+
+    assert_eq!(artifacts.len(), 2);
+    let core = artifacts
+        .iter()
+        .find(|a| a.kind == CrashKind::Coredump)
+        .unwrap();
+    assert_eq!(core.summary, "coredump of app-agent (pid 812, uid 0)");
+    let pstore = artifacts
+        .iter()
+        .find(|a| a.kind == CrashKind::Pstore)
+        .unwrap();
+    assert_eq!(
+        pstore.summary,
+        "[  99.1] Kernel panic - not syncing: Fatal exception"
+    );
+
+    assert_eq!(bundle.entries().len(), 2);
+    bundle.write_index().unwrap();
+    assert!(
+        root.join("bundle/crash_artifacts/console-ramoops-0")
+            .exists()
+    );
+    assert!(root.join("bundle/index.json").exists());
+    fs::remove_dir_all(&root).unwrap();
+}
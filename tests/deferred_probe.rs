@@ -0,0 +1,75 @@
+#![cfg(feature = "i2c")]
+
+use tux_validation::deferred_probe;
+use tux_validation::device::DeviceAddress;
+use tux_validation::discovery;
+use tux_validation::manifest::{self, Manifest};
+use tux_validation::testing::FakeSysfs;
+
+#[test]
+fn parses_devices_deferred() {
+    let deferred = deferred_probe::parse_devices_deferred(
+        "1-0050\tat24: supplier regulator-vcc3v3 not ready\nfe5a0000.i2c\t\nspi0.0\n",
+    );
+    assert_eq!(deferred.len(), 3);
+    assert_eq!(deferred[0].name, "1-0050");
+    assert_eq!(
+        deferred[0].reason.as_deref(),
+        Some("at24: supplier regulator-vcc3v3 not ready")
+    );
+    assert_eq!(deferred[1].reason, None);
+    assert_eq!(deferred[2].name, "spi0.0");
+}
+
+#[test]
+fn tells_deferred_devices_from_absent_ones() {
+    let fake = FakeSysfs::new()
+        .i2c_adapter(1, "rk3x-i2c")
+        .i2c_device(1, 0x50, "24c02", None)
+        .i2c_device(1, 0x51, "pcf8563", None)
+        .file(
+            "sys/kernel/debug/devices_deferred",
+            "1-0050\tat24: supplier regulator-vcc3v3 not ready\n",
+        );
+    let root = fake.root();
+    let board = discovery::discover_board_in(&root.discovery_context()).unwrap();
+    let eeprom = board
+        .find_device(&DeviceAddress::I2c { bus: 1, addr: 0x50 })
+        .unwrap();
+    assert!(eeprom.probe_deferred);
+    assert_eq!(
+        eeprom.kernel_log,
+        ["deferred probe pending: at24: supplier regulator-vcc3v3 not ready"]
+    );
+    let rtc = board
+        .find_device(&DeviceAddress::I2c { bus: 1, addr: 0x51 })
+        .unwrap();
+    assert!(!rtc.probe_deferred);
+
+    let manifest = Manifest::from_toml_str(
+        "[[i2c]]\nbus = 1\naddress = 0x50\nname = \"eeprom\"\ndriver = \"at24\"\n\n\
+         [[i2c]]\nbus = 1\naddress = 0x51\nname = \"rtc\"\ndriver = \"rtc-pcf8563\"\n\n\
+         [[i2c]]\nbus = 1\naddress = 0x68\nname = \"imu\"\n",
+    )
+    .unwrap();
+    let messages: Vec<String> = manifest::validate(&manifest, &board.buses)
+        .findings
+        .into_iter()
+        .map(|f| f.message)
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "eeprom probe deferred: at24 is waiting on a dependency",
+            "rtc bound to no driver (expected rtc-pcf8563)",
+            "imu not found",
+        ]
+    );
+
+    // Without debugfs nothing is marked
+    let plain = FakeSysfs::new()
+        .i2c_adapter(1, "rk3x-i2c")
+        .i2c_device(1, 0x50, "24c02", None);
+    let root = plain.root();
+    assert_eq!(deferred_probe::read_deferred_in(&root.sys).unwrap(), None);
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "sensors")]
+
+use std::fs;
+use tux_validation::derating::{DeratingCurve, Threshold};
+use tux_validation::manifest::Manifest;
+use tux_validation::sampling::SampleLimits;
+
+#[test]
+fn thresholds_follow_the_board_temperature() {
+    let curve = DeratingCurve::new(vec![(60.0, 2016.0), (25.0, 2400.0), (85.0, 1608.0)]).unwrap();
+    assert_eq!(curve.at(0.0), 2400.0);
+    assert_eq!(curve.at(42.5), 2208.0);
+    assert_eq!(curve.at(100.0), 1608.0);
+    assert!(DeratingCurve::new(vec![]).is_err());
+
+    let root = std::env::temp_dir().join(format!("tux-derating-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("class/hwmon/hwmon1")).unwrap();
+    fs::write(root.join("class/hwmon/hwmon1/temp1_input"), "60000\n").unwrap();
+
+    let manifest = Manifest::from_toml_str(
+        r#"
+[sampling]
+samples = 10
+
+[sampling.limits]
+min_mean = 1500
+max = { channel = "hwmon1/temp1_input", curve = [[25, 2400], [60, 2016], [85, 1608]] }
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        manifest.threshold("sampling", "samples").unwrap(),
+        Some(Threshold::Fixed(10.0))
+    );
+    assert_eq!(manifest.threshold("sampling", "interval").unwrap(), None);
+    let limits = SampleLimits::resolve_in(&manifest.sections["sampling"]["limits"], &root).unwrap();
+    assert_eq!(limits.min_mean, Some(1500.0));
+    assert_eq!(limits.max, Some(2016.0));
+    assert_eq!(limits.max_stddev, None);
+
+    let missing = Threshold::from_value(&serde_json::json!({
+        "channel": "hwmon9/temp1_input",
+        "curve": [[25, 1.0]],
+    }))
+    .unwrap();
+    assert!(missing.resolve_in(&root).is_err());
+    assert!(Threshold::from_value(&serde_json::json!("fast")).is_err());
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "hardware")]
+
+use tux_validation::device::{DeviceAddress, Subsystem, TuxDevice};
+use tux_validation::testing::FakeSysfs;
+
+#[test]
+fn sysfs_alone_yields_the_same_device_without_udev() {
+    let fake = FakeSysfs::new()
+        .i2c_device(1, 0x50, "24c02", Some("at24"))
+        .file(
+            "sys/bus/i2c/devices/1-0050/of_node/compatible",
+            "atmel,24c02\0",
+        )
+        .file("sys/bus/i2c/devices/1-0050/of_node/name", "eeprom\0")
+        .i2c_device(1, 0x1b, "rk808", None)
+        .file("sys/bus/i2c/devices/1-001b/uevent", "")
+        .file(
+            "sys/bus/i2c/devices/1-001b/modalias",
+            "of:NpmicT(null)Crockchip,rk808\n",
+        )
+        .udev_entry("+i2c:1-0050", &["ID_PATH=platform-fe5a0000.i2c"]);
+    let root = fake.root();
+    let dir = root.sys.join("bus/i2c/devices/1-0050");
+    let address = DeviceAddress::I2c { bus: 1, addr: 0x50 };
+
+    let with_udev = TuxDevice::from_udev_in(&dir, &root.udev_db, Subsystem::I2c, address.clone());
+    assert!(with_udev.in_udev);
+    assert_eq!(with_udev.attributes["ID_PATH"], "platform-fe5a0000.i2c");
+
+    // No udev running, e.g. in a container: same device, without the udev properties
+    let without = TuxDevice::from_udev_in(
+        &dir,
+        &fake.path().join("no-udev"),
+        Subsystem::I2c,
+        address.clone(),
+    );
+    assert!(!without.in_udev);
+    assert_eq!(
+        without,
+        TuxDevice::from_sysfs(&dir, Subsystem::I2c, address)
+    );
+    let mut expected = with_udev.clone();
+    expected.in_udev = false;
+    expected.attributes.remove("ID_PATH");
+    assert_eq!(without, expected);
+    assert_eq!(without.driver.as_deref(), Some("at24"));
+    assert_eq!(without.attributes["OF_COMPATIBLE_0"], "atmel,24c02");
+    assert_eq!(without.attributes["OF_COMPATIBLE_N"], "1");
+    assert_eq!(without.attributes["OF_NAME"], "eeprom");
+
+    // An empty uevent, as some container runtimes mask it: modalias comes from sysfs
+    let pmic = TuxDevice::from_sysfs(
+        &root.sys.join("bus/i2c/devices/1-001b"),
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 1, addr: 0x1b },
+    );
+    assert_eq!(
+        pmic.modalias.as_deref(),
+        Some("of:NpmicT(null)Crockchip,rk808")
+    );
+    assert_eq!(pmic.driver, None);
+    assert_eq!(pmic.name, "rk808");
+}
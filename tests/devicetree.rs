@@ -0,0 +1,229 @@
+use std::fs;
+use std::path::Path;
+use tux_validation::devicetree::{self, BindingRule};
+
+fn cells(values: &[u32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_be_bytes()).collect()
+}
+
+fn node(base: &Path, path: &str, properties: &[(&str, Vec<u8>)]) {
+    let dir = base.join(path.trim_start_matches('/'));
+    fs::create_dir_all(&dir).unwrap();
+    for (name, value) in properties {
+        fs::write(dir.join(name), value).unwrap();
+    }
+}
+
+#[test]
+fn lints_phandles_unit_addresses_and_bindings() {
+    let root = std::env::temp_dir().join(format!("tux-devicetree-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let base = root.join("firmware/devicetree/base");
+    node(&base, "/", &[("compatible", b"radxa,rock-5b\0".to_vec())]);
+    node(
+        &base,
+        "/clock-controller@fd7c0000",
+        &[("phandle", cells(&[1])), ("#clock-cells", cells(&[1]))],
+    );
+    node(
+        &base,
+        "/gpio@fec20000",
+        &[
+            ("phandle", cells(&[2])),
+            ("#gpio-cells", cells(&[2])),
+            ("#interrupt-cells", cells(&[2])),
+        ],
+    );
+    node(
+        &base,
+        "/i2c@fe5a0000",
+        &[
+            ("clocks", cells(&[1, 10, 1, 11])),
+            ("pinctrl-0", cells(&[9])),
+        ],
+    );
+    node(
+        &base,
+        "/i2c@fe5a0000/sensor@48",
+        &[
+            ("compatible", b"ti,tmp102\0".to_vec()),
+            ("alert-gpios", cells(&[2, 5, 0])),
+        ],
+    );
+    node(
+        &base,
+        "/i2c@fe5a0000/touch@48",
+        &[
+            ("compatible", b"goodix,gt911\0".to_vec()),
+            ("interrupts-extended", cells(&[2, 7, 8])),
+            ("reset-gpios", cells(&[1, 3, 0])),
+        ],
+    );
+    node(
+        &base,
+        "/i2c@fe5a0000/eeprom@50",
+        &[
+            ("compatible", b"atmel,24c02\0".to_vec()),
+            ("status", b"disabled\0".to_vec()),
+        ],
+    );
+
+    let rules = vec![
+        BindingRule {
+            compatible: "ti,tmp102".to_string(),
+            required: vec!["interrupts".to_string()],
+        },
+        BindingRule {
+            compatible: "goodix,*".to_string(),
+            required: vec!["interrupts".to_string()],
+        },
+        BindingRule {
+            compatible: "atmel,24c02".to_string(),
+            required: vec!["pagesize".to_string()],
+        },
+    ];
+    let warnings = devicetree::lint_in(&root, &rules).unwrap();
+    let found: Vec<(&str, &str)> = warnings.iter().map(|w| (w.path.as_str(), w.kind)).collect();
+    assert_eq!(
+        found,
+        vec![
+            ("/i2c@fe5a0000", "broken-phandle"),
+            ("/i2c@fe5a0000/sensor@48", "missing-property"),
+            ("/i2c@fe5a0000/touch@48", "broken-phandle"),
+            ("/i2c@fe5a0000/touch@48", "duplicate-unit-address"),
+        ]
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "/i2c@fe5a0000: pinctrl-0 references missing phandle 0x9 (broken-phandle)"
+    );
+    assert_eq!(
+        warnings[2].message,
+        "reset-gpios target /clock-controller@fd7c0000 has no #gpio-cells"
+    );
+    assert_eq!(
+        warnings[3].message,
+        "unit address 48 also used by /i2c@fe5a0000/sensor@48"
+    );
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn cross_references_declared_i2c_clients_with_the_audit() {
+    use std::collections::BTreeMap;
+    use std::os::unix::fs::symlink;
+    use tux_validation::device::{DeviceAddress, Subsystem, TuxBus, TuxDevice};
+
+    let root = std::env::temp_dir().join(format!("tux-dt-i2c-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let base = root.join("firmware/devicetree/base");
+    let i2c1 = "/soc/i2c@fe5a0000";
+    node(&base, i2c1, &[("#address-cells", cells(&[1]))]);
+    node(
+        &base,
+        &format!("{}/pmic@20", i2c1),
+        &[
+            ("reg", cells(&[0x20])),
+            ("compatible", b"rockchip,rk817\0".to_vec()),
+        ],
+    );
+    node(
+        &base,
+        &format!("{}/eeprom@50", i2c1),
+        &[("reg", cells(&[0x50]))],
+    );
+    node(
+        &base,
+        &format!("{}/sensor@48", i2c1),
+        &[("reg", cells(&[0x48])), ("status", b"disabled\0".to_vec())],
+    );
+    node(
+        &base,
+        &format!("{}/i2c-mux@70", i2c1),
+        &[("reg", cells(&[0x70]))],
+    );
+    node(
+        &base,
+        &format!("{}/i2c-mux@70/i2c@0", i2c1),
+        &[("reg", cells(&[0]))],
+    );
+    node(
+        &base,
+        &format!("{}/i2c-mux@70/i2c@0/codec@1a", i2c1),
+        &[("reg", cells(&[0x1a]))],
+    );
+    node(
+        &base,
+        "/soc/i2c@fe5b0000/rtc@51",
+        &[("reg", cells(&[0x51]))],
+    );
+    for (bus, path) in [
+        (1, i2c1.to_string()),
+        (5, format!("{}/i2c-mux@70/i2c@0", i2c1)),
+    ] {
+        let dir = root.join(format!("bus/i2c/devices/i2c-{}", bus));
+        fs::create_dir_all(&dir).unwrap();
+        let target = format!("../../../../firmware/devicetree/base{}", path);
+        symlink(target, dir.join("of_node")).unwrap();
+    }
+
+    let declared = devicetree::read_i2c_devices_in(&root).unwrap();
+    let addrs: Vec<(Option<u32>, u16)> = declared.iter().map(|d| (d.bus, d.addr)).collect();
+    assert_eq!(
+        addrs,
+        [
+            (Some(1), 0x50),
+            (Some(1), 0x70),
+            (Some(5), 0x1a),
+            (Some(1), 0x20),
+            (Some(1), 0x48),
+            (None, 0x51)
+        ]
+    );
+    assert_eq!(declared[3].compatible, ["rockchip,rk817"]);
+    assert!(!declared[4].enabled);
+
+    let device = |bus: u32, addr: u16, driver: Option<&str>, probed: bool| {
+        let mut device = TuxDevice::new(Subsystem::I2c, DeviceAddress::I2c { bus, addr }, "dev");
+        device.driver = driver.map(String::from);
+        device.hw_responded = probed;
+        if driver.is_some() || !probed {
+            device.sysfs_path = Some(format!("/sys/bus/i2c/devices/{}-{:04x}", bus, addr).into());
+        }
+        device
+    };
+    let bus = |bus: u32, devices| TuxBus {
+        subsystem: Subsystem::I2c,
+        id: format!("i2c-{}", bus),
+        name: String::new(),
+        devices,
+        metadata: BTreeMap::new(),
+    };
+    let buses = [
+        bus(
+            1,
+            vec![
+                device(1, 0x20, Some("rk808"), true),
+                device(1, 0x3c, None, true),
+                device(1, 0x48, None, true),
+                device(1, 0x50, None, false),
+                device(1, 0x70, Some("pca954x"), true),
+            ],
+        ),
+        bus(5, vec![]),
+    ];
+    let xref = devicetree::cross_reference_i2c(&declared, &buses);
+    let paths = |devices: &[devicetree::DtI2cDevice]| -> Vec<String> {
+        devices
+            .iter()
+            .map(|d| d.path.rsplit('/').next().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(paths(&xref.bound), ["i2c-mux@70", "pmic@20"]);
+    assert_eq!(paths(&xref.not_probed), ["eeprom@50", "codec@1a"]);
+    assert_eq!(paths(&xref.unmapped), ["rtc@51"]);
+    assert_eq!(paths(&xref.disabled), ["sensor@48"]);
+    assert_eq!(xref.undeclared, [DeviceAddress::I2c { bus: 1, addr: 0x3c }]);
+    fs::remove_dir_all(&root).unwrap();
+}
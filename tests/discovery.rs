@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+use std::fs;
+use tux_validation::device::{DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use tux_validation::discovery::{self, Discoverer, DiscoveryContext};
+use tux_validation::report;
+
+const NPU: Subsystem = Subsystem::Other("npu");
+
+/// A vendor subsystem another crate would add: one bus per accelerator core.
+struct NpuDiscoverer {
+    cores: u32,
+}
+
+impl Discoverer for NpuDiscoverer {
+    fn subsystem(&self) -> Subsystem {
+        NPU
+    }
+
+    fn discover(&self, context: &DiscoveryContext) -> anyhow::Result<Vec<TuxBus>> {
+        let firmware = fs::read_to_string(context.sys_root.join("npu_firmware"))?;
+        Ok((0..self.cores)
+            .map(|core| {
+                let address = DeviceAddress::parse(NPU, &format!("npu{}", core)).unwrap();
+                let mut device = TuxDevice::new(NPU, address, "rknpu");
+                device
+                    .attributes
+                    .insert("firmware".to_string(), firmware.trim().to_string());
+                TuxBus {
+                    subsystem: NPU,
+                    id: format!("npu{}", core),
+                    name: "rknpu".to_string(),
+                    devices: vec![device],
+                    metadata: BTreeMap::new(),
+                }
+            })
+            .collect())
+    }
+}
+
+#[test]
+fn registered_subsystems_join_the_board_model() {
+    let root = std::env::temp_dir().join(format!("tux-discovery-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("npu_firmware"), "1.6.0\n").unwrap();
+    let context = DiscoveryContext {
+        sys_root: root.clone(),
+        udev_db: root.join("udev"),
+        hw_probe: false,
+        manifest: None,
+    };
+
+    // Built-in subsystems missing from this sysfs yield no buses
+    assert!(discovery::find("spi").is_some());
+    assert_eq!(discovery::find("usb").is_some(), cfg!(feature = "usb"));
+    assert_eq!(discovery::find("i2c").is_some(), cfg!(feature = "i2c"));
+    assert!(
+        discovery::discover_board_in(&context)
+            .unwrap()
+            .buses
+            .is_empty()
+    );
+    assert_eq!(Subsystem::from_name("npu"), None);
+
+    discovery::register(NpuDiscoverer { cores: 1 });
+    discovery::register(NpuDiscoverer { cores: 2 }); // Replaces the first
+    let count = |s: Subsystem| {
+        discovery::discoverers()
+            .iter()
+            .filter(|d| d.subsystem() == s)
+            .count()
+    };
+    assert_eq!(count(NPU), 1);
+    assert_eq!(Subsystem::from_name("npu"), Some(NPU));
+
+    let board = discovery::discover_board_in(&context).unwrap();
+    let ids: Vec<&str> = board.buses.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["npu0", "npu1"]);
+    let address = DeviceAddress::Other {
+        subsystem: "npu",
+        name: "npu1".to_string(),
+    };
+    assert_eq!(
+        board.find_device(&address).unwrap().attributes["firmware"],
+        "1.6.0"
+    );
+
+    // Reports of the new subsystem round-trip like built-in ones
+    let json = report::to_json(&board.buses);
+    assert_eq!(json["buses"][0]["subsystem"], "npu");
+    assert_eq!(json["buses"][1]["devices"][0]["address"], "npu1");
+    assert_eq!(report::from_json(&json).unwrap(), board.buses);
+
+    // A failing subsystem names itself
+    fs::remove_file(root.join("npu_firmware")).unwrap();
+    let err = discovery::discover_board_in(&context).unwrap_err();
+    assert!(
+        format!("{:#}", err).starts_with("npu discovery: "),
+        "{:#}",
+        err
+    );
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::fs;
+use tux_validation::calibration::crc32;
+use tux_validation::device::{DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use tux_validation::eeprom::{self, EepromFormat};
+
+fn onie(tlvs: &[(u8, &[u8])]) -> Vec<u8> {
+    let mut area = Vec::new();
+    for (kind, value) in tlvs {
+        area.push(*kind);
+        area.push(value.len() as u8);
+        area.extend_from_slice(value);
+    }
+    let mut data = b"TlvInfo\0\x01".to_vec();
+    data.extend_from_slice(&((area.len() + 6) as u16).to_be_bytes());
+    data.extend(area);
+    data.extend([0xfe, 4]);
+    let crc = crc32(&data);
+    data.extend(crc.to_be_bytes());
+    data.resize(256, 0xff);
+    data
+}
+
+/// A FRU with only a board area, built on 2020-01-01.
+fn fru(fields: &[&str]) -> Vec<u8> {
+    let mut area = vec![0x01, 0, 0x00, 0xc0, 0x9c, 0xc0];
+    for field in fields {
+        area.push(0xc0 | field.len() as u8);
+        area.extend_from_slice(field.as_bytes());
+    }
+    area.push(0xc1);
+    area.resize((area.len() + 1).div_ceil(8) * 8, 0);
+    area[1] = (area.len() / 8) as u8;
+    let sum = area.iter().fold(0u8, |s, b| s.wrapping_add(*b));
+    *area.last_mut().unwrap() = sum.wrapping_neg();
+    let mut data = vec![0x01, 0, 0, 1, 0, 0, 0, 0];
+    data[7] = data
+        .iter()
+        .fold(0u8, |s, b| s.wrapping_add(*b))
+        .wrapping_neg();
+    data.extend(area);
+    data
+}
+
+#[test]
+fn decodes_onie_tlv() {
+    let data = onie(&[
+        (0x21, b"TUX-1000"),
+        (0x23, b"SN12345"),
+        (0x24, &[0x02, 0x42, 0xac, 0x11, 0x00, 0x02]),
+        (0x26, &[3]),
+        (0xfd, &[0x00, 0x00, 0x9a, 0x01]),
+    ]);
+    let id = eeprom::decode(&data).unwrap().unwrap();
+    assert_eq!(id.format, EepromFormat::OnieTlv);
+    assert_eq!(id.fields["product_name"], "TUX-1000");
+    assert_eq!(id.fields["serial_number"], "SN12345");
+    assert_eq!(id.fields["base_mac_address"], "02:42:ac:11:00:02");
+    assert_eq!(id.fields["device_version"], "3");
+    assert_eq!(id.fields["vendor_extension"], "00009a01");
+
+    let mut corrupt = data.clone();
+    corrupt[14] ^= 0x01;
+    let err = eeprom::decode(&corrupt).unwrap_err();
+    assert_eq!(err.to_string(), "CRC-32 mismatch");
+}
+
+#[test]
+fn decodes_ipmi_fru_board_area() {
+    let data = fru(&["Linaro", "Tux Board", "B0042", "TB-01", "", "rev=C"]);
+    let id = eeprom::decode(&data).unwrap().unwrap();
+    assert_eq!(id.format, EepromFormat::IpmiFru);
+    assert_eq!(id.fields["board_manufacturer"], "Linaro");
+    assert_eq!(id.fields["board_product_name"], "Tux Board");
+    assert_eq!(id.fields["board_serial"], "B0042");
+    assert_eq!(id.fields["board_part_number"], "TB-01");
+    assert_eq!(id.fields["board_custom_1"], "rev=C");
+    assert_eq!(id.fields["board_mfg_date"], "2020-01-01 00:00");
+    assert!(!id.fields.contains_key("board_fru_file_id"));
+
+    let mut corrupt = data.clone();
+    corrupt[12] ^= 0x20;
+    let err = eeprom::decode(&corrupt).unwrap_err();
+    assert_eq!(err.to_string(), "FRU board area checksum mismatch");
+}
+
+#[test]
+fn decodes_key_value_and_ignores_blank_parts() {
+    let mut data = b"board=tux-1000\nREVISION = C\n\n".to_vec();
+    data.resize(128, 0xff);
+    let id = eeprom::decode(&data).unwrap().unwrap();
+    assert_eq!(id.format, EepromFormat::KeyValue);
+    assert_eq!(id.fields["board"], "tux-1000");
+    assert_eq!(id.fields["revision"], "C");
+    assert_eq!(id.attributes()["eeprom_format"], "key_value");
+    assert_eq!(id.attributes()["eeprom_board"], "tux-1000");
+
+    assert_eq!(eeprom::decode(&[0xff; 256]).unwrap(), None);
+    assert_eq!(eeprom::decode(b"calibration blob\x00").unwrap(), None);
+}
+
+#[test]
+fn annotates_devices_with_an_eeprom_attribute() {
+    let root = std::env::temp_dir().join(format!("tux-eeprom-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let board = root.join("1-0050");
+    let corrupt = root.join("1-0051");
+    fs::create_dir_all(&board).unwrap();
+    fs::create_dir_all(&corrupt).unwrap();
+    fs::write(board.join("eeprom"), onie(&[(0x21, b"TUX-1000")])).unwrap();
+    fs::write(corrupt.join("eeprom"), &onie(&[(0x21, b"TUX-1000")])[..20]).unwrap();
+
+    let device = |addr: u16, dir: Option<&std::path::Path>| {
+        let mut device =
+            TuxDevice::new(Subsystem::I2c, DeviceAddress::I2c { bus: 1, addr }, "24c02");
+        device.sysfs_path = dir.map(|d| d.to_path_buf());
+        device
+    };
+    let mut bus = TuxBus {
+        subsystem: Subsystem::I2c,
+        id: "i2c-1".to_string(),
+        name: String::new(),
+        devices: vec![
+            device(0x50, Some(&board)),
+            device(0x51, Some(&corrupt)),
+            device(0x52, None),
+        ],
+        metadata: BTreeMap::new(),
+    };
+    eeprom::annotate_bus(&mut bus);
+    assert_eq!(bus.devices[0].attributes["eeprom_format"], "onie_tlv");
+    assert_eq!(bus.devices[0].attributes["eeprom_product_name"], "TUX-1000");
+    assert!(bus.devices[1].attributes["eeprom_error"].contains("truncated"));
+    assert!(bus.devices[2].attributes.is_empty());
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,73 @@
+use tux_validation::Error;
+use tux_validation::manifest::Manifest;
+use tux_validation::os_release;
+
+#[test]
+fn typed_errors_survive_anyhow() {
+    let typed = Error::PermissionDenied {
+        path: "/dev/i2c-1".into(),
+    };
+    assert_eq!(
+        typed.to_string(),
+        "Permission denied accessing /dev/i2c-1. Try sudo."
+    );
+    let wrapped = anyhow::Error::from(typed).context("i2c discovery");
+    assert!(matches!(
+        Error::of(&wrapped),
+        Some(Error::PermissionDenied { .. })
+    ));
+    assert!(matches!(
+        Error::from(wrapped),
+        Error::PermissionDenied { .. }
+    ));
+    assert!(matches!(
+        Error::from(anyhow::anyhow!("something else")),
+        Error::Other(_)
+    ));
+    assert!(Error::of(&anyhow::anyhow!("something else")).is_none());
+
+    let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+    assert!(matches!(
+        Error::io("/sys/x", denied),
+        Error::PermissionDenied { .. }
+    ));
+    let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+    assert!(matches!(Error::io("/sys/x", missing), Error::Io { .. }));
+}
+
+#[test]
+fn loading_tells_missing_files_from_bad_content() {
+    let dir = std::env::temp_dir().join(format!("tux-error-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("board.toml");
+    std::fs::write(&path, "[[i2c]]\nbus = 1\n").unwrap();
+    match Manifest::load(&path).unwrap_err() {
+        Error::ParseError { what, message } => {
+            assert_eq!(what, path.display().to_string());
+            assert!(message.contains("missing integer `address`"), "{}", message);
+        }
+        e => panic!("{:?}", e),
+    }
+    assert!(matches!(
+        Manifest::load(&dir.join("missing.toml")).unwrap_err(),
+        Error::Io { .. }
+    ));
+    assert!(matches!(
+        os_release::parse_os_release(&dir.join("os-release").to_string_lossy()).unwrap_err(),
+        Error::Io { .. }
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "i2c")]
+#[test]
+fn probing_a_missing_bus_is_bus_not_found() {
+    use tux_validation::i2c::{self, ProbeMode};
+    match i2c::probe_address(9999, 0x50, ProbeMode::QuickWrite).unwrap_err() {
+        Error::BusNotFound { bus, path } => {
+            assert_eq!(bus, "i2c-9999");
+            assert_eq!(path, "/dev/i2c-9999");
+        }
+        e => panic!("{:?}", e),
+    }
+}
@@ -0,0 +1,58 @@
+use std::fs;
+use std::os::unix::fs::symlink;
+use tux_validation::device::{Board, DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use tux_validation::export::{self, ForceBind};
+
+#[test]
+fn exports_force_binds_and_aliases() {
+    let root = std::env::temp_dir().join(format!("tux-export-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("1-0050")).unwrap();
+    fs::create_dir_all(root.join("drivers/at24")).unwrap();
+    fs::create_dir_all(root.join("module/at24")).unwrap();
+    symlink(root.join("module/at24"), root.join("drivers/at24/module")).unwrap();
+    symlink(root.join("drivers/at24"), root.join("1-0050/driver")).unwrap();
+
+    let mut eeprom = TuxDevice::new(
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 1, addr: 0x50 },
+        "24c02",
+    );
+    eeprom.sysfs_path = Some(root.join("1-0050"));
+    eeprom.driver = Some("at24".to_string());
+    eeprom.modalias = Some("of:NeepromT(null)Catmel,24c02".to_string());
+    let mut ghost = TuxDevice::new(
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 1, addr: 0x20 },
+        "Unidentified",
+    );
+    ghost.hw_responded = true;
+    let board = Board {
+        buses: vec![TuxBus {
+            subsystem: Subsystem::I2c,
+            id: "i2c-1".to_string(),
+            name: String::new(),
+            devices: vec![ghost, eeprom],
+            metadata: Default::default(),
+        }],
+        links: Vec::new(),
+    };
+
+    let rules = export::udev_rules(
+        &board,
+        &[ForceBind {
+            address: DeviceAddress::I2c { bus: 1, addr: 0x20 },
+            chip: "pca9555".to_string(),
+            module: Some("gpio-pca953x".to_string()),
+        }],
+    );
+    assert!(rules.contains("# 1-0020 (pca9555): responds to probe, not declared\n"));
+    assert!(rules.contains(
+        "KERNEL==\"i2c-1\", RUN+=\"/sbin/modprobe gpio-pca953x\", \
+         RUN+=\"/bin/sh -c 'echo pca9555 0x20 > /sys/bus/i2c/devices/i2c-1/new_device'\""
+    ));
+
+    let config = export::modprobe_config(&board);
+    assert!(config.ends_with("alias of:NeepromT(null)Catmel,24c02 at24\n"));
+    fs::remove_dir_all(&root).unwrap();
+}
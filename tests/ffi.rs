@@ -0,0 +1,27 @@
+#![cfg(feature = "ffi")]
+
+use std::ffi::{CStr, CString};
+use tux_validation::ffi;
+
+#[test]
+fn errors_are_reported_through_last_error() {
+    let version = unsafe { CStr::from_ptr(ffi::tux_version()) };
+    assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+
+    let path = CString::new("/nonexistent/board.toml").unwrap();
+    let report = unsafe { ffi::tux_run_manifest(path.as_ptr(), false) };
+    assert!(report.is_null());
+    let error = unsafe { CStr::from_ptr(ffi::tux_last_error()) };
+    assert!(error.to_str().unwrap().contains("/nonexistent/board.toml"));
+    assert_eq!(ffi::tux_last_error_kind(), ffi::TuxErrorKind::TuxErrorOther);
+
+    let bad = std::env::temp_dir().join(format!("tux-ffi-{}.toml", std::process::id()));
+    std::fs::write(&bad, "[not_a_check]\n").unwrap();
+    let path = CString::new(bad.to_string_lossy().as_bytes()).unwrap();
+    assert!(unsafe { ffi::tux_run_manifest(path.as_ptr(), false) }.is_null());
+    assert_eq!(ffi::tux_last_error_kind(), ffi::TuxErrorKind::TuxErrorParse);
+    std::fs::remove_file(&bad).unwrap();
+
+    assert!(unsafe { ffi::tux_run_manifest(std::ptr::null(), false) }.is_null());
+    unsafe { ffi::tux_string_free(std::ptr::null_mut()) };
+}
@@ -0,0 +1,50 @@
+use std::fs;
+use tux_validation::field::FieldDiagnostics;
+use tux_validation::safety::{Access, SafetyPolicy};
+
+#[test]
+fn field_run_collects_support_bundle() {
+    let base = std::env::temp_dir().join(format!("tux-field-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&base);
+    let root = base.join("root");
+    fs::create_dir_all(root.join("proc/140")).unwrap();
+    fs::write(
+        root.join("proc/cmdline"),
+        "console=ttyS2 root=/dev/mmcblk0p2\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("proc/140/stat"),
+        "140 (irq/45-mmc0) D 2 0 0 0 -1 2129984 0 0 0 0 0 0\n",
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("sys/fs/pstore")).unwrap();
+    fs::write(
+        root.join("sys/fs/pstore/dmesg-ramoops-0"),
+        "Panic#1 Part1\n<0>[  12.3] Kernel panic - not syncing: Fatal exception\n",
+    )
+    .unwrap();
+
+    let diagnostics = FieldDiagnostics::curated();
+    assert_eq!(diagnostics.policy(), SafetyPolicy::READ_ONLY);
+    let report = diagnostics.run(&root, &base.join("bundle")).unwrap();
+
+    let ids: Vec<&str> = report.findings.iter().map(|(id, _)| id.as_str()).collect();
+    assert!(ids.contains(&"kernel_threads"));
+    assert!(ids.contains(&"crash_artifacts"));
+    // No sys/class/tty in the fake root
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].0, "usb_serial");
+    assert!(base.join("bundle/system_snapshot/cmdline").exists());
+    assert!(base.join("bundle/crash_artifacts/dmesg-ramoops-0").exists());
+    assert!(report.index.exists());
+    fs::remove_dir_all(&base).unwrap();
+}
+
+#[test]
+fn read_only_policy_rejects_bus_traffic() {
+    let policy = SafetyPolicy::READ_ONLY;
+    assert!(policy.require(Access::ReadOnly, "sysfs scan").is_ok());
+    assert!(policy.require(Access::BusTraffic, "I2C hw probe").is_err());
+    assert!(SafetyPolicy::STATION.permits(Access::Write));
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "hardware")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use tux_validation::fixture::{self, FixtureIdSource};
+
+#[test]
+fn strap_bits_are_lsb_first() {
+    assert_eq!(fixture::strap_value(&[true, false, true]), 5);
+    assert_eq!(fixture::strap_value(&[]), 0);
+}
+
+#[test]
+fn selects_overlay_from_eeprom_id() {
+    let root = std::env::temp_dir().join(format!("tux-fixture-{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    let eeprom = root.join("eeprom");
+    let mut bytes = vec![0xffu8; 16];
+    bytes.extend_from_slice(b"JIG-B\0");
+    bytes.resize(64, 0xff);
+    fs::write(&eeprom, &bytes).unwrap();
+
+    let map = "# fixture -> overlay\nJIG-A = jig-a.toml\nJIG-B = jig-b.toml\n* = generic.toml\n";
+    let profiles =
+        fixture::parse_profiles_from_reader(map.as_bytes(), Path::new("/etc/tux")).unwrap();
+
+    let source = FixtureIdSource::Eeprom {
+        path: eeprom.clone(),
+        offset: 16,
+        len: 16,
+    };
+    assert_eq!(fixture::read_fixture_id(&source).unwrap(), "JIG-B");
+    assert_eq!(
+        fixture::detect_overlay(&source, &profiles).unwrap(),
+        PathBuf::from("/etc/tux/jig-b.toml")
+    );
+    assert_eq!(
+        fixture::select_profile(&profiles, "JIG-Z").unwrap().overlay,
+        PathBuf::from("/etc/tux/generic.toml")
+    );
+
+    // Erased EEPROM
+    let blank = FixtureIdSource::Eeprom {
+        path: eeprom,
+        offset: 0,
+        len: 16,
+    };
+    assert!(fixture::read_fixture_id(&blank).is_err());
+    assert!(fixture::select_profile(&profiles[..2], "JIG-Z").is_err());
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,55 @@
+use tux_validation::gpio::{self, ExpectedGpioLine, GpioChip, GpioLine};
+
+fn line(offset: u32, name: &str, consumer: Option<&str>) -> GpioLine {
+    GpioLine {
+        offset,
+        name: name.to_string(),
+        consumer: consumer.map(str::to_string),
+        used: consumer.is_some(),
+        ..Default::default()
+    }
+}
+
+fn expect(chip: &str, line: &str, consumer: &str) -> ExpectedGpioLine {
+    ExpectedGpioLine {
+        chip: chip.to_string(),
+        line: line.to_string(),
+        consumer: consumer.to_string(),
+    }
+}
+
+#[test]
+fn validates_line_consumers_by_label_name_and_offset() {
+    let chips = vec![GpioChip {
+        chip: 3,
+        label: "gpio3".to_string(),
+        lines: vec![
+            line(0, "PCIE_RESET", Some("reset")),
+            line(1, "LED_STATUS", Some("heartbeat")),
+            line(2, "", None),
+        ],
+    }];
+    let result = gpio::validate_gpio_lines(
+        &chips,
+        &[
+            expect("gpio3", "PCIE_RESET", "reset"),
+            expect("gpiochip3", "1", "led"),
+            expect("gpio3", "2", "cs"),
+            expect("gpio3", "WIFI_EN", "enable"),
+            expect("gpio7", "0", "reset"),
+        ],
+    );
+    assert_eq!(result.present, vec!["gpio3/PCIE_RESET"]);
+    assert_eq!(
+        result.mismatched,
+        vec![
+            "gpiochip3/1: held by heartbeat (expected led)",
+            "gpio3/2: not requested (expected cs)",
+        ]
+    );
+    assert_eq!(
+        result.missing,
+        vec!["gpio3/WIFI_EN: no such line", "gpio7/0: no such gpiochip"]
+    );
+    assert!(!result.is_ok());
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "hardware")]
+
+use std::fs;
+use tux_validation::device::{Board, DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use tux_validation::gpio_expander::{self, ExpectedExpander};
+
+#[test]
+fn expander_linked_to_gpiochip() {
+    let root = std::env::temp_dir().join(format!("tux-expander-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let dev_dir = root.join("1-0020");
+    fs::create_dir_all(dev_dir.join("gpiochip5")).unwrap();
+    fs::create_dir_all(dev_dir.join("gpio/gpiochip496")).unwrap();
+    fs::write(dev_dir.join("gpio/gpiochip496/base"), "496\n").unwrap();
+    fs::write(dev_dir.join("gpio/gpiochip496/ngpio"), "8\n").unwrap();
+    fs::write(dev_dir.join("gpio/gpiochip496/label"), "1-0020\n").unwrap();
+
+    // No legacy attributes, as without CONFIG_GPIO_SYSFS, and no /dev/gpiochip97 here
+    let bare_dir = root.join("1-0022");
+    fs::create_dir_all(bare_dir.join("gpiochip97")).unwrap();
+
+    let address = DeviceAddress::I2c { bus: 1, addr: 0x20 };
+    let mut expander = TuxDevice::new(Subsystem::I2c, address.clone(), "pca9555");
+    expander.sysfs_path = Some(dev_dir);
+    expander.driver = Some("pca953x".to_string());
+    let mut bare = TuxDevice::new(
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 1, addr: 0x22 },
+        "tca6416",
+    );
+    bare.sysfs_path = Some(bare_dir);
+    bare.driver = Some("pca953x".to_string());
+    let mut board = Board {
+        buses: vec![TuxBus {
+            subsystem: Subsystem::I2c,
+            id: "i2c-1".to_string(),
+            name: String::new(),
+            devices: vec![expander, bare],
+            metadata: Default::default(),
+        }],
+        links: Vec::new(),
+    };
+
+    assert_eq!(gpio_expander::link_gpiochips(&mut board), 2);
+    let chip = gpio_expander::linked_gpiochip(&board, &address).unwrap();
+    assert_eq!(chip.address, DeviceAddress::Gpio { chip: 5 });
+    assert_eq!(chip.attributes["label"], "1-0020");
+
+    let expected = [
+        ExpectedExpander {
+            bus: 1,
+            addr: 0x20,
+            base: Some(496),
+            ..Default::default()
+        },
+        ExpectedExpander {
+            bus: 1,
+            addr: 0x21,
+            ..Default::default()
+        },
+        ExpectedExpander {
+            bus: 1,
+            addr: 0x22,
+            ..Default::default()
+        },
+    ];
+    let mismatches = gpio_expander::validate_expanders(&board, &expected);
+    assert_eq!(
+        mismatches,
+        vec![
+            "1-0020: gpiochip5 has 8 lines (expected 16)",
+            "1-0021: expander not found",
+            "1-0022: gpiochip97 line count unavailable (expected 16)",
+        ]
+    );
+    fs::remove_dir_all(&root).unwrap();
+}
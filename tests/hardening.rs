@@ -0,0 +1,37 @@
+#![cfg(feature = "hardware")]
+
+use std::io::Cursor;
+use tux_validation::hardening::{self, ExpectedUser};
+
+#[test]
+fn validate_accounts_and_default_passwords() {
+    let passwd = "root:x:0:0:root:/root:/bin/sh\nweston:x:1000:1000::/home/weston:/bin/false\n";
+    let group = "root:x:0:\nvideo:x:44:weston\ninput:x:101:\n";
+    let shadow = "root::19000:0:99999:7:::\nweston:$6$abc$known:19000::::::\nsshd:!:19000::::::\n";
+
+    let users = hardening::parse_passwd_from_reader(Cursor::new(passwd)).unwrap();
+    let groups = hardening::parse_group_from_reader(Cursor::new(group)).unwrap();
+    let shadow = hardening::parse_shadow_from_reader(Cursor::new(shadow)).unwrap();
+
+    let expected = vec![ExpectedUser {
+        name: "weston".to_string(),
+        uid: Some(1000),
+        groups: vec!["video".to_string(), "input".to_string()],
+        ..Default::default()
+    }];
+    let mismatches =
+        hardening::validate_accounts(&users, &groups, &expected, &["render".to_string()]);
+    assert_eq!(
+        mismatches,
+        vec!["group render missing", "user weston: not in group input"]
+    );
+
+    let violations = hardening::check_default_passwords(&shadow, &["$6$abc$known".to_string()]);
+    assert_eq!(
+        violations,
+        vec![
+            "user root: no password set",
+            "user weston: default password hash"
+        ]
+    );
+}
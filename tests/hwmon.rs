@@ -0,0 +1,130 @@
+#![cfg(feature = "sensors")]
+
+use std::fs;
+use tux_validation::hwmon;
+use tux_validation::manifest::Manifest;
+use tux_validation::measurement::Unit;
+
+#[test]
+fn resolves_channels_by_label_instead_of_index() {
+    let root = std::env::temp_dir().join(format!("tux-hwmon-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let class = root.join("class/hwmon");
+    for (hwmon, name, label) in [
+        ("hwmon0", "ina3221", "board-rails"),
+        ("hwmon1", "ina3221", "module-rails"),
+    ] {
+        let dir = class.join(hwmon);
+        fs::create_dir_all(dir.join("device/of_node")).unwrap();
+        fs::write(dir.join("name"), format!("{}\n", name)).unwrap();
+        fs::write(dir.join("device/of_node/label"), format!("{}\0", label)).unwrap();
+    }
+    // Channel order of the second chip differs, as between kernel versions
+    fs::write(class.join("hwmon0/in1_input"), "5012\n").unwrap();
+    fs::write(class.join("hwmon0/in1_label"), "VDD_IN\n").unwrap();
+    fs::write(class.join("hwmon0/curr1_input"), "1200\n").unwrap();
+    fs::write(class.join("hwmon0/curr1_label"), "VDD_IN\n").unwrap();
+    fs::write(class.join("hwmon1/in2_input"), "812\n").unwrap();
+    fs::write(class.join("hwmon1/in2_label"), "VDD_GPU\n").unwrap();
+    fs::write(class.join("hwmon1/in1_input"), "1000\n").unwrap();
+    fs::write(class.join("hwmon1/in1_label"), "VDD_IN\n").unwrap();
+    fs::create_dir_all(class.join("hwmon2")).unwrap();
+    fs::write(class.join("hwmon2/name"), "cpu_thermal\n").unwrap();
+    fs::write(class.join("hwmon2/temp1_input"), "45000\n").unwrap();
+
+    let channels = hwmon::list_channels_in(&root).unwrap();
+    assert_eq!(channels.len(), 5);
+
+    let gpu = hwmon::resolve_channel_in(&root, "VDD_GPU").unwrap();
+    assert_eq!(gpu.input(), "hwmon1/in2_input");
+    let reading = hwmon::read_channel_in(&root, "VDD_GPU").unwrap();
+    assert_eq!(reading.value_in(Unit::Volt), Some(0.812));
+    assert_eq!(reading.source, "VDD_GPU");
+
+    let err = hwmon::resolve_channel_in(&root, "in1")
+        .unwrap_err()
+        .to_string();
+    assert_eq!(err, "no hwmon channel matches in1");
+    let err = hwmon::resolve_channel_in(&root, "VDD_IN")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("ambiguous"), "{}", err);
+    assert_eq!(
+        hwmon::resolve_channel_in(&root, "module-rails/VDD_IN")
+            .unwrap()
+            .input(),
+        "hwmon1/in1_input"
+    );
+    let temp = hwmon::read_channel_in(&root, "cpu_thermal/temp1").unwrap();
+    assert_eq!(temp.value_in(Unit::Celsius), Some(45.0));
+    assert_eq!(
+        hwmon::resolve_channel_in(&root, "hwmon2/temp1_input")
+            .unwrap()
+            .chip,
+        "cpu_thermal"
+    );
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn checks_readings_against_manifest_limits() {
+    let root = std::env::temp_dir().join(format!("tux-hwmon-limits-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let rails = root.join("class/hwmon/hwmon0");
+    let thermal = root.join("class/hwmon/hwmon1");
+    fs::create_dir_all(&rails).unwrap();
+    fs::create_dir_all(&thermal).unwrap();
+    fs::write(rails.join("name"), "ina3221\n").unwrap();
+    fs::write(rails.join("in1_input"), "5012\n").unwrap();
+    fs::write(rails.join("in1_label"), "VDD_IN\n").unwrap();
+    fs::write(rails.join("in2_input"), "712\n").unwrap();
+    fs::write(rails.join("in2_label"), "VDD_GPU\n").unwrap();
+    fs::write(rails.join("curr1_input"), "1200\n").unwrap();
+    fs::write(thermal.join("name"), "cpu_thermal\n").unwrap();
+    fs::write(thermal.join("temp1_input"), "45000\n").unwrap();
+    fs::write(thermal.join("fan1_input"), "x\n").unwrap(); // Unreadable
+
+    let devices = hwmon::find_hwmon_devices_in(&root).unwrap();
+    assert_eq!(devices.len(), 2);
+    assert_eq!(devices[0].address.to_string(), "hwmon0");
+    assert_eq!(devices[0].name, "ina3221");
+    assert_eq!(devices[0].attributes["in1"], "5.012 V");
+    assert_eq!(devices[0].attributes["in2_label"], "VDD_GPU");
+    assert_eq!(devices[0].attributes["curr1"], "1.2 A");
+    assert_eq!(devices[1].attributes["temp1"], "45 °C");
+    assert!(!devices[1].attributes.contains_key("fan1"));
+
+    let manifest = Manifest::from_toml_str(
+        r#"
+        [hwmon]
+        limits = [
+            { channel = "VDD_IN", min = 4.75, max = 5.25 },
+            { channel = "VDD_GPU", min = 0.75, max = 0.9 },
+            { channel = "ina3221/curr1", max = { channel = "cpu_thermal/temp1", curve = [[25, 1.5], [65, 0.5]] } },
+            { channel = "cpu_thermal/temp1", max = 85 },
+            { channel = "VDD_DDR", min = 1.0 },
+        ]
+        "#,
+    )
+    .unwrap();
+    let limits = hwmon::HwmonLimit::from_manifest(&manifest).unwrap();
+    assert_eq!(limits.len(), 5);
+    let problems = hwmon::validate_limits_in(&root, &limits);
+    assert_eq!(
+        problems,
+        vec![
+            "VDD_GPU: 0.712 V below 0.75 V",
+            "ina3221/curr1: 1.2 A above 1 A", // Derated to 1 A at 45 °C
+            "VDD_DDR: no hwmon channel matches VDD_DDR",
+        ]
+    );
+
+    let err = hwmon::HwmonLimit::from_manifest(
+        &Manifest::from_toml_str("[hwmon]\nlimits = [{ channel = \"VDD_IN\" }]\n").unwrap(),
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "hwmon VDD_IN: needs `min`, `max` or both");
+
+    let _ = fs::remove_dir_all(&root);
+}
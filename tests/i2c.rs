@@ -0,0 +1,607 @@
+#![cfg(feature = "i2c")]
+
+use anyhow::Result;
+use std::fs;
+use std::os::unix::fs::symlink;
+use tux_validation::device::DeviceAddress;
+use tux_validation::i2c::{self, I2cScanner};
+
+struct MockScanner;
+
+impl I2cScanner for MockScanner {
+    fn scan_hw_probe(&self) -> Result<(Vec<u16>, Vec<u16>)> {
+        Ok((vec![0x3c], vec![0x50]))
+    }
+    fn scan_sysfs(&self) -> Result<Vec<u16>> {
+        Ok(vec![0x50])
+    }
+}
+
+#[test]
+fn audit_bus_merges_udev_and_probe() {
+    let root = std::env::temp_dir().join(format!("tux-i2c-audit-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let devices = root.join("sys/bus/i2c/devices");
+    fs::create_dir_all(devices.join("i2c-1")).unwrap();
+    fs::write(devices.join("i2c-1/name"), "rk3x-i2c\n").unwrap();
+    fs::create_dir_all(devices.join("1-0050")).unwrap();
+    fs::write(
+        devices.join("1-0050/uevent"),
+        "DRIVER=at24\nOF_COMPATIBLE_0=atmel,24c02\nMODALIAS=of:NeepromT(null)Catmel,24c02\n",
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("sys/bus/i2c/drivers/at24")).unwrap();
+    symlink(
+        root.join("sys/bus/i2c/drivers/at24"),
+        devices.join("1-0050/driver"),
+    )
+    .unwrap();
+    fs::create_dir_all(devices.join("2-0050")).unwrap();
+    fs::create_dir_all(root.join("udev")).unwrap();
+    fs::write(
+        root.join("udev/+i2c:1-0050"),
+        "I:1234\nE:ID_PATH=platform-fe5a0000.i2c\n",
+    )
+    .unwrap();
+
+    let bus = i2c::audit_i2c_bus_in(&MockScanner, &root.join("sys"), &root.join("udev"), 1, true)
+        .unwrap();
+    assert_eq!(bus.id, "i2c-1");
+    assert_eq!(bus.name, "rk3x-i2c");
+    assert_eq!(bus.devices.len(), 2);
+
+    let ghost = &bus.devices[0];
+    assert_eq!(ghost.address, DeviceAddress::I2c { bus: 1, addr: 0x3c });
+    assert!(ghost.is_ghost());
+
+    let eeprom = &bus.devices[1];
+    assert_eq!(eeprom.name, "24c02");
+    assert_eq!(eeprom.driver.as_deref(), Some("at24"));
+    assert!(eeprom.in_udev && eeprom.hw_responded && !eeprom.is_ghost());
+    assert_eq!(eeprom.attributes["ID_PATH"], "platform-fe5a0000.i2c");
+    assert!(bus.metadata.is_empty());
+
+    // Channel of a mux at 1-0070
+    fs::create_dir_all(devices.join("1-0070")).unwrap();
+    fs::create_dir_all(devices.join("i2c-5")).unwrap();
+    symlink("../1-0070", devices.join("i2c-5/mux_device")).unwrap();
+    let muxed = i2c::audit_i2c_bus_in(
+        &MockScanner,
+        &root.join("sys"),
+        &root.join("udev"),
+        5,
+        false,
+    )
+    .unwrap();
+    assert_eq!(muxed.metadata["mux_device"], "1-0070");
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn validation_result_serializes() {
+    let result = i2c::validate_bus(&MockScanner, &[0x3c, 0x68], true).unwrap();
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["present"], serde_json::json!([0x3c]));
+    assert_eq!(json["missing"], serde_json::json!([0x68]));
+    assert_eq!(json["unexpected"], serde_json::json!([0x50]));
+
+    let suite = result.junit_suite(1);
+    assert_eq!(suite.name, "i2c-1");
+    assert_eq!(suite.failures(), 1);
+    assert_eq!(suite.cases[1].name, "0x68");
+}
+
+#[test]
+fn forbidden_addresses_must_be_scanned() {
+    use tux_validation::i2c::LinuxI2cScanner;
+
+    let result = i2c::validate_bus_with_forbidden(&MockScanner, &[0x3c], &[0x50], true).unwrap();
+    assert_eq!(result.forbidden, [0x50]);
+    assert!(result.unexpected.is_empty() && !result.is_ok());
+
+    // Reserved, so never scanned: this used to pass whatever is on the bus
+    let err = i2c::validate_bus_with_forbidden(&MockScanner, &[], &[0x7f], false).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("0x7f is outside the scanned range 0x08-0x77")
+    );
+    let narrow = LinuxI2cScanner::new(1).with_addresses(0x50..=0x57);
+    assert_eq!(narrow.addresses(), 0x50..=0x57);
+    let err = i2c::validate_bus_with_forbidden(&narrow, &[], &[0x68], false).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("0x68 is outside the scanned range 0x50-0x57")
+    );
+    // Never probed nor looked up, so not reported missing either
+    let err = i2c::validate_bus(&narrow, &[0x1b, 0x50], false).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Expected address 0x1b is outside the scanned range 0x50-0x57")
+    );
+}
+
+#[test]
+fn manifest_fragment_from_audit() {
+    use tux_validation::device::{Subsystem, TuxBus, TuxDevice};
+    use tux_validation::manifest_gen;
+
+    let mut eeprom = TuxDevice::new(
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 1, addr: 0x50 },
+        "24c02",
+    );
+    eeprom.sysfs_path = Some("/sys/bus/i2c/devices/1-0050".into());
+    eeprom.driver = Some("at24".to_string());
+    let mut ghost = TuxDevice::new(
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 1, addr: 0x3c },
+        "Unidentified",
+    );
+    ghost.hw_responded = true;
+    let bus = TuxBus {
+        subsystem: Subsystem::I2c,
+        id: "i2c-1".to_string(),
+        name: "rk3x-i2c".to_string(),
+        devices: vec![ghost, eeprom],
+        metadata: Default::default(),
+    };
+
+    let fragment = manifest_gen::i2c_manifest_fragment(&[bus]);
+    assert!(fragment.contains("\n# i2c-1 (rk3x-i2c)\n[[i2c]]\nbus = 1\naddress = 0x3c\n"));
+    assert!(fragment.contains("severity = \"info\"  # responds to probe"));
+    assert!(
+        fragment.contains(
+            "address = 0x50\nname = \"24c02\"\ndriver = \"at24\"\nseverity = \"error\"\n"
+        )
+    );
+}
+
+#[test]
+fn paced_probe_resumes_from_checkpoint() {
+    use std::time::Duration;
+    use tux_validation::i2c::{PacedProbeConfig, ProbeCheckpoint, ProbeOutcome};
+
+    let root = std::env::temp_dir().join(format!("tux-i2c-paced-{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    let checkpoint = root.join("i2c-3.checkpoint");
+    let config = PacedProbeConfig {
+        delay: Duration::ZERO,
+        checkpoint: Some(checkpoint.clone()),
+        addresses: i2c::DEFAULT_ADDRESSES,
+        modes: Default::default(),
+        policy: Default::default(),
+    };
+    let outcome = |addr: u16| match addr {
+        0x1a => ProbeOutcome::Bound,
+        0x50 => ProbeOutcome::Unbound,
+        _ => ProbeOutcome::Absent,
+    };
+
+    // Interrupted at 0x40
+    let err = i2c::paced_probe(3, &config, |addr| {
+        if addr == 0x40 {
+            anyhow::bail!("interrupted")
+        }
+        Ok(outcome(addr))
+    });
+    assert!(err.is_err());
+    let saved = ProbeCheckpoint::load(&checkpoint).unwrap();
+    assert_eq!(saved.next_addr, 0x40);
+    assert_eq!(saved.bound, vec![0x1a]);
+
+    // A checkpoint for another bus is refused
+    assert!(i2c::paced_probe(4, &config, |a| Ok(outcome(a))).is_err());
+
+    let mut probed = Vec::new();
+    let (unbound, bound) = i2c::paced_probe(3, &config, |addr| {
+        probed.push(addr);
+        Ok(outcome(addr))
+    })
+    .unwrap();
+    assert_eq!(probed.first(), Some(&0x40));
+    assert_eq!(unbound, vec![0x50]);
+    assert_eq!(bound, vec![0x1a]);
+    assert!(!checkpoint.exists());
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn probes_are_restricted_to_the_address_range() {
+    use std::time::Duration;
+    use tux_validation::i2c::{LinuxI2cScanner, PacedProbeConfig, ProbeOutcome};
+
+    assert_eq!(i2c::clamp_addresses(0x50..=0x57), 0x50..=0x57);
+    assert_eq!(i2c::clamp_addresses(0x00..=0x7f), i2c::DEFAULT_ADDRESSES);
+    let scanner = LinuxI2cScanner::new(1).with_addresses(0x50..=0x57);
+    assert_eq!((scanner.bus_id, scanner.addresses), (1, 0x50..=0x57));
+
+    let config = PacedProbeConfig {
+        delay: Duration::ZERO,
+        checkpoint: None,
+        addresses: 0x50..=0x57,
+        modes: Default::default(),
+        policy: Default::default(),
+    };
+    let mut probed = Vec::new();
+    let (unbound, _) = i2c::paced_probe(1, &config, |addr| {
+        probed.push(addr);
+        Ok(if addr == 0x50 || addr == 0x1a {
+            ProbeOutcome::Unbound
+        } else {
+            ProbeOutcome::Absent
+        })
+    })
+    .unwrap();
+    assert_eq!(probed, (0x50..=0x57).collect::<Vec<u16>>());
+    assert_eq!(unbound, vec![0x50]);
+}
+
+#[test]
+fn eeprom_ranges_are_probed_with_receive_byte() {
+    use tux_validation::i2c::{LinuxI2cScanner, ProbeMode, ProbeModes};
+
+    let modes = ProbeModes::default();
+    assert_eq!(modes.mode_for(0x1a), ProbeMode::QuickWrite);
+    assert_eq!(modes.mode_for(0x30), ProbeMode::ReadByte);
+    assert_eq!(modes.mode_for(0x38), ProbeMode::QuickWrite);
+    assert_eq!(modes.mode_for(0x5f), ProbeMode::ReadByte);
+    assert_eq!(modes.mode_for(0x60), ProbeMode::QuickWrite);
+    assert_eq!(modes.mode_for(0x68), ProbeMode::ReadByte); // DS1307 and friends
+    assert_eq!(modes.mode_for(0x6f), ProbeMode::ReadByte);
+    assert_eq!(modes.mode_for(0x70), ProbeMode::QuickWrite);
+
+    let scanner = LinuxI2cScanner::new(1)
+        .with_probe_mode(0x50..=0x57, ProbeMode::QuickWrite)
+        .with_probe_mode(0x68..=0x68, ProbeMode::ReadByte);
+    assert_eq!(scanner.modes.mode_for(0x50), ProbeMode::QuickWrite);
+    assert_eq!(scanner.modes.mode_for(0x58), ProbeMode::ReadByte);
+    assert_eq!(scanner.modes.mode_for(0x68), ProbeMode::ReadByte);
+    assert_eq!(
+        ProbeModes::uniform(ProbeMode::ReadByte).mode_for(0x1a),
+        ProbeMode::ReadByte
+    );
+}
+
+fn stat(
+    addr: u16,
+    outcome: i2c::ProbeOutcome,
+    errnos: &[nix::errno::Errno],
+    us: u64,
+) -> i2c::ProbeStat {
+    i2c::ProbeStat {
+        addr,
+        outcome,
+        attempts: errnos.len() as u32 + 1,
+        errnos: errnos.iter().map(|e| *e as i32).collect(),
+        latency: std::time::Duration::from_micros(us),
+    }
+}
+
+#[test]
+fn bus_health_from_probe_stats() {
+    use i2c::{BusHealth, ProbeOutcome::*};
+    use nix::errno::Errno::{EAGAIN, ETIMEDOUT};
+
+    let clean: Vec<_> = (0x08..=0x77).map(|a| stat(a, Absent, &[], 120)).collect();
+    let report = i2c::assess_health(&clean);
+    assert_eq!(report.health, BusHealth::Good);
+    assert_eq!(report.transactions, 112);
+
+    let mut flaky = clean.clone();
+    flaky[3] = stat(0x0b, Absent, &[EAGAIN], 130);
+    let report = i2c::assess_health(&flaky);
+    assert_eq!(report.health, BusHealth::Marginal);
+    assert_eq!(report.retried, vec![0x0b]);
+
+    let mut weak = clean.clone();
+    for s in weak.iter_mut().take(4) {
+        *s = stat(s.addr, Absent, &[ETIMEDOUT, ETIMEDOUT], 25_000);
+    }
+    let report = i2c::assess_health(&weak);
+    assert_eq!(report.health, BusHealth::Bad);
+    assert_eq!(report.errors[&(ETIMEDOUT as i32)], 8);
+
+    struct StatsScanner(Vec<i2c::ProbeStat>);
+    impl I2cScanner for StatsScanner {
+        fn scan_hw_probe(&self) -> Result<(Vec<u16>, Vec<u16>)> {
+            unreachable!("probe_stats takes precedence")
+        }
+        fn scan_sysfs(&self) -> Result<Vec<u16>> {
+            Ok(Vec::new())
+        }
+        fn probe_stats(&self) -> Result<Vec<i2c::ProbeStat>> {
+            Ok(self.0.clone())
+        }
+    }
+    let root = std::env::temp_dir().join(format!("tux-i2c-health-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("sys/bus/i2c/devices/i2c-3")).unwrap();
+    fs::create_dir_all(root.join("udev")).unwrap();
+    weak[10] = stat(0x12, Unbound, &[], 110);
+    let bus = i2c::audit_i2c_bus_in(
+        &StatsScanner(weak),
+        &root.join("sys"),
+        &root.join("udev"),
+        3,
+        true,
+    )
+    .unwrap();
+    assert_eq!(bus.metadata["health"], "bad");
+    assert_eq!(bus.metadata["health_errors"], "ETIMEDOUT=8");
+    assert_eq!(bus.devices.len(), 1);
+    assert!(bus.devices[0].hw_responded);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn manifest_overrides_probe_modes() {
+    use tux_validation::i2c::{ProbeMode, ProbeModes};
+    use tux_validation::manifest::Manifest;
+
+    let manifest = Manifest::from_toml_str(
+        r#"
+[i2c_probe]
+ranges = [
+    { start = 0x68, end = 0x68, mode = "skip" },
+    { bus = 3, start = 0x50, end = 0x57, mode = "quick_write" },
+]
+"#,
+    )
+    .unwrap();
+    let bus1 = ProbeModes::from_manifest(&manifest, 1).unwrap();
+    assert_eq!(bus1.mode_for(0x68), ProbeMode::Skip);
+    assert_eq!(bus1.mode_for(0x50), ProbeMode::ReadByte); // The EEPROM default
+    let bus3 = ProbeModes::from_manifest(&manifest, 3).unwrap();
+    assert_eq!(bus3.mode_for(0x50), ProbeMode::QuickWrite);
+    assert_eq!(bus3.mode_for(0x58), ProbeMode::ReadByte);
+    assert_eq!(
+        ProbeModes::from_manifest(&Manifest::default(), 1).unwrap(),
+        ProbeModes::default()
+    );
+    for (range, _, mode) in i2c::ADDRESS_CLASSES {
+        assert!(
+            range
+                .clone()
+                .all(|addr| ProbeModes::default().mode_for(addr) == mode)
+        );
+    }
+
+    let bad = Manifest::from_toml_str(
+        "[i2c_probe]\nranges = [{ start = 0x68, end = 0x68, mode = \"write\" }]\n",
+    )
+    .unwrap();
+    let err = ProbeModes::from_manifest(&bad, 1).unwrap_err();
+    assert!(err.to_string().contains("quick_write, read_byte or skip"));
+    // Skipped addresses are never put on the bus, not even for a bus that doesn't exist
+    assert_eq!(
+        i2c::probe_address(9999, 0x68, ProbeMode::Skip).unwrap(),
+        i2c::ProbeOutcome::Absent
+    );
+}
+
+#[test]
+fn buses_are_scanned_concurrently_except_behind_a_mux() {
+    use std::os::unix::fs::symlink;
+    use std::sync::Mutex;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let root = std::env::temp_dir().join(format!("tux-i2c-parallel-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let devices = root.join("bus/i2c/devices");
+    for bus in [0, 1, 2, 3, 4, 5] {
+        fs::create_dir_all(devices.join(format!("i2c-{}", bus))).unwrap();
+    }
+    // i2c-3 and i2c-4 are channels of a mux on i2c-1; i2c-5 hangs off a mux on i2c-3
+    fs::create_dir_all(devices.join("1-0070")).unwrap();
+    fs::create_dir_all(devices.join("3-0071")).unwrap();
+    symlink("../1-0070", devices.join("i2c-3/mux_device")).unwrap();
+    symlink("../1-0070", devices.join("i2c-4/mux_device")).unwrap();
+    symlink("../3-0071", devices.join("i2c-5/mux_device")).unwrap();
+    assert_eq!(i2c::root_bus_in(&root, 5), 1);
+    assert_eq!(i2c::root_bus_in(&root, 2), 2);
+
+    // Bus 0 only finishes once bus 2 has started, which a serial scan would never do
+    let (started, wait) = mpsc::channel();
+    let wait = Mutex::new(wait);
+    let active = Mutex::new(Vec::new());
+    let mux_overlap = Mutex::new(false);
+    let bus_ids = [5, 0, 4, 1, 2, 3];
+    let scanned = i2c::scan_buses_in_parallel(&root, &bus_ids, |bus_id| {
+        let root_bus = i2c::root_bus_in(&root, bus_id);
+        {
+            let mut active = active.lock().unwrap();
+            if active.contains(&root_bus) {
+                *mux_overlap.lock().unwrap() = true;
+            }
+            active.push(root_bus);
+        }
+        match bus_id {
+            0 => wait
+                .lock()
+                .unwrap()
+                .recv_timeout(Duration::from_secs(10))
+                .map_err(|_| anyhow::anyhow!("bus 2 never started"))?,
+            2 => started.send(()).unwrap(),
+            _ => std::thread::sleep(Duration::from_millis(5)),
+        }
+        active.lock().unwrap().retain(|b| *b != root_bus);
+        Ok(format!("i2c-{}", bus_id))
+    })
+    .unwrap();
+    assert_eq!(
+        scanned,
+        ["i2c-5", "i2c-0", "i2c-4", "i2c-1", "i2c-2", "i2c-3"]
+    );
+    assert!(!*mux_overlap.lock().unwrap());
+
+    let err = i2c::scan_buses_in_parallel(&root, &[2, 4, 3], |bus_id| {
+        if bus_id == 2 {
+            Ok(())
+        } else {
+            anyhow::bail!("i2c-{} failed", bus_id)
+        }
+    })
+    .unwrap_err();
+    assert_eq!(err.to_string(), "i2c-4 failed");
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn adapter_counter_deltas_flag_erroring_buses() {
+    use std::path::PathBuf;
+
+    let root = std::env::temp_dir().join(format!("tux-i2c-stats-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let sys = root.join("sys");
+    let stats = sys.join("bus/i2c/devices/i2c-2/statistics");
+    fs::create_dir_all(&stats).unwrap();
+    fs::create_dir_all(sys.join("bus/i2c/devices/i2c-4")).unwrap();
+    fs::create_dir_all(root.join("udev")).unwrap();
+    fs::write(stats.join("transfers"), "100\n").unwrap();
+    fs::write(stats.join("nacks"), "3\n").unwrap();
+    fs::write(stats.join("timeouts"), "0\n").unwrap();
+    assert_eq!(i2c::AdapterStats::read_in(&sys, 4), None);
+
+    // Probing moves the counters as a driver on a noisy bus would
+    struct NoisyScanner(PathBuf);
+    impl I2cScanner for NoisyScanner {
+        fn scan_hw_probe(&self) -> Result<(Vec<u16>, Vec<u16>)> {
+            fs::write(self.0.join("transfers"), "220\n")?;
+            fs::write(self.0.join("timeouts"), "2\n")?;
+            Ok((Vec::new(), Vec::new()))
+        }
+        fn scan_sysfs(&self) -> Result<Vec<u16>> {
+            Ok(Vec::new())
+        }
+    }
+    let (_, delta) =
+        i2c::with_adapter_stats_in(&sys, 2, || NoisyScanner(stats.clone()).scan_hw_probe())
+            .unwrap();
+    let delta = delta.unwrap();
+    assert_eq!(delta.counters["transfers"], 120);
+    assert_eq!(delta.counters["nacks"], 0);
+    assert_eq!(delta.errors(), 2);
+    assert_eq!(delta.transfers(), 120);
+
+    // A reset counter counts from zero
+    let before = i2c::AdapterStats::read_in(&sys, 2).unwrap();
+    fs::write(stats.join("transfers"), "5\n").unwrap();
+    let after = i2c::AdapterStats::read_in(&sys, 2).unwrap();
+    assert_eq!(after.since(&before).counters["transfers"], 5);
+
+    fs::write(stats.join("timeouts"), "0\n").unwrap();
+    let bus = i2c::audit_i2c_bus_in(
+        &NoisyScanner(stats.clone()),
+        &sys,
+        &root.join("udev"),
+        2,
+        true,
+    )
+    .unwrap();
+    assert_eq!(bus.metadata["stats_errors"], "2");
+    assert_eq!(
+        bus.metadata["stats_warning"],
+        "2 adapter errors in 215 transfers"
+    );
+    let quiet = i2c::audit_i2c_bus_in(
+        &NoisyScanner(stats.clone()),
+        &sys,
+        &root.join("udev"),
+        2,
+        false,
+    )
+    .unwrap();
+    assert_eq!(quiet.metadata["stats_errors"], "0");
+    assert!(!quiet.metadata.contains_key("stats_warning"));
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn probe_policy_retries_slow_waking_devices() {
+    use std::time::Duration;
+    use tux_validation::i2c::{ProbeModes, ProbePolicy};
+    use tux_validation::manifest::Manifest;
+
+    // A part that NACKs its first two addressings while it wakes up
+    let wakes_after = |nacks: u32| {
+        let mut calls = 0;
+        move || {
+            calls += 1;
+            calls > nacks
+        }
+    };
+    assert!(!ProbePolicy::default().attempt(wakes_after(2)));
+    let policy = ProbePolicy {
+        retries: 2,
+        retry_delay: Duration::ZERO,
+        per_transfer_timeout: None,
+    };
+    assert!(policy.attempt(wakes_after(2)));
+    assert!(!policy.attempt(wakes_after(3)));
+
+    let manifest = Manifest::from_toml_str(
+        "[i2c_probe]\nretries = 3\nretry_delay = \"5ms\"\nper_transfer_timeout = \"25ms\"\n",
+    )
+    .unwrap();
+    let policy = ProbePolicy::from_manifest(&manifest).unwrap();
+    assert_eq!(policy.retries, 3);
+    assert_eq!(policy.retry_delay, Duration::from_millis(5));
+    assert_eq!(policy.per_transfer_timeout, Some(Duration::from_millis(25)));
+    // A section with only a policy leaves the probe modes alone
+    assert_eq!(
+        ProbeModes::from_manifest(&manifest, 1).unwrap(),
+        ProbeModes::default()
+    );
+    assert_eq!(
+        ProbePolicy::from_manifest(&Manifest::default()).unwrap(),
+        ProbePolicy::default()
+    );
+    let bad = Manifest::from_toml_str("[i2c_probe]\nretries = \"many\"\n").unwrap();
+    assert!(ProbePolicy::from_manifest(&bad).is_err());
+}
+
+#[test]
+fn probe_stats_follow_the_probe_policy() {
+    use nix::errno::Errno;
+    use std::time::Duration;
+    use tux_validation::i2c::{ProbeOutcome, ProbePolicy};
+
+    let nack = Errno::ENXIO as i32;
+    let replies = |script: Vec<Result<(), i32>>| {
+        let mut script = script.into_iter();
+        move || script.next().unwrap_or(Err(Errno::ENXIO as i32))
+    };
+    let policy = ProbePolicy {
+        retries: 4,
+        retry_delay: Duration::ZERO,
+        per_transfer_timeout: None,
+    };
+    // A part asleep for four addressings answers the fifth with the configured retries
+    let stat = i2c::probe_stat_with(
+        0x68,
+        &policy,
+        replies(vec![Err(nack), Err(nack), Err(nack), Err(nack), Ok(())]),
+    );
+    assert_eq!(stat.outcome, ProbeOutcome::Unbound);
+    assert_eq!(stat.attempts, 5);
+    assert!(stat.errnos.is_empty());
+    let asleep = i2c::probe_stat_with(0x68, &policy, replies(Vec::new()));
+    assert_eq!((asleep.outcome, asleep.attempts), (ProbeOutcome::Absent, 5));
+
+    // Without retries a NACK is final, but electrical errors still get the health attempts
+    let once = ProbePolicy {
+        retry_delay: Duration::ZERO,
+        ..ProbePolicy::default()
+    };
+    let absent = i2c::probe_stat_with(0x20, &once, replies(vec![Err(nack), Ok(())]));
+    assert_eq!((absent.outcome, absent.attempts), (ProbeOutcome::Absent, 1));
+    let timeout = Errno::ETIMEDOUT as i32;
+    let noisy = i2c::probe_stat_with(0x20, &once, replies(vec![Err(timeout), Ok(())]));
+    assert_eq!((noisy.outcome, noisy.attempts), (ProbeOutcome::Unbound, 2));
+    assert_eq!(noisy.errnos, [timeout]);
+    assert_eq!(
+        i2c::probe_stat_with(0x20, &once, replies(vec![Err(timeout); 5])).attempts,
+        i2c::HEALTH_PROBE_ATTEMPTS
+    );
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use tux_validation::device::{DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use tux_validation::incremental::{self, Scope};
+
+const MONITOR: &str = "\
+monitor will print the received events for:
+KERNEL - the kernel uevent
+
+KERNEL[1523.114020] add      /devices/platform/fe5a0000.i2c/i2c-1/1-0068 (i2c)
+UDEV  [1523.120331] bind     /devices/platform/fe5a0000.i2c/i2c-1/1-0068 (i2c)
+KERNEL[1523.200100] remove   /devices/platform/fe5a0000.i2c/i2c-1/1-0070/i2c-5 (i2c)
+KERNEL[1524.000000] add      /devices/platform/fc800000.usb/usb1/1-1 (usb)
+";
+
+fn bus(id: &str, devices: Vec<TuxDevice>) -> TuxBus {
+    TuxBus {
+        subsystem: Subsystem::I2c,
+        id: id.to_string(),
+        name: String::new(),
+        devices,
+        metadata: BTreeMap::new(),
+    }
+}
+
+fn previous() -> Vec<TuxBus> {
+    let eeprom = TuxDevice::new(
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 1, addr: 0x50 },
+        "eeprom",
+    );
+    vec![
+        bus("i2c-1", vec![eeprom]),
+        bus("i2c-2", vec![]),
+        bus("i2c-5", vec![]),
+    ]
+}
+
+#[test]
+fn events_select_affected_buses() {
+    let events = incremental::parse_udevadm_monitor(MONITOR);
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[1].action, "bind");
+    assert_eq!(events[3].subsystem, "usb");
+
+    let scope = Scope {
+        events,
+        subsystems: Vec::new(),
+    };
+    let buses: Vec<u32> = incremental::affected_i2c_buses(&previous(), &scope)
+        .into_iter()
+        .collect();
+    assert_eq!(buses, vec![1, 5]);
+
+    let all = Scope {
+        events: Vec::new(),
+        subsystems: vec![Subsystem::I2c],
+    };
+    assert_eq!(incremental::affected_i2c_buses(&previous(), &all).len(), 3);
+
+    let merged = incremental::merge(
+        &previous(),
+        vec![bus("i2c-1", vec![]), bus("i2c-7", vec![])],
+        &["i2c-5".to_string()],
+    );
+    let ids: Vec<&str> = merged.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["i2c-1", "i2c-2", "i2c-7"]);
+    assert!(merged[0].devices.is_empty());
+}
+
+#[cfg(feature = "i2c")]
+#[test]
+fn revalidate_reaudits_only_affected_buses() {
+    use anyhow::Result;
+    use std::fs;
+    use tux_validation::i2c::I2cScanner;
+
+    struct NoProbe;
+    impl I2cScanner for NoProbe {
+        fn scan_hw_probe(&self) -> Result<(Vec<u16>, Vec<u16>)> {
+            Ok((Vec::new(), Vec::new()))
+        }
+        fn scan_sysfs(&self) -> Result<Vec<u16>> {
+            Ok(Vec::new())
+        }
+    }
+
+    let root = std::env::temp_dir().join(format!("tux-incremental-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let devices = root.join("sys/bus/i2c/devices");
+    for dir in ["i2c-1", "i2c-2", "1-0050", "1-0068"] {
+        fs::create_dir_all(devices.join(dir)).unwrap();
+    }
+    fs::create_dir_all(root.join("udev")).unwrap();
+
+    let scope = Scope {
+        events: incremental::parse_udevadm_monitor(MONITOR),
+        subsystems: Vec::new(),
+    };
+    let updated = incremental::revalidate_in(
+        &previous(),
+        &scope,
+        &root.join("sys"),
+        &root.join("udev"),
+        |_| NoProbe,
+        false,
+    )
+    .unwrap();
+    let ids: Vec<&str> = updated.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["i2c-1", "i2c-2"]);
+    assert_eq!(updated[0].devices.len(), 2);
+    fs::remove_dir_all(&root).unwrap();
+}
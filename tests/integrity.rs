@@ -0,0 +1,56 @@
+use ed25519_dalek::{Signer, SigningKey};
+use std::fs;
+use std::path::PathBuf;
+use tux_validation::integrity;
+
+#[test]
+fn signed_manifest_detects_corruption() {
+    let root = std::env::temp_dir().join(format!("tux-integrity-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("boot")).unwrap();
+    fs::write(root.join("boot/Image"), b"kernel").unwrap();
+    fs::write(root.join("boot/board.dtb"), b"corrupted dtb").unwrap();
+
+    let kernel_hash = integrity::sha256_file(&root.join("boot/Image")).unwrap();
+    let manifest = format!(
+        "{}  /boot/Image\n{}  /boot/board.dtb\n{} */lib/libc.so.6\n",
+        kernel_hash,
+        "0".repeat(64),
+        "1".repeat(64)
+    );
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+    let signature = key.sign(manifest.as_bytes()).to_bytes();
+    let public_key = key.verifying_key().to_bytes();
+    fs::write(root.join("manifest.sha256"), &manifest).unwrap();
+    fs::write(root.join("manifest.sig"), signature).unwrap();
+
+    assert!(
+        integrity::verify_signature(b"tampered", &signature, &public_key).is_err(),
+        "signature must not verify other content"
+    );
+    let entries = integrity::load_signed_manifest(
+        &root.join("manifest.sha256"),
+        &root.join("manifest.sig"),
+        &public_key,
+    )
+    .unwrap();
+    assert_eq!(entries.len(), 3);
+
+    let result = integrity::check_files(&root, &entries, &[]);
+    assert_eq!(result.verified, vec![PathBuf::from("/boot/Image")]);
+    assert_eq!(result.missing, vec![PathBuf::from("/lib/libc.so.6")]);
+    assert_eq!(result.mismatched.len(), 1);
+    assert!(!result.is_ok());
+
+    let critical = [
+        PathBuf::from("/boot/Image"),
+        PathBuf::from("/boot/u-boot.itb"),
+    ];
+    let result = integrity::check_files(&root, &entries, &critical);
+    assert_eq!(result.verified.len(), 1);
+    assert_eq!(
+        result.mismatched,
+        vec!["/boot/u-boot.itb: not covered by manifest"]
+    );
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "journald")]
+
+use std::io::Cursor;
+use std::path::PathBuf;
+use tux_validation::journal::{self, JournalRule};
+
+#[test]
+fn scan_journal_for_device_errors_and_failed_units() {
+    let mock_journal = r#"{"MESSAGE":"at24 1-0050: probe failed -6","PRIORITY":"3","_KERNEL_DEVICE":"+i2c:1-0050","__REALTIME_TIMESTAMP":"1700000000000000"}
+{"MESSAGE":"Started Weston.","PRIORITY":"6","_SYSTEMD_UNIT":"init.scope"}
+{"MESSAGE":"app-agent.service: Failed with result 'exit-code'.","PRIORITY":"4","MESSAGE_ID":"be02cf6855d2428ba40df7e9d022f03d","_SYSTEMD_UNIT":"init.scope","UNIT":"app-agent.service"}
+{"MESSAGE":"kiosk.service: Failed with result 'signal'.","PRIORITY":"4","MESSAGE_ID":"be02cf6855d2428ba40df7e9d022f03d","_SYSTEMD_UNIT":"user@1000.service","USER_UNIT":"kiosk.service"}
+"#;
+    let entries = journal::parse_journal_json_from_reader(Cursor::new(mock_journal)).unwrap();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(entries[1].unit.as_deref(), Some("init.scope"));
+
+    let rules = vec![JournalRule {
+        id: "probe-failed".to_string(),
+        pattern: "probe failed".to_string(),
+        max_priority: Some(3),
+        unit: None,
+    }];
+    let findings = journal::scan_entries(&entries, &rules);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(
+        findings[0].device,
+        Some(PathBuf::from("/sys/bus/i2c/devices/1-0050"))
+    );
+    assert_eq!(
+        journal::findings_for_device(&findings, "/sys/bus/i2c/devices/1-0050").len(),
+        1
+    );
+    assert_eq!(
+        journal::failed_units(&entries),
+        vec!["app-agent.service", "kiosk.service"]
+    );
+}
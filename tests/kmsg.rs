@@ -0,0 +1,75 @@
+use tux_validation::device::{DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use tux_validation::kmsg::{self, Issue};
+
+const RECORDS: &str = "\
+6,339,5140900,-;Run /sbin/init as init process
+3,812,2184625,-;at24 1-0050: probe with driver at24 failed with error -517
+ SUBSYSTEM=i2c
+ DEVICE=+i2c:1-0050
+3,813,2190011,-;rk3x-i2c fe5a0000.i2c: timeout, ipd: 0x10, state: 1
+4,814,2200000,-;pca953x 2-0020: failed writing register
+ SUBSYSTEM=i2c
+ DEVICE=+i2c:2-0020
+4,815,3000000,-;WARNING: CPU: 0 PID: 42 at drivers/regulator/core.c:2396 _regulator_put+0x40/0x50
+6,816,3100000,-;rtc-pcf8563 0-0051: registered as rtc0
+";
+
+#[test]
+fn classifies_kmsg_records() {
+    let records = kmsg::parse_records(RECORDS);
+    assert_eq!(records.len(), 6);
+    assert_eq!(records[1].level, 3);
+    assert_eq!(records[1].device.as_deref(), Some("1-0050"));
+    assert_eq!(records[2].device.as_deref(), Some("fe5a0000.i2c"));
+    assert_eq!(records[0].device, None);
+
+    let findings = kmsg::findings(&records);
+    let issues: Vec<Issue> = findings.iter().map(|f| f.issue).collect();
+    assert_eq!(
+        issues,
+        [
+            Issue::ProbeDeferred,
+            Issue::TransferFailed,
+            Issue::TransferFailed,
+            Issue::Warning,
+        ]
+    );
+    assert_eq!(findings[0].error, Some(-517));
+    assert_eq!(
+        findings[0].to_string(),
+        "[    2.184625] at24 1-0050: probe with driver at24 failed with error -517 (EPROBE_DEFER)"
+    );
+
+    // Plain dmesg output parses too
+    let dmesg = kmsg::parse_records("[    2.184625] at24 1-0050: probe failed with error -19\n");
+    let findings = kmsg::findings(&dmesg);
+    assert_eq!(findings[0].issue, Issue::ProbeFailed);
+    assert!(findings[0].to_string().ends_with("(ENODEV)"));
+}
+
+#[test]
+fn attaches_findings_to_devices() {
+    let findings = kmsg::findings(&kmsg::parse_records(RECORDS));
+    let device = |bus, addr| TuxDevice::new(Subsystem::I2c, DeviceAddress::I2c { bus, addr }, "");
+    let mut buses = vec![TuxBus {
+        subsystem: Subsystem::I2c,
+        id: "i2c-2".to_string(),
+        name: String::new(),
+        devices: vec![device(2, 0x20), device(0, 0x51)],
+        metadata: Default::default(),
+    }];
+    kmsg::attach(&mut buses, &findings);
+    assert_eq!(
+        buses[0].devices[0].kernel_log,
+        ["[    2.200000] pca953x 2-0020: failed writing register"]
+    );
+    assert!(buses[0].devices[1].kernel_log.is_empty());
+
+    // A missing device has no TuxDevice, but the log still explains it
+    let missing = DeviceAddress::I2c { bus: 1, addr: 0x50 };
+    assert_eq!(kmsg::for_device(&findings, &missing).len(), 1);
+
+    let json = buses[0].devices[0].to_json();
+    assert_eq!(TuxDevice::from_json(&json).unwrap(), buses[0].devices[0]);
+    assert!(buses[0].devices[1].to_json().get("kernel_log").is_none());
+}
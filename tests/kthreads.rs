@@ -0,0 +1,60 @@
+use std::fs;
+use tux_validation::kthreads::{self, ExpectedKthread, KernelThread};
+
+fn write_stat(proc_dir: &std::path::Path, pid: u32, comm: &str, state: char, flags: u64) {
+    let dir = proc_dir.join(pid.to_string());
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("stat"),
+        format!(
+            "{} ({}) {} 2 0 0 0 -1 {} 0 0 0 0 0 0\n",
+            pid, comm, state, flags
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn finds_missing_and_stuck_kernel_threads() {
+    let root = std::env::temp_dir().join(format!("tux-kthreads-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    write_stat(&root, 12, "ksoftirqd/0", 'S', 0x0020_8040);
+    write_stat(&root, 140, "irq/45-mmc0", 'D', 0x0020_8040);
+    write_stat(&root, 812, "app (main)", 'S', 0x0040_0100);
+    fs::write(root.join("uptime"), "1.0 1.0\n").unwrap();
+
+    let threads = kthreads::list_kernel_threads_in(&root).unwrap();
+    let names: Vec<&str> = threads.iter().map(|t| t.comm.as_str()).collect();
+    assert_eq!(names, vec!["ksoftirqd/0", "irq/45-mmc0"]);
+
+    let earlier = vec![KernelThread {
+        pid: 140,
+        comm: "irq/45-mmc0".to_string(),
+        state: 'D',
+    }];
+    let blocked = kthreads::blocked_in_both(&earlier, &threads);
+    let hung = kthreads::hung_tasks_from_kmsg(
+        "3,812,245100000,-;INFO: task irq/45-mmc0:140 blocked for more than 120 seconds.\n",
+    );
+    assert_eq!(hung, vec![("irq/45-mmc0".to_string(), 140)]);
+
+    let expected = [
+        ExpectedKthread {
+            name: "ksoftirqd/*".to_string(),
+            min_count: 2,
+        },
+        ExpectedKthread {
+            name: "irq/45-mmc0".to_string(),
+            min_count: 1,
+        },
+    ];
+    assert_eq!(
+        kthreads::validate_kthreads(&threads, &blocked, &hung, &expected),
+        vec![
+            "ksoftirqd/*: 1 thread(s) found (expected at least 2)",
+            "irq/45-mmc0 (pid 140): stuck in uninterruptible sleep",
+            "irq/45-mmc0 (pid 140): reported by the hung task detector",
+        ]
+    );
+    fs::remove_dir_all(&root).unwrap();
+}
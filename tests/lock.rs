@@ -0,0 +1,58 @@
+use std::fs;
+use tux_validation::lock::{Resource, ResourceLock};
+
+#[test]
+fn bus_and_global_locks_exclude_each_other() {
+    let dir = std::env::temp_dir().join(format!("tux-lock-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let pid = std::process::id();
+
+    let bus3 = ResourceLock::acquire_in(&dir, Resource::I2cBus(3)).unwrap();
+    let bus4 = ResourceLock::acquire_in(&dir, Resource::I2cBus(4)).unwrap();
+    let err = ResourceLock::acquire_in(&dir, Resource::I2cBus(3)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("i2c-3 is busy, held by PID {}", pid)
+    );
+    let err = ResourceLock::acquire_in(&dir, Resource::Global).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "global lock is busy, held by PID {} (i2c-3), PID {} (i2c-4)",
+            pid, pid
+        )
+    );
+    drop(bus3);
+    drop(bus4);
+
+    let global = ResourceLock::acquire_in(&dir, Resource::Global).unwrap();
+    let err = ResourceLock::acquire_in(&dir, Resource::I2cBus(3)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("i2c-3 is busy, held by PID {} (global lock)", pid)
+    );
+    drop(global);
+    assert!(ResourceLock::acquire_in(&dir, Resource::I2cBus(3)).is_ok());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn lock_io_errors_are_typed() {
+    use tux_validation::error::Error;
+
+    // A file where the lock directory should be
+    let path = std::env::temp_dir().join(format!("tux-lock-file-{}", std::process::id()));
+    fs::write(&path, "").unwrap();
+    let err = ResourceLock::acquire_in(&path.join("locks"), Resource::I2cBus(1)).unwrap_err();
+    assert!(matches!(Error::of(&err), Some(Error::Io { path: p, .. }) if *p == path.join("locks")));
+    fs::remove_file(&path).unwrap();
+
+    // SAFETY: no other test of this binary reads the environment
+    unsafe { std::env::set_var("XDG_RUNTIME_DIR", "/run/user/1000") };
+    assert_eq!(
+        tux_validation::lock::user_lock_dir(),
+        Some(std::path::PathBuf::from("/run/user/1000/tux-validation"))
+    );
+    unsafe { std::env::set_var("XDG_RUNTIME_DIR", "") };
+    assert_eq!(tux_validation::lock::user_lock_dir(), None);
+}
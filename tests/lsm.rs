@@ -0,0 +1,42 @@
+use std::fs;
+use tux_validation::lsm::{self, Lsm, LsmRule};
+
+#[test]
+fn apparmor_profiles_must_be_enforcing() {
+    let sys = std::env::temp_dir().join(format!("tux-lsm-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&sys);
+    fs::create_dir_all(sys.join("kernel/security/apparmor")).unwrap();
+    fs::write(
+        sys.join("kernel/security/lsm"),
+        "lockdown,capability,apparmor",
+    )
+    .unwrap();
+    fs::write(
+        sys.join("kernel/security/apparmor/profiles"),
+        "/usr/bin/app-agent (enforce)\nweston (complain)\n",
+    )
+    .unwrap();
+
+    let status = lsm::read_status_in(&sys).unwrap();
+    assert_eq!(status.active, vec!["lockdown", "capability", "apparmor"]);
+    assert!(status.selinux.is_none());
+
+    let rules = vec![LsmRule {
+        lsm: Lsm::AppArmor,
+        enforcing: true,
+        required_profiles: vec![
+            "/usr/bin/app-agent".to_string(),
+            "weston".to_string(),
+            "nginx".to_string(),
+        ],
+        min_policy_version: None,
+    }];
+    assert_eq!(
+        lsm::validate_status(&status, &rules),
+        vec![
+            "AppArmor profile weston in complain mode",
+            "AppArmor profile nginx not loaded"
+        ]
+    );
+    fs::remove_dir_all(&sys).unwrap();
+}
@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+use tux_validation::device::{DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use tux_validation::manifest::{self, Manifest};
+use tux_validation::manifest_gen;
+
+fn bus1() -> TuxBus {
+    let mut eeprom = TuxDevice::new(
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 1, addr: 0x50 },
+        "eeprom",
+    );
+    eeprom.sysfs_path = Some("/sys/bus/i2c/devices/1-0050".into());
+    eeprom.driver = Some("at24".to_string());
+    let mut codec = TuxDevice::new(
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 1, addr: 0x1a },
+        "codec",
+    );
+    codec.sysfs_path = Some("/sys/bus/i2c/devices/1-001a".into());
+    TuxBus {
+        subsystem: Subsystem::I2c,
+        id: "i2c-1".to_string(),
+        name: "rk3x-i2c".to_string(),
+        devices: vec![eeprom, codec],
+        metadata: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn generated_fragment_round_trips() {
+    let fragment = manifest_gen::i2c_manifest_fragment(&[bus1()]);
+    let manifest = Manifest::from_toml_str(&fragment).unwrap();
+    assert_eq!(manifest.i2c.len(), 2);
+    assert_eq!(manifest.i2c[0].address, 0x50);
+    assert_eq!(manifest.i2c[0].driver.as_deref(), Some("at24"));
+    assert_eq!(manifest.i2c[1].severity, "warning");
+    assert!(manifest::check_buses(&manifest, &[bus1()]).is_empty());
+}
+
+#[test]
+fn reports_missing_and_wrong_driver() {
+    let manifest = Manifest::from_toml_str(
+        r#"
+[[i2c]]
+bus = 1
+address = 0x1a
+name = "codec"
+driver = "rt5640"
+severity = "warning"
+
+[[i2c]]
+bus = 1
+address = 0x68
+name = "rtc"
+"#,
+    )
+    .unwrap();
+    let findings = manifest::check_buses(&manifest, &[bus1()]);
+    let messages: Vec<&str> = findings.iter().map(|f| f.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "codec bound to no driver (expected rt5640)",
+            "rtc not found"
+        ]
+    );
+    let report = manifest::report_json(&findings);
+    assert_eq!(report["passed"], false);
+    assert_eq!(report["findings"][1]["address"], "1-0068");
+
+    assert!(Manifest::from_toml_str("[[i2c]]\nbus = 1\n").is_err());
+}
+
+#[test]
+fn yaml_manifest_with_optional_devices() {
+    let manifest = Manifest::from_yaml_str(
+        r#"
+i2c:
+  - bus: 1
+    address: 0x50
+    name: eeprom
+    driver: at24
+  - bus: 1
+    address: 0x6f
+    name: rtc
+    required: false
+  - bus: 300
+    address: 0x20
+    name: expander
+"#,
+    )
+    .unwrap();
+    assert_eq!(manifest.i2c.len(), 3);
+    assert!(!manifest.i2c[1].required);
+
+    let result = manifest::validate(&manifest, &[bus1()]);
+    assert_eq!(result.present, vec![manifest.i2c[0].clone()]);
+    assert_eq!(result.absent_optional, vec![manifest.i2c[1].clone()]);
+    assert_eq!(result.findings.len(), 1);
+    assert_eq!(
+        result.findings[0].message,
+        "expander not found (i2c-300 missing)"
+    );
+    assert!(!result.is_ok());
+    let report = result.to_json();
+    assert_eq!(report["passed"], false);
+    assert_eq!(report["present"][0], "1-0050");
+    assert_eq!(report["absent_optional"][0], "1-006f");
+
+    let dir = std::env::temp_dir().join(format!("tux-manifest-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("board.yml");
+    std::fs::write(&path, "i2c:\n  - {bus: 1, address: 0x50}\n").unwrap();
+    assert_eq!(Manifest::load(&path).unwrap().i2c[0].address, 0x50);
+    std::fs::write(&path, "bogus: []\n").unwrap();
+    assert!(Manifest::load(&path).is_err());
+    std::fs::write(
+        &path,
+        "i2c:\n  - {bus: 1, address: 0x50, required: maybe}\n",
+    )
+    .unwrap();
+    assert!(Manifest::load(&path).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn identity_registers_prove_the_right_part_is_fitted() {
+    use tux_validation::manifest::RegisterCheck;
+
+    let manifest = Manifest::from_toml_str(
+        r#"
+[[i2c]]
+bus = 1
+address = 0x50
+name = "eeprom"
+registers = [{ register = 0x00, value = 0x24, mask = 0xf0 }]
+
+[[i2c]]
+bus = 1
+address = 0x1a
+name = "codec"
+registers = [{ register = 0xfe, value = 0x10 }, { register = 0xff, value = 0xec }]
+
+[[i2c]]
+bus = 1
+address = 0x68
+name = "imu"
+required = false
+registers = [{ register = 0x75, value = 0x71 }]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        manifest.i2c[0].registers,
+        [RegisterCheck {
+            register: 0x00,
+            value: 0x24,
+            mask: 0xf0
+        }]
+    );
+    assert_eq!(manifest.i2c[1].registers[1].mask, 0xff);
+
+    // The EEPROM matches under its mask; a second-source codec answers with another ID
+    let mut read = vec![];
+    let mut result = manifest::validate(&manifest, &[bus1()]);
+    assert_eq!(result.present.len(), 2);
+    manifest::verify_identities(&manifest, &[bus1()], &mut result, |device| {
+        read.push(device.address);
+        manifest::verify_registers_with(&device.registers, |register| {
+            Ok(match (device.address, register) {
+                (0x50, 0x00) => 0x2a,
+                (0x1a, 0xfe) => 0x10,
+                (0x1a, 0xff) => 0xea,
+                _ => anyhow::bail!("unexpected read"),
+            })
+        })
+    });
+    assert_eq!(read, [0x50, 0x1a]); // The absent optional IMU is never addressed
+    assert_eq!(result.present.len(), 1);
+    assert_eq!(result.present[0].name, "eeprom");
+    assert_eq!(result.findings.len(), 1);
+    assert_eq!(
+        result.findings[0].message,
+        "codec register 0xff reads 0xea (expected 0xec)"
+    );
+    assert!(!result.is_ok());
+
+    let mut result = manifest::validate(&manifest, &[bus1()]);
+    manifest::verify_identities(&manifest, &[bus1()], &mut result, |_| {
+        anyhow::bail!("Remote I/O error")
+    });
+    assert_eq!(
+        result.findings[0].message,
+        "eeprom identity not readable: Remote I/O error"
+    );
+
+    let bad =
+        "[[i2c]]\nbus = 1\naddress = 0x68\nregisters = [{ register = 0x75, value = 0x171 }]\n";
+    assert!(Manifest::from_toml_str(bad).is_err());
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "hardware")]
+
+use tux_validation::measurement::{self, Measurement, Quantity, Unit};
+use tux_validation::sfp::DdmReadings;
+
+#[test]
+fn converts_units_and_groups_by_quantity() {
+    let rail = Measurement::new(3312.0, Unit::Millivolt, "hwmon0/in1_input");
+    assert!((rail.value_in(Unit::Volt).unwrap() - 3.312).abs() < 1e-9);
+    assert_eq!(rail.value_in(Unit::Ampere), None);
+    assert_eq!(rail.to_string(), "3312 mV");
+
+    let rx = Measurement::new(0.5, Unit::Milliwatt, "sfp0/rx_power");
+    let dbm = rx.value_in(Unit::Dbm).unwrap();
+    assert!((dbm + 3.0103).abs() < 1e-4);
+    let back = Measurement::new(dbm, Unit::Dbm, "")
+        .value_in(Unit::Microwatt)
+        .unwrap();
+    assert!((back - 500.0).abs() < 1e-9);
+
+    let ddm = DdmReadings {
+        temperature_c: Some(41.5),
+        vcc_v: Some(3.3),
+        rx_power_mw: Some(0.5),
+        ..Default::default()
+    };
+    let mut all = ddm.measurements("sfp0");
+    all.push(rail);
+    let voltages = measurement::of_quantity(&all, Quantity::Voltage);
+    assert_eq!(voltages.len(), 2);
+    assert_eq!(voltages[0].source, "sfp0/vcc");
+}
@@ -0,0 +1,72 @@
+use tux_validation::media::{self, BARS, Frame, PixelFormat};
+
+/// Resamples a frame into RGB24 at another size, like a capture of the display would.
+fn capture_of(frame: &Frame, width: u32, height: u32) -> Frame {
+    let mut data = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let rgb = frame
+                .rgb_at(x * frame.width / width, y * frame.height / height)
+                .unwrap();
+            data.extend(rgb.map(|c| c.saturating_sub(20)));
+        }
+    }
+    Frame {
+        width,
+        height,
+        stride: width * 3,
+        format: PixelFormat::Rgb24,
+        data,
+    }
+}
+
+#[test]
+fn verifies_a_captured_test_pattern() {
+    let pattern = media::test_pattern(1920, 1080);
+    assert_eq!(pattern.rgb_at(0, 0), Some([255, 255, 255]));
+    assert_eq!(pattern.rgb_at(1919, 1079), Some([0, 0, 0]));
+    assert_eq!(pattern.rgb_at(1920, 0), None);
+
+    let capture = capture_of(&pattern, 640, 360);
+    let bars = media::check_bars(&capture, media::DEFAULT_TOLERANCE);
+    assert_eq!(bars.len(), BARS.len());
+    assert!(bars.iter().all(|b| b.within_tolerance), "{:?}", bars);
+    assert_eq!(media::blank_frame_problem(&capture), None);
+
+    // A capture with red and blue swapped, e.g. a wrong pixel format on the link
+    let mut swapped = capture.clone();
+    for pixel in swapped.data.chunks_exact_mut(3) {
+        pixel.swap(0, 2);
+    }
+    let failed: Vec<String> = media::check_bars(&swapped, media::DEFAULT_TOLERANCE)
+        .iter()
+        .filter(|b| !b.within_tolerance)
+        .map(|b| b.to_string())
+        .collect();
+    assert_eq!(
+        failed,
+        vec![
+            "yellow bar captured as #00ebeb",
+            "cyan bar captured as #ebeb00",
+            "red bar captured as #0000eb",
+            "blue bar captured as #eb0000",
+        ]
+    );
+}
+
+#[test]
+fn flags_a_blank_camera_frame() {
+    // Mid-grey YUYV, as from a covered lens
+    let frame = Frame {
+        width: 64,
+        height: 48,
+        stride: 128,
+        format: PixelFormat::Yuyv,
+        data: [126, 128, 126, 128].repeat(64 * 48 / 2),
+    };
+    assert_eq!(frame.rgb_at(5, 5), Some([128, 128, 128]));
+    assert_eq!(
+        media::blank_frame_problem(&frame).unwrap(),
+        "captured frame is uniform (mean luma 128, stddev 0.0)"
+    );
+}
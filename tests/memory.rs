@@ -0,0 +1,85 @@
+use std::fs;
+use tux_validation::manifest::Manifest;
+use tux_validation::memory::{self, ExpectedMemory};
+
+const IOMEM: &str = "\
+00000000-0009ffff : reserved
+40000000-8dffffff : System RAM
+  40210000-417fffff : Kernel code
+  41800000-41bfffff : Kernel data
+8e000000-8fffffff : optee
+90000000-bfffffff : System RAM
+  b0000000-b7ffffff : reserved
+fe200000-fe2000ff : fe200000.gpio gpio@7e200000
+";
+
+#[test]
+fn parses_meminfo_and_iomem() {
+    let meminfo = memory::parse_meminfo(
+        "MemTotal:        1956900 kB\nCmaTotal:         262144 kB\nHugePages_Total:       0\n",
+    );
+    assert_eq!(meminfo.total(), Some(1956900 * 1024));
+    assert_eq!(meminfo.get("CmaTotal"), Some(256 * 1024 * 1024));
+    assert_eq!(meminfo.get("HugePages_Total"), Some(0));
+
+    let iomem = memory::parse_iomem(IOMEM).unwrap();
+    assert_eq!(iomem.len(), 8);
+    assert_eq!(iomem[2].name, "Kernel code");
+    assert_eq!(iomem[2].depth, 1);
+    assert_eq!(iomem[4].size(), 0x200_0000);
+    assert_eq!(iomem[7].name, "fe200000.gpio gpio@7e200000");
+    assert!(memory::parse_iomem("40000000 System RAM\n").is_err());
+}
+
+#[test]
+fn finds_missing_ram_and_wrong_reserved_regions() {
+    let root = std::env::temp_dir().join(format!("tux-memory-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    // A 2 GiB board where one rank didn't train, and a smaller OP-TEE carve-out
+    fs::write(
+        root.join("meminfo"),
+        "MemTotal:         955000 kB\nCmaTotal:         131072 kB\n",
+    )
+    .unwrap();
+    fs::write(root.join("iomem"), IOMEM).unwrap();
+    let manifest = Manifest::from_toml_str(
+        "[memory]\nfitted_mb = 2048\n\n\
+         [[memory.regions]]\nname = \"cma\"\nsize = 268435456\n\n\
+         [[memory.regions]]\nname = \"optee\"\nstart = 0x8e000000\nsize = 0x1000000\n\n\
+         [[memory.regions]]\nname = \"reserved\"\nstart = 0xb0000000\nsize = 0x8000000\n\n\
+         [[memory.regions]]\nname = \"ramoops\"\n",
+    )
+    .unwrap();
+    let expected = ExpectedMemory::from_manifest(&manifest).unwrap();
+    assert_eq!(expected.tolerance_percent, 10);
+    assert_eq!(expected.regions.len(), 4);
+
+    let meminfo = memory::read_meminfo_in(&root).unwrap();
+    let iomem = memory::read_iomem_in(&root).unwrap();
+    assert_eq!(
+        memory::validate_memory(&meminfo, &iomem, &expected),
+        [
+            "932 MiB of RAM (expected 2048 MiB, at most 10% less)",
+            "CMA pool is 128 MiB (expected 256 MiB)",
+            "optee at 0x8e000000 is 0x2000000 bytes (expected 0x1000000)",
+            "reserved region ramoops not found",
+        ]
+    );
+
+    // Addresses read as zero without root: presence is still checked, sizes aren't
+    let hidden: String = IOMEM
+        .lines()
+        .map(|l| {
+            let indent = l.len() - l.trim_start().len();
+            let name = l.split_once(" : ").unwrap().1;
+            format!("{}00000000-00000000 : {}\n", " ".repeat(indent), name)
+        })
+        .collect();
+    let iomem = memory::parse_iomem(&hidden).unwrap();
+    let problems = memory::validate_memory(&meminfo, &iomem, &expected);
+    assert!(problems.contains(&"optee: /proc/iomem addresses are hidden, run as root".to_string()));
+
+    let bad = Manifest::from_toml_str("[memory]\ntolerance_percent = 150\n").unwrap();
+    assert!(ExpectedMemory::from_manifest(&bad).is_err());
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "i2c")]
+
+use anyhow::Result;
+use std::io::Cursor;
+use tux_validation::i2c::{self, I2cScanner};
+use tux_validation::messages::Catalog;
+
+struct MockScanner;
+
+impl I2cScanner for MockScanner {
+    fn scan_hw_probe(&self) -> Result<(Vec<u16>, Vec<u16>)> {
+        Ok((Vec::new(), Vec::new()))
+    }
+    fn scan_sysfs(&self) -> Result<Vec<u16>> {
+        Ok(vec![0x68])
+    }
+}
+
+#[test]
+fn renders_localized_messages_with_stable_ids() {
+    let result = i2c::validate_bus(&MockScanner, &[0x50], false).unwrap();
+    let messages = result.messages(1);
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].to_log_string(), "i2c.missing bus=1 addr=50");
+
+    let english = Catalog::english();
+    assert_eq!(
+        english.render(&messages[1]),
+        "Unexpected device 0x68 on bus 1"
+    );
+
+    let german = Catalog::parse_from_reader(
+        "de",
+        Cursor::new("# Deutsch\ni2c.missing = Erwartetes Gerät 0x{addr} an Bus {bus} fehlt\n"),
+    )
+    .unwrap();
+    assert_eq!(
+        german.render(&messages[0]),
+        "Erwartetes Gerät 0x50 an Bus 1 fehlt"
+    );
+    // Untranslated messages fall back to English
+    assert_eq!(
+        german.render(&messages[1]),
+        "Unexpected device 0x68 on bus 1"
+    );
+    assert!(german.missing_ids().contains(&"i2c.unexpected"));
+}
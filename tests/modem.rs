@@ -0,0 +1,49 @@
+#![cfg(feature = "hardware")]
+
+use std::io::Cursor;
+use tux_validation::modem::{self, ExpectedSimProfile, SimSlot};
+
+#[test]
+fn parse_mmcli_sim_and_validate_profile() {
+    let mock_sim = "\
+sim.dbus-path                       : /org/freedesktop/ModemManager1/SIM/0
+sim.properties.active               : yes
+sim.properties.imsi                 : 234300123456789
+sim.properties.iccid                : 8944303382781234569
+sim.properties.operator-name        : --
+";
+    let map = modem::parse_mmcli_from_reader(Cursor::new(mock_sim)).unwrap();
+    let sim = modem::sim_info_from_mmcli(&map).unwrap();
+    assert_eq!(sim.iccid, "8944303382781234569");
+    assert_eq!(sim.operator_name, None);
+
+    let slot = SimSlot {
+        slot: 1,
+        primary: true,
+        sim: Some(sim),
+    };
+    let expected = ExpectedSimProfile {
+        iccid_prefixes: vec!["894430".to_string()],
+        mcc_mnc: vec!["23415".to_string()],
+    };
+    let mismatches = modem::validate_sim(&slot, &expected);
+    assert_eq!(mismatches.len(), 1);
+    assert!(mismatches[0].contains("IMSI 234300123456789"));
+}
+
+#[test]
+fn parse_at_ccid_variants() {
+    assert_eq!(
+        modem::parse_ccid_response("AT+CCID\r\n+CCID: 8944303382781234569F\r\n\r\nOK\r\n"),
+        Some("8944303382781234569".to_string())
+    );
+    assert_eq!(
+        modem::parse_ccid_response("\r\n+ICCID: \"89011234567890123456\"\r\nOK"),
+        Some("89011234567890123456".to_string())
+    );
+    assert_eq!(modem::parse_ccid_response("+CME ERROR: 10\r\n"), None);
+    assert_eq!(
+        modem::parse_cimi_response("AT+CIMI\r\n310260123456789\r\n\r\nOK\r\n"),
+        Some("310260123456789".to_string())
+    );
+}
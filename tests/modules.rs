@@ -0,0 +1,82 @@
+use std::fs;
+use tux_validation::manifest::Manifest;
+use tux_validation::modules::{self, ExpectedModules};
+use tux_validation::system_root::SystemRoot;
+
+const PROC_MODULES: &str = "\
+snd_soc_wm8960 45056 0 - Live 0x0000000000000000
+snd_soc_simple_card 20480 2 - Live 0x0000000000000000
+galcore 462848 1 - Loading 0x0000000000000000 (OE)
+8021q 32768 0 - Live 0x0000000000000000 (O)
+snd_soc_core 245760 3 snd_soc_wm8960,snd_soc_simple_card, Live 0x0000000000000000
+";
+
+#[test]
+fn parses_proc_modules() {
+    let loaded = modules::parse_proc_modules(PROC_MODULES).unwrap();
+    assert_eq!(loaded.len(), 5);
+    assert_eq!(loaded[2].name, "galcore");
+    assert_eq!(loaded[2].state, "Loading");
+    assert_eq!(loaded[2].taint, "OE");
+    assert_eq!(
+        loaded[2].taint_description(),
+        "O (out-of-tree), E (unsigned)"
+    );
+    assert_eq!(loaded[4].refcount, 3);
+    assert_eq!(loaded[4].users, ["snd_soc_wm8960", "snd_soc_simple_card"]);
+    assert!(loaded[0].users.is_empty() && loaded[0].taint.is_empty());
+    assert!(modules::parse_proc_modules("galcore 462848\n").is_err());
+}
+
+#[test]
+fn checks_required_modules_and_taint() {
+    let dir = std::env::temp_dir().join(format!("tux-modules-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let root = SystemRoot::under(&dir);
+    fs::create_dir_all(root.proc.join("sys/kernel")).unwrap();
+    fs::write(root.proc.join("modules"), PROC_MODULES).unwrap();
+    fs::write(root.proc.join("sys/kernel/osrelease"), "6.6.23-tux\n").unwrap();
+    let lib = root.file("/lib/modules/6.6.23-tux");
+    fs::create_dir_all(&lib).unwrap();
+    fs::write(
+        lib.join("modules.builtin"),
+        "kernel/drivers/net/phy/realtek.ko\nkernel/drivers/mmc/host/sdhci-of-dwcmshc.ko\n",
+    )
+    .unwrap();
+    // Loaded modules have an initstate; built-in ones with parameters don't
+    fs::create_dir_all(root.sys.join("module/galcore")).unwrap();
+    fs::write(root.sys.join("module/galcore/initstate"), "coming\n").unwrap();
+    fs::create_dir_all(root.sys.join("module/printk/parameters")).unwrap();
+
+    let inventory = modules::read_modules_in(&root).unwrap();
+    assert!(inventory.is_builtin("realtek"));
+    assert!(inventory.is_builtin("sdhci-of-dwcmshc"));
+    assert!(inventory.is_builtin("printk"));
+    assert!(!inventory.is_builtin("galcore"));
+
+    let manifest = Manifest::from_toml_str(
+        "[modules]\nallowed_taint = \"o\"\nrequired = [\n\
+         \"snd_soc_simple_card\",\n\
+         { name = \"snd-soc-wm8960\", min_refcount = 1 },\n\
+         { name = \"realtek\", builtin = false },\n\
+         { name = \"8021q\", builtin = true },\n\
+         \"rtl8723bs\",\n]\n",
+    )
+    .unwrap();
+    let expected = ExpectedModules::from_manifest(&manifest).unwrap();
+    assert_eq!(
+        modules::validate_modules(&inventory, &expected),
+        [
+            "snd-soc-wm8960: refcount 0 (expected at least 1)",
+            "realtek: built in (expected a loadable module)",
+            "8021q: loaded as a module (expected built in)",
+            "rtl8723bs: not loaded or built in",
+            "galcore: state Loading",
+            "galcore: tainted O (out-of-tree), E (unsigned)",
+        ]
+    );
+
+    let bad = Manifest::from_toml_str("[modules]\nrequired = \"galcore\"\n").unwrap();
+    assert!(ExpectedModules::from_manifest(&bad).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
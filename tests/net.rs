@@ -0,0 +1,255 @@
+#![cfg(feature = "net")]
+
+use std::fs;
+use std::io::Cursor;
+use std::os::unix::fs::symlink;
+use std::path::PathBuf;
+use tux_validation::manifest::Manifest;
+use tux_validation::net::{self, ExpectedBond, ExpectedBridge, ExpectedTopology, ExpectedVlan};
+
+fn fake_net_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tux-net-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn parse_vlan_config() {
+    let mock_data = "VLAN Dev name    | VLAN ID\n\
+                     Name-Type: VLAN_NAME_TYPE_RAW_PLUS_VID_NO_PAD\n\
+                     eth0.100       | 100  | eth0\n\
+                     lan.20         | 20  | br0\n";
+
+    let vlans = net::parse_vlan_config_from_reader(Cursor::new(mock_data)).unwrap();
+
+    assert_eq!(vlans.len(), 2);
+    assert_eq!(vlans[0].name, "eth0.100");
+    assert_eq!(vlans[0].vlan_id, 100);
+    assert_eq!(vlans[1].parent, "br0");
+}
+
+#[test]
+fn validate_bridge_and_bond_topology() {
+    let dir = fake_net_dir("topology");
+    fs::create_dir_all(dir.join("br0/bridge")).unwrap();
+    fs::create_dir_all(dir.join("br0/brif/lan1")).unwrap();
+    fs::create_dir_all(dir.join("br0/brif/lan2")).unwrap();
+    fs::write(dir.join("br0/bridge/stp_state"), "1\n").unwrap();
+    fs::create_dir_all(dir.join("bond0/bonding")).unwrap();
+    fs::write(dir.join("bond0/bonding/mode"), "active-backup 1\n").unwrap();
+    fs::write(dir.join("bond0/bonding/slaves"), "eth0 eth1\n").unwrap();
+    fs::write(dir.join("bonding_masters"), "bond0\n").unwrap();
+
+    let mut topology = net::discover_topology_in(&dir).unwrap();
+    topology.vlans =
+        net::parse_vlan_config_from_reader(Cursor::new("lan.20 | 20 | br0\n")).unwrap();
+
+    assert!(topology.bridges[0].stp_enabled);
+    assert_eq!(topology.bonds[0].mode, "active-backup");
+
+    let expected = ExpectedTopology {
+        bridges: vec![ExpectedBridge {
+            name: "br0".to_string(),
+            ports: vec!["lan1".to_string(), "lan3".to_string()],
+        }],
+        vlans: vec![ExpectedVlan {
+            name: "lan.20".to_string(),
+            vlan_id: 20,
+            parent: "br0".to_string(),
+        }],
+        bonds: vec![ExpectedBond {
+            name: "bond0".to_string(),
+            mode: Some("802.3ad".to_string()),
+            slaves: vec!["eth0".to_string(), "eth1".to_string()],
+        }],
+        namespaces: vec!["mgmt".to_string()],
+    };
+    let result = net::validate_topology(&topology, &expected);
+
+    assert_eq!(result.present, vec!["br0", "lan.20", "bond0"]);
+    assert_eq!(result.missing, vec!["netns:mgmt"]);
+    assert_eq!(result.mismatched.len(), 3); // lan3 missing, lan2 extra, bond mode
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn discover_dsa_switch_ports() {
+    let dir = fake_net_dir("dsa");
+    let iface = |name: &str, ifindex: &str, iflink: &str| {
+        fs::create_dir_all(dir.join(name)).unwrap();
+        fs::write(dir.join(name).join("ifindex"), ifindex).unwrap();
+        fs::write(dir.join(name).join("iflink"), iflink).unwrap();
+    };
+    iface("eth0", "2", "2");
+    fs::create_dir_all(dir.join("eth0/dsa")).unwrap();
+    fs::write(dir.join("eth0/dsa/tagging"), "ksz9477\n").unwrap();
+    for (port, carrier) in [("lan1", "1"), ("lan2", "0")] {
+        iface(port, if port == "lan1" { "3" } else { "4" }, "2");
+        fs::write(dir.join(port).join("phys_switch_id"), "00000000\n").unwrap();
+        fs::write(dir.join(port).join("carrier"), carrier).unwrap();
+        fs::write(dir.join(port).join("operstate"), "up").unwrap();
+    }
+
+    // Both ports sit on the same switch device, bound to the ksz9477 driver
+    fs::create_dir_all(dir.join("devices/spi0.0")).unwrap();
+    fs::create_dir_all(dir.join("drivers/ksz9477-switch")).unwrap();
+    symlink(
+        dir.join("drivers/ksz9477-switch"),
+        dir.join("devices/spi0.0/driver"),
+    )
+    .unwrap();
+    symlink(dir.join("devices/spi0.0"), dir.join("lan1/device")).unwrap();
+
+    let switches = net::discover_switches_in(&dir).unwrap();
+    assert_eq!(switches.len(), 1);
+    assert_eq!(switches[0].driver, "ksz9477-switch");
+    assert_eq!(switches[0].cpu_port.as_deref(), Some("eth0"));
+    assert_eq!(switches[0].tagging.as_deref(), Some("ksz9477"));
+
+    let expected = vec![net::ExpectedSwitch {
+        model: "ksz9477".to_string(),
+        port_count: 2,
+        cpu_port: Some("eth0".to_string()),
+        ports: vec![net::ExpectedSwitchPort {
+            name: "lan2".to_string(),
+            link_up: true,
+            speed: None,
+        }],
+    }];
+    let result = net::validate_switches(&switches, &expected);
+    assert_eq!(result.present, vec!["ksz9477"]);
+    assert_eq!(
+        result.mismatched,
+        vec!["ksz9477: port lan2 has no link (up)"]
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn flags_placeholder_and_unprovisioned_macs() {
+    let mac: net::MacAddress = "00:1B:C5:0a:12:34".parse().unwrap();
+    assert_eq!(mac.to_string(), "00:1b:c5:0a:12:34");
+    assert!(mac.has_prefix("00:1b:c5"));
+    assert!(mac.has_prefix("00:1B:C5:0"));
+    assert!(mac.matches_pattern("00:1b:c5:0a:xx:xx"));
+    assert!(!mac.matches_pattern("00:1b:c5:0b:xx:xx"));
+    assert!(!mac.is_locally_administered());
+    assert!(
+        "02:00:00:00:00:01"
+            .parse::<net::MacAddress>()
+            .unwrap()
+            .is_locally_administered()
+    );
+    assert!("00:1b:c5:0a:12".parse::<net::MacAddress>().is_err());
+    assert!("00:1b:c5:0a:12:34:56".parse::<net::MacAddress>().is_err());
+
+    let dir = fake_net_dir("mac");
+    let iface = |name: &str, address: &str, assign: &str, carrier: &str| {
+        fs::create_dir_all(dir.join(name).join("device")).unwrap();
+        fs::write(dir.join(name).join("address"), format!("{}\n", address)).unwrap();
+        fs::write(dir.join(name).join("addr_assign_type"), assign).unwrap();
+        fs::write(dir.join(name).join("carrier"), carrier).unwrap();
+        fs::write(dir.join(name).join("operstate"), "up").unwrap();
+    };
+    iface("eth0", "00:1b:c5:0a:12:34", "0", "1");
+    iface("eth1", "00:00:00:00:00:00", "0", "0");
+    iface("eth2", "5e:a1:03:9c:44:10", "1", "1"); // No EEPROM: the driver made one up
+    iface("usb0", "02:00:00:00:00:01", "3", "0");
+    fs::create_dir_all(dir.join("wwan0")).unwrap();
+
+    let interfaces = net::read_interfaces_in(&dir).unwrap();
+    assert_eq!(interfaces.len(), 5);
+    assert!(interfaces[0].physical && interfaces[0].link.is_up());
+    assert!(interfaces[2].random_mac);
+    assert_eq!(interfaces[4].mac, None);
+
+    let manifest = Manifest::from_toml_str(
+        "[[net_interfaces.interfaces]]\nname = \"eth0\"\nmac_pattern = \"00:1b:c5:0a:xx:xx\"\ncarrier = true\n\n\
+         [[net_interfaces.interfaces]]\nname = \"eth1\"\nmac_prefix = \"00:1b:c5\"\n\n\
+         [[net_interfaces.interfaces]]\nname = \"eth2\"\nmac_prefix = \"00:1b:c5\"\n\n\
+         [[net_interfaces.interfaces]]\nname = \"usb0\"\nallow_local = true\ncarrier = true\n\n\
+         [[net_interfaces.interfaces]]\nname = \"wlan0\"\n",
+    )
+    .unwrap();
+    let expected = net::ExpectedInterfaces::from_manifest(&manifest).unwrap();
+    let result = net::validate_interfaces(&interfaces, &expected);
+    assert_eq!(result.present, vec!["eth0", "eth1", "eth2", "usb0"]);
+    assert_eq!(result.missing, vec!["wlan0"]);
+    assert_eq!(
+        result.mismatched,
+        vec![
+            "eth1: MAC address is all zeros",
+            "eth2: random MAC address 5e:a1:03:9c:44:10",
+            "eth2: MAC address 5e:a1:03:9c:44:10 (expected prefix 00:1b:c5)",
+            "usb0: no carrier (up)",
+        ]
+    );
+
+    let bad = Manifest::from_toml_str(
+        "[[net_interfaces.interfaces]]\nname = \"eth0\"\nmac_pattern = \"00:1b:xx\"\n",
+    )
+    .unwrap();
+    assert!(net::ExpectedInterfaces::from_manifest(&bad).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn checks_negotiated_link_and_phy_driver() {
+    let dir = fake_net_dir("link");
+    let drivers = fake_net_dir("link-drivers");
+    for driver in ["st_gmac", "Generic PHY", "RTL8211F Gigabit Ethernet"] {
+        fs::create_dir_all(drivers.join(driver)).unwrap();
+    }
+    let iface = |name: &str, speed: &str, duplex: &str, phy_driver: &str| {
+        let iface_dir = dir.join(name);
+        fs::create_dir_all(iface_dir.join("phydev")).unwrap();
+        fs::create_dir_all(iface_dir.join("device")).unwrap();
+        symlink(drivers.join("st_gmac"), iface_dir.join("device/driver")).unwrap();
+        symlink(drivers.join(phy_driver), iface_dir.join("phydev/driver")).unwrap();
+        fs::write(iface_dir.join("phydev/phy_id"), "0x001cc916\n").unwrap();
+        fs::write(iface_dir.join("address"), "00:1b:c5:0a:12:34\n").unwrap();
+        fs::write(iface_dir.join("carrier"), "1\n").unwrap();
+        fs::write(iface_dir.join("operstate"), "up\n").unwrap();
+        fs::write(iface_dir.join("speed"), speed).unwrap();
+        fs::write(iface_dir.join("duplex"), duplex).unwrap();
+    };
+    iface("eth0", "1000\n", "full\n", "RTL8211F Gigabit Ethernet");
+    // Strapped to the wrong address: found by ID scan, bound to the fallback driver
+    iface("eth1", "100\n", "half\n", "Generic PHY");
+
+    let interfaces = net::read_interfaces_in(&dir).unwrap();
+    let names: Vec<&str> = interfaces.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(names, ["eth0", "eth1"]);
+    assert_eq!(interfaces[0].driver.as_deref(), Some("st_gmac"));
+    assert_eq!(interfaces[0].full_duplex, Some(true));
+    assert_eq!(interfaces[0].phy_id.as_deref(), Some("0x001cc916"));
+    assert_eq!(interfaces[0].autoneg, None);
+
+    let manifest = Manifest::from_toml_str(
+        "[[net_interfaces.interfaces]]\nname = \"eth0\"\nspeed = 1000\nduplex = \"full\"\n\
+         phy_driver = \"RTL8211F Gigabit Ethernet\"\n\n\
+         [[net_interfaces.interfaces]]\nname = \"eth1\"\nspeed = 1000\nduplex = \"full\"\n\
+         autoneg = true\ndriver = \"st_gmac\"\nphy_driver = \"RTL8211F Gigabit Ethernet\"\n",
+    )
+    .unwrap();
+    let expected = net::ExpectedInterfaces::from_manifest(&manifest).unwrap();
+    let result = net::validate_interfaces(&interfaces, &expected);
+    assert_eq!(
+        result.mismatched,
+        vec![
+            "eth1: speed 100 Mb/s (expected 1000 Mb/s)",
+            "eth1: half duplex (expected full)",
+            "eth1: autoneg unknown (expected on)",
+            "eth1: PHY driver Generic PHY (expected RTL8211F Gigabit Ethernet)",
+        ]
+    );
+
+    let bad = Manifest::from_toml_str(
+        "[[net_interfaces.interfaces]]\nname = \"eth0\"\nduplex = \"auto\"\n",
+    )
+    .unwrap();
+    assert!(net::ExpectedInterfaces::from_manifest(&bad).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+    fs::remove_dir_all(&drivers).unwrap();
+}
@@ -0,0 +1,29 @@
+use tux_validation::ota::{self, ExpectedOta, Rauc};
+
+#[test]
+fn rauc_booted_slot_version_and_health() {
+    let mock_json = r#"{
+        "compatible": "acme-gw",
+        "booted": "B",
+        "boot_primary": "rootfs.1",
+        "slots": [
+            {"rootfs.0": {"class": "rootfs", "bootname": "A", "state": "inactive", "boot_status": "good",
+                          "slot_status": {"bundle": {"version": "1.4.0"}}}},
+            {"rootfs.1": {"class": "rootfs", "bootname": "B", "state": "booted", "boot_status": "bad",
+                          "slot_status": {"bundle": {"version": "1.5.0"}}}}
+        ]
+    }"#;
+
+    let status = Rauc::status_from_json(mock_json).unwrap();
+    assert_eq!(status.installed_version.as_deref(), Some("1.5.0"));
+    assert!(!status.healthy);
+
+    let expected = ExpectedOta {
+        version: Some("1.5.0".to_string()),
+        require_healthy: true,
+    };
+    assert_eq!(
+        ota::validate_ota(&status, &expected),
+        vec!["rauc: rootfs.1: boot status bad"]
+    );
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "otel")]
+
+use std::fs;
+use tux_validation::measurement::{Measurement, Unit};
+use tux_validation::otel::{CheckSpan, RunTrace};
+
+#[test]
+fn run_exports_as_otlp_trace() {
+    let mut trace = RunTrace::start("station-run", "station-07").unwrap();
+    trace
+        .record(
+            CheckSpan::start("i2c.bus1")
+                .attr("device", "1-0050")
+                .measurement(&Measurement::new(3.3, Unit::Volt, "vdd"))
+                .finish(false),
+        )
+        .unwrap();
+    trace.record(CheckSpan::start("soc")).unwrap();
+    let otlp = trace.finish(true);
+
+    let spans = otlp["resourceSpans"][0]["scopeSpans"][0]["spans"]
+        .as_array()
+        .unwrap();
+    assert_eq!(spans.len(), 3);
+    let root = &spans[0];
+    assert_eq!(root["traceId"].as_str().unwrap().len(), 32);
+    assert_eq!(root["status"]["code"], 1);
+    assert!(root.get("parentSpanId").is_none());
+
+    let check = &spans[1];
+    assert_eq!(check["traceId"], root["traceId"]);
+    assert_eq!(check["parentSpanId"], root["spanId"]);
+    assert_eq!(check["status"]["code"], 2);
+    let attrs = check["attributes"].as_array().unwrap();
+    assert_eq!(attrs[0]["value"]["stringValue"], "1-0050");
+    assert_eq!(attrs[1]["key"], "measurement.vdd");
+    assert_eq!(attrs[1]["value"]["doubleValue"], 3.3);
+    assert_eq!(spans[2]["status"]["code"], 0);
+
+    let path = std::env::temp_dir().join(format!("tux-otel-{}.jsonl", std::process::id()));
+    let _ = fs::remove_file(&path);
+    tux_validation::otel::append_otlp_json(&path, &otlp).unwrap();
+    tux_validation::otel::append_otlp_json(&path, &otlp).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+    fs::remove_file(&path).unwrap();
+}
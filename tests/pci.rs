@@ -0,0 +1,73 @@
+#![cfg(feature = "pci")]
+
+use std::fs;
+use std::os::unix::fs::symlink;
+use tux_validation::pci::{self, ExpectedPciDevice};
+
+#[test]
+fn audits_pci_functions_and_validates_them() {
+    let root = std::env::temp_dir().join(format!("tux-pci-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let devices = root.join("sys/bus/pci/devices");
+    for (slot, vendor, device, class) in [
+        ("0000:00:00.0", "0x1d87", "0x3588", "0x060400"),
+        ("0000:01:00.0", "0x14e4", "0x449d", "0x028000"),
+    ] {
+        let dir = devices.join(slot);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("vendor"), format!("{}\n", vendor)).unwrap();
+        fs::write(dir.join("device"), format!("{}\n", device)).unwrap();
+        fs::write(dir.join("class"), format!("{}\n", class)).unwrap();
+    }
+    fs::create_dir_all(root.join("sys/bus/pci/drivers/pcieport")).unwrap();
+    symlink(
+        root.join("sys/bus/pci/drivers/pcieport"),
+        devices.join("0000:00:00.0/driver"),
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("udev")).unwrap();
+    fs::write(
+        root.join("udev/+pci:0000:00:00.0"),
+        "E:ID_MODEL_FROM_DATABASE=RK3588 PCIe Root Port\n",
+    )
+    .unwrap();
+
+    let buses = pci::audit_pci_buses_in(&root.join("sys"), &root.join("udev")).unwrap();
+    let ids: Vec<&str> = buses.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["pci0000:00", "pci0000:01"]);
+    let root_port = &buses[0].devices[0];
+    assert_eq!(root_port.name, "RK3588 PCIe Root Port");
+    assert_eq!(pci::class(root_port), Some(0x060400));
+    assert_eq!(buses[1].devices[0].name, "14e4:449d");
+
+    let all: Vec<_> = buses.into_iter().flat_map(|b| b.devices).collect();
+    let expected = [
+        ExpectedPciDevice {
+            slot: Some("0000:00:00.0".to_string()),
+            vendor_id: 0x1d87,
+            device_id: 0x3588,
+            driver: Some("pcieport".to_string()),
+            ..Default::default()
+        },
+        ExpectedPciDevice {
+            vendor_id: 0x14e4,
+            device_id: 0x449d,
+            class: Some(0x028000),
+            driver: Some("brcmfmac".to_string()),
+            ..Default::default()
+        },
+        ExpectedPciDevice {
+            vendor_id: 0x144d,
+            device_id: 0xa80a,
+            ..Default::default()
+        },
+    ];
+    let result = pci::validate_pci_devices(&all, &expected);
+    assert_eq!(result.present.len(), 1);
+    assert_eq!(
+        result.mismatched,
+        vec!["0000:01:00.0: bound to no driver (expected brcmfmac)"]
+    );
+    assert_eq!(result.missing, vec!["144d:a80a"]);
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,94 @@
+use std::fs;
+use tux_validation::manifest::Manifest;
+use tux_validation::pinctrl::{self, ExpectedPins, PinId};
+
+const ROCKCHIP: &str = "\
+Pinmux settings per pin
+Format: pin (name): mux_owner|gpio_owner (strict) hog?
+pin 8 (GPIO0_B0): pinctrl (GPIO UNCLAIMED) (HOG) function pmic group pmic-int
+pin 9 (GPIO0_B1): fe5a0000.i2c (GPIO UNCLAIMED) function i2c1 group i2c1m0-xfer
+pin 10 (GPIO0_B2): fe5a0000.i2c (GPIO UNCLAIMED) function i2c1 group i2c1m0-xfer
+pin 11 (GPIO0_B3): (MUX UNCLAIMED) gpio0:11
+pin 12 (GPIO0_B4): (MUX UNCLAIMED) (GPIO UNCLAIMED)
+pin 13 (GPIO0_B5): feb20000.spi (GPIO UNCLAIMED) function spi0 group spi0m1-pins
+";
+
+#[test]
+fn parses_pinmux_pins() {
+    let pins = pinctrl::parse_pinmux_pins("pinctrl", ROCKCHIP).unwrap();
+    assert_eq!(pins.len(), 6);
+    assert!(pins[0].hog);
+    assert_eq!(pins[0].mux_owner.as_deref(), Some("pinctrl"));
+    assert_eq!(pins[1].name, "GPIO0_B1");
+    assert_eq!(pins[1].function.as_deref(), Some("i2c1"));
+    assert_eq!(pins[1].group.as_deref(), Some("i2c1m0-xfer"));
+    assert_eq!(pins[1].gpio_owner, None);
+    assert_eq!(pins[3].mux_owner, None);
+    assert_eq!(pins[3].gpio_owner.as_deref(), Some("gpio0:11"));
+    assert_eq!(
+        pins[4],
+        pinctrl::Pin {
+            controller: "pinctrl".to_string(),
+            number: 12,
+            name: "GPIO0_B4".to_string(),
+            ..Default::default()
+        }
+    );
+
+    // Strict controllers, e.g. i.MX and STM32
+    let strict = pinctrl::parse_pinmux_pins(
+        "soc:pinctrl@50002000",
+        "pin 21 (PB5): device 40015000.i2c function af4 group PB5\n\
+         pin 22 (PB6): GPIO gpiob:6\n\
+         pin 23 (PB7): UNCLAIMED\n",
+    )
+    .unwrap();
+    assert_eq!(strict[0].mux_owner.as_deref(), Some("40015000.i2c"));
+    assert_eq!(strict[1].gpio_owner.as_deref(), Some("gpiob:6"));
+    assert_eq!(strict[2].mux_owner, None);
+    assert!(pinctrl::parse_pinmux_pins("pinctrl", "pin x (PB5): UNCLAIMED\n").is_err());
+}
+
+#[test]
+fn catches_pads_left_in_gpio_mode() {
+    let sys = std::env::temp_dir().join(format!("tux-pinctrl-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&sys);
+    let dir = sys.join("kernel/debug/pinctrl/pinctrl-rockchip-pinctrl");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("pinmux-pins"), ROCKCHIP).unwrap();
+    // A pinconf-only controller has no pinmux-pins
+    fs::create_dir_all(sys.join("kernel/debug/pinctrl/rk805-pinctrl")).unwrap();
+
+    let pins = pinctrl::read_pins_in(&sys).unwrap().unwrap();
+    assert_eq!(pins.len(), 6);
+    assert_eq!(pins[0].controller, "pinctrl-rockchip-pinctrl");
+
+    // The overlay moved i2c3 to GPIO0_B3/B4, but names the m1 group of the wrong bus
+    let manifest = Manifest::from_toml_str(
+        "[[pinctrl.pins]]\npin = \"GPIO0_B1\"\nfunction = \"i2c1\"\ngroup = \"i2c1m0-xfer\"\nowner = \"fe5a0000.i2c\"\n\n\
+         [[pinctrl.pins]]\npin = \"GPIO0_B3\"\nfunction = \"i2c3\"\n\n\
+         [[pinctrl.pins]]\npin = \"GPIO0_B4\"\nfunction = \"i2c3\"\ngroup = \"i2c3m1-xfer\"\n\n\
+         [[pinctrl.pins]]\npin = 13\ncontroller = \"pinctrl-rockchip-pinctrl\"\nfunction = \"spi0\"\ngroup = \"spi0m0-pins\"\n\n\
+         [[pinctrl.pins]]\npin = \"GPIO4_C6\"\n",
+    )
+    .unwrap();
+    let expected = ExpectedPins::from_manifest(&manifest).unwrap();
+    assert_eq!(expected.pins[3].pin, PinId::Number(13));
+    assert_eq!(
+        pinctrl::validate_pins(&pins, &expected),
+        [
+            "GPIO0_B3: claimed as GPIO by gpio0:11 (expected function i2c3)",
+            "GPIO0_B4: unclaimed (expected function i2c3)",
+            "GPIO0_B5: group spi0m1-pins (expected spi0m0-pins)",
+            "GPIO4_C6: not found",
+        ]
+    );
+
+    let bad = Manifest::from_toml_str("[[pinctrl.pins]]\npin = 13\nfunction = \"spi0\"\n").unwrap();
+    assert!(ExpectedPins::from_manifest(&bad).is_err());
+    assert_eq!(
+        pinctrl::read_pins_in(&sys.join("nonexistent")).unwrap(),
+        None
+    );
+    fs::remove_dir_all(&sys).unwrap();
+}
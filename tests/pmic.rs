@@ -0,0 +1,64 @@
+use std::fs;
+use tux_validation::device::{Board, DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use tux_validation::pmic::{self, ExpectedPmic, PmicContext};
+
+#[test]
+fn rk809_plugin_checks_subfunctions() {
+    let root = std::env::temp_dir().join(format!("tux-pmic-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let dev_dir = root.join("0-0020");
+    for (n, name) in [(0, "vdd_logic"), (1, "vcc_3v3")] {
+        let dir = dev_dir.join(format!("rk808-regulator/regulator/regulator.{}", n + 4));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("name"), format!("{}\n", name)).unwrap();
+    }
+    fs::create_dir_all(root.join("regmap/0-0020")).unwrap();
+    fs::write(
+        root.join("regmap/0-0020/registers"),
+        "ed: 80\nee: 92\nf5: 80\nf6: 00\n",
+    )
+    .unwrap();
+
+    let address = DeviceAddress::I2c { bus: 0, addr: 0x20 };
+    let mut device = TuxDevice::new(Subsystem::I2c, address.clone(), "rk809");
+    device.sysfs_path = Some(dev_dir);
+    device.driver = Some("rk808".to_string());
+    let board = Board {
+        buses: vec![TuxBus {
+            subsystem: Subsystem::I2c,
+            id: "i2c-0".to_string(),
+            name: String::new(),
+            devices: vec![device],
+            metadata: Default::default(),
+        }],
+        links: Vec::new(),
+    };
+    let ctx = PmicContext {
+        interrupts: "           CPU0       CPU1\n 78:  12  0  rockchip_gpio_irq  7 Level  rk808\n"
+            .to_string(),
+        regmap_dir: root.join("regmap"),
+    };
+    let expected = [ExpectedPmic {
+        bus: 0,
+        addr: 0x20,
+        regulators: vec!["vcc_3v3".to_string(), "vcc_1v8".to_string()],
+        registers: vec![("ID_MSB".to_string(), 0xf0, 0x80)],
+    }];
+
+    let reports = pmic::validate_pmics(&board, &pmic::builtin_plugins(), &expected, &ctx);
+    assert_eq!(reports.len(), 1);
+    let (addr, report) = &reports[0];
+    assert_eq!(addr, &address);
+    assert_eq!(report.plugin, "rk817");
+    assert_eq!(report.regulators, vec!["vcc_3v3", "vdd_logic"]);
+    assert!(report.irq_registered);
+    assert_eq!(report.registers["ON_SOURCE"], 0x80);
+    assert_eq!(
+        report.problems,
+        vec![
+            "0-0020: regulator vcc_1v8 missing",
+            "0-0020: RTC not registered"
+        ]
+    );
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,21 @@
+use std::io::Cursor;
+use tux_validation::ptp::{self, OffsetStats};
+
+#[test]
+fn offsets_only_from_locked_servo() {
+    let mock_log = "\
+ptp4l[100.001]: master offset     -4521 s0 freq  +1234 path delay   567
+ptp4l[101.001]: master offset       -12 s2 freq  +1200 path delay   570
+phc2sys[102.002]: CLOCK_REALTIME phc offset        35 s2 freq    -12 delay   1203
+ptp4l[103.001]: port 1: new foreign master 001122.fffe.334455-1
+ptp4l[104.001]: master offset         8 s2 freq  +1190 path delay   569
+";
+
+    let offsets = ptp::offsets_from_reader(Cursor::new(mock_log)).unwrap();
+    assert_eq!(offsets, vec![-12, 35, 8]);
+
+    let stats = OffsetStats::from_samples(&offsets).unwrap();
+    assert_eq!(stats.max_abs, 35);
+    assert!(stats.within(100));
+    assert!(!stats.within(20));
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "python")]
+
+use std::collections::BTreeMap;
+use tux_validation::device::{DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use tux_validation::python::PyBus;
+
+#[test]
+fn bus_converts_for_python() {
+    let mut ghost = TuxDevice::new(
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 3, addr: 0x3c },
+        "",
+    );
+    ghost.hw_responded = true;
+    let bus = TuxBus {
+        subsystem: Subsystem::I2c,
+        id: "i2c-3".to_string(),
+        name: "rk3x-i2c".to_string(),
+        devices: vec![ghost],
+        metadata: BTreeMap::from([("health".to_string(), "ok".to_string())]),
+    };
+    let py = PyBus::from(&bus);
+    assert_eq!(py.subsystem, "i2c");
+    assert_eq!(py.devices[0].address, "3-003c");
+    assert!(py.devices[0].is_ghost);
+    assert_eq!(py.metadata["health"], "ok");
+}
@@ -0,0 +1,132 @@
+use std::fs;
+use tux_validation::manifest::Manifest;
+use tux_validation::quarantine::{self, Quarantine, Target};
+use tux_validation::report::TestCase;
+use tux_validation::safety::Access;
+
+#[test]
+fn targets_parse_as_bus_or_device() {
+    assert_eq!(
+        "i2c-1".parse::<Target>().unwrap(),
+        Target::Bus("i2c-1".to_string())
+    );
+    let device: Target = "i2c-1/1-0050".parse().unwrap();
+    assert_eq!(device.to_string(), "i2c-1/1-0050");
+    assert_eq!(device.bus(), Target::Bus("i2c-1".to_string()));
+    assert!("".parse::<Target>().is_err());
+    assert!("i2c-1/".parse::<Target>().is_err());
+}
+
+#[test]
+fn repeated_failures_quarantine_until_cleared() {
+    let eeprom: Target = "i2c-1/1-0050".parse().unwrap();
+    let mut list = Quarantine {
+        threshold: 2,
+        ..Default::default()
+    };
+    assert!(!list.record_failure(&eeprom, "write test: NACK"));
+    list.record_pass(&eeprom);
+    assert!(list.entries.is_empty());
+
+    assert!(!list.record_failure(&eeprom, "write test: NACK"));
+    assert!(list.record_failure(&eeprom, "write test: timeout"));
+    let (by, entry) = list.quarantined(&eeprom).unwrap();
+    assert_eq!(by, eeprom);
+    assert_eq!(entry.failures, 2);
+    list.record_pass(&eeprom);
+    assert!(list.quarantined(&eeprom).is_some());
+
+    assert!(list.require(&eeprom, Access::ReadOnly, "audit").is_ok());
+    let err = list
+        .require(&eeprom, Access::Write, "write test")
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("QUARANTINED: i2c-1/1-0050 after 2")
+    );
+    assert!(err.to_string().contains("last: write test: timeout"));
+
+    let mut case = TestCase::passed("i2c-1", "eeprom write");
+    assert!(quarantine::skip_case(&mut case, &list, &eeprom));
+    assert!(case.skipped.unwrap().starts_with("QUARANTINED"));
+
+    assert!(list.clear(&eeprom));
+    assert!(list.quarantined(&eeprom).is_none());
+    assert!(!list.clear(&eeprom));
+}
+
+#[test]
+fn quarantined_bus_covers_its_devices() {
+    let bus = Target::Bus("i2c-3".to_string());
+    let mut list = Quarantine {
+        threshold: 1,
+        ..Default::default()
+    };
+    list.record_failure(&bus, "bus stuck low");
+    let device: Target = "i2c-3/3-0020".parse().unwrap();
+    assert_eq!(list.quarantined(&device).unwrap().0, bus);
+    assert!(list.quarantined(&"i2c-4/4-0020".parse().unwrap()).is_none());
+
+    let ran = list.run_guarded(&device, "gpio toggle", None, |_| Ok(()));
+    assert!(ran.is_none());
+}
+
+#[test]
+fn guarded_runs_count_failures_and_unclean_teardowns() {
+    let target = Target::Bus("i2c-2".to_string());
+    let mut list = Quarantine {
+        threshold: 2,
+        ..Default::default()
+    };
+    let run = list
+        .run_guarded(&target, "margining", None, |_| -> anyhow::Result<()> {
+            anyhow::bail!("rail out of range")
+        })
+        .unwrap();
+    assert!(run.result.is_err());
+    assert_eq!(list.entries["i2c-2"].failures, 1);
+
+    let run = list
+        .run_guarded(&target, "margining", None, |teardown| {
+            teardown.register("restore rail", || anyhow::bail!("sysfs gone"));
+            Ok(())
+        })
+        .unwrap();
+    assert!(run.result.is_ok() && !run.is_clean());
+    assert!(list.quarantined(&target).is_some());
+    assert!(
+        list.entries["i2c-2"]
+            .last_failure
+            .contains("teardown failed")
+    );
+}
+
+#[test]
+fn list_persists_across_runs() {
+    let dir = std::env::temp_dir().join(format!("tux-quarantine-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("state/quarantine.json");
+    let manifest = Manifest::from_toml_str(&format!(
+        "[quarantine]\nthreshold = 1\npath = {:?}\n",
+        path.to_string_lossy()
+    ))
+    .unwrap();
+
+    let (mut list, at) = Quarantine::load_for_manifest(&manifest).unwrap();
+    assert_eq!(at, path);
+    assert!(list.entries.is_empty());
+    list.record_failure(&Target::Bus("spi0".to_string()), "loopback mismatch");
+    list.save(&path).unwrap();
+
+    let (reloaded, _) = Quarantine::load_for_manifest(&manifest).unwrap();
+    assert_eq!(reloaded, list);
+    assert!(
+        reloaded
+            .quarantined(&Target::Bus("spi0".to_string()))
+            .is_some()
+    );
+
+    let bad = Manifest::from_toml_str("[quarantine]\nthreshold = 0\n").unwrap();
+    assert!(Quarantine::load_for_manifest(&bad).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,38 @@
+use tux_validation::manifest::Manifest;
+use tux_validation::registry;
+use tux_validation::safety::Access;
+
+#[test]
+fn registry_ids_are_unique_and_sorted() {
+    let ids: Vec<&str> = registry::CHECKS.iter().map(|c| c.id).collect();
+    let mut sorted = ids.clone();
+    sorted.sort();
+    sorted.dedup();
+    assert_eq!(ids, sorted);
+    // IDs double as TOML section names
+    assert!(ids.iter().all(|id| {
+        id.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    }));
+
+    assert_eq!(
+        registry::find("gpio_loopback").unwrap().access,
+        Access::Write
+    );
+    assert_eq!(registry::unknown_ids(["i2c", "i2c_typo"]), vec!["i2c_typo"]);
+
+    let json = registry::to_json();
+    assert_eq!(
+        json["checks"].as_array().unwrap().len(),
+        registry::CHECKS.len()
+    );
+    assert_eq!(json["checks"][0]["params"][0]["name"], "items");
+    assert_eq!(json["common_params"][0]["name"], "timeout");
+}
+
+#[test]
+fn manifest_rejects_unknown_checks() {
+    let err =
+        Manifest::from_toml_str("[[i2c]]\nbus = 1\naddress = 0x50\n\n[[i2c_tpyo]]\n").unwrap_err();
+    assert!(err.to_string().contains("i2c_tpyo"));
+}
@@ -0,0 +1,114 @@
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+use tux_validation::manifest::Manifest;
+use tux_validation::regulator::{self, ExpectedRegulators};
+
+fn rail(sys: &Path, index: u32, provider: &str, attrs: &[(&str, &str)], consumers: &[&str]) {
+    let dir = sys.join(format!("class/regulator/regulator.{}", index));
+    fs::create_dir_all(&dir).unwrap();
+    for (name, value) in attrs {
+        fs::write(dir.join(name), format!("{}\n", value)).unwrap();
+    }
+    let provider_dir = sys.join("devices/platform").join(provider);
+    fs::create_dir_all(&provider_dir).unwrap();
+    symlink(&provider_dir, dir.join("device")).unwrap();
+    for consumer in consumers {
+        symlink(&provider_dir, dir.join(consumer)).unwrap();
+    }
+}
+
+#[test]
+fn checks_rails_against_the_power_tree() {
+    let sys = std::env::temp_dir().join(format!("tux-regulator-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&sys);
+    let pmic = "ff3c0000.i2c/i2c-0/0-0020/rk817-regulator";
+    rail(
+        &sys,
+        0,
+        "reg-dummy",
+        &[("name", "regulator-dummy"), ("type", "voltage")],
+        &[],
+    );
+    rail(
+        &sys,
+        3,
+        pmic,
+        &[
+            ("name", "vcc_3v3"),
+            ("state", "enabled"),
+            ("microvolts", "3300000"),
+            ("num_users", "2"),
+        ],
+        &["fe2c0000.mmc-vqmmc", "spi0.0-vcc"],
+    );
+    // A fixed regulator the bootloader left on, at a voltage the board doesn't expect
+    rail(
+        &sys,
+        12,
+        "vcc-sd-regulator",
+        &[
+            ("name", "vcc_sd"),
+            ("state", "enabled"),
+            ("microvolts", "1800000"),
+        ],
+        &[],
+    );
+    rail(
+        &sys,
+        4,
+        pmic,
+        &[
+            ("name", "vdd_gpu"),
+            ("state", "disabled"),
+            ("microvolts", "900000"),
+        ],
+        &[],
+    );
+
+    let regulators = regulator::list_regulators_in(&sys).unwrap();
+    let ids: Vec<&str> = regulators.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        ["regulator.0", "regulator.3", "regulator.4", "regulator.12"]
+    );
+    let vcc = &regulators[1];
+    assert_eq!(vcc.enabled, Some(true));
+    assert_eq!(vcc.users, Some(2));
+    assert_eq!(vcc.consumers, ["fe2c0000.mmc-vqmmc", "spi0.0-vcc"]);
+    assert!(vcc.has_consumer("spi0.0") && !vcc.has_consumer("spi0"));
+    assert!(vcc.provided_by("0-0020"));
+    assert_eq!(regulators[0].enabled, None);
+
+    let manifest = Manifest::from_toml_str(
+        "[[regulator.rails]]\nname = \"vcc_3v3\"\nmin_mv = 3200\nmax_mv = 3400\n\
+         consumers = [\"fe2c0000.mmc\", \"spi0.0-vcc\"]\npmic = \"0-0020\"\n\n\
+         [[regulator.rails]]\nname = \"vcc_sd\"\nmin_mv = 3000\nconsumers = [\"fe2b0000.mmc\"]\npmic = \"0-0020\"\n\n\
+         [[regulator.rails]]\nname = \"vdd_gpu\"\n\n\
+         [[regulator.rails]]\nname = \"vdd_npu\"\nenabled = false\n",
+    )
+    .unwrap();
+    let expected = ExpectedRegulators::from_manifest(&manifest).unwrap();
+    assert_eq!(
+        regulator::validate_regulators(&regulators, &expected),
+        [
+            "vcc_sd: 1800 mV (expected at least 3000 mV)",
+            "vcc_sd: no consumer fe2b0000.mmc",
+            "vcc_sd: provided by vcc-sd-regulator (expected 0-0020)",
+            "vdd_gpu: disabled (expected enabled)",
+            "vdd_npu: not registered",
+        ]
+    );
+
+    let bad = Manifest::from_toml_str(
+        "[[regulator.rails]]\nname = \"vcc\"\nmin_mv = 3400\nmax_mv = 3200\n",
+    )
+    .unwrap();
+    assert!(ExpectedRegulators::from_manifest(&bad).is_err());
+    assert!(
+        regulator::list_regulators_in(&sys.join("nonexistent"))
+            .unwrap()
+            .is_empty()
+    );
+    fs::remove_dir_all(&sys).unwrap();
+}
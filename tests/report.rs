@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+use tux_validation::device::{DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use tux_validation::report::{self, Change};
+
+fn bus(devices: Vec<TuxDevice>) -> TuxBus {
+    TuxBus {
+        subsystem: Subsystem::I2c,
+        id: "i2c-1".to_string(),
+        name: "rk3x-i2c".to_string(),
+        devices,
+        metadata: BTreeMap::from([("health".to_string(), "ok".to_string())]),
+    }
+}
+
+fn device(addr: u16, name: &str, driver: Option<&str>) -> TuxDevice {
+    let mut device = TuxDevice::new(Subsystem::I2c, DeviceAddress::I2c { bus: 1, addr }, name);
+    device.driver = driver.map(String::from);
+    device.sysfs_path = Some(format!("/sys/bus/i2c/devices/1-{:04x}", addr).into());
+    device
+}
+
+#[test]
+fn report_round_trips_through_json() {
+    let mut eeprom = device(0x50, "eeprom", Some("at24"));
+    eeprom
+        .attributes
+        .insert("OF_NAME".to_string(), "eeprom".to_string());
+    let buses = vec![bus(vec![eeprom])];
+    let json = report::to_json(&buses);
+    let first = &json["buses"][0]["devices"][0];
+    assert_eq!(json["buses"][0]["subsystem"], "i2c");
+    assert_eq!(first["address"], "1-0050");
+    assert_eq!(first["sysfs_path"], "/sys/bus/i2c/devices/1-0050");
+    let text = serde_json::to_string(&json).unwrap();
+    assert_eq!(report::parse(&text).unwrap(), buses);
+    assert!(report::parse("{}").is_err());
+
+    for address in [
+        DeviceAddress::I2c {
+            bus: 300,
+            addr: 0x50,
+        },
+        DeviceAddress::Usb {
+            bus: 1,
+            port: "1.2".to_string(),
+        },
+        DeviceAddress::Pci {
+            domain: 0,
+            bus: 1,
+            device: 0,
+            function: 0,
+        },
+        DeviceAddress::Gpio { chip: 4 },
+        DeviceAddress::Spi { bus: 1, cs: 2 },
+        DeviceAddress::Other {
+            subsystem: "npu",
+            name: "npu0".to_string(),
+        },
+    ] {
+        let subsystem = match address {
+            DeviceAddress::I2c { .. } => Subsystem::I2c,
+            DeviceAddress::Usb { .. } => Subsystem::Usb,
+            DeviceAddress::Pci { .. } => Subsystem::Pci,
+            DeviceAddress::Gpio { .. } => Subsystem::Gpio,
+            DeviceAddress::Spi { .. } => Subsystem::Spi,
+            DeviceAddress::Other { subsystem, .. } => Subsystem::Other(subsystem),
+        };
+        assert_eq!(
+            DeviceAddress::parse(subsystem, &address.to_string()),
+            Some(address)
+        );
+    }
+}
+
+#[test]
+fn diff_lists_device_changes() {
+    let old = vec![bus(vec![
+        device(0x1a, "codec", Some("rt5640")),
+        device(0x50, "eeprom", Some("at24")),
+    ])];
+    let mut ghost = TuxDevice::new(
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 1, addr: 0x3c },
+        "",
+    );
+    ghost.hw_responded = true;
+    let new = vec![bus(vec![device(0x1a, "codec", None), ghost])];
+
+    let diffs = report::diff(&old, &new);
+    let changes: Vec<&Change> = diffs.iter().map(|d| &d.change).collect();
+    assert_eq!(
+        changes,
+        vec![
+            &Change::DriverChanged {
+                from: Some("rt5640".to_string()),
+                to: None
+            },
+            &Change::Added,
+            &Change::Removed,
+        ]
+    );
+    assert_eq!(
+        diffs[0].to_string(),
+        "1-001a codec: driver rt5640 -> no driver"
+    );
+    assert!(report::diff(&new, &new).is_empty());
+}
+
+#[test]
+fn manifest_result_renders_as_junit() {
+    use tux_validation::manifest::{self, Manifest};
+
+    let manifest = Manifest::from_toml_str(
+        r#"
+[[i2c]]
+bus = 1
+address = 0x50
+name = "eeprom"
+driver = "at24"
+
+[[i2c]]
+bus = 1
+address = 0x1a
+name = "codec <rt5640>"
+
+[[i2c]]
+bus = 1
+address = 0x68
+name = "rtc"
+severity = "warning"
+
+[[i2c]]
+bus = 2
+address = 0x3c
+name = "oled"
+required = false
+"#,
+    )
+    .unwrap();
+    let result = manifest::validate(
+        &manifest,
+        &[bus(vec![device(0x50, "eeprom", Some("at24"))])],
+    );
+    let suites = report::manifest_suites(&result);
+    assert_eq!(suites.len(), 2);
+    assert_eq!(
+        (suites[0].name.as_str(), suites[0].failures()),
+        ("i2c-1", 1)
+    );
+    assert_eq!(suites[1].skipped(), 1);
+
+    let xml = report::junit(&suites);
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+    assert!(
+        xml.contains(
+            "<testsuites name=\"tux-validation\" tests=\"4\" failures=\"1\" skipped=\"1\">"
+        )
+    );
+    assert!(xml.contains("    <testcase classname=\"i2c-1\" name=\"eeprom at 0x50\"/>\n"));
+    assert!(xml.contains(
+        "      <failure message=\"codec &lt;rt5640&gt; not found\">codec &lt;rt5640&gt; not found</failure>\n"
+    ));
+    assert!(xml.contains("      <system-out>warning: rtc not found</system-out>\n"));
+    assert!(xml.contains("      <skipped message=\"optional, not fitted\"/>\n"));
+    assert!(xml.ends_with("</testsuites>\n"));
+
+    let mut health = bus(Vec::new());
+    health
+        .metadata
+        .insert("health".to_string(), "bad".to_string());
+    let suites = report::bus_suites(&[health]);
+    assert_eq!(
+        suites[0].cases[0].failure.as_deref(),
+        Some("bus health is bad")
+    );
+}
+
+#[test]
+fn board_renders_as_a_static_html_page() {
+    use tux_validation::manifest::{self, Manifest};
+
+    let manifest = Manifest::from_toml_str(
+        r#"
+[[i2c]]
+bus = 1
+address = 0x50
+name = "eeprom"
+driver = "at24"
+
+[[i2c]]
+bus = 1
+address = 0x1a
+name = "codec"
+driver = "rt5640"
+"#,
+    )
+    .unwrap();
+    let mut eeprom = device(0x50, "eeprom", Some("at24"));
+    eeprom
+        .attributes
+        .insert("OF_NAME".to_string(), "<eeprom>".to_string());
+    let mut ghost = device(0x3c, "unknown", None);
+    ghost.sysfs_path = None;
+    ghost.hw_responded = true;
+    let buses = vec![bus(vec![eeprom, ghost])];
+    let result = manifest::validate(&manifest, &buses);
+
+    let html = report::html::render("rock-5b & co", &buses, Some(&result));
+    assert!(html.starts_with("<!DOCTYPE html>\n"));
+    assert!(html.contains("<title>rock-5b &amp; co</title>"));
+    assert!(!html.contains("<link") && !html.contains("<script"));
+    assert!(html.contains("<h2>i2c-1 <small>rk3x-i2c</small></h2>"));
+    assert!(html.contains("<p>health: ok</p>"));
+    assert!(html.contains("<span class=\"badge error\">FAILED</span>"));
+    assert!(html.contains("<span class=\"badge ok\">bound</span>"));
+    assert!(html.contains("<span class=\"badge warning\">ghost</span>"));
+    assert!(html.contains("<details><summary>Attributes (1)</summary>"));
+    assert!(html.contains("<code>&lt;eeprom&gt;</code>"));
+    // The codec is not on the bus, so it's listed on its own
+    assert!(html.contains("<h2>Not found</h2>"));
+    assert!(html.contains("<span class=\"badge error\">codec not found</span>"));
+    assert!(html.ends_with("</html>\n"));
+
+    let html = report::html::render("report", &buses, None);
+    assert!(!html.contains("Manifest:"));
+}
+
+#[test]
+fn manifest_renders_as_a_test_plan() {
+    let manifest = tux_validation::manifest::Manifest::from_toml_str(
+        r#"
+[[i2c]]
+bus = 1
+address = 0x68
+name = "imu"
+driver = "inv-mpu6050"
+registers = [{ register = 0x75, value = 0x71 }]
+
+[[i2c]]
+bus = 1
+address = 0x50
+name = "eeprom"
+required = false
+
+[rpi_firmware]
+allow_throttling = true
+timeout = "30s"
+colour = "blue"
+
+[absence]
+"#,
+    )
+    .unwrap();
+
+    let checks = report::plan::planned_checks(&manifest);
+    assert_eq!(checks.len(), 2);
+    assert_eq!(checks[0].id, "absence");
+    assert_eq!(checks[0].missing(), vec!["items"]);
+    assert!(checks[1].missing().is_empty());
+    let colour = checks[1]
+        .params
+        .iter()
+        .find(|p| p.name == "colour")
+        .unwrap();
+    assert!(!colour.known);
+
+    let md = report::plan::markdown("Plan | v1", &manifest);
+    assert!(md.starts_with("# Plan | v1\n"));
+    assert!(md.contains(
+        "| 1 | 0x68 | imu | error | present, bound to inv-mpu6050, 0x75 & 0xff = 0x71 |"
+    ));
+    assert!(md.contains("| 1 | 0x50 | eeprom | error | may be absent |"));
+    assert!(md.contains("| items | MISSING | Items that must be absent |"));
+    assert!(md.contains("| allow_throttling | true |"));
+    assert!(md.contains("| min_revision | default |"));
+    assert!(md.contains("| timeout | 30s |"));
+    assert!(md.contains("| colour | blue | not a parameter of this check |"));
+    assert!(md.contains("Access: read-only"));
+    assert!(md.contains("| Approved for production | | | |"));
+
+    let html = report::plan::html("Plan & v1", &manifest);
+    assert!(html.contains("<title>Plan &amp; v1</title>"));
+    assert!(html.contains("<span class=\"badge error\">MISSING</span>"));
+    assert!(html.contains("0x75 &amp; 0xff = 0x71"));
+    assert!(html.ends_with("</html>\n"));
+}
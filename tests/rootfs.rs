@@ -0,0 +1,44 @@
+#![cfg(feature = "hardware")]
+
+use std::io::Cursor;
+use tux_validation::rootfs::{self, ExpectedRootfs};
+
+#[test]
+fn overlay_root_with_readonly_lower() {
+    let mock_mountinfo = "\
+20 1 179:2 / /rom ro,relatime shared:1 - squashfs /dev/mmcblk0p2 ro
+21 1 179:4 / /data rw,relatime shared:2 - ext4 /dev/mmcblk0p4 rw
+22 1 0:30 / / rw,relatime shared:3 - overlay overlay rw,lowerdir=/rom,upperdir=/data/upper,workdir=/data/work
+23 22 0:5 / /dev rw,nosuid shared:4 - devtmpfs devtmpfs rw,size=4096k
+";
+    let mounts = rootfs::parse_mountinfo_from_reader(Cursor::new(mock_mountinfo)).unwrap();
+    let root = rootfs::mount_for_path(&mounts, "/").unwrap();
+    assert_eq!(root.fs_type, "overlay");
+    assert_eq!(root.super_option("upperdir"), Some("/data/upper"));
+
+    let expected = ExpectedRootfs {
+        read_only: true,
+        overlay: true,
+        upper_source: Some("/dev/mmcblk0p4".to_string()),
+        ..Default::default()
+    };
+    assert!(rootfs::validate_mounts(&mounts, &expected).is_empty());
+
+    let expected = ExpectedRootfs {
+        upper_source: Some("/dev/mmcblk0p3".to_string()),
+        ..expected
+    };
+    assert_eq!(
+        rootfs::validate_mounts(&mounts, &expected),
+        vec!["overlay upperdir /data/upper on /dev/mmcblk0p4 (expected /dev/mmcblk0p3)"]
+    );
+
+    assert_eq!(
+        rootfs::parse_verity_status("0 4194304 verity V"),
+        Some(true)
+    );
+    assert_eq!(
+        rootfs::parse_verity_status("0 4194304 verity C"),
+        Some(false)
+    );
+}
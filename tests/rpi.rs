@@ -0,0 +1,82 @@
+#![cfg(feature = "rpi")]
+
+use std::fs;
+use tux_validation::manifest::Manifest;
+use tux_validation::rpi::{self, ExpectedFirmware, FirmwareStatus};
+
+#[test]
+fn reads_throttling_flags_from_sysfs() {
+    let root = std::env::temp_dir().join(format!("tux-rpi-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let firmware = root.join("devices/platform/soc/soc:firmware");
+    fs::create_dir_all(&firmware).unwrap();
+    fs::write(firmware.join("get_throttled"), "50000\n").unwrap();
+    let hwmon = root.join("class/hwmon/hwmon1");
+    fs::create_dir_all(&hwmon).unwrap();
+    fs::write(hwmon.join("name"), "rpi_volt\n").unwrap();
+    fs::write(hwmon.join("in0_lcrit_alarm"), "0\n").unwrap();
+
+    let status = rpi::read_status_in(&root);
+    assert_eq!(status.throttled, Some(0x50000));
+    assert_eq!(status.undervoltage_alarm, Some(false));
+    assert_eq!(
+        status.throttled_flags(),
+        ["under-voltage has occurred", "throttling has occurred"]
+    );
+    assert_eq!(
+        rpi::read_status_in(&root.join("missing")),
+        FirmwareStatus::default()
+    );
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn latched_undervoltage_fails_the_manifest() {
+    let manifest = Manifest::from_toml_str(
+        "[rpi_firmware]\nmin_revision = 1700000000\nallow_throttling = true\n",
+    )
+    .unwrap();
+    let expected = ExpectedFirmware::from_manifest(&manifest).unwrap();
+    assert_eq!(expected.min_revision, Some(1_700_000_000));
+    assert!(!expected.allow_undervoltage);
+
+    let healthy = FirmwareStatus {
+        revision: Some(1_710_000_000),
+        throttled: Some(0),
+        undervoltage_alarm: Some(false),
+    };
+    assert!(rpi::validate_firmware(&healthy, &expected).is_empty());
+
+    // The supply recovered, but dipped earlier in the run
+    let dipped = FirmwareStatus {
+        throttled: Some(0x50000),
+        ..healthy
+    };
+    let problems = rpi::validate_firmware(&dipped, &expected);
+    assert_eq!(problems.len(), 1, "{:?}", problems);
+    assert!(problems[0].starts_with("under-voltage detected (get_throttled 0x50000)"));
+
+    let strict = ExpectedFirmware::default();
+    let problems = rpi::validate_firmware(&dipped, &strict);
+    assert_eq!(problems[1], "firmware reports throttling has occurred");
+
+    let old = FirmwareStatus {
+        revision: Some(1_600_000_000),
+        ..healthy
+    };
+    assert_eq!(
+        rpi::validate_firmware(&old, &expected),
+        ["firmware revision 1600000000 is older than 1700000000"]
+    );
+    let unknown = FirmwareStatus {
+        revision: None,
+        ..healthy
+    };
+    assert_eq!(
+        rpi::validate_firmware(&unknown, &expected),
+        ["firmware revision not available"]
+    );
+
+    let bad = Manifest::from_toml_str("[rpi_firmware]\nallow_undervoltage = \"yes\"\n").unwrap();
+    assert!(ExpectedFirmware::from_manifest(&bad).is_err());
+}
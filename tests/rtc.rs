@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tux_validation::manifest::Manifest;
+use tux_validation::rtc::{self, ExpectedRtc};
+use tux_validation::system_root::SystemRoot;
+
+fn rtc_dir(root: &Path, device: &str, attrs: &[(&str, &str)], node: bool) {
+    let dir = root.join("sys/class/rtc").join(device);
+    fs::create_dir_all(&dir).unwrap();
+    for (name, value) in attrs {
+        fs::write(dir.join(name), format!("{}\n", value)).unwrap();
+    }
+    if node {
+        fs::create_dir_all(root.join("dev")).unwrap();
+        fs::write(root.join("dev").join(device), "").unwrap();
+    }
+}
+
+#[test]
+fn checks_rtc_presence_drift_and_retention() {
+    let root = std::env::temp_dir().join(format!("tux-rtc-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    rtc_dir(
+        &root,
+        "rtc0",
+        &[
+            ("name", "rtc-pcf8563 0-0051"),
+            ("since_epoch", "1791979200"),
+            ("date", "2026-10-14"),
+            ("hctosys", "1"),
+        ],
+        true,
+    );
+    // Lost its coin cell: back at the part's epoch, and no device node
+    rtc_dir(
+        &root,
+        "rtc1",
+        &[
+            ("name", "rtc-rk808 rk808-rtc"),
+            ("since_epoch", "946684923"),
+            ("date", "2000-01-01"),
+            ("hctosys", "0"),
+        ],
+        false,
+    );
+    // Never set: the driver fails reads, which leaves since_epoch out here
+    rtc_dir(&root, "rtc10", &[("name", "rtc-efi rtc-efi.0")], true);
+
+    let sys_root = SystemRoot::under(&root);
+    let rtcs = rtc::list_rtcs_in(&sys_root).unwrap();
+    let devices: Vec<&str> = rtcs.iter().map(|r| r.device.as_str()).collect();
+    assert_eq!(devices, ["rtc0", "rtc1", "rtc10"]);
+    assert_eq!(rtcs[0].since_epoch, Some(1_791_979_200));
+    assert!(rtcs[0].hctosys && rtcs[0].has_node);
+    assert!(!rtcs[1].has_node);
+    assert_eq!(rtcs[2].since_epoch, None);
+
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_791_979_230);
+    let manifest = Manifest::from_toml_str("[rtc]\nname = \"pcf8563\"\nhctosys = true\n").unwrap();
+    let expected = ExpectedRtc::from_manifest(&manifest).unwrap().unwrap();
+    assert_eq!(expected.device, "rtc0");
+    assert_eq!(expected.max_drift_s, 2);
+    assert_eq!(
+        rtc::validate_rtc(&rtcs, now, Some((Duration::from_secs(10), 0)), &expected),
+        [
+            "rtc0: 30 s behind system time (at most 2 s)",
+            "rtc0: advanced 0 s in 10 s",
+        ]
+    );
+    let tolerant = ExpectedRtc {
+        max_drift_s: 60,
+        ..expected.clone()
+    };
+    assert!(
+        rtc::validate_rtc(&rtcs, now, Some((Duration::from_secs(10), 10)), &tolerant).is_empty()
+    );
+
+    let manifest = Manifest::from_toml_str(
+        "[rtc]\ndevice = \"/dev/rtc1\"\nname = \"pcf8563\"\nhctosys = true\n",
+    )
+    .unwrap();
+    let expected = ExpectedRtc::from_manifest(&manifest).unwrap().unwrap();
+    assert_eq!(
+        rtc::validate_rtc(&rtcs, now, None, &expected),
+        [
+            "rtc1: no device node /dev/rtc1",
+            "rtc1: rtc-rk808 rk808-rtc (expected pcf8563)",
+            "rtc1: didn't set the system clock at boot",
+            "rtc1: reads 2000-01-01 (reset, or lost its backup supply)",
+        ]
+    );
+    let never_set = ExpectedRtc {
+        device: "rtc10".to_string(),
+        ..ExpectedRtc::default()
+    };
+    assert_eq!(
+        rtc::validate_rtc(&rtcs, now, None, &never_set),
+        ["rtc10: time not readable (never set, or lost its backup supply)"]
+    );
+    let missing = ExpectedRtc {
+        device: "rtc2".to_string(),
+        ..ExpectedRtc::default()
+    };
+    assert_eq!(
+        rtc::validate_rtc(&rtcs, now, None, &missing),
+        ["rtc2: not registered"]
+    );
+
+    assert!(rtc::measure_retention_in(&sys_root, "rtc10", Duration::ZERO).is_err());
+    assert_eq!(
+        rtc::measure_retention_in(&sys_root, "rtc0", Duration::ZERO).unwrap(),
+        0
+    );
+    let bad = Manifest::from_toml_str("[rtc]\nmax_drift_s = -1\n").unwrap();
+    assert!(ExpectedRtc::from_manifest(&bad).is_err());
+    assert!(
+        ExpectedRtc::from_manifest(&Manifest::from_toml_str("").unwrap())
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        rtc::list_rtcs_in(&SystemRoot::under(&root.join("nonexistent")))
+            .unwrap()
+            .is_empty()
+    );
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,47 @@
+use tux_validation::runtime::{self, Dependency};
+use tux_validation::system_root::SystemRoot;
+
+#[test]
+fn finds_dependencies_in_the_system_tree() {
+    let root = std::env::temp_dir().join(format!("tux-runtime-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("usr/sbin")).unwrap();
+    std::fs::create_dir_all(root.join("run/dbus")).unwrap();
+    std::fs::write(root.join("usr/sbin/dmsetup"), "").unwrap();
+    let system = SystemRoot::under(&root);
+
+    // Programs are looked up on the PATH of this process, or the default, inside the tree
+    let path = std::env::var("PATH").unwrap_or_default();
+    if path.is_empty() || path.split(':').any(|d| d == "/usr/sbin") {
+        assert!(runtime::available_in(
+            &system,
+            &Dependency::program("dmsetup")
+        ));
+    }
+    assert!(runtime::available_in(
+        &system,
+        &Dependency::program("/usr/sbin/dmsetup")
+    ));
+    assert!(!runtime::available_in(
+        &system,
+        &Dependency::program("mmcli")
+    ));
+    assert!(!runtime::available_in(&system, &Dependency::DBus));
+    std::fs::write(root.join("run/dbus/system_bus_socket"), "").unwrap();
+    assert!(runtime::available_in(&system, &Dependency::DBus));
+
+    let err = runtime::require(&Dependency::program("tux-no-such-program")).unwrap_err();
+    if runtime::INITRAMFS {
+        assert_eq!(
+            err.to_string(),
+            "tux-no-such-program is not used by initramfs builds"
+        );
+    } else {
+        assert_eq!(
+            err.to_string(),
+            "tux-no-such-program is not available on this system"
+        );
+    }
+
+    let _ = std::fs::remove_dir_all(&root);
+}
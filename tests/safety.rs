@@ -0,0 +1,43 @@
+use std::fs;
+use tux_validation::safety::{self, Access, SafetyPolicy};
+use tux_validation::teardown::Teardown;
+
+#[test]
+fn read_only_run_refuses_intrusive_operations() {
+    let path = std::env::temp_dir().join(format!("tux-safety-{}", std::process::id()));
+    fs::write(&path, "0").unwrap();
+    assert_eq!(SafetyPolicy::active(), SafetyPolicy::STATION);
+
+    let field = SafetyPolicy::READ_ONLY.enforce();
+    let err = safety::require(Access::BusTraffic, "I2C hw probe").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "I2C hw probe needs BusTraffic access, policy allows at most ReadOnly"
+    );
+    assert!(safety::require(Access::ReadOnly, "sysfs scan").is_ok());
+    // Refused before the bus is opened, so not "Bus i2c-9999 not found"
+    #[cfg(feature = "i2c")]
+    {
+        use tux_validation::i2c::{self, ProbeMode};
+        let err = i2c::probe_address(9999, 0x50, ProbeMode::ReadByte).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Probing i2c-9999 needs BusTraffic access")
+        );
+    }
+    let teardown = Teardown::new();
+    assert!(teardown.write_attribute(&path, "1").is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "0");
+
+    // A broader policy inside a restricted run doesn't widen it, whatever order they end in
+    let station = SafetyPolicy::STATION.enforce();
+    assert_eq!(SafetyPolicy::active(), SafetyPolicy::READ_ONLY);
+    drop(field);
+    assert_eq!(SafetyPolicy::active(), SafetyPolicy::STATION);
+    drop(station);
+
+    teardown.write_attribute(&path, "1").unwrap();
+    assert!(teardown.run().is_empty());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "0");
+    fs::remove_file(&path).unwrap();
+}
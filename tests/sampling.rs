@@ -0,0 +1,35 @@
+use std::fs;
+use tux_validation::evidence::EvidenceBundle;
+use tux_validation::sampling::{self, SampleLimits, SampledCheck};
+
+#[test]
+fn sampled_check_asserts_on_stats_and_stores_samples() {
+    let root = std::env::temp_dir().join(format!("tux-sampling-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let mut bundle = EvidenceBundle::create(&root).unwrap();
+
+    let mut readings = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].into_iter();
+    let check = SampledCheck {
+        id: "adc_noise".to_string(),
+        samples: 8,
+        limits: SampleLimits {
+            max_mean: Some(6.0),
+            max_stddev: Some(1.5),
+            max: Some(8.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result =
+        sampling::run_sampled(&check, Some(&mut bundle), || Ok(readings.next().unwrap())).unwrap();
+
+    let stats = result.stats.unwrap();
+    assert_eq!(stats.mean, 5.0);
+    assert!((stats.stddev - 2.138).abs() < 0.001);
+    assert_eq!(result.violations.len(), 2);
+    assert!(result.violations[1].starts_with("adc_noise: max 9 above 8"));
+
+    let csv = fs::read_to_string(root.join("adc_noise/adc_noise.csv")).unwrap();
+    assert!(csv.starts_with("index,value\n0,2\n"));
+    fs::remove_dir_all(&root).unwrap();
+}
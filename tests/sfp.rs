@@ -0,0 +1,48 @@
+#![cfg(feature = "hardware")]
+
+use tux_validation::sfp::{self, DdmLimits, ExpectedSfpModule};
+
+fn mock_eeprom() -> Vec<u8> {
+    let mut eeprom = vec![0u8; 512];
+    eeprom[0] = 0x03; // SFP
+    eeprom[2] = 0x07; // LC
+    eeprom[20..36].copy_from_slice(b"FS              ");
+    eeprom[40..56].copy_from_slice(b"SFP-10GSR-85    ");
+    eeprom[60..62].copy_from_slice(&850u16.to_be_bytes());
+    eeprom[68..84].copy_from_slice(b"F2030412345     ");
+    eeprom[92] = 0x68; // DDM implemented, internally calibrated
+
+    let a2 = &mut eeprom[256..];
+    a2[96..98].copy_from_slice(&(35 * 256i16).to_be_bytes()); // 35 C
+    a2[98..100].copy_from_slice(&33000u16.to_be_bytes()); // 3.3 V
+    a2[100..102].copy_from_slice(&3000u16.to_be_bytes()); // 6 mA
+    a2[102..104].copy_from_slice(&5000u16.to_be_bytes()); // 0.5 mW
+    a2[104..106].copy_from_slice(&20u16.to_be_bytes()); // 0.002 mW
+    eeprom
+}
+
+#[test]
+fn decode_and_validate_sfp_module() {
+    let eeprom = mock_eeprom();
+    let info = sfp::decode_eeprom(&eeprom).unwrap();
+    let ddm = sfp::decode_ddm(&eeprom[256..]).unwrap();
+
+    assert_eq!(info.vendor, "FS");
+    assert_eq!(info.part_number, "SFP-10GSR-85");
+    assert_eq!(info.wavelength_nm, Some(850));
+    assert!(info.ddm_supported);
+    assert_eq!(ddm.temperature_c, Some(35.0));
+
+    let expected = ExpectedSfpModule {
+        vendor: Some("fs".to_string()),
+        part_number_prefix: Some("SFP-10GSR".to_string()),
+        wavelength_nm: Some(850),
+        ddm: DdmLimits {
+            temperature_c: Some((-5.0, 70.0)),
+            rx_power_mw: Some((0.01, 1.0)),
+            ..Default::default()
+        },
+    };
+    let mismatches = sfp::validate_module(&info, &ddm, &expected);
+    assert_eq!(mismatches, vec!["rx power 0.002 mW outside [0.01, 1]"]);
+}
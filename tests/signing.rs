@@ -0,0 +1,58 @@
+use std::fs;
+use tux_validation::evidence::EvidenceBundle;
+use tux_validation::signing::{self, StationKey};
+
+#[test]
+fn manifest_must_carry_approved_signature() {
+    let root = std::env::temp_dir().join(format!("tux-signing-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    let manifest = root.join("board.toml");
+    fs::write(&manifest, "[[i2c]]\nbus = 1\naddress = 0x50\n").unwrap();
+
+    let approver = StationKey::from_bytes(&[1u8; 32]);
+    let key_file = root.join("approver.pub");
+    let hex: String = approver
+        .public_key()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    fs::write(&key_file, format!("{}\n", hex)).unwrap();
+    let public_key = signing::read_key_file(&key_file).unwrap();
+
+    // Unsigned
+    assert!(signing::read_verified(&manifest, &public_key).is_err());
+    approver.sign_file(&manifest).unwrap();
+    assert!(signing::read_verified(&manifest, &public_key).is_ok());
+    // Signed by someone else
+    StationKey::from_bytes(&[2u8; 32])
+        .sign_file(&manifest)
+        .unwrap();
+    assert!(signing::read_verified(&manifest, &public_key).is_err());
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn signed_bundle_detects_tampering() {
+    let dir = std::env::temp_dir().join(format!("tux-signing-bundle-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let mut bundle = EvidenceBundle::create(&dir).unwrap();
+    bundle
+        .add_text("i2c", "bus1.txt", "0x50 present\n", "Scan")
+        .unwrap();
+    let station = StationKey::from_bytes(&[3u8; 32]);
+    station.sign_bundle(&bundle).unwrap();
+
+    let result = signing::verify_bundle(&dir, &station.public_key()).unwrap();
+    assert!(result.is_ok());
+    assert_eq!(result.verified.len(), 2);
+
+    fs::write(dir.join("i2c/bus1.txt"), "0x50 missing\n").unwrap();
+    assert!(
+        !signing::verify_bundle(&dir, &station.public_key())
+            .unwrap()
+            .is_ok()
+    );
+    assert!(signing::verify_bundle(&dir, &[0u8; 32]).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,85 @@
+use anyhow::Result;
+use std::fs;
+use tux_validation::measurement::{Measurement, Unit};
+use tux_validation::soak::{self, CheckRun, SoakCheck, SoakConfig};
+
+/// Reads a slowly rising rail voltage; fails whenever it is above 3.35 V.
+struct Rail {
+    iteration: u32,
+}
+
+impl SoakCheck for Rail {
+    fn id(&self) -> &str {
+        "vdd_3v3"
+    }
+
+    fn run(&mut self) -> Result<CheckRun> {
+        self.iteration += 1;
+        let millivolts = 3300.0 + 20.0 * self.iteration as f64;
+        let mut run = CheckRun {
+            measurements: vec![Measurement::new(millivolts, Unit::Millivolt, "in1")],
+            ..Default::default()
+        };
+        if millivolts > 3350.0 {
+            run.failures.push(format!("vdd_3v3 at {} mV", millivolts));
+        }
+        Ok(run)
+    }
+}
+
+/// Errors on every other run.
+struct Flaky {
+    runs: u32,
+}
+
+impl SoakCheck for Flaky {
+    fn id(&self) -> &str {
+        "eeprom"
+    }
+
+    fn run(&mut self) -> Result<CheckRun> {
+        self.runs += 1;
+        if self.runs.is_multiple_of(2) {
+            anyhow::bail!("read timed out");
+        }
+        Ok(CheckRun::default())
+    }
+}
+
+#[test]
+fn soak_rolls_up_failures_and_drift() {
+    let dir = std::env::temp_dir().join(format!("tux-soak-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let config = SoakConfig {
+        iterations: Some(4),
+        report_dir: Some(dir.clone()),
+        ..Default::default()
+    };
+    let mut rail = Rail { iteration: 0 };
+    let mut flaky = Flaky { runs: 0 };
+    let rollup = soak::run_soak(&config, &mut [&mut rail, &mut flaky]).unwrap();
+
+    assert_eq!(rollup.iterations, 4);
+    assert_eq!(rollup.first_failure, Some(2));
+    assert!(!rollup.passed());
+    let rail = &rollup.checks[0];
+    assert_eq!((rail.failures, rail.first_failure), (2, Some(3)));
+    assert_eq!(
+        rail.last_failure_message.as_deref(),
+        Some("vdd_3v3 at 3380 mV")
+    );
+    assert_eq!(rollup.checks[1].failure_rate(), 0.5);
+    assert_eq!(rollup.drift.len(), 1);
+    assert_eq!(rollup.drift[0].delta(), 60.0);
+    assert_eq!(rollup.drift[0].stats.mean, 3350.0);
+
+    let second: serde_json::Value =
+        serde_json::from_slice(&fs::read(dir.join("iteration-0002.json")).unwrap()).unwrap();
+    assert_eq!(second["checks"][1]["failures"][0], "read timed out");
+    let saved: serde_json::Value =
+        serde_json::from_slice(&fs::read(dir.join("rollup.json")).unwrap()).unwrap();
+    assert_eq!(saved, rollup.to_json());
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(soak::run_soak(&SoakConfig::default(), &mut []).is_err());
+}
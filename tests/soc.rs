@@ -0,0 +1,41 @@
+use std::cmp::Ordering;
+use std::fs;
+use tux_validation::soc::{self, ExpectedSoc};
+
+#[test]
+fn soc_info_with_nvmem_fallback_and_revision_range() {
+    let root = std::env::temp_dir().join(format!("tux-soc-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let soc0 = root.join("bus/soc/devices/soc0");
+    fs::create_dir_all(&soc0).unwrap();
+    fs::write(soc0.join("family"), "Allwinner\n").unwrap();
+    fs::write(soc0.join("soc_id"), "H616\n").unwrap();
+    fs::write(soc0.join("revision"), "ES1.0\n").unwrap();
+    let sid = root.join("bus/nvmem/devices/sunxi-sid0");
+    fs::create_dir_all(&sid).unwrap();
+    fs::write(sid.join("nvmem"), (0u8..32).collect::<Vec<u8>>()).unwrap();
+
+    let info = soc::read_soc_info_in(&root);
+    assert_eq!(info.soc_id.as_deref(), Some("H616"));
+    assert_eq!(
+        info.chip_id.as_deref(),
+        Some("000102030405060708090a0b0c0d0e0f")
+    );
+    assert_eq!(info.facts()["soc.family"], "Allwinner");
+
+    assert_eq!(soc::compare_revisions("1.10", "1.9"), Ordering::Greater);
+    let expected = ExpectedSoc {
+        soc_id: Some("H616".to_string()),
+        min_revision: Some("1.1".to_string()),
+        forbidden_revisions: vec!["ES1.0".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(
+        soc::validate_soc(&info, &expected),
+        vec![
+            "SoC revision ES1.0 is not allowed",
+            "SoC revision ES1.0 is older than 1.1"
+        ]
+    );
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,30 @@
+use std::io::Cursor;
+use std::net::IpAddr;
+use tux_validation::sockets::{self, AllowedSocket, Protocol};
+
+#[test]
+fn audit_listening_tcp_ports() {
+    // ssh on 0.0.0.0:22, telnet on 0.0.0.0:23, a loopback-only listener and an established connection
+    let mock_tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1001 1 0000000000000000 100 0 0 10 0
+   1: 00000000:0017 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1002 1 0000000000000000 100 0 0 10 0
+   2: 0100007F:0CEA 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1003 1 0000000000000000 100 0 0 10 0
+   3: 0A00000A:0016 0B00000A:C350 01 00000000:00000000 02:0004F4C9 00000000     0        0 1004 4 0000000000000000 20 4 1 10 -1
+";
+    let listening =
+        sockets::parse_proc_net_from_reader(Cursor::new(mock_tcp), Protocol::Tcp).unwrap();
+    assert_eq!(listening.len(), 3);
+    if cfg!(target_endian = "little") {
+        assert_eq!(listening[2].address, "127.0.0.1".parse::<IpAddr>().unwrap());
+    }
+
+    let allowed = vec![AllowedSocket {
+        protocol: Protocol::Tcp,
+        port: 22,
+        address: None,
+    }];
+    let result = sockets::audit_ports(&listening, &allowed, true);
+    assert_eq!(result.allowed.len(), 1);
+    assert_eq!(result.unexpected.len(), 1);
+    assert_eq!(result.unexpected[0].port, 23);
+}
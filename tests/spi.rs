@@ -0,0 +1,60 @@
+use std::fs;
+use std::os::unix::fs::symlink;
+use tux_validation::device::DeviceAddress;
+use tux_validation::spi;
+
+#[test]
+fn audits_spi_controllers_and_devices() {
+    let root = std::env::temp_dir().join(format!("tux-spi-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let sys = root.join("sys");
+    let base = sys.join("firmware/devicetree/base");
+    fs::create_dir_all(base.join("spi@fe610000")).unwrap();
+    fs::create_dir_all(sys.join("class/spi_master/spi0")).unwrap();
+    symlink(
+        base.join("spi@fe610000"),
+        sys.join("class/spi_master/spi0/of_node"),
+    )
+    .unwrap();
+
+    let devices = sys.join("bus/spi/devices");
+    let imu = devices.join("spi0.1");
+    fs::create_dir_all(imu.join("of_node")).unwrap();
+    fs::write(
+        imu.join("of_node/spi-max-frequency"),
+        10_000_000u32.to_be_bytes(),
+    )
+    .unwrap();
+    fs::write(imu.join("of_node/spi-cpol"), b"").unwrap();
+    fs::write(imu.join("of_node/spi-cpha"), b"").unwrap();
+    fs::write(imu.join("of_node/spi-cs-high"), b"").unwrap();
+    fs::write(
+        imu.join("uevent"),
+        "DRIVER=bmi088\nOF_COMPATIBLE_0=bosch,bmi088-accel\nMODALIAS=spi:bmi088-accel\n",
+    )
+    .unwrap();
+    let raw = devices.join("spi0.0");
+    fs::create_dir_all(raw.join("of_node")).unwrap();
+    fs::create_dir_all(raw.join("spidev/spidev0.0")).unwrap();
+    fs::create_dir_all(devices.join("spi3.0")).unwrap(); // Controller not listed
+
+    let buses = spi::audit_spi_buses_in(&sys, &root.join("udev")).unwrap();
+    assert_eq!(buses.len(), 1);
+    assert_eq!(buses[0].id, "spi0");
+    assert_eq!(buses[0].name, "spi@fe610000");
+    assert_eq!(buses[0].metadata["chip_selects"], "0,1");
+
+    let raw = &buses[0].devices[0];
+    assert_eq!(raw.address, DeviceAddress::Spi { bus: 0, cs: 0 });
+    assert_eq!(raw.attributes["spidev"], "/dev/spidev0.0");
+    assert_eq!(raw.attributes["mode"], "0");
+    assert!(!raw.attributes.contains_key("max_speed_hz"));
+
+    let imu = &buses[0].devices[1];
+    assert_eq!(imu.name, "bmi088-accel");
+    assert_eq!(imu.attributes["mode"], "3");
+    assert_eq!(imu.attributes["mode_flags"], "cs-high");
+    assert_eq!(imu.attributes["max_speed_hz"], "10000000");
+
+    let _ = fs::remove_dir_all(&root);
+}
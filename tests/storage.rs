@@ -0,0 +1,104 @@
+#![cfg(feature = "storage")]
+
+use std::fs;
+use std::path::Path;
+use tux_validation::manifest::Manifest;
+use tux_validation::storage::{self, BlockKind, Cid, ExpectedStorage};
+
+const EMMC_CID: &str = "15010038475446345210123456787a01"; // Samsung 8GTF4R
+const SD_CID: &str = "0353445343333247"; // SanDisk SC32G, first half
+
+fn disk(sys: &Path, name: &str, sectors: u64, device: &[(&str, &str)]) {
+    let dir = sys.join("block").join(name);
+    fs::create_dir_all(dir.join("device")).unwrap();
+    fs::write(dir.join("size"), format!("{}\n", sectors)).unwrap();
+    for (file, contents) in device {
+        fs::write(dir.join("device").join(file), format!("{}\n", contents)).unwrap();
+    }
+}
+
+#[test]
+fn decodes_mmc_and_sd_cids() {
+    let cid = Cid::parse(EMMC_CID, false).unwrap();
+    assert_eq!(cid.manfid, 0x15);
+    assert_eq!(cid.product, "8GTF4R");
+    assert_eq!(cid.revision, 0x10);
+    assert_eq!(cid.serial, 0x12345678);
+    assert_eq!(cid.manufacturer(false), Some("Samsung"));
+
+    let cid = Cid::parse(&format!("{}80deadbeef013a01", SD_CID), true).unwrap();
+    assert_eq!(cid.manfid, 0x03);
+    assert_eq!(cid.oemid, 0x5344);
+    assert_eq!(cid.product, "SC32G");
+    assert_eq!(cid.serial, 0xdeadbeef);
+    assert_eq!(cid.manufacturer(true), Some("SanDisk"));
+    assert!(Cid::parse("1501", false).is_err());
+}
+
+#[test]
+fn checks_disks_against_the_bom() {
+    let root = std::env::temp_dir().join(format!("tux-storage-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    // A 7.3 GiB eMMC with its boot areas, a 32 GB SD card, an NVMe drive and a loop device
+    disk(
+        &root,
+        "mmcblk0",
+        15269888,
+        &[
+            ("type", "MMC"),
+            ("cid", EMMC_CID),
+            ("csd", "d02701320f5903fff6dbffef8e40400d"),
+        ],
+    );
+    disk(&root, "mmcblk0boot0", 8192, &[]);
+    disk(&root, "mmcblk0rpmb", 8192, &[]);
+    disk(
+        &root,
+        "mmcblk1",
+        62333952,
+        &[
+            ("type", "SD"),
+            ("cid", &format!("{}80deadbeef013a01", SD_CID)),
+        ],
+    );
+    fs::write(root.join("block/mmcblk1/removable"), "1\n").unwrap();
+    disk(
+        &root,
+        "nvme0n1",
+        1000215216,
+        &[("model", "Samsung SSD 980 500GB    ")],
+    );
+    disk(&root, "loop0", 0, &[]);
+
+    let devices = storage::list_block_devices_in(&root).unwrap();
+    let names: Vec<&str> = devices.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, ["loop0", "mmcblk0", "mmcblk1", "nvme0n1"]);
+    let emmc = &devices[1];
+    assert_eq!(emmc.kind, BlockKind::Emmc);
+    assert_eq!(emmc.size_bytes, 15269888 * 512);
+    assert_eq!(emmc.model.as_deref(), Some("8GTF4R"));
+    assert_eq!(emmc.csd_structure(), Some(3));
+    assert!(devices[2].removable);
+    assert_eq!(devices[3].model.as_deref(), Some("Samsung SSD 980 500GB"));
+
+    // The BOM says a Micron eMMC: the fitted Samsung part is an unapproved substitute
+    let manifest = Manifest::from_toml_str(
+        "[[storage.disks]]\nkind = \"emmc\"\nmin_size_mb = 7500\nmanfid = 0x13\nproduct = \"MTFC8G\"\n\n\
+         [[storage.disks]]\nkind = \"sd\"\nmin_size_mb = 30000\nmax_size_mb = 33000\n\n\
+         [[storage.disks]]\nkind = \"nvme\"\nproduct = \"Samsung SSD 980 500GB\"\n\n\
+         [[storage.disks]]\nkind = \"scsi\"\nname = \"sda\"\n",
+    )
+    .unwrap();
+    let expected = ExpectedStorage::from_manifest(&manifest).unwrap();
+    assert_eq!(
+        storage::validate_storage(&devices, &expected),
+        [
+            "mmcblk0: manufacturer 0x15 (Samsung) (expected 0x13)",
+            "mmcblk0: part 8GTF4R (expected MTFC8G)",
+            "SCSI disk sda not found",
+        ]
+    );
+
+    let bad = Manifest::from_toml_str("[[storage.disks]]\nkind = \"floppy\"\n").unwrap();
+    assert!(ExpectedStorage::from_manifest(&bad).is_err());
+}
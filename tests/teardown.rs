@@ -0,0 +1,133 @@
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tux_validation::manifest::{self, Manifest};
+use tux_validation::teardown::{self, Teardown};
+
+/// A teardown action that records that it ran.
+fn record(log: &Arc<Mutex<Vec<String>>>, teardown: &Teardown, name: &str) {
+    let log = log.clone();
+    let name = name.to_string();
+    teardown.register(&name.clone(), move || {
+        log.lock().unwrap().push(name);
+        Ok(())
+    });
+}
+
+#[test]
+fn teardown_runs_however_the_check_ends() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+
+    let l = log.clone();
+    let run = teardown::run_guarded("ok", None, move |t| {
+        record(&l, t, "unbind");
+        record(&l, t, "power off");
+        assert_eq!(t.pending(), vec!["power off", "unbind"]);
+        Ok(42)
+    });
+    assert!(run.is_clean());
+    assert_eq!(run.result.unwrap(), 42);
+    assert_eq!(*log.lock().unwrap(), vec!["power off", "unbind"]);
+
+    log.lock().unwrap().clear();
+    let l = log.clone();
+    let run = teardown::run_guarded("failing", None, move |t| -> anyhow::Result<()> {
+        record(&l, t, "margin");
+        t.register("broken", || anyhow::bail!("EIO"));
+        record(&l, t, "delete");
+        anyhow::bail!("rails out of range")
+    });
+    assert_eq!(run.result.unwrap_err().to_string(), "rails out of range");
+    // The failing action doesn't stop the ones after it
+    assert_eq!(run.teardown, vec!["broken: EIO"]);
+    assert_eq!(*log.lock().unwrap(), vec!["delete", "margin"]);
+
+    log.lock().unwrap().clear();
+    let l = log.clone();
+    let run = teardown::run_guarded("panicking", None, move |t| -> anyhow::Result<()> {
+        record(&l, t, "rebind");
+        panic!("index out of bounds")
+    });
+    assert_eq!(
+        run.result.unwrap_err().to_string(),
+        "panicking: panicked: index out of bounds"
+    );
+    assert_eq!(*log.lock().unwrap(), vec!["rebind"]);
+
+    log.lock().unwrap().clear();
+    let l = log.clone();
+    let run = teardown::run_guarded("hanging", Some(Duration::from_millis(50)), move |t| {
+        record(&l, t, "port power on");
+        while !t.is_cancelled() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+        // Registered after the runner gave up, so undone at once
+        record(&l, t, "late");
+        Ok(())
+    });
+    assert!(
+        run.result
+            .unwrap_err()
+            .to_string()
+            .starts_with("hanging: timed out after 50ms")
+    );
+    assert_eq!(*log.lock().unwrap(), vec!["port power on"]);
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(*log.lock().unwrap(), vec!["port power on", "late"]);
+}
+
+#[test]
+fn sysfs_changes_are_restored() {
+    let root = std::env::temp_dir().join(format!("tux-teardown-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let adapter = root.join("bus/i2c/devices/i2c-1");
+    fs::create_dir_all(&adapter).unwrap();
+    let power = root.join("power_level");
+    fs::write(&power, "on\n").unwrap();
+
+    let teardown = Teardown::new();
+    teardown.write_attribute(&power, "off").unwrap();
+    teardown.new_i2c_device(&adapter, "24c02", 0x50).unwrap();
+    assert_eq!(fs::read_to_string(&power).unwrap(), "off");
+    assert_eq!(
+        fs::read_to_string(adapter.join("new_device")).unwrap(),
+        "24c02 0x50"
+    );
+    assert!(
+        teardown
+            .unbind_driver(&root.join("bus/i2c/devices/1-0050"))
+            .is_err()
+    );
+
+    assert!(teardown.run().is_empty());
+    assert_eq!(fs::read_to_string(&power).unwrap(), "on");
+    assert_eq!(
+        fs::read_to_string(adapter.join("delete_device")).unwrap(),
+        "0x50"
+    );
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn check_timeouts_come_from_the_manifest() {
+    let manifest = Manifest::from_toml_str(
+        "[gpio_loopback]\ntimeout = \"1.5m\"\n\n[media_pipeline]\ntimeout = 20\n\n[pci]\ntimeout = \"fast\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+        manifest.timeout("gpio_loopback").unwrap(),
+        Some(Duration::from_secs(90))
+    );
+    assert_eq!(
+        manifest.timeout("media_pipeline").unwrap(),
+        Some(Duration::from_secs(20))
+    );
+    assert_eq!(manifest.timeout("usb").unwrap(), None);
+    assert!(manifest.timeout("pci").is_err());
+    assert_eq!(
+        manifest::parse_duration(&serde_json::json!("500ms")).unwrap(),
+        Duration::from_millis(500)
+    );
+    assert!(manifest::parse_duration(&serde_json::json!("3 days")).is_err());
+}
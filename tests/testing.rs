@@ -0,0 +1,74 @@
+#![cfg(feature = "i2c")]
+
+use tux_validation::device::{DeviceAddress, Subsystem};
+use tux_validation::i2c::{self, I2cScanner, LinuxI2cScanner};
+use tux_validation::testing::FakeSysfs;
+use tux_validation::{discovery, os_release};
+
+fn board() -> FakeSysfs {
+    FakeSysfs::new()
+        .os_release("ID=debian\nVERSION_CODENAME=\"bookworm\"\n")
+        .i2c_adapter(1, "rk3x-i2c")
+        .i2c_device(1, 0x50, "24c02", Some("at24"))
+        .i2c_device(1, 0x1b, "rk808", None)
+        .udev_entry("+i2c:1-0050", &["ID_PATH=platform-fe5a0000.i2c"])
+}
+
+#[test]
+fn fake_tree_is_discovered_like_a_live_system() {
+    let fake = board();
+    let board = discovery::find("i2c")
+        .unwrap()
+        .discover(&fake.root().discovery_context())
+        .unwrap();
+    assert_eq!(board.len(), 1);
+    assert_eq!(board[0].subsystem, Subsystem::I2c);
+    let eeprom = &board[0].devices[1];
+    assert_eq!(eeprom.address, DeviceAddress::I2c { bus: 1, addr: 0x50 });
+    assert_eq!(eeprom.name, "24c02");
+    assert_eq!(eeprom.driver.as_deref(), Some("at24"));
+    assert!(eeprom.in_udev);
+    assert_eq!(board[0].devices[0].driver, None);
+
+    let scanner = LinuxI2cScanner::new(1).with_sys_root(&fake.root().sys);
+    assert_eq!(scanner.scan_sysfs().unwrap(), vec![0x1b, 0x50]);
+    assert_eq!(i2c::get_device_info_in(&fake.root().sys, 1, 0x1b), "rk808");
+    assert_eq!(
+        i2c::get_device_info_in(&fake.root().sys, 1, 0x20),
+        "Unidentified"
+    );
+
+    let osr = os_release::read_os_release_in(&fake.root()).unwrap();
+    assert_eq!(osr["VERSION_CODENAME"], "bookworm");
+}
+
+#[test]
+fn sysfs_scan_keeps_to_its_bus_and_range() {
+    let fake = board()
+        .i2c_adapter(11, "rk3x-i2c")
+        .i2c_device(11, 0x52, "24c02", None)
+        .i2c_device(1, 0x03, "reserved", None)
+        .dir("sys/bus/i2c/devices/1-a050"); // 10-bit address
+    let sys = fake.root().sys;
+    let scanner = LinuxI2cScanner::new(1).with_sys_root(&sys);
+    assert_eq!(scanner.scan_sysfs().unwrap(), vec![0x1b, 0x50]);
+    let scanner = scanner.with_addresses(0x40..=0x57);
+    assert_eq!(scanner.scan_sysfs().unwrap(), vec![0x50]);
+    let scanner = LinuxI2cScanner::new(11).with_sys_root(&sys);
+    assert_eq!(scanner.scan_sysfs().unwrap(), vec![0x52]);
+    let scanner = LinuxI2cScanner::new(1).with_sys_root(&fake.path().join("missing"));
+    assert_eq!(scanner.scan_sysfs().unwrap(), Vec::<u16>::new());
+}
+
+#[test]
+fn fake_tree_is_removed_on_drop() {
+    let fake = FakeSysfs::new().file("usr/lib/os-release", "ID=alpine\n");
+    let path = fake.path().to_path_buf();
+    assert_eq!(fake.root().os_release(), path.join("usr/lib/os-release"));
+    assert_eq!(
+        os_release::read_os_release_in(&fake.root()).unwrap()["ID"],
+        "alpine"
+    );
+    drop(fake);
+    assert!(!path.exists());
+}
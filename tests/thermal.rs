@@ -0,0 +1,102 @@
+#![cfg(feature = "sensors")]
+
+use std::fs;
+use tux_validation::manifest::Manifest;
+use tux_validation::thermal::{self, ExpectedThermal};
+
+#[test]
+fn checks_zones_and_cooling_devices_against_the_manifest() {
+    let root = std::env::temp_dir().join(format!("tux-thermal-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let class = root.join("class/thermal");
+    for (dir, files) in [
+        (
+            "thermal_zone0",
+            &[
+                ("type", "cpu-thermal"),
+                ("temp", "47500"),
+                ("trip_point_0_type", "passive"),
+                ("trip_point_0_temp", "85000"),
+                ("trip_point_1_type", "critical"),
+                ("trip_point_1_temp", "110000"),
+            ][..],
+        ),
+        (
+            "thermal_zone1",
+            &[("type", "gpu-thermal"), ("temp", "91000")],
+        ),
+        (
+            "thermal_zone2",
+            &[
+                ("type", "ddr-thermal"),
+                ("temp", "40000"),
+                ("mode", "disabled"),
+            ],
+        ),
+        (
+            "cooling_device0",
+            &[("type", "pwm-fan"), ("cur_state", "4"), ("max_state", "4")],
+        ),
+        (
+            "cooling_device1",
+            &[
+                ("type", "cpufreq-cpu0"),
+                ("cur_state", "0"),
+                ("max_state", "12"),
+            ],
+        ),
+    ] {
+        fs::create_dir_all(class.join(dir)).unwrap();
+        for (name, value) in files {
+            fs::write(class.join(dir).join(name), format!("{}\n", value)).unwrap();
+        }
+    }
+
+    let zones = thermal::list_zones_in(&root).unwrap();
+    assert_eq!(zones.len(), 3);
+    assert_eq!(zones[0].temperature, Some(47.5));
+    assert_eq!(
+        zones[0].trips,
+        vec![
+            ("passive".to_string(), 85.0),
+            ("critical".to_string(), 110.0)
+        ]
+    );
+    assert!(!zones[2].enabled);
+    let cooling = thermal::list_cooling_devices_in(&root).unwrap();
+    assert!(cooling[0].at_max());
+    assert!(!cooling[1].at_max());
+
+    let manifest = Manifest::from_toml_str(
+        r#"
+        [thermal]
+        zones = [
+            { type = "cpu-thermal", max_temp = 80 },
+            { type = "gpu-thermal", min_temp = 0, max_temp = 85 },
+            { type = "ddr-thermal" },
+            { type = "npu-thermal" },
+        ]
+        cooling = [{ type = "pwm-fan" }, { type = "cpufreq-cpu0" }, { type = "cpufreq-cpu4" }]
+        "#,
+    )
+    .unwrap();
+    let expected = ExpectedThermal::from_manifest(&manifest).unwrap();
+    assert_eq!(
+        thermal::validate_thermal(&zones, &cooling, &expected),
+        vec![
+            "gpu-thermal at 91 °C, above 85 °C",
+            "ddr-thermal (thermal_zone2) is disabled",
+            "thermal zone npu-thermal not found",
+            "pwm-fan (cooling_device0) stuck at max state 4",
+            "cooling device cpufreq-cpu4 not registered",
+        ]
+    );
+
+    let mut always_on = expected.clone();
+    always_on.zones.clear();
+    always_on.cooling.truncate(1);
+    always_on.cooling[0].allow_max = true;
+    assert!(thermal::validate_thermal(&zones, &cooling, &always_on).is_empty());
+
+    let _ = fs::remove_dir_all(&root);
+}
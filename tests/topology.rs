@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+use tux_validation::device::{Board, DeviceAddress, DeviceLink, Subsystem, TuxBus, TuxDevice};
+use tux_validation::manifest::ManifestFinding;
+use tux_validation::topology::{NodeStatus, TopologyGraph};
+
+fn i2c(bus: u32, addr: u16, name: &str, driver: Option<&str>) -> TuxDevice {
+    let mut device = TuxDevice::new(Subsystem::I2c, DeviceAddress::I2c { bus, addr }, name);
+    device.sysfs_path = Some(format!("/sys/bus/i2c/devices/{}-{:04x}", bus, addr).into());
+    device.driver = driver.map(String::from);
+    device
+}
+
+fn board() -> Board {
+    let mut ghost = TuxDevice::new(
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 5, addr: 0x3c },
+        "Unidentified",
+    );
+    ghost.hw_responded = true;
+    let bus = |id: &str, devices, metadata| TuxBus {
+        subsystem: Subsystem::I2c,
+        id: id.to_string(),
+        name: String::new(),
+        devices,
+        metadata,
+    };
+    Board {
+        buses: vec![
+            bus(
+                "i2c-1",
+                vec![
+                    i2c(1, 0x20, "pca9555", Some("pca953x")),
+                    i2c(1, 0x50, "eeprom", None),
+                    i2c(1, 0x70, "pca9548", Some("pca954x")),
+                ],
+                BTreeMap::new(),
+            ),
+            bus(
+                "i2c-5",
+                vec![ghost],
+                BTreeMap::from([("mux_device".to_string(), "1-0070".to_string())]),
+            ),
+            TuxBus {
+                subsystem: Subsystem::Gpio,
+                id: "gpio".to_string(),
+                name: String::new(),
+                devices: vec![TuxDevice::new(
+                    Subsystem::Gpio,
+                    DeviceAddress::Gpio { chip: 4 },
+                    "gpiochip4",
+                )],
+                metadata: BTreeMap::new(),
+            },
+        ],
+        links: vec![DeviceLink {
+            from: DeviceAddress::I2c { bus: 1, addr: 0x20 },
+            to: DeviceAddress::Gpio { chip: 4 },
+            relation: "provides".to_string(),
+        }],
+    }
+}
+
+#[test]
+fn graph_follows_soc_buses_muxes_devices() {
+    let findings = [ManifestFinding {
+        address: DeviceAddress::I2c { bus: 1, addr: 0x50 },
+        severity: "error".to_string(),
+        message: "eeprom bound to no driver (expected at24)".to_string(),
+    }];
+    let graph = TopologyGraph::from_board(&board(), "RK3588", &findings);
+
+    let node = |id: &str| graph.nodes.iter().find(|n| n.id == id).unwrap();
+    assert_eq!(node("i2c:1-0070").kind, "mux");
+    assert_eq!(node("i2c:1-0050").status, NodeStatus::Failed);
+    assert_eq!(node("i2c:1-0020").status, NodeStatus::Ok);
+    assert_eq!(node("i2c:5-003c").status, NodeStatus::Ghost);
+
+    let parent = |id: &str| {
+        graph
+            .edges
+            .iter()
+            .find(|e| e.to == id)
+            .map(|e| e.from.as_str())
+    };
+    assert_eq!(parent("i2c-1"), Some("soc"));
+    assert_eq!(parent("i2c-5"), Some("i2c:1-0070"));
+    assert!(
+        graph.edges.iter().any(|e| e.from == "i2c:1-0020"
+            && e.to == "gpio:gpiochip4"
+            && e.relation == "provides")
+    );
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph board {"));
+    assert!(dot.contains("\"i2c:1-0070\" -> \"i2c-5\";"));
+    assert!(dot.contains("fillcolor=tomato"));
+
+    let json = graph.to_json();
+    assert_eq!(json["nodes"].as_array().unwrap().len(), graph.nodes.len());
+    assert_eq!(json["nodes"][0]["kind"], "soc");
+}
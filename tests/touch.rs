@@ -0,0 +1,60 @@
+#![cfg(feature = "i2c")]
+
+use std::fs;
+use std::path::PathBuf;
+use tux_validation::device::{Board, DeviceAddress, Subsystem, TuxBus, TuxDevice};
+use tux_validation::touch::{self, ExpectedTouch, TouchFamily};
+
+#[test]
+fn goodix_input_and_firmware_version() {
+    let root = std::env::temp_dir().join(format!("tux-touch-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let dev_dir = root.join("3-005d");
+    let input = dev_dir.join("input/input2");
+    fs::create_dir_all(input.join("event1")).unwrap();
+    fs::create_dir_all(input.join("id")).unwrap();
+    fs::write(input.join("name"), "Goodix Capacitive TouchScreen\n").unwrap();
+    fs::write(input.join("id/version"), "1060\n").unwrap(); // The kernel's "%04x"
+
+    let mut device = TuxDevice::new(
+        Subsystem::I2c,
+        DeviceAddress::I2c { bus: 3, addr: 0x5d },
+        "gt911",
+    );
+    device.sysfs_path = Some(dev_dir.clone());
+    device.driver = Some("Goodix-TS".to_string());
+    assert_eq!(TouchFamily::detect(&device), Some(TouchFamily::Goodix));
+
+    let info = touch::inspect_touch(&dev_dir, TouchFamily::Goodix);
+    assert_eq!(info.input.as_deref(), Some("input2"));
+    assert_eq!(info.event, Some(PathBuf::from("/dev/input/event1")));
+
+    let board = Board {
+        buses: vec![TuxBus {
+            subsystem: Subsystem::I2c,
+            id: "i2c-3".to_string(),
+            name: String::new(),
+            devices: vec![device],
+            metadata: Default::default(),
+        }],
+        links: Vec::new(),
+    };
+    let expected = [ExpectedTouch {
+        bus: 3,
+        addr: 0x5d,
+        firmware_version: Some("0x1061".to_string()),
+        input_name: Some("Goodix Capacitive TouchScreen".to_string()),
+    }];
+    assert_eq!(
+        touch::validate_touch(&board, &expected),
+        vec!["3-005d: firmware version Some(\"1060\") (expected 0x1061)"]
+    );
+    for version in ["0x1060", "1060"] {
+        let expected = [ExpectedTouch {
+            firmware_version: Some(version.to_string()),
+            ..expected[0].clone()
+        }];
+        assert!(touch::validate_touch(&board, &expected).is_empty());
+    }
+    fs::remove_dir_all(&root).unwrap();
+}
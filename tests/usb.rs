@@ -0,0 +1,146 @@
+#![cfg(feature = "usb")]
+
+use std::cmp::Ordering;
+use std::fs;
+use tux_validation::device::DeviceAddress;
+use tux_validation::usb::{self, ExpectedUsbDevice, MinFirmware};
+
+fn write_device(dir: &std::path::Path, attributes: &[(&str, &str)]) {
+    fs::create_dir_all(dir).unwrap();
+    for (name, value) in attributes {
+        fs::write(dir.join(name), format!("{}\n", value)).unwrap();
+    }
+}
+
+#[test]
+fn audits_usb_tree_and_validates_ports() {
+    let root = std::env::temp_dir().join(format!("tux-usb-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let devices = root.join("sys/bus/usb/devices");
+    write_device(
+        &devices.join("usb1"),
+        &[("product", "xHCI Host Controller"), ("speed", "480")],
+    );
+    write_device(
+        &devices.join("1-1"),
+        &[
+            ("idVendor", "0424"),
+            ("idProduct", "2514"),
+            ("product", "USB2514B"),
+        ],
+    );
+    write_device(
+        &devices.join("1-1.2"),
+        &[
+            ("idVendor", "0403"),
+            ("idProduct", "6011"),
+            ("product", "Quad RS232-HS"),
+            ("uevent", "MAJOR=189\nMINOR=3\nDEVTYPE=usb_device\n"),
+        ],
+    );
+    write_device(&devices.join("1-1.2:1.0"), &[("bInterfaceNumber", "00")]);
+    fs::create_dir_all(root.join("udev")).unwrap();
+    fs::write(root.join("udev/c189:3"), "E:ID_MODEL=Quad_RS232-HS\n").unwrap();
+
+    let buses = usb::audit_usb_buses_in(&root.join("sys"), &root.join("udev")).unwrap();
+    assert_eq!(buses.len(), 1);
+    assert_eq!(buses[0].id, "usb1");
+    assert_eq!(buses[0].name, "xHCI Host Controller");
+    assert_eq!(buses[0].metadata["speed"], "480");
+    let found = &buses[0].devices;
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[1].address.to_string(), "1-1.2");
+    assert_eq!(found[1].name, "Quad RS232-HS");
+    assert!(found[1].in_udev);
+
+    let expected = [
+        ExpectedUsbDevice {
+            bus: 1,
+            port: "1.2".to_string(),
+            vendor_id: 0x0403,
+            product_id: 0x6011,
+            ..Default::default()
+        },
+        ExpectedUsbDevice {
+            bus: 1,
+            port: "1".to_string(),
+            vendor_id: 0x0424,
+            product_id: 0x2517,
+            ..Default::default()
+        },
+        ExpectedUsbDevice {
+            bus: 1,
+            port: "1.4".to_string(),
+            vendor_id: 0x2c7c,
+            product_id: 0x0125,
+            ..Default::default()
+        },
+    ];
+    let result = usb::validate_usb_devices(found, &expected);
+    assert_eq!(result.present.len(), 1);
+    assert_eq!(
+        result.mismatched,
+        vec!["1-1: found 0424:2514 USB2514B (expected 0424:2517)"]
+    );
+    assert_eq!(
+        result.missing,
+        vec![DeviceAddress::Usb {
+            bus: 1,
+            port: "1.4".to_string()
+        }]
+    );
+    assert!(!result.is_ok());
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn flags_outdated_hub_firmware() {
+    assert_eq!(usb::compare_versions("1.10", "1.9"), Ordering::Greater);
+    assert_eq!(usb::compare_versions("v2.0", "2.0"), Ordering::Equal);
+
+    let root = std::env::temp_dir().join(format!("tux-usb-fw-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let devices = root.join("sys/bus/usb/devices");
+    write_device(
+        &devices.join("usb1"),
+        &[("product", "xHCI Host Controller")],
+    );
+    for (port, bcd) in [("1-1", "0132"), ("1-2", "0160")] {
+        write_device(
+            &devices.join(port),
+            &[
+                ("idVendor", "0424"),
+                ("idProduct", "2514"),
+                ("bcdDevice", bcd),
+                ("product", "USB2514B"),
+            ],
+        );
+    }
+    write_device(&devices.join("1-2/1-2:1.0"), &[("fw_version", "3.4.1")]);
+    let found = usb::find_usb_devices_in(&root.join("sys"), &root.join("udev")).unwrap();
+
+    let hub = |port: &str, firmware: Option<&str>| ExpectedUsbDevice {
+        bus: 1,
+        port: port.to_string(),
+        vendor_id: 0x0424,
+        product_id: 0x2514,
+        min_bcd_device: Some(0x0150),
+        min_firmware: firmware.map(|version| MinFirmware {
+            attribute: "1-2:1.0/fw_version".to_string(),
+            version: version.to_string(),
+        }),
+    };
+    let result = usb::validate_usb_devices(&found, &[hub("1", None), hub("2", Some("3.4.0"))]);
+    assert_eq!(result.present.len(), 1);
+    assert_eq!(
+        result.mismatched,
+        vec!["1-1: USB2514B bcdDevice 1.32 below minimum 1.50"]
+    );
+
+    let result = usb::validate_usb_devices(&found, &[hub("2", Some("3.10"))]);
+    assert_eq!(
+        result.mismatched,
+        vec!["1-2: USB2514B 1-2:1.0/fw_version 3.4.1 below minimum 3.10"]
+    );
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::os::unix::fs::symlink;
+use tux_validation::usb_serial::{self, ExpectedSerialChannel};
+
+#[test]
+fn maps_ft4232_channels_to_ttys() {
+    let root = std::env::temp_dir().join(format!("tux-usb-serial-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let usb = root.join("sys/devices/platform/xhci-hcd.0/usb1/1-1.2");
+    fs::create_dir_all(&usb).unwrap();
+    fs::write(usb.join("idVendor"), "0403\n").unwrap();
+    fs::write(usb.join("idProduct"), "6011\n").unwrap();
+    fs::write(usb.join("serial"), "FT4XYZ\n").unwrap();
+    fs::create_dir_all(root.join("sys/class/tty")).unwrap();
+    fs::create_dir_all(root.join("udev")).unwrap();
+    fs::create_dir_all(root.join("dev/serial/by-path")).unwrap();
+
+    // Channels 0 and 1 came up swapped
+    for (interface, tty) in [(0, "ttyUSB1"), (1, "ttyUSB0")] {
+        let port = usb.join(format!("1-1.2:1.{}/{}", interface, tty));
+        fs::create_dir_all(&port).unwrap();
+        fs::write(
+            usb.join(format!("1-1.2:1.{}/bInterfaceNumber", interface)),
+            format!("{:02x}\n", interface),
+        )
+        .unwrap();
+        let class_dir = root.join("sys/class/tty").join(tty);
+        fs::create_dir_all(&class_dir).unwrap();
+        symlink(&port, class_dir.join("device")).unwrap();
+        let minor = &tty[6..];
+        fs::write(class_dir.join("dev"), format!("188:{}\n", minor)).unwrap();
+        let link = format!("platform-xhci-hcd.0-usb-0:1.2:1.{}-port0", interface);
+        fs::write(
+            root.join(format!("udev/c188:{}", minor)),
+            format!("S:serial/by-path/{}\n", link),
+        )
+        .unwrap();
+        symlink(
+            format!("../../{}", tty),
+            root.join("dev/serial/by-path").join(&link),
+        )
+        .unwrap();
+    }
+
+    let ports =
+        usb_serial::list_usb_serial_ports_in(&root.join("sys"), &root.join("udev")).unwrap();
+    assert_eq!(ports.len(), 2);
+    assert_eq!(ports[0].tty, "ttyUSB1");
+    assert_eq!((ports[0].vendor_id, ports[0].interface), (0x0403, 0));
+    assert_eq!(
+        ports[0].symlinks,
+        vec!["serial/by-path/platform-xhci-hcd.0-usb-0:1.2:1.0-port0"]
+    );
+
+    let expected = [
+        ExpectedSerialChannel {
+            vendor_id: 0x0403,
+            product_id: 0x6011,
+            interface: 0,
+            tty: Some("ttyUSB0".to_string()),
+            by_path: Some("platform-xhci-hcd.0-usb-0:1.2:1.0-port0".to_string()),
+            ..Default::default()
+        },
+        ExpectedSerialChannel {
+            vendor_id: 0x0403,
+            product_id: 0x6011,
+            interface: 3,
+            ..Default::default()
+        },
+    ];
+    let mismatches = usb_serial::validate_channels(&ports, &expected, &root.join("dev"));
+    assert_eq!(
+        mismatches,
+        vec![
+            "0403:6011 interface 0: enumerated as ttyUSB1 (expected ttyUSB0)",
+            "0403:6011 interface 3: not found"
+        ]
+    );
+    fs::remove_dir_all(&root).unwrap();
+}